@@ -0,0 +1,163 @@
+//! Benchmarks of the conversion and scan hot paths
+//!
+//! Run on the host with
+//! `cargo bench --target x86_64-unknown-linux-gnu` (or your host triple, as
+//! the crate defaults to an embedded target). Results are relative: they
+//! show whether a change to the color conversion, bit plane extraction,
+//! scan-out or effect rendering made it faster or slower, not how long it
+//! takes on a microcontroller.
+
+use core::convert::Infallible;
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_hal::digital::{ErrorType, OutputPin};
+use embedded_hal_async::delay::DelayNs;
+use hub75::effects::{CellColors, Life, NoiseEffect, Rule};
+use hub75::{
+    AnimationEffect, AnimationEffectTrait, Direction, Hub75Color, Hub75Display, Hub75FrameBuffer,
+    Hub75Pins,
+};
+
+const WIDTH: usize = 64;
+const HEIGHT: usize = 32;
+const COLOR_BITS: usize = 6;
+
+type Frame = Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>;
+
+/// Pin that ignores every write
+struct NoopPin;
+
+impl ErrorType for NoopPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for NoopPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Delay that returns immediately
+struct NoDelay;
+
+impl DelayNs for NoDelay {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
+
+/// Poll a future that never waits to completion
+fn block_on<F: Future>(future: F) -> F::Output {
+    match pin!(future)
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => output,
+        Poll::Pending => unreachable!("nothing in the benchmarks waits"),
+    }
+}
+
+/// A frame with a different color in every pixel
+fn test_frame() -> Frame {
+    let mut frame = Frame::new();
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let color = Hub75Color::from_rgb8((x * 4) as u8, (y * 8) as u8, (x ^ y) as u8);
+            frame.set_pixel(x, y, color).unwrap();
+        }
+    }
+    frame
+}
+
+fn conversion(c: &mut Criterion) {
+    let colors: Vec<Rgb565> = (0..=u16::MAX)
+        .step_by(97)
+        .map(|raw| Rgb565::from(embedded_graphics::pixelcolor::raw::RawU16::new(raw)))
+        .collect();
+    c.bench_function("rgb565_to_hub75_color", |b| {
+        b.iter(|| {
+            for &color in &colors {
+                black_box(Hub75Color::<COLOR_BITS>::from(black_box(color)));
+            }
+        })
+    });
+
+    let mut frame = Frame::new();
+    c.bench_function("fill_solid_rgb565", |b| {
+        b.iter(|| {
+            Rectangle::new(Point::zero(), Size::new(WIDTH as u32, HEIGHT as u32))
+                .into_styled(PrimitiveStyle::with_fill(Rgb565::CSS_ORANGE))
+                .draw(&mut frame)
+                .unwrap();
+            black_box(&frame);
+        })
+    });
+}
+
+fn scan(c: &mut Criterion) {
+    let frame = test_frame();
+    c.bench_function("get_row_bit_plane", |b| {
+        b.iter(|| {
+            for bit_plane in 0..COLOR_BITS {
+                for row in 0..HEIGHT / 2 {
+                    black_box(frame.get_row_bit_plane(row, bit_plane).unwrap());
+                }
+            }
+        })
+    });
+
+    let pins = Hub75Pins::new_64x32(
+        NoopPin, NoopPin, NoopPin, NoopPin, NoopPin, NoopPin, NoopPin, NoopPin, NoopPin, NoopPin,
+        NoopPin, NoopPin, NoopPin,
+    );
+    let mut display = Hub75Display::<_, WIDTH, HEIGHT, COLOR_BITS>::new(pins).unwrap();
+    display.back_buffer().copy_from(&frame);
+    c.bench_function("render_frame", |b| {
+        b.iter(|| block_on(display.render_frame(&mut NoDelay)).unwrap())
+    });
+}
+
+fn effects(c: &mut Criterion) {
+    let current = test_frame();
+    let mut next = Frame::new();
+    next.fill(Hub75Color::blue());
+    for (name, effect) in [
+        ("effect_fade", AnimationEffect::Fade),
+        ("effect_slide", AnimationEffect::Slide(Direction::Left)),
+    ] {
+        let mut frame = current.clone();
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                frame.copy_from(&current);
+                effect
+                    .apply_effect_into(&mut frame, Some(&next), 30, 60)
+                    .unwrap();
+                black_box(&frame);
+            })
+        });
+    }
+
+    let mut frame = Frame::new();
+    let mut noise = NoiseEffect::<WIDTH, HEIGHT, COLOR_BITS>::clouds(7);
+    c.bench_function("noise_next_into", |b| {
+        b.iter(|| black_box(noise.next_into(&mut frame)))
+    });
+
+    let mut life = Life::<WIDTH, HEIGHT, COLOR_BITS, 2>::new(Rule::LIFE, CellColors::Rainbow);
+    life.randomize(7, 96);
+    c.bench_function("life_next_into", |b| {
+        b.iter(|| black_box(life.next_into(&mut frame)))
+    });
+}
+
+criterion_group!(benches, conversion, scan, effects);
+criterion_main!(benches);
@@ -19,22 +19,20 @@ use embedded_graphics::{
     prelude::*,
     primitives::{Circle, PrimitiveStyleBuilder, Rectangle},
 };
-use hub75::{Hub75Display, Hub75Pins, Hub75RgbPins, Hub75AddressPins, Hub75ControlPins};
+use hub75::{math, Hsv, Hub75Display, Hub75Pins, Hub75RgbPins, Hub75AddressPins, Hub75ControlPins};
 use rand::{RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use {defmt_rtt as _, panic_probe as _};
 
 use core::ops::DerefMut;
-use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::mutex::Mutex;
-use static_cell::StaticCell;
 
 type Display = Hub75Display<Output<'static>, 32, 32, 1>;
-
-static DISPLAY: StaticCell<Mutex<NoopRawMutex, Display>> = StaticCell::new();
+type SharedDisplay = Mutex<CriticalSectionRawMutex, Display>;
 
 #[embassy_executor::task]
-pub async fn refresh_task(display_handle: &'static Mutex<NoopRawMutex, Display>) -> ! {
+pub async fn refresh_task(display_handle: &'static SharedDisplay) -> ! {
 
     defmt::info!("Starting refresh task");
     let mut delay = embassy_time::Delay;
@@ -49,7 +47,7 @@ pub async fn refresh_task(display_handle: &'static Mutex<NoopRawMutex, Display>)
 }
 
 #[embassy_executor::task]
-async fn animation_task(display_handle: &'static Mutex<NoopRawMutex, Display>) {
+async fn animation_task(display_handle: &'static SharedDisplay) {
     info!("Starting animation task");
     
     let mut rng = ChaCha8Rng::seed_from_u64(12345);
@@ -93,7 +91,7 @@ fn rainbow_bars(display: &mut Display, frame: u32) {
     
     for x in 0..32 {
         let hue = ((x + offset) * 6) % 360;
-        let color = hsv_to_rgb565(hue as u16, 255, 255);
+        let color = Rgb565::from(Hsv::new(hue as u16, 255, 255));
         
         Rectangle::new(Point::new(x as i32, 0), Size::new(1, 32))
             .into_styled(PrimitiveStyleBuilder::new().fill_color(color).build())
@@ -108,9 +106,11 @@ fn bouncing_balls(display: &mut Display, frame: u32, _rng: &mut ChaCha8Rng) {
     let colors = [Rgb565::RED, Rgb565::GREEN, Rgb565::BLUE];
     
     for i in 0..ball_count {
-        let phase = frame as f32 * 0.1 + i as f32 * 2.0;
-        let x = (8.0 + 10.0 * (phase * 0.7).sin()) as i32;
-        let y = (8.0 + 10.0 * (phase).sin()) as i32;
+        // Angles are fractions of a turn, 65536 per revolution
+        let phase_x = (frame as u16).wrapping_mul(730).wrapping_add(i as u16 * 14602);
+        let phase_y = (frame as u16).wrapping_mul(1043).wrapping_add(i as u16 * 20861);
+        let x = 8 + 10 * math::sin(phase_x) as i32 / 32767;
+        let y = 8 + 10 * math::sin(phase_y) as i32 / 32767;
         
         Circle::new(Point::new(x - 3, y - 3), 6)
             .into_styled(PrimitiveStyleBuilder::new().fill_color(colors[i]).build())
@@ -120,23 +120,19 @@ fn bouncing_balls(display: &mut Display, frame: u32, _rng: &mut ChaCha8Rng) {
 }
 
 fn plasma_effect(display: &mut Display, frame: u32) {
-    let time = frame as f32 * 0.1;
+    let time = frame as u16;
     
-    for y in 0..16 {
-        for x in 0..16 {
-            let fx = x as f32;
-            let fy = y as f32;
-            
-            // Plasma calculation (simplified integer math)
-            let v1 = (fx * 0.1 + time).sin();
-            let v2 = ((fx + fy) * 0.08 + time * 1.2).sin();
-            let v3 = ((fx - fy) * 0.12 + time * 0.8).sin();
-            let plasma = (v1 + v2 + v3) * 127.0 + 128.0;
+    for y in 0..16u16 {
+        for x in 0..16u16 {
+            // Plasma calculation with fixed-point sine, 65536 angle units per turn
+            let v1 = math::sin(x.wrapping_mul(1043).wrapping_add(time.wrapping_mul(1043))) as i32;
+            let v2 = math::sin((x + y).wrapping_mul(834).wrapping_add(time.wrapping_mul(1252))) as i32;
+            let v3 = math::sin(x.wrapping_sub(y).wrapping_mul(1252).wrapping_add(time.wrapping_mul(834))) as i32;
             
-            let hue = (plasma as u16) % 360;
-            let color = hsv_to_rgb565(hue, 255, 200);
+            let hue = ((v1 + v2 + v3) * 60 / 32767 + 180) as u16;
+            let color = Rgb565::from(Hsv::new(hue, 255, 200));
             
-            Rectangle::new(Point::new(x*2 as i32, y*2 as i32), Size::new(2, 2))
+            Rectangle::new(Point::new((x * 2) as i32, (y * 2) as i32), Size::new(2, 2))
                 .into_styled(PrimitiveStyleBuilder::new().fill_color(color).build())
                 .draw(display)
                 .ok();
@@ -160,46 +156,6 @@ fn random_sparkles(display: &mut Display, rng: &mut ChaCha8Rng) {
     }
 }
 
-// Simple HSV to RGB565 conversion
-fn hsv_to_rgb565(h: u16, s: u8, v: u8) -> Rgb565 {
-    let h = h % 360;
-    let s = s as u16;
-    let v = v as u16;
-    
-    let c = (v * s) / 255;
-    let x = c * (60 - ((h % 120) as i16 - 60).abs() as u16) / 60;
-    let m = v - c;
-    
-    let (r, g, b) = match h / 60 {
-        0 => (c, x, 0),
-        1 => (x, c, 0),
-        2 => (0, c, x),
-        3 => (0, x, c),
-        4 => (x, 0, c),
-        _ => (c, 0, x),
-    };
-    
-    let r = ((r + m) >> 3) as u8;
-    let g = ((g + m) >> 2) as u8;
-    let b = ((b + m) >> 3) as u8;
-    
-    Rgb565::new(r, g, b)
-}
-
-// Simplified sin function using lookup table
-trait FloatExt {
-    fn sin(self) -> f32;
-}
-
-impl FloatExt for f32 {
-    fn sin(self) -> f32 {
-        // Very simple sin approximation for embedded use
-        let x = self % (2.0 * 3.14159);
-        let x2 = x * x;
-        x - (x2 * x) / 6.0 + (x2 * x2 * x) / 120.0
-    }
-}
-
 #[embassy_executor::main]
 async fn main(spawner: Spawner) {
     let p = embassy_nrf::init(Default::default());
@@ -241,7 +197,7 @@ async fn main(spawner: Spawner) {
     // Enable double buffering for smooth updates
     display.set_double_buffering(true);
 
-    let display = DISPLAY.init(Mutex::new(display));
+    let display = hub75::display_mutex!(Display = display);
 
     spawner.spawn(animation_task(display)).unwrap();
     spawner.spawn(refresh_task(display)).unwrap();
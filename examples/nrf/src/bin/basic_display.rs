@@ -3,7 +3,8 @@
 //! This example demonstrates:
 //! - Setting up a HUB75 display with embassy-nrf
 //! - Drawing basic shapes and text
-//! - Running the display refresh task
+//! - Spawning the display refresh task with `spawn_refresh!`
+//! - Drawing from another task through the returned `Hub75Handle`
 //!
 //! Hardware connections (example for 64x32 panel):
 //! - R1, G1, B1: P0.02, P0.03, P0.04
@@ -17,11 +18,8 @@
 #![no_main]
 
 use core::fmt::Write;
-use core::ops::DerefMut;
 use embassy_executor::Spawner;
 use embassy_nrf::gpio::{Level, Output, OutputDrive};
-use embassy_sync::blocking_mutex::raw::NoopRawMutex;
-use embassy_sync::mutex::Mutex;
 use embassy_time::{Duration, Timer};
 use embedded_graphics::{
     mono_font::{ascii::FONT_6X10, MonoTextStyle},
@@ -30,106 +28,64 @@ use embedded_graphics::{
     primitives::{Circle, PrimitiveStyleBuilder, Rectangle},
     text::Text,
 };
-use hub75::{display, Hub75AddressPins, Hub75ControlPins, Hub75Display, Hub75Pins, Hub75RgbPins};
-use static_cell::StaticCell;
+use hub75::shared::Hub75Handle;
+use hub75::{Hub75AddressPins, Hub75ControlPins, Hub75Display, Hub75Pins, Hub75RgbPins};
 use {defmt_rtt as _, panic_probe as _};
 
 use heapless::String;
 
 type Display = Hub75Display<Output<'static>, 32, 32, 2>;
 
-static DISPLAY: StaticCell<Mutex<NoopRawMutex, Display>> = StaticCell::new();
-
-#[embassy_executor::task]
-pub async fn refresh_task(display_handle: &'static Mutex<NoopRawMutex, Display>) -> ! {
-
-    defmt::info!("Starting refresh task");
-    let mut delay = embassy_time::Delay;
-
-    loop {
-        {
-            let mut display = display_handle.lock().await;
-            let _ = display.render_frame(&mut delay).await;
-        }
-        Timer::after(Duration::from_millis(5)).await;
-    }
-}
-
 #[embassy_executor::task]
-async fn combined_display_task(display_handle: &'static Mutex<NoopRawMutex, Display>) {
+async fn combined_display_task(handle: Hub75Handle<'static, 32, 32, 2, 256>) {
     defmt::info!("Starting draw task");
 
     let mut counter = 0u32;
-
-    // Create a delay provider using embassy-time
-    let mut delay = embassy_time::Delay;
+    let mut display = handle;
 
     loop {
-        // Clear the back buffer
-        // defmt::info!("LOOP");
-        {
-            let mut display_guard = display_handle.lock().await;
-            let mut display = display_guard.deref_mut();
-            display.clear();
-
-            Rectangle::new(Point::new(0, 0), Size::new(32, 32))
-                .into_styled(
-                    PrimitiveStyleBuilder::new()
-                        .fill_color(Rgb565::WHITE)
-                        .build(),
-                )
-                .draw(display)
-                .unwrap();
-
-            // Draw a red rectangle
-            Rectangle::new(Point::new(5, 16), Size::new(2, 2))
-                .into_styled(PrimitiveStyleBuilder::new().fill_color(Rgb565::RED).build())
-                .draw(display)
-                .unwrap();
-
-            // Draw a green circle
-            Circle::new(Point::new(16, 16), 6)
-                .into_styled(
-                    PrimitiveStyleBuilder::new()
-                        .fill_color(Rgb565::GREEN)
-                        .build(),
-                )
-                .draw(display)
-                .unwrap();
-
-            // // Draw a blue rectangle
-            // Rectangle::new(Point::new(45, 2), Size::new(15, 12))
-            //     .into_styled(PrimitiveStyleBuilder::new().fill_color(Rgb565::BLUE).build())
-            //     .draw(&mut display)
-            //     .unwrap();
-
-            // // Draw counter text
-            // let mut text_buffer = heapless::String::<32>::new();
-            // core::fmt::write(&mut text_buffer, format_args!("Count: {}", counter)).unwrap();
-
-            // Text::new(
-            //     &text_buffer,
-            //     Point::new(2, 25),
-            //     MonoTextStyle::new(&FONT_6X10, Rgb565::WHITE),
-            // )
-            // .draw(&mut display)
-            // .unwrap();
-
-            let mut buf: String<64> = String::new();
-            write!(buf, "nRF{}", counter % 100).unwrap();
-
-            // Show nRF52 info
-            Text::new(
-                buf.as_str(),
-                Point::new(2, 10),
-                MonoTextStyle::new(&FONT_6X10, Rgb565::BLACK),
+        // Queue the next frame into the back buffer
+        display.clear().unwrap();
+
+        Rectangle::new(Point::new(0, 0), Size::new(32, 32))
+            .into_styled(
+                PrimitiveStyleBuilder::new()
+                    .fill_color(Rgb565::WHITE)
+                    .build(),
             )
-            .draw(display)
+            .draw(&mut display)
             .unwrap();
 
-            // Swap buffers to display the new frame
-            display.swap_buffers();
-        }
+        // Draw a red rectangle
+        Rectangle::new(Point::new(5, 16), Size::new(2, 2))
+            .into_styled(PrimitiveStyleBuilder::new().fill_color(Rgb565::RED).build())
+            .draw(&mut display)
+            .unwrap();
+
+        // Draw a green circle
+        Circle::new(Point::new(16, 16), 6)
+            .into_styled(
+                PrimitiveStyleBuilder::new()
+                    .fill_color(Rgb565::GREEN)
+                    .build(),
+            )
+            .draw(&mut display)
+            .unwrap();
+
+        let mut buf: String<64> = String::new();
+        write!(buf, "nRF{}", counter % 100).unwrap();
+
+        // Show nRF52 info
+        Text::new(
+            buf.as_str(),
+            Point::new(2, 10),
+            MonoTextStyle::new(&FONT_6X10, Rgb565::BLACK),
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        // Swap buffers to display the new frame
+        display.swap_buffers().unwrap();
 
         counter = counter.wrapping_add(1);
         Timer::after(Duration::from_millis(100)).await;
@@ -180,12 +136,10 @@ async fn main(spawner: Spawner) {
     // Enable double buffering for smooth updates
     display.set_double_buffering(true);
 
-    let display = DISPLAY.init(Mutex::new(display));
-
-    // Since the display can't be cloned, we need to use a different approach
-    // For now, let's combine both tasks into one
-    spawner.spawn(combined_display_task(display)).unwrap();
-    spawner.spawn(refresh_task(display)).unwrap();
+    // The refresh task owns the display; drawing goes through the queue, so
+    // the display is never locked
+    let handle = hub75::spawn_refresh!(spawner, display: Display = display, queue = 256).unwrap();
+    spawner.spawn(combined_display_task(handle)).unwrap();
 
     defmt::info!("Tasks spawned, entering main loop");
 
@@ -24,7 +24,7 @@ use embedded_graphics::{
     },
     text::Text,
 };
-use hub75::{Hub75Display, Hub75Pins, Hub75RgbPins, Hub75AddressPins, Hub75ControlPins};
+use hub75::{math, Hub75Display, Hub75Pins, Hub75RgbPins, Hub75AddressPins, Hub75ControlPins};
 use {defmt_rtt as _, panic_probe as _};
 
 type Display = Hub75Display<Output<'static>, 32, 32, 4>;
@@ -114,11 +114,10 @@ fn line_patterns_demo(display: &mut Display, frame: u32) {
     let center_y = 12;
     
     for i in 0..8 {
-        let angle = (frame + i * 12) % 360;
-        let angle_rad = angle as f32 * 3.14159 / 180.0;
+        let angle = math::degrees(frame + i * 12);
         
-        let end_x = center_x + (20.0 * angle_rad.cos()) as i32;
-        let end_y = center_y + (10.0 * angle_rad.sin()) as i32;
+        let end_x = center_x + 20 * math::cos(angle) as i32 / 32767;
+        let end_y = center_y + 10 * math::sin(angle) as i32 / 32767;
         
         Line::new(Point::new(center_x, center_y), Point::new(end_x, end_y))
             .into_styled(PrimitiveStyle::with_stroke(colors[i as usize % colors.len()], 1))
@@ -145,8 +144,9 @@ fn concentric_circles_demo(display: &mut Display, frame: u32) {
 fn triangle_wave_demo(display: &mut Display, frame: u32) {
     // Draw a sine wave using triangles
     for x in 0..64 {
-        let wave_phase = (x + frame) as f32 * 0.2;
-        let y = 12.0 + 8.0 * (wave_phase * 3.14159 / 20.0).sin();
+        // One full wave every 200 steps of x + frame
+        let wave_phase = ((x + frame) % 200 * 65536 / 200) as u16;
+        let y = 12 + 8 * math::sin(wave_phase) as i32 / 32767;
         
         let color = match x % 3 {
             0 => Rgb565::RED,
@@ -155,9 +155,9 @@ fn triangle_wave_demo(display: &mut Display, frame: u32) {
         };
         
         Triangle::new(
-            Point::new(x as i32, y as i32),
-            Point::new(x as i32 + 2, y as i32 + 3),
-            Point::new(x as i32 - 2, y as i32 + 3),
+            Point::new(x as i32, y),
+            Point::new(x as i32 + 2, y + 3),
+            Point::new(x as i32 - 2, y + 3),
         )
         .into_styled(PrimitiveStyleBuilder::new().fill_color(color).build())
         .draw(display)
@@ -165,24 +165,6 @@ fn triangle_wave_demo(display: &mut Display, frame: u32) {
     }
 }
 
-// Simple trigonometric functions for embedded use
-trait FloatExt {
-    fn sin(self) -> f32;
-    fn cos(self) -> f32;
-}
-
-impl FloatExt for f32 {
-    fn sin(self) -> f32 {
-        let x = self % (2.0 * 3.14159);
-        let x2 = x * x;
-        x - (x2 * x) / 6.0 + (x2 * x2 * x) / 120.0
-    }
-    
-    fn cos(self) -> f32 {
-        (self + 3.14159 / 2.0).sin()
-    }
-}
-
 #[embassy_executor::main]
 async fn main(spawner: Spawner) {
     let p = embassy_nrf::init(Default::default());
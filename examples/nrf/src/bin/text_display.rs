@@ -4,6 +4,7 @@
 //! - Displaying scrolling text
 //! - Using different fonts and colors
 //! - Text animation effects
+//! - Playing an intro animation with `spawn_animation!` before refreshing
 //!
 //! Hardware connections: Same as basic_display.rs
 
@@ -21,17 +22,20 @@ use embedded_graphics::{
     prelude::*,
     text::Text,
 };
-use hub75::{Hub75Display, Hub75Pins, Hub75RgbPins, Hub75AddressPins, Hub75ControlPins};
+use hub75::shared::Hub75Handle;
+use hub75::{
+    Animation, AnimationData, AnimationEffect, Hub75AddressPins, Hub75ControlPins, Hub75Display,
+    Hub75Pins, Hub75RgbPins,
+};
 use {defmt_rtt as _, panic_probe as _};
 
 type Display = Hub75Display<Output<'static>, 32, 32, 6>;
+type Intro = Animation<'static, 32, 32, 6, AnimationEffect>;
 
 #[embassy_executor::task]
-async fn text_animation_task(mut display: Display) {
+async fn text_animation_task(display: Hub75Handle<'static, 32, 32, 6, 256>) {
     info!("Starting text animation task");
     
-    display.set_double_buffering(true);
-    
     let messages = [
         "Hello nRF!",
         "HUB75 Display",
@@ -43,7 +47,7 @@ async fn text_animation_task(mut display: Display) {
     let mut scroll_offset = 32i32; // Start off-screen to the right
     
     loop {
-        display.clear();
+        display.clear().ok();
         
         let current_message = messages[message_index];
         
@@ -79,7 +83,7 @@ async fn text_animation_task(mut display: Display) {
         .draw(&mut display)
         .ok();
         
-        display.swap_buffers();
+        display.swap_buffers().ok();
         
         // Update scroll position
         scroll_offset -= 1;
@@ -128,7 +132,7 @@ async fn main(spawner: Spawner) {
         },
     };
 
-    let display = match Hub75Display::new(pins) {
+    let mut display = match Hub75Display::new(pins) {
         Ok(display) => display,
         Err(e) => {
             error!("Failed to create display: {:?}", e);
@@ -137,7 +141,25 @@ async fn main(spawner: Spawner) {
     };
     info!("Display initialized");
 
-    spawner.spawn(text_animation_task(display)).unwrap();
+    display.set_double_buffering(true);
+
+    // Spell out a greeting before the scrolling text starts; drawing queued
+    // meanwhile is overwritten by the intro's next frame
+    let intro = match Intro::new(AnimationData::Text("HUB75"), AnimationEffect::Fade, 60) {
+        Ok(intro) => intro,
+        Err(e) => {
+            error!("Failed to create intro: {:?}", e);
+            return;
+        }
+    };
+    let handle = hub75::spawn_animation!(
+        spawner,
+        display: Display = display,
+        animation: Intro = intro,
+        queue = 256,
+    )
+    .unwrap();
+    spawner.spawn(text_animation_task(handle)).unwrap();
 
     info!("Text animation started");
     
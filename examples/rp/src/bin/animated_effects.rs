@@ -19,7 +19,7 @@ use embedded_graphics::{
     prelude::*,
     primitives::{PrimitiveStyleBuilder, Rectangle},
 };
-use hub75::{Hub75Display, Hub75Pins, Hub75RgbPins, Hub75AddressPins, Hub75ControlPins};
+use hub75::{math, Hsv, Hub75Display, Hub75Pins, Hub75RgbPins, Hub75AddressPins, Hub75ControlPins};
 use rand::{RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use {defmt_rtt as _, panic_halt as _};
@@ -68,12 +68,15 @@ async fn animation_task(mut display: Display) {
 fn rainbow_wave_effect(display: &mut Display, frame: u32) {
     for y in 0..32 {
         for x in 0..64 {
-            let wave1 = ((x as f32 * 0.1 + frame as f32 * 0.05).sin() * 127.0 + 128.0) as u8;
-            let wave2 = ((y as f32 * 0.15 + frame as f32 * 0.03).sin() * 127.0 + 128.0) as u8;
+            // Angles are fractions of a turn, 65536 per revolution
+            let phase1 = (x as u16).wrapping_mul(1043).wrapping_add((frame as u16).wrapping_mul(522));
+            let phase2 = (y as u16 * 1565).wrapping_add((frame as u16).wrapping_mul(313));
+            let wave1 = (128 + math::sin(phase1) as i32 * 127 / 32767) as u8;
+            let wave2 = (128 + math::sin(phase2) as i32 * 127 / 32767) as u8;
             let combined = ((wave1 as u16 + wave2 as u16) / 2) as u8;
             
             let hue = (combined as u16 * 360 / 255) % 360;
-            let color = hsv_to_rgb565(hue, 255, 200);
+            let color = Rgb565::from(Hsv::new(hue, 255, 200));
             
             Rectangle::new(Point::new(x as i32, y as i32), Size::new(1, 1))
                 .into_styled(PrimitiveStyleBuilder::new().fill_color(color).build())
@@ -141,22 +144,24 @@ fn fire_effect(display: &mut Display, _frame: u32, rng: &mut ChaCha8Rng) {
 }
 
 fn plasma_tunnel_effect(display: &mut Display, frame: u32) {
-    let time = frame as f32 * 0.1;
-    let center_x = 32.0;
-    let center_y = 16.0;
+    let time = (frame as u16).wrapping_mul(1043);
+    let center_x = 32;
+    let center_y = 16;
     
     for y in 0..32 {
         for x in 0..64 {
-            let dx = x as f32 - center_x;
-            let dy = y as f32 - center_y;
-            let distance = (dx * dx + dy * dy).sqrt();
-            let angle = dy.atan2(dx);
+            let dx = x - center_x;
+            let dy = y - center_y;
+            // Distance in 1/16 pixels and angle in 1/65536 turns
+            let distance = math::isqrt(((dx * dx + dy * dy) as u32) << 8);
+            let angle = math::atan2(dy, dx);
             
-            let plasma = (distance * 0.1 + time).sin() + (angle * 3.0 + time * 2.0).sin();
-            let intensity = ((plasma + 2.0) * 127.0) as u8;
+            let ring = math::sin((distance as u16 * 65).wrapping_add(time)) as i32;
+            let spiral = math::sin(angle.wrapping_mul(3).wrapping_add(time.wrapping_mul(2))) as i32;
+            let intensity = ((ring + spiral) * 127 / 65534 + 127) as u8;
             
             let hue = ((intensity as u16 * 2 + frame as u16) % 360) as u16;
-            let color = hsv_to_rgb565(hue, 255, intensity);
+            let color = Rgb565::from(Hsv::new(hue, 255, intensity));
             
             Rectangle::new(Point::new(x as i32, y as i32), Size::new(1, 1))
                 .into_styled(PrimitiveStyleBuilder::new().fill_color(color).build())
@@ -208,71 +213,6 @@ fn starfield_effect(display: &mut Display, frame: u32, rng: &mut ChaCha8Rng) {
     }
 }
 
-// HSV to RGB565 conversion
-fn hsv_to_rgb565(h: u16, s: u8, v: u8) -> Rgb565 {
-    let h = h % 360;
-    let s = s as u16;
-    let v = v as u16;
-    
-    let c = (v * s) / 255;
-    let x = c * (60 - ((h % 120) as i16 - 60).abs() as u16) / 60;
-    let m = v - c;
-    
-    let (r, g, b) = match h / 60 {
-        0 => (c, x, 0),
-        1 => (x, c, 0),
-        2 => (0, c, x),
-        3 => (0, x, c),
-        4 => (x, 0, c),
-        _ => (c, 0, x),
-    };
-    
-    let r = ((r + m) >> 3) as u8;
-    let g = ((g + m) >> 2) as u8;
-    let b = ((b + m) >> 3) as u8;
-    
-    Rgb565::new(r, g, b)
-}
-
-// Simplified math functions for embedded use
-trait FloatExt {
-    fn sin(self) -> f32;
-    fn sqrt(self) -> f32;
-    fn atan2(self, other: f32) -> f32;
-}
-
-impl FloatExt for f32 {
-    fn sin(self) -> f32 {
-        let x = self % (2.0 * 3.14159);
-        let x2 = x * x;
-        x - (x2 * x) / 6.0 + (x2 * x2 * x) / 120.0
-    }
-    
-    fn sqrt(self) -> f32 {
-        if self <= 0.0 { return 0.0; }
-        let mut x = self;
-        let mut prev = 0.0;
-        while (x - prev).abs() > 0.01 {
-            prev = x;
-            x = (x + self / x) * 0.5;
-        }
-        x
-    }
-    
-    fn atan2(self, other: f32) -> f32 {
-        // Simplified atan2 approximation
-        if other.abs() > self.abs() {
-            let ratio = self / other;
-            let result = ratio / (1.0 + 0.28 * ratio * ratio);
-            if other < 0.0 { result + 3.14159 } else { result }
-        } else {
-            let ratio = other / self;
-            let result = 1.5708 - ratio / (1.0 + 0.28 * ratio * ratio);
-            if self < 0.0 { result + 3.14159 } else { result }
-        }
-    }
-}
-
 #[embassy_executor::main]
 async fn main(spawner: Spawner) {
     let p = embassy_rp::init(Default::default());
@@ -9,13 +9,33 @@
 //! - **Frame-based timing**: Animations advance based on frame count rather than elapsed time
 //! - **Effect system**: Different visual effects can be applied to frame transitions
 //! - **Generic runtime**: Works with Embassy, RTIC, or any async runtime
+//! - **Procedural sources**: [`AnimationData::generator`] draws frames on demand
 //!
 //! # Animation Effects
 //!
 //! - `None`: Direct frame display without transitions
-//! - `Fade`: Smooth fade between frames
-//! - `Slide`: Sliding transition effects
-//! - `Scroll`: Scrolling text or image effects
+//! - `Fade`: Fade out to black and back in
+//! - `Crossfade`: Smooth blend from each frame into the next
+//! - `Slide`: The next frame slides in over the current one in any [`Direction`]
+//! - `Push`: The next frame pushes the current one off in any [`Direction`]
+//! - `Wipe`: Line-by-line reveal in any [`Direction`], from an edge or the center
+//! - `Dissolve`, `Iris`, `Curtain`, `Pixelate`: Further transitions into the
+//!   next frame
+//!
+//! Animations play once by default; a [`PlaybackMode`] makes them loop, repeat
+//! a fixed number of times or ping-pong back and forth.
+//!
+//! Playback can also be paused, resumed, sought to a given step and reversed,
+//! for interactive uses such as scrubbing through an animation.
+//!
+//! The playback speed can be changed at runtime with
+//! [`Animation::set_speed`], so one asset can play at different rates.
+//! Low frame rate content can be smoothed with
+//! [`Animation::set_interpolation`], which blends between steps on the
+//! frames in between.
+//!
+//! Effect progress can be shaped with an [`Easing`] curve so that transitions
+//! don't move at constant velocity.
 //!
 //! # Examples
 //!
@@ -57,21 +77,132 @@
 //! # }
 //! ```
 
-use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer, AnimationError, Hub75Error};
+use core::cell::RefCell;
+
+use heapless::Deque;
+
+use crate::{
+    color::Hub75Color,
+    font::{self, GLYPH_HEIGHT, GLYPH_WIDTH},
+    frame_buffer::Hub75FrameBuffer,
+    AnimationError, Hub75Error,
+};
 
 /// Trait for animation effects
 pub trait AnimationEffectTrait<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
     /// Apply the effect to generate a frame
+    ///
+    /// Returns the frame by value; prefer
+    /// [`apply_effect_into`](Self::apply_effect_into) for large frames.
     fn apply_effect(
         &self,
         current_frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
         next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
         progress: usize,
         total_steps: usize,
-    ) -> Result<Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>, Hub75Error>;
+    ) -> Result<Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>, Hub75Error> {
+        let mut frame = current_frame.clone();
+        self.apply_effect_into(&mut frame, next_frame, progress, total_steps)?;
+        Ok(frame)
+    }
+
+    /// Apply the effect in place
+    ///
+    /// `frame` holds the current frame on entry and the resulting frame on
+    /// return, so no additional frame buffer is needed.
+    fn apply_effect_into(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
+        progress: usize,
+        total_steps: usize,
+    ) -> Result<(), Hub75Error>;
 
     /// Get the total number of steps for this effect with the given frame count
     fn total_steps(&self, frame_count: usize) -> usize;
+
+    /// Get the number of steps spent on each frame before moving to the next
+    fn steps_per_frame(&self) -> usize {
+        self.total_steps(1)
+    }
+
+    /// Whether the effect reads the next frame
+    ///
+    /// When `false`, `next_frame` is always `None` and animations never need
+    /// to load the next frame.
+    fn uses_next_frame(&self) -> bool {
+        true
+    }
+}
+
+impl<T, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS> for &T
+where
+    T: AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS> + ?Sized,
+{
+    fn apply_effect_into(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
+        progress: usize,
+        total_steps: usize,
+    ) -> Result<(), Hub75Error> {
+        (**self).apply_effect_into(frame, next_frame, progress, total_steps)
+    }
+
+    fn total_steps(&self, frame_count: usize) -> usize {
+        (**self).total_steps(frame_count)
+    }
+
+    fn steps_per_frame(&self) -> usize {
+        (**self).steps_per_frame()
+    }
+
+    fn uses_next_frame(&self) -> bool {
+        (**self).uses_next_frame()
+    }
+}
+
+/// Direction in which content moves during a transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    /// Towards the left edge
+    #[default]
+    Left,
+    /// Towards the right edge
+    Right,
+    /// Towards the top edge
+    Up,
+    /// Towards the bottom edge
+    Down,
+}
+
+impl Direction {
+    /// Whether the direction runs along the x axis
+    pub const fn is_horizontal(self) -> bool {
+        matches!(self, Direction::Left | Direction::Right)
+    }
+
+    /// Number of pixels along the axis of this direction
+    const fn span(self, width: usize, height: usize) -> usize {
+        if self.is_horizontal() {
+            width
+        } else {
+            height
+        }
+    }
+}
+
+/// Where a wipe starts revealing the frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Origin {
+    /// From the edge opposite the wipe direction
+    #[default]
+    Edge,
+    /// From the center line outwards along the wipe axis
+    Center,
 }
 
 /// Animation effects that can be applied
@@ -80,116 +211,549 @@ pub trait AnimationEffectTrait<const WIDTH: usize, const HEIGHT: usize, const CO
 pub enum AnimationEffect {
     /// No effect - frames are displayed as-is
     None,
-    /// Sliding effect - frames slide in from the right
-    Slide,
+    /// Sliding effect - the next frame slides in over the current frame,
+    /// moving in the given direction
+    Slide(Direction),
+    /// Push effect - the next frame moves in the given direction and pushes
+    /// the current frame off the display
+    Push(Direction),
     /// Fade effect - frames fade in and out
     Fade,
-    /// Wipe effect - frames are revealed column by column
-    Wipe,
+    /// Crossfade effect - frames blend gradually into the next frame
+    Crossfade,
+    /// Wipe effect - frames are revealed line by line in the given direction
+    Wipe(Direction, Origin),
+    /// Dissolve effect - pixels switch to the next frame in scattered order
+    Dissolve,
+    /// Iris effect - the next frame is revealed in a circle growing from the
+    /// center
+    Iris,
+    /// Curtain effect - the frame splits down the middle and both halves
+    /// slide apart to reveal the next frame
+    Curtain,
+    /// Pixelate effect - the frame breaks into growing blocks, then the next
+    /// frame resolves from blocks
+    Pixelate,
 }
 
 impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
     AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS> for AnimationEffect
 {
-    fn apply_effect(
+    fn apply_effect_into(
         &self,
-        current_frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
         next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
         progress: usize,
         _total_steps: usize,
-    ) -> Result<Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>, Hub75Error> {
-        match self {
-            AnimationEffect::None => Ok(current_frame.clone()),
-            AnimationEffect::Slide => self.apply_slide_effect(current_frame, next_frame, progress),
-            AnimationEffect::Fade => self.apply_fade_effect(current_frame, progress),
-            AnimationEffect::Wipe => self.apply_wipe_effect(current_frame, progress),
+    ) -> Result<(), Hub75Error> {
+        match *self {
+            AnimationEffect::None => Ok(()),
+            AnimationEffect::Slide(direction) => {
+                Self::apply_slide_effect(frame, next_frame, direction, progress)
+            }
+            AnimationEffect::Push(direction) => {
+                Self::apply_push_effect(frame, next_frame, direction, progress)
+            }
+            AnimationEffect::Fade => Self::apply_fade_effect(frame, progress),
+            AnimationEffect::Crossfade => Self::apply_crossfade_effect(frame, next_frame, progress),
+            AnimationEffect::Wipe(direction, origin) => {
+                Self::apply_wipe_effect(frame, direction, origin, progress)
+            }
+            AnimationEffect::Dissolve => Self::apply_dissolve_effect(frame, next_frame, progress),
+            AnimationEffect::Iris => Self::apply_iris_effect(frame, next_frame, progress),
+            AnimationEffect::Curtain => Self::apply_curtain_effect(frame, next_frame, progress),
+            AnimationEffect::Pixelate => Self::apply_pixelate_effect(frame, next_frame, progress),
         }
     }
 
     fn total_steps(&self, frame_count: usize) -> usize {
-        match self {
-            AnimationEffect::None => frame_count,
-            AnimationEffect::Slide => frame_count * WIDTH,
-            AnimationEffect::Fade => frame_count * 16,
-            AnimationEffect::Wipe => frame_count * WIDTH,
+        frame_count
+            * <Self as AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS>>::steps_per_frame(self)
+    }
+
+    fn steps_per_frame(&self) -> usize {
+        match *self {
+            AnimationEffect::None => 1,
+            AnimationEffect::Slide(direction) | AnimationEffect::Push(direction) => {
+                direction.span(WIDTH, HEIGHT)
+            }
+            AnimationEffect::Fade
+            | AnimationEffect::Crossfade
+            | AnimationEffect::Dissolve
+            | AnimationEffect::Pixelate => 16,
+            AnimationEffect::Wipe(direction, Origin::Edge) => direction.span(WIDTH, HEIGHT),
+            AnimationEffect::Wipe(direction, Origin::Center) => {
+                direction.span(WIDTH, HEIGHT).div_ceil(2)
+            }
+            AnimationEffect::Iris => {
+                // Enough steps for the circle to reach the corners
+                Self::iris_distance_squared(0, 0, WIDTH, HEIGHT).isqrt() / 2 + 1
+            }
+            AnimationEffect::Curtain => WIDTH.div_ceil(2),
         }
     }
+
+    fn uses_next_frame(&self) -> bool {
+        !matches!(
+            self,
+            AnimationEffect::None | AnimationEffect::Fade | AnimationEffect::Wipe(..)
+        )
+    }
 }
 
 impl AnimationEffect {
-    /// Apply slide effect
+    /// Apply one step of a transition between two frame sources
+    ///
+    /// `frame` holds the outgoing image on entry and `render_incoming` draws
+    /// the incoming one. Effects that read the next frame blend from outgoing
+    /// to incoming; the others (such as `Wipe` or `Fade`) are applied to the
+    /// incoming image alone, so they reveal the new content.
+    pub(crate) fn apply_transition<
+        const WIDTH: usize,
+        const HEIGHT: usize,
+        const COLOR_BITS: usize,
+    >(
+        self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        step: usize,
+        render_incoming: impl FnOnce(
+            &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        ) -> Result<(), Hub75Error>,
+    ) -> Result<(), Hub75Error> {
+        if !AnimationEffectTrait::<WIDTH, HEIGHT, COLOR_BITS>::uses_next_frame(&self) {
+            render_incoming(frame)?;
+            return self.apply_effect_into(frame, None, step, 0);
+        }
+        self.apply_transition_with_incoming(frame, step, render_incoming)
+    }
+
+    /// Apply a transition step, rendering the incoming image into a temporary
+    ///
+    /// Kept out of line so that the temporary only takes stack space when the
+    /// effect actually needs it.
+    #[inline(never)]
+    fn apply_transition_with_incoming<
+        const WIDTH: usize,
+        const HEIGHT: usize,
+        const COLOR_BITS: usize,
+    >(
+        self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        step: usize,
+        render_incoming: impl FnOnce(
+            &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        ) -> Result<(), Hub75Error>,
+    ) -> Result<(), Hub75Error> {
+        let mut incoming = Hub75FrameBuffer::new();
+        render_incoming(&mut incoming)?;
+        self.apply_effect_into(frame, Some(&incoming), step, 0)
+    }
+
+    /// Row `y` of the incoming frame, or a black row without one
+    fn incoming_row<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
+        y: usize,
+    ) -> Result<[Hub75Color<COLOR_BITS>; WIDTH], Hub75Error> {
+        match next_frame {
+            Some(next) => Ok(*next.get_row(y)?),
+            None => Ok([Hub75Color::black(); WIDTH]),
+        }
+    }
+
+    /// Apply slide effect in place
+    ///
+    /// The first `sequence` lines of the next frame (black without one) are
+    /// drawn over the current frame, entering from the edge opposite to
+    /// `direction`.
     fn apply_slide_effect<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
-        &self,
-        current_frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
         next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
+        direction: Direction,
         sequence: usize,
-    ) -> Result<Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>, Hub75Error> {
-        let mut result = Hub75FrameBuffer::new();
-        let next = next_frame.cloned().unwrap_or_else(Hub75FrameBuffer::new);
+    ) -> Result<(), Hub75Error> {
+        let shift = sequence.min(direction.span(WIDTH, HEIGHT));
 
-        for y in 0..HEIGHT {
-            for x in 0..WIDTH {
-                let pixel = if x + sequence < WIDTH {
-                    current_frame
-                        .get_pixel(x + sequence, y)
-                        .unwrap_or(Hub75Color::black())
-                } else {
-                    let next_x = x + sequence - WIDTH;
-                    next.get_pixel(next_x, y).unwrap_or(Hub75Color::black())
-                };
-                result.set_pixel(x, y, pixel)?;
+        match direction {
+            Direction::Left => {
+                for y in 0..HEIGHT {
+                    let incoming = Self::incoming_row(next_frame, y)?;
+                    frame.get_row_mut(y)?[WIDTH - shift..].copy_from_slice(&incoming[..shift]);
+                }
+            }
+            Direction::Right => {
+                for y in 0..HEIGHT {
+                    let incoming = Self::incoming_row(next_frame, y)?;
+                    frame.get_row_mut(y)?[..shift].copy_from_slice(&incoming[WIDTH - shift..]);
+                }
+            }
+            Direction::Up => {
+                for y in HEIGHT - shift..HEIGHT {
+                    *frame.get_row_mut(y)? = Self::incoming_row(next_frame, y + shift - HEIGHT)?;
+                }
+            }
+            Direction::Down => {
+                for y in 0..shift {
+                    *frame.get_row_mut(y)? = Self::incoming_row(next_frame, HEIGHT - shift + y)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply push effect in place
+    ///
+    /// The frame is shifted by `sequence` pixels in `direction` and the
+    /// uncovered lines are taken from the next frame (black without one).
+    fn apply_push_effect<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
+        direction: Direction,
+        sequence: usize,
+    ) -> Result<(), Hub75Error> {
+        let shift = sequence.min(direction.span(WIDTH, HEIGHT));
+
+        match direction {
+            Direction::Left => {
+                for y in 0..HEIGHT {
+                    let incoming = Self::incoming_row(next_frame, y)?;
+                    let row = frame.get_row_mut(y)?;
+                    row.copy_within(shift.., 0);
+                    row[WIDTH - shift..].copy_from_slice(&incoming[..shift]);
+                }
+            }
+            Direction::Right => {
+                for y in 0..HEIGHT {
+                    let incoming = Self::incoming_row(next_frame, y)?;
+                    let row = frame.get_row_mut(y)?;
+                    row.copy_within(..WIDTH - shift, shift);
+                    row[..shift].copy_from_slice(&incoming[WIDTH - shift..]);
+                }
+            }
+            Direction::Up => {
+                for y in 0..HEIGHT {
+                    let row = if y + shift < HEIGHT {
+                        *frame.get_row(y + shift)?
+                    } else {
+                        Self::incoming_row(next_frame, y + shift - HEIGHT)?
+                    };
+                    *frame.get_row_mut(y)? = row;
+                }
+            }
+            Direction::Down => {
+                for y in (0..HEIGHT).rev() {
+                    let row = if y >= shift {
+                        *frame.get_row(y - shift)?
+                    } else {
+                        Self::incoming_row(next_frame, HEIGHT - shift + y)?
+                    };
+                    *frame.get_row_mut(y)? = row;
+                }
             }
         }
-        Ok(result)
+        Ok(())
     }
 
-    /// Apply fade effect
+    /// Apply fade effect in place
     fn apply_fade_effect<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
-        &self,
-        current_frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
         sequence: usize,
-    ) -> Result<Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>, Hub75Error> {
-        let mut result = Hub75FrameBuffer::new();
+    ) -> Result<(), Hub75Error> {
         let fade_factor = if sequence < 8 {
             sequence
         } else {
             15 - sequence
         };
+        let brightness = (fade_factor * 255 / 7) as u8;
 
         for y in 0..HEIGHT {
-            for x in 0..WIDTH {
-                let original = current_frame.get_pixel(x, y)?;
-                let faded = Hub75Color::new(
-                    (original.r * fade_factor as u8) / 15,
-                    (original.g * fade_factor as u8) / 15,
-                    (original.b * fade_factor as u8) / 15,
-                );
-                result.set_pixel(x, y, faded)?;
+            for pixel in frame.get_row_mut(y)? {
+                *pixel = pixel.scale(brightness);
             }
         }
-        Ok(result)
+        Ok(())
     }
 
-    /// Apply wipe effect
-    fn apply_wipe_effect<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
-        &self,
-        current_frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    /// Apply crossfade effect in place
+    ///
+    /// Each of the 16 steps blends a further sixteenth of the way towards the
+    /// next frame (black without one); the next frame itself is shown fully
+    /// once the animation moves on to it.
+    fn apply_crossfade_effect<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
         sequence: usize,
-    ) -> Result<Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>, Hub75Error> {
-        let mut result = Hub75FrameBuffer::new();
+    ) -> Result<(), Hub75Error> {
+        let t = (sequence.min(15) * 16) as u8;
+
+        for y in 0..HEIGHT {
+            let incoming = Self::incoming_row(next_frame, y)?;
+            for (pixel, &target) in frame.get_row_mut(y)?.iter_mut().zip(incoming.iter()) {
+                *pixel = Hub75Color::lerp(*pixel, target, t);
+            }
+        }
+        Ok(())
+    }
+
+    /// Pseudo-random dissolve order of a pixel, from 0 to 15
+    ///
+    /// A fixed integer hash keeps the pattern stable from step to step
+    /// without needing a random number generator.
+    fn dissolve_order(x: usize, y: usize) -> usize {
+        let mut hash = (x as u32).wrapping_mul(0x9E37_79B1) ^ (y as u32).wrapping_mul(0x85EB_CA77);
+        hash ^= hash >> 15;
+        hash = hash.wrapping_mul(0x2C1B_3C6D);
+        hash ^= hash >> 12;
+        (hash & 0xF) as usize
+    }
+
+    /// Apply dissolve effect in place
+    ///
+    /// On each of the 16 steps roughly a sixteenth of the pixels switch to the
+    /// next frame (black without one).
+    fn apply_dissolve_effect<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
+        sequence: usize,
+    ) -> Result<(), Hub75Error> {
+        for y in 0..HEIGHT {
+            let incoming = Self::incoming_row(next_frame, y)?;
+            for (x, pixel) in frame.get_row_mut(y)?.iter_mut().enumerate() {
+                if Self::dissolve_order(x, y) < sequence {
+                    *pixel = incoming[x];
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Squared distance of pixel (x, y) from the display center, in half pixels
+    fn iris_distance_squared(x: usize, y: usize, width: usize, height: usize) -> usize {
+        let dx = (2 * x + 1).abs_diff(width);
+        let dy = (2 * y + 1).abs_diff(height);
+        dx * dx + dy * dy
+    }
+
+    /// Apply iris effect in place
+    ///
+    /// Pixels within `sequence` pixels of the center show the next frame
+    /// (black without one).
+    fn apply_iris_effect<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
+        sequence: usize,
+    ) -> Result<(), Hub75Error> {
+        let radius_squared = 4 * sequence * sequence;
+
+        for y in 0..HEIGHT {
+            let incoming = Self::incoming_row(next_frame, y)?;
+            for (x, pixel) in frame.get_row_mut(y)?.iter_mut().enumerate() {
+                if Self::iris_distance_squared(x, y, WIDTH, HEIGHT) < radius_squared {
+                    *pixel = incoming[x];
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply curtain effect in place
+    ///
+    /// Both halves of the frame move `sequence` columns outwards and the gap
+    /// between them shows the next frame (black without one).
+    fn apply_curtain_effect<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
+        sequence: usize,
+    ) -> Result<(), Hub75Error> {
+        let half = WIDTH / 2;
+        let shift = sequence.min(half);
 
         for y in 0..HEIGHT {
-            for x in 0..WIDTH {
-                if x <= sequence {
-                    let pixel = current_frame.get_pixel(x, y)?;
-                    result.set_pixel(x, y, pixel)?;
+            let incoming = Self::incoming_row(next_frame, y)?;
+            let row = frame.get_row_mut(y)?;
+            row.copy_within(shift..half, 0);
+            row.copy_within(half..WIDTH - shift, half + shift);
+            row[half - shift..half + shift].copy_from_slice(&incoming[half - shift..half + shift]);
+        }
+        Ok(())
+    }
+
+    /// Apply pixelate effect in place
+    ///
+    /// Over the first 8 steps the frame is drawn with blocks growing from 1 to
+    /// 8 pixels; over the last 8 the next frame (black without one) is drawn
+    /// with blocks shrinking back to single pixels. Each block takes the color
+    /// of its top-left pixel.
+    fn apply_pixelate_effect<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
+        sequence: usize,
+    ) -> Result<(), Hub75Error> {
+        let sequence = sequence.min(15);
+        let (block, from_next) = if sequence < 8 {
+            (sequence + 1, false)
+        } else {
+            (16 - sequence, true)
+        };
+
+        for y in (0..HEIGHT).step_by(block) {
+            let source = if from_next {
+                Self::incoming_row(next_frame, y)?
+            } else {
+                *frame.get_row(y)?
+            };
+            for x in (0..WIDTH).step_by(block) {
+                frame.fill_rect(x, y, block, block, source[x]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply wipe effect in place, blanking the lines not yet revealed
+    fn apply_wipe_effect<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        direction: Direction,
+        origin: Origin,
+        sequence: usize,
+    ) -> Result<(), Hub75Error> {
+        let span = direction.span(WIDTH, HEIGHT);
+        let (start, revealed) = match origin {
+            Origin::Edge => {
+                let revealed = (sequence + 1).min(span);
+                match direction {
+                    Direction::Right | Direction::Down => (0, revealed),
+                    Direction::Left | Direction::Up => (span - revealed, revealed),
+                }
+            }
+            Origin::Center => {
+                let revealed = (2 * (sequence + 1)).min(span);
+                ((span - revealed) / 2, revealed)
+            }
+        };
+        let end = start + revealed;
+
+        let black = Hub75Color::black();
+        if direction.is_horizontal() {
+            frame.fill_rect(0, 0, start, HEIGHT, black);
+            frame.fill_rect(end, 0, WIDTH - end, HEIGHT, black);
+        } else {
+            frame.fill_rect(0, 0, WIDTH, start, black);
+            frame.fill_rect(0, end, WIDTH, HEIGHT - end, black);
+        }
+        Ok(())
+    }
+}
+
+/// Easing curve applied to effect progress
+///
+/// By default effects advance at constant velocity. An easing curve remaps the
+/// position within each frame's transition so that, for example, a slide
+/// starts slowly and speeds up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Easing {
+    /// Constant velocity
+    #[default]
+    Linear,
+    /// Start slowly and accelerate (quadratic)
+    EaseIn,
+    /// Start quickly and decelerate (quadratic)
+    EaseOut,
+    /// Accelerate over the first half and decelerate over the second
+    EaseInOut,
+    /// Reach the end quickly and bounce against it, like a dropped ball
+    Bounce,
+}
+
+impl Easing {
+    /// Fixed-point representation of 1.0 used by [`apply`](Self::apply)
+    pub const ONE: u16 = u16::MAX;
+
+    /// Map a 0.16 fixed-point progress value through the curve
+    ///
+    /// `t` runs from 0 (start) to [`ONE`](Self::ONE) (end); the result uses the
+    /// same scale and always starts at 0 and ends at `ONE`.
+    pub fn apply(&self, t: u16) -> u16 {
+        const ONE: u32 = Easing::ONE as u32;
+        // k * t^2 for a 0.16 fraction, rounded
+        let square = |t: u32, k: u64| {
+            let t = t as u64;
+            ((k * t * t + ONE as u64 / 2) / ONE as u64) as u32
+        };
+
+        let t = t as u32;
+        let eased = match self {
+            Easing::Linear => t,
+            Easing::EaseIn => square(t, 1),
+            Easing::EaseOut => ONE - square(ONE - t, 1),
+            Easing::EaseInOut if t <= ONE / 2 => square(t, 2),
+            Easing::EaseInOut => ONE - square(ONE - t, 2),
+            Easing::Bounce => {
+                // Standard ease-out bounce: four parabolas of curvature
+                // 121/16, each rebound smaller than the last
+                let bounce = |center: u32, offset: u32| {
+                    let d = t.abs_diff(center) as u64;
+                    ((121 * d * d / 16 + ONE as u64 / 2) / ONE as u64) as u32 + offset
+                };
+                if t < ONE * 4 / 11 {
+                    bounce(0, 0)
+                } else if t < ONE * 8 / 11 {
+                    bounce(ONE * 6 / 11, ONE * 3 / 4)
+                } else if t < ONE * 10 / 11 {
+                    bounce(ONE * 9 / 11, ONE * 15 / 16)
+                } else {
+                    bounce(ONE * 21 / 22, ONE * 63 / 64)
                 }
             }
+        };
+        eased.min(ONE) as u16
+    }
+
+    /// Map step `step` of a transition lasting `steps` steps to its eased step
+    fn apply_step(&self, step: usize, steps: usize) -> usize {
+        if *self == Easing::Linear || steps <= 1 {
+            return step;
         }
-        Ok(result)
+
+        let one = Self::ONE as u64;
+        let steps = steps as u64;
+        let t = (step as u64).min(steps) * one / steps;
+        let eased = self.apply(t as u16) as u64;
+        ((eased * steps + one / 2) / one).min(steps - 1) as usize
     }
 }
 
+/// How an animation continues once it reaches its last step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PlaybackMode {
+    /// Play once, then report [`AnimationState::Done`]
+    #[default]
+    Once,
+    /// Restart from the first step forever
+    Loop,
+    /// Play `n` times in total, then report [`AnimationState::Done`]
+    LoopN(usize),
+    /// Play forwards, then backwards, forever
+    PingPong,
+}
+
+/// Number of undelivered events an animation keeps before dropping the oldest
+const EVENT_QUEUE_LEN: usize = 4;
+
+/// Notable moments during playback, polled with
+/// [`Animation::poll_event`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AnimationEvent {
+    /// Playback moved on to another frame of the animation data
+    FrameChanged(usize),
+    /// A play-through finished and playback continues, with the number of
+    /// play-throughs so far
+    LoopCompleted(usize),
+    /// The last play-through finished
+    Done,
+}
+
 /// Current state of an animation
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -198,107 +762,166 @@ pub enum AnimationState<const WIDTH: usize, const HEIGHT: usize, const COLOR_BIT
     Wait,
     /// Apply the given frame to the display
     Apply(Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>),
+    /// The buffer passed to [`Animation::next_into`] holds the next frame
+    Rendered,
     /// Animation is complete
     Done,
 }
 
+/// Closure drawing frame `index` into a frame buffer, see [`AnimationData::generator`]
+pub type FrameGenerator<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> =
+    dyn FnMut(usize, &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) + 'a;
+
 /// Animation data source
 pub enum AnimationData<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
     /// Array of frame buffers
     Frames(&'a [Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>]),
     /// Raw RGB data (width * height * 3 bytes per frame)
     RgbData(&'a [u8]),
-    /// Text data to be converted to frames
+    /// Text shown one character per frame, centered
     Text(&'a str),
+    /// Text scrolling in from the right and out to the left, one pixel per
+    /// frame
+    ScrollingText(&'a str),
+    /// Frames drawn on demand by a closure, see [`AnimationData::generator`]
+    Generator {
+        /// Number of frames the generator produces
+        frame_count: usize,
+        /// Closure drawing the frame with the given index
+        generate: RefCell<&'a mut FrameGenerator<'a, WIDTH, HEIGHT, COLOR_BITS>>,
+    },
 }
 
 impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
     AnimationData<'a, WIDTH, HEIGHT, COLOR_BITS>
 {
+    /// Create a procedural data source from a generator closure
+    ///
+    /// `generate(index, frame)` draws frame `index` into `frame`, which is
+    /// cleared beforehand. Nothing is pre-rendered, so procedural content such
+    /// as plasma or scrolling graphs costs no RAM beyond the frame being drawn.
+    ///
+    /// Effects that read the next frame request each index more than once, so
+    /// the output should depend only on `index`.
+    pub fn generator(
+        frame_count: usize,
+        generate: &'a mut FrameGenerator<'a, WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Self {
+        AnimationData::Generator {
+            frame_count,
+            generate: RefCell::new(generate),
+        }
+    }
+
     /// Get the number of frames in the animation data
     pub fn frame_count(&self) -> usize {
         match self {
+            AnimationData::Generator { frame_count, .. } => *frame_count,
             AnimationData::Frames(frames) => frames.len(),
             AnimationData::RgbData(data) => data.len() / (WIDTH * HEIGHT * 3),
-            AnimationData::Text(text) => text.len(), // One frame per character
+            AnimationData::Text(text) => text.chars().count(),
+            // Scroll until the last character has left the screen
+            AnimationData::ScrollingText(text) => font::text_width(text) + WIDTH,
         }
     }
 
     /// Get a specific frame from the animation data
+    ///
+    /// Returns the frame by value; prefer [`load_frame_into`](Self::load_frame_into)
+    /// for large frames.
     pub fn get_frame(
         &self,
         index: usize,
     ) -> Result<Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>, Hub75Error> {
+        let mut frame = Hub75FrameBuffer::new();
+        self.load_frame_into(index, &mut frame)?;
+        Ok(frame)
+    }
+
+    /// Load a specific frame from the animation data into a provided buffer
+    pub fn load_frame_into(
+        &self,
+        index: usize,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
         match self {
             AnimationData::Frames(frames) => {
-                if index < frames.len() {
-                    Ok(frames[index].clone())
-                } else {
-                    Err(Hub75Error::AnimationError(AnimationError::InvalidData))
-                }
+                let source = frames
+                    .get(index)
+                    .ok_or(Hub75Error::AnimationError(AnimationError::InvalidData))?;
+                frame.copy_from(source);
+                Ok(())
             }
             AnimationData::RgbData(data) => {
                 let frame_size = WIDTH * HEIGHT * 3;
                 let start = index * frame_size;
                 let end = start + frame_size;
 
-                if end <= data.len() {
-                    Hub75FrameBuffer::from_rgb_data(&data[start..end])
-                } else {
-                    Err(Hub75Error::AnimationError(AnimationError::InvalidData))
+                if end > data.len() {
+                    return Err(Hub75Error::AnimationError(AnimationError::InvalidData));
                 }
+                for (i, rgb) in data[start..end].chunks_exact(3).enumerate() {
+                    let color = Hub75Color::from_rgb8(rgb[0], rgb[1], rgb[2]);
+                    // SAFETY: i < WIDTH * HEIGHT, so both coordinates are in bounds
+                    unsafe { frame.set_pixel_unchecked(i % WIDTH, i / WIDTH, color) };
+                }
+                Ok(())
             }
             AnimationData::Text(text) => {
-                // For text, create a frame with the character at the given index
-                if index < text.len() {
-                    let mut frame = Hub75FrameBuffer::new();
-                    // This is a simplified implementation - in practice you'd want
-                    // to use a font renderer here
-                    let char_byte = text.as_bytes()[index];
-                    self.render_character_to_frame(&mut frame, char_byte)?;
-                    Ok(frame)
-                } else {
-                    Err(Hub75Error::AnimationError(AnimationError::InvalidData))
+                let c = text
+                    .chars()
+                    .nth(index)
+                    .ok_or(Hub75Error::AnimationError(AnimationError::InvalidData))?;
+                frame.clear();
+                let x = (WIDTH as i32 - GLYPH_WIDTH as i32) / 2;
+                frame.draw_char(c, x, Self::text_top(), Hub75Color::white());
+                Ok(())
+            }
+            AnimationData::ScrollingText(text) => {
+                if index >= self.frame_count() {
+                    return Err(Hub75Error::AnimationError(AnimationError::InvalidData));
                 }
+                frame.clear();
+                let x = WIDTH as i32 - index as i32;
+                frame.draw_text(text, x, Self::text_top(), Hub75Color::white());
+                Ok(())
             }
-        }
-    }
-
-    /// Render a character to a frame buffer (simplified implementation)
-    fn render_character_to_frame(
-        &self,
-        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
-        char_byte: u8,
-    ) -> Result<(), Hub75Error> {
-        // This is a very basic 5x5 font implementation
-        // In a real implementation, you'd use a proper font library
-        let pattern = match char_byte {
-            b'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001],
-            b'B' => [0b11110, 0b10001, 0b11110, 0b10001, 0b11110],
-            b'C' => [0b01111, 0b10000, 0b10000, 0b10000, 0b01111],
-            _ => [0b11111, 0b10001, 0b10001, 0b10001, 0b11111], // Default pattern
-        };
-
-        let color = Hub75Color::white();
-        let start_x = (WIDTH - 5) / 2; // Center horizontally
-        let start_y = (HEIGHT - 5) / 2; // Center vertically
-
-        for (y, &row) in pattern.iter().enumerate() {
-            for x in 0..5 {
-                if (row >> (4 - x)) & 1 == 1 {
-                    if start_x + x < WIDTH && start_y + y < HEIGHT {
-                        frame.set_pixel(start_x + x, start_y + y, color)?;
-                    }
+            AnimationData::Generator {
+                frame_count,
+                generate,
+            } => {
+                if index >= *frame_count {
+                    return Err(Hub75Error::AnimationError(AnimationError::InvalidData));
                 }
+                // Only fails if the generator itself tries to load a frame
+                let mut generate = generate
+                    .try_borrow_mut()
+                    .map_err(|_| Hub75Error::AnimationError(AnimationError::InvalidData))?;
+                frame.clear();
+                generate(index, frame);
+                Ok(())
             }
         }
+    }
 
-        Ok(())
+    /// Top row of text centered vertically in the frame
+    fn text_top() -> i32 {
+        (HEIGHT as i32 - GLYPH_HEIGHT as i32) / 2
     }
 }
 
 /// Animation controller
-pub struct Animation<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+///
+/// Generic over the effect, so custom transitions can be used by implementing
+/// [`AnimationEffectTrait`]. A `&dyn AnimationEffectTrait` also works when the
+/// effect is chosen at runtime.
+pub struct Animation<
+    'a,
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    E = AnimationEffect,
+> {
     /// Animation data source
     data: AnimationData<'a, WIDTH, HEIGHT, COLOR_BITS>,
     /// Current frame index
@@ -310,20 +933,53 @@ pub struct Animation<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BI
     /// Total number of steps in the animation
     total_steps: usize,
     /// Animation effect to apply
-    effect: AnimationEffect,
+    effect: E,
+    /// Easing curve applied to the effect progress
+    easing: Easing,
+    /// What happens after the last step
+    playback_mode: PlaybackMode,
+    /// Number of times the animation has played through
+    plays: usize,
+    /// Whether playback was requested to run backwards
+    reverse: bool,
+    /// Whether steps are currently being played backwards
+    reversed: bool,
+    /// Whether playback is paused
+    paused: bool,
+    /// Whether a sought step should be rendered without waiting
+    seek_pending: bool,
+    /// Whether frames between steps blend towards the next step
+    interpolate: bool,
+    /// Step most recently rendered by `next_into`
+    shown_step: usize,
+    /// Events not yet taken by `poll_event`
+    events: Deque<AnimationEvent, EVENT_QUEUE_LEN>,
     /// Number of frames between steps
     frames_per_step: usize,
-    /// Current frame counter
+    /// Current frame counter, in units of 1/`speed_denominator` frames
     frame_counter: usize,
+    /// Playback speed numerator
+    speed_numerator: usize,
+    /// Playback speed denominator
+    speed_denominator: usize,
+    /// Duration of one step for time-based playback, in milliseconds
+    step_duration_ms: u32,
+    /// Time of the previous `next_at_into` call, if time-based playback started
+    last_time_ms: Option<u64>,
+    /// Elapsed time since the shown step began, in units of
+    /// 1/`speed_denominator` milliseconds
+    time_counter: u64,
 }
 
-impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
-    Animation<'a, WIDTH, HEIGHT, COLOR_BITS>
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, E>
+    Animation<'a, WIDTH, HEIGHT, COLOR_BITS, E>
+where
+    E: AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS>,
 {
     /// Create a new animation
     pub fn new(
         data: AnimationData<'a, WIDTH, HEIGHT, COLOR_BITS>,
-        effect: AnimationEffect,
+        effect: E,
         total_frames: usize,
     ) -> Result<Self, AnimationError> {
         let frame_count = data.frame_count();
@@ -331,11 +987,7 @@ impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
             return Err(AnimationError::InvalidData);
         }
 
-        let total_steps =
-            <AnimationEffect as AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS>>::total_steps(
-                &effect,
-                frame_count,
-            );
+        let total_steps = effect.total_steps(frame_count);
         let frames_per_step = total_frames / total_steps.max(1);
 
         Ok(Self {
@@ -345,87 +997,494 @@ impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
             step: 0,
             total_steps,
             effect,
+            easing: Easing::Linear,
+            playback_mode: PlaybackMode::Once,
+            plays: 0,
+            reverse: false,
+            reversed: false,
+            paused: false,
+            seek_pending: false,
+            interpolate: false,
+            shown_step: 0,
+            events: Deque::new(),
             frames_per_step,
             frame_counter: 0,
+            speed_numerator: 1,
+            speed_denominator: 1,
+            step_duration_ms: 0,
+            last_time_ms: None,
+            time_counter: 0,
         })
     }
 
     /// Get the next animation state
+    ///
+    /// Returns the frame by value; prefer [`next_into`](Self::next_into) for
+    /// large frames.
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        let mut frame = Hub75FrameBuffer::new();
+        match self.next_into(&mut frame) {
+            AnimationState::Rendered => AnimationState::Apply(frame),
+            state => state,
+        }
+    }
+
+    /// Advance the animation, rendering into a provided frame buffer
+    ///
+    /// Returns [`AnimationState::Rendered`] when `frame` has been updated with
+    /// the next frame, typically the display's back buffer. Never returns
+    /// [`AnimationState::Apply`], so no frame buffer is moved on the stack.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
         if self.step >= self.total_steps {
             return AnimationState::Done;
         }
-
-        self.frame_counter += 1;
-        if self.frame_counter < self.frames_per_step {
+        if self.seek_pending {
+            self.seek_pending = false;
+            self.frame_counter = 0;
+        } else if self.paused {
             return AnimationState::Wait;
-        }
+        } else {
+            self.frame_counter += self.speed_numerator;
+            let threshold = self.frames_per_step.max(1) * self.speed_denominator;
+            if self.frame_counter < threshold {
+                if self.interpolate && self.shown_step != self.step {
+                    let t = (self.frame_counter * 255 / threshold) as u8;
+                    return self.render_interpolated(self.shown_step, self.step, t, frame);
+                }
+                return AnimationState::Wait;
+            }
 
-        // Reset frame counter for next step
-        self.frame_counter = 0;
+            // Faster than one step per frame: skip the steps that are due but
+            // would never be seen
+            for _ in 1..self.frame_counter / threshold {
+                self.advance_step();
+            }
+            self.frame_counter %= threshold;
+
+            if self.step >= self.total_steps {
+                return AnimationState::Done;
+            }
+        }
 
         // Generate the current frame based on the effect
-        let frame = match self.generate_current_frame() {
-            Ok(frame) => frame,
-            Err(_) => return AnimationState::Done,
-        };
+        if self.render_current_frame(frame).is_err() {
+            return AnimationState::Done;
+        }
+        self.shown_step = self.step;
 
-        // Advance to the next step
-        self.advance_step();
+        // Advance to the next step, holding the sought step while paused
+        if !self.paused {
+            self.advance_step();
+        }
 
-        AnimationState::Apply(frame)
+        AnimationState::Rendered
     }
 
-    /// Generate the current frame based on the effect and current state
-    fn generate_current_frame(
-        &self,
-    ) -> Result<Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>, Hub75Error> {
-        let current_frame = self.data.get_frame(self.frame_index)?;
-        let next_frame = if self.frame_index + 1 < self.data.frame_count() {
-            Some(self.data.get_frame(self.frame_index + 1)?)
+    /// Get the next animation state for the given time
+    ///
+    /// Returns the frame by value; prefer [`next_at_into`](Self::next_at_into)
+    /// for large frames.
+    pub fn next_at(&mut self, now_ms: u64) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        let mut frame = Hub75FrameBuffer::new();
+        match self.next_at_into(now_ms, &mut frame) {
+            AnimationState::Rendered => AnimationState::Apply(frame),
+            state => state,
+        }
+    }
+
+    /// Advance the animation to the given time, rendering into a provided
+    /// frame buffer
+    ///
+    /// An alternative to [`next_into`](Self::next_into) for loops that do not
+    /// run at a steady rate. `now_ms` is a monotonic timestamp from the
+    /// caller's clock; the first call shows the current step and later calls
+    /// move on by however many steps have elapsed, skipping any that were
+    /// missed. Set the step duration with
+    /// [`set_duration_ms`](Self::set_duration_ms) first, and do not mix with
+    /// frame-based stepping on the same animation.
+    pub fn next_at_into(
+        &mut self,
+        now_ms: u64,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        let elapsed = self
+            .last_time_ms
+            .map_or(0, |last| now_ms.saturating_sub(last));
+        let started = self.last_time_ms.is_some();
+        self.last_time_ms = Some(now_ms);
+
+        if self.step >= self.total_steps {
+            return AnimationState::Done;
+        }
+        if self.seek_pending || !started {
+            self.seek_pending = false;
+            self.time_counter = 0;
+        } else if self.paused {
+            return AnimationState::Wait;
         } else {
+            self.time_counter += elapsed * self.speed_numerator as u64;
+            let threshold = self.step_duration_ms.max(1) as u64 * self.speed_denominator as u64;
+            if self.time_counter < threshold {
+                return match self.following_step() {
+                    Some(next) if self.interpolate => {
+                        let t = (self.time_counter * 255 / threshold) as u8;
+                        self.render_interpolated(self.step, next, t, frame)
+                    }
+                    _ => AnimationState::Wait,
+                };
+            }
+
+            for _ in 0..self.time_counter / threshold {
+                self.advance_step();
+                if self.step >= self.total_steps {
+                    return AnimationState::Done;
+                }
+            }
+            self.time_counter %= threshold;
+        }
+
+        if self.render_current_frame(frame).is_err() {
+            return AnimationState::Done;
+        }
+        self.shown_step = self.step;
+
+        AnimationState::Rendered
+    }
+
+    /// Take the oldest event that occurred during playback
+    ///
+    /// Call after each step to react to frame changes, loops and the end of
+    /// the animation. Only the most recent few events are kept.
+    pub fn poll_event(&mut self) -> Option<AnimationEvent> {
+        self.events.pop_front()
+    }
+
+    /// Set the duration of one play-through for time-based playback
+    ///
+    /// The duration is split evenly over the steps, at least one millisecond
+    /// each.
+    pub fn set_duration_ms(&mut self, duration_ms: u32) {
+        self.step_duration_ms = (duration_ms / self.total_steps.max(1) as u32).max(1);
+    }
+
+    /// Set the duration of a single step for time-based playback
+    pub fn set_step_duration_ms(&mut self, step_duration_ms: u32) {
+        self.step_duration_ms = step_duration_ms.max(1);
+    }
+
+    /// Get the duration of a single step for time-based playback
+    pub fn step_duration_ms(&self) -> u32 {
+        self.step_duration_ms
+    }
+
+    /// Set the easing curve applied to the effect progress
+    pub fn set_easing(&mut self, easing: Easing) {
+        self.easing = easing;
+    }
+
+    /// Get the current easing curve
+    pub fn easing(&self) -> Easing {
+        self.easing
+    }
+
+    /// Set what happens when the animation reaches its last step
+    pub fn set_playback_mode(&mut self, mode: PlaybackMode) {
+        self.playback_mode = mode;
+    }
+
+    /// Get the current playback mode
+    pub fn playback_mode(&self) -> PlaybackMode {
+        self.playback_mode
+    }
+
+    /// Set the playback speed as the fraction `numerator / denominator`
+    ///
+    /// `(1, 1)` is the speed given by `total_frames` at creation, `(1, 2)`
+    /// plays at half speed and `(3, 1)` at triple speed. When more than one
+    /// step falls due in a single frame, the steps in between are skipped.
+    /// A zero numerator holds the current step.
+    pub fn set_speed(
+        &mut self,
+        numerator: usize,
+        denominator: usize,
+    ) -> Result<(), AnimationError> {
+        if denominator == 0 {
+            return Err(AnimationError::InvalidDuration);
+        }
+
+        // Keep the progress towards the next step across the change
+        self.frame_counter = self.frame_counter * denominator / self.speed_denominator;
+        self.time_counter = self.time_counter * denominator as u64 / self.speed_denominator as u64;
+        self.speed_numerator = numerator;
+        self.speed_denominator = denominator;
+        Ok(())
+    }
+
+    /// Get the playback speed as `(numerator, denominator)`
+    pub fn speed(&self) -> (usize, usize) {
+        (self.speed_numerator, self.speed_denominator)
+    }
+
+    /// Pause playback
+    ///
+    /// While paused, [`next_into`](Self::next_into) keeps returning
+    /// [`AnimationState::Wait`] and the position does not change.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resume paused playback
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Check if playback is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Jump to the given step
+    ///
+    /// Steps past the end are clamped, leaving the animation done. The step is
+    /// rendered on the next call to [`next_into`](Self::next_into), even while
+    /// the animation is paused, so scrubbing gives immediate feedback.
+    pub fn seek(&mut self, step: usize) {
+        self.step = step.min(self.total_steps);
+        self.sync_position();
+        self.seek_pending = true;
+    }
+
+    /// Blend between steps on frames that would otherwise wait
+    ///
+    /// When the display refreshes faster than the content changes, for
+    /// example a 10 fps GIF shown at 60 Hz, the frames between two steps
+    /// return [`AnimationState::Rendered`] with the shown step blended
+    /// towards the next one in proportion to the time elapsed, instead of
+    /// [`AnimationState::Wait`]. Smooths low frame rate content at the cost
+    /// of rendering two steps into a temporary frame buffer on those frames.
+    /// Time-based playback only blends towards the following step of the
+    /// current play-through.
+    pub fn set_interpolation(&mut self, interpolate: bool) {
+        self.interpolate = interpolate;
+    }
+
+    /// Check if frames between steps are interpolated
+    pub fn is_interpolating(&self) -> bool {
+        self.interpolate
+    }
+
+    /// Play steps backwards (or forwards again)
+    ///
+    /// Takes effect from the current step. A paused or finished animation stays
+    /// that way until resumed, sought or reset.
+    pub fn set_reverse(&mut self, reverse: bool) {
+        self.reverse = reverse;
+        self.reversed = reverse;
+    }
+
+    /// Check if playback was set to run backwards
+    pub fn is_reversed(&self) -> bool {
+        self.reverse
+    }
+
+    /// Get the step that will be rendered next
+    pub fn current_step(&self) -> usize {
+        self.step
+    }
+
+    /// Get the total number of steps in one play-through
+    pub fn total_steps(&self) -> usize {
+        self.total_steps
+    }
+
+    /// Render the given step into `frame` without moving the playback position
+    ///
+    /// Steps past the end render the last step.
+    pub fn render_step_into(
+        &mut self,
+        step: usize,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        let current = self.step;
+        self.step = step.min(self.total_steps.saturating_sub(1));
+        self.sync_position();
+        let result = self.render_current_frame(frame);
+        self.step = current;
+        self.sync_position();
+        result
+    }
+
+    /// Render the step most recently shown by [`next_into`](Self::next_into)
+    /// again
+    ///
+    /// Before the first step is shown, and after a reset, this is the first
+    /// step.
+    pub fn render_current_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        self.render_step_into(self.shown_step, frame)
+    }
+
+    /// Step after the current one in the playback direction, without wrapping
+    fn following_step(&self) -> Option<usize> {
+        if self.paused {
             None
-        };
+        } else if self.reversed {
+            self.step.checked_sub(1)
+        } else {
+            Some(self.step + 1).filter(|&next| next < self.total_steps)
+        }
+    }
 
-        <AnimationEffect as AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS>>::apply_effect(
-            &self.effect,
-            &current_frame,
-            next_frame.as_ref(),
-            self.sequence,
-            self.total_steps,
-        )
+    /// Render step `from` blended towards step `to`
+    ///
+    /// `t` is a 0.8 fixed-point fraction of the way to `to`. Kept out of line
+    /// so that the temporary only takes stack space when interpolating.
+    #[inline(never)]
+    fn render_interpolated(
+        &mut self,
+        from: usize,
+        to: usize,
+        t: u8,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        let mut next_frame = Hub75FrameBuffer::new();
+        if self.render_step_into(from, frame).is_err()
+            || self.render_step_into(to, &mut next_frame).is_err()
+        {
+            return AnimationState::Done;
+        }
+        frame.lerp(&next_frame, t);
+        AnimationState::Rendered
+    }
+
+    /// Effect progress for the current step, after easing
+    fn progress(&self) -> usize {
+        self.easing
+            .apply_step(self.sequence, self.effect.steps_per_frame())
+    }
+
+    /// Render the current frame based on the effect and current state
+    fn render_current_frame(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        self.data.load_frame_into(self.frame_index, frame)?;
+
+        let next_index = self.frame_index + 1;
+        if !self.effect.uses_next_frame() || next_index >= self.data.frame_count() {
+            return self
+                .effect
+                .apply_effect_into(frame, None, self.progress(), self.total_steps);
+        }
+
+        // Borrow the next frame directly when the data holds frame buffers
+        match self.data {
+            AnimationData::Frames(frames) => self.effect.apply_effect_into(
+                frame,
+                frames.get(next_index),
+                self.progress(),
+                self.total_steps,
+            ),
+            _ => self.render_with_loaded_next_frame(frame, next_index),
+        }
+    }
+
+    /// Render the current frame, decoding the next frame into a temporary buffer
+    ///
+    /// Kept out of line so that the temporary only takes stack space when an
+    /// effect actually needs it.
+    #[inline(never)]
+    fn render_with_loaded_next_frame(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        next_index: usize,
+    ) -> Result<(), Hub75Error> {
+        let mut next_frame = Hub75FrameBuffer::new();
+        self.data.load_frame_into(next_index, &mut next_frame)?;
+        self.effect
+            .apply_effect_into(frame, Some(&next_frame), self.progress(), self.total_steps)
     }
 
     /// Advance to the next step in the animation
+    ///
+    /// Wraps around or turns back at the ends according to the playback mode.
     fn advance_step(&mut self) {
-        self.step += 1;
+        let frame_index = self.frame_index;
+        let at_end = if self.reversed {
+            self.step == 0
+        } else {
+            self.step + 1 >= self.total_steps
+        };
 
-        match self.effect {
-            AnimationEffect::None => {
-                self.frame_index = self.step;
-            }
-            AnimationEffect::Slide => {
-                self.sequence += 1;
-                if self.sequence >= WIDTH {
-                    self.sequence = 0;
-                    self.frame_index += 1;
-                }
-            }
-            AnimationEffect::Fade => {
-                self.sequence += 1;
-                if self.sequence >= 16 {
-                    self.sequence = 0;
-                    self.frame_index += 1;
-                }
-            }
-            AnimationEffect::Wipe => {
-                self.sequence += 1;
-                if self.sequence >= WIDTH {
-                    self.sequence = 0;
-                    self.frame_index += 1;
-                }
+        if at_end {
+            self.finish_play();
+        } else if self.reversed {
+            self.step -= 1;
+        } else {
+            self.step += 1;
+        }
+
+        self.sync_position();
+        if self.frame_index != frame_index && self.step < self.total_steps {
+            self.push_event(AnimationEvent::FrameChanged(self.frame_index));
+        }
+    }
+
+    /// Queue an event, dropping the oldest one when the queue is full
+    fn push_event(&mut self, event: AnimationEvent) {
+        if self.events.is_full() {
+            self.events.pop_front();
+        }
+        let _ = self.events.push_back(event);
+    }
+
+    /// First step of a play-through in the current direction
+    fn first_step(&self) -> usize {
+        if self.reversed {
+            self.total_steps.saturating_sub(1)
+        } else {
+            0
+        }
+    }
+
+    /// Handle reaching the last step according to the playback mode
+    ///
+    /// Finishing moves the step past the end, which marks the animation done.
+    fn finish_play(&mut self) {
+        self.plays += 1;
+        match self.playback_mode {
+            PlaybackMode::Loop => self.step = self.first_step(),
+            PlaybackMode::LoopN(n) if self.plays < n => self.step = self.first_step(),
+            PlaybackMode::Once | PlaybackMode::LoopN(_) => self.step = self.total_steps,
+            PlaybackMode::PingPong => {
+                // The end step was just shown, so continue from its neighbour
+                self.reversed = !self.reversed;
+                self.step = if self.reversed {
+                    self.total_steps.saturating_sub(2)
+                } else {
+                    1.min(self.total_steps - 1)
+                };
             }
         }
+
+        self.push_event(if self.step >= self.total_steps {
+            AnimationEvent::Done
+        } else {
+            AnimationEvent::LoopCompleted(self.plays)
+        });
+    }
+
+    /// Derive the frame index and sequence position from the current step
+    fn sync_position(&mut self) {
+        let steps_per_frame = self.effect.steps_per_frame().max(1);
+        self.frame_index = self.step / steps_per_frame;
+        self.sequence = self.step % steps_per_frame;
     }
 
     /// Check if the animation is complete
@@ -434,11 +1493,20 @@ impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
     }
 
     /// Reset the animation to the beginning
+    ///
+    /// When playing in reverse, the beginning is the last step. The playback
+    /// mode, direction and pause state are kept.
     pub fn reset(&mut self) {
-        self.frame_index = 0;
-        self.sequence = 0;
-        self.step = 0;
+        self.reversed = self.reverse;
+        self.step = self.first_step();
+        self.shown_step = self.step;
+        self.sync_position();
         self.frame_counter = 0;
+        self.last_time_ms = None;
+        self.time_counter = 0;
+        self.plays = 0;
+        self.seek_pending = false;
+        self.events.clear();
     }
 }
 
@@ -475,15 +1543,44 @@ mod tests {
         let data = AnimationData::Frames(&frames);
         assert_eq!(data.frame_count(), 3);
 
-        let text_data = AnimationData::<32, 16, 6>::Text("Hello");
+        let text_data = AnimationData::<32, 16, 6>::Text("Héllo");
         assert_eq!(text_data.frame_count(), 5);
+        let text_data = AnimationData::<32, 16, 6>::ScrollingText("Héllo");
+        assert_eq!(text_data.frame_count(), 5 * 6 + 32);
 
         // RGB data: 32 * 16 * 3 = 1536 bytes per frame
-        let rgb_data = vec![0u8; 1536 * 2]; // 2 frames
+        let rgb_data = [0u8; 1536 * 2]; // 2 frames
         let rgb_animation_data = AnimationData::<32, 16, 6>::RgbData(&rgb_data);
         assert_eq!(rgb_animation_data.frame_count(), 2);
     }
 
+    #[test]
+    fn test_text_frames() {
+        let lit = |frame: &Hub75FrameBuffer<12, 8, 6>| {
+            (0..8)
+                .flat_map(|y| (0..12).map(move |x| (x, y)))
+                .filter(|&(x, y)| frame.get_pixel(x, y).unwrap() != Hub75Color::black())
+                .count()
+        };
+
+        // Multi-byte characters count once
+        let text = AnimationData::<12, 8, 6>::Text("éA");
+        assert_eq!(text.frame_count(), 2);
+        let a = text.get_frame(1).unwrap();
+        assert!(lit(&a) > 0);
+        assert_ne!(a, text.get_frame(0).unwrap());
+        assert!(text.get_frame(2).is_err());
+
+        // Scrolling text enters from the right and leaves to the left
+        let scrolling = AnimationData::<12, 8, 6>::ScrollingText("AA");
+        let last = scrolling.frame_count() - 1;
+        assert_eq!(lit(&scrolling.get_frame(0).unwrap()), 0);
+        assert_eq!(lit(&scrolling.get_frame(12).unwrap()), 2 * lit(&a));
+        assert_eq!(lit(&scrolling.get_frame(last).unwrap()), 0);
+        assert!(lit(&scrolling.get_frame(last - font::GLYPH_ADVANCE).unwrap()) > 0);
+        assert!(scrolling.get_frame(last + 1).is_err());
+    }
+
     #[test]
     fn test_animation_effects() {
         let frames = [Hub75FrameBuffer::<32, 16, 6>::new()];
@@ -498,11 +1595,642 @@ mod tests {
 
         let slide_anim = Animation::new(
             AnimationData::Frames(&frames),
-            AnimationEffect::Slide,
+            AnimationEffect::Slide(Direction::Left),
             60, // 60 frames total
         )
         .unwrap();
 
         assert!(slide_anim.total_steps > none_anim.total_steps);
     }
+
+    #[test]
+    fn test_effects_in_place() {
+        let mut current = Hub75FrameBuffer::<8, 2, 6>::new();
+        current.fill(Hub75Color::red());
+        let mut next = Hub75FrameBuffer::<8, 2, 6>::new();
+        next.fill(Hub75Color::blue());
+
+        let mut frame = current.clone();
+        AnimationEffect::Slide(Direction::Left)
+            .apply_effect_into(&mut frame, Some(&next), 3, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(4, 1).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(5, 1).unwrap(), Hub75Color::blue());
+
+        let mut frame = current.clone();
+        AnimationEffect::Wipe(Direction::Right, Origin::Edge)
+            .apply_effect_into(&mut frame, None, 2, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(2, 0).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(3, 0).unwrap(), Hub75Color::black());
+
+        // The by-value variant matches the in-place result
+        let faded = AnimationEffect::Fade
+            .apply_effect(&current, None, 4, 0)
+            .unwrap();
+        let mut frame = current.clone();
+        AnimationEffect::Fade
+            .apply_effect_into(&mut frame, None, 4, 0)
+            .unwrap();
+        assert_eq!(faded, frame);
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::new(36, 0, 0));
+
+        let mut frame = current.clone();
+        AnimationEffect::Crossfade
+            .apply_effect_into(&mut frame, Some(&next), 8, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::new(31, 0, 32));
+
+        let mut frame = current.clone();
+        AnimationEffect::Crossfade
+            .apply_effect_into(&mut frame, Some(&next), 0, 0)
+            .unwrap();
+        assert_eq!(frame, current);
+    }
+
+    #[test]
+    fn test_effect_directions() {
+        let mut current = Hub75FrameBuffer::<4, 4, 6>::new();
+        current.fill(Hub75Color::red());
+        let mut next = Hub75FrameBuffer::<4, 4, 6>::new();
+        next.fill(Hub75Color::blue());
+        next.set_pixel(0, 3, Hub75Color::green()).unwrap();
+
+        let mut frame = current.clone();
+        AnimationEffect::Slide(Direction::Right)
+            .apply_effect_into(&mut frame, Some(&next), 1, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::blue());
+        assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::red());
+
+        // Sliding up pulls the top rows of the next frame in from the bottom
+        let mut frame = current.clone();
+        AnimationEffect::Slide(Direction::Up)
+            .apply_effect_into(&mut frame, Some(&next), 1, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(0, 2).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(0, 3).unwrap(), Hub75Color::blue());
+
+        // Sliding down pulls the bottom rows of the next frame in from the top
+        let mut frame = current.clone();
+        AnimationEffect::Slide(Direction::Down)
+            .apply_effect_into(&mut frame, Some(&next), 1, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::green());
+        assert_eq!(frame.get_pixel(0, 1).unwrap(), Hub75Color::red());
+
+        let mut frame = current.clone();
+        AnimationEffect::Wipe(Direction::Up, Origin::Edge)
+            .apply_effect_into(&mut frame, None, 0, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(0, 2).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(0, 3).unwrap(), Hub75Color::red());
+
+        let mut frame = current.clone();
+        AnimationEffect::Wipe(Direction::Left, Origin::Center)
+            .apply_effect_into(&mut frame, None, 0, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(2, 0).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(3, 0).unwrap(), Hub75Color::black());
+
+        let center = AnimationEffect::Wipe(Direction::Left, Origin::Center);
+        assert_eq!(AnimationEffectTrait::<4, 4, 6>::steps_per_frame(&center), 2);
+    }
+
+    #[test]
+    fn test_transition_effects() {
+        let mut current = Hub75FrameBuffer::<8, 4, 6>::new();
+        current.fill(Hub75Color::red());
+        current.set_pixel(7, 0, Hub75Color::green()).unwrap();
+        let mut next = Hub75FrameBuffer::<8, 4, 6>::new();
+        next.fill(Hub75Color::blue());
+
+        // Slide covers the current frame, push moves it along
+        let mut frame = current.clone();
+        AnimationEffect::Slide(Direction::Right)
+            .apply_effect_into(&mut frame, Some(&next), 2, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::blue());
+        assert_eq!(frame.get_pixel(7, 0).unwrap(), Hub75Color::green());
+
+        let mut frame = current.clone();
+        AnimationEffect::Push(Direction::Left)
+            .apply_effect_into(&mut frame, Some(&next), 2, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(5, 0).unwrap(), Hub75Color::green());
+        assert_eq!(frame.get_pixel(6, 0).unwrap(), Hub75Color::blue());
+
+        let mut frame = current.clone();
+        AnimationEffect::Dissolve
+            .apply_effect_into(&mut frame, Some(&next), 0, 0)
+            .unwrap();
+        assert_eq!(frame, current);
+        AnimationEffect::Dissolve
+            .apply_effect_into(&mut frame, Some(&next), 16, 0)
+            .unwrap();
+        assert_eq!(frame, next);
+
+        let mut frame = current.clone();
+        AnimationEffect::Iris
+            .apply_effect_into(&mut frame, Some(&next), 1, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(3, 1).unwrap(), Hub75Color::blue());
+        assert_eq!(frame.get_pixel(4, 2).unwrap(), Hub75Color::blue());
+        assert_eq!(frame.get_pixel(2, 1).unwrap(), Hub75Color::red());
+
+        let mut frame = current.clone();
+        AnimationEffect::Curtain
+            .apply_effect_into(&mut frame, None, 1, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(2, 0).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(3, 0).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(4, 0).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(5, 0).unwrap(), Hub75Color::red());
+
+        // Blocks take the color of their top-left pixel
+        let mut frame = current.clone();
+        AnimationEffect::Pixelate
+            .apply_effect_into(&mut frame, Some(&next), 3, 0)
+            .unwrap();
+        assert_eq!(frame.get_pixel(7, 3).unwrap(), Hub75Color::red());
+        AnimationEffect::Pixelate
+            .apply_effect_into(&mut frame, Some(&next), 8, 0)
+            .unwrap();
+        assert_eq!(frame, next);
+    }
+
+    #[test]
+    fn test_easing() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::Bounce,
+        ] {
+            assert_eq!(easing.apply(0), 0);
+            assert_eq!(easing.apply(Easing::ONE), Easing::ONE);
+        }
+
+        let quarter = Easing::ONE / 4;
+        assert!(Easing::EaseIn.apply(quarter) < quarter);
+        assert!(Easing::EaseOut.apply(quarter) > quarter);
+        assert_eq!(Easing::EaseInOut.apply(Easing::ONE / 2), Easing::ONE / 2);
+        // The first bounce lands back on the floor at 4/11
+        let eleventh = Easing::ONE / 11;
+        assert!(Easing::Bounce.apply(eleventh * 4) > Easing::ONE - 64);
+        assert!(Easing::Bounce.apply(eleventh * 6) < Easing::ONE / 4 * 3 + 8);
+
+        // Steps stay in range and start at zero
+        for step in 0..16 {
+            assert!(Easing::EaseOut.apply_step(step, 16) < 16);
+        }
+        assert_eq!(Easing::EaseIn.apply_step(0, 16), 0);
+        assert_eq!(Easing::EaseIn.apply_step(4, 16), 1);
+        assert_eq!(Easing::EaseOut.apply_step(4, 16), 7);
+    }
+
+    #[test]
+    fn test_animation_easing() {
+        let frames = [Hub75FrameBuffer::<8, 1, 6>::new()];
+        let mut animation = Animation::new(
+            AnimationData::Frames(&frames),
+            AnimationEffect::Wipe(Direction::Right, Origin::Edge),
+            8,
+        )
+        .unwrap();
+        assert_eq!(animation.progress(), 0);
+
+        animation.set_easing(Easing::EaseOut);
+        assert_eq!(animation.easing(), Easing::EaseOut);
+        animation.sequence = 2;
+        assert_eq!(animation.progress(), 3);
+    }
+
+    /// Collect the frame index shown on each of the next `N` steps
+    fn frame_indices<const N: usize>(animation: &mut Animation<'_, 2, 1, 6>) -> [Option<usize>; N] {
+        let mut frame = Hub75FrameBuffer::new();
+        let mut indices = [None; N];
+        for index in &mut indices {
+            let shown = animation.frame_index;
+            if let AnimationState::Rendered = animation.next_into(&mut frame) {
+                *index = Some(shown);
+            }
+        }
+        indices
+    }
+
+    #[test]
+    fn test_playback_modes() {
+        let frames = [
+            Hub75FrameBuffer::new(),
+            Hub75FrameBuffer::new(),
+            Hub75FrameBuffer::new(),
+        ];
+        let mut animation =
+            Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 3).unwrap();
+        assert_eq!(animation.playback_mode(), PlaybackMode::Once);
+        assert_eq!(
+            frame_indices::<4>(&mut animation),
+            [Some(0), Some(1), Some(2), None]
+        );
+        assert!(animation.is_done());
+
+        animation.reset();
+        animation.set_playback_mode(PlaybackMode::Loop);
+        assert_eq!(
+            frame_indices::<7>(&mut animation),
+            [
+                Some(0),
+                Some(1),
+                Some(2),
+                Some(0),
+                Some(1),
+                Some(2),
+                Some(0)
+            ]
+        );
+        assert!(!animation.is_done());
+
+        animation.reset();
+        animation.set_playback_mode(PlaybackMode::LoopN(2));
+        assert_eq!(
+            frame_indices::<7>(&mut animation),
+            [Some(0), Some(1), Some(2), Some(0), Some(1), Some(2), None]
+        );
+        assert!(animation.is_done());
+
+        animation.reset();
+        animation.set_playback_mode(PlaybackMode::PingPong);
+        assert_eq!(
+            frame_indices::<7>(&mut animation),
+            [
+                Some(0),
+                Some(1),
+                Some(2),
+                Some(1),
+                Some(0),
+                Some(1),
+                Some(2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_playback_control() {
+        let frames = [
+            Hub75FrameBuffer::new(),
+            Hub75FrameBuffer::new(),
+            Hub75FrameBuffer::new(),
+        ];
+        let mut animation =
+            Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 3).unwrap();
+        assert_eq!(animation.total_steps(), 3);
+
+        animation.pause();
+        assert!(animation.is_paused());
+        assert_eq!(frame_indices::<2>(&mut animation), [None, None]);
+
+        // Seeking renders the sought step once, even while paused
+        animation.seek(2);
+        assert_eq!(frame_indices::<2>(&mut animation), [Some(2), None]);
+        assert_eq!(animation.current_step(), 2);
+
+        animation.resume();
+        animation.seek(1);
+        assert_eq!(frame_indices::<3>(&mut animation), [Some(1), Some(2), None]);
+        assert!(animation.is_done());
+
+        animation.set_reverse(true);
+        assert!(animation.is_reversed());
+        animation.reset();
+        assert_eq!(
+            frame_indices::<4>(&mut animation),
+            [Some(2), Some(1), Some(0), None]
+        );
+        assert!(animation.is_done());
+
+        animation.set_playback_mode(PlaybackMode::Loop);
+        animation.reset();
+        assert_eq!(
+            frame_indices::<4>(&mut animation),
+            [Some(2), Some(1), Some(0), Some(2)]
+        );
+    }
+
+    #[test]
+    fn test_playback_speed() {
+        let frames = [
+            Hub75FrameBuffer::new(),
+            Hub75FrameBuffer::new(),
+            Hub75FrameBuffer::new(),
+        ];
+        let mut animation =
+            Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 6).unwrap();
+        assert_eq!(animation.speed(), (1, 1));
+        assert_eq!(
+            frame_indices::<4>(&mut animation),
+            [None, Some(0), None, Some(1)]
+        );
+
+        animation.reset();
+        animation.set_speed(1, 2).unwrap();
+        assert_eq!(
+            frame_indices::<4>(&mut animation),
+            [None, None, None, Some(0)]
+        );
+
+        // Four times faster than one step every two frames skips every other step
+        animation.reset();
+        animation.set_speed(4, 1).unwrap();
+        let mut frame = Hub75FrameBuffer::new();
+        assert!(matches!(
+            animation.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(animation.current_step(), 2);
+        assert!(matches!(
+            animation.next_into(&mut frame),
+            AnimationState::Done
+        ));
+
+        assert_eq!(
+            animation.set_speed(1, 0),
+            Err(AnimationError::InvalidDuration)
+        );
+    }
+
+    #[test]
+    fn test_playback_events() {
+        let frames = [Hub75FrameBuffer::new(), Hub75FrameBuffer::new()];
+        let mut animation =
+            Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 2).unwrap();
+        animation.set_playback_mode(PlaybackMode::LoopN(2));
+
+        let mut events = heapless::Vec::<AnimationEvent, 8>::new();
+        let mut frame = Hub75FrameBuffer::<2, 1, 6>::new();
+        for _ in 0..6 {
+            animation.next_into(&mut frame);
+            while let Some(event) = animation.poll_event() {
+                events.push(event).unwrap();
+            }
+        }
+        assert_eq!(
+            events,
+            [
+                AnimationEvent::FrameChanged(1),
+                AnimationEvent::LoopCompleted(1),
+                AnimationEvent::FrameChanged(0),
+                AnimationEvent::FrameChanged(1),
+                AnimationEvent::Done,
+            ]
+        );
+
+        // Events are kept until polled, dropping the oldest
+        animation.reset();
+        animation.set_playback_mode(PlaybackMode::Loop);
+        for _ in 0..5 {
+            animation.next_into(&mut frame);
+        }
+        for event in [
+            AnimationEvent::FrameChanged(1),
+            AnimationEvent::LoopCompleted(2),
+            AnimationEvent::FrameChanged(0),
+            AnimationEvent::FrameChanged(1),
+        ] {
+            assert_eq!(animation.poll_event(), Some(event));
+        }
+        assert_eq!(animation.poll_event(), None);
+    }
+
+    #[test]
+    fn test_time_based_stepping() {
+        let frames = [
+            Hub75FrameBuffer::new(),
+            Hub75FrameBuffer::new(),
+            Hub75FrameBuffer::new(),
+            Hub75FrameBuffer::new(),
+        ];
+        let mut animation =
+            Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 1).unwrap();
+        animation.set_duration_ms(400);
+        assert_eq!(animation.step_duration_ms(), 100);
+
+        let mut frame = Hub75FrameBuffer::<2, 1, 6>::new();
+        let mut step_at = |animation: &mut Animation<'_, 2, 1, 6>, now_ms| match animation
+            .next_at_into(now_ms, &mut frame)
+        {
+            AnimationState::Rendered => Some(animation.current_step()),
+            _ => None,
+        };
+
+        // Uneven call times still land on the right steps
+        assert_eq!(step_at(&mut animation, 1000), Some(0));
+        assert_eq!(step_at(&mut animation, 1070), None);
+        assert_eq!(step_at(&mut animation, 1130), Some(1));
+
+        // A late call skips the missed step
+        assert_eq!(step_at(&mut animation, 1390), Some(3));
+        assert_eq!(step_at(&mut animation, 1500), None);
+        assert!(animation.is_done());
+
+        // Time spent paused does not count
+        animation.reset();
+        assert_eq!(step_at(&mut animation, 2000), Some(0));
+        animation.pause();
+        assert_eq!(step_at(&mut animation, 2500), None);
+        animation.resume();
+        assert_eq!(step_at(&mut animation, 2550), None);
+        animation.set_speed(2, 1).unwrap();
+        assert_eq!(step_at(&mut animation, 2575), Some(1));
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let mut white = Hub75FrameBuffer::<2, 1, 8>::new();
+        white.fill(Hub75Color::white());
+        let frames = [Hub75FrameBuffer::new(), white];
+        let mut animation =
+            Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 8).unwrap();
+        animation.set_interpolation(true);
+        assert!(animation.is_interpolating());
+
+        // Nothing to blend from until the first step is shown
+        let mut frame = Hub75FrameBuffer::new();
+        let mut shown = heapless::Vec::<Option<u8>, 8>::new();
+        for _ in 0..8 {
+            let level = match animation.next_into(&mut frame) {
+                AnimationState::Rendered => Some(frame.get_pixel(1, 0).unwrap().r),
+                _ => None,
+            };
+            shown.push(level).unwrap();
+        }
+        assert_eq!(
+            shown,
+            [
+                None,
+                None,
+                None,
+                Some(0),
+                Some(63),
+                Some(127),
+                Some(191),
+                Some(255)
+            ]
+        );
+
+        // Time-based playback blends towards the following step only
+        animation.reset();
+        animation.set_step_duration_ms(100);
+        let mut level_at = |animation: &mut Animation<'_, 2, 1, 8>, now_ms| match animation
+            .next_at_into(now_ms, &mut frame)
+        {
+            AnimationState::Rendered => Some(frame.get_pixel(0, 0).unwrap().g),
+            _ => None,
+        };
+        assert_eq!(level_at(&mut animation, 1000), Some(0));
+        assert_eq!(level_at(&mut animation, 1050), Some(127));
+        assert_eq!(level_at(&mut animation, 1100), Some(255));
+        assert_eq!(level_at(&mut animation, 1150), None);
+
+        // Without interpolation, or while paused, the frames in between wait
+        animation.reset();
+        animation.set_interpolation(false);
+        assert_eq!(level_at(&mut animation, 2000), Some(0));
+        assert_eq!(level_at(&mut animation, 2050), None);
+        animation.set_interpolation(true);
+        animation.pause();
+        assert_eq!(level_at(&mut animation, 2070), None);
+    }
+
+    #[test]
+    fn test_generator_data() {
+        let mut calls = 0;
+        let mut plasma = |index: usize, frame: &mut Hub75FrameBuffer<4, 2, 6>| {
+            calls += 1;
+            frame.set_pixel(index, 0, Hub75Color::white()).unwrap();
+        };
+        let mut frame = Hub75FrameBuffer::new();
+        frame.fill(Hub75Color::red());
+        {
+            let data = AnimationData::generator(3, &mut plasma);
+            assert_eq!(data.frame_count(), 3);
+            assert!(data.get_frame(3).is_err());
+
+            let mut animation = Animation::new(data, AnimationEffect::None, 3).unwrap();
+            for _ in 0..2 {
+                assert!(matches!(
+                    animation.next_into(&mut frame),
+                    AnimationState::Rendered
+                ));
+            }
+        }
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::white());
+        assert_eq!(frame.get_pixel(1, 1).unwrap(), Hub75Color::black());
+        assert_eq!(calls, 2);
+    }
+
+    /// Custom effect inverting every other step
+    struct Blink;
+
+    impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+        AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS> for Blink
+    {
+        fn apply_effect_into(
+            &self,
+            frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+            _next_frame: Option<&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>,
+            progress: usize,
+            _total_steps: usize,
+        ) -> Result<(), Hub75Error> {
+            if progress % 2 == 1 {
+                frame.clear();
+            }
+            Ok(())
+        }
+
+        fn total_steps(&self, frame_count: usize) -> usize {
+            frame_count * 2
+        }
+
+        fn uses_next_frame(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_custom_effect() {
+        let mut frames = [Hub75FrameBuffer::<4, 2, 6>::new(), Hub75FrameBuffer::new()];
+        frames[0].fill(Hub75Color::red());
+        frames[1].fill(Hub75Color::green());
+
+        let mut animation = Animation::new(AnimationData::Frames(&frames), Blink, 4).unwrap();
+        let mut frame = Hub75FrameBuffer::new();
+        let mut shown = [Hub75Color::black(); 4];
+        for color in &mut shown {
+            assert!(matches!(
+                animation.next_into(&mut frame),
+                AnimationState::Rendered
+            ));
+            *color = frame.get_pixel(0, 0).unwrap();
+        }
+        assert_eq!(
+            shown,
+            [
+                Hub75Color::red(),
+                Hub75Color::black(),
+                Hub75Color::green(),
+                Hub75Color::black()
+            ]
+        );
+        assert!(animation.is_done());
+
+        // Effects chosen at runtime work through a trait object
+        let effect: &dyn AnimationEffectTrait<4, 2, 6> =
+            &AnimationEffect::Wipe(Direction::Right, Origin::Edge);
+        let animation = Animation::new(AnimationData::Frames(&frames), effect, 8).unwrap();
+        assert_eq!(animation.total_steps, 8);
+    }
+
+    #[test]
+    fn test_next_into() {
+        // Two 4x1 RGB frames: red then green
+        let mut data = [0u8; 4 * 3 * 2];
+        for pixel in data.chunks_exact_mut(3).take(4) {
+            pixel[0] = 255;
+        }
+        for pixel in data.chunks_exact_mut(3).skip(4) {
+            pixel[1] = 255;
+        }
+
+        let mut animation = Animation::<4, 1, 6>::new(
+            AnimationData::RgbData(&data),
+            AnimationEffect::Slide(Direction::Left),
+            8,
+        )
+        .unwrap();
+        let mut frame = Hub75FrameBuffer::new();
+
+        assert!(matches!(
+            animation.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(frame.get_pixel(3, 0).unwrap(), Hub75Color::red());
+
+        assert!(matches!(
+            animation.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(frame.get_pixel(2, 0).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(3, 0).unwrap(), Hub75Color::green());
+
+        let mut steps = 2;
+        while !matches!(animation.next_into(&mut frame), AnimationState::Done) {
+            steps += 1;
+        }
+        assert_eq!(steps, 8);
+    }
 }
@@ -0,0 +1,335 @@
+//! Proportional fonts in the BDF format
+//!
+//! BDF (Glyph Bitmap Distribution Format) is the plain-text bitmap font
+//! format of X11, and the format most pixel fonts for LED signs are available
+//! in. A [`BdfFont`] indexes the glyphs of a BDF file, typically embedded
+//! with `include_str!`, once without allocating, and decodes their bitmaps
+//! straight from the file while drawing. It implements [`Font`], so text is
+//! measured with [`Font::text_width`] and drawn with
+//! [`Hub75FrameBuffer::draw_text_with`](crate::Hub75FrameBuffer::draw_text_with).
+//!
+//! Characters without a glyph are drawn as the font's `DEFAULT_CHAR`, or
+//! skipped if it has none.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::bdf::BdfFont;
+//! use hub75::font::Font;
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//!
+//! const BDF: &str = "\
+//! STARTFONT 2.1
+//! FONTBOUNDINGBOX 3 3 0 0
+//! STARTPROPERTIES 2
+//! FONT_ASCENT 3
+//! FONT_DESCENT 0
+//! ENDPROPERTIES
+//! CHARS 1
+//! STARTCHAR period
+//! ENCODING 46
+//! DWIDTH 2 0
+//! BBX 1 1 0 0
+//! BITMAP
+//! 80
+//! ENDCHAR
+//! ENDFONT
+//! ";
+//!
+//! let font = BdfFont::<1>::parse(BDF).unwrap();
+//! assert_eq!(font.text_width("..."), 6);
+//!
+//! let mut frame = Hub75FrameBuffer::<8, 4, 6>::new();
+//! frame.draw_text_with(&font, "..", 0, 0, Hub75Color::white());
+//! assert_eq!(frame.get_pixel(2, 2).unwrap(), Hub75Color::white());
+//! ```
+
+use heapless::Vec;
+
+use crate::{font::Font, AnimationError, Hub75Error};
+
+/// One glyph of a BDF font
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Glyph<'a> {
+    /// Character the glyph is for
+    encoding: u32,
+    /// Horizontal distance to the next glyph
+    advance: u16,
+    /// Width of the bitmap
+    width: u16,
+    /// Height of the bitmap
+    height: u16,
+    /// Offset of the bitmap's left edge from the origin
+    x_offset: i16,
+    /// Offset of the bitmap's bottom edge above the baseline
+    y_offset: i16,
+    /// Hexadecimal bitmap rows, one per line
+    bitmap: &'a str,
+}
+
+/// Proportional bitmap font parsed from a BDF file with up to `GLYPHS` glyphs
+#[derive(Debug, Clone)]
+pub struct BdfFont<'a, const GLYPHS: usize> {
+    /// Glyphs sorted by encoding
+    glyphs: Vec<Glyph<'a>, GLYPHS>,
+    /// Rows above the baseline
+    ascent: i16,
+    /// Rows below the baseline
+    descent: i16,
+    /// Character drawn for characters without a glyph
+    default_char: Option<u32>,
+}
+
+/// Parse the next whitespace-separated number of a line
+fn number<T: core::str::FromStr>(words: &mut core::str::SplitWhitespace) -> Result<T, Hub75Error> {
+    words
+        .next()
+        .and_then(|word| word.parse().ok())
+        .ok_or(AnimationError::InvalidData.into())
+}
+
+impl<'a, const GLYPHS: usize> BdfFont<'a, GLYPHS> {
+    /// Index the glyphs of a BDF file
+    ///
+    /// Glyphs without a Unicode encoding are left out. Returns
+    /// [`AnimationError::InvalidData`] if `source` is not a valid BDF file and
+    /// [`Hub75Error::BufferOverflow`] if it has more than `GLYPHS` glyphs.
+    pub fn parse(source: &'a str) -> Result<Self, Hub75Error> {
+        let mut font = Self {
+            glyphs: Vec::new(),
+            ascent: 0,
+            descent: 0,
+            default_char: None,
+        };
+        let mut ascent = None;
+        let mut descent = None;
+        let mut glyph: Option<Glyph<'a>> = None;
+        // Byte offset of the first bitmap row of the current glyph
+        let mut bitmap_start = None;
+        let mut started = false;
+
+        let mut offset = 0;
+        for line in source.split_inclusive('\n') {
+            let start = offset;
+            offset += line.len();
+            let mut words = line.split_whitespace();
+            let Some(keyword) = words.next() else {
+                continue;
+            };
+            if bitmap_start.is_some() && keyword != "ENDCHAR" {
+                continue;
+            }
+
+            match keyword {
+                "STARTFONT" => started = true,
+                _ if !started => return Err(AnimationError::InvalidData.into()),
+                "FONTBOUNDINGBOX" => {
+                    let _width: i16 = number(&mut words)?;
+                    let height: i16 = number(&mut words)?;
+                    let _x: i16 = number(&mut words)?;
+                    let y: i16 = number(&mut words)?;
+                    // Explicit properties take precedence
+                    ascent = ascent.or(Some(height + y));
+                    descent = descent.or(Some(-y));
+                }
+                "FONT_ASCENT" => ascent = Some(number(&mut words)?),
+                "FONT_DESCENT" => descent = Some(number(&mut words)?),
+                "DEFAULT_CHAR" => font.default_char = Some(number(&mut words)?),
+                "STARTCHAR" => {
+                    glyph = Some(Glyph {
+                        encoding: u32::MAX,
+                        advance: 0,
+                        width: 0,
+                        height: 0,
+                        x_offset: 0,
+                        y_offset: 0,
+                        bitmap: "",
+                    })
+                }
+                "ENCODING" | "DWIDTH" | "BBX" | "BITMAP" | "ENDCHAR" => {
+                    let glyph = glyph.as_mut().ok_or(AnimationError::InvalidData)?;
+                    match keyword {
+                        // Unencoded glyphs are -1, which stays out of range
+                        "ENCODING" => {
+                            glyph.encoding =
+                                number::<i64>(&mut words)?.try_into().unwrap_or(u32::MAX)
+                        }
+                        "DWIDTH" => glyph.advance = number(&mut words)?,
+                        "BBX" => {
+                            glyph.width = number(&mut words)?;
+                            glyph.height = number(&mut words)?;
+                            glyph.x_offset = number(&mut words)?;
+                            glyph.y_offset = number(&mut words)?;
+                        }
+                        "BITMAP" => bitmap_start = Some(offset),
+                        _ => {
+                            let bitmap = bitmap_start.take().ok_or(AnimationError::InvalidData)?;
+                            glyph.bitmap = &source[bitmap..start];
+                            if char::from_u32(glyph.encoding).is_some() {
+                                font.glyphs
+                                    .push(*glyph)
+                                    .map_err(|_| Hub75Error::BufferOverflow)?;
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !started || bitmap_start.is_some() {
+            return Err(AnimationError::InvalidData.into());
+        }
+        font.ascent = ascent.ok_or(AnimationError::InvalidData)?;
+        font.descent = descent.ok_or(AnimationError::InvalidData)?;
+        font.glyphs.sort_unstable_by_key(|glyph| glyph.encoding);
+        Ok(font)
+    }
+
+    /// Get the number of glyphs in the font
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Check whether the font has no glyphs
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    /// Check whether the font has a glyph for `c`
+    pub fn contains(&self, c: char) -> bool {
+        self.find(c as u32).is_some()
+    }
+
+    /// Get the number of rows above the baseline
+    pub fn ascent(&self) -> usize {
+        self.ascent.max(0) as usize
+    }
+
+    /// Look up the glyph for an encoding
+    fn find(&self, encoding: u32) -> Option<&Glyph<'a>> {
+        self.glyphs
+            .binary_search_by_key(&encoding, |glyph| glyph.encoding)
+            .ok()
+            .map(|index| &self.glyphs[index])
+    }
+
+    /// Get the glyph drawn for a character
+    fn glyph(&self, c: char) -> Option<&Glyph<'a>> {
+        self.find(c as u32)
+            .or_else(|| self.default_char.and_then(|default| self.find(default)))
+    }
+}
+
+impl<const GLYPHS: usize> Font for BdfFont<'_, GLYPHS> {
+    fn line_height(&self) -> usize {
+        (self.ascent + self.descent).max(0) as usize
+    }
+
+    fn advance(&self, c: char) -> usize {
+        self.glyph(c).map_or(0, |glyph| glyph.advance as usize)
+    }
+
+    fn for_each_pixel(&self, c: char, pixel: &mut dyn FnMut(i32, i32)) {
+        let Some(glyph) = self.glyph(c) else {
+            return;
+        };
+        let top = (self.ascent - glyph.y_offset - glyph.height as i16) as i32;
+        for (y, row) in (top..).zip(glyph.bitmap.lines().take(glyph.height as usize)) {
+            let row = row.trim().as_bytes();
+            for x in 0..glyph.width as usize {
+                // Each pair of hex digits is a byte, most significant bit first
+                let digit = row
+                    .get(x / 4)
+                    .and_then(|&digit| (digit as char).to_digit(16))
+                    .unwrap_or(0);
+                if digit >> (3 - x % 4) & 1 == 1 {
+                    pixel(glyph.x_offset as i32 + x as i32, y);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer};
+
+    /// Two glyphs, a 9 pixel wide bar with a descender and an unencoded one
+    const FONT: &str = "STARTFONT 2.1
+FONT -test-
+FONTBOUNDINGBOX 9 4 0 -1
+STARTPROPERTIES 1
+DEFAULT_CHAR 95
+ENDPROPERTIES
+CHARS 3
+STARTCHAR underscore
+ENCODING 95
+DWIDTH 10 0
+BBX 9 2 0 -1
+BITMAP
+FF80
+8080
+ENDCHAR
+STARTCHAR i
+ENCODING 105
+DWIDTH 2 0
+BBX 1 3 0 0
+BITMAP
+80
+00
+80
+ENDCHAR
+STARTCHAR unencoded
+ENCODING -1
+DWIDTH 4 0
+BBX 1 1 0 0
+BITMAP
+80
+ENDCHAR
+ENDFONT
+";
+
+    #[test]
+    fn test_parse_and_measure() {
+        let font = BdfFont::<2>::parse(FONT).unwrap();
+        assert_eq!(font.len(), 2);
+        assert!(font.contains('i') && !font.contains('j'));
+        // Ascent and descent come from the bounding box
+        assert_eq!((font.ascent(), font.line_height()), (3, 4));
+        // Missing characters fall back to the default '_'
+        assert_eq!(font.text_width("iiz"), 14);
+
+        assert!(matches!(
+            BdfFont::<1>::parse(FONT),
+            Err(Hub75Error::BufferOverflow)
+        ));
+        assert!(matches!(
+            BdfFont::<2>::parse("FONT x\n"),
+            Err(Hub75Error::AnimationError(AnimationError::InvalidData))
+        ));
+        assert!(matches!(
+            BdfFont::<2>::parse("STARTFONT 2.1\nSTARTCHAR a\nBITMAP\n80\n"),
+            Err(Hub75Error::AnimationError(AnimationError::InvalidData))
+        ));
+    }
+
+    #[test]
+    fn test_draw_bdf_text() {
+        let font = BdfFont::<2>::parse(FONT).unwrap();
+        let mut frame = Hub75FrameBuffer::<16, 4, 6>::new();
+        let white = Hub75Color::white();
+        assert_eq!(frame.draw_text_with(&font, "i_", 1, 0, white), 13);
+
+        // 'i' is a dotted column from the top row to the baseline
+        assert_eq!(frame.get_pixel(1, 0).unwrap(), white);
+        assert_eq!(frame.get_pixel(1, 1).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(1, 2).unwrap(), white);
+        // '_' sits on the baseline, and its second row is below it
+        assert!((3..12).all(|x| frame.get_pixel(x, 2).unwrap() == white));
+        assert_eq!(frame.get_pixel(3, 3).unwrap(), white);
+        assert_eq!(frame.get_pixel(4, 3).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(11, 3).unwrap(), white);
+    }
+}
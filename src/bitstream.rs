@@ -0,0 +1,235 @@
+//! Precomputed scan data for DMA-driven output
+//!
+//! Shifting pixels out one pin write at a time keeps the CPU busy for the
+//! whole refresh. On MCUs that can stream a buffer to a GPIO port, or feed a
+//! PIO or timer-paced parallel interface, the color data can instead be
+//! computed once per frame into a [`Bitstream`] and handed to the DMA
+//! controller.
+//!
+//! The stream holds one byte per column, with the six color pins in the low
+//! bits as given by the `*_BIT` constants. Bytes are ordered by bit plane,
+//! then row, then column, which is the order
+//! [`Hub75Display::render_frame`](crate::Hub75Display::render_frame) shifts
+//! them out in, so each row is a contiguous slice and a whole frame is one
+//! transfer. Clock, latch, output enable and the row address stay with the
+//! application or the peripheral pacing the transfer.
+//!
+//! `ROWS` must be `HEIGHT / 2` and is checked at compile time.
+//!
+//! # DMA buffers
+//!
+//! With the `dma` feature the stream implements `embedded_dma::ReadTarget`,
+//! so `&'static Bitstream` and `&'static mut Bitstream` are
+//! [`ReadBuffer`](embedded_dma::ReadBuffer)s of bytes and can be passed
+//! straight to HAL DMA APIs. Streams are word aligned; when `WIDTH` is a
+//! multiple of 4, [`Bitstream::words`] and [`Bitstream::row_words`] give
+//! `u32` views for 32-bit transfers. [`Bitstream::new`] is `const`, so a
+//! stream can be placed in a `static` to get the `'static` lifetime without
+//! an allocator.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::bitstream::{Bitstream, R1_BIT, R2_BIT};
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! frame.set_pixel(0, 16, Hub75Color::red()).unwrap();
+//!
+//! let mut stream = Bitstream::<64, 32, 6, 16>::new();
+//! stream.pack(&frame);
+//!
+//! // The lower half of the panel shares row 0 with the upper half
+//! assert_eq!(stream.row(5, 0).unwrap()[0], R2_BIT);
+//! assert_eq!(stream.row(5, 0).unwrap()[0] & R1_BIT, 0);
+//! assert_eq!(stream.as_bytes().len(), Bitstream::<64, 32, 6, 16>::BYTES);
+//! ```
+
+use crate::frame_buffer::Hub75FrameBuffer;
+
+/// Bit of the upper half red pin
+pub const R1_BIT: u8 = 1 << 0;
+/// Bit of the upper half green pin
+pub const G1_BIT: u8 = 1 << 1;
+/// Bit of the upper half blue pin
+pub const B1_BIT: u8 = 1 << 2;
+/// Bit of the lower half red pin
+pub const R2_BIT: u8 = 1 << 3;
+/// Bit of the lower half green pin
+pub const G2_BIT: u8 = 1 << 4;
+/// Bit of the lower half blue pin
+pub const B2_BIT: u8 = 1 << 5;
+
+/// Pack the pin levels of one column into a byte
+#[inline(always)]
+pub(crate) fn pack_levels(levels: (bool, bool, bool, bool, bool, bool)) -> u8 {
+    let (r1, g1, b1, r2, g2, b2) = levels;
+    [
+        (r1, R1_BIT),
+        (g1, G1_BIT),
+        (b1, B1_BIT),
+        (r2, R2_BIT),
+        (g2, G2_BIT),
+        (b2, B2_BIT),
+    ]
+    .into_iter()
+    .filter(|&(level, _)| level)
+    .fold(0, |byte, (_, bit)| byte | bit)
+}
+
+/// Color pin levels for every column, row and bit plane of a frame
+#[repr(C, align(4))]
+pub struct Bitstream<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    const ROWS: usize,
+> {
+    /// Pin bytes indexed by bit plane, row and column
+    planes: [[[u8; WIDTH]; ROWS]; COLOR_BITS],
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const ROWS: usize>
+    Bitstream<WIDTH, HEIGHT, COLOR_BITS, ROWS>
+{
+    /// Fails to compile when `ROWS` is not half of `HEIGHT`
+    const ROWS_MATCH: () = assert!(ROWS * 2 == HEIGHT, "ROWS must be HEIGHT / 2");
+
+    /// Length of the stream in bytes
+    pub const BYTES: usize = WIDTH * ROWS * COLOR_BITS;
+
+    /// Create a stream with every pin low
+    pub const fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::ROWS_MATCH;
+        Self {
+            planes: [[[0; WIDTH]; ROWS]; COLOR_BITS],
+        }
+    }
+
+    /// Compute the stream for a frame without any color correction
+    pub fn pack(&mut self, frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) {
+        for (bit_plane, rows) in self.planes.iter_mut().enumerate() {
+            for (row, bytes) in rows.iter_mut().enumerate() {
+                if let Ok(levels) = frame.get_row_bit_plane(row, bit_plane) {
+                    for (byte, levels) in bytes.iter_mut().zip(levels) {
+                        *byte = pack_levels(levels);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get the bytes shifted out for a row during a bit plane
+    pub fn row(&self, bit_plane: usize, row: usize) -> Option<&[u8; WIDTH]> {
+        self.planes.get(bit_plane)?.get(row)
+    }
+
+    /// Get the bytes of a row for writing
+    pub fn row_mut(&mut self, bit_plane: usize, row: usize) -> Option<&mut [u8; WIDTH]> {
+        self.planes.get_mut(bit_plane)?.get_mut(row)
+    }
+
+    /// Get the whole stream in output order
+    pub fn as_bytes(&self) -> &[u8] {
+        self.planes.as_flattened().as_flattened()
+    }
+
+    /// Get the whole stream as 32-bit words, four columns to a word
+    ///
+    /// Returns `None` unless `WIDTH` is a multiple of 4.
+    pub fn words(&self) -> Option<&[u32]> {
+        Self::cast_words(self.as_bytes())
+    }
+
+    /// Get a row as 32-bit words, four columns to a word
+    ///
+    /// Returns `None` unless `WIDTH` is a multiple of 4 and the row exists.
+    pub fn row_words(&self, bit_plane: usize, row: usize) -> Option<&[u32]> {
+        Self::cast_words(self.row(bit_plane, row)?)
+    }
+
+    /// Reinterpret bytes starting at a row boundary as words
+    fn cast_words(bytes: &[u8]) -> Option<&[u32]> {
+        if WIDTH % 4 != 0 {
+            return None;
+        }
+        // SAFETY: the stream is 4-byte aligned and every row starts at a
+        // multiple of WIDTH bytes, so `bytes` is aligned and a whole number of
+        // words long; any bit pattern is a valid u32
+        Some(unsafe { core::slice::from_raw_parts(bytes.as_ptr().cast(), bytes.len() / 4) })
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const ROWS: usize> Default
+    for Bitstream<WIDTH, HEIGHT, COLOR_BITS, ROWS>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// SAFETY: the stream is a plain array of bytes with no padding, so its size is
+// exactly the bytes it holds
+#[cfg(feature = "dma")]
+unsafe impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const ROWS: usize>
+    embedded_dma::ReadTarget for Bitstream<WIDTH, HEIGHT, COLOR_BITS, ROWS>
+{
+    type Word = u8;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Hub75Color;
+
+    type TestStream = Bitstream<8, 4, 2, 2>;
+
+    #[test]
+    fn test_pack_layout() {
+        let mut frame = Hub75FrameBuffer::<8, 4, 2>::new();
+        frame.set_pixel(1, 0, Hub75Color::new(1, 2, 3)).unwrap();
+        frame.set_pixel(4, 3, Hub75Color::new(3, 0, 2)).unwrap();
+
+        let mut stream = TestStream::new();
+        stream.pack(&frame);
+
+        assert_eq!(stream.row(0, 0).unwrap()[1], R1_BIT | B1_BIT);
+        assert_eq!(stream.row(1, 0).unwrap()[1], G1_BIT | B1_BIT);
+        assert_eq!(stream.row(0, 1).unwrap()[4], R2_BIT);
+        assert_eq!(stream.row(1, 1).unwrap()[4], R2_BIT | B2_BIT);
+        assert!(stream.row(2, 0).is_none());
+        assert!(stream.row(0, 2).is_none());
+
+        // Bit plane 1, row 1, column 4 in output order
+        assert_eq!(stream.as_bytes().len(), TestStream::BYTES);
+        assert_eq!(stream.as_bytes()[8 * 2 + 8 + 4], R2_BIT | B2_BIT);
+    }
+
+    #[test]
+    fn test_word_views() {
+        let mut stream = TestStream::new();
+        stream.row_mut(1, 1).unwrap()[4] = 0x2A;
+
+        let words = stream.words().unwrap();
+        assert_eq!(words.len(), TestStream::BYTES / 4);
+        assert_eq!(words[7], u32::from_ne_bytes([0x2A, 0, 0, 0]));
+        assert_eq!(stream.row_words(1, 1).unwrap()[1], words[7]);
+        assert!(Bitstream::<6, 4, 2, 2>::new().words().is_none());
+    }
+
+    #[cfg(feature = "dma")]
+    #[test]
+    fn test_read_buffer() {
+        use embedded_dma::ReadBuffer;
+
+        static STREAM: TestStream = TestStream::new();
+        let (pointer, length) = unsafe { (&STREAM).read_buffer() };
+        assert_eq!(pointer, STREAM.as_bytes().as_ptr());
+        assert_eq!(length, TestStream::BYTES);
+
+        let (pointer, length) = unsafe { STREAM.row_words(1, 0).unwrap().read_buffer() };
+        assert_eq!(pointer as usize % 4, 0);
+        assert_eq!(length, 2);
+    }
+}
@@ -0,0 +1,179 @@
+//! Frame buffer storage strategies for the display driver
+//!
+//! [`Hub75Display`](crate::Hub75Display) is generic over how its frame buffers
+//! are stored. Two strategies are provided:
+//!
+//! - [`DoubleBuffer`] (the default): a front buffer that is scanned out and a
+//!   separate back buffer that is drawn into. Buffers are exchanged with
+//!   [`swap_buffers`](crate::Hub75Display::swap_buffers), so a frame is only
+//!   ever shown once it is complete.
+//! - [`SingleBuffer`]: a single buffer that is both drawn into and scanned out.
+//!   This halves the frame buffer RAM, which matters for large panels on small
+//!   parts (two 128x64 buffers need 48KB on their own).
+//!
+//! Both store [`Hub75Color`] pixels unless another pixel type, such as
+//! [`Hub75Color16`](crate::Hub75Color16) for depths above 8 bits, is named
+//! as their last parameter.
+//!
+//! # Tearing trade-off
+//!
+//! With [`SingleBuffer`] every drawing operation is visible on the next
+//! refreshed frame. If a refresh happens while a frame is only partially
+//! drawn, the panel shows a mix of old and new content ("tearing"). Because
+//! rendering borrows the display mutably, drawing and refreshing from the same
+//! task never interleave within a single refresh, but refreshing between two
+//! drawing calls will show the intermediate state. To keep tearing invisible:
+//!
+//! - Draw a complete frame between two refreshes, keeping drawing short
+//!   compared to the refresh period, or
+//! - Render into a separately owned frame buffer and copy it in one step with
+//!   [`display_frame`](crate::Hub75Display::display_frame) or
+//!   [`Hub75FrameBuffer::copy_from`], which only costs RAM while that buffer
+//!   is alive.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::{buffering::SingleBuffer, Hub75Display, Hub75Pins};
+//!
+//! # fn example(pins: Hub75Pins<impl embedded_hal::digital::OutputPin + 'static>) -> Result<(), hub75::Hub75Error> {
+//! // 128x64 display with a single 24KB frame buffer instead of two
+//! let mut display =
+//!     Hub75Display::<_, 128, 64, 6, SingleBuffer<128, 64, 6>>::new(pins)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    color::{Hub75Color, Hub75Pixel},
+    frame_buffer::Hub75FrameBuffer,
+};
+
+/// Storage for the frame buffers used by the display driver
+pub trait BufferStorage<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Color type of the stored pixels
+    type Pixel: Hub75Pixel<COLOR_BITS>;
+
+    /// Whether this storage has a back buffer separate from the front buffer
+    const HAS_BACK_BUFFER: bool;
+
+    /// Create storage with all buffers cleared to black
+    fn new() -> Self;
+
+    /// Get the buffer that is currently scanned out
+    fn front(&self) -> &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, Self::Pixel>;
+
+    /// Get the buffer that is currently scanned out, mutably
+    fn front_mut(&mut self) -> &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, Self::Pixel>;
+
+    /// Get the buffer that is drawn into (the front buffer without a back buffer)
+    fn back(&self) -> &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, Self::Pixel>;
+
+    /// Get the buffer that is drawn into, mutably
+    fn back_mut(&mut self) -> &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, Self::Pixel>;
+
+    /// Exchange the front and back buffers (no-op without a back buffer)
+    fn swap(&mut self);
+}
+
+/// Separate front and back buffers (two full frame buffers)
+pub struct DoubleBuffer<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    P = Hub75Color<COLOR_BITS>,
+> {
+    /// Front frame buffer (currently being displayed)
+    front: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P>,
+    /// Back frame buffer (for drawing)
+    back: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P>,
+}
+
+impl<
+        const WIDTH: usize,
+        const HEIGHT: usize,
+        const COLOR_BITS: usize,
+        P: Hub75Pixel<COLOR_BITS>,
+    > BufferStorage<WIDTH, HEIGHT, COLOR_BITS> for DoubleBuffer<WIDTH, HEIGHT, COLOR_BITS, P>
+{
+    type Pixel = P;
+
+    const HAS_BACK_BUFFER: bool = true;
+
+    fn new() -> Self {
+        Self {
+            front: Hub75FrameBuffer::default(),
+            back: Hub75FrameBuffer::default(),
+        }
+    }
+
+    fn front(&self) -> &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P> {
+        &self.front
+    }
+
+    fn front_mut(&mut self) -> &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P> {
+        &mut self.front
+    }
+
+    fn back(&self) -> &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P> {
+        &self.back
+    }
+
+    fn back_mut(&mut self) -> &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P> {
+        &mut self.back
+    }
+
+    fn swap(&mut self) {
+        self.front.swap(&mut self.back);
+    }
+}
+
+/// A single frame buffer that is both drawn into and displayed
+///
+/// Uses half the RAM of [`DoubleBuffer`] at the cost of possible tearing; see
+/// the [module documentation](self) for details.
+pub struct SingleBuffer<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    P = Hub75Color<COLOR_BITS>,
+> {
+    /// The only frame buffer
+    buffer: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P>,
+}
+
+impl<
+        const WIDTH: usize,
+        const HEIGHT: usize,
+        const COLOR_BITS: usize,
+        P: Hub75Pixel<COLOR_BITS>,
+    > BufferStorage<WIDTH, HEIGHT, COLOR_BITS> for SingleBuffer<WIDTH, HEIGHT, COLOR_BITS, P>
+{
+    type Pixel = P;
+
+    const HAS_BACK_BUFFER: bool = false;
+
+    fn new() -> Self {
+        Self {
+            buffer: Hub75FrameBuffer::default(),
+        }
+    }
+
+    fn front(&self) -> &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P> {
+        &self.buffer
+    }
+
+    fn front_mut(&mut self) -> &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P> {
+        &mut self.buffer
+    }
+
+    fn back(&self) -> &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P> {
+        &self.buffer
+    }
+
+    fn back_mut(&mut self) -> &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P> {
+        &mut self.buffer
+    }
+
+    fn swap(&mut self) {}
+}
@@ -10,11 +10,61 @@ pub trait ColorConvert<T> {
     fn to_color(self) -> T;
 }
 
+mod sealed {
+    pub trait Sealed {}
+
+    impl<const BITS: usize> Sealed for super::Hub75Color<BITS> {}
+    impl<const BITS: usize> Sealed for super::Hub75Color16<BITS> {}
+}
+
+/// Color types that frame buffers and the display driver can store
+///
+/// Implemented by [`Hub75Color`], with 8-bit channels, and [`Hub75Color16`],
+/// with 16-bit channels for depths above 8 bits. Generic code reads and
+/// writes the channels as `u32` levels through this trait.
+pub trait Hub75Pixel<const BITS: usize>:
+    sealed::Sealed + Copy + Default + Eq + fmt::Debug + 'static
+{
+    /// Maximum level of a channel
+    ///
+    /// Fails to compile if `BITS` does not fit in the channel type.
+    const MAX_LEVEL: u32;
+    /// Number of bytes stored per channel
+    const CHANNEL_BYTES: usize;
+
+    /// Get the channel levels as `[r, g, b]`
+    fn levels(&self) -> [u32; 3];
+
+    /// Create a color from channel levels, clamping them to `MAX_LEVEL`
+    fn from_levels(levels: [u32; 3]) -> Self;
+
+    /// Convert from 8-bit RGB values, scaling to the bit depth
+    fn from_rgb8(r: u8, g: u8, b: u8) -> Self;
+
+    /// Convert to 8-bit RGB values, scaling from the bit depth
+    fn to_rgb8(&self) -> (u8, u8, u8);
+
+    /// Get the bit value of each channel for a specific bit plane
+    fn get_bit(&self, bit_plane: usize) -> (bool, bool, bool) {
+        if bit_plane >= BITS {
+            return (false, false, false);
+        }
+
+        let [r, g, b] = self.levels().map(|level| level >> bit_plane & 1 != 0);
+        (r, g, b)
+    }
+}
+
+/// 4x4 Bayer threshold matrix used for ordered dithering
+pub(crate) const BAYER_4X4: [[u8; 4]; 4] =
+    [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
 /// RGB color representation for HUB75 displays
 ///
 /// This struct represents a color with configurable bit depth for each channel.
 /// The bit depth is specified as a const generic parameter and determines the
-/// maximum value for each color component.
+/// maximum value for each color component. Channels are stored as `u8`,
+/// limiting the depth to 8 bits; use [`Hub75Color16`] for deeper colors.
 ///
 /// # Examples
 ///
@@ -58,173 +108,777 @@ pub struct Hub75Color<const BITS: usize> {
     pub b: u8,
 }
 
-impl<const BITS: usize> Hub75Color<BITS> {
-    /// Maximum value for this bit depth (computed at compile time)
-    pub const MAX_VALUE: u8 = (1 << BITS) - 1;
-
-    /// Create a new color with the specified RGB values
-    /// Values are automatically clamped to the bit depth
-    pub const fn new(r: u8, g: u8, b: u8) -> Self {
-        Self {
-            r: if r > Self::MAX_VALUE {
-                Self::MAX_VALUE
-            } else {
-                r
-            },
-            g: if g > Self::MAX_VALUE {
-                Self::MAX_VALUE
-            } else {
-                g
-            },
-            b: if b > Self::MAX_VALUE {
-                Self::MAX_VALUE
-            } else {
-                b
-            },
+/// RGB color with 16-bit channels, for color depths above 8 bits
+///
+/// Works like [`Hub75Color`], with the same methods and conversions, for S-PWM
+/// panels and high-end BCM setups driven at 10-16 bits per channel. Frame
+/// buffers of this type take twice the RAM of 8-bit ones.
+///
+/// # Examples
+///
+/// ```rust
+/// use hub75::Hub75Color16;
+///
+/// // 12-bit color (4096 levels per channel)
+/// let white: Hub75Color16<12> = Hub75Color16::new(4095, 4095, 4095);
+/// assert_eq!(Hub75Color16::<12>::from_rgb8(255, 255, 255), white);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Hub75Color16<const BITS: usize> {
+    /// Red color component (0 to MAX_VALUE)
+    pub r: u16,
+    /// Green color component (0 to MAX_VALUE)
+    pub g: u16,
+    /// Blue color component (0 to MAX_VALUE)
+    pub b: u16,
+}
+
+/// Implement the methods and conversions of a color type with `$ch` channels
+macro_rules! impl_color {
+    ($color:ident, $ch:ty, $depth_message:literal) => {
+        impl<const BITS: usize> $color<BITS> {
+            /// Maximum value for this bit depth (computed at compile time)
+            ///
+            /// Fails to compile if `BITS` does not fit in the channel type.
+            pub const MAX_VALUE: $ch = {
+                assert!(BITS >= 1 && BITS <= <$ch>::BITS as usize, $depth_message);
+                ((1u32 << BITS) - 1) as $ch
+            };
+
+            /// Create a new color with the specified RGB values
+            /// Values are automatically clamped to the bit depth
+            pub const fn new(r: $ch, g: $ch, b: $ch) -> Self {
+                Self {
+                    r: if r > Self::MAX_VALUE {
+                        Self::MAX_VALUE
+                    } else {
+                        r
+                    },
+                    g: if g > Self::MAX_VALUE {
+                        Self::MAX_VALUE
+                    } else {
+                        g
+                    },
+                    b: if b > Self::MAX_VALUE {
+                        Self::MAX_VALUE
+                    } else {
+                        b
+                    },
+                }
+            }
+
+            /// Create a black color (all components zero)
+            pub const fn black() -> Self {
+                Self { r: 0, g: 0, b: 0 }
+            }
+
+            /// Create a white color (all components at maximum)
+            pub const fn white() -> Self {
+                Self {
+                    r: Self::MAX_VALUE,
+                    g: Self::MAX_VALUE,
+                    b: Self::MAX_VALUE,
+                }
+            }
+
+            /// Create a red color
+            pub const fn red() -> Self {
+                Self {
+                    r: Self::MAX_VALUE,
+                    g: 0,
+                    b: 0,
+                }
+            }
+
+            /// Create a green color
+            pub const fn green() -> Self {
+                Self {
+                    r: 0,
+                    g: Self::MAX_VALUE,
+                    b: 0,
+                }
+            }
+
+            /// Create a blue color
+            pub const fn blue() -> Self {
+                Self {
+                    r: 0,
+                    g: 0,
+                    b: Self::MAX_VALUE,
+                }
+            }
+
+            /// Create a yellow color
+            pub const fn yellow() -> Self {
+                Self {
+                    r: Self::MAX_VALUE,
+                    g: Self::MAX_VALUE,
+                    b: 0,
+                }
+            }
+
+            /// Create a cyan color
+            pub const fn cyan() -> Self {
+                Self {
+                    r: 0,
+                    g: Self::MAX_VALUE,
+                    b: Self::MAX_VALUE,
+                }
+            }
+
+            /// Create a magenta color
+            pub const fn magenta() -> Self {
+                Self {
+                    r: Self::MAX_VALUE,
+                    g: 0,
+                    b: Self::MAX_VALUE,
+                }
+            }
+
+            /// Create an orange color
+            pub const fn orange() -> Self {
+                Self::from_rgb_u32(0xFF8000)
+            }
+
+            /// Create a gray color from an 8-bit level (0 = black, 255 = white)
+            pub const fn gray(level: u8) -> Self {
+                Self::from_rgb8(level, level, level)
+            }
+
+            /// Linearly interpolate between two colors
+            ///
+            /// `t` is a 0.8 fixed-point fraction: 0 returns `a`, 255 returns `b`.
+            /// Results are rounded to the nearest level.
+            pub const fn lerp(a: Self, b: Self, t: u8) -> Self {
+                const fn mix(a: $ch, b: $ch, t: u8) -> $ch {
+                    let t = t as u32;
+                    ((a as u32 * (255 - t) + b as u32 * t + 127) / 255) as $ch
+                }
+                Self {
+                    r: mix(a.r, b.r, t),
+                    g: mix(a.g, b.g, t),
+                    b: mix(a.b, b.b, t),
+                }
+            }
+
+            /// Add two colors channel by channel, saturating at `MAX_VALUE`
+            pub const fn blend_add(self, other: Self) -> Self {
+                Self::new(
+                    self.r.saturating_add(other.r),
+                    self.g.saturating_add(other.g),
+                    self.b.saturating_add(other.b),
+                )
+            }
+
+            /// Subtract a color channel by channel, saturating at zero
+            pub const fn saturating_sub(self, other: Self) -> Self {
+                Self {
+                    r: self.r.saturating_sub(other.r),
+                    g: self.g.saturating_sub(other.g),
+                    b: self.b.saturating_sub(other.b),
+                }
+            }
+
+            /// Multiply every channel by an integer factor, saturating at `MAX_VALUE`
+            pub const fn saturating_mul(self, factor: u8) -> Self {
+                const fn mul(channel: $ch, factor: u8, max: $ch) -> $ch {
+                    let product = channel as u32 * factor as u32;
+                    if product > max as u32 {
+                        max
+                    } else {
+                        product as $ch
+                    }
+                }
+                Self {
+                    r: mul(self.r, factor, Self::MAX_VALUE),
+                    g: mul(self.g, factor, Self::MAX_VALUE),
+                    b: mul(self.b, factor, Self::MAX_VALUE),
+                }
+            }
+
+            /// Multiply two colors channel by channel
+            ///
+            /// Each channel is treated as a fraction of `MAX_VALUE`, so multiplying by
+            /// white leaves a color unchanged and multiplying by black yields black.
+            pub const fn blend_multiply(self, other: Self) -> Self {
+                const fn mul(a: $ch, b: $ch, max: $ch) -> $ch {
+                    if max == 0 {
+                        return 0;
+                    }
+                    ((a as u32 * b as u32 + max as u32 / 2) / max as u32) as $ch
+                }
+                Self {
+                    r: mul(self.r, other.r, Self::MAX_VALUE),
+                    g: mul(self.g, other.g, Self::MAX_VALUE),
+                    b: mul(self.b, other.b, Self::MAX_VALUE),
+                }
+            }
+
+            /// Scale the color by a brightness factor
+            ///
+            /// `brightness` is a 0.8 fixed-point fraction: 255 leaves the color
+            /// unchanged and 0 yields black. Results are rounded to the nearest level.
+            pub const fn scale(self, brightness: u8) -> Self {
+                Self::lerp(Self::black(), self, brightness)
+            }
+
+            /// Get the perceived brightness (Rec. 601 luma) at this color's bit depth
+            pub const fn luma(&self) -> $ch {
+                ((self.r as u32 * 77 + self.g as u32 * 150 + self.b as u32 * 29 + 128) >> 8) as $ch
+            }
+
+            /// Convert to a gray of the same perceived brightness
+            pub const fn to_grayscale(self) -> Self {
+                let luma = self.luma();
+                Self {
+                    r: luma,
+                    g: luma,
+                    b: luma,
+                }
+            }
+
+            /// Get the bit value for a specific bit plane
+            pub fn get_bit(&self, bit_plane: usize) -> (bool, bool, bool) {
+                if bit_plane >= BITS {
+                    return (false, false, false);
+                }
+
+                let mask: $ch = 1 << bit_plane;
+                (
+                    (self.r & mask) != 0,
+                    (self.g & mask) != 0,
+                    (self.b & mask) != 0,
+                )
+            }
+
+            /// Convert from 8-bit RGB values, scaling to the target bit depth
+            ///
+            /// Above 8 bits the value is bit-replicated into the low bits, so 255 maps
+            /// to `MAX_VALUE`.
+            pub const fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
+                const fn widen(value: u8, bits: usize) -> $ch {
+                    let value = value as u32;
+                    ((value << (bits - 8)) | (value >> (16 - bits))) as $ch
+                }
+
+                if BITS > 8 {
+                    Self::new(widen(r, BITS), widen(g, BITS), widen(b, BITS))
+                } else {
+                    let shift = 8 - BITS;
+                    Self::new(
+                        (r >> shift) as $ch,
+                        (g >> shift) as $ch,
+                        (b >> shift) as $ch,
+                    )
+                }
+            }
+
+            /// Convert from 8-bit RGB values with ordered (Bayer) dithering
+            ///
+            /// The position of the pixel selects a threshold from a 4x4 Bayer matrix,
+            /// so values between two levels are spread over a pattern of both levels
+            /// instead of all rounding down. This avoids visible banding in gradients
+            /// at low bit depths. Identical to [`from_rgb8`](Self::from_rgb8) for
+            /// `BITS >= 8`.
+            pub const fn from_rgb8_dithered(r: u8, g: u8, b: u8, x: usize, y: usize) -> Self {
+                if BITS >= 8 {
+                    return Self::from_rgb8(r, g, b);
+                }
+
+                const fn dither(value: u8, offset: u32, shift: usize, max: $ch) -> $ch {
+                    let level = (value as u32 + offset) >> shift;
+                    if level > max as u32 {
+                        max
+                    } else {
+                        level as $ch
+                    }
+                }
+
+                let shift = 8 - BITS;
+                let threshold = BAYER_4X4[y & 3][x & 3] as u32;
+                // Threshold scaled to the step between two levels
+                let offset = ((2 * threshold + 1) << shift) / 32;
+                Self {
+                    r: dither(r, offset, shift, Self::MAX_VALUE),
+                    g: dither(g, offset, shift, Self::MAX_VALUE),
+                    b: dither(b, offset, shift, Self::MAX_VALUE),
+                }
+            }
+
+            /// Convert from a packed `0xRRGGBB` value, scaling to the target bit depth
+            ///
+            /// The top 8 bits are ignored.
+            pub const fn from_rgb_u32(rgb: u32) -> Self {
+                Self::from_rgb8((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+            }
+
+            /// Convert to a packed `0xRRGGBB` value, scaling from the current bit depth
+            pub const fn to_rgb_u32(&self) -> u32 {
+                let (r, g, b) = self.to_rgb8();
+                (r as u32) << 16 | (g as u32) << 8 | b as u32
+            }
+
+            /// Convert to 8-bit RGB values, scaling from the current bit depth
+            pub const fn to_rgb8(&self) -> (u8, u8, u8) {
+                if BITS > 8 {
+                    let shift = BITS - 8;
+                    (
+                        ((self.r as u32) >> shift) as u8,
+                        ((self.g as u32) >> shift) as u8,
+                        ((self.b as u32) >> shift) as u8,
+                    )
+                } else {
+                    let shift = 8 - BITS;
+                    (
+                        ((self.r as u32) << shift) as u8,
+                        ((self.g as u32) << shift) as u8,
+                        ((self.b as u32) << shift) as u8,
+                    )
+                }
+            }
         }
-    }
 
-    /// Create a black color (all components zero)
-    pub const fn black() -> Self {
-        Self { r: 0, g: 0, b: 0 }
-    }
+        impl<const BITS: usize> Default for $color<BITS> {
+            fn default() -> Self {
+                Self::black()
+            }
+        }
 
-    /// Create a white color (all components at maximum)
-    pub const fn white() -> Self {
-        Self {
-            r: Self::MAX_VALUE,
-            g: Self::MAX_VALUE,
-            b: Self::MAX_VALUE,
+        impl<const BITS: usize> fmt::Display for $color<BITS> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "RGB({}, {}, {})", self.r, self.g, self.b)
+            }
         }
-    }
 
-    /// Create a red color
-    pub const fn red() -> Self {
-        Self {
-            r: Self::MAX_VALUE,
-            g: 0,
-            b: 0,
+        impl<const BITS: usize> Hub75Pixel<BITS> for $color<BITS> {
+            const MAX_LEVEL: u32 = Self::MAX_VALUE as u32;
+            const CHANNEL_BYTES: usize = core::mem::size_of::<$ch>();
+
+            fn levels(&self) -> [u32; 3] {
+                [self.r as u32, self.g as u32, self.b as u32]
+            }
+
+            fn from_levels(levels: [u32; 3]) -> Self {
+                let [r, g, b] = levels.map(|level| level.min(Self::MAX_LEVEL) as $ch);
+                Self { r, g, b }
+            }
+
+            fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
+                Self::from_rgb8(r, g, b)
+            }
+
+            fn to_rgb8(&self) -> (u8, u8, u8) {
+                Self::to_rgb8(self)
+            }
+
+            fn get_bit(&self, bit_plane: usize) -> (bool, bool, bool) {
+                Self::get_bit(self, bit_plane)
+            }
         }
-    }
 
-    /// Create a green color
-    pub const fn green() -> Self {
-        Self {
-            r: 0,
-            g: Self::MAX_VALUE,
-            b: 0,
+        impl<const BITS: usize> From<Hsv> for $color<BITS> {
+            fn from(hsv: Hsv) -> Self {
+                let (r, g, b) = hsv.to_rgb8();
+                Self::from_rgb8(r, g, b)
+            }
         }
-    }
 
-    /// Create a blue color
-    pub const fn blue() -> Self {
-        Self {
-            r: 0,
-            g: 0,
-            b: Self::MAX_VALUE,
+        impl<const BITS: usize> From<$color<BITS>> for Hsv {
+            fn from(color: $color<BITS>) -> Self {
+                let (r, g, b) = color.to_rgb8();
+                Hsv::from_rgb8(r, g, b)
+            }
+        }
+
+        impl<const BITS: usize> From<$color<BITS>> for LinearRgb {
+            fn from(color: $color<BITS>) -> Self {
+                let (r, g, b) = color.to_rgb8();
+                Self::from_srgb8(r, g, b)
+            }
+        }
+
+        impl<const BITS: usize> From<LinearRgb> for $color<BITS> {
+            fn from(color: LinearRgb) -> Self {
+                let (r, g, b) = color.to_srgb8();
+                Self::from_rgb8(r, g, b)
+            }
+        }
+
+        /// Channel-wise addition, saturating at `MAX_VALUE`
+        impl<const BITS: usize> core::ops::Add for $color<BITS> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                self.blend_add(rhs)
+            }
+        }
+
+        impl<const BITS: usize> core::ops::AddAssign for $color<BITS> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        /// Channel-wise subtraction, saturating at zero
+        impl<const BITS: usize> core::ops::Sub for $color<BITS> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                self.saturating_sub(rhs)
+            }
+        }
+
+        impl<const BITS: usize> core::ops::SubAssign for $color<BITS> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        /// Multiplication of every channel by an integer, saturating at `MAX_VALUE`
+        ///
+        /// Use [`scale`](Self::scale) to dim by a fraction.
+        impl<const BITS: usize> core::ops::Mul<u8> for $color<BITS> {
+            type Output = Self;
+
+            fn mul(self, rhs: u8) -> Self::Output {
+                self.saturating_mul(rhs)
+            }
         }
+
+        impl<const BITS: usize> core::ops::MulAssign<u8> for $color<BITS> {
+            fn mul_assign(&mut self, rhs: u8) {
+                *self = *self * rhs;
+            }
+        }
+    };
+}
+
+impl_color!(
+    Hub75Color,
+    u8,
+    "color depth exceeds channel storage, use `Hub75Color16` for depths above 8 bits"
+);
+impl_color!(
+    Hub75Color16,
+    u16,
+    "color depth exceeds the 16 bits of channel storage"
+);
+
+/// Hue/saturation/value color representation
+///
+/// Convenient for rainbow, plasma and other effects that sweep through hues.
+/// All conversions use integer arithmetic only.
+///
+/// # Examples
+///
+/// ```rust
+/// use hub75::{Hsv, Hub75Color};
+///
+/// let orange = Hsv::new(30, 255, 255);
+/// let color: Hub75Color<8> = orange.into();
+/// assert_eq!(color, Hub75Color::new(255, 128, 0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Hsv {
+    /// Hue in degrees (0-359, larger values wrap around)
+    pub h: u16,
+    /// Saturation (0 = gray, 255 = fully saturated)
+    pub s: u8,
+    /// Value (0 = black, 255 = full brightness)
+    pub v: u8,
+}
+
+impl Hsv {
+    /// Create a new HSV color
+    pub const fn new(h: u16, s: u8, v: u8) -> Self {
+        Self { h, s, v }
     }
 
-    /// Get the bit value for a specific bit plane
-    pub fn get_bit(&self, bit_plane: usize) -> (bool, bool, bool) {
-        if bit_plane >= BITS {
-            return (false, false, false);
+    /// Convert to 8-bit RGB values
+    pub const fn to_rgb8(&self) -> (u8, u8, u8) {
+        let v = self.v as u32;
+        let s = self.s as u32;
+        if s == 0 {
+            return (self.v, self.v, self.v);
         }
 
-        let mask = 1 << bit_plane;
-        (
-            (self.r & mask) != 0,
-            (self.g & mask) != 0,
-            (self.b & mask) != 0,
-        )
+        let h = (self.h % 360) as u32;
+        let region = h / 60;
+        // Position within the 60 degree region, scaled to 0-255
+        let remainder = ((h % 60) * 255 + 30) / 60;
+
+        let p = (v * (255 - s) + 127) / 255;
+        let q = (v * (255 * 255 - s * remainder) + 255 * 127) / (255 * 255);
+        let t = (v * (255 * 255 - s * (255 - remainder)) + 255 * 127) / (255 * 255);
+
+        let (r, g, b) = match region {
+            0 => (v, t, p),
+            1 => (q, v, p),
+            2 => (p, v, t),
+            3 => (p, q, v),
+            4 => (t, p, v),
+            _ => (v, p, q),
+        };
+        (r as u8, g as u8, b as u8)
     }
 
-    /// Convert from 8-bit RGB values, scaling to the target bit depth
+    /// Convert from 8-bit RGB values
     pub const fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
-        if BITS >= 8 {
-            Self::new(r, g, b)
-        } else {
-            let shift = 8 - BITS;
-            Self::new(r >> shift, g >> shift, b >> shift)
+        let (r, g, b) = (r as i32, g as i32, b as i32);
+        let max = if r > g { r } else { g };
+        let max = if max > b { max } else { b };
+        let min = if r < g { r } else { g };
+        let min = if min < b { min } else { b };
+        let delta = max - min;
+
+        if delta == 0 {
+            return Self::new(0, 0, max as u8);
         }
-    }
 
-    /// Convert to 8-bit RGB values, scaling from the current bit depth
-    pub const fn to_rgb8(&self) -> (u8, u8, u8) {
-        if BITS >= 8 {
-            (self.r, self.g, self.b)
+        let s = (delta * 255 + max / 2) / max;
+        let h = if max == r {
+            (60 * (g - b) + delta / 2).div_euclid(delta)
+        } else if max == g {
+            120 + (60 * (b - r) + delta / 2).div_euclid(delta)
         } else {
-            let shift = 8 - BITS;
-            (self.r << shift, self.g << shift, self.b << shift)
-        }
+            240 + (60 * (r - g) + delta / 2).div_euclid(delta)
+        };
+
+        Self::new(h.rem_euclid(360) as u16, s as u8, max as u8)
     }
 }
 
-impl<const BITS: usize> Default for Hub75Color<BITS> {
-    fn default() -> Self {
-        Self::black()
-    }
+/// Convert an 8-bit sRGB value to 16-bit linear light (0 to 65535)
+pub fn srgb_to_linear(value: u8) -> u16 {
+    SRGB_TO_LINEAR[value as usize]
 }
 
-impl<const BITS: usize> fmt::Display for Hub75Color<BITS> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "RGB({}, {}, {})", self.r, self.g, self.b)
+/// Convert a 16-bit linear light value to 8-bit sRGB, rounding to the nearest level
+pub fn linear_to_srgb(value: u16) -> u8 {
+    let upper = SRGB_TO_LINEAR.partition_point(|&level| level < value);
+    if upper == 0 {
+        return 0;
+    }
+    if upper == SRGB_TO_LINEAR.len() {
+        return 255;
+    }
+
+    let lower = upper - 1;
+    if value - SRGB_TO_LINEAR[lower] < SRGB_TO_LINEAR[upper] - value {
+        lower as u8
+    } else {
+        upper as u8
     }
 }
 
-#[cfg(feature = "embedded-graphics")]
-mod embedded_graphics_support {
-    use super::*;
-    use embedded_graphics_core::pixelcolor::{Rgb565, Rgb888, RgbColor};
-
-    impl<const BITS: usize> ColorConvert<Rgb565> for Hub75Color<BITS> {
-        fn from_color(color: Rgb565) -> Self {
-            Self::from_rgb8(
-                (color.r() as u16 * 255 / 31) as u8,
-                (color.g() as u16 * 255 / 63) as u8,
-                (color.b() as u16 * 255 / 31) as u8,
-            )
-        }
+/// Color with linear light channels, for blending and fading
+///
+/// Color values are sRGB encoded, so blending them directly gives dark, muddy
+/// mixes (red and green average to a brownish olive rather than yellow).
+/// Convert colors to `LinearRgb`, composite in linear space and convert back
+/// once at the end.
+///
+/// # Examples
+///
+/// ```rust
+/// use hub75::{Hub75Color, LinearRgb};
+///
+/// let red = LinearRgb::from(Hub75Color::<8>::red());
+/// let green = LinearRgb::from(Hub75Color::<8>::green());
+/// let mixed: Hub75Color<8> = LinearRgb::lerp(red, green, 128).into();
+///
+/// assert_eq!(mixed, Hub75Color::new(187, 188, 0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LinearRgb {
+    /// Red channel (0 to 65535)
+    pub r: u16,
+    /// Green channel (0 to 65535)
+    pub g: u16,
+    /// Blue channel (0 to 65535)
+    pub b: u16,
+}
 
-        fn to_color(self) -> Rgb565 {
-            let (r, g, b) = self.to_rgb8();
-            Rgb565::new(r >> 3, g >> 2, b >> 3)
-        }
+impl LinearRgb {
+    /// Create a new linear color
+    pub const fn new(r: u16, g: u16, b: u16) -> Self {
+        Self { r, g, b }
     }
 
-    impl<const BITS: usize> ColorConvert<Rgb888> for Hub75Color<BITS> {
-        fn from_color(color: Rgb888) -> Self {
-            Self::from_rgb8(color.r(), color.g(), color.b())
-        }
+    /// Convert from 8-bit sRGB values
+    pub fn from_srgb8(r: u8, g: u8, b: u8) -> Self {
+        Self::new(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b))
+    }
 
-        fn to_color(self) -> Rgb888 {
-            let (r, g, b) = self.to_rgb8();
-            Rgb888::new(r, g, b)
-        }
+    /// Convert to 8-bit sRGB values
+    pub fn to_srgb8(&self) -> (u8, u8, u8) {
+        (
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b),
+        )
     }
 
-    // Keep From/Into for backward compatibility
-    impl<const BITS: usize> From<Rgb565> for Hub75Color<BITS> {
-        fn from(color: Rgb565) -> Self {
-            Self::from_color(color)
+    /// Linearly interpolate between two colors (`t`: 0 = `a`, 255 = `b`)
+    pub const fn lerp(a: Self, b: Self, t: u8) -> Self {
+        const fn mix(a: u16, b: u16, t: u8) -> u16 {
+            let t = t as u32;
+            ((a as u32 * (255 - t) + b as u32 * t + 127) / 255) as u16
         }
+        Self::new(mix(a.r, b.r, t), mix(a.g, b.g, t), mix(a.b, b.b, t))
     }
 
-    impl<const BITS: usize> From<Rgb888> for Hub75Color<BITS> {
-        fn from(color: Rgb888) -> Self {
-            Self::from_color(color)
-        }
+    /// Add two colors channel by channel, saturating at full intensity
+    pub const fn blend_add(self, other: Self) -> Self {
+        Self::new(
+            self.r.saturating_add(other.r),
+            self.g.saturating_add(other.g),
+            self.b.saturating_add(other.b),
+        )
+    }
+
+    /// Scale the color by a brightness factor (255 = unchanged)
+    pub const fn scale(self, brightness: u8) -> Self {
+        Self::lerp(Self::new(0, 0, 0), self, brightness)
+    }
+}
+
+/// sRGB to linear lookup table (8-bit sRGB in, 16-bit linear out)
+const SRGB_TO_LINEAR: [u16; 256] = [
+    0, 20, 40, 60, 80, 99, 119, 139, 159, 179, 199, 219, 241, 264, 288, 313, 340, 367, 396, 427,
+    458, 491, 526, 562, 599, 637, 677, 718, 761, 805, 851, 898, 947, 997, 1048, 1101, 1156, 1212,
+    1270, 1330, 1391, 1453, 1517, 1583, 1651, 1720, 1790, 1863, 1937, 2013, 2090, 2170, 2250, 2333,
+    2418, 2504, 2592, 2681, 2773, 2866, 2961, 3058, 3157, 3258, 3360, 3464, 3570, 3678, 3788, 3900,
+    4014, 4129, 4247, 4366, 4488, 4611, 4736, 4864, 4993, 5124, 5257, 5392, 5530, 5669, 5810, 5953,
+    6099, 6246, 6395, 6547, 6700, 6856, 7014, 7174, 7335, 7500, 7666, 7834, 8004, 8177, 8352, 8528,
+    8708, 8889, 9072, 9258, 9445, 9635, 9828, 10022, 10219, 10417, 10619, 10822, 11028, 11235,
+    11446, 11658, 11873, 12090, 12309, 12530, 12754, 12980, 13209, 13440, 13673, 13909, 14146,
+    14387, 14629, 14874, 15122, 15371, 15623, 15878, 16135, 16394, 16656, 16920, 17187, 17456,
+    17727, 18001, 18277, 18556, 18837, 19121, 19407, 19696, 19987, 20281, 20577, 20876, 21177,
+    21481, 21787, 22096, 22407, 22721, 23038, 23357, 23678, 24002, 24329, 24658, 24990, 25325,
+    25662, 26001, 26344, 26688, 27036, 27386, 27739, 28094, 28452, 28813, 29176, 29542, 29911,
+    30282, 30656, 31033, 31412, 31794, 32179, 32567, 32957, 33350, 33745, 34143, 34544, 34948,
+    35355, 35764, 36176, 36591, 37008, 37429, 37852, 38278, 38706, 39138, 39572, 40009, 40449,
+    40891, 41337, 41785, 42236, 42690, 43147, 43606, 44069, 44534, 45002, 45473, 45947, 46423,
+    46903, 47385, 47871, 48359, 48850, 49344, 49841, 50341, 50844, 51349, 51858, 52369, 52884,
+    53401, 53921, 54445, 54971, 55500, 56032, 56567, 57105, 57646, 58190, 58737, 59287, 59840,
+    60396, 60955, 61517, 62082, 62650, 63221, 63795, 64372, 64952, 65535,
+];
+
+#[cfg(feature = "embedded-graphics")]
+mod embedded_graphics_support {
+    use super::*;
+    use embedded_graphics_core::pixelcolor::{
+        raw::{RawData, RawU24},
+        PixelColor, Rgb565, Rgb888, RgbColor,
+    };
+
+    /// Implement the embedded-graphics conversions of a color type with `$ch`
+    /// channels
+    macro_rules! impl_color_conversions {
+        ($color:ident, $ch:ty) => {
+            /// Native panel color space
+            ///
+            /// The raw representation packs the unscaled channel values as `0xRRGGBB`,
+            /// so drawing with `Hub75Color` avoids any conversion through `Rgb565`.
+            /// Depths above 8 bits only keep the 8 most significant bits per channel.
+            impl<const BITS: usize> PixelColor for $color<BITS> {
+                type Raw = RawU24;
+            }
+
+            impl<const BITS: usize> From<RawU24> for $color<BITS> {
+                fn from(raw: RawU24) -> Self {
+                    let value = raw.into_inner();
+                    let (r, g, b) = ((value >> 16) as u8, (value >> 8) as u8, value as u8);
+                    if BITS > 8 {
+                        Self::from_rgb8(r, g, b)
+                    } else {
+                        Self::new(r as $ch, g as $ch, b as $ch)
+                    }
+                }
+            }
+
+            impl<const BITS: usize> From<$color<BITS>> for RawU24 {
+                fn from(color: $color<BITS>) -> Self {
+                    let (r, g, b) = if BITS > 8 {
+                        let (r, g, b) = color.to_rgb8();
+                        (r as u32, g as u32, b as u32)
+                    } else {
+                        (color.r as u32, color.g as u32, color.b as u32)
+                    };
+                    RawU24::new(r << 16 | g << 8 | b)
+                }
+            }
+
+            impl<const BITS: usize> ColorConvert<Rgb565> for $color<BITS> {
+                fn from_color(color: Rgb565) -> Self {
+                    Self::from_rgb8(
+                        (color.r() as u16 * 255 / 31) as u8,
+                        (color.g() as u16 * 255 / 63) as u8,
+                        (color.b() as u16 * 255 / 31) as u8,
+                    )
+                }
+
+                fn to_color(self) -> Rgb565 {
+                    let (r, g, b) = self.to_rgb8();
+                    Rgb565::new(r >> 3, g >> 2, b >> 3)
+                }
+            }
+
+            impl<const BITS: usize> ColorConvert<Rgb888> for $color<BITS> {
+                fn from_color(color: Rgb888) -> Self {
+                    Self::from_rgb8(color.r(), color.g(), color.b())
+                }
+
+                fn to_color(self) -> Rgb888 {
+                    let (r, g, b) = self.to_rgb8();
+                    Rgb888::new(r, g, b)
+                }
+            }
+
+            // Keep From/Into for backward compatibility
+            impl<const BITS: usize> From<Rgb565> for $color<BITS> {
+                fn from(color: Rgb565) -> Self {
+                    Self::from_color(color)
+                }
+            }
+
+            impl<const BITS: usize> From<Rgb888> for $color<BITS> {
+                fn from(color: Rgb888) -> Self {
+                    Self::from_color(color)
+                }
+            }
+
+            impl<const BITS: usize> From<$color<BITS>> for Rgb565 {
+                fn from(color: $color<BITS>) -> Self {
+                    color.to_color()
+                }
+            }
+
+            impl<const BITS: usize> From<$color<BITS>> for Rgb888 {
+                fn from(color: $color<BITS>) -> Self {
+                    color.to_color()
+                }
+            }
+        };
     }
 
-    impl<const BITS: usize> From<Hub75Color<BITS>> for Rgb565 {
-        fn from(color: Hub75Color<BITS>) -> Self {
-            color.to_color()
+    impl_color_conversions!(Hub75Color, u8);
+    impl_color_conversions!(Hub75Color16, u16);
+
+    impl From<Hsv> for Rgb565 {
+        fn from(hsv: Hsv) -> Self {
+            Hub75Color::<8>::from(hsv).to_color()
         }
     }
 
-    impl<const BITS: usize> From<Hub75Color<BITS>> for Rgb888 {
-        fn from(color: Hub75Color<BITS>) -> Self {
-            color.to_color()
+    impl From<Hsv> for Rgb888 {
+        fn from(hsv: Hsv) -> Self {
+            let (r, g, b) = hsv.to_rgb8();
+            Rgb888::new(r, g, b)
         }
     }
 }
@@ -270,7 +924,243 @@ mod tests {
 
         // Should be close to original values (some precision loss expected)
         assert!(r >= 252); // 63 << 2 = 252
-        assert!(g >= 124 && g <= 128); // 32 << 2 = 128
-        assert!(b >= 60 && b <= 64); // 16 << 2 = 64
+        assert!((124..=128).contains(&g)); // 32 << 2 = 128
+        assert!((60..=64).contains(&b)); // 16 << 2 = 64
+    }
+
+    #[test]
+    fn test_rgb8_dithered() {
+        // Exact levels are unaffected by the dither pattern
+        for (x, y) in [(0, 0), (1, 2), (3, 3)] {
+            assert_eq!(
+                Hub75Color::<4>::from_rgb8_dithered(0, 128, 255, x, y),
+                Hub75Color::new(0, 8, 15)
+            );
+        }
+
+        // Halfway between levels 8 and 9: half of the 4x4 tile rounds up
+        let ups = (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .filter(|&(x, y)| Hub75Color::<4>::from_rgb8_dithered(136, 0, 0, x, y).r == 9)
+            .count();
+        assert_eq!(ups, 8);
+
+        assert_eq!(
+            Hub75Color::<8>::from_rgb8_dithered(1, 2, 3, 3, 1),
+            Hub75Color::new(1, 2, 3)
+        );
+    }
+
+    #[test]
+    fn test_wide_color() {
+        let color = Hub75Color16::<12>::from_rgb8(255, 128, 0);
+        assert_eq!(color, Hub75Color16::new(4095, 2056, 0));
+        assert_eq!(color.to_rgb8(), (255, 128, 0));
+        assert_eq!(Hub75Color16::<12>::new(5000, 0, 0), Hub75Color16::red());
+
+        // Every bit plane up to the depth is addressable
+        assert_eq!(color.get_bit(11), (true, true, false));
+        assert_eq!(
+            Hub75Color16::<12>::new(0, 2048, 0).get_bit(11),
+            (false, true, false)
+        );
+        assert_eq!(
+            Hub75Color16::<12>::new(0, 2048, 0).get_bit(10),
+            (false, false, false)
+        );
+
+        // Narrow depths behave the same in either color type
+        assert_eq!(
+            Hub75Color16::<6>::from_rgb8(255, 128, 64).to_rgb8(),
+            Hub75Color::<6>::from_rgb8(255, 128, 64).to_rgb8()
+        );
+
+        // Levels round-trip through the pixel trait, clamped to the depth
+        let levels = Hub75Pixel::levels(&color);
+        assert_eq!(levels, [4095, 2056, 0]);
+        assert_eq!(
+            <Hub75Color16<12> as Hub75Pixel<12>>::from_levels(levels),
+            color
+        );
+        assert_eq!(
+            <Hub75Color<6> as Hub75Pixel<6>>::from_levels([100, 20, 0]),
+            Hub75Color::new(63, 20, 0)
+        );
+    }
+
+    #[test]
+    fn test_rgb_u32() {
+        assert_eq!(
+            Hub75Color::<8>::from_rgb_u32(0x12_34_56),
+            Hub75Color::new(0x12, 0x34, 0x56)
+        );
+        assert_eq!(
+            Hub75Color::<8>::new(0x12, 0x34, 0x56).to_rgb_u32(),
+            0x123456
+        );
+        assert_eq!(
+            Hub75Color::<6>::from_rgb_u32(0xFF00_FFFF),
+            Hub75Color::cyan()
+        );
+        assert_eq!(
+            Hub75Color::<4>::from_rgb_u32(0xFFFF00).to_rgb_u32(),
+            0xF0F000
+        );
+    }
+
+    #[test]
+    fn test_named_colors() {
+        const ORANGE: Hub75Color<6> = Hub75Color::orange();
+        assert_eq!(ORANGE, Hub75Color::new(63, 32, 0));
+        assert_eq!(Hub75Color::<6>::yellow(), Hub75Color::new(63, 63, 0));
+        assert_eq!(Hub75Color::<6>::magenta(), Hub75Color::new(63, 0, 63));
+        assert_eq!(Hub75Color::<6>::gray(0), Hub75Color::black());
+        assert_eq!(Hub75Color::<6>::gray(255), Hub75Color::white());
+        assert_eq!(Hub75Color::<6>::gray(128), Hub75Color::new(32, 32, 32));
+    }
+
+    #[test]
+    fn test_srgb_linear() {
+        assert_eq!(srgb_to_linear(0), 0);
+        assert_eq!(srgb_to_linear(255), 65535);
+        for value in 0..=255 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(value)), value);
+        }
+        // Mid-gray in sRGB is about 21% linear light
+        assert_eq!(linear_to_srgb(32768), 188);
+    }
+
+    #[test]
+    fn test_linear_blending() {
+        let red = LinearRgb::from(Hub75Color::<8>::red());
+        let green = LinearRgb::from(Hub75Color::<8>::green());
+
+        // Blending in linear space keeps the mix brighter than naive averaging
+        let linear: Hub75Color<8> = LinearRgb::lerp(red, green, 128).into();
+        let naive = Hub75Color::lerp(Hub75Color::<8>::red(), Hub75Color::green(), 128);
+        assert!(linear.r > naive.r && linear.g > naive.g);
+
+        let white = LinearRgb::from(Hub75Color::<8>::white());
+        assert_eq!(white.scale(0), LinearRgb::default());
+        assert_eq!(red.blend_add(green).blend_add(white), white);
+        assert_eq!(
+            Hub75Color::<6>::from(LinearRgb::from_srgb8(255, 0, 0)),
+            Hub75Color::red()
+        );
+    }
+
+    #[test]
+    fn test_luma() {
+        assert_eq!(Hub75Color::<8>::white().luma(), 255);
+        assert_eq!(Hub75Color::<8>::black().luma(), 0);
+        assert_eq!(Hub75Color::<6>::white().luma(), 63);
+        // Green contributes most, blue least
+        assert_eq!(Hub75Color::<8>::red().luma(), 77);
+        assert_eq!(Hub75Color::<8>::green().luma(), 149);
+        assert_eq!(Hub75Color::<8>::blue().luma(), 29);
+
+        assert_eq!(
+            Hub75Color::<6>::yellow().to_grayscale(),
+            Hub75Color::new(56, 56, 56)
+        );
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = Hub75Color::<6>::new(0, 63, 10);
+        let b = Hub75Color::<6>::new(63, 0, 10);
+
+        assert_eq!(Hub75Color::lerp(a, b, 0), a);
+        assert_eq!(Hub75Color::lerp(a, b, 255), b);
+        assert_eq!(Hub75Color::lerp(a, b, 128), Hub75Color::new(32, 31, 10));
+    }
+
+    #[test]
+    fn test_saturating_operators() {
+        let color = Hub75Color::<6>::new(40, 20, 0);
+
+        assert_eq!(color + color, Hub75Color::new(63, 40, 0));
+        assert_eq!(
+            color - Hub75Color::new(10, 30, 5),
+            Hub75Color::new(30, 0, 0)
+        );
+        assert_eq!(color * 2, Hub75Color::new(63, 40, 0));
+        assert_eq!(color * 1, color);
+        assert_eq!(
+            Hub75Color::<8>::new(200, 100, 0) * 255,
+            Hub75Color::yellow()
+        );
+
+        let mut trail = Hub75Color::<4>::new(15, 8, 2);
+        trail -= Hub75Color::new(2, 2, 2);
+        assert_eq!(trail, Hub75Color::new(13, 6, 0));
+        trail += Hub75Color::new(5, 0, 1);
+        assert_eq!(trail, Hub75Color::new(15, 6, 1));
+        trail *= 3;
+        assert_eq!(trail, Hub75Color::new(15, 15, 3));
+    }
+
+    #[test]
+    fn test_blend_and_scale() {
+        let gray = Hub75Color::<6>::new(40, 40, 40);
+
+        assert_eq!(gray.blend_add(gray), Hub75Color::white());
+        assert_eq!(
+            Hub75Color::<8>::new(200, 10, 0).blend_add(Hub75Color::new(100, 10, 0)),
+            Hub75Color::new(255, 20, 0)
+        );
+
+        assert_eq!(gray.blend_multiply(Hub75Color::white()), gray);
+        assert_eq!(
+            gray.blend_multiply(Hub75Color::black()),
+            Hub75Color::black()
+        );
+        assert_eq!(
+            Hub75Color::<6>::white().blend_multiply(Hub75Color::red()),
+            Hub75Color::red()
+        );
+
+        assert_eq!(gray.scale(255), gray);
+        assert_eq!(gray.scale(0), Hub75Color::black());
+        assert_eq!(gray.scale(128), Hub75Color::new(20, 20, 20));
+    }
+
+    #[test]
+    fn test_hsv_to_rgb() {
+        assert_eq!(Hsv::new(0, 255, 255).to_rgb8(), (255, 0, 0));
+        assert_eq!(Hsv::new(120, 255, 255).to_rgb8(), (0, 255, 0));
+        assert_eq!(Hsv::new(240, 255, 255).to_rgb8(), (0, 0, 255));
+        assert_eq!(Hsv::new(60, 255, 255).to_rgb8(), (255, 255, 0));
+        assert_eq!(Hsv::new(360 + 180, 255, 255).to_rgb8(), (0, 255, 255));
+        assert_eq!(Hsv::new(200, 0, 100).to_rgb8(), (100, 100, 100));
+        assert_eq!(Hsv::new(300, 255, 0).to_rgb8(), (0, 0, 0));
+
+        let color: Hub75Color<4> = Hsv::new(0, 255, 255).into();
+        assert_eq!(color, Hub75Color::red());
+    }
+
+    #[test]
+    fn test_rgb_to_hsv_round_trip() {
+        assert_eq!(Hsv::from_rgb8(255, 0, 0), Hsv::new(0, 255, 255));
+        assert_eq!(Hsv::from_rgb8(0, 0, 255), Hsv::new(240, 255, 255));
+        assert_eq!(Hsv::from_rgb8(255, 0, 128), Hsv::new(330, 255, 255));
+        assert_eq!(Hsv::from_rgb8(50, 50, 50), Hsv::new(0, 0, 50));
+
+        for h in (0..360).step_by(7) {
+            let (r, g, b) = Hsv::new(h, 255, 255).to_rgb8();
+            let hsv = Hsv::from_rgb8(r, g, b);
+            assert!(hsv.h.abs_diff(h) <= 1, "hue {} -> {}", h, hsv.h);
+            assert_eq!((hsv.s, hsv.v), (255, 255));
+        }
+    }
+
+    #[cfg(feature = "embedded-graphics")]
+    #[test]
+    fn test_raw_representation() {
+        use embedded_graphics_core::pixelcolor::{raw::RawU24, IntoStorage};
+
+        let color = Hub75Color::<6>::new(63, 32, 1);
+        assert_eq!(color.into_storage(), 0x3f_20_01);
+        assert_eq!(Hub75Color::<6>::from(RawU24::new(0x3f_20_01)), color);
     }
 }
@@ -0,0 +1,334 @@
+//! Layering several frame sources into one frame
+//!
+//! A [`Composite`] stacks [`FrameSource`]s, such as a background animation and
+//! a foreground ticker, and blends them into a single frame on every step.
+//! Each layer has its own [`BlendMode`]. A composite is itself a frame source,
+//! so it can be used as a scene or layered again.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{AnimationState, Hub75Color, Hub75FrameBuffer};
+//! use hub75::composite::{BlendMode, Composite};
+//!
+//! let mut background = |frame: &mut Hub75FrameBuffer<64, 32, 6>| frame.fill(Hub75Color::blue());
+//! let mut ticker = |frame: &mut Hub75FrameBuffer<64, 32, 6>| {
+//!     frame.hline(0, 24, 64, Hub75Color::white());
+//! };
+//!
+//! let mut composite = Composite::<64, 32, 6, 2>::new();
+//! composite.add_layer(&mut background, BlendMode::Replace).unwrap();
+//! composite.add_layer(&mut ticker, BlendMode::Normal).unwrap();
+//!
+//! let mut frame = Hub75FrameBuffer::new();
+//! assert!(matches!(composite.next_into(&mut frame), AnimationState::Rendered));
+//! assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::blue());
+//! assert_eq!(frame.get_pixel(0, 24).unwrap(), Hub75Color::white());
+//! ```
+
+use heapless::Vec;
+
+use crate::{
+    animation::AnimationState, color::Hub75Color, frame_buffer::Hub75FrameBuffer,
+    scene::FrameSource, Hub75Error,
+};
+
+/// How a layer is combined with the layers below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BlendMode {
+    /// Layer pixels replace those below, except black pixels which are
+    /// transparent
+    #[default]
+    Normal,
+    /// Layer pixels replace those below, including black
+    Replace,
+    /// Channels are added, saturating at full intensity
+    Add,
+    /// Channels are multiplied, so the layer acts as a filter
+    Multiply,
+    /// Layer is mixed over the layers below with the given opacity
+    /// (0.8 fixed-point, 255 = opaque)
+    Alpha(u8),
+}
+
+impl BlendMode {
+    /// Combine a layer pixel `above` with the composited pixel `below`
+    pub fn apply<const BITS: usize>(
+        &self,
+        below: Hub75Color<BITS>,
+        above: Hub75Color<BITS>,
+    ) -> Hub75Color<BITS> {
+        match *self {
+            BlendMode::Normal if above == Hub75Color::black() => below,
+            BlendMode::Normal | BlendMode::Replace => above,
+            BlendMode::Add => below.blend_add(above),
+            BlendMode::Multiply => below.blend_multiply(above),
+            BlendMode::Alpha(alpha) => Hub75Color::lerp(below, above, alpha),
+        }
+    }
+}
+
+/// A frame source in a composite, with its blend mode
+struct Layer<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Source of the layer content
+    source: &'a mut dyn FrameSource<WIDTH, HEIGHT, COLOR_BITS>,
+    /// How the layer is combined with the layers below
+    blend: BlendMode,
+}
+
+/// A stack of up to `N` frame sources blended into one frame
+///
+/// Layers are drawn bottom to top in the order they were added. The blend
+/// mode of the bottom layer is ignored, as there is nothing below it.
+pub struct Composite<
+    'a,
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    const N: usize,
+> {
+    /// Layers from bottom to top
+    layers: Vec<Layer<'a, WIDTH, HEIGHT, COLOR_BITS>, N>,
+}
+
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize>
+    Composite<'a, WIDTH, HEIGHT, COLOR_BITS, N>
+{
+    /// Create a composite without layers
+    pub fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+
+    /// Add a layer on top of the existing ones
+    pub fn add_layer(
+        &mut self,
+        source: &'a mut dyn FrameSource<WIDTH, HEIGHT, COLOR_BITS>,
+        blend: BlendMode,
+    ) -> Result<(), Hub75Error> {
+        self.layers
+            .push(Layer { source, blend })
+            .map_err(|_| Hub75Error::BufferOverflow)
+    }
+
+    /// Change the blend mode of the layer at `index` (0 is the bottom layer)
+    pub fn set_blend_mode(&mut self, index: usize, blend: BlendMode) -> Result<(), Hub75Error> {
+        let layer = self
+            .layers
+            .get_mut(index)
+            .ok_or(Hub75Error::InvalidCoordinates)?;
+        layer.blend = blend;
+        Ok(())
+    }
+
+    /// Get the number of layers
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Check if the composite has no layers
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Advance every layer by one frame and blend them into `frame`
+    ///
+    /// Layers waiting for their next step contribute their current image.
+    /// Returns [`AnimationState::Rendered`] if any layer changed,
+    /// [`AnimationState::Wait`] if none did and [`AnimationState::Done`] once
+    /// every layer is done.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        let Some((bottom, upper)) = self.layers.split_first_mut() else {
+            return AnimationState::Done;
+        };
+
+        let mut summary = Summary::default();
+        let state = bottom.source.next_into(frame);
+        let bottom_result = if summary.record(&state) {
+            Ok(())
+        } else {
+            bottom.source.render_into(frame)
+        };
+        let result =
+            bottom_result.and_then(|()| Self::blend_layers(frame, upper, Some(&mut summary)));
+        if result.is_err() {
+            return AnimationState::Done;
+        }
+
+        if summary.all_done {
+            AnimationState::Done
+        } else if summary.any_rendered {
+            AnimationState::Rendered
+        } else {
+            AnimationState::Wait
+        }
+    }
+
+    /// Blend the upper layers into `frame`, advancing them when `summary` is
+    /// given and re-rendering their current image otherwise
+    ///
+    /// Kept out of line so that the temporary layer buffer only takes stack
+    /// space while blending.
+    #[inline(never)]
+    fn blend_layers(
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        layers: &mut [Layer<'a, WIDTH, HEIGHT, COLOR_BITS>],
+        mut summary: Option<&mut Summary>,
+    ) -> Result<(), Hub75Error> {
+        if layers.is_empty() {
+            return Ok(());
+        }
+
+        let mut layer_frame = Hub75FrameBuffer::new();
+        for layer in layers {
+            let rendered = match summary.as_deref_mut() {
+                Some(summary) => summary.record(&layer.source.next_into(&mut layer_frame)),
+                None => false,
+            };
+            if !rendered {
+                layer.source.render_into(&mut layer_frame)?;
+            }
+
+            for y in 0..HEIGHT {
+                let above = layer_frame.get_row(y)?;
+                for (pixel, &color) in frame.get_row_mut(y)?.iter_mut().zip(above.iter()) {
+                    *pixel = layer.blend.apply(*pixel, color);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Render the current image of every layer, blended, into `frame`
+    pub fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        let Some((bottom, upper)) = self.layers.split_first_mut() else {
+            return Err(Hub75Error::AnimationError(
+                crate::AnimationError::InvalidData,
+            ));
+        };
+        bottom.source.render_into(frame)?;
+        Self::blend_layers(frame, upper, None)
+    }
+}
+
+/// Combined state of the layers advanced in one step
+struct Summary {
+    /// Whether any layer rendered a new image
+    any_rendered: bool,
+    /// Whether every layer is done
+    all_done: bool,
+}
+
+impl Default for Summary {
+    fn default() -> Self {
+        Self {
+            any_rendered: false,
+            all_done: true,
+        }
+    }
+}
+
+impl Summary {
+    /// Record the state of one layer, returning whether it rendered
+    fn record<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        &mut self,
+        state: &AnimationState<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> bool {
+        let rendered = matches!(state, AnimationState::Rendered);
+        self.any_rendered |= rendered;
+        self.all_done &= matches!(state, AnimationState::Done);
+        rendered
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize> Default
+    for Composite<'_, WIDTH, HEIGHT, COLOR_BITS, N>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for Composite<'_, WIDTH, HEIGHT, COLOR_BITS, N>
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        Composite::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        Composite::render_into(self, frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::{Animation, AnimationData, AnimationEffect};
+
+    #[test]
+    fn test_blend_modes() {
+        let below = Hub75Color::<6>::new(40, 10, 0);
+        let above = Hub75Color::<6>::new(30, 0, 63);
+
+        assert_eq!(BlendMode::Normal.apply(below, above), above);
+        assert_eq!(BlendMode::Normal.apply(below, Hub75Color::black()), below);
+        assert_eq!(
+            BlendMode::Replace.apply(below, Hub75Color::black()),
+            Hub75Color::black()
+        );
+        assert_eq!(
+            BlendMode::Add.apply(below, above),
+            Hub75Color::new(63, 10, 63)
+        );
+        assert_eq!(BlendMode::Multiply.apply(below, Hub75Color::white()), below);
+        assert_eq!(BlendMode::Alpha(0).apply(below, above), below);
+        assert_eq!(BlendMode::Alpha(255).apply(below, above), above);
+    }
+
+    #[test]
+    fn test_composite_layers() {
+        // Background animation stepping every other frame
+        let mut red = Hub75FrameBuffer::<2, 1, 6>::new();
+        red.fill(Hub75Color::red());
+        let frames = [red];
+        let mut background =
+            Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 2).unwrap();
+        let mut overlay = |frame: &mut Hub75FrameBuffer<2, 1, 6>| {
+            frame.set_pixel(1, 0, Hub75Color::blue()).unwrap();
+        };
+
+        let mut composite = Composite::<2, 1, 6, 2>::new();
+        assert!(matches!(
+            composite.next_into(&mut Hub75FrameBuffer::new()),
+            AnimationState::Done
+        ));
+        composite
+            .add_layer(&mut background, BlendMode::Replace)
+            .unwrap();
+        composite.add_layer(&mut overlay, BlendMode::Add).unwrap();
+        assert_eq!(composite.len(), 2);
+        assert!(composite.set_blend_mode(2, BlendMode::Normal).is_err());
+
+        // The waiting background still shows beneath the overlay
+        let mut frame = Hub75FrameBuffer::new();
+        assert!(matches!(
+            composite.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::magenta());
+    }
+}
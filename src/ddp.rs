@@ -0,0 +1,249 @@
+//! Receiving the Distributed Display Protocol
+//!
+//! DDP is the lightweight UDP protocol spoken by WLED, xLights and other LED
+//! software. Each packet carries RGB data for a byte offset into the display,
+//! so large frames are split across packets, and the last packet of a frame
+//! sets the push flag to ask for it to be shown. A [`DdpReceiver`] writes the
+//! pixels into its own frame buffer in row-major order from the top-left and
+//! reports [`DdpEvent::Present`] on push, when the application should swap the
+//! frame onto the display.
+//!
+//! The network stack stays with the application: hand each UDP payload
+//! received on [`DDP_PORT`] to [`DdpReceiver::handle`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::ddp::{DdpEvent, DdpReceiver};
+//!
+//! // Push flag set, RGB data for pixel 65, the second pixel of row 1
+//! let packet = [0x41, 0x01, 0x0B, 0x01, 0, 0, 0, 195, 0, 3, 0, 255, 0];
+//!
+//! let mut receiver = DdpReceiver::<64, 32, 6>::new();
+//! let mut display_frame = Hub75FrameBuffer::new();
+//! if let Ok(Some(DdpEvent::Present)) = receiver.handle(&packet) {
+//!     display_frame.copy_from(receiver.frame());
+//! }
+//! assert_eq!(display_frame.get_pixel(1, 1).unwrap(), Hub75Color::green());
+//! ```
+
+use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer, AnimationError, Hub75Error};
+
+/// UDP port DDP is sent to
+pub const DDP_PORT: u16 = 4048;
+/// Size of the header without a timecode
+pub const HEADER_LEN: usize = 10;
+
+const VERSION_MASK: u8 = 0xC0;
+const VERSION_1: u8 = 0x40;
+const FLAG_TIMECODE: u8 = 0x10;
+const FLAG_REPLY: u8 = 0x04;
+const FLAG_QUERY: u8 = 0x02;
+const FLAG_PUSH: u8 = 0x01;
+
+/// Data types accepted as 8-bit RGB: undefined, the legacy RGB code and RGB24
+const RGB_TYPES: [u8; 3] = [0x00, 0x01, 0x0B];
+
+/// Destination of the default output device
+const ID_DISPLAY: u8 = 0x01;
+/// Destination of every device
+const ID_ALL: u8 = 0xFF;
+
+/// Outcome of a packet that changed the receiver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DdpEvent {
+    /// Pixels were updated
+    Updated,
+    /// The push flag was set and the frame should be shown
+    Present,
+}
+
+/// Writes DDP pixel data into a frame buffer
+pub struct DdpReceiver<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Frame being built
+    frame: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    /// Sequence number of the last packet, 0 if the sender does not number them
+    sequence: u8,
+    /// Packets skipped according to their sequence numbers
+    dropped: u32,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    DdpReceiver<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a receiver with a blank frame
+    pub fn new() -> Self {
+        Self {
+            frame: Hub75FrameBuffer::new(),
+            sequence: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Get the frame built from the packets received so far
+    pub fn frame(&self) -> &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS> {
+        &self.frame
+    }
+
+    /// Get the number of packets found missing from sequence numbers
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Handle a DDP packet
+    ///
+    /// Returns `Ok(None)` for packets that are valid but carry no pixels for
+    /// the display, such as queries, replies and other data types, and
+    /// [`AnimationError::InvalidData`] for malformed packets or data that does
+    /// not start on a pixel boundary. Pixels past the end of the panel are
+    /// ignored.
+    pub fn handle(&mut self, packet: &[u8]) -> Result<Option<DdpEvent>, Hub75Error> {
+        let header = packet
+            .get(..HEADER_LEN)
+            .ok_or(AnimationError::InvalidData)?;
+        let flags = header[0];
+        if flags & VERSION_MASK != VERSION_1 {
+            return Err(AnimationError::InvalidData.into());
+        }
+        if flags & (FLAG_QUERY | FLAG_REPLY) != 0
+            || !RGB_TYPES.contains(&header[2])
+            || ![ID_DISPLAY, ID_ALL].contains(&header[3])
+        {
+            return Ok(None);
+        }
+
+        let offset = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let length = u16::from_be_bytes([header[8], header[9]]) as usize;
+        let start = if flags & FLAG_TIMECODE != 0 {
+            HEADER_LEN + 4
+        } else {
+            HEADER_LEN
+        };
+        let data = packet
+            .get(start..start + length)
+            .ok_or(AnimationError::InvalidData)?;
+        if offset % 3 != 0 {
+            return Err(AnimationError::InvalidData.into());
+        }
+
+        self.track_sequence(header[1] & 0x0F);
+        for (i, rgb) in data.chunks_exact(3).enumerate() {
+            let pixel = offset / 3 + i;
+            if pixel >= WIDTH * HEIGHT {
+                break;
+            }
+            let color = Hub75Color::from_rgb8(rgb[0], rgb[1], rgb[2]);
+            let _ = self.frame.set_pixel(pixel % WIDTH, pixel / WIDTH, color);
+        }
+
+        Ok(Some(if flags & FLAG_PUSH != 0 {
+            DdpEvent::Present
+        } else {
+            DdpEvent::Updated
+        }))
+    }
+
+    /// Count packets missing between the last sequence number and this one
+    fn track_sequence(&mut self, sequence: u8) {
+        // Sequence numbers run from 1 to 15; 0 means they are not used
+        if sequence != 0 && self.sequence != 0 {
+            let expected = self.sequence % 15 + 1;
+            self.dropped += ((sequence + 15 - expected) % 15) as u32;
+        }
+        self.sequence = sequence;
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> Default
+    for DdpReceiver<WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestReceiver = DdpReceiver<4, 4, 6>;
+
+    fn packet(flags: u8, sequence: u8, offset: u32, data: &[u8]) -> ([u8; 64], usize) {
+        let mut packet = [0; 64];
+        packet[0] = VERSION_1 | flags;
+        packet[1] = sequence;
+        packet[2] = 0x0B;
+        packet[3] = ID_DISPLAY;
+        packet[4..8].copy_from_slice(&offset.to_be_bytes());
+        packet[8..10].copy_from_slice(&(data.len() as u16).to_be_bytes());
+        packet[10..10 + data.len()].copy_from_slice(data);
+        (packet, 10 + data.len())
+    }
+
+    #[test]
+    fn test_offsets_and_push() {
+        let mut receiver = TestReceiver::new();
+
+        // A frame split over two packets, pushed by the second
+        let (first, length) = packet(0, 1, 0, &[255, 0, 0]);
+        assert_eq!(
+            receiver.handle(&first[..length]),
+            Ok(Some(DdpEvent::Updated))
+        );
+        let (second, length) = packet(FLAG_PUSH, 2, 3 * 14, &[0, 0, 255, 0, 255, 0, 9, 9, 9]);
+        assert_eq!(
+            receiver.handle(&second[..length]),
+            Ok(Some(DdpEvent::Present))
+        );
+
+        let frame = receiver.frame();
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(2, 3).unwrap(), Hub75Color::blue());
+        assert_eq!(frame.get_pixel(3, 3).unwrap(), Hub75Color::green());
+        assert_eq!(receiver.dropped(), 0);
+
+        // Four bytes of timecode come before the three bytes of data
+        let (mut timed, length) = packet(FLAG_TIMECODE | FLAG_PUSH, 0, 3, &[0; 7]);
+        timed[8..10].copy_from_slice(&3u16.to_be_bytes());
+        timed[14..17].copy_from_slice(&[255, 255, 0]);
+        assert_eq!(
+            receiver.handle(&timed[..length]),
+            Ok(Some(DdpEvent::Present))
+        );
+        assert_eq!(
+            receiver.frame().get_pixel(1, 0).unwrap(),
+            Hub75Color::yellow()
+        );
+    }
+
+    #[test]
+    fn test_ignored_and_invalid() {
+        let mut receiver = TestReceiver::new();
+
+        let (mut query, length) = packet(FLAG_QUERY, 0, 0, &[]);
+        assert_eq!(receiver.handle(&query[..length]), Ok(None));
+        query[0] = VERSION_1;
+        query[3] = 250;
+        assert_eq!(receiver.handle(&query[..length]), Ok(None));
+
+        let (truncated, length) = packet(0, 0, 0, &[1, 2, 3]);
+        assert!(receiver.handle(&truncated[..length - 1]).is_err());
+        let (misaligned, length) = packet(0, 0, 1, &[1, 2, 3]);
+        assert!(receiver.handle(&misaligned[..length]).is_err());
+        let (mut old, length) = packet(0, 0, 0, &[]);
+        old[0] = 0x80;
+        assert!(receiver.handle(&old[..length]).is_err());
+    }
+
+    #[test]
+    fn test_dropped_packets() {
+        let mut receiver = TestReceiver::new();
+        for sequence in [14, 15, 3] {
+            let (data, length) = packet(0, sequence, 0, &[]);
+            receiver.handle(&data[..length]).unwrap();
+        }
+        assert_eq!(receiver.dropped(), 2);
+    }
+}
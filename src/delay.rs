@@ -0,0 +1,323 @@
+//! Delays for short bit planes
+//!
+//! The least significant bit planes are lit for only a few hundred
+//! nanoseconds, but executor delays are usually timer based with a
+//! granularity of a microsecond or more, so short planes come out far too
+//! long and the low bits of every color are lost. A [`SplitDelay`] sends
+//! delays below a threshold to a busy-wait delay and leaves longer ones to
+//! the executor, so the CPU only spins for the short planes.
+//!
+//! With the `cortex-m` feature, `CycleDelay` busy-waits on the core's
+//! cycle counter: the DWT `CYCCNT` register on Cortex-M3 and up, or the
+//! SysTick timer on any Cortex-M, including the M0+ of the RP2040.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use hub75::delay::{CycleDelay, SplitDelay};
+//!
+//! // SAFETY: nothing else uses the DWT cycle counter
+//! let busy = unsafe { CycleDelay::dwt(64_000_000) };
+//! // Spin for planes shorter than 5 us, await the rest
+//! let mut delay = SplitDelay::new(embassy_time::Delay, busy, 5_000);
+//!
+//! loop {
+//!     display.render_frame(&mut delay).await?;
+//! }
+//! ```
+
+use embedded_hal::delay::DelayNs as BlockingDelayNs;
+
+/// Delay that busy-waits below a threshold and defers to another delay above
+pub struct SplitDelay<D, B> {
+    /// Delay used at or above the threshold
+    delay: D,
+    /// Busy-wait delay used below the threshold
+    busy: B,
+    /// Shortest delay handed to `delay`, in nanoseconds
+    threshold_ns: u32,
+}
+
+impl<D, B: BlockingDelayNs> SplitDelay<D, B> {
+    /// Busy-wait with `busy` for delays shorter than `threshold_ns`
+    pub fn new(delay: D, busy: B, threshold_ns: u32) -> Self {
+        Self {
+            delay,
+            busy,
+            threshold_ns,
+        }
+    }
+
+    /// Set the shortest delay not busy-waited, in nanoseconds
+    pub fn set_threshold_ns(&mut self, threshold_ns: u32) {
+        self.threshold_ns = threshold_ns;
+    }
+
+    /// Get the shortest delay not busy-waited, in nanoseconds
+    pub fn threshold_ns(&self) -> u32 {
+        self.threshold_ns
+    }
+
+    /// Return the two delays
+    pub fn release(self) -> (D, B) {
+        (self.delay, self.busy)
+    }
+}
+
+impl<D: BlockingDelayNs, B: BlockingDelayNs> BlockingDelayNs for SplitDelay<D, B> {
+    fn delay_ns(&mut self, ns: u32) {
+        if ns < self.threshold_ns {
+            self.busy.delay_ns(ns);
+        } else {
+            self.delay.delay_ns(ns);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, B> embedded_hal_async::delay::DelayNs for SplitDelay<D, B>
+where
+    D: embedded_hal_async::delay::DelayNs,
+    B: BlockingDelayNs,
+{
+    async fn delay_ns(&mut self, ns: u32) {
+        if ns < self.threshold_ns {
+            self.busy.delay_ns(ns);
+        } else {
+            self.delay.delay_ns(ns).await;
+        }
+    }
+}
+
+#[cfg(feature = "cortex-m")]
+pub use cycle::CycleDelay;
+
+#[cfg(feature = "cortex-m")]
+mod cycle {
+    use embedded_hal::delay::DelayNs;
+
+    const DEMCR: usize = 0xE000_EDFC;
+    /// Enables the DWT and ITM units
+    const DEMCR_TRCENA: u32 = 1 << 24;
+    const DWT_CTRL: usize = 0xE000_1000;
+    const DWT_CTRL_CYCCNTENA: u32 = 1;
+    const DWT_CYCCNT: usize = 0xE000_1004;
+
+    const SYST_CSR: usize = 0xE000_E010;
+    /// Enabled, counting the processor clock, without interrupt
+    const SYST_CSR_ENABLE_CORE_CLOCK: u32 = 1 | 1 << 2;
+    const SYST_RVR: usize = 0xE000_E014;
+    const SYST_CVR: usize = 0xE000_E018;
+    /// SysTick is a 24-bit counter
+    const SYST_MASK: u32 = 0x00FF_FFFF;
+
+    /// Busy-wait delay counting core clock cycles
+    pub struct CycleDelay {
+        /// Address of the counter register
+        counter: usize,
+        /// Bits of the counter in use
+        mask: u32,
+        /// Whether the counter counts down
+        down: bool,
+        /// Core clock frequency in Hz
+        sysclk_hz: u32,
+    }
+
+    impl CycleDelay {
+        /// Count cycles with the DWT cycle counter (Cortex-M3 and up)
+        ///
+        /// # Safety
+        ///
+        /// The DWT cycle counter must not be reset or disabled by anything
+        /// else for as long as the delay exists. Debuggers may use it too.
+        pub unsafe fn dwt(sysclk_hz: u32) -> Self {
+            Self::dwt_at(DEMCR, DWT_CTRL, DWT_CYCCNT, sysclk_hz)
+        }
+
+        /// Count cycles with the SysTick timer (any Cortex-M)
+        ///
+        /// Sets SysTick free running over its full 24-bit range without an
+        /// interrupt.
+        ///
+        /// # Safety
+        ///
+        /// Nothing else, such as an RTOS tick or time driver, may use SysTick
+        /// for as long as the delay exists.
+        pub unsafe fn systick(sysclk_hz: u32) -> Self {
+            Self::systick_at(SYST_CSR, SYST_RVR, SYST_CVR, sysclk_hz)
+        }
+
+        /// Enable the DWT cycle counter at the given registers
+        unsafe fn dwt_at(demcr: usize, ctrl: usize, cyccnt: usize, sysclk_hz: u32) -> Self {
+            write(demcr, read(demcr) | DEMCR_TRCENA);
+            write(ctrl, read(ctrl) | DWT_CTRL_CYCCNTENA);
+            Self {
+                counter: cyccnt,
+                mask: u32::MAX,
+                down: false,
+                sysclk_hz,
+            }
+        }
+
+        /// Start SysTick at the given registers
+        unsafe fn systick_at(csr: usize, rvr: usize, cvr: usize, sysclk_hz: u32) -> Self {
+            write(csr, 0);
+            write(rvr, SYST_MASK);
+            write(cvr, 0);
+            write(csr, SYST_CSR_ENABLE_CORE_CLOCK);
+            Self {
+                counter: cvr,
+                mask: SYST_MASK,
+                down: true,
+                sysclk_hz,
+            }
+        }
+
+        /// Get the core clock frequency in Hz
+        pub fn sysclk_hz(&self) -> u32 {
+            self.sysclk_hz
+        }
+
+        /// Convert a duration to core clock cycles, rounding up
+        pub fn ns_to_cycles(&self, ns: u32) -> u64 {
+            (ns as u64 * self.sysclk_hz as u64).div_ceil(1_000_000_000)
+        }
+
+        /// Cycles counted between two readings of the counter
+        fn elapsed(&self, last: u32, now: u32) -> u32 {
+            let elapsed = if self.down {
+                last.wrapping_sub(now)
+            } else {
+                now.wrapping_sub(last)
+            };
+            elapsed & self.mask
+        }
+    }
+
+    impl DelayNs for CycleDelay {
+        fn delay_ns(&mut self, ns: u32) {
+            // Accumulate across polls so delays longer than a counter wrap
+            // are still exact
+            let mut remaining = self.ns_to_cycles(ns);
+            let mut last = unsafe { read(self.counter) };
+            while remaining > 0 {
+                let now = unsafe { read(self.counter) };
+                remaining = remaining.saturating_sub(self.elapsed(last, now) as u64);
+                last = now;
+            }
+        }
+    }
+
+    unsafe fn read(address: usize) -> u32 {
+        core::ptr::read_volatile(address as *const u32)
+    }
+
+    unsafe fn write(address: usize, value: u32) {
+        core::ptr::write_volatile(address as *mut u32, value)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_dwt_setup() {
+            let mut registers = [0x10u32, 0x4000_0000, 1234];
+            let [demcr, ctrl, cyccnt] =
+                core::array::from_fn(|i| &mut registers[i] as *mut u32 as usize);
+            let mut delay = unsafe { CycleDelay::dwt_at(demcr, ctrl, cyccnt, 64_000_000) };
+            assert_eq!(registers[0], 0x10 | DEMCR_TRCENA);
+            assert_eq!(registers[1], 0x4000_0000 | DWT_CTRL_CYCCNTENA);
+
+            // The counter counts up and wraps at 32 bits
+            assert_eq!(delay.elapsed(u32::MAX - 1, 3), 5);
+            assert_eq!(delay.ns_to_cycles(1_000), 64);
+            assert_eq!(delay.ns_to_cycles(10), 1);
+            delay.delay_ns(0);
+        }
+
+        #[test]
+        fn test_systick_setup() {
+            let mut registers = [0u32; 3];
+            let [csr, rvr, cvr] = core::array::from_fn(|i| &mut registers[i] as *mut u32 as usize);
+            let delay = unsafe { CycleDelay::systick_at(csr, rvr, cvr, 125_000_000) };
+            assert_eq!(registers, [SYST_CSR_ENABLE_CORE_CLOCK, SYST_MASK, 0]);
+            assert_eq!(delay.sysclk_hz(), 125_000_000);
+
+            // The counter counts down and wraps at 24 bits
+            assert_eq!(delay.elapsed(10, 4), 6);
+            assert_eq!(delay.elapsed(2, SYST_MASK - 1), 4);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "async")]
+    use core::future::Future;
+    #[cfg(feature = "async")]
+    use core::task::{Context, Poll, Waker};
+
+    /// Delay remembering the durations it was asked for
+    #[derive(Default)]
+    struct Log(heapless::Vec<u32, 8>);
+
+    impl BlockingDelayNs for Log {
+        fn delay_ns(&mut self, ns: u32) {
+            self.0.push(ns).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_split_delay() {
+        let mut delay = SplitDelay::new(Log::default(), Log::default(), 1_000);
+        delay.delay_ns(999);
+        delay.delay_ns(1_000);
+        delay.set_threshold_ns(5_000);
+        assert_eq!(delay.threshold_ns(), 5_000);
+        delay.delay_ns(4_000);
+
+        let (timer, busy) = delay.release();
+        assert_eq!(timer.0, [1_000]);
+        assert_eq!(busy.0, [999, 4_000]);
+    }
+
+    #[cfg(feature = "async")]
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = core::pin::pin!(future);
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("recording futures never wait"),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_split_delay_async() {
+        /// Async delay remembering the durations it was asked for
+        #[derive(Default)]
+        struct AsyncLog(heapless::Vec<u32, 8>);
+
+        impl embedded_hal_async::delay::DelayNs for AsyncLog {
+            async fn delay_ns(&mut self, ns: u32) {
+                self.0.push(ns).unwrap();
+            }
+        }
+
+        let mut delay = SplitDelay::new(AsyncLog::default(), Log::default(), 1_000);
+        block_on(embedded_hal_async::delay::DelayNs::delay_ns(
+            &mut delay, 200,
+        ));
+        block_on(embedded_hal_async::delay::DelayNs::delay_ns(
+            &mut delay, 8_000,
+        ));
+
+        let (timer, busy) = delay.release();
+        assert_eq!(timer.0, [8_000]);
+        assert_eq!(busy.0, [200]);
+    }
+}
@@ -0,0 +1,385 @@
+//! Installation diagnostics
+//!
+//! A [`PixelWalker`] lights one pixel, row or column at a time and steps
+//! through the whole panel on a timer. Watching it walk makes dead pixels
+//! and stuck rows easy to spot, and shows whether chained panels are wired
+//! in the order the frame buffer expects: the lit pixel should leave one
+//! panel exactly where it enters the next.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{AnimationState, Hub75FrameBuffer};
+//! use hub75::diagnostics::{PixelWalker, WalkMode};
+//!
+//! let mut walker = PixelWalker::<64, 32, 6>::new(WalkMode::Row);
+//! walker.set_step_ms(250);
+//! walker.set_cycle_colors(true);
+//!
+//! let mut frame = Hub75FrameBuffer::new();
+//! assert!(matches!(walker.next_at_into(0, &mut frame), AnimationState::Rendered));
+//! assert!(matches!(walker.next_at_into(100, &mut frame), AnimationState::Wait));
+//! assert!(matches!(walker.next_at_into(250, &mut frame), AnimationState::Rendered));
+//! assert_eq!(walker.position(), 1);
+//! ```
+
+use crate::{
+    animation::AnimationState, color::Hub75Color, frame_buffer::Hub75FrameBuffer,
+    scene::FrameSource, Hub75Error,
+};
+
+/// What is lit at each step of a walk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WalkMode {
+    /// One pixel, left to right then top to bottom
+    #[default]
+    Pixel,
+    /// One full row, top to bottom
+    Row,
+    /// One full column, left to right
+    Column,
+}
+
+/// Colors used on successive passes when cycling colors
+const PASS_COLORS: [(u8, u8, u8); 4] = [(255, 0, 0), (0, 255, 0), (0, 0, 255), (255, 255, 255)];
+
+/// Diagnostic that steps a lit pixel, row or column across the panel
+#[derive(Debug, Clone)]
+pub struct PixelWalker<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// What is lit at each step
+    mode: WalkMode,
+    /// Color when not cycling colors
+    color: Hub75Color<COLOR_BITS>,
+    /// Whether each pass uses the next of red, green, blue and white
+    cycle_colors: bool,
+    /// Whether the walk starts over after the last pass
+    looping: bool,
+    /// Current step within the pass
+    position: usize,
+    /// Number of completed passes
+    pass: usize,
+    /// Whether the walk has finished
+    done: bool,
+    /// Frames per step for frame-based stepping
+    frames_per_step: u16,
+    /// Frames the current step has been shown for
+    frame_counter: u16,
+    /// Milliseconds per step for time-based stepping
+    step_ms: u32,
+    /// Time the current step was first shown
+    step_started_ms: Option<u64>,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    PixelWalker<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a walker lighting white in the given mode
+    ///
+    /// It loops forever, stepping every 30 frames or every 500 ms.
+    pub fn new(mode: WalkMode) -> Self {
+        Self {
+            mode,
+            color: Hub75Color::white(),
+            cycle_colors: false,
+            looping: true,
+            position: 0,
+            pass: 0,
+            done: false,
+            frames_per_step: 30,
+            frame_counter: 0,
+            step_ms: 500,
+            step_started_ms: None,
+        }
+    }
+
+    /// Set what is lit at each step and start over
+    pub fn set_mode(&mut self, mode: WalkMode) {
+        self.mode = mode;
+        self.reset();
+    }
+
+    /// Get what is lit at each step
+    pub fn mode(&self) -> WalkMode {
+        self.mode
+    }
+
+    /// Set the color lit when not cycling colors
+    pub fn set_color(&mut self, color: Hub75Color<COLOR_BITS>) {
+        self.color = color;
+    }
+
+    /// Use red, green, blue and then white on successive passes
+    ///
+    /// Each LED has three dies, and one can fail alone; a white-only walk
+    /// shows such a pixel as merely tinted.
+    pub fn set_cycle_colors(&mut self, cycle: bool) {
+        self.cycle_colors = cycle;
+    }
+
+    /// Set whether the walk starts over after the last pass
+    ///
+    /// When cycling colors a walk is four passes long, otherwise one.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Set how many frames each step is shown for with [`Self::next_into`]
+    pub fn set_frames_per_step(&mut self, frames: u16) {
+        self.frames_per_step = frames.max(1);
+    }
+
+    /// Set how long each step is shown for with [`Self::next_at_into`]
+    pub fn set_step_ms(&mut self, step_ms: u32) {
+        self.step_ms = step_ms.max(1);
+    }
+
+    /// Number of steps in one pass over the panel
+    pub const fn steps(&self) -> usize {
+        match self.mode {
+            WalkMode::Pixel => WIDTH * HEIGHT,
+            WalkMode::Row => HEIGHT,
+            WalkMode::Column => WIDTH,
+        }
+    }
+
+    /// Current step: the pixel index `y * WIDTH + x`, row or column
+    ///
+    /// Log this next to what is seen on the panel to pinpoint a fault.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Coordinates of the lit pixel in [`WalkMode::Pixel`]
+    pub fn pixel(&self) -> (usize, usize) {
+        (self.position % WIDTH, self.position / WIDTH)
+    }
+
+    /// Number of passes completed
+    pub fn pass(&self) -> usize {
+        self.pass
+    }
+
+    /// Check if a non-looping walk has finished
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Go back to the first step of the first pass
+    pub fn reset(&mut self) {
+        self.position = 0;
+        self.pass = 0;
+        self.done = false;
+        self.frame_counter = 0;
+        self.step_started_ms = None;
+    }
+
+    /// Color lit on the current pass
+    pub fn current_color(&self) -> Hub75Color<COLOR_BITS> {
+        if self.cycle_colors {
+            let (r, g, b) = PASS_COLORS[self.pass % PASS_COLORS.len()];
+            Hub75Color::from_rgb8(r, g, b)
+        } else {
+            self.color
+        }
+    }
+
+    /// Move to the next step, for walks driven by a button instead of a timer
+    pub fn step_forward(&mut self) {
+        if self.done {
+            return;
+        }
+        self.position += 1;
+        if self.position < self.steps() {
+            return;
+        }
+
+        self.position = 0;
+        self.pass += 1;
+        let passes = if self.cycle_colors {
+            PASS_COLORS.len()
+        } else {
+            1
+        };
+        if self.pass % passes == 0 && !self.looping {
+            self.done = true;
+        }
+    }
+
+    /// Draw the current step, clearing everything else
+    pub fn render_into(&self, frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) {
+        frame.clear();
+        if self.done {
+            return;
+        }
+        let color = self.current_color();
+        match self.mode {
+            WalkMode::Pixel => {
+                let (x, y) = self.pixel();
+                frame.set_pixel(x, y, color).ok();
+            }
+            WalkMode::Row => frame.hline(0, self.position, WIDTH, color),
+            WalkMode::Column => frame.vline(self.position, 0, HEIGHT, color),
+        }
+    }
+
+    /// Advance by one frame, stepping every few frames
+    ///
+    /// Returns [`AnimationState::Wait`] between steps and
+    /// [`AnimationState::Done`] once a non-looping walk has finished.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        if self.done {
+            return AnimationState::Done;
+        }
+        let first = self.frame_counter == 0;
+        self.frame_counter += 1;
+        if self.frame_counter > self.frames_per_step {
+            self.frame_counter = 1;
+            self.step_forward();
+        } else if !first {
+            return AnimationState::Wait;
+        }
+        self.show(frame)
+    }
+
+    /// Advance to the given time, stepping every few milliseconds
+    ///
+    /// `now_ms` is a monotonic timestamp from the caller's clock. The first
+    /// call shows the current step; a late call moves on by a single step so
+    /// that no pixel is skipped.
+    pub fn next_at_into(
+        &mut self,
+        now_ms: u64,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        if self.done {
+            return AnimationState::Done;
+        }
+        match self.step_started_ms {
+            None => self.step_started_ms = Some(now_ms),
+            Some(started) if now_ms.saturating_sub(started) >= self.step_ms as u64 => {
+                self.step_started_ms = Some(now_ms);
+                self.step_forward();
+            }
+            Some(_) => return AnimationState::Wait,
+        }
+        self.show(frame)
+    }
+
+    /// Render the current step, or report the end of the walk
+    fn show(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        if self.done {
+            return AnimationState::Done;
+        }
+        PixelWalker::render_into(self, frame);
+        AnimationState::Rendered
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for PixelWalker<WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        PixelWalker::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        PixelWalker::render_into(self, frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit(frame: &Hub75FrameBuffer<4, 3, 6>) -> usize {
+        (0..3)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .filter(|&(x, y)| frame.get_pixel(x, y).unwrap() != Hub75Color::black())
+            .count()
+    }
+
+    #[test]
+    fn test_pixel_walk() {
+        let mut walker = PixelWalker::<4, 3, 6>::new(WalkMode::Pixel);
+        walker.set_looping(false);
+        walker.set_frames_per_step(2);
+        let mut frame = Hub75FrameBuffer::new();
+
+        assert!(matches!(
+            walker.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert!(matches!(walker.next_into(&mut frame), AnimationState::Wait));
+        assert!(matches!(
+            walker.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(walker.pixel(), (1, 0));
+        assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::white());
+        assert_eq!(lit(&frame), 1);
+
+        for _ in 0..20 {
+            walker.next_into(&mut frame);
+        }
+        assert_eq!(walker.pixel(), (3, 2));
+        for _ in 0..2 {
+            walker.next_into(&mut frame);
+        }
+        assert!(walker.is_done());
+        assert!(matches!(walker.next_into(&mut frame), AnimationState::Done));
+    }
+
+    #[test]
+    fn test_rows_and_columns_on_a_timer() {
+        let mut walker = PixelWalker::<4, 3, 6>::new(WalkMode::Column);
+        walker.set_step_ms(100);
+        let mut frame = Hub75FrameBuffer::new();
+
+        walker.next_at_into(1_000, &mut frame);
+        assert_eq!(lit(&frame), 3);
+        assert!(frame.get_pixel(0, 2).unwrap() != Hub75Color::black());
+
+        // A late call still moves by a single column
+        walker.next_at_into(1_350, &mut frame);
+        assert_eq!(walker.position(), 1);
+        assert!(frame.get_pixel(1, 2).unwrap() != Hub75Color::black());
+
+        walker.set_mode(WalkMode::Row);
+        assert_eq!(walker.steps(), 3);
+        walker.next_at_into(2_000, &mut frame);
+        assert_eq!(lit(&frame), 4);
+    }
+
+    #[test]
+    fn test_color_cycling() {
+        let mut walker = PixelWalker::<4, 3, 6>::new(WalkMode::Row);
+        walker.set_cycle_colors(true);
+        walker.set_looping(false);
+        assert_eq!(walker.current_color(), Hub75Color::red());
+
+        for _ in 0..3 {
+            walker.step_forward();
+        }
+        assert_eq!(walker.pass(), 1);
+        assert_eq!(walker.current_color(), Hub75Color::green());
+
+        for _ in 0..9 {
+            walker.step_forward();
+        }
+        assert!(walker.is_done());
+    }
+}
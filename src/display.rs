@@ -1,11 +1,59 @@
 //! Core HUB75 display driver implementation
 
-use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer, pins::Hub75Pins, Hub75Error};
+#[cfg(feature = "async")]
+use crate::{
+    animation::{Animation, AnimationEffectTrait, AnimationState},
+    spi::SpiOutput,
+};
+use crate::{
+    bitstream::{self, Bitstream},
+    buffering::{BufferStorage, DoubleBuffer},
+    color::{Hub75Color, Hub75Pixel},
+    font::{TextStyle, TextWriter},
+    frame_buffer::Hub75FrameBuffer,
+    modulation::{Bcm, Modulation},
+    pins::{AddressCapacity, AddressPins, Hub75ControlPins, Hub75Pins, Hub75RgbPins},
+    sink::FrameSink,
+    timing::{NanoClock, PlaneTimer},
+    window::Window,
+    Hub75Error,
+};
+use embedded_graphics_core::primitives::Rectangle;
 use embedded_hal::digital::OutputPin;
+#[cfg(feature = "async")]
+use embedded_hal::spi::SpiBus;
+#[cfg(feature = "async")]
 use embedded_hal_async::delay::DelayNs;
 
+/// Hardware that shifts a row of color data into the panel in place of the
+/// CLK pin
+#[cfg(feature = "async")]
+pub(crate) trait RowShifter {
+    /// Shift out one row of column pin levels, leaving the clock idle low
+    #[allow(clippy::type_complexity)]
+    fn shift_row<P: OutputPin>(
+        &mut self,
+        columns: &[(bool, bool, bool, bool, bool, bool)],
+        rgb: &mut Hub75RgbPins<P>,
+    ) -> Result<(), Hub75Error>;
+}
+
+/// A row left lit by one step of the scan
+struct LitRow {
+    /// Scan row that is lit
+    row: usize,
+    /// Subframe the row is lit for
+    bit_plane: usize,
+    /// How long to keep the row lit, in nanoseconds
+    delay_ns: u32,
+    /// Timer reading before the row was shifted out, if timed
+    shift_start: Option<u32>,
+    /// Timer reading when the row was lit, if timed
+    lit_start: Option<u32>,
+}
+
 /// Brightness levels for the display
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Brightness {
     level: u8,
@@ -18,19 +66,82 @@ impl Brightness {
     pub const MIN: Self = Self { level: 0 };
 
     /// Create a new brightness level (0-255)
-    pub fn new(level: u8) -> Self {
+    pub const fn new(level: u8) -> Self {
         Self { level }
     }
 
+    /// Create a brightness from a percentage, clamped to 100
+    ///
+    /// Like levels, percentages are perceived brightness when the display
+    /// uses a [`BrightnessCurve`] other than linear, so 50% looks half as
+    /// bright rather than driving the LEDs for half the time.
+    pub fn from_percent(percent: u8) -> Self {
+        let percent = percent.min(100) as u16;
+        Self {
+            level: ((percent * 255 + 50) / 100) as u8,
+        }
+    }
+
     /// Get the brightness level
     pub fn level(&self) -> u8 {
         self.level
     }
+
+    /// Get the brightness as a percentage, rounded to the nearest one
+    pub fn percent(&self) -> u8 {
+        ((self.level as u16 * 100 + 127) / 255) as u8
+    }
+
+    /// Scale the brightness by `numerator / denominator`, saturating at the
+    /// maximum
+    ///
+    /// A zero denominator gives the maximum brightness.
+    pub fn scale(self, numerator: u16, denominator: u16) -> Self {
+        let level = (self.level as u32 * numerator as u32)
+            .checked_div(denominator as u32)
+            .unwrap_or(u32::MAX);
+        Self {
+            level: level.min(255) as u8,
+        }
+    }
+
+    /// Get the share of on-time this brightness drives, out of 255, after
+    /// mapping it through `curve`
+    pub fn duty(&self, curve: BrightnessCurve) -> u8 {
+        curve.apply(self.level)
+    }
 }
 
 impl Default for Brightness {
     fn default() -> Self {
-        Self { level: 128 } // 50% brightness
+        BrightnessPreset::Normal.into()
+    }
+}
+
+/// Named brightness levels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BrightnessPreset {
+    /// Dark rooms and night time (12.5%)
+    Dim,
+    /// Indoor use, the display default (50%)
+    #[default]
+    Normal,
+    /// Bright rooms (75%)
+    Bright,
+    /// Full brightness, for daylight
+    Max,
+}
+
+impl From<BrightnessPreset> for Brightness {
+    fn from(preset: BrightnessPreset) -> Self {
+        let level = match preset {
+            BrightnessPreset::Dim => 32,
+            BrightnessPreset::Normal => 128,
+            BrightnessPreset::Bright => 192,
+            BrightnessPreset::Max => 255,
+        };
+        Self { level }
     }
 }
 
@@ -54,20 +165,344 @@ impl core::ops::Sub<u8> for Brightness {
     }
 }
 
+impl core::ops::Mul<u8> for Brightness {
+    type Output = Self;
+
+    fn mul(self, rhs: u8) -> Self::Output {
+        Self {
+            level: self.level.saturating_mul(rhs),
+        }
+    }
+}
+
+/// Dividing by zero gives the maximum brightness, like
+/// [`Brightness::scale`] with a zero denominator.
+impl core::ops::Div<u8> for Brightness {
+    type Output = Self;
+
+    fn div(self, rhs: u8) -> Self::Output {
+        Self {
+            level: self.level.checked_div(rhs).unwrap_or(u8::MAX),
+        }
+    }
+}
+
+/// Perceptual response curve applied to brightness and color intensity
+///
+/// LEDs respond linearly to on-time, but perceived lightness does not: with a
+/// linear mapping most of the visible change happens in the lowest levels. The
+/// curve is applied to the global [`Brightness`] and to each color channel at
+/// scan time, so application colors stay untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BrightnessCurve {
+    /// No correction, output is proportional to the input value
+    #[default]
+    Linear,
+    /// Standard gamma 2.2 correction
+    Gamma22,
+    /// CIE 1931 lightness (L*) correction
+    Cie1931,
+}
+
+impl BrightnessCurve {
+    /// Map an 8-bit level through the curve
+    pub fn apply(&self, value: u8) -> u8 {
+        match self {
+            BrightnessCurve::Linear => value,
+            BrightnessCurve::Gamma22 => GAMMA22_LUT[value as usize],
+            BrightnessCurve::Cie1931 => CIE1931_LUT[value as usize],
+        }
+    }
+
+    /// Map each channel of a color through the curve
+    ///
+    /// Channels are scaled to 8 bits, corrected and scaled back to the color's
+    /// bit depth, rounding to the nearest level.
+    pub fn apply_color<const BITS: usize, P: Hub75Pixel<BITS>>(&self, color: P) -> P {
+        if *self == BrightnessCurve::Linear {
+            return color;
+        }
+
+        let max = P::MAX_LEVEL;
+        let channel = |value: u32| {
+            let value8 = (value * 255 + max / 2) / max;
+            let corrected = self.apply(value8 as u8) as u32;
+            (corrected * max + 127) / 255
+        };
+        P::from_levels(color.levels().map(channel))
+    }
+}
+
+/// Per-channel gain used to calibrate the panel white point
+///
+/// Gains are 0.8 fixed-point values where 255 is unity (1.0) and 0 turns the
+/// channel off. Since channels cannot be driven above full on-time, balance is
+/// achieved by attenuating the channels that are too strong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ColorBalance {
+    /// Red channel gain
+    pub r_gain: u8,
+    /// Green channel gain
+    pub g_gain: u8,
+    /// Blue channel gain
+    pub b_gain: u8,
+}
+
+impl ColorBalance {
+    /// Unity gain on all channels (no correction)
+    pub const NEUTRAL: Self = Self {
+        r_gain: 255,
+        g_gain: 255,
+        b_gain: 255,
+    };
+
+    /// Create a new color balance from per-channel gains (255 = 1.0)
+    pub const fn new(r_gain: u8, g_gain: u8, b_gain: u8) -> Self {
+        Self {
+            r_gain,
+            g_gain,
+            b_gain,
+        }
+    }
+
+    /// Apply the gains to a color, rounding to the nearest level
+    pub fn apply<const BITS: usize, P: Hub75Pixel<BITS>>(&self, color: P) -> P {
+        if *self == Self::NEUTRAL {
+            return color;
+        }
+
+        let [r, g, b] = color.levels();
+        let scale = |value: u32, gain: u8| (value * gain as u32 + 127) / 255;
+        P::from_levels([
+            scale(r, self.r_gain),
+            scale(g, self.g_gain),
+            scale(b, self.b_gain),
+        ])
+    }
+}
+
+impl Default for ColorBalance {
+    fn default() -> Self {
+        Self::NEUTRAL
+    }
+}
+
+/// Number of scan rows addressable with the A-E address pins
+pub const MAX_SCAN_ROWS: usize = 32;
+
+/// Number of chained panels that can be calibrated individually
+pub const MAX_CHAINED_PANELS: usize = 8;
+
+/// Number of brightness zones a display can hold
+pub const MAX_BRIGHTNESS_ZONES: usize = 4;
+
+/// Rectangular area of the display with its own brightness
+///
+/// The zone's brightness multiplies the pixels inside it at scan time, on
+/// top of the global brightness, so drawn content is left untouched. Where
+/// zones overlap their brightnesses are multiplied together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BrightnessZone {
+    /// Area of the display covered
+    pub area: Rectangle,
+    /// Multiplier for the area, [`Brightness::MAX`] leaves it unchanged
+    pub brightness: Brightness,
+}
+
+impl BrightnessZone {
+    /// Create a zone over `area` at `brightness`
+    pub fn new(area: Rectangle, brightness: impl Into<Brightness>) -> Self {
+        Self {
+            area,
+            brightness: brightness.into(),
+        }
+    }
+
+    /// Check whether the zone covers a pixel
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        let (x, y) = (x as i64, y as i64);
+        let left = self.area.top_left.x as i64;
+        let top = self.area.top_left.y as i64;
+        x >= left
+            && y >= top
+            && x < left + self.area.size.width as i64
+            && y < top + self.area.size.height as i64
+    }
+}
+
+// `Rectangle` does not implement `defmt::Format`, so the area is spelled out
+#[cfg(feature = "defmt")]
+impl defmt::Format for BrightnessZone {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "BrightnessZone {{ area: ({}, {}) {}x{}, brightness: {} }}",
+            self.area.top_left.x,
+            self.area.top_left.y,
+            self.area.size.width,
+            self.area.size.height,
+            self.brightness
+        )
+    }
+}
+
+/// Global tint applied to the whole display at scan time
+///
+/// Tint colors are given as 8-bit RGB values and scaled to the display's color
+/// depth. Changing the tint takes effect on the next refreshed frame without
+/// re-rendering any content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Tint {
+    /// No tint
+    #[default]
+    None,
+    /// Multiply every pixel by a color (e.g. red for a night mode)
+    ///
+    /// Each channel is scaled by `channel / 255`, so white leaves content unchanged.
+    Multiply {
+        /// Red multiplier (255 = unchanged)
+        r: u8,
+        /// Green multiplier (255 = unchanged)
+        g: u8,
+        /// Blue multiplier (255 = unchanged)
+        b: u8,
+    },
+    /// Blend every pixel toward a color (e.g. flash white on alert)
+    Overlay {
+        /// Red component of the overlay color
+        r: u8,
+        /// Green component of the overlay color
+        g: u8,
+        /// Blue component of the overlay color
+        b: u8,
+        /// Blend amount (0 = content only, 255 = overlay color only)
+        amount: u8,
+    },
+}
+
+impl Tint {
+    /// Apply the tint to a color
+    pub fn apply<const BITS: usize, P: Hub75Pixel<BITS>>(&self, color: P) -> P {
+        let [red, green, blue] = color.levels();
+        match *self {
+            Tint::None => color,
+            Tint::Multiply { r, g, b } => {
+                let scale = |value: u32, factor: u8| (value * factor as u32 + 127) / 255;
+                P::from_levels([scale(red, r), scale(green, g), scale(blue, b)])
+            }
+            Tint::Overlay { r, g, b, amount } => {
+                let [target_r, target_g, target_b] = P::from_rgb8(r, g, b).levels();
+                let amount = amount as u32;
+                let blend = |value: u32, target: u32| {
+                    (value * (255 - amount) + target * amount + 127) / 255
+                };
+                P::from_levels([
+                    blend(red, target_r),
+                    blend(green, target_g),
+                    blend(blue, target_b),
+                ])
+            }
+        }
+    }
+}
+
+/// How the rows and bit planes of a frame are interleaved
+///
+/// A frame lights every row once in every bit plane. Showing all rows of one
+/// plane before the next leaves each row dark for long stretches while the
+/// other planes are scanned, which cameras and fast eye movements pick up as
+/// rolling bands. Spreading a row's planes over the frame avoids this.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ScanOrder {
+    /// All rows of one bit plane, then all rows of the next
+    #[default]
+    PlaneMajor,
+    /// All bit planes of one row, then all bit planes of the next
+    RowMajor,
+    /// Passes over all rows, with each row one bit plane further on than the
+    /// row before, so that neighbouring rows never show the same plane
+    Interleaved,
+}
+
+/// Order in which the bit planes of a frame are shown
+///
+/// The order does not change brightness, only when in the frame each plane
+/// is lit, which can matter on camera or against a particular refresh rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PlaneOrder {
+    /// Least significant (shortest) plane first
+    #[default]
+    LsbFirst,
+    /// Most significant (longest) plane first
+    MsbFirst,
+}
+
+impl PlaneOrder {
+    /// Get the subframe shown `index`-th out of `subframes`
+    #[inline(always)]
+    fn subframe(&self, index: usize, subframes: usize) -> usize {
+        match self {
+            PlaneOrder::LsbFirst => index,
+            PlaneOrder::MsbFirst => subframes - 1 - index,
+        }
+    }
+}
+
+/// How the color channels drive the RGB data pins
+///
+/// Monochrome message boards only need a single intensity per pixel. In the
+/// monochrome modes only the red channel of the front buffer is read, so
+/// content can be drawn in red (or converted from a
+/// [`MonoFrameBuffer`](crate::mono::MonoFrameBuffer)) and the green and blue
+/// channels are skipped entirely during scan-out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ColorMode {
+    /// Each channel drives its own data pin
+    #[default]
+    Rgb,
+    /// The red channel drives all three data pins (white or single-color panels)
+    Monochrome,
+    /// The red channel drives the red data pins, green and blue are held low
+    RedOnly,
+}
+
+impl ColorMode {
+    /// Map the channel bits of a pixel to the data pin levels
+    #[inline(always)]
+    fn pin_levels(&self, (r, g, b): (bool, bool, bool)) -> (bool, bool, bool) {
+        match self {
+            ColorMode::Rgb => (r, g, b),
+            ColorMode::Monochrome => (r, r, r),
+            ColorMode::RedOnly => (r, false, false),
+        }
+    }
+}
+
 /// Main HUB75 display driver with configurable dimensions and color depth
 ///
 /// This is the core driver for HUB75 RGB LED matrix displays. It provides:
-/// - Binary Code Modulation (BCM) for high color depth
+/// - Binary Code Modulation (BCM) for high color depth, or another
+///   [`Modulation`] scheme
 /// - Double buffering for smooth animations
 /// - Configurable refresh rates and brightness
 /// - embedded-graphics integration
 ///
 /// # Type Parameters
 ///
-/// - `P`: Pin type implementing `OutputPin` (e.g., `embassy_rp::gpio::Output`)
+/// - `P`: Pin type implementing `OutputPin` (e.g., `embassy_rp::gpio::Output`),
+///   used for the RGB pins
 /// - `WIDTH`: Display width in pixels (e.g., 64)
 /// - `HEIGHT`: Display height in pixels (e.g., 32)
 /// - `COLOR_BITS`: Color depth in bits per channel (typically 4, 6, or 8)
+/// - `B`: Frame buffer storage, [`DoubleBuffer`] by default (see [`crate::buffering`])
+/// - `A`, `C`: Pin types of the address and control pins, `P` by default (see
+///   [`Hub75Pins`] for mixing pin types)
 ///
 /// # Examples
 ///
@@ -88,41 +523,142 @@ impl core::ops::Sub<u8> for Brightness {
 /// # }
 /// ```
 ///
+/// # Configuration Checks
+///
+/// `COLOR_BITS` must be between 1 and the bits of a pixel channel, `WIDTH` must
+/// not be 0, and `HEIGHT` must be even, not 0 and at most twice
+/// [`MAX_SCAN_ROWS`]. Anything else fails to compile when the display is
+/// created:
+///
+/// ```rust,compile_fail
+/// # use hub75::{Hub75Display, Hub75Pins};
+/// # struct Pin;
+/// # impl embedded_hal::digital::ErrorType for Pin { type Error = core::convert::Infallible; }
+/// # impl embedded_hal::digital::OutputPin for Pin {
+/// #     fn set_low(&mut self) -> Result<(), Self::Error> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Self::Error> { Ok(()) }
+/// # }
+/// # fn pins() -> Hub75Pins<Pin> { unimplemented!() }
+/// // A scan row drives two pixel rows, so HEIGHT must be even
+/// let display = Hub75Display::<_, 64, 31, 6>::new(pins());
+/// ```
+///
 /// # Memory Usage
 ///
-/// The display uses two frame buffers (front and back) when double buffering is enabled.
-/// Memory usage per buffer: `WIDTH * HEIGHT * COLOR_BITS / 8` bytes.
+/// With the default [`DoubleBuffer`] storage the display holds two frame buffers
+/// (front and back). Each buffer stores three channel bytes per pixel whatever
+/// the color depth up to 8 bits, so a 64x32 display takes 6,144 bytes per
+/// buffer;
+/// [`memory::frame_buffer_bytes`](crate::memory::frame_buffer_bytes) and
+/// [`memory::display_buffer_bytes`](crate::memory::display_buffer_bytes) give
+/// the exact sizes for a configuration.
+///
+/// On RAM-constrained targets use [`SingleBuffer`](crate::buffering::SingleBuffer)
+/// storage to keep only one buffer, accepting possible tearing.
 ///
-/// For a 64x32 display with 6-bit color: `64 * 32 * 6 / 8 = 1,536 bytes` per buffer.
+/// # Color Depths Above 8 Bits
+///
+/// Naming [`Hub75Color16`](crate::Hub75Color16) as the pixel type of the
+/// storage allows depths of up to 16 bits, at twice the buffer RAM. Pixels are
+/// then set as `Hub75Color16`; drawing text, windows and embedded-graphics
+/// need the default [`Hub75Color`] pixels.
+///
+/// ```rust,no_run
+/// use hub75::{buffering::DoubleBuffer, Hub75Color16, Hub75Display, Hub75Pins};
+///
+/// # fn example(pins: Hub75Pins<impl embedded_hal::digital::OutputPin + 'static>) -> Result<(), hub75::Hub75Error> {
+/// let mut display = Hub75Display::<_, 64, 32, 12, DoubleBuffer<64, 32, 12, Hub75Color16<12>>>::new(pins)?;
+/// display.set_pixel(0, 0, Hub75Color16::new(4095, 2048, 0))?;
+/// # Ok(())
+/// # }
+/// ```
 pub struct Hub75Display<
     P: OutputPin + 'static,
     const WIDTH: usize,
     const HEIGHT: usize,
     const COLOR_BITS: usize,
+    B = DoubleBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    A: OutputPin + 'static = P,
+    C: OutputPin + 'static = P,
 > {
     /// Pin configuration
-    pins: Hub75Pins<P>,
-    /// Front frame buffer (currently being displayed)
-    front_buffer: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
-    /// Back frame buffer (for double buffering)
-    back_buffer: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    pins: Hub75Pins<P, A, C>,
+    /// Frame buffer storage (front buffer and optional back buffer)
+    buffers: B,
     /// Current row being scanned
     current_row: usize,
-    /// Current bit plane being displayed
+    /// Current subframe being displayed, the bit plane under BCM
     current_bit_plane: usize,
+    /// Position of the next `render_step` within the frame
+    scan_step: usize,
+    /// Order in which the bit planes are shown
+    plane_order: PlaneOrder,
+    /// How rows and bit planes are interleaved
+    scan_order: ScanOrder,
     /// Display brightness
     brightness: Brightness,
     /// Base refresh interval in nanoseconds
     refresh_interval_ns: u32,
     /// Whether double buffering is enabled
     double_buffering: bool,
+    /// Perceptual curve for brightness and color intensity
+    brightness_curve: BrightnessCurve,
+    /// Per-channel white balance gains
+    color_balance: ColorBalance,
+    /// Global tint applied to displayed content
+    tint: Tint,
+    /// Temporal dithering (frame rate control) of scan-time color correction
+    temporal_dithering: bool,
+    /// Number of frames rendered, used to sequence temporal dithering
+    frame_count: u8,
+    /// How color channels drive the data pins
+    color_mode: ColorMode,
+    /// Whether the front buffer was swapped in and not yet rendered
+    swap_pending: bool,
+    /// Frames swapped out before they were ever rendered
+    dropped_frames: u32,
+    /// Areas dimmed or brightened independently at scan time
+    zones: heapless::Vec<BrightnessZone, MAX_BRIGHTNESS_ZONES>,
+    /// Width of each chained panel in pixels
+    panel_width: usize,
+    /// Gains of each chained panel, from the input connector onwards
+    panel_balance: [ColorBalance; MAX_CHAINED_PANELS],
+    /// On-time of each scan row (255 = 1.0)
+    row_gains: [u8; MAX_SCAN_ROWS],
+    /// How color levels are turned into on-times
+    modulation: &'static (dyn Modulation + Sync),
+    /// Time subtracted from every plane delay to make up for overhead
+    timing_overhead_ns: u32,
 }
 
-impl<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
-    Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS>
+impl<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, B, A, C>
+    Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>
 where
     P: OutputPin,
+    A: OutputPin,
+    C: OutputPin,
+    B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS>,
 {
+    /// Number of bytes written by [`capture`](Self::capture)
+    pub const CAPTURE_BYTES: usize = WIDTH * HEIGHT * 3;
+
+    /// Fails to compile when the panel configuration cannot be driven
+    const CONFIG_VALID: () = {
+        assert!(COLOR_BITS >= 1, "COLOR_BITS must be at least 1");
+        // Fails for depths the pixel type cannot store
+        assert!(B::Pixel::MAX_LEVEL >= 1);
+        assert!(WIDTH > 0, "WIDTH must not be 0");
+        assert!(HEIGHT > 0, "HEIGHT must not be 0");
+        assert!(
+            HEIGHT % 2 == 0,
+            "HEIGHT must be even, each scan row drives two pixel rows"
+        );
+        assert!(
+            HEIGHT / 2 <= MAX_SCAN_ROWS,
+            "HEIGHT / 2 exceeds the 32 scan rows five address pins can select"
+        );
+    };
+
     /// Create a new HUB75 display driver
     ///
     /// Initializes the display with the provided pin configuration. The pins are
@@ -164,7 +700,10 @@ where
     /// # Ok(())
     /// # }
     /// ```
-    pub fn new(mut pins: Hub75Pins<P>) -> Result<Self, Hub75Error> {
+    pub fn new(mut pins: Hub75Pins<P, A, C>) -> Result<Self, Hub75Error> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CONFIG_VALID;
+
         // Initialize pins to default state
         pins.init()?;
 
@@ -176,153 +715,1069 @@ where
 
         Ok(Self {
             pins,
-            front_buffer: Hub75FrameBuffer::new(),
-            back_buffer: Hub75FrameBuffer::new(),
+            buffers: B::new(),
             current_row: 0,
             current_bit_plane: 0,
+            scan_step: 0,
+            plane_order: PlaneOrder::default(),
+            scan_order: ScanOrder::default(),
             brightness: Brightness::default(),
             refresh_interval_ns: 100_000, // 100 microseconds = 10kHz base refresh rate
             double_buffering: false,
+            brightness_curve: BrightnessCurve::default(),
+            color_balance: ColorBalance::default(),
+            tint: Tint::default(),
+            temporal_dithering: false,
+            frame_count: 0,
+            color_mode: ColorMode::default(),
+            swap_pending: false,
+            dropped_frames: 0,
+            zones: heapless::Vec::new(),
+            panel_width: WIDTH,
+            panel_balance: [ColorBalance::NEUTRAL; MAX_CHAINED_PANELS],
+            row_gains: [255; MAX_SCAN_ROWS],
+            modulation: &Bcm,
+            timing_overhead_ns: 0,
+        })
+    }
+
+    /// Create a new HUB75 display driver, checking at compile time that the
+    /// address pins can select every scan row
+    ///
+    /// Works like [`new`](Self::new), but with the address pin count carried
+    /// by [`AddressPins`], a panel too tall for the pins fails to compile
+    /// instead of returning [`Hub75Error::InvalidCoordinates`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hub75::{AddressPins, Hub75ControlPins, Hub75Display, Hub75RgbPins};
+    /// use embedded_hal::digital::OutputPin;
+    ///
+    /// # fn example<P: OutputPin + Clone + 'static>(pin: P) -> Result<(), hub75::Hub75Error> {
+    /// let rgb = Hub75RgbPins {
+    ///     r1: pin.clone(), g1: pin.clone(), b1: pin.clone(),
+    ///     r2: pin.clone(), g2: pin.clone(), b2: pin.clone(),
+    /// };
+    /// let address = AddressPins::abcde(pin.clone(), pin.clone(), pin.clone(), pin.clone(), pin.clone());
+    /// let control = Hub75ControlPins { clk: pin.clone(), lat: pin.clone(), oe: pin };
+    ///
+    /// let display = Hub75Display::<_, 64, 64, 6>::new_checked(rgb, address, control)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// With only four address pins, the 32 scan rows of a 64x64 panel are out
+    /// of reach:
+    ///
+    /// ```rust,compile_fail
+    /// # use hub75::{AddressPins, Hub75ControlPins, Hub75Display, Hub75RgbPins};
+    /// # #[derive(Clone)]
+    /// # struct Pin;
+    /// # impl embedded_hal::digital::ErrorType for Pin { type Error = core::convert::Infallible; }
+    /// # impl embedded_hal::digital::OutputPin for Pin {
+    /// #     fn set_low(&mut self) -> Result<(), Self::Error> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Self::Error> { Ok(()) }
+    /// # }
+    /// # let rgb = Hub75RgbPins { r1: Pin, g1: Pin, b1: Pin, r2: Pin, g2: Pin, b2: Pin };
+    /// # let control = Hub75ControlPins { clk: Pin, lat: Pin, oe: Pin };
+    /// let address = AddressPins::abcd(Pin, Pin, Pin, Pin);
+    /// let display = Hub75Display::<_, 64, 64, 6>::new_checked(rgb, address, control);
+    /// ```
+    pub fn new_checked<const ADDRESS_PINS: usize>(
+        rgb: Hub75RgbPins<P>,
+        address: AddressPins<A, ADDRESS_PINS>,
+        control: Hub75ControlPins<C>,
+    ) -> Result<Self, Hub75Error> {
+        #[allow(clippy::let_unit_value)]
+        let () = AddressCapacity::<ADDRESS_PINS, HEIGHT>::CHECK;
+
+        Self::new(Hub75Pins {
+            rgb,
+            address: address.into_inner(),
+            control,
         })
     }
 
     /// Enable or disable double buffering
+    ///
+    /// Has no effect when the buffer storage has no back buffer.
     pub fn set_double_buffering(&mut self, enabled: bool) {
         self.double_buffering = enabled;
     }
 
+    /// Check whether drawing goes to a separate back buffer
+    pub fn is_double_buffered(&self) -> bool {
+        self.double_buffering && B::HAS_BACK_BUFFER
+    }
+
     /// Swap front and back buffers (for double buffering)
     pub fn swap_buffers(&mut self) {
-        if self.double_buffering {
-            self.front_buffer.swap(&mut self.back_buffer);
+        if self.is_double_buffered() {
+            self.swap();
+        }
+    }
+
+    /// Swap front and back buffers only if the back buffer content differs
+    ///
+    /// Returns `true` if the buffers were swapped. This compares the pixels of
+    /// both buffers, stopping at the first difference, so callers can redraw
+    /// unconditionally and still skip presenting identical frames. Always
+    /// returns `false` when double buffering is disabled.
+    pub fn swap_buffers_if_changed(&mut self) -> bool {
+        if !self.is_double_buffered() {
+            return false;
+        }
+        if self.buffers.back() == self.buffers.front() {
+            return false;
+        }
+        self.swap();
+        true
+    }
+
+    /// Get the number of frames swapped out again before they were rendered
+    ///
+    /// A frame is dropped when the buffers are swapped twice without a
+    /// [`render_frame`](Self::render_frame) in between, which means content is
+    /// produced faster than the panel refreshes.
+    pub fn dropped_frames(&self) -> u32 {
+        self.dropped_frames
+    }
+
+    /// Swap the buffers, counting the front buffer as dropped if it was
+    /// never rendered
+    fn swap(&mut self) {
+        if self.swap_pending {
+            self.dropped_frames = self.dropped_frames.wrapping_add(1);
+            crate::trace_event!("hub75: frame dropped ({=u32} total)", self.dropped_frames);
         }
+        self.buffers.swap();
+        self.swap_pending = true;
+        crate::trace_event!("hub75: buffers swapped");
     }
 
     /// Get a reference to the back buffer for drawing
-    pub fn back_buffer(&mut self) -> &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS> {
-        if self.double_buffering {
-            &mut self.back_buffer
+    pub fn back_buffer(&mut self) -> &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, B::Pixel> {
+        if self.is_double_buffered() {
+            self.buffers.back_mut()
         } else {
-            &mut self.front_buffer
+            self.buffers.front_mut()
         }
     }
 
     /// Get a reference to the front buffer (currently displayed)
-    pub fn front_buffer(&self) -> &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS> {
-        &self.front_buffer
-    }
-
-    /// Set the display brightness
-    pub fn set_brightness(&mut self, brightness: Brightness) {
-        self.brightness = brightness;
+    pub fn front_buffer(&self) -> &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, B::Pixel> {
+        self.buffers.front()
     }
 
-    /// Get the current brightness
-    pub fn brightness(&self) -> Brightness {
-        self.brightness
+    /// Run `f` on the back buffer, returning its result
+    ///
+    /// Same buffer as [`back_buffer`](Self::back_buffer): the front buffer
+    /// when double buffering is off, so drawing shows from the next row
+    /// scanned. The display is borrowed while `f` runs, so no frame is
+    /// rendered in the meantime.
+    pub fn with_back_buffer<R>(
+        &mut self,
+        f: impl FnOnce(&mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, B::Pixel>) -> R,
+    ) -> R {
+        f(self.back_buffer())
     }
 
-    /// Set the base refresh interval in nanoseconds
-    pub fn set_refresh_interval_ns(&mut self, interval_ns: u32) {
-        self.refresh_interval_ns = interval_ns;
+    /// Run `f` on the front buffer, returning its result
+    ///
+    /// The front buffer only changes through the display, so for as long as
+    /// `f` runs it holds exactly the pixels being shown and cannot be swapped
+    /// or drawn into, which makes this the place to copy out a screenshot.
+    /// From another task or priority level, call it with the access that
+    /// context has to the display: inside a mutex or RTIC resource lock, or
+    /// through a [`Hub75Handle`](crate::shared::Hub75Handle) with
+    /// [`request_inspect`](crate::shared::Hub75Handle::request_inspect),
+    /// which has the refresh task run the closure between frames.
+    pub fn inspect_front<R>(
+        &self,
+        f: impl FnOnce(&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, B::Pixel>) -> R,
+    ) -> R {
+        f(self.buffers.front())
     }
 
-    /// Clear the display (set all pixels to black)
-    pub fn clear(&mut self) {
-        self.back_buffer().clear();
-        if !self.double_buffering {
-            self.front_buffer.clear();
+    /// Copy the front buffer into `rgb` as RGB888, returning the number of
+    /// bytes written
+    ///
+    /// Pixels are written row by row, three bytes each, with every channel
+    /// scaled to 8 bits. This is the content as drawn, before scan-time
+    /// brightness and color correction. Returns
+    /// [`Hub75Error::BufferOverflow`] if `rgb` is shorter than
+    /// [`CAPTURE_BYTES`](Self::CAPTURE_BYTES).
+    pub fn capture(&self, rgb: &mut [u8]) -> Result<usize, Hub75Error> {
+        let rgb = rgb
+            .get_mut(..Self::CAPTURE_BYTES)
+            .ok_or(Hub75Error::BufferOverflow)?;
+        let front = self.buffers.front();
+        for (y, line) in rgb.chunks_exact_mut(WIDTH * 3).enumerate() {
+            for (color, bytes) in front.get_row(y)?.iter().zip(line.chunks_exact_mut(3)) {
+                let (r, g, b) = color.to_rgb8();
+                bytes.copy_from_slice(&[r, g, b]);
+            }
         }
+        Ok(Self::CAPTURE_BYTES)
     }
 
-    /// Set a pixel at the specified coordinates
-    pub fn set_pixel(
-        &mut self,
-        x: usize,
-        y: usize,
-        color: Hub75Color<COLOR_BITS>,
-    ) -> Result<(), Hub75Error> {
-        self.back_buffer().set_pixel(x, y, color)
+    /// Write the front buffer as a PNG with each pixel drawn `scale` pixels
+    /// wide
+    ///
+    /// Like [`capture`](Self::capture), shows the content as drawn. With a
+    /// `scale` of 3 or more the LEDs are separated by a dark gap, as on the
+    /// panel. Needs the `std` feature.
+    #[cfg(any(test, feature = "std"))]
+    pub fn capture_png(&self, writer: impl std::io::Write, scale: usize) -> std::io::Result<()> {
+        let mut rgb = std::vec![0; Self::CAPTURE_BYTES];
+        // Cannot fail, the buffer is exactly large enough
+        let _ = self.capture(&mut rgb);
+        crate::png::write_leds_png(writer, WIDTH, HEIGHT, &rgb, scale)
     }
 
-    /// Get a pixel at the specified coordinates
-    pub fn get_pixel(&self, x: usize, y: usize) -> Result<Hub75Color<COLOR_BITS>, Hub75Error> {
-        self.front_buffer().get_pixel(x, y)
+    /// Set the display brightness from a level or a [`BrightnessPreset`]
+    pub fn set_brightness(&mut self, brightness: impl Into<Brightness>) {
+        self.brightness = brightness.into();
     }
 
-    /// Fill the display with a single color
-    pub fn fill(&mut self, color: Hub75Color<COLOR_BITS>) {
-        self.back_buffer().fill(color);
+    /// Get the current brightness
+    pub fn brightness(&self) -> Brightness {
+        self.brightness
     }
 
-    /// Render a single bit plane for the current row
-    pub fn render_bit_plane(&mut self) -> Result<(), Hub75Error> {
-        // Disable output during data loading
-        self.pins.control.disable_output()?;
+    /// Set the perceptual curve used for brightness and color intensity
+    pub fn set_brightness_curve(&mut self, curve: BrightnessCurve) {
+        self.brightness_curve = curve;
+    }
 
-        // Set row address
-        self.pins.address.set_address(self.current_row)?;
+    /// Get the current brightness curve
+    pub fn brightness_curve(&self) -> BrightnessCurve {
+        self.brightness_curve
+    }
 
-        // Get bit plane data for current row
-        let bit_data = self
-            .front_buffer
-            .get_row_bit_plane(self.current_row, self.current_bit_plane)?;
+    /// Set per-channel white balance gains (0.8 fixed-point, 255 = 1.0)
+    ///
+    /// The gains are applied at scan time, so panels with a color cast can be
+    /// calibrated to a neutral white without changing application colors.
+    pub fn set_color_balance(&mut self, r_gain: u8, g_gain: u8, b_gain: u8) {
+        self.color_balance = ColorBalance::new(r_gain, g_gain, b_gain);
+    }
 
-        // Shift out RGB data for all columns
-        for &(upper_r, upper_g, upper_b, lower_r, lower_g, lower_b) in &bit_data {
-            // Set RGB pins
-            self.pins
-                .rgb
-                .set_rgb(upper_r, upper_g, upper_b, lower_r, lower_g, lower_b)?;
+    /// Get the current white balance gains
+    pub fn color_balance(&self) -> ColorBalance {
+        self.color_balance
+    }
 
-            // Clock pulse to shift data
-            self.pins.control.clock_pulse()?;
+    /// Set the width of each panel in a horizontal chain
+    ///
+    /// The display width must be a whole number of panels, at most
+    /// [`MAX_CHAINED_PANELS`]; otherwise [`Hub75Error::InvalidCoordinates`]
+    /// is returned. Resets the per-panel gains.
+    pub fn set_panel_width(&mut self, panel_width: usize) -> Result<(), Hub75Error> {
+        if panel_width == 0 || WIDTH % panel_width != 0 || WIDTH / panel_width > MAX_CHAINED_PANELS
+        {
+            return Err(Hub75Error::InvalidCoordinates);
         }
+        self.panel_width = panel_width;
+        self.clear_panel_balance();
+        Ok(())
+    }
 
-        // Latch the data
-        self.pins.control.latch_pulse()?;
-
-        // Enable output
-        self.pins.control.enable_output()?;
+    /// Get the number of chained panels
+    pub fn panel_count(&self) -> usize {
+        WIDTH / self.panel_width
+    }
 
+    /// Set the gains of one chained panel (0.8 fixed-point, 255 = 1.0)
+    ///
+    /// Panel 0 is the leftmost. The gains are applied at scan time on top of
+    /// the [color balance](Self::set_color_balance), so panels from different
+    /// batches can be matched and the seams between them disappear. Returns
+    /// [`Hub75Error::InvalidCoordinates`] for a panel outside the chain.
+    pub fn set_panel_balance(
+        &mut self,
+        panel: usize,
+        balance: ColorBalance,
+    ) -> Result<(), Hub75Error> {
+        if panel >= self.panel_count() {
+            return Err(Hub75Error::InvalidCoordinates);
+        }
+        self.panel_balance[panel] = balance;
         Ok(())
     }
 
-    /// Render a complete frame using Binary Code Modulation
-    pub async fn render_frame(&mut self, delay: &mut impl DelayNs) -> Result<(), Hub75Error> {
-        
-        // Prevents ghosting
-        // https://github.com/david-sawatzke/hub75-rs/blob/ba1eaa6de065909bba5776557e7986229df54863/src/lib.rs#L296
-        delay.delay_us(2).await;
-        self.pins.control.lat.set_low().unwrap();
-        delay.delay_us(2).await;
-        self.pins.control.lat.set_high().unwrap();
+    /// Get the gains of one chained panel
+    pub fn panel_balance(&self, panel: usize) -> Option<ColorBalance> {
+        self.panel_balance[..self.panel_count()].get(panel).copied()
+    }
 
-        for bit_plane in 0..COLOR_BITS {
-            for row in 0..(HEIGHT / 2) {
-                self.current_row = row;
-                self.current_bit_plane = bit_plane;
+    /// Reset the gains of all chained panels to neutral
+    pub fn clear_panel_balance(&mut self) {
+        self.panel_balance = [ColorBalance::NEUTRAL; MAX_CHAINED_PANELS];
+    }
 
-                self.render_bit_plane()?;
+    /// Set a per-row intensity compensation table (0.8 fixed-point, 255 = 1.0)
+    ///
+    /// Holds one gain per scan row, `HEIGHT / 2` in all, each lighting the
+    /// row and its partner in the lower half. The gains scale how long each
+    /// row is lit in every bit plane, so they cost no color depth. Since a
+    /// row cannot be lit for longer than its slot, compensate row-dependent
+    /// brightness by dimming the brighter rows to match the dimmest. Returns
+    /// [`Hub75Error::InvalidCoordinates`] if the table has the wrong length.
+    pub fn set_row_compensation(&mut self, gains: &[u8]) -> Result<(), Hub75Error> {
+        if gains.len() != HEIGHT / 2 {
+            return Err(Hub75Error::InvalidCoordinates);
+        }
+        self.row_gains[..HEIGHT / 2].copy_from_slice(gains);
+        Ok(())
+    }
 
-                // BCM timing - exponentially longer delays for higher bit planes
-                let bit_duration_ns = self.refresh_interval_ns * (1 << bit_plane);
+    /// Get the per-row intensity compensation table
+    pub fn row_compensation(&self) -> &[u8] {
+        &self.row_gains[..HEIGHT / 2]
+    }
 
-                // Apply brightness scaling
-                let brightness_factor = self.brightness.level() as u32;
-                let scaled_duration_ns = bit_duration_ns * brightness_factor / 255;
+    /// Reset the per-row intensity compensation to unity gain
+    pub fn clear_row_compensation(&mut self) {
+        self.row_gains = [255; MAX_SCAN_ROWS];
+    }
 
-                delay.delay_ns(scaled_duration_ns).await;
+    /// Set a global tint applied to everything shown on the display
+    ///
+    /// The tint is applied at scan time, so the overall color cast can be
+    /// changed (or flashed) without re-rendering the frame buffers.
+    pub fn set_tint(&mut self, tint: Tint) {
+        self.tint = tint;
+    }
 
-                // Disable output before moving to next row/bit plane
-                self.pins.control.disable_output().ok();
-            }
-        }
+    /// Get the current global tint
+    pub fn tint(&self) -> Tint {
+        self.tint
+    }
 
+    /// Add a zone with its own brightness, returning its index
+    ///
+    /// Returns [`Hub75Error::BufferOverflow`] if the display already has
+    /// [`MAX_BRIGHTNESS_ZONES`] zones.
+    pub fn add_brightness_zone(&mut self, zone: BrightnessZone) -> Result<usize, Hub75Error> {
+        self.zones
+            .push(zone)
+            .map_err(|_| Hub75Error::BufferOverflow)?;
+        Ok(self.zones.len() - 1)
+    }
+
+    /// Change the brightness of a zone
+    pub fn set_zone_brightness(
+        &mut self,
+        index: usize,
+        brightness: impl Into<Brightness>,
+    ) -> Result<(), Hub75Error> {
+        let zone = self
+            .zones
+            .get_mut(index)
+            .ok_or(Hub75Error::InvalidCoordinates)?;
+        zone.brightness = brightness.into();
+        Ok(())
+    }
+
+    /// Get the brightness zones
+    pub fn brightness_zones(&self) -> &[BrightnessZone] {
+        &self.zones
+    }
+
+    /// Remove all brightness zones
+    pub fn clear_brightness_zones(&mut self) {
+        self.zones.clear();
+    }
+
+    /// Enable or disable temporal dithering (frame rate control)
+    ///
+    /// Scan-time color correction (brightness curve, color balance and tint) is
+    /// computed at 8-bit precision. Without dithering the result is rounded to
+    /// the nearest displayable level, which bands badly at 4-6 bits. With
+    /// dithering enabled each channel alternates between the two adjacent
+    /// levels over a 4-frame cycle, adding about 2 bits of perceived depth
+    /// without lengthening the BCM period. Neighbouring pixels are offset in
+    /// the cycle to keep flicker from being visible.
+    ///
+    /// Has no effect while no scan-time correction is active, since frame
+    /// buffer content is already exactly representable.
+    pub fn set_temporal_dithering(&mut self, enabled: bool) {
+        self.temporal_dithering = enabled;
+    }
+
+    /// Check whether temporal dithering is enabled
+    pub fn temporal_dithering(&self) -> bool {
+        self.temporal_dithering
+    }
+
+    /// Set how the color channels drive the RGB data pins
+    pub fn set_color_mode(&mut self, mode: ColorMode) {
+        self.color_mode = mode;
+    }
+
+    /// Get the current color mode
+    pub fn color_mode(&self) -> ColorMode {
+        self.color_mode
+    }
+
+    /// Set how color levels are turned into on-times
+    ///
+    /// Binary Code Modulation ([`Bcm`]) is the default. See the
+    /// [`modulation`](crate::modulation) module for the trade-offs of the
+    /// other schemes. Restarts the frame being stepped through by
+    /// [`render_step`](Self::render_step).
+    pub fn set_modulation(&mut self, modulation: &'static (dyn Modulation + Sync)) {
+        self.modulation = modulation;
+        self.scan_step = 0;
+    }
+
+    /// Get the modulation scheme
+    pub fn modulation(&self) -> &'static (dyn Modulation + Sync) {
+        self.modulation
+    }
+
+    /// Set the order in which the bit planes of a frame are shown
+    ///
+    /// Restarts the frame being stepped through by
+    /// [`render_step`](Self::render_step).
+    pub fn set_plane_order(&mut self, order: PlaneOrder) {
+        self.plane_order = order;
+        self.scan_step = 0;
+    }
+
+    /// Get the order in which the bit planes are shown
+    pub fn plane_order(&self) -> PlaneOrder {
+        self.plane_order
+    }
+
+    /// Set how the rows and bit planes of a frame are interleaved
+    ///
+    /// Restarts the frame being stepped through by
+    /// [`render_step`](Self::render_step).
+    pub fn set_scan_order(&mut self, order: ScanOrder) {
+        self.scan_order = order;
+        self.scan_step = 0;
+    }
+
+    /// Get how the rows and bit planes are interleaved
+    pub fn scan_order(&self) -> ScanOrder {
+        self.scan_order
+    }
+
+    /// Get the number of subframes each row is shown in per frame
+    pub fn subframes(&self) -> usize {
+        self.modulation.subframes(COLOR_BITS)
+    }
+
+    /// Set the base refresh interval in nanoseconds
+    pub fn set_refresh_interval_ns(&mut self, interval_ns: u32) {
+        self.refresh_interval_ns = interval_ns;
+    }
+
+    /// Set the time subtracted from every plane delay, in nanoseconds
+    ///
+    /// Makes up for a row staying lit longer than its delay, by the time the
+    /// delay overshoots and the time taken to turn the output off. Planes
+    /// shorter than the overhead get no delay at all.
+    pub fn set_timing_overhead_ns(&mut self, overhead_ns: u32) {
+        self.timing_overhead_ns = overhead_ns;
+    }
+
+    /// Get the time subtracted from every plane delay, in nanoseconds
+    pub fn timing_overhead_ns(&self) -> u32 {
+        self.timing_overhead_ns
+    }
+
+    /// Adjust the timing overhead by the overhead left in the last frame
+    /// measured by `timer`
+    ///
+    /// Returns the new overhead, or `None` if nothing was measured yet.
+    pub fn calibrate_timing<T: NanoClock>(&mut self, timer: &PlaneTimer<T>) -> Option<u32> {
+        let residual = timer.overhead_ns()?;
+        self.timing_overhead_ns =
+            (self.timing_overhead_ns as i64 + residual as i64).clamp(0, u32::MAX as i64) as u32;
+        Some(self.timing_overhead_ns)
+    }
+
+    /// Clear the display (set all pixels to black)
+    pub fn clear(&mut self) {
+        self.back_buffer().clear();
+        if !self.is_double_buffered() {
+            self.buffers.front_mut().clear();
+        }
+    }
+
+    /// Set a pixel at the specified coordinates
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: B::Pixel) -> Result<(), Hub75Error> {
+        self.back_buffer().set_pixel(x, y, color)
+    }
+
+    /// Get a pixel at the specified coordinates
+    pub fn get_pixel(&self, x: usize, y: usize) -> Result<B::Pixel, Hub75Error> {
+        self.front_buffer().get_pixel(x, y)
+    }
+
+    /// Fill the display with a single color
+    pub fn fill(&mut self, color: B::Pixel) {
+        self.back_buffer().fill(color);
+    }
+
+    /// Render a single bit plane (subframe) for the current row
+    pub fn render_bit_plane(&mut self) -> Result<(), Hub75Error> {
+        // Disable output during data loading
+        self.pins.control.disable_output()?;
+
+        // Set row address
+        self.pins.address.set_address(self.current_row)?;
+
+        // Get bit plane data for current row
+        let bit_data = self.scan_row_subframe(self.current_row, self.current_bit_plane)?;
+
+        // Shift out RGB data for all columns
+        for &(upper_r, upper_g, upper_b, lower_r, lower_g, lower_b) in &bit_data {
+            // Set RGB pins
+            self.pins
+                .rgb
+                .set_rgb(upper_r, upper_g, upper_b, lower_r, lower_g, lower_b)?;
+
+            // Clock pulse to shift data
+            self.pins.control.clock_pulse()?;
+        }
+
+        // Latch the data
+        self.pins.control.latch_pulse()?;
+
+        // Enable output
+        self.pins.control.enable_output()?;
+
+        Ok(())
+    }
+
+    /// Render the next row and bit plane of the frame, leaving it lit
+    ///
+    /// Steps through a frame in the same order as
+    /// [`render_frame`](Self::render_frame), one row and bit plane per call,
+    /// and returns how long in nanoseconds the row should stay lit before the
+    /// next call. Nothing waits, so a timer interrupt can drive the refresh
+    /// without an async executor.
+    pub fn render_step(&mut self) -> Result<u32, Hub75Error> {
+        let lit = self.light_next_row(None::<&mut PlaneTimer<fn() -> u32>>, |display| {
+            display.render_bit_plane()
+        })?;
+        Ok(lit.delay_ns)
+    }
+
+    /// Turn the output off and restart the scan at the first step of a frame
+    ///
+    /// Callers then pulse the latch line with [`set_latch`](Self::set_latch),
+    /// waiting 2 µs before each edge, which prevents ghosting.
+    // https://github.com/david-sawatzke/hub75-rs/blob/ba1eaa6de065909bba5776557e7986229df54863/src/lib.rs#L296
+    fn begin_frame(&mut self) -> Result<(), Hub75Error> {
+        // A previous render_step may have left a row lit
+        self.pins.control.disable_output()?;
+        self.scan_step = 0;
+        Ok(())
+    }
+
+    /// Drive the latch line low or high
+    fn set_latch(&mut self, high: bool) -> Result<(), Hub75Error> {
+        if high {
+            crate::pin_op!(self.pins.control.lat.set_high());
+        } else {
+            crate::pin_op!(self.pins.control.lat.set_low());
+        }
         Ok(())
     }
 
+    /// Load and light the row of the next scan step with `load`, advancing
+    /// the scan
+    ///
+    /// The row is left lit; the caller waits for [`LitRow::delay_ns`] and then
+    /// calls [`finish_row`](Self::finish_row).
+    fn light_next_row<T: NanoClock>(
+        &mut self,
+        mut timer: Option<&mut PlaneTimer<T>>,
+        load: impl FnOnce(&mut Self) -> Result<(), Hub75Error>,
+    ) -> Result<LitRow, Hub75Error> {
+        if self.scan_step == 0 {
+            crate::trace_event!("hub75: frame {=u8} start", self.frame_count);
+            self.swap_pending = false;
+        }
+
+        let (row, bit_plane) = self.scan_position(self.scan_step);
+        self.current_row = row;
+        self.current_bit_plane = bit_plane;
+
+        let shift_start = timer.as_mut().map(|timer| timer.now_ns());
+        load(self)?;
+        let lit_start = timer.as_mut().map(|timer| timer.now_ns());
+
+        self.scan_step += 1;
+        if self.scan_step == self.scan_steps() {
+            self.scan_step = 0;
+            crate::trace_event!("hub75: frame {=u8} end", self.frame_count);
+            self.frame_count = self.frame_count.wrapping_add(1);
+        }
+
+        Ok(LitRow {
+            row,
+            bit_plane,
+            delay_ns: self.plane_delay_ns(row, bit_plane),
+            shift_start,
+            lit_start,
+        })
+    }
+
+    /// Turn off a row lit by [`light_next_row`](Self::light_next_row) once its
+    /// time is up, recording the timing with `timer` if given
+    fn finish_row<T: NanoClock>(&mut self, lit: LitRow, timer: Option<&mut PlaneTimer<T>>) {
+        // Disable output before moving to next row/bit plane
+        self.pins.control.disable_output().ok();
+
+        let requested_ns = self.bit_plane_duration_ns(lit.row, lit.bit_plane);
+        if let (Some(timer), Some(shift_start), Some(lit_start)) =
+            (timer, lit.shift_start, lit.lit_start)
+        {
+            let lit_end = timer.now_ns();
+            timer.record_shift(shift_start, lit_start);
+            timer.record_plane(lit.bit_plane, requested_ns, lit_start, lit_end);
+        }
+
+        if lit.row + 1 == HEIGHT / 2 {
+            crate::trace_event!(
+                "hub75: bit plane {=usize} lit {=u64} ns per row",
+                lit.bit_plane,
+                requested_ns
+            );
+        }
+    }
+
+    /// Get the number of rows lit one after another in a frame
+    fn scan_steps(&self) -> usize {
+        self.subframes() * (HEIGHT / 2)
+    }
+
+    /// Get the row and subframe lit at a step of the frame
+    #[inline(always)]
+    fn scan_position(&self, step: usize) -> (usize, usize) {
+        let rows = HEIGHT / 2;
+        let subframes = self.subframes();
+        let (row, index) = match self.scan_order {
+            ScanOrder::PlaneMajor => (step % rows, step / rows),
+            ScanOrder::RowMajor => (step / subframes, step % subframes),
+            ScanOrder::Interleaved => (step % rows, (step / rows + step % rows) % subframes),
+        };
+        (row, self.plane_order.subframe(index, subframes))
+    }
+
+    /// Render a complete frame with the display's modulation scheme, blocking on
+    /// `delay` between rows
+    ///
+    /// Drives the pins exactly like [`render_frame`](Self::render_frame), for
+    /// applications built without the `async` feature or without an executor.
+    pub fn render_frame_blocking(
+        &mut self,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+    ) -> Result<(), Hub75Error> {
+        self.render_frame_blocking_measured(delay, None::<&mut PlaneTimer<fn() -> u32>>)
+    }
+
+    /// Render a complete frame like
+    /// [`render_frame_blocking`](Self::render_frame_blocking), measuring how
+    /// long each row is lit with `timer`
+    ///
+    /// See [`render_frame_timed`](Self::render_frame_timed).
+    pub fn render_frame_blocking_timed<T: NanoClock>(
+        &mut self,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+        timer: &mut PlaneTimer<T>,
+    ) -> Result<(), Hub75Error> {
+        self.render_frame_blocking_measured(delay, Some(timer))
+    }
+
+    /// Render a complete frame blocking on `delay`, measuring it with `timer`
+    /// if given
+    fn render_frame_blocking_measured<T: NanoClock>(
+        &mut self,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+        mut timer: Option<&mut PlaneTimer<T>>,
+    ) -> Result<(), Hub75Error> {
+        self.begin_frame()?;
+        delay.delay_us(2);
+        self.set_latch(false)?;
+        delay.delay_us(2);
+        self.set_latch(true)?;
+
+        for _ in 0..self.scan_steps() {
+            let lit =
+                self.light_next_row(timer.as_deref_mut(), |display| display.render_bit_plane())?;
+            delay.delay_ns(lit.delay_ns);
+            self.finish_row(lit, timer.as_deref_mut());
+        }
+
+        if let Some(timer) = timer {
+            self.finish_timed_frame(timer);
+        }
+
+        Ok(())
+    }
+
+    /// Make the measurements of a timed frame current and auto-calibrate
+    fn finish_timed_frame<T: NanoClock>(&mut self, timer: &mut PlaneTimer<T>) {
+        timer.finish_frame();
+        if timer.is_auto_calibrating() {
+            self.calibrate_timing(timer);
+        }
+    }
+
+    /// Compute the color data of the front buffer into a bitstream for DMA
+    ///
+    /// Scan-time color correction, the color mode and temporal dithering for
+    /// the current frame are applied just as [`render_frame`](Self::render_frame)
+    /// would, so the stream matches what the pins would be driven with. The
+    /// stream always holds Binary Code Modulation bit planes, whatever the
+    /// display's [modulation](Self::set_modulation).
+    pub fn write_bitstream<const ROWS: usize>(
+        &self,
+        stream: &mut Bitstream<WIDTH, HEIGHT, COLOR_BITS, ROWS>,
+    ) -> Result<(), Hub75Error> {
+        for bit_plane in 0..COLOR_BITS {
+            for row in 0..ROWS {
+                let levels = self.scan_row_bit_plane(row, bit_plane)?;
+                let bytes = stream
+                    .row_mut(bit_plane, row)
+                    .ok_or(Hub75Error::InvalidCoordinates)?;
+                for (byte, levels) in bytes.iter_mut().zip(levels) {
+                    *byte = bitstream::pack_levels(levels);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check whether any scan-time color correction is active
+    fn has_scan_correction(&self) -> bool {
+        self.brightness_curve != BrightnessCurve::Linear
+            || self.color_balance != ColorBalance::NEUTRAL
+            || self.tint != Tint::None
+            || !self.zones.is_empty()
+            || self.panel_balance != [ColorBalance::NEUTRAL; MAX_CHAINED_PANELS]
+    }
+
+    /// Apply the gains of the chained panel containing column `x`
+    #[inline(always)]
+    fn panel_color<const BITS: usize, Px: Hub75Pixel<BITS>>(&self, color: Px, x: usize) -> Px {
+        self.panel_balance[x / self.panel_width].apply(color)
+    }
+
+    /// Get the combined brightness of the zones covering a pixel (255 = 1.0)
+    #[inline(always)]
+    fn zone_factor(&self, x: usize, y: usize) -> u8 {
+        self.zones
+            .iter()
+            .filter(|zone| zone.contains(x, y))
+            .fold(255, |factor, zone| {
+                ((factor as u16 * zone.brightness.level() as u16 + 127) / 255) as u8
+            })
+    }
+
+    /// Apply the brightness zones to a pixel
+    #[inline(always)]
+    fn zone_color<const BITS: usize, Px: Hub75Pixel<BITS>>(
+        &self,
+        color: Px,
+        x: usize,
+        y: usize,
+    ) -> Px {
+        if self.zones.is_empty() {
+            return color;
+        }
+        let factor = self.zone_factor(x, y);
+        Tint::Multiply {
+            r: factor,
+            g: factor,
+            b: factor,
+        }
+        .apply(color)
+    }
+
+    /// Apply scan-time color correction to a pixel of the front buffer
+    #[inline(always)]
+    fn scan_color(&self, color: B::Pixel, x: usize, y: usize) -> B::Pixel {
+        let color = self.tint.apply(color);
+        let color = self.zone_color(color, x, y);
+        let color = self.brightness_curve.apply_color(color);
+        let color = self.color_balance.apply(color);
+        self.panel_color(color, x)
+    }
+
+    /// Get the temporal dithering threshold (0-3) of a pixel for the current frame
+    #[inline(always)]
+    fn frc_phase(&self, x: usize, y: usize) -> u8 {
+        // Visit the thresholds in an order that spreads on-frames evenly
+        const SEQUENCE: [u8; 4] = [0, 2, 1, 3];
+        SEQUENCE[(self.frame_count as usize + x + 2 * y) & 3]
+    }
+
+    /// Apply scan-time color correction at 8-bit precision and quantize with
+    /// temporal dithering
+    #[inline(always)]
+    fn scan_color_dithered(&self, color: B::Pixel, x: usize, y: usize, phase: u8) -> B::Pixel {
+        let max = B::Pixel::MAX_LEVEL;
+        let expand = |value: u32| ((value * 255 + max / 2) / max) as u8;
+        // Quantize with 2 extra fractional bits and round up on a fraction
+        // of the frames proportional to them
+        let quantize = |value: u8| {
+            let fixed = (value as u32 * max * 4 + 127) / 255;
+            let level = fixed >> 2;
+            let round_up = (fixed & 3) as u8 > phase;
+            level + round_up as u32
+        };
+
+        let [r, g, b] = color.levels();
+        let color = Hub75Color::<8>::new(expand(r), expand(g), expand(b));
+        let color = self.tint.apply(color);
+        let color = self.zone_color(color, x, y);
+        let color = self.brightness_curve.apply_color(color);
+        let color = self.color_balance.apply(color);
+        let color = self.panel_color(color, x);
+        B::Pixel::from_levels([quantize(color.r), quantize(color.g), quantize(color.b)])
+    }
+
+    /// Apply whichever scan-time color correction is active to a pixel
+    #[inline(always)]
+    fn corrected_color(&self, color: B::Pixel, x: usize, y: usize) -> B::Pixel {
+        if !self.has_scan_correction() {
+            color
+        } else if self.temporal_dithering {
+            self.scan_color_dithered(color, x, y, self.frc_phase(x, y))
+        } else {
+            self.scan_color(color, x, y)
+        }
+    }
+
+    /// Get the data of a subframe for a row of the front buffer after color
+    /// correction, as chosen by the modulation scheme
+    #[allow(clippy::type_complexity)]
+    fn scan_row_subframe(
+        &self,
+        row: usize,
+        subframe: usize,
+    ) -> Result<heapless::Vec<(bool, bool, bool, bool, bool, bool), WIDTH>, Hub75Error> {
+        if let Some(bit_plane) = self.modulation.bit_plane(COLOR_BITS, subframe) {
+            return self.scan_row_bit_plane(row, bit_plane);
+        }
+
+        if row >= HEIGHT / 2 {
+            return Err(Hub75Error::InvalidCoordinates);
+        }
+        if subframe >= self.subframes() {
+            return Err(Hub75Error::InvalidColor);
+        }
+
+        let front = self.buffers.front();
+        let upper = front.get_row(row)?;
+        let lower = front.get_row(row + HEIGHT / 2)?;
+        let mut result = heapless::Vec::new();
+
+        let lit = |color: B::Pixel, x: usize, y: usize| {
+            // Offset neighbouring pixels in any cycle spread over frames
+            let phase = self.frame_count.wrapping_add((x + 2 * y) as u8);
+            let [r, g, b] = color
+                .levels()
+                .map(|level| self.modulation.is_lit(COLOR_BITS, level, subframe, phase));
+            self.color_mode.pin_levels((r, g, b))
+        };
+        for (x, (upper_pixel, lower_pixel)) in upper.iter().zip(lower).enumerate() {
+            let lower_y = row + HEIGHT / 2;
+            let (upper_r, upper_g, upper_b) =
+                lit(self.corrected_color(*upper_pixel, x, row), x, row);
+            let (lower_r, lower_g, lower_b) =
+                lit(self.corrected_color(*lower_pixel, x, lower_y), x, lower_y);
+
+            result
+                .push((upper_r, upper_g, upper_b, lower_r, lower_g, lower_b))
+                .map_err(|_| Hub75Error::BufferOverflow)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Get bit plane data for a row of the front buffer after color correction
+    #[allow(clippy::type_complexity)]
+    fn scan_row_bit_plane(
+        &self,
+        row: usize,
+        bit_plane: usize,
+    ) -> Result<heapless::Vec<(bool, bool, bool, bool, bool, bool), WIDTH>, Hub75Error> {
+        let front = self.buffers.front();
+        if !self.has_scan_correction() && self.color_mode == ColorMode::Rgb {
+            return front.get_row_bit_plane(row, bit_plane);
+        }
+
+        if row >= HEIGHT / 2 {
+            return Err(Hub75Error::InvalidCoordinates);
+        }
+        if bit_plane >= COLOR_BITS {
+            return Err(Hub75Error::InvalidColor);
+        }
+
+        let upper = front.get_row(row)?;
+        let lower = front.get_row(row + HEIGHT / 2)?;
+        let mut result = heapless::Vec::new();
+
+        for (x, (upper_pixel, lower_pixel)) in upper.iter().zip(lower).enumerate() {
+            let upper = self.corrected_color(*upper_pixel, x, row);
+            let lower = self.corrected_color(*lower_pixel, x, row + HEIGHT / 2);
+            let (upper_r, upper_g, upper_b) = self.color_mode.pin_levels(upper.get_bit(bit_plane));
+            let (lower_r, lower_g, lower_b) = self.color_mode.pin_levels(lower.get_bit(bit_plane));
+
+            result
+                .push((upper_r, upper_g, upper_b, lower_r, lower_g, lower_b))
+                .map_err(|_| Hub75Error::BufferOverflow)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Render a complete frame using Binary Code Modulation, or the scheme set
+    /// with [`set_modulation`](Self::set_modulation)
+    #[cfg(feature = "async")]
+    pub async fn render_frame(&mut self, delay: &mut impl DelayNs) -> Result<(), Hub75Error> {
+        self.render_frame_measured(delay, None::<&mut PlaneTimer<fn() -> u32>>)
+            .await
+    }
+
+    /// Render a complete frame like [`render_frame`](Self::render_frame),
+    /// measuring how long each row is lit with `timer`
+    ///
+    /// The timer keeps the requested and measured on-time of every subframe
+    /// and the time taken to shift out each row; see the
+    /// [`timing`](crate::timing) module. With auto-calibration enabled on
+    /// the timer, the [timing overhead](Self::set_timing_overhead_ns) is
+    /// adjusted after the frame.
+    #[cfg(feature = "async")]
+    pub async fn render_frame_timed<T: NanoClock>(
+        &mut self,
+        delay: &mut impl DelayNs,
+        timer: &mut PlaneTimer<T>,
+    ) -> Result<(), Hub75Error> {
+        self.render_frame_measured(delay, Some(timer)).await
+    }
+
+    /// Render a complete frame, measuring it with `timer` if given
+    #[cfg(feature = "async")]
+    async fn render_frame_measured<T: NanoClock>(
+        &mut self,
+        delay: &mut impl DelayNs,
+        mut timer: Option<&mut PlaneTimer<T>>,
+    ) -> Result<(), Hub75Error> {
+        self.begin_frame()?;
+        delay.delay_us(2).await;
+        self.set_latch(false)?;
+        delay.delay_us(2).await;
+        self.set_latch(true)?;
+
+        for _ in 0..self.scan_steps() {
+            let lit =
+                self.light_next_row(timer.as_deref_mut(), |display| display.render_bit_plane())?;
+            delay.delay_ns(lit.delay_ns).await;
+            self.finish_row(lit, timer.as_deref_mut());
+        }
+
+        if let Some(timer) = timer {
+            self.finish_timed_frame(timer);
+        }
+
+        Ok(())
+    }
+
+    /// Render a complete frame with an SPI peripheral clocking the data out
+    ///
+    /// Works like [`render_frame`](Self::render_frame), but each row is
+    /// shifted out through `output` instead of toggling the CLK pin, which is
+    /// held low. `WIDTH` must be a multiple of 8; see the [`spi`](crate::spi)
+    /// module for wiring and the resolution of the lines left on GPIO.
+    #[cfg(feature = "async")]
+    pub async fn render_frame_spi<S: SpiBus>(
+        &mut self,
+        output: &mut SpiOutput<S>,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Hub75Error> {
+        self.render_frame_shifted(output, delay).await
+    }
+
+    /// Render a complete frame with nRF52 peripherals generating the clock
+    ///
+    /// Works like [`render_frame`](Self::render_frame), but the color pins are
+    /// written straight to the port and each clock pulse is timed by `clock`
+    /// instead of toggling the CLK pin, which is held low. See the
+    /// [`nrf`](crate::nrf) module for setup.
+    #[cfg(all(feature = "async", feature = "nrf52"))]
+    pub async fn render_frame_nrf(
+        &mut self,
+        clock: &mut crate::nrf::NrfClock,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Hub75Error> {
+        self.render_frame_shifted(clock, delay).await
+    }
+
+    /// Render a complete frame with `shifter` clocking each row out
+    #[cfg(feature = "async")]
+    async fn render_frame_shifted(
+        &mut self,
+        shifter: &mut impl RowShifter,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Hub75Error> {
+        self.begin_frame()?;
+        delay.delay_us(2).await;
+        self.set_latch(false)?;
+        delay.delay_us(2).await;
+        self.set_latch(true)?;
+
+        for _ in 0..self.scan_steps() {
+            let lit = self.light_next_row(None::<&mut PlaneTimer<fn() -> u32>>, |display| {
+                display.pins.control.disable_output()?;
+                display.pins.address.set_address(display.current_row)?;
+                let bit_data =
+                    display.scan_row_subframe(display.current_row, display.current_bit_plane)?;
+                shifter.shift_row(&bit_data, &mut display.pins.rgb)?;
+                display.pins.control.latch_pulse()?;
+                display.pins.control.enable_output()
+            })?;
+            delay.delay_ns(lit.delay_ns).await;
+            self.finish_row(lit, None::<&mut PlaneTimer<fn() -> u32>>);
+        }
+
+        Ok(())
+    }
+
+    /// Get how long a row is lit during a bit plane
+    fn bit_plane_duration_ns(&self, row: usize, bit_plane: usize) -> u64 {
+        // Subframe timing - exponentially longer delays for higher bit planes under BCM
+        let bit_duration_ns =
+            self.refresh_interval_ns as u64 * self.modulation.weight(COLOR_BITS, bit_plane) as u64;
+
+        // Apply brightness scaling through the perceptual curve
+        let brightness_factor = self.brightness.duty(self.brightness_curve) as u64;
+
+        // Compensate row-dependent brightness
+        let row_gain = self.row_gains[row] as u64;
+        bit_duration_ns * brightness_factor * row_gain / (255 * 255)
+    }
+
+    /// Get how long to delay while a row is lit during a bit plane, less the
+    /// timing overhead
+    fn plane_delay_ns(&self, row: usize, bit_plane: usize) -> u32 {
+        let duration_ns = self.bit_plane_duration_ns(row, bit_plane);
+        let delay_ns = duration_ns.saturating_sub(self.timing_overhead_ns as u64);
+        delay_ns.min(u32::MAX as u64) as u32
+    }
+
     /// Continuous refresh task
+    #[cfg(feature = "async")]
     pub async fn refresh_task(&mut self, delay: &mut impl DelayNs) -> ! {
         loop {
             if self.render_frame(delay).await.is_err() {
@@ -332,18 +1787,19 @@ where
     }
 
     /// Display a frame for a specific duration
+    #[cfg(feature = "async")]
     pub async fn display_frame(
         &mut self,
-        frame: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        frame: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, B::Pixel>,
         duration_ns: u32,
         delay: &mut impl DelayNs,
     ) -> Result<(), Hub75Error> {
         // Copy frame to appropriate buffer
-        if self.double_buffering {
-            self.back_buffer.copy_from(&frame);
+        if self.is_double_buffered() {
+            self.buffers.back_mut().copy_from(&frame);
             self.swap_buffers();
         } else {
-            self.front_buffer.copy_from(&frame);
+            self.buffers.front_mut().copy_from(&frame);
         }
 
         // Calculate how many frames to render based on duration and refresh rate
@@ -373,20 +1829,139 @@ where
     }
 }
 
+/// Drawing and publishing need the default [`Hub75Color`] pixels
+impl<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, B, A, C>
+    Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>
+where
+    P: OutputPin,
+    A: OutputPin,
+    C: OutputPin,
+    B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS, Pixel = Hub75Color<COLOR_BITS>>,
+{
+    /// Swap front and back buffers and publish the new front buffer to `sink`
+    ///
+    /// Without double buffering nothing is swapped and the buffer being shown
+    /// is published as it is. Errors from the sink are returned after the
+    /// swap has happened.
+    pub fn swap_buffers_into<S>(&mut self, sink: &mut S) -> Result<(), Hub75Error>
+    where
+        S: FrameSink<WIDTH, HEIGHT, COLOR_BITS>,
+    {
+        self.swap_buffers();
+        sink.publish(self.buffers.front())
+    }
+    /// Swap buffers only if the back buffer content differs, publishing the
+    /// new front buffer to `sink` when they were swapped
+    ///
+    /// Returns `true` if the buffers were swapped; see
+    /// [`swap_buffers_if_changed`](Self::swap_buffers_if_changed).
+    pub fn swap_buffers_if_changed_into<S>(&mut self, sink: &mut S) -> Result<bool, Hub75Error>
+    where
+        S: FrameSink<WIDTH, HEIGHT, COLOR_BITS>,
+    {
+        if !self.swap_buffers_if_changed() {
+            return Ok(false);
+        }
+        sink.publish(self.buffers.front())?;
+        Ok(true)
+    }
+    /// Get a clipped and translated view onto a region of the back buffer
+    ///
+    /// See [`Window`] for details. Useful for drawing independent panes of a
+    /// layout without manual coordinate bookkeeping.
+    pub fn window(&mut self, area: Rectangle) -> Window<'_, WIDTH, HEIGHT, COLOR_BITS> {
+        self.back_buffer().window(area)
+    }
+    /// Get a [`TextWriter`] drawing into the back buffer with its top-left
+    /// corner at `(x, y)`
+    ///
+    /// `style` can be a [`TextStyle`] or just a color, so
+    /// `write!(display.text_at(0, 10, Hub75Color::red()), "T={}C", t)` draws
+    /// formatted text without a string buffer.
+    pub fn text_at(
+        &mut self,
+        x: i32,
+        y: i32,
+        style: impl Into<TextStyle<COLOR_BITS>>,
+    ) -> TextWriter<'_, WIDTH, HEIGHT, COLOR_BITS> {
+        self.back_buffer().text_at(x, y, style)
+    }
+    /// Play an animation to the end
+    ///
+    /// Renders each step straight into the back buffer and swaps it in, then
+    /// refreshes the panel once per animation frame. Returns once the
+    /// animation reports [`AnimationState::Done`], so looping animations only
+    /// return on error.
+    #[cfg(feature = "async")]
+    pub async fn play_animation<E>(
+        &mut self,
+        animation: &mut Animation<'_, WIDTH, HEIGHT, COLOR_BITS, E>,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Hub75Error>
+    where
+        E: AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS>,
+    {
+        loop {
+            match animation.next_into(self.back_buffer()) {
+                AnimationState::Rendered | AnimationState::Apply(_) => self.swap_buffers(),
+                AnimationState::Wait => {}
+                AnimationState::Done => return Ok(()),
+            }
+            self.render_frame(delay).await?;
+        }
+    }
+}
+
+/// Gamma 2.2 lookup table (8-bit in, 8-bit out)
+const GAMMA22_LUT: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2,
+    3, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 8, 8, 8, 9, 9, 9, 10, 10, 11, 11,
+    11, 12, 12, 13, 13, 13, 14, 14, 15, 15, 16, 16, 17, 17, 18, 18, 19, 19, 20, 20, 21, 22, 22, 23,
+    23, 24, 25, 25, 26, 26, 27, 28, 28, 29, 30, 30, 31, 32, 33, 33, 34, 35, 35, 36, 37, 38, 39, 39,
+    40, 41, 42, 43, 43, 44, 45, 46, 47, 48, 49, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61,
+    62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 73, 74, 75, 76, 77, 78, 79, 81, 82, 83, 84, 85, 87, 88,
+    89, 90, 91, 93, 94, 95, 97, 98, 99, 100, 102, 103, 105, 106, 107, 109, 110, 111, 113, 114, 116,
+    117, 119, 120, 121, 123, 124, 126, 127, 129, 130, 132, 133, 135, 137, 138, 140, 141, 143, 145,
+    146, 148, 149, 151, 153, 154, 156, 158, 159, 161, 163, 165, 166, 168, 170, 172, 173, 175, 177,
+    179, 181, 182, 184, 186, 188, 190, 192, 194, 196, 197, 199, 201, 203, 205, 207, 209, 211, 213,
+    215, 217, 219, 221, 223, 225, 227, 229, 231, 234, 236, 238, 240, 242, 244, 246, 248, 251, 253,
+    255,
+];
+
+/// CIE 1931 lightness lookup table (8-bit in, 8-bit out)
+const CIE1931_LUT: [u8; 256] = [
+    0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 3, 4,
+    4, 4, 4, 4, 4, 5, 5, 5, 5, 5, 6, 6, 6, 6, 6, 7, 7, 7, 7, 8, 8, 8, 8, 9, 9, 9, 10, 10, 10, 10,
+    11, 11, 11, 12, 12, 12, 13, 13, 13, 14, 14, 15, 15, 15, 16, 16, 17, 17, 17, 18, 18, 19, 19, 20,
+    20, 21, 21, 22, 22, 23, 23, 24, 24, 25, 25, 26, 26, 27, 28, 28, 29, 29, 30, 31, 31, 32, 32, 33,
+    34, 34, 35, 36, 37, 37, 38, 39, 39, 40, 41, 42, 43, 43, 44, 45, 46, 47, 47, 48, 49, 50, 51, 52,
+    53, 54, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 65, 66, 67, 68, 70, 71, 72, 73, 74, 75, 76,
+    77, 79, 80, 81, 82, 83, 85, 86, 87, 88, 90, 91, 92, 94, 95, 96, 98, 99, 100, 102, 103, 105,
+    106, 108, 109, 110, 112, 113, 115, 116, 118, 120, 121, 123, 124, 126, 128, 129, 131, 132, 134,
+    136, 138, 139, 141, 143, 145, 146, 148, 150, 152, 154, 155, 157, 159, 161, 163, 165, 167, 169,
+    171, 173, 175, 177, 179, 181, 183, 185, 187, 189, 191, 193, 196, 198, 200, 202, 204, 207, 209,
+    211, 214, 216, 218, 220, 223, 225, 228, 230, 232, 235, 237, 240, 242, 245, 247, 250, 252, 255,
+];
+
 #[cfg(feature = "embedded-graphics")]
 mod embedded_graphics_support {
     use super::*;
+    use crate::frame_buffer::{DitheredTarget, NativeTarget};
     use embedded_graphics_core::{
         draw_target::DrawTarget,
         geometry::{OriginDimensions, Size},
-        pixelcolor::Rgb565,
+        pixelcolor::{PixelColor, Rgb565, Rgb888},
+        primitives::Rectangle,
         Pixel,
     };
 
-    impl<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> DrawTarget
-        for Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS>
+    impl<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, B, A, C> DrawTarget
+        for Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>
     where
         P: OutputPin,
+        A: OutputPin,
+        C: OutputPin,
+        B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS, Pixel = Hub75Color<COLOR_BITS>>,
     {
         type Color = Rgb565;
         type Error = Hub75Error;
@@ -397,12 +1972,48 @@ mod embedded_graphics_support {
         {
             self.back_buffer().draw_iter(pixels)
         }
+
+        fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Self::Color>,
+        {
+            self.back_buffer().fill_contiguous(area, colors)
+        }
+
+        fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+            self.back_buffer().fill_solid(area, color)
+        }
+    }
+
+    impl<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, B, A, C>
+        Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>
+    where
+        P: OutputPin,
+        A: OutputPin,
+        C: OutputPin,
+        B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS, Pixel = Hub75Color<COLOR_BITS>>,
+    {
+        /// Get a draw target over the back buffer that accepts native [`Hub75Color`] values
+        pub fn native_target(&mut self) -> NativeTarget<'_, WIDTH, HEIGHT, COLOR_BITS> {
+            self.back_buffer().native_target()
+        }
+
+        /// Get a draw target over the back buffer that converts colors with ordered dithering
+        pub fn dithered_target<T>(&mut self) -> DitheredTarget<'_, T, WIDTH, HEIGHT, COLOR_BITS>
+        where
+            T: PixelColor + Into<Rgb888>,
+        {
+            self.back_buffer().dithered_target()
+        }
     }
 
-    impl<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> OriginDimensions
-        for Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS>
+    impl<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, B, A, C>
+        OriginDimensions for Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>
     where
         P: OutputPin,
+        A: OutputPin,
+        C: OutputPin,
+        B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS>,
     {
         fn size(&self) -> Size {
             Size::new(WIDTH as u32, HEIGHT as u32)
@@ -427,7 +2038,7 @@ mod tests {
     }
 
     impl ErrorType for MockPin {
-        type Error = ();
+        type Error = core::convert::Infallible;
     }
 
     impl OutputPin for MockPin {
@@ -442,6 +2053,24 @@ mod tests {
         }
     }
 
+    fn mock_pins() -> Hub75Pins<MockPin> {
+        Hub75Pins::new_64x32(
+            MockPin::new(),
+            MockPin::new(),
+            MockPin::new(), // RGB1
+            MockPin::new(),
+            MockPin::new(),
+            MockPin::new(), // RGB2
+            MockPin::new(),
+            MockPin::new(),
+            MockPin::new(),
+            MockPin::new(), // Address
+            MockPin::new(),
+            MockPin::new(),
+            MockPin::new(), // Control
+        )
+    }
+
     #[test]
     fn test_display_creation() {
         let pins = Hub75Pins::new_64x32(
@@ -487,4 +2116,779 @@ mod tests {
         brightness = Brightness::new(10) - 20;
         assert_eq!(brightness.level(), 0);
     }
+
+    #[test]
+    fn test_brightness_percent_and_presets() {
+        assert_eq!(Brightness::from_percent(0), Brightness::MIN);
+        assert_eq!(Brightness::from_percent(50).level(), 128);
+        assert_eq!(Brightness::from_percent(150), Brightness::MAX);
+        assert_eq!(Brightness::new(64).percent(), 25);
+
+        assert_eq!(Brightness::default(), BrightnessPreset::Normal.into());
+        assert_eq!(Brightness::from(BrightnessPreset::Max), Brightness::MAX);
+        assert!(
+            Brightness::from(BrightnessPreset::Dim) < Brightness::from(BrightnessPreset::Bright)
+        );
+
+        assert_eq!((Brightness::new(100) * 2).level(), 200);
+        assert_eq!(Brightness::new(200) * 2, Brightness::MAX);
+        assert_eq!((Brightness::new(200) / 4).level(), 50);
+        assert_eq!(Brightness::new(200) / 0, Brightness::MAX);
+        assert_eq!(Brightness::new(200).scale(3, 4).level(), 150);
+        assert_eq!(Brightness::new(200).scale(1, 0), Brightness::MAX);
+
+        // Presets are perceptual levels mapped through the display's curve
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        display.set_brightness(BrightnessPreset::Dim);
+        let linear = display.bit_plane_duration_ns(0, 0);
+        display.set_brightness_curve(BrightnessCurve::Cie1931);
+        assert!(display.bit_plane_duration_ns(0, 0) < linear);
+        assert_eq!(
+            display.brightness().duty(BrightnessCurve::Cie1931),
+            BrightnessCurve::Cie1931.apply(32)
+        );
+    }
+
+    #[test]
+    fn test_brightness_zones() {
+        use embedded_graphics_core::geometry::{Point, Size};
+
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        display.set_double_buffering(true);
+        display.fill(Hub75Color::new(60, 60, 60));
+        display.swap_buffers();
+
+        let clock = BrightnessZone::new(
+            Rectangle::new(Point::new(0, 0), Size::new(32, 16)),
+            Brightness::new(64),
+        );
+        let alert = BrightnessZone::new(
+            Rectangle::new(Point::new(16, 8), Size::new(48, 24)),
+            BrightnessPreset::Max,
+        );
+        assert_eq!(display.add_brightness_zone(clock), Ok(0));
+        assert_eq!(display.add_brightness_zone(alert), Ok(1));
+
+        // Content is untouched, only the scanned levels change
+        assert_eq!(display.get_pixel(0, 0), Ok(Hub75Color::new(60, 60, 60)));
+        let bit = |display: &Hub75Display<MockPin, 64, 32, 6>, x: usize, plane: usize| {
+            display.scan_row_bit_plane(0, plane).unwrap()[x].0
+        };
+        // 60 = 0b111100 outside zones, 15 = 0b001111 in the clock zone
+        assert!(!bit(&display, 40, 0) && bit(&display, 40, 5));
+        assert!(bit(&display, 0, 0) && !bit(&display, 0, 5));
+
+        assert_eq!(display.zone_factor(20, 10), 64);
+        display
+            .set_zone_brightness(1, Brightness::new(128))
+            .unwrap();
+        assert_eq!(display.zone_factor(20, 10), 32);
+        assert_eq!(display.zone_factor(40, 20), 128);
+        assert_eq!(
+            display.set_zone_brightness(2, BrightnessPreset::Dim),
+            Err(Hub75Error::InvalidCoordinates)
+        );
+
+        for _ in 2..MAX_BRIGHTNESS_ZONES {
+            display.add_brightness_zone(clock).unwrap();
+        }
+        assert_eq!(
+            display.add_brightness_zone(clock),
+            Err(Hub75Error::BufferOverflow)
+        );
+        display.clear_brightness_zones();
+        assert!(display.brightness_zones().is_empty());
+        assert!(bit(&display, 0, 5));
+    }
+
+    #[test]
+    fn test_brightness_curves() {
+        for curve in [
+            BrightnessCurve::Linear,
+            BrightnessCurve::Gamma22,
+            BrightnessCurve::Cie1931,
+        ] {
+            assert_eq!(curve.apply(0), 0);
+            assert_eq!(curve.apply(255), 255);
+            assert_eq!(
+                curve.apply_color(Hub75Color::<6>::white()),
+                Hub75Color::white()
+            );
+        }
+
+        assert_eq!(BrightnessCurve::Linear.apply(128), 128);
+        assert!(BrightnessCurve::Gamma22.apply(128) < 64);
+        assert!(BrightnessCurve::Cie1931.apply(128) < 64);
+
+        // Curves are monotonic
+        let mut last = 0;
+        for level in 0..=255 {
+            let value = BrightnessCurve::Cie1931.apply(level);
+            assert!(value >= last);
+            last = value;
+        }
+
+        let mid = Hub75Color::<6>::new(32, 32, 32);
+        assert!(BrightnessCurve::Cie1931.apply_color(mid).r < 32);
+    }
+
+    #[test]
+    fn test_color_balance() {
+        let white = Hub75Color::<6>::white();
+        assert_eq!(ColorBalance::NEUTRAL.apply(white), white);
+
+        let balance = ColorBalance::new(255, 192, 128);
+        assert_eq!(balance.apply(white), Hub75Color::new(63, 47, 32));
+        assert_eq!(balance.apply(Hub75Color::<6>::black()), Hub75Color::black());
+
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        display.set_color_balance(255, 192, 128);
+        assert_eq!(display.color_balance(), balance);
+        assert_eq!(display.scan_color(white, 0, 0), Hub75Color::new(63, 47, 32));
+
+        // Application colors are untouched
+        display.fill(white);
+        assert_eq!(display.get_pixel(0, 0).unwrap(), white);
+    }
+
+    #[test]
+    fn test_row_compensation() {
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        display.set_refresh_interval_ns(1_000);
+        display.set_brightness(Brightness::MAX);
+        assert_eq!(display.row_compensation(), [255; 16]);
+        assert_eq!(
+            display.set_row_compensation(&[255; 32]),
+            Err(Hub75Error::InvalidCoordinates)
+        );
+
+        let mut gains = [255; 16];
+        gains[3] = 204;
+        display.set_row_compensation(&gains).unwrap();
+        assert_eq!(display.row_compensation(), gains);
+
+        // Only the compensated row is lit for less time, in every bit plane
+        assert_eq!(display.bit_plane_duration_ns(2, 0), 1_000);
+        assert_eq!(display.bit_plane_duration_ns(3, 0), 800);
+        assert_eq!(display.bit_plane_duration_ns(3, 5), 25_600);
+
+        display.clear_row_compensation();
+        assert_eq!(display.bit_plane_duration_ns(3, 0), 1_000);
+    }
+
+    #[test]
+    fn test_panel_balance() {
+        let white = Hub75Color::<6>::white();
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        assert_eq!(display.panel_count(), 1);
+        assert_eq!(
+            display.set_panel_width(24),
+            Err(Hub75Error::InvalidCoordinates)
+        );
+        assert_eq!(
+            display.set_panel_width(4),
+            Err(Hub75Error::InvalidCoordinates)
+        );
+
+        display.set_panel_width(32).unwrap();
+        assert_eq!(display.panel_count(), 2);
+        display
+            .set_panel_balance(1, ColorBalance::new(255, 192, 128))
+            .unwrap();
+        assert_eq!(
+            display.set_panel_balance(2, ColorBalance::NEUTRAL),
+            Err(Hub75Error::InvalidCoordinates)
+        );
+        assert_eq!(display.panel_balance(0), Some(ColorBalance::NEUTRAL));
+        assert_eq!(display.panel_balance(2), None);
+
+        // Only the second panel is corrected, on top of the color balance
+        assert_eq!(display.scan_color(white, 31, 0), white);
+        assert_eq!(
+            display.scan_color(white, 32, 0),
+            Hub75Color::new(63, 47, 32)
+        );
+        display.set_color_balance(128, 255, 255);
+        assert_eq!(
+            display.scan_color(white, 63, 5),
+            Hub75Color::new(32, 47, 32)
+        );
+
+        display.clear_panel_balance();
+        display.set_color_balance(255, 255, 255);
+        assert!(!display.has_scan_correction());
+    }
+
+    #[test]
+    fn test_tint() {
+        let white = Hub75Color::<6>::white();
+        let gray = Hub75Color::<6>::new(32, 32, 32);
+
+        assert_eq!(Tint::None.apply(gray), gray);
+
+        let night = Tint::Multiply { r: 255, g: 0, b: 0 };
+        assert_eq!(night.apply(white), Hub75Color::red());
+
+        let flash = Tint::Overlay {
+            r: 255,
+            g: 255,
+            b: 255,
+            amount: 255,
+        };
+        assert_eq!(flash.apply(Hub75Color::<6>::black()), white);
+
+        let half = Tint::Overlay {
+            r: 0,
+            g: 0,
+            b: 0,
+            amount: 128,
+        };
+        assert_eq!(half.apply(gray), Hub75Color::new(16, 16, 16));
+
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        display.set_tint(night);
+        assert_eq!(display.tint(), night);
+        assert_eq!(display.scan_color(white, 0, 0), Hub75Color::red());
+    }
+
+    #[test]
+    fn test_temporal_dithering() {
+        let mut display = Hub75Display::<_, 64, 32, 4>::new(mock_pins()).unwrap();
+        assert!(!display.temporal_dithering());
+
+        // Half gain on a level-3 red lands between levels 1 and 2
+        display.set_color_balance(127, 255, 255);
+        display.fill(Hub75Color::new(3, 0, 0));
+        display.swap_buffers();
+
+        let level_at = |display: &Hub75Display<_, 64, 32, 4>, x: usize| {
+            (0..4)
+                .map(|plane| (display.scan_row_bit_plane(0, plane).unwrap()[x].0 as u8) << plane)
+                .sum::<u8>()
+        };
+
+        // Without dithering the level is rounded the same way every frame
+        assert_eq!(level_at(&display, 0), 1);
+
+        display.set_temporal_dithering(true);
+        let mut total = 0;
+        for frame in 0..4 {
+            display.frame_count = frame;
+            let level = level_at(&display, 0);
+            assert!(level == 1 || level == 2);
+            total += level as u32;
+        }
+        // 3 * 127 / 255 = 1.49, shown as level 2 on 2 of 4 frames
+        assert_eq!(total, 6);
+
+        // Neighbouring pixels are at different points in the cycle
+        display.frame_count = 0;
+        assert_ne!(level_at(&display, 0), level_at(&display, 1));
+    }
+
+    #[test]
+    fn test_color_mode() {
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        assert_eq!(display.color_mode(), ColorMode::Rgb);
+
+        display.set_pixel(0, 0, Hub75Color::new(63, 0, 0)).unwrap();
+        display.set_pixel(1, 0, Hub75Color::new(0, 63, 63)).unwrap();
+        display.swap_buffers();
+
+        let bits = display.scan_row_bit_plane(0, 5).unwrap();
+        assert_eq!(bits[0], (true, false, false, false, false, false));
+        assert_eq!(bits[1], (false, true, true, false, false, false));
+
+        display.set_color_mode(ColorMode::Monochrome);
+        let bits = display.scan_row_bit_plane(0, 5).unwrap();
+        assert_eq!(bits[0], (true, true, true, false, false, false));
+        assert_eq!(bits[1], (false, false, false, false, false, false));
+
+        display.set_color_mode(ColorMode::RedOnly);
+        display.set_color_balance(0, 255, 255);
+        let bits = display.scan_row_bit_plane(0, 5).unwrap();
+        assert_eq!(bits[0], (false, false, false, false, false, false));
+    }
+
+    #[test]
+    fn test_write_bitstream() {
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        display
+            .set_pixel(2, 17, Hub75Color::new(63, 0, 63))
+            .unwrap();
+        display.swap_buffers();
+
+        let mut stream = Bitstream::<64, 32, 6, 16>::new();
+        display.write_bitstream(&mut stream).unwrap();
+        assert_eq!(
+            stream.row(5, 1).unwrap()[2],
+            bitstream::R2_BIT | bitstream::B2_BIT
+        );
+
+        display.set_color_mode(ColorMode::Monochrome);
+        display.write_bitstream(&mut stream).unwrap();
+        assert_eq!(
+            stream.row(5, 1).unwrap()[2],
+            bitstream::R2_BIT | bitstream::G2_BIT | bitstream::B2_BIT
+        );
+    }
+
+    #[test]
+    fn test_swap_buffers_if_changed() {
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        display.set_double_buffering(true);
+
+        assert!(!display.swap_buffers_if_changed());
+
+        display.set_pixel(3, 4, Hub75Color::green()).unwrap();
+        assert!(display.swap_buffers_if_changed());
+        assert_eq!(display.get_pixel(3, 4).unwrap(), Hub75Color::green());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_dropped_frames() {
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        display.set_double_buffering(true);
+        let mut delay = NoDelay { calls: 0 };
+        let mut render = |display: &mut Hub75Display<MockPin, 64, 32, 6>| {
+            let mut future = core::pin::pin!(display.render_frame(&mut delay));
+            let poll = core::future::Future::poll(
+                future.as_mut(),
+                &mut core::task::Context::from_waker(core::task::Waker::noop()),
+            );
+            assert!(poll.is_ready());
+        };
+
+        display.swap_buffers();
+        render(&mut display);
+        display.swap_buffers();
+        assert_eq!(display.dropped_frames(), 0);
+
+        // The second swap replaces a frame that was never shown
+        display.swap_buffers();
+        assert_eq!(display.dropped_frames(), 1);
+        render(&mut display);
+        display.swap_buffers();
+        assert_eq!(display.dropped_frames(), 1);
+    }
+
+    #[test]
+    fn test_swap_buffers_into_sink() {
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        display.set_double_buffering(true);
+        let mut mirror = Hub75FrameBuffer::new();
+        let mut published = 0;
+
+        display.set_pixel(1, 2, Hub75Color::red()).unwrap();
+        display.swap_buffers_into(&mut mirror).unwrap();
+        assert_eq!(mirror.get_pixel(1, 2).unwrap(), Hub75Color::red());
+
+        // Unchanged frames are not published again
+        display.back_buffer().copy_from(&mirror);
+        let mut counter = |_: &Hub75FrameBuffer<64, 32, 6>| published += 1;
+        assert_eq!(
+            display.swap_buffers_if_changed_into(&mut counter),
+            Ok(false)
+        );
+        display.set_pixel(0, 0, Hub75Color::blue()).unwrap();
+        assert_eq!(display.swap_buffers_if_changed_into(&mut counter), Ok(true));
+        assert_eq!(published, 1);
+    }
+
+    #[test]
+    fn test_render_frame_blocking() {
+        use crate::waveform::{Signal, WaveformRecorder};
+
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        display.set_pixel(7, 11, Hub75Color::white()).unwrap();
+        let mut delay = recorder.delay();
+
+        display.render_frame_blocking(&mut delay).unwrap();
+        assert_eq!(recorder.check_protocol(32, 8), Ok(()));
+
+        // Starts over from the first row even after a partial frame
+        display.render_step().unwrap();
+        recorder.clear();
+        display.render_frame_blocking(&mut delay).unwrap();
+        assert_eq!(recorder.check_protocol(32, 8), Ok(()));
+        assert_eq!(recorder.rising_edges(Signal::Clk), 4 * 8 * 32);
+    }
+
+    #[test]
+    fn test_capture() {
+        let mut display = Hub75Display::<_, 4, 2, 6>::new(mock_pins()).unwrap();
+        display.set_pixel(1, 0, Hub75Color::white()).unwrap();
+        display.set_pixel(3, 1, Hub75Color::new(0, 21, 63)).unwrap();
+        display.set_brightness(Brightness::new(10));
+
+        let mut rgb = [0xAA; 25];
+        assert_eq!(
+            display.capture(&mut rgb[..23]),
+            Err(Hub75Error::BufferOverflow)
+        );
+        assert_eq!(display.capture(&mut rgb), Ok(24));
+        assert_eq!(&rgb[..6], &[0, 0, 0, 252, 252, 252]);
+        assert_eq!(&rgb[21..], &[0, 84, 252, 0xAA]);
+
+        let mut png = std::vec::Vec::new();
+        display.capture_png(&mut png, 1).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(&png[16..24], &[0, 0, 0, 4, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    fn test_new_checked() {
+        use crate::pins::{AddressPins, Hub75ControlPins, Hub75RgbPins};
+        use crate::waveform::{Signal, WaveformRecorder};
+
+        let recorder = WaveformRecorder::new();
+        let pin = |signal| recorder.pin(signal);
+        let rgb = Hub75RgbPins {
+            r1: pin(Signal::R1),
+            g1: pin(Signal::G1),
+            b1: pin(Signal::B1),
+            r2: pin(Signal::R2),
+            g2: pin(Signal::G2),
+            b2: pin(Signal::B2),
+        };
+        let address = AddressPins::abc(pin(Signal::A), pin(Signal::B), pin(Signal::C));
+        let control = Hub75ControlPins {
+            clk: pin(Signal::Clk),
+            lat: pin(Signal::Lat),
+            oe: pin(Signal::Oe),
+        };
+
+        let mut display = Hub75Display::<_, 32, 16, 4>::new_checked(rgb, address, control).unwrap();
+        display.fill(Hub75Color::white());
+        display
+            .render_frame_blocking(&mut recorder.delay())
+            .unwrap();
+        assert_eq!(recorder.check_protocol(32, 8), Ok(()));
+    }
+
+    #[test]
+    fn test_timing_calibration() {
+        use crate::timing::PlaneTimer;
+        use crate::waveform::{RecordingDelay, WaveformRecorder};
+
+        /// Delay overshooting every request by a fixed time
+        struct SlowDelay(RecordingDelay);
+
+        impl embedded_hal::delay::DelayNs for SlowDelay {
+            fn delay_ns(&mut self, ns: u32) {
+                embedded_hal::delay::DelayNs::delay_ns(&mut self.0, ns + 500);
+            }
+        }
+
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        display.set_refresh_interval_ns(2_000);
+        let mut delay = SlowDelay(recorder.delay());
+        let clock_recorder = recorder.clone();
+        let mut timer = PlaneTimer::new(move || clock_recorder.time_ns() as u32);
+
+        // The overshoot and turning the output off add to every plane
+        display
+            .render_frame_blocking_timed(&mut delay, &mut timer)
+            .unwrap();
+        let requested = timer.requested_ns(0).unwrap();
+        assert_eq!(timer.measured_ns(0), Some(requested + 510));
+        assert_eq!(timer.overhead_ns(), Some(510));
+        assert!(timer.shift_ns().unwrap() > 32 * 10);
+        assert_eq!(display.timing_overhead_ns(), 0);
+
+        // Auto-calibration takes the overhead off every delay
+        timer.set_auto_calibration(true);
+        display
+            .render_frame_blocking_timed(&mut delay, &mut timer)
+            .unwrap();
+        assert_eq!(display.timing_overhead_ns(), 510);
+        display
+            .render_frame_blocking_timed(&mut delay, &mut timer)
+            .unwrap();
+        for subframe in 0..4 {
+            assert_eq!(timer.deviation_ns(subframe), Some(0));
+        }
+        assert_eq!(display.timing_overhead_ns(), 510);
+        assert_eq!(display.render_step().unwrap(), requested - 510);
+        assert_eq!(recorder.check_protocol(32, 8), Ok(()));
+    }
+
+    #[test]
+    fn test_plane_order() {
+        use crate::waveform::WaveformRecorder;
+
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        display.set_refresh_interval_ns(1_000);
+        display.set_brightness(Brightness::MAX);
+        display.set_pixel(7, 3, Hub75Color::new(8, 0, 0)).unwrap();
+        assert_eq!(display.plane_order(), PlaneOrder::LsbFirst);
+
+        // A partial frame is restarted with the new order
+        display.render_step().unwrap();
+        display.set_plane_order(PlaneOrder::MsbFirst);
+        let durations: heapless::Vec<u32, 32> =
+            (0..32).map(|_| display.render_step().unwrap()).collect();
+        assert_eq!(durations[..8], [8_000; 8]);
+        assert_eq!(durations[24..], [1_000; 8]);
+        assert_eq!(display.scan_step, 0);
+
+        // The row holding the pixel is lit in the first, most significant plane
+        assert_eq!(display.scan_position(3), (3, 3));
+        assert!(display.scan_row_subframe(3, 3).unwrap()[7].0);
+
+        let mut delay = recorder.delay();
+        recorder.clear();
+        display.render_frame_blocking(&mut delay).unwrap();
+        assert_eq!(recorder.check_protocol(32, 8), Ok(()));
+    }
+
+    #[test]
+    fn test_scan_order() {
+        use crate::waveform::WaveformRecorder;
+
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        display.fill(Hub75Color::new(9, 3, 15));
+
+        let positions = |display: &Hub75Display<_, 32, 16, 4>| {
+            let mut positions: heapless::Vec<(usize, usize), 32> =
+                (0..32).map(|step| display.scan_position(step)).collect();
+            let ordered = positions.clone();
+            positions.sort_unstable();
+            // Every row is lit in every plane exactly once
+            assert!(positions
+                .iter()
+                .copied()
+                .eq((0..8).flat_map(|row| (0..4).map(move |plane| (row, plane)))));
+            ordered
+        };
+
+        assert_eq!(positions(&display)[..3], [(0, 0), (1, 0), (2, 0)]);
+        display.set_scan_order(ScanOrder::RowMajor);
+        assert_eq!(display.scan_order(), ScanOrder::RowMajor);
+        assert_eq!(
+            positions(&display)[..5],
+            [(0, 0), (0, 1), (0, 2), (0, 3), (1, 0)]
+        );
+        display.set_plane_order(PlaneOrder::MsbFirst);
+        assert_eq!(positions(&display)[..2], [(0, 3), (0, 2)]);
+        display.set_plane_order(PlaneOrder::LsbFirst);
+        display.set_scan_order(ScanOrder::Interleaved);
+        assert_eq!(positions(&display)[..3], [(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(positions(&display)[8..10], [(0, 1), (1, 2)]);
+
+        let mut delay = recorder.delay();
+        display.render_frame_blocking(&mut delay).unwrap();
+        assert_eq!(recorder.check_protocol(32, 8), Ok(()));
+    }
+
+    #[test]
+    fn test_modulation() {
+        use crate::modulation::{Hybrid, Pwm};
+        use crate::waveform::{Signal, WaveformRecorder};
+
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        display.set_refresh_interval_ns(1_000);
+        display.set_brightness(Brightness::MAX);
+        display.set_pixel(7, 3, Hub75Color::new(5, 0, 15)).unwrap();
+        assert_eq!(display.subframes(), 4);
+
+        // One equal subframe per level, lit while the level is above it
+        display.set_modulation(&Pwm);
+        assert_eq!(display.subframes(), 15);
+        assert_eq!(display.bit_plane_duration_ns(0, 14), 1_000);
+        let red = |display: &Hub75Display<_, 32, 16, 4>, subframe| {
+            display.scan_row_subframe(3, subframe).unwrap()[7].0
+        };
+        assert!(red(&display, 4) && !red(&display, 5));
+        assert_eq!(
+            display.scan_row_subframe(3, 15),
+            Err(Hub75Error::InvalidColor)
+        );
+
+        let mut delay = recorder.delay();
+        display.render_frame_blocking(&mut delay).unwrap();
+        assert_eq!(recorder.check_protocol(32, 8), Ok(()));
+        assert_eq!(recorder.rising_edges(Signal::Clk), 15 * 8 * 32);
+
+        // Two BCM subframes, the lower two bits dithered over frames
+        display.set_modulation(&Hybrid { temporal_bits: 2 });
+        assert_eq!(display.subframes(), 2);
+        assert_eq!(display.bit_plane_duration_ns(0, 1), 8_000);
+        // Red level 5 is shown as 1 in three frames and 2 in one
+        let lit_frames = (0..4)
+            .filter(|_| {
+                let lit = red(&display, 1);
+                assert_ne!(lit, red(&display, 0));
+                display.render_frame_blocking(&mut delay).unwrap();
+                lit
+            })
+            .count();
+        assert_eq!(lit_frames, 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_render_frame_blocking_matches_async() {
+        use crate::waveform::WaveformRecorder;
+        use core::future::Future;
+        use core::task::{Context, Waker};
+
+        let capture = |blocking: bool| {
+            let recorder = WaveformRecorder::new();
+            let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+            display.fill(Hub75Color::new(9, 3, 15));
+            let mut delay = recorder.delay();
+            if blocking {
+                display.render_frame_blocking(&mut delay).unwrap();
+            } else {
+                let mut future = core::pin::pin!(display.render_frame(&mut delay));
+                assert!(future
+                    .as_mut()
+                    .poll(&mut Context::from_waker(Waker::noop()))
+                    .is_ready());
+            }
+            let mut vcd = std::vec::Vec::new();
+            recorder.write_vcd(&mut vcd).unwrap();
+            vcd
+        };
+        assert!(capture(true) == capture(false));
+    }
+
+    /// Delay that returns immediately, counting the calls
+    #[cfg(feature = "async")]
+    struct NoDelay {
+        calls: usize,
+    }
+
+    #[cfg(feature = "async")]
+    impl DelayNs for NoDelay {
+        async fn delay_ns(&mut self, _ns: u32) {
+            self.calls += 1;
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_play_animation() {
+        use crate::animation::{AnimationData, AnimationEffect};
+        use core::future::Future;
+        use core::task::{Context, Poll, Waker};
+
+        let mut frames = [Hub75FrameBuffer::new(), Hub75FrameBuffer::new()];
+        frames[1].fill(Hub75Color::blue());
+        let mut animation =
+            Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 4).unwrap();
+        let mut display = Hub75Display::<_, 64, 32, 6>::new(mock_pins()).unwrap();
+        display.set_double_buffering(true);
+        let mut delay = NoDelay { calls: 0 };
+
+        // Nothing in the driver actually waits, so a single poll completes
+        let result = {
+            let mut future = core::pin::pin!(display.play_animation(&mut animation, &mut delay));
+            future
+                .as_mut()
+                .poll(&mut Context::from_waker(Waker::noop()))
+        };
+        assert!(matches!(result, Poll::Ready(Ok(()))));
+
+        assert!(animation.is_done());
+        assert_eq!(display.get_pixel(0, 0).unwrap(), Hub75Color::blue());
+        assert!(delay.calls > 0);
+    }
+
+    #[test]
+    fn test_single_buffer_storage() {
+        use crate::buffering::SingleBuffer;
+
+        let mut display =
+            Hub75Display::<_, 64, 32, 6, SingleBuffer<64, 32, 6>>::new(mock_pins()).unwrap();
+        display.set_double_buffering(true);
+        assert!(!display.is_double_buffered());
+
+        // Drawing is immediately visible and swapping is a no-op
+        display.set_pixel(3, 4, Hub75Color::green()).unwrap();
+        assert_eq!(display.get_pixel(3, 4).unwrap(), Hub75Color::green());
+        display.swap_buffers();
+        assert_eq!(display.get_pixel(3, 4).unwrap(), Hub75Color::green());
+        assert!(!display.swap_buffers_if_changed());
+
+        assert!(
+            core::mem::size_of::<Hub75Display<MockPin, 64, 32, 6, SingleBuffer<64, 32, 6>>>()
+                < core::mem::size_of::<Hub75Display<MockPin, 64, 32, 6>>()
+        );
+    }
+
+    #[test]
+    fn test_deep_color_storage() {
+        use crate::color::Hub75Color16;
+
+        type Deep = DoubleBuffer<4, 2, 12, Hub75Color16<12>>;
+        let mut display = Hub75Display::<_, 4, 2, 12, Deep>::new(mock_pins()).unwrap();
+        display
+            .set_pixel(1, 0, Hub75Color16::new(2048, 1, 4095))
+            .unwrap();
+        assert_eq!(
+            display.get_pixel(1, 0).unwrap(),
+            Hub75Color16::new(2048, 1, 4095)
+        );
+
+        // Every one of the 12 bit planes is scanned out
+        let top = display.scan_row_bit_plane(0, 11).unwrap();
+        assert_eq!(top[1], (true, false, true, false, false, false));
+        let lowest = display.scan_row_bit_plane(0, 0).unwrap();
+        assert_eq!(lowest[1], (false, true, true, false, false, false));
+
+        // Scan-time correction works at the full depth
+        display.set_color_balance(128, 255, 255);
+        let corrected = display.scan_row_bit_plane(0, 10).unwrap();
+        assert_eq!(corrected[1], (true, false, true, false, false, false));
+        assert!(display.render_step().is_ok());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_mixed_pin_types() {
+        use crate::pins::{EitherPin, Hub75AddressPins, Hub75ControlPins};
+        use crate::waveform::{Signal, WaveformRecorder};
+        use core::future::Future;
+        use core::task::{Context, Waker};
+
+        // MCU pins for color data, an expander-style pin for address E and
+        // a different pin type for the control lines
+        let recorder = WaveformRecorder::new();
+        let address = |signal| EitherPin::<MockPin, _>::Right(recorder.pin(signal));
+        let pins = Hub75Pins {
+            rgb: mock_pins().rgb,
+            address: Hub75AddressPins {
+                a: EitherPin::Left(MockPin::new()),
+                b: EitherPin::Left(MockPin::new()),
+                c: EitherPin::Left(MockPin::new()),
+                d: Some(EitherPin::Left(MockPin::new())),
+                e: Some(address(Signal::E)),
+            },
+            control: Hub75ControlPins {
+                clk: recorder.pin(Signal::Clk),
+                lat: recorder.pin(Signal::Lat),
+                oe: recorder.pin(Signal::Oe),
+            },
+        };
+
+        let mut display =
+            Hub75Display::<_, 64, 64, 2, DoubleBuffer<64, 64, 2>, _, _>::new(pins).unwrap();
+        let mut delay = recorder.delay();
+        let mut future = core::pin::pin!(display.render_frame(&mut delay));
+        assert!(future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+            .is_ready());
+
+        assert_eq!(recorder.rising_edges(Signal::Clk), 2 * 32 * 64);
+        assert_eq!(recorder.rising_edges(Signal::E), 2);
+    }
 }
@@ -0,0 +1,485 @@
+//! Receiving DMX universes from lighting software
+//!
+//! A [`DmxReceiver`] turns Art-Net (feature `artnet`) and sACN / E1.31
+//! (feature `sacn`) packets into pixels of its own frame buffer, so the panel
+//! can be patched into existing lighting software as a fixture. The network
+//! stack stays with the application: hand each UDP payload received on port
+//! 6454 (Art-Net) or 5568 (sACN) to [`DmxReceiver::handle`], for example from
+//! an embassy-net or smoltcp socket.
+//!
+//! Each universe carries up to 170 RGB pixels. Universes are mapped onto the
+//! panel in order starting at the first universe, each covering a run of
+//! pixels in row-major order. By default a universe covers as many whole rows
+//! as fit, so a 64 pixel wide panel takes two rows per universe.
+//!
+//! The receiver reports [`DmxEvent::Present`] when the frame should be shown.
+//! Without synchronisation that is when every mapped universe has arrived.
+//! Once the sender uses Art-Net's ArtSync or gives sACN packets a sync
+//! address, data only updates the frame and the sync packet presents it, so
+//! several fixtures change in step.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::Hub75FrameBuffer;
+//! use hub75::dmx::{DmxEvent, DmxReceiver};
+//!
+//! # fn receive_udp(_buffer: &mut [u8]) -> usize { 0 }
+//! let mut receiver = DmxReceiver::<64, 32, 6>::new(0);
+//! let mut display_frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! let mut buffer = [0u8; 638];
+//! loop {
+//!     // Payload of a datagram received on the Art-Net or sACN port
+//!     let length = receive_udp(&mut buffer);
+//!     if let Ok(Some(DmxEvent::Present)) = receiver.handle(&buffer[..length]) {
+//!         display_frame.copy_from(receiver.frame());
+//!     }
+//! }
+//! ```
+
+use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer, AnimationError, Hub75Error};
+
+/// Channels in a DMX universe
+pub const DMX_CHANNELS: usize = 512;
+/// RGB pixels that fit in a universe
+pub const MAX_PIXELS_PER_UNIVERSE: usize = DMX_CHANNELS / 3;
+/// Universes tracked for presenting frames without synchronisation
+pub const MAX_TRACKED_UNIVERSES: usize = 64;
+
+/// UDP port Art-Net is sent to
+#[cfg(feature = "artnet")]
+pub const ARTNET_PORT: u16 = 6454;
+/// UDP port sACN is sent to
+#[cfg(feature = "sacn")]
+pub const SACN_PORT: u16 = 5568;
+
+#[cfg(feature = "artnet")]
+const ARTNET_ID: &[u8; 8] = b"Art-Net\0";
+#[cfg(feature = "artnet")]
+const OP_DMX: u16 = 0x5000;
+#[cfg(feature = "artnet")]
+const OP_SYNC: u16 = 0x5200;
+
+#[cfg(feature = "sacn")]
+const ACN_PREAMBLE: &[u8; 16] = b"\x00\x10\x00\x00ASC-E1.17\x00\x00\x00";
+#[cfg(feature = "sacn")]
+const VECTOR_ROOT_DATA: u32 = 0x0000_0004;
+#[cfg(feature = "sacn")]
+const VECTOR_ROOT_EXTENDED: u32 = 0x0000_0008;
+#[cfg(feature = "sacn")]
+const VECTOR_FRAMING_DATA: u32 = 0x0000_0002;
+#[cfg(feature = "sacn")]
+const VECTOR_FRAMING_SYNC: u32 = 0x0000_0001;
+#[cfg(feature = "sacn")]
+const VECTOR_DMP_SET_PROPERTY: u8 = 0x02;
+#[cfg(feature = "sacn")]
+const OPTION_PREVIEW: u8 = 0x80;
+
+/// Outcome of a packet that changed the receiver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DmxEvent {
+    /// Pixels of a universe were updated
+    Updated {
+        /// Universe that was received
+        universe: u16,
+    },
+    /// The frame is complete and should be shown
+    Present,
+}
+
+/// Where synchronised frames come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sync {
+    /// Present once every universe has arrived
+    Unsynchronised,
+    /// Present on ArtSync
+    #[cfg(feature = "artnet")]
+    ArtNet,
+    /// Present on an sACN sync packet for this address
+    #[cfg(feature = "sacn")]
+    Sacn(u16),
+}
+
+/// Maps DMX universes onto a frame buffer
+pub struct DmxReceiver<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Frame being built
+    frame: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    /// Universe holding the top-left pixel
+    first_universe: u16,
+    /// Pixels carried by each universe
+    pixels_per_universe: usize,
+    /// Universes received since the last present, by offset from the first
+    received: u64,
+    /// How frames are being synchronised
+    sync: Sync,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    DmxReceiver<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a receiver whose top-left pixel is in `first_universe`
+    ///
+    /// Each universe covers as many whole rows as fit in 170 pixels, or 170
+    /// pixels if a single row is wider.
+    pub fn new(first_universe: u16) -> Self {
+        let rows = (MAX_PIXELS_PER_UNIVERSE / WIDTH.max(1)).max(1);
+        Self {
+            frame: Hub75FrameBuffer::new(),
+            first_universe,
+            pixels_per_universe: (rows * WIDTH).clamp(1, MAX_PIXELS_PER_UNIVERSE),
+            received: 0,
+            sync: Sync::Unsynchronised,
+        }
+    }
+
+    /// Get the frame built from the universes received so far
+    pub fn frame(&self) -> &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS> {
+        &self.frame
+    }
+
+    /// Set the universe holding the top-left pixel
+    pub fn set_first_universe(&mut self, universe: u16) {
+        self.first_universe = universe;
+        self.received = 0;
+    }
+
+    /// Get the universe holding the top-left pixel
+    pub fn first_universe(&self) -> u16 {
+        self.first_universe
+    }
+
+    /// Make each universe cover `rows` whole rows
+    ///
+    /// Returns [`Hub75Error::BufferOverflow`] if the rows hold more than 170
+    /// pixels.
+    pub fn set_rows_per_universe(&mut self, rows: usize) -> Result<(), Hub75Error> {
+        self.set_pixels_per_universe(rows * WIDTH)
+    }
+
+    /// Make each universe cover `pixels` pixels in row-major order
+    ///
+    /// Returns [`Hub75Error::BufferOverflow`] if `pixels` is zero or above 170.
+    pub fn set_pixels_per_universe(&mut self, pixels: usize) -> Result<(), Hub75Error> {
+        if pixels == 0 || pixels > MAX_PIXELS_PER_UNIVERSE {
+            return Err(Hub75Error::BufferOverflow);
+        }
+        self.pixels_per_universe = pixels;
+        self.received = 0;
+        Ok(())
+    }
+
+    /// Get the number of pixels each universe covers
+    pub fn pixels_per_universe(&self) -> usize {
+        self.pixels_per_universe
+    }
+
+    /// Get the number of universes needed to cover the panel
+    ///
+    /// Frames spanning more than [`MAX_TRACKED_UNIVERSES`] universes are only
+    /// presented by sync packets.
+    pub fn universe_count(&self) -> usize {
+        (WIDTH * HEIGHT).div_ceil(self.pixels_per_universe)
+    }
+
+    /// Get the universe and first channel (counting from 1) of a pixel
+    pub fn address_of(&self, x: usize, y: usize) -> Option<(u16, u16)> {
+        if x >= WIDTH || y >= HEIGHT {
+            return None;
+        }
+        let pixel = y * WIDTH + x;
+        let universe = self.first_universe as usize + pixel / self.pixels_per_universe;
+        let channel = (pixel % self.pixels_per_universe) * 3 + 1;
+        Some((u16::try_from(universe).ok()?, channel as u16))
+    }
+
+    /// Check if frames are presented by sync packets
+    pub fn is_synchronised(&self) -> bool {
+        self.sync != Sync::Unsynchronised
+    }
+
+    /// Go back to presenting frames once every universe has arrived
+    ///
+    /// Art-Net senders stop sending ArtSync without notice; the specification
+    /// asks receivers to fall back after four seconds without one, which the
+    /// application can do by calling this.
+    pub fn clear_sync(&mut self) {
+        self.sync = Sync::Unsynchronised;
+    }
+
+    /// Handle an Art-Net or sACN packet, detected from its header
+    ///
+    /// Returns `Ok(None)` for packets that are valid but do not change the
+    /// frame, such as polls, preview data and universes outside the panel,
+    /// and [`AnimationError::InvalidData`] for malformed packets.
+    pub fn handle(&mut self, packet: &[u8]) -> Result<Option<DmxEvent>, Hub75Error> {
+        #[cfg(feature = "artnet")]
+        if packet.starts_with(ARTNET_ID) {
+            return self.handle_artnet(packet);
+        }
+        #[cfg(feature = "sacn")]
+        if packet.starts_with(ACN_PREAMBLE) {
+            return self.handle_sacn(packet);
+        }
+        Err(AnimationError::InvalidData.into())
+    }
+
+    /// Handle an Art-Net packet
+    #[cfg(feature = "artnet")]
+    pub fn handle_artnet(&mut self, packet: &[u8]) -> Result<Option<DmxEvent>, Hub75Error> {
+        if packet.len() < 10 || !packet.starts_with(ARTNET_ID) {
+            return Err(AnimationError::InvalidData.into());
+        }
+        match u16::from_le_bytes([packet[8], packet[9]]) {
+            OP_DMX => {
+                let header = packet.get(..18).ok_or(AnimationError::InvalidData)?;
+                let universe = u16::from_le_bytes([header[14], header[15] & 0x7F]);
+                let length = u16::from_be_bytes([header[16], header[17]]) as usize;
+                let data = packet
+                    .get(18..18 + length.min(DMX_CHANNELS))
+                    .ok_or(AnimationError::InvalidData)?;
+                Ok(self.apply(universe, data))
+            }
+            OP_SYNC => {
+                self.sync = Sync::ArtNet;
+                Ok(self.present())
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Handle an sACN / E1.31 packet
+    #[cfg(feature = "sacn")]
+    pub fn handle_sacn(&mut self, packet: &[u8]) -> Result<Option<DmxEvent>, Hub75Error> {
+        let be16 = |at: usize| u16::from_be_bytes([packet[at], packet[at + 1]]);
+        let be32 = |at: usize| {
+            u32::from_be_bytes([packet[at], packet[at + 1], packet[at + 2], packet[at + 3]])
+        };
+        if packet.len() < 44 || !packet.starts_with(ACN_PREAMBLE) {
+            return Err(AnimationError::InvalidData.into());
+        }
+
+        match (be32(18), be32(40)) {
+            (VECTOR_ROOT_DATA, VECTOR_FRAMING_DATA) => {
+                if packet.len() < 126 || packet[117] != VECTOR_DMP_SET_PROPERTY {
+                    return Err(AnimationError::InvalidData.into());
+                }
+                // Preview data and alternate start codes are not for display
+                if packet[112] & OPTION_PREVIEW != 0 || packet[125] != 0 {
+                    return Ok(None);
+                }
+                let sync_address = be16(109);
+                let universe = be16(113);
+                let count = (be16(123) as usize).saturating_sub(1).min(DMX_CHANNELS);
+                let data = packet
+                    .get(126..126 + count)
+                    .ok_or(AnimationError::InvalidData)?;
+                self.sync = match sync_address {
+                    0 => Sync::Unsynchronised,
+                    address => Sync::Sacn(address),
+                };
+                Ok(self.apply(universe, data))
+            }
+            (VECTOR_ROOT_EXTENDED, VECTOR_FRAMING_SYNC) => {
+                if packet.len() < 47 {
+                    return Err(AnimationError::InvalidData.into());
+                }
+                if self.sync == Sync::Sacn(be16(45)) {
+                    Ok(self.present())
+                } else {
+                    Ok(None)
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Write a universe's channels into the frame
+    fn apply(&mut self, universe: u16, data: &[u8]) -> Option<DmxEvent> {
+        let offset = universe.checked_sub(self.first_universe)? as usize;
+        if offset >= self.universe_count() {
+            return None;
+        }
+
+        let first_pixel = offset * self.pixels_per_universe;
+        for (i, rgb) in data
+            .chunks_exact(3)
+            .take(self.pixels_per_universe)
+            .enumerate()
+        {
+            let pixel = first_pixel + i;
+            let color = Hub75Color::from_rgb8(rgb[0], rgb[1], rgb[2]);
+            let _ = self.frame.set_pixel(pixel % WIDTH, pixel / WIDTH, color);
+        }
+
+        if offset < MAX_TRACKED_UNIVERSES {
+            self.received |= 1 << offset;
+        }
+        let count = self.universe_count();
+        let all = if count >= 64 {
+            u64::MAX
+        } else {
+            (1 << count) - 1
+        };
+        if self.sync == Sync::Unsynchronised
+            && count <= MAX_TRACKED_UNIVERSES
+            && self.received == all
+        {
+            return self.present();
+        }
+        Some(DmxEvent::Updated { universe })
+    }
+
+    /// Start collecting the next frame
+    fn present(&mut self) -> Option<DmxEvent> {
+        self.received = 0;
+        Some(DmxEvent::Present)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestReceiver = DmxReceiver<4, 4, 6>;
+
+    #[cfg(feature = "artnet")]
+    fn art_dmx(universe: u16, data: &[u8]) -> ([u8; 64], usize) {
+        let mut packet = [0; 64];
+        packet[..8].copy_from_slice(ARTNET_ID);
+        packet[8..10].copy_from_slice(&OP_DMX.to_le_bytes());
+        packet[11] = 14;
+        packet[14..16].copy_from_slice(&universe.to_le_bytes());
+        packet[16..18].copy_from_slice(&(data.len() as u16).to_be_bytes());
+        packet[18..18 + data.len()].copy_from_slice(data);
+        (packet, 18 + data.len())
+    }
+
+    #[cfg(feature = "sacn")]
+    fn sacn_data(universe: u16, sync_address: u16, data: &[u8]) -> ([u8; 160], usize) {
+        let mut packet = [0; 160];
+        packet[..16].copy_from_slice(ACN_PREAMBLE);
+        packet[18..22].copy_from_slice(&VECTOR_ROOT_DATA.to_be_bytes());
+        packet[40..44].copy_from_slice(&VECTOR_FRAMING_DATA.to_be_bytes());
+        packet[108] = 100;
+        packet[109..111].copy_from_slice(&sync_address.to_be_bytes());
+        packet[113..115].copy_from_slice(&universe.to_be_bytes());
+        packet[117] = VECTOR_DMP_SET_PROPERTY;
+        packet[118] = 0xA1;
+        packet[122] = 1;
+        packet[123..125].copy_from_slice(&(data.len() as u16 + 1).to_be_bytes());
+        packet[126..126 + data.len()].copy_from_slice(data);
+        (packet, 126 + data.len())
+    }
+
+    #[test]
+    fn test_mapping() {
+        let mut receiver = TestReceiver::new(1);
+        assert_eq!(receiver.pixels_per_universe(), 168);
+        assert_eq!(receiver.universe_count(), 1);
+
+        receiver.set_rows_per_universe(1).unwrap();
+        assert_eq!(receiver.universe_count(), 4);
+        assert_eq!(receiver.address_of(1, 2), Some((3, 4)));
+        assert_eq!(receiver.address_of(4, 0), None);
+        assert_eq!(
+            receiver.set_pixels_per_universe(171),
+            Err(Hub75Error::BufferOverflow)
+        );
+
+        // Wide panels split rows across universes
+        let wide = DmxReceiver::<200, 2, 6>::new(0);
+        assert_eq!(wide.pixels_per_universe(), 170);
+        assert_eq!(wide.address_of(199, 0), Some((1, 88)));
+    }
+
+    #[cfg(feature = "artnet")]
+    #[test]
+    fn test_artnet() {
+        let mut receiver = TestReceiver::new(0);
+        receiver.set_rows_per_universe(2).unwrap();
+
+        let (packet, length) = art_dmx(1, &[0, 0, 0, 255, 0, 0]);
+        assert_eq!(
+            receiver.handle(&packet[..length]),
+            Ok(Some(DmxEvent::Updated { universe: 1 }))
+        );
+        assert_eq!(receiver.frame().get_pixel(1, 2).unwrap(), Hub75Color::red());
+
+        // The last missing universe completes the frame
+        let (packet, length) = art_dmx(0, &[]);
+        assert_eq!(
+            receiver.handle(&packet[..length]),
+            Ok(Some(DmxEvent::Present))
+        );
+
+        // Universes outside the panel and other opcodes are ignored
+        let (packet, length) = art_dmx(2, &[255, 255, 255]);
+        assert_eq!(receiver.handle(&packet[..length]), Ok(None));
+        let (mut poll, _) = art_dmx(0, &[]);
+        poll[8..10].copy_from_slice(&0x2000u16.to_le_bytes());
+        assert_eq!(receiver.handle(&poll[..14]), Ok(None));
+        assert!(receiver.handle(&packet[..12]).is_err());
+
+        // After an ArtSync only sync packets present
+        let mut sync = [0; 14];
+        sync[..8].copy_from_slice(ARTNET_ID);
+        sync[8..10].copy_from_slice(&OP_SYNC.to_le_bytes());
+        assert_eq!(receiver.handle(&sync), Ok(Some(DmxEvent::Present)));
+        assert!(receiver.is_synchronised());
+        for universe in 0..2 {
+            let (packet, length) = art_dmx(universe, &[]);
+            assert_eq!(
+                receiver.handle(&packet[..length]),
+                Ok(Some(DmxEvent::Updated { universe }))
+            );
+        }
+        assert_eq!(receiver.handle(&sync), Ok(Some(DmxEvent::Present)));
+    }
+
+    #[cfg(feature = "sacn")]
+    #[test]
+    fn test_sacn() {
+        let mut receiver = TestReceiver::new(1);
+
+        let (packet, length) = sacn_data(1, 0, &[0, 0, 255]);
+        assert_eq!(
+            receiver.handle(&packet[..length]),
+            Ok(Some(DmxEvent::Present))
+        );
+        assert_eq!(
+            receiver.frame().get_pixel(0, 0).unwrap(),
+            Hub75Color::blue()
+        );
+
+        // Preview data is not shown
+        let (mut packet, length) = sacn_data(1, 0, &[0, 255, 0]);
+        packet[112] = OPTION_PREVIEW;
+        assert_eq!(receiver.handle(&packet[..length]), Ok(None));
+        assert_eq!(
+            receiver.frame().get_pixel(0, 0).unwrap(),
+            Hub75Color::blue()
+        );
+
+        // With a sync address, the matching sync packet presents
+        let (packet, length) = sacn_data(1, 7, &[0, 255, 0]);
+        assert_eq!(
+            receiver.handle(&packet[..length]),
+            Ok(Some(DmxEvent::Updated { universe: 1 }))
+        );
+        let mut sync = [0; 49];
+        sync[..16].copy_from_slice(ACN_PREAMBLE);
+        sync[18..22].copy_from_slice(&VECTOR_ROOT_EXTENDED.to_be_bytes());
+        sync[40..44].copy_from_slice(&VECTOR_FRAMING_SYNC.to_be_bytes());
+        sync[45..47].copy_from_slice(&8u16.to_be_bytes());
+        assert_eq!(receiver.handle(&sync), Ok(None));
+        sync[45..47].copy_from_slice(&7u16.to_be_bytes());
+        assert_eq!(receiver.handle(&sync), Ok(Some(DmxEvent::Present)));
+        assert_eq!(
+            receiver.frame().get_pixel(0, 0).unwrap(),
+            Hub75Color::green()
+        );
+
+        assert!(receiver.handle(&packet[..100]).is_err());
+    }
+}
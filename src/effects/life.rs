@@ -0,0 +1,526 @@
+//! Cellular automata such as Conway's Game of Life
+//!
+//! A [`Life`] board stores one bit per cell, packed into `WORDS` 32-bit words
+//! per row, so a 64x32 board takes 256 bytes per generation. Each
+//! generation is computed from the last by a [`Rule`] giving the neighbour
+//! counts at which dead cells are born and live cells survive; the classic
+//! Game of Life is [`Rule::LIFE`], and other rules can be parsed from the
+//! usual `B3/S23` notation.
+//!
+//! Ambient displays run for days, so a board can reseed itself once it
+//! settles into still lifes and blinkers.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{AnimationState, Hub75Color, Hub75FrameBuffer};
+//! use hub75::effects::{CellColors, Life, Rule};
+//!
+//! let mut life = Life::<64, 32, 6, 2>::new(Rule::LIFE, CellColors::Rainbow);
+//! life.randomize(7, 80);
+//! life.set_frames_per_generation(4);
+//! life.set_reseed_after(Some(30));
+//!
+//! let mut frame = Hub75FrameBuffer::new();
+//! assert!(matches!(life.next_into(&mut frame), AnimationState::Rendered));
+//! ```
+
+use crate::{
+    animation::AnimationState,
+    color::{Hsv, Hub75Color},
+    frame_buffer::Hub75FrameBuffer,
+    math,
+    scene::FrameSource,
+    AnimationError, Hub75Error,
+};
+
+/// Words of cell bits needed for a row of `width` cells
+pub const fn words_for(width: usize) -> usize {
+    width.div_ceil(32)
+}
+
+/// Neighbour counts at which cells are born and survive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Rule {
+    /// Bit `n` set when a dead cell with `n` live neighbours is born
+    birth: u16,
+    /// Bit `n` set when a live cell with `n` live neighbours survives
+    survival: u16,
+}
+
+impl Rule {
+    /// Conway's Game of Life, `B3/S23`
+    pub const LIFE: Self = Self::new(1 << 3, 1 << 2 | 1 << 3);
+    /// HighLife, `B36/S23`, known for its self-replicating patterns
+    pub const HIGH_LIFE: Self = Self::new(1 << 3 | 1 << 6, 1 << 2 | 1 << 3);
+    /// Seeds, `B2/S`, where every cell dies each generation
+    pub const SEEDS: Self = Self::new(1 << 2, 0);
+    /// Day & Night, `B3678/S34678`, symmetric between live and dead cells
+    pub const DAY_AND_NIGHT: Self = Self::new(
+        1 << 3 | 1 << 6 | 1 << 7 | 1 << 8,
+        1 << 3 | 1 << 4 | 1 << 6 | 1 << 7 | 1 << 8,
+    );
+
+    /// Create a rule from neighbour count bitmasks
+    ///
+    /// Bit `n` of `birth` makes dead cells with `n` live neighbours come
+    /// alive; bit `n` of `survival` keeps live cells with `n` live neighbours
+    /// alive. Bits above 8 are ignored.
+    pub const fn new(birth: u16, survival: u16) -> Self {
+        Self {
+            birth: birth & 0x1FF,
+            survival: survival & 0x1FF,
+        }
+    }
+
+    /// Parse a rule in `B3/S23` notation
+    ///
+    /// Returns [`AnimationError::InvalidData`] for anything else.
+    pub fn parse(rule: &str) -> Result<Self, Hub75Error> {
+        let (birth, survival) = rule.split_once('/').ok_or(AnimationError::InvalidData)?;
+        let counts = |part: &str, prefix: char| -> Result<u16, Hub75Error> {
+            let digits = part
+                .strip_prefix(prefix)
+                .or_else(|| part.strip_prefix(prefix.to_ascii_lowercase()))
+                .ok_or(AnimationError::InvalidData)?;
+            digits.chars().try_fold(0, |mask, digit| match digit {
+                '0'..='8' => Ok(mask | 1 << (digit as u8 - b'0')),
+                _ => Err(AnimationError::InvalidData.into()),
+            })
+        };
+        Ok(Self::new(counts(birth, 'B')?, counts(survival, 'S')?))
+    }
+
+    /// Check whether a cell is alive in the next generation
+    pub const fn next_state(&self, alive: bool, neighbours: u8) -> bool {
+        let mask = if alive { self.survival } else { self.birth };
+        neighbours <= 8 && mask & (1 << neighbours) != 0
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::LIFE
+    }
+}
+
+/// How live cells are colored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CellColors<const COLOR_BITS: usize> {
+    /// Every live cell the same color
+    Solid(Hub75Color<COLOR_BITS>),
+    /// Cells born this generation in one color, older cells in another
+    Births {
+        /// Color of cells that just came alive
+        born: Hub75Color<COLOR_BITS>,
+        /// Color of cells that survived from the last generation
+        alive: Hub75Color<COLOR_BITS>,
+    },
+    /// Hue by position, shifting a little every generation
+    Rainbow,
+}
+
+/// Cellular automaton on a bitfield, drawn over the whole frame
+#[derive(Debug, Clone)]
+pub struct Life<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    const WORDS: usize,
+> {
+    /// Current generation, one bit per cell
+    cells: [[u32; WORDS]; HEIGHT],
+    /// Previous generation, for coloring births and spotting blinkers
+    previous: [[u32; WORDS]; HEIGHT],
+    /// Birth and survival rule
+    rule: Rule,
+    /// Live cell colors
+    colors: CellColors<COLOR_BITS>,
+    /// Dead cell color
+    background: Hub75Color<COLOR_BITS>,
+    /// Brightness kept by dying cells each frame, 0 for no trail
+    fade: u8,
+    /// Whether the edges wrap around to the opposite side
+    wrap: bool,
+    /// Frames each generation is shown for
+    frames_per_generation: u16,
+    /// Frames the current generation has been shown for
+    frame_counter: u16,
+    /// Generations computed since the board was seeded
+    generation: u32,
+    /// Settled generations before reseeding, if enabled
+    reseed_after: Option<u16>,
+    /// Generations in a row that repeated one of the last two
+    settled: u16,
+    /// Seed and density used by [`Self::randomize`]
+    seed: (u32, u8),
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const WORDS: usize>
+    Life<WIDTH, HEIGHT, COLOR_BITS, WORDS>
+{
+    /// Fails to compile when `WORDS` is too small for `WIDTH`
+    const ENOUGH_WORDS: () = assert!(WORDS * 32 >= WIDTH, "WORDS must be words_for(WIDTH)");
+
+    /// Create an empty board
+    ///
+    /// Generations advance every frame, edges wrap around and the board never
+    /// reseeds itself.
+    pub fn new(rule: Rule, colors: CellColors<COLOR_BITS>) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::ENOUGH_WORDS;
+        Self {
+            cells: [[0; WORDS]; HEIGHT],
+            previous: [[0; WORDS]; HEIGHT],
+            rule,
+            colors,
+            background: Hub75Color::black(),
+            fade: 0,
+            wrap: true,
+            frames_per_generation: 1,
+            frame_counter: 0,
+            generation: 0,
+            reseed_after: None,
+            settled: 0,
+            seed: (0, 64),
+        }
+    }
+
+    /// Set the birth and survival rule
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    /// Set how live cells are colored
+    pub fn set_colors(&mut self, colors: CellColors<COLOR_BITS>) {
+        self.colors = colors;
+    }
+
+    /// Set the color of dead cells
+    pub fn set_background(&mut self, color: Hub75Color<COLOR_BITS>) {
+        self.background = color;
+    }
+
+    /// Leave dying cells fading out, keeping `fade` of 255 brightness a frame
+    ///
+    /// Fading reads the previous frame back from the frame buffer, so it
+    /// needs the same buffer every frame. A fade of 0 disables trails.
+    pub fn set_fade(&mut self, fade: u8) {
+        self.fade = fade;
+    }
+
+    /// Set whether edges wrap around to the opposite side
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    /// Set how many frames each generation is shown for (at least 1)
+    pub fn set_frames_per_generation(&mut self, frames: u16) {
+        self.frames_per_generation = frames.max(1);
+    }
+
+    /// Reseed once the board has settled for this many generations
+    ///
+    /// A board counts as settled when a generation repeats one of the two
+    /// before it, which covers still lifes, blinkers and an empty board.
+    pub fn set_reseed_after(&mut self, generations: Option<u16>) {
+        self.reseed_after = generations;
+    }
+
+    /// Fill the board with random cells
+    ///
+    /// About `density` out of 255 cells come alive. The same seed always
+    /// gives the same board; automatic reseeding moves on to the next seed,
+    /// starting from seed 0 at a density of 64 if never randomized.
+    pub fn randomize(&mut self, seed: u32, density: u8) {
+        self.seed = (seed, density);
+        for (y, row) in self.cells.iter_mut().enumerate() {
+            for (word, bits) in row.iter_mut().enumerate() {
+                *bits = 0;
+                for bit in 0..32 {
+                    let x = word * 32 + bit;
+                    if x < WIDTH && (math::hash(x as u32, y as u32, seed) as u8) < density {
+                        *bits |= 1 << bit;
+                    }
+                }
+            }
+        }
+        self.previous = [[0; WORDS]; HEIGHT];
+        self.generation = 0;
+        self.settled = 0;
+        self.frame_counter = 0;
+    }
+
+    /// Kill every cell
+    pub fn clear(&mut self) {
+        self.cells = [[0; WORDS]; HEIGHT];
+        self.previous = [[0; WORDS]; HEIGHT];
+        self.generation = 0;
+        self.settled = 0;
+    }
+
+    /// Set whether a cell is alive
+    pub fn set_cell(&mut self, x: usize, y: usize, alive: bool) -> Result<(), Hub75Error> {
+        if x >= WIDTH || y >= HEIGHT {
+            return Err(Hub75Error::InvalidCoordinates);
+        }
+        if alive {
+            self.cells[y][x / 32] |= 1 << (x % 32);
+        } else {
+            self.cells[y][x / 32] &= !(1 << (x % 32));
+        }
+        Ok(())
+    }
+
+    /// Check whether a cell is alive, treating cells off the board as dead
+    pub fn cell(&self, x: usize, y: usize) -> bool {
+        x < WIDTH && y < HEIGHT && Self::bit(&self.cells, x, y)
+    }
+
+    /// Number of live cells
+    pub fn population(&self) -> u32 {
+        self.cells
+            .iter()
+            .flatten()
+            .map(|bits| bits.count_ones())
+            .sum()
+    }
+
+    /// Generations computed since the board was seeded
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Read a cell from a grid, with coordinates already in range
+    fn bit(grid: &[[u32; WORDS]; HEIGHT], x: usize, y: usize) -> bool {
+        grid[y][x / 32] & (1 << (x % 32)) != 0
+    }
+
+    /// Count the live neighbours of a cell
+    fn neighbours(&self, x: usize, y: usize) -> u8 {
+        let mut count = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                let inside =
+                    (0..WIDTH as isize).contains(&nx) && (0..HEIGHT as isize).contains(&ny);
+                if (dx, dy) != (0, 0) && (inside || self.wrap) {
+                    let (nx, ny) = (
+                        nx.rem_euclid(WIDTH as isize),
+                        ny.rem_euclid(HEIGHT as isize),
+                    );
+                    count += Self::bit(&self.cells, nx as usize, ny as usize) as u8;
+                }
+            }
+        }
+        count
+    }
+
+    /// Compute the next generation
+    pub fn step(&mut self) {
+        let mut next = [[0; WORDS]; HEIGHT];
+        for (y, row) in next.iter_mut().enumerate() {
+            for x in 0..WIDTH {
+                let alive = Self::bit(&self.cells, x, y);
+                if self.rule.next_state(alive, self.neighbours(x, y)) {
+                    row[x / 32] |= 1 << (x % 32);
+                }
+            }
+        }
+
+        if next == self.cells || next == self.previous {
+            self.settled = self.settled.saturating_add(1);
+        } else {
+            self.settled = 0;
+        }
+        self.previous = self.cells;
+        self.cells = next;
+        self.generation = self.generation.wrapping_add(1);
+
+        if let Some(limit) = self.reseed_after {
+            if self.settled >= limit {
+                let (seed, density) = self.seed;
+                self.randomize(seed.wrapping_add(1), density);
+            }
+        }
+    }
+
+    /// Color of a live cell
+    fn live_color(&self, x: usize, y: usize) -> Hub75Color<COLOR_BITS> {
+        match self.colors {
+            CellColors::Solid(color) => color,
+            CellColors::Births { born, alive } => {
+                if Self::bit(&self.previous, x, y) {
+                    alive
+                } else {
+                    born
+                }
+            }
+            CellColors::Rainbow => {
+                let hue = (x * 360 / WIDTH + y * 120 / HEIGHT + self.generation as usize) % 360;
+                Hsv::new(hue as u16, 255, 255).into()
+            }
+        }
+    }
+
+    /// Draw the current generation over the whole frame
+    pub fn render_into(&self, frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) {
+        for y in 0..HEIGHT {
+            if let Ok(row) = frame.get_row_mut(y) {
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    *pixel = if Self::bit(&self.cells, x, y) {
+                        self.live_color(x, y)
+                    } else if self.fade > 0 {
+                        pixel.scale(self.fade).blend_add(self.background)
+                    } else {
+                        self.background
+                    };
+                }
+            }
+        }
+    }
+
+    /// Draw the board, computing a new generation when it is due
+    ///
+    /// Never finishes, so always returns [`AnimationState::Rendered`].
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        Life::render_into(self, frame);
+        self.frame_counter += 1;
+        if self.frame_counter >= self.frames_per_generation {
+            self.frame_counter = 0;
+            self.step();
+        }
+        AnimationState::Rendered
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const WORDS: usize>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for Life<WIDTH, HEIGHT, COLOR_BITS, WORDS>
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        Life::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        Life::render_into(self, frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestLife = Life<8, 8, 6, 1>;
+
+    fn board(cells: &[(usize, usize)]) -> TestLife {
+        let mut life = TestLife::new(Rule::LIFE, CellColors::Solid(Hub75Color::white()));
+        for &(x, y) in cells {
+            life.set_cell(x, y, true).unwrap();
+        }
+        life
+    }
+
+    #[test]
+    fn test_rules() {
+        assert_eq!(Rule::parse("B3/S23").unwrap(), Rule::LIFE);
+        assert_eq!(Rule::parse("b36/s23").unwrap(), Rule::HIGH_LIFE);
+        assert_eq!(Rule::parse("B2/S").unwrap(), Rule::SEEDS);
+        assert_eq!(Rule::parse("B3678/S34678").unwrap(), Rule::DAY_AND_NIGHT);
+        assert!(Rule::parse("B9/S23").is_err());
+        assert!(Rule::parse("23/3").is_err());
+
+        assert!(Rule::LIFE.next_state(false, 3));
+        assert!(!Rule::LIFE.next_state(false, 2));
+        assert!(Rule::LIFE.next_state(true, 2));
+        assert!(!Rule::LIFE.next_state(true, 4));
+    }
+
+    #[test]
+    fn test_blinker_and_glider() {
+        // A blinker flips between horizontal and vertical
+        let mut life = board(&[(2, 3), (3, 3), (4, 3)]);
+        life.step();
+        assert!(life.cell(3, 2) && life.cell(3, 3) && life.cell(3, 4));
+        assert_eq!(life.population(), 3);
+        life.step();
+        assert!(life.cell(2, 3) && life.cell(4, 3));
+
+        // A glider crosses the wrapped edges and returns after 4 * 8 steps
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let mut life = board(&glider);
+        let start = life.cells;
+        for _ in 0..32 {
+            life.step();
+        }
+        assert_eq!(life.cells, start);
+        assert_eq!(life.generation(), 32);
+
+        // Without wrapping it crashes into the corner and settles as a block
+        let mut life = board(&glider);
+        life.set_wrap(false);
+        for _ in 0..32 {
+            life.step();
+        }
+        assert_eq!(life.population(), 4);
+    }
+
+    #[test]
+    fn test_reseed_when_settled() {
+        let mut life = board(&[(2, 3), (3, 3), (4, 3)]);
+        life.set_reseed_after(Some(3));
+        // The blinker repeats from its second generation on
+        for _ in 0..3 {
+            life.step();
+        }
+        assert_eq!(life.generation(), 3);
+        life.step();
+        assert_eq!(life.generation(), 0);
+        assert!(life.population() > 3);
+
+        // Randomizing is repeatable
+        life.randomize(5, 128);
+        let first = life.cells;
+        life.randomize(5, 128);
+        assert_eq!(life.cells, first);
+        assert!(life.population() > 16 && life.population() < 48);
+    }
+
+    #[test]
+    fn test_rendering() {
+        let mut life = board(&[(2, 3), (3, 3), (4, 3)]);
+        life.set_colors(CellColors::Births {
+            born: Hub75Color::green(),
+            alive: Hub75Color::red(),
+        });
+        life.set_frames_per_generation(2);
+        let mut frame = Hub75FrameBuffer::new();
+
+        life.next_into(&mut frame);
+        assert_eq!(frame.get_pixel(3, 3).unwrap(), Hub75Color::green());
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::black());
+        life.next_into(&mut frame);
+        assert_eq!(life.generation(), 1);
+
+        // The middle cell survives, the new ends were just born
+        life.set_fade(128);
+        life.next_into(&mut frame);
+        assert_eq!(frame.get_pixel(3, 3).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(3, 2).unwrap(), Hub75Color::green());
+        assert_eq!(
+            frame.get_pixel(2, 3).unwrap(),
+            Hub75Color::green().scale(128)
+        );
+    }
+}
@@ -0,0 +1,13 @@
+//! Procedural effects
+//!
+//! Effects generate their frames from code instead of stored images, so they
+//! cost no flash and can run forever without repeating. Each keeps its own
+//! state between frames and implements
+//! [`FrameSource`](crate::scene::FrameSource), so it can be shown as a scene
+//! or layered in a [`Composite`](crate::composite::Composite).
+
+pub mod life;
+pub mod noise;
+
+pub use life::{CellColors, Life, Rule};
+pub use noise::{NoiseColors, NoiseEffect, NoiseField};
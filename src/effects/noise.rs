@@ -0,0 +1,369 @@
+//! Animated noise backgrounds
+//!
+//! A [`NoiseField`] samples smooth fractal noise at any pixel and point in
+//! time, for user code that wants organic textures of its own. A
+//! [`NoiseEffect`] renders a field through a color mapping every frame, with
+//! presets for clouds, lava and aurora backgrounds.
+//!
+//! Time is the third dimension of the noise rather than an offset, so the
+//! pattern churns in place instead of sliding across the panel.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{AnimationState, Hub75FrameBuffer};
+//! use hub75::effects::{NoiseEffect, NoiseField};
+//!
+//! // Sample a field directly
+//! let field = NoiseField::new(42);
+//! let level = field.sample(10, 5, 0);
+//!
+//! // Or let an effect draw it
+//! let mut lava = NoiseEffect::<64, 32, 6>::lava(42);
+//! let mut frame = Hub75FrameBuffer::new();
+//! assert!(matches!(lava.next_into(&mut frame), AnimationState::Rendered));
+//! ```
+
+use crate::{
+    animation::AnimationState,
+    color::{Hsv, Hub75Color},
+    frame_buffer::Hub75FrameBuffer,
+    math,
+    scene::FrameSource,
+    Hub75Error,
+};
+
+/// Most octaves a field can sum
+pub const MAX_OCTAVES: u8 = 6;
+
+/// Smooth fractal noise over two dimensions and time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NoiseField {
+    /// Seed selecting the pattern
+    seed: u32,
+    /// Lattice distance per pixel, in 1/65536 cells
+    step: u32,
+    /// Number of octaves summed
+    octaves: u8,
+}
+
+impl NoiseField {
+    /// Create a field with 8 pixel features and two octaves
+    pub const fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            step: 65536 / 8,
+            octaves: 2,
+        }
+    }
+
+    /// Set the size of the largest features in pixels
+    pub fn set_scale(&mut self, pixels: u16) {
+        self.step = 65536 / pixels.max(1) as u32;
+    }
+
+    /// Get the size of the largest features in pixels
+    pub fn scale(&self) -> u16 {
+        (65536 / self.step) as u16
+    }
+
+    /// Set the number of octaves, clamped to 1 through [`MAX_OCTAVES`]
+    ///
+    /// Each octave adds detail at half the size and half the strength of the
+    /// one before, at the cost of another noise lookup per pixel.
+    pub fn set_octaves(&mut self, octaves: u8) {
+        self.octaves = octaves.clamp(1, MAX_OCTAVES);
+    }
+
+    /// Get the number of octaves
+    pub fn octaves(&self) -> u8 {
+        self.octaves
+    }
+
+    /// Set the seed selecting the pattern
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+    }
+
+    /// Sample the field at a pixel, between 0 and 255
+    ///
+    /// `time` is in 1/256ths of the time it takes the largest features to
+    /// change completely.
+    pub fn sample(&self, x: i32, y: i32, time: u32) -> u8 {
+        let mut total = 0;
+        let mut weights = 0;
+        for octave in 0..self.octaves {
+            // Lattice coordinates in 24.8 fixed point, doubling per octave
+            let step = self.step << octave;
+            let lx = ((x as i64 * step as i64) >> 8) as u32;
+            let ly = ((y as i64 * step as i64) >> 8) as u32;
+            let seed = self.seed.wrapping_add(octave as u32);
+            let weight = 256 >> octave;
+
+            total += math::value_noise3(lx, ly, time, seed) as u32 * weight;
+            weights += weight;
+        }
+        (total / weights) as u8
+    }
+}
+
+/// How noise levels are turned into colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NoiseColors<const COLOR_BITS: usize> {
+    /// Blend from `low` at level 0 to `high` at level 255
+    Gradient {
+        /// Color of the lowest level
+        low: Hub75Color<COLOR_BITS>,
+        /// Color of the highest level
+        high: Hub75Color<COLOR_BITS>,
+    },
+    /// Sweep hues from `start` through `span` degrees, darkening low levels
+    Hue {
+        /// Hue of the lowest level, in degrees
+        start: u16,
+        /// Hue range covered by the levels, in degrees
+        span: u16,
+    },
+}
+
+impl<const COLOR_BITS: usize> NoiseColors<COLOR_BITS> {
+    /// Color for a noise level
+    pub fn color(&self, level: u8) -> Hub75Color<COLOR_BITS> {
+        match *self {
+            NoiseColors::Gradient { low, high } => Hub75Color::lerp(low, high, level),
+            NoiseColors::Hue { start, span } => {
+                let hue = (start as u32 + span as u32 * level as u32 / 255) % 360;
+                Hsv::new(hue as u16, 255, level).into()
+            }
+        }
+    }
+}
+
+/// Animated noise drawn over the whole frame
+#[derive(Debug, Clone)]
+pub struct NoiseEffect<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Field being sampled
+    field: NoiseField,
+    /// Mapping from levels to colors
+    colors: NoiseColors<COLOR_BITS>,
+    /// Current time in the field
+    time: u32,
+    /// Time advanced per frame
+    speed: u16,
+    /// Contrast around the middle level, where 256 leaves levels unchanged
+    contrast: u16,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    NoiseEffect<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create an effect drawing `field` through `colors`
+    ///
+    /// The pattern changes completely about every 128 frames.
+    pub fn new(field: NoiseField, colors: NoiseColors<COLOR_BITS>) -> Self {
+        Self {
+            field,
+            colors,
+            time: 0,
+            speed: 2,
+            contrast: 256,
+        }
+    }
+
+    /// Slow white clouds on a blue sky
+    pub fn clouds(seed: u32) -> Self {
+        let mut field = NoiseField::new(seed);
+        field.set_scale(16);
+        field.set_octaves(3);
+        let mut effect = Self::new(
+            field,
+            NoiseColors::Gradient {
+                low: Hub75Color::from_rgb8(0, 40, 160),
+                high: Hub75Color::white(),
+            },
+        );
+        effect.set_speed(1);
+        effect
+    }
+
+    /// Churning red and yellow lava
+    pub fn lava(seed: u32) -> Self {
+        let mut field = NoiseField::new(seed);
+        field.set_scale(12);
+        let mut effect = Self::new(
+            field,
+            NoiseColors::Gradient {
+                low: Hub75Color::from_rgb8(64, 0, 0),
+                high: Hub75Color::from_rgb8(255, 200, 0),
+            },
+        );
+        effect.set_contrast(512);
+        effect
+    }
+
+    /// Green to violet curtains fading into a dark sky
+    pub fn aurora(seed: u32) -> Self {
+        let mut field = NoiseField::new(seed);
+        field.set_scale(24);
+        let mut effect = Self::new(
+            field,
+            NoiseColors::Hue {
+                start: 120,
+                span: 160,
+            },
+        );
+        effect.set_contrast(384);
+        effect
+    }
+
+    /// Get the field being sampled
+    pub fn field(&self) -> &NoiseField {
+        &self.field
+    }
+
+    /// Get the field being sampled for changes
+    pub fn field_mut(&mut self) -> &mut NoiseField {
+        &mut self.field
+    }
+
+    /// Set the mapping from levels to colors
+    pub fn set_colors(&mut self, colors: NoiseColors<COLOR_BITS>) {
+        self.colors = colors;
+    }
+
+    /// Set how far the field moves through time each frame
+    ///
+    /// A speed of 256 changes the largest features completely every frame.
+    pub fn set_speed(&mut self, speed: u16) {
+        self.speed = speed;
+    }
+
+    /// Set the contrast, where 256 is unchanged and 512 doubles it
+    ///
+    /// Raw noise spends most of its time near the middle level; extra
+    /// contrast pushes it out towards the ends of the color mapping.
+    pub fn set_contrast(&mut self, contrast: u16) {
+        self.contrast = contrast;
+    }
+
+    /// Jump back to the start of the animation
+    pub fn reset(&mut self) {
+        self.time = 0;
+    }
+
+    /// Noise level of a pixel at the current time, after contrast
+    pub fn level(&self, x: i32, y: i32) -> u8 {
+        let level = self.field.sample(x, y, self.time) as i32;
+        (128 + (level - 128) * self.contrast as i32 / 256).clamp(0, 255) as u8
+    }
+
+    /// Draw the field at the current time over the whole frame
+    pub fn render_into(&self, frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) {
+        for y in 0..HEIGHT {
+            if let Ok(row) = frame.get_row_mut(y) {
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    *pixel = self.colors.color(self.level(x as i32, y as i32));
+                }
+            }
+        }
+    }
+
+    /// Draw the current frame and move forward in time
+    ///
+    /// Never finishes, so always returns [`AnimationState::Rendered`].
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        NoiseEffect::render_into(self, frame);
+        self.time = self.time.wrapping_add(self.speed as u32);
+        AnimationState::Rendered
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for NoiseEffect<WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        NoiseEffect::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        NoiseEffect::render_into(self, frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_sampling() {
+        let mut field = NoiseField::new(3);
+        field.set_octaves(0);
+        assert_eq!(field.octaves(), 1);
+        field.set_scale(4);
+        assert_eq!(field.scale(), 4);
+
+        // One octave at lattice points is plain value noise
+        assert_eq!(
+            field.sample(8, 4, 0),
+            math::value_noise3(2 << 8, 1 << 8, 0, 3)
+        );
+
+        // Neighbouring pixels and moments stay close with more octaves
+        field.set_octaves(3);
+        for x in -20..20 {
+            let step = field.sample(x, 7, 50) as i32 - field.sample(x + 1, 7, 50) as i32;
+            assert!(step.abs() < 64, "{x}");
+            let tick = field.sample(x, 7, 50) as i32 - field.sample(x, 7, 51) as i32;
+            assert!(tick.abs() <= 3, "{x}");
+        }
+    }
+
+    #[test]
+    fn test_color_mapping() {
+        let gradient = NoiseColors::<6>::Gradient {
+            low: Hub75Color::black(),
+            high: Hub75Color::red(),
+        };
+        assert_eq!(gradient.color(0), Hub75Color::black());
+        assert_eq!(gradient.color(255), Hub75Color::red());
+
+        let hue = NoiseColors::<6>::Hue {
+            start: 120,
+            span: 120,
+        };
+        assert_eq!(hue.color(0), Hub75Color::black());
+        assert_eq!(hue.color(255), Hub75Color::blue());
+    }
+
+    #[test]
+    fn test_effect_animates() {
+        let mut effect = NoiseEffect::<16, 8, 6>::lava(1);
+        let mut first = Hub75FrameBuffer::new();
+        let mut later = Hub75FrameBuffer::new();
+
+        assert!(matches!(
+            effect.next_into(&mut first),
+            AnimationState::Rendered
+        ));
+        for _ in 0..64 {
+            effect.next_into(&mut later);
+        }
+        assert!(first != later);
+
+        effect.reset();
+        effect.next_into(&mut later);
+        assert!(first == later);
+    }
+}
@@ -0,0 +1,348 @@
+//! Ready-made Embassy tasks
+//!
+//! Embassy tasks cannot be generic, so the tasks are generated in the
+//! application by macros that name its concrete display type:
+//!
+//! - [`spawn_refresh!`](crate::spawn_refresh) moves the display into a
+//!   refresh task and returns a [`Hub75Handle`] for drawing from any other
+//!   task on the same executor
+//! - [`spawn_animation!`](crate::spawn_animation) does the same, but plays an
+//!   animation before settling into the refresh loop
+//! - [`display_mutex!`](crate::display_mutex) puts a display behind a
+//!   `'static` mutex for applications that prefer locking
+//!
+//! The expansions use `embassy-executor`, `embassy-time` and `static_cell`
+//! (plus `embassy-sync` for the mutex), which the application must depend on;
+//! this crate itself stays free of Embassy dependencies. Each invocation
+//! creates its own task and `static` storage, so it may only run once.
+//! The snippets below need those crates and are not compiled here; the nRF
+//! examples (`basic_display`, `text_display` and `animated_patterns` in
+//! `examples/nrf`) use each macro, so building them compiles the expansions.
+//!
+//! Refreshing is the one job that must never wait behind drawing. Pass the
+//! spawner of a higher-priority `InterruptExecutor` to keep other tasks from
+//! delaying it; the handle stays with the thread-mode tasks that draw.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use embassy_nrf::gpio::Output;
+//! use hub75::Hub75Display;
+//!
+//! type Display = Hub75Display<Output<'static>, 64, 32, 6>;
+//!
+//! #[embassy_executor::main]
+//! async fn main(spawner: Spawner) {
+//!     // ... configure pins ...
+//!     let display = Display::new(pins).unwrap();
+//!     let mut screen =
+//!         hub75::spawn_refresh!(spawner, display: Display = display, queue = 256).unwrap();
+//!
+//!     loop {
+//!         screen.clear().ok();
+//!         // ... draw on `screen` with embedded-graphics ...
+//!         screen.swap_buffers().ok();
+//!         Timer::after_millis(16).await;
+//!     }
+//! }
+//! ```
+
+use crate::{
+    animation::{Animation, AnimationEffectTrait, AnimationState},
+    buffering::BufferStorage,
+    shared::{Hub75Handle, Hub75Receiver, SharedHub75},
+    Hub75Color, Hub75Display, Hub75Error,
+};
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::delay::DelayNs;
+
+/// Queue types matching a display type, used by the task macros
+pub trait DisplayQueue {
+    /// Queue holding `N` commands
+    type Queue<const N: usize>;
+    /// Receiver end of the queue
+    type Receiver<'a, const N: usize>;
+    /// Handle end of the queue
+    type Handle<'a, const N: usize>;
+}
+
+impl<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, B, A, C> DisplayQueue
+    for Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>
+where
+    P: OutputPin,
+    A: OutputPin,
+    C: OutputPin,
+    B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS>,
+{
+    type Queue<const N: usize> = SharedHub75<WIDTH, HEIGHT, COLOR_BITS, N>;
+    type Receiver<'a, const N: usize> = Hub75Receiver<'a, WIDTH, HEIGHT, COLOR_BITS, N>;
+    type Handle<'a, const N: usize> = Hub75Handle<'a, WIDTH, HEIGHT, COLOR_BITS, N>;
+}
+
+/// Refresh the display forever, applying queued commands between frames
+///
+/// This is the body of the task spawned by [`spawn_refresh!`](crate::spawn_refresh).
+/// Errors from a frame are ignored and the next frame is rendered as usual.
+pub async fn refresh<
+    P,
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    B,
+    A,
+    C,
+    const N: usize,
+>(
+    display: &mut Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>,
+    commands: &mut Hub75Receiver<'_, WIDTH, HEIGHT, COLOR_BITS, N>,
+    delay: &mut impl DelayNs,
+) -> !
+where
+    P: OutputPin,
+    A: OutputPin,
+    C: OutputPin,
+    B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS, Pixel = Hub75Color<COLOR_BITS>>,
+{
+    loop {
+        let _ = refresh_once(display, commands, delay).await;
+    }
+}
+
+/// Apply the queued commands and render one frame
+pub async fn refresh_once<
+    P,
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    B,
+    A,
+    C,
+    const N: usize,
+>(
+    display: &mut Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>,
+    commands: &mut Hub75Receiver<'_, WIDTH, HEIGHT, COLOR_BITS, N>,
+    delay: &mut impl DelayNs,
+) -> Result<(), Hub75Error>
+where
+    P: OutputPin,
+    A: OutputPin,
+    C: OutputPin,
+    B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS, Pixel = Hub75Color<COLOR_BITS>>,
+{
+    commands.apply(display);
+    display.render_frame(delay).await
+}
+
+/// Play an animation to the end, applying queued commands between frames
+///
+/// Like [`Hub75Display::play_animation`], but other tasks can still change
+/// the brightness or queue drawing while it runs. Drawing lands in the same
+/// back buffer as the animation and is overwritten by its next step.
+pub async fn animate<
+    P,
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    B,
+    A,
+    C,
+    E,
+    const N: usize,
+>(
+    display: &mut Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>,
+    animation: &mut Animation<'_, WIDTH, HEIGHT, COLOR_BITS, E>,
+    commands: &mut Hub75Receiver<'_, WIDTH, HEIGHT, COLOR_BITS, N>,
+    delay: &mut impl DelayNs,
+) -> Result<(), Hub75Error>
+where
+    P: OutputPin,
+    A: OutputPin,
+    C: OutputPin,
+    B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS, Pixel = Hub75Color<COLOR_BITS>>,
+    E: AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS>,
+{
+    loop {
+        commands.apply(display);
+        match animation.next_into(display.back_buffer()) {
+            AnimationState::Rendered | AnimationState::Apply(_) => display.swap_buffers(),
+            AnimationState::Wait => {}
+            AnimationState::Done => return Ok(()),
+        }
+        display.render_frame(delay).await?;
+    }
+}
+
+/// Spawn a task that owns and refreshes a display
+///
+/// Evaluates to `Result<Hub75Handle, SpawnError>`; the handle queues drawing
+/// for the task and implements `DrawTarget` with the `embedded-graphics`
+/// feature. The queue holds `queue` commands.
+///
+/// ```rust,ignore
+/// let screen = hub75::spawn_refresh!(spawner, display: Display = display, queue = 256)?;
+/// ```
+#[macro_export]
+macro_rules! spawn_refresh {
+    ($spawner:expr, display: $ty:ty = $display:expr, queue = $n:expr $(,)?) => {{
+        type Queue = <$ty as $crate::embassy::DisplayQueue>::Queue<{ $n }>;
+        type Receiver = <$ty as $crate::embassy::DisplayQueue>::Receiver<'static, { $n }>;
+        static QUEUE: ::static_cell::StaticCell<Queue> = ::static_cell::StaticCell::new();
+
+        #[::embassy_executor::task]
+        async fn hub75_refresh(mut display: $ty, mut commands: Receiver) -> ! {
+            $crate::embassy::refresh(&mut display, &mut commands, &mut ::embassy_time::Delay).await
+        }
+
+        let (handle, commands) = QUEUE.init(<Queue>::new()).split();
+        $spawner
+            .spawn(hub75_refresh($display, commands))
+            .map(|()| handle)
+    }};
+}
+
+/// Spawn a task that owns a display, plays an animation on it and then keeps
+/// refreshing
+///
+/// The animation must live for `'static`, as must any frames it borrows.
+/// Evaluates to `Result<Hub75Handle, SpawnError>` like
+/// [`spawn_refresh!`](crate::spawn_refresh).
+///
+/// ```rust,ignore
+/// type Intro = Animation<'static, 64, 32, 6, AnimationEffect>;
+/// let screen = hub75::spawn_animation!(
+///     spawner,
+///     display: Display = display,
+///     animation: Intro = intro,
+///     queue = 64,
+/// )?;
+/// ```
+#[macro_export]
+macro_rules! spawn_animation {
+    (
+        $spawner:expr,
+        display: $ty:ty = $display:expr,
+        animation: $anim_ty:ty = $animation:expr,
+        queue = $n:expr $(,)?
+    ) => {{
+        type Queue = <$ty as $crate::embassy::DisplayQueue>::Queue<{ $n }>;
+        type Receiver = <$ty as $crate::embassy::DisplayQueue>::Receiver<'static, { $n }>;
+        static QUEUE: ::static_cell::StaticCell<Queue> = ::static_cell::StaticCell::new();
+
+        #[::embassy_executor::task]
+        async fn hub75_animation(
+            mut display: $ty,
+            mut animation: $anim_ty,
+            mut commands: Receiver,
+        ) -> ! {
+            let mut delay = ::embassy_time::Delay;
+            let _ =
+                $crate::embassy::animate(&mut display, &mut animation, &mut commands, &mut delay)
+                    .await;
+            $crate::embassy::refresh(&mut display, &mut commands, &mut delay).await
+        }
+
+        let (handle, commands) = QUEUE.init(<Queue>::new()).split();
+        $spawner
+            .spawn(hub75_animation($display, $animation, commands))
+            .map(|()| handle)
+    }};
+}
+
+/// Put a display behind a `'static` Embassy mutex
+///
+/// Evaluates to `&'static Mutex<CriticalSectionRawMutex, $ty>`. Hold the lock
+/// for a single frame at a time in the refresh task so drawing tasks get a
+/// turn; [`spawn_refresh!`](crate::spawn_refresh) avoids the lock entirely.
+///
+/// ```rust,ignore
+/// let display = hub75::display_mutex!(Display = Display::new(pins)?);
+/// ```
+#[macro_export]
+macro_rules! display_mutex {
+    ($ty:ty = $display:expr) => {{
+        type Shared = ::embassy_sync::mutex::Mutex<
+            ::embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex,
+            $ty,
+        >;
+        static DISPLAY: ::static_cell::StaticCell<Shared> = ::static_cell::StaticCell::new();
+        &*DISPLAY.init(::embassy_sync::mutex::Mutex::new($display))
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animation::{AnimationData, AnimationEffect},
+        color::Hub75Color,
+        display::Brightness,
+        frame_buffer::Hub75FrameBuffer,
+        waveform::{RecordingPin, WaveformRecorder},
+    };
+    use core::future::Future;
+    use core::task::{Context, Poll, Waker};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = core::pin::pin!(future);
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("recording futures never wait"),
+        }
+    }
+
+    type Display<P> = Hub75Display<P, 32, 16, 4>;
+
+    #[test]
+    fn test_queue_types_match_display() {
+        type Queue = <Display<RecordingPin> as DisplayQueue>::Queue<4>;
+        type Handle<'a> = <Display<RecordingPin> as DisplayQueue>::Handle<'a, 4>;
+
+        let mut queue = Queue::new();
+        let (handle, _): (Handle<'_>, _) = queue.split();
+        assert_eq!(handle.free(), 4);
+    }
+
+    #[test]
+    fn test_refresh_once_applies_commands() {
+        let recorder = WaveformRecorder::new();
+        let mut display = Display::new(recorder.pins(3)).unwrap();
+        let mut delay = recorder.delay();
+        let mut queue = SharedHub75::<32, 16, 4, 8>::new();
+        let (handle, mut commands) = queue.split();
+
+        handle.set_pixel(2, 9, Hub75Color::white()).unwrap();
+        block_on(refresh_once(&mut display, &mut commands, &mut delay)).unwrap();
+        assert_eq!(display.get_pixel(2, 9), Ok(Hub75Color::white()));
+        assert_eq!(recorder.check_protocol(32, 8), Ok(()));
+    }
+
+    #[test]
+    fn test_animate_applies_commands() {
+        let recorder = WaveformRecorder::new();
+        let mut display = Display::new(recorder.pins(3)).unwrap();
+        display.set_double_buffering(true);
+        let mut delay = recorder.delay();
+        let mut queue = SharedHub75::<32, 16, 4, 8>::new();
+        let (handle, mut commands) = queue.split();
+
+        let mut frames = [Hub75FrameBuffer::new(), Hub75FrameBuffer::new()];
+        frames[1].fill(Hub75Color::blue());
+        let mut animation =
+            Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 4).unwrap();
+        handle.set_brightness(Brightness::new(99)).unwrap();
+
+        block_on(animate(
+            &mut display,
+            &mut animation,
+            &mut commands,
+            &mut delay,
+        ))
+        .unwrap();
+        assert!(animation.is_done());
+        assert_eq!(display.brightness(), Brightness::new(99));
+        assert_eq!(display.get_pixel(0, 0), Ok(Hub75Color::blue()));
+    }
+}
@@ -0,0 +1,405 @@
+//! Driving pins through an I2C or SPI GPIO expander
+//!
+//! When the MCU runs short of pins, the address lines, which only change
+//! once per row, can move to a GPIO expander. An [`Expander`] keeps a copy of
+//! the expander's output latch and hands out [`ExpanderPin`]s that implement
+//! `OutputPin`, so they plug into [`Hub75AddressPins`] or any other pin group.
+//!
+//! Each bus transaction takes far longer than a GPIO write, so pins can be
+//! deferred: setting a deferred pin only updates the latch copy, and the next
+//! write-through pin or [`Expander::flush`] sends every pending change at
+//! once. [`Expander::address_pins`] builds an address group this way, with
+//! the last pin set by the driver writing the whole row address in a single
+//! transaction, and nothing sent when the address does not change.
+//!
+//! The expander itself is reached through [`ExpanderPort`], implemented by
+//! [`I2cExpander`] for common I2C parts. SPI expanders and other chips only
+//! need the one method of the trait.
+//!
+//! Displays need `'static` pins, so keep the expander in a `static`, for
+//! example with `static_cell`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::expander::{Expander, ExpanderChip, I2cExpander};
+//! use hub75::{buffering::DoubleBuffer, Hub75Display, Hub75Pins};
+//! use embedded_hal::{digital::OutputPin, i2c::I2c};
+//!
+//! # fn example<I: I2c + 'static, P: OutputPin + 'static>(
+//! #     i2c: I,
+//! #     pins: Hub75Pins<P>,
+//! #     expander: &'static mut Option<Expander<I2cExpander<I>>>,
+//! # ) -> Result<(), hub75::Hub75Error> {
+//! let port = I2cExpander::new(i2c, ExpanderChip::Pcf8574, 0x20)?;
+//! let expander: &'static Expander<_> = expander.insert(Expander::new(port));
+//!
+//! // Row address on expander outputs 0-3, everything else on the MCU
+//! let pins = Hub75Pins {
+//!     rgb: pins.rgb,
+//!     address: expander.address_pins(0, 4)?,
+//!     control: pins.control,
+//! };
+//! let display = Hub75Display::<_, 64, 32, 6, DoubleBuffer<64, 32, 6>, _, _>::new(pins)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use core::cell::RefCell;
+
+use crate::{pins::Hub75AddressPins, Hub75Error};
+use embedded_hal::digital::{ErrorKind, ErrorType, OutputPin};
+use embedded_hal::i2c::I2c;
+
+/// Bus access to an expander's outputs
+pub trait ExpanderPort {
+    /// Error of a bus transaction
+    type Error: core::fmt::Debug;
+
+    /// Set every output at once, output 0 in the least significant bit
+    fn write_outputs(&mut self, outputs: u16) -> Result<(), Self::Error>;
+}
+
+/// I2C GPIO expander parts supported by [`I2cExpander`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ExpanderChip {
+    /// PCF8574 or PCF8574A, 8 quasi-bidirectional outputs
+    Pcf8574,
+    /// PCF8575, 16 quasi-bidirectional outputs
+    Pcf8575,
+    /// MCP23008, 8 push-pull outputs
+    Mcp23008,
+    /// MCP23017, 16 push-pull outputs
+    Mcp23017,
+}
+
+impl ExpanderChip {
+    /// Get the number of outputs of the part
+    pub const fn outputs(self) -> u8 {
+        match self {
+            ExpanderChip::Pcf8574 | ExpanderChip::Mcp23008 => 8,
+            ExpanderChip::Pcf8575 | ExpanderChip::Mcp23017 => 16,
+        }
+    }
+}
+
+/// GPIO expander on an I2C bus
+pub struct I2cExpander<I> {
+    /// Bus the expander is on
+    i2c: I,
+    /// Part on the bus
+    chip: ExpanderChip,
+    /// 7-bit bus address
+    address: u8,
+}
+
+impl<I: I2c> I2cExpander<I> {
+    /// Set up an expander with all its pins as outputs driven low
+    pub fn new(i2c: I, chip: ExpanderChip, address: u8) -> Result<Self, Hub75Error> {
+        let mut expander = Self { i2c, chip, address };
+        expander
+            .write_outputs(0)
+            .map_err(|_| Hub75Error::PinError)?;
+        // MCP parts start with every pin as an input
+        let directions: &[u8] = match chip {
+            ExpanderChip::Mcp23008 => &[0x00, 0x00],
+            ExpanderChip::Mcp23017 => &[0x00, 0x00, 0x00],
+            ExpanderChip::Pcf8574 | ExpanderChip::Pcf8575 => &[],
+        };
+        if !directions.is_empty() {
+            expander
+                .i2c
+                .write(address, directions)
+                .map_err(|_| Hub75Error::PinError)?;
+        }
+        Ok(expander)
+    }
+
+    /// Release the bus
+    pub fn release(self) -> I {
+        self.i2c
+    }
+}
+
+impl<I: I2c> ExpanderPort for I2cExpander<I> {
+    type Error = I::Error;
+
+    fn write_outputs(&mut self, outputs: u16) -> Result<(), Self::Error> {
+        let [low, high] = outputs.to_le_bytes();
+        match self.chip {
+            ExpanderChip::Pcf8574 => self.i2c.write(self.address, &[low]),
+            ExpanderChip::Pcf8575 => self.i2c.write(self.address, &[low, high]),
+            // Output latch registers, OLATA then OLATB on the MCP23017
+            ExpanderChip::Mcp23008 => self.i2c.write(self.address, &[0x0A, low]),
+            ExpanderChip::Mcp23017 => self.i2c.write(self.address, &[0x14, low, high]),
+        }
+    }
+}
+
+/// Shared state of an expander's outputs
+struct Latch<P> {
+    /// Bus access to the expander
+    port: P,
+    /// Levels the pins have been set to
+    outputs: u16,
+    /// Levels last written to the expander
+    written: u16,
+}
+
+/// Expander whose outputs are handed out as pins
+pub struct Expander<P> {
+    /// Latch shared by the pins
+    latch: RefCell<Latch<P>>,
+}
+
+impl<P: ExpanderPort> Expander<P> {
+    /// Wrap an expander whose outputs are all low
+    pub fn new(port: P) -> Self {
+        Self {
+            latch: RefCell::new(Latch {
+                port,
+                outputs: 0,
+                written: 0,
+            }),
+        }
+    }
+
+    /// Get a pin that writes to the expander whenever the outputs change
+    pub fn pin(&self, output: u8) -> ExpanderPin<'_, P> {
+        ExpanderPin {
+            expander: self,
+            mask: 1 << (output & 15),
+            deferred: false,
+        }
+    }
+
+    /// Get a pin whose changes are only written by the next write-through
+    /// pin or [`flush`](Self::flush)
+    pub fn deferred_pin(&self, output: u8) -> ExpanderPin<'_, P> {
+        ExpanderPin {
+            deferred: true,
+            ..self.pin(output)
+        }
+    }
+
+    /// Get address pins on `count` consecutive outputs, A on `first`
+    ///
+    /// The pins below the last are deferred, so each row address is written
+    /// in one transaction. Returns [`Hub75Error::InvalidCoordinates`] for
+    /// fewer than 3 or more than 5 pins or outputs past 15.
+    pub fn address_pins(
+        &self,
+        first: u8,
+        count: usize,
+    ) -> Result<Hub75AddressPins<ExpanderPin<'_, P>>, Hub75Error> {
+        if !(3..=5).contains(&count) || first as usize + count > 16 {
+            return Err(Hub75Error::InvalidCoordinates);
+        }
+        let pin = |i: usize| {
+            let output = first + i as u8;
+            if i + 1 == count {
+                self.pin(output)
+            } else {
+                self.deferred_pin(output)
+            }
+        };
+        Ok(Hub75AddressPins {
+            a: pin(0),
+            b: pin(1),
+            c: pin(2),
+            d: (count > 3).then(|| pin(3)),
+            e: (count > 4).then(|| pin(4)),
+        })
+    }
+
+    /// Write pending changes to the expander
+    pub fn flush(&self) -> Result<(), Hub75Error> {
+        let mut latch = self.latch.borrow_mut();
+        if latch.outputs == latch.written {
+            return Ok(());
+        }
+        let outputs = latch.outputs;
+        latch
+            .port
+            .write_outputs(outputs)
+            .map_err(|_| Hub75Error::PinError)?;
+        latch.written = outputs;
+        Ok(())
+    }
+
+    /// Get the levels the pins have been set to, output 0 in bit 0
+    pub fn outputs(&self) -> u16 {
+        self.latch.borrow().outputs
+    }
+
+    /// Release the expander
+    pub fn release(self) -> P {
+        self.latch.into_inner().port
+    }
+}
+
+/// Output of an [`Expander`]
+pub struct ExpanderPin<'a, P> {
+    /// Expander the output belongs to
+    expander: &'a Expander<P>,
+    /// Bit of the output
+    mask: u16,
+    /// Whether changes wait for another pin to write them
+    deferred: bool,
+}
+
+impl<P: ExpanderPort> ExpanderPin<'_, P> {
+    /// Set the output level, writing the latch unless deferred
+    fn set(&mut self, high: bool) -> Result<(), ErrorKind> {
+        {
+            let mut latch = self.expander.latch.borrow_mut();
+            if high {
+                latch.outputs |= self.mask;
+            } else {
+                latch.outputs &= !self.mask;
+            }
+        }
+        if self.deferred {
+            return Ok(());
+        }
+        self.expander.flush().map_err(|_| ErrorKind::Other)
+    }
+}
+
+impl<P: ExpanderPort> ErrorType for ExpanderPin<'_, P> {
+    type Error = ErrorKind;
+}
+
+impl<P: ExpanderPort> OutputPin for ExpanderPin<'_, P> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set(false)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "async")]
+    use crate::{buffering::DoubleBuffer, color::Hub75Color, Hub75Display, Hub75Pins};
+    #[cfg(feature = "async")]
+    use core::future::Future;
+    #[cfg(feature = "async")]
+    use core::task::{Context, Waker};
+    use embedded_hal::i2c::{ErrorType as I2cErrorType, Operation};
+    use std::vec::Vec;
+
+    /// Port recording every write
+    #[derive(Default)]
+    struct RecordingPort {
+        writes: Vec<u16>,
+    }
+
+    impl ExpanderPort for RecordingPort {
+        type Error = ();
+
+        fn write_outputs(&mut self, outputs: u16) -> Result<(), ()> {
+            self.writes.push(outputs);
+            Ok(())
+        }
+    }
+
+    /// I2C bus recording the bytes written to each address
+    #[derive(Default)]
+    struct RecordingI2c {
+        writes: Vec<(u8, Vec<u8>)>,
+    }
+
+    impl I2cErrorType for RecordingI2c {
+        type Error = core::convert::Infallible;
+    }
+
+    impl I2c for RecordingI2c {
+        fn transaction(
+            &mut self,
+            address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            for operation in operations {
+                if let Operation::Write(bytes) = operation {
+                    self.writes.push((address, bytes.to_vec()));
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_deferred_writes() {
+        let expander = Expander::new(RecordingPort::default());
+        let mut a = expander.deferred_pin(2);
+        let mut b = expander.deferred_pin(3);
+        let mut c = expander.pin(4);
+
+        a.set_high().unwrap();
+        b.set_high().unwrap();
+        assert_eq!(expander.outputs(), 0b01100);
+        c.set_high().unwrap();
+        c.set_high().unwrap();
+        b.set_low().unwrap();
+        expander.flush().unwrap();
+        expander.flush().unwrap();
+
+        assert_eq!(expander.release().writes, [0b11100, 0b10100]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_address_pins_on_display() {
+        let expander: &'static _ = std::boxed::Box::leak(std::boxed::Box::new(Expander::new(
+            RecordingPort::default(),
+        )));
+        assert!(expander.address_pins(0, 6).is_err());
+        assert!(expander.address_pins(14, 3).is_err());
+
+        let mcu = crate::waveform::WaveformRecorder::new().pins(4);
+        let pins = Hub75Pins {
+            rgb: mcu.rgb,
+            address: expander.address_pins(4, 4).unwrap(),
+            control: mcu.control,
+        };
+        let mut display =
+            Hub75Display::<_, 64, 32, 2, DoubleBuffer<64, 32, 2>, _, _>::new(pins).unwrap();
+        display.fill(Hub75Color::white());
+        let mut delay = crate::waveform::WaveformRecorder::new().delay();
+        let mut future = core::pin::pin!(display.render_frame(&mut delay));
+        assert!(future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+            .is_ready());
+
+        // One write per row change: rows 1-15, then back to 0 and 1-15 again
+        let writes = &expander.latch.borrow().port.writes;
+        assert_eq!(writes.len(), 31);
+        assert_eq!(writes[0], 1 << 4);
+        assert_eq!(writes[14], 15 << 4);
+        assert_eq!(writes[15], 0);
+    }
+
+    #[test]
+    fn test_i2c_chips() {
+        let mut pcf =
+            I2cExpander::new(RecordingI2c::default(), ExpanderChip::Pcf8575, 0x20).unwrap();
+        pcf.write_outputs(0x1234).unwrap();
+        assert_eq!(
+            pcf.release().writes,
+            [(0x20, std::vec![0, 0]), (0x20, std::vec![0x34, 0x12])]
+        );
+
+        let mut mcp =
+            I2cExpander::new(RecordingI2c::default(), ExpanderChip::Mcp23017, 0x27).unwrap();
+        mcp.write_outputs(0x0180).unwrap();
+        let writes = mcp.release().writes;
+        assert_eq!(writes[1], (0x27, std::vec![0x00, 0x00, 0x00]));
+        assert_eq!(writes[2], (0x27, std::vec![0x14, 0x80, 0x01]));
+    }
+}
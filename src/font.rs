@@ -0,0 +1,723 @@
+//! Built-in 5x7 bitmap font
+//!
+//! Covers printable ASCII (`' '` to `'~'`) and a handful of common symbols
+//! such as `'°'`, `'€'` and arrows. Accented Latin letters are drawn as their
+//! base letter (see [`fold`]), and any other character as a hollow box. Text
+//! can be drawn straight into a [`Hub75FrameBuffer`]
+//! without embedded-graphics, and is clipped at the frame edges, so it can be
+//! positioned partly off screen for scrolling.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::font;
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! let end = frame.draw_text("Hi!", 2, 12, Hub75Color::green());
+//! assert_eq!(end, 2 + font::text_width("Hi!") as i32);
+//! assert_eq!(frame.get_pixel(2, 12).unwrap(), Hub75Color::green());
+//! ```
+//!
+//! Formatted text can be written straight into the frame through a
+//! [`TextWriter`], without formatting into a string first:
+//!
+//! ```rust
+//! use core::fmt::Write;
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! let t = 21;
+//! write!(frame.text_at(0, 10, Hub75Color::red()), "T={}C", t).unwrap();
+//! ```
+
+use core::fmt;
+
+use crate::{color::Hub75Color, composite::BlendMode, frame_buffer::Hub75FrameBuffer};
+use embedded_graphics_core::{
+    geometry::{Point, Size},
+    primitives::Rectangle,
+};
+
+/// Width of a glyph in pixels
+pub const GLYPH_WIDTH: usize = 5;
+/// Height of a glyph in pixels
+pub const GLYPH_HEIGHT: usize = 7;
+/// Horizontal distance from one character to the next, including spacing
+pub const GLYPH_ADVANCE: usize = GLYPH_WIDTH + 1;
+
+/// Glyph for characters outside printable ASCII
+const REPLACEMENT: [u8; GLYPH_WIDTH] = [0x7F, 0x41, 0x41, 0x41, 0x7F];
+
+/// Glyphs for `' '` to `'~'`, one byte per column with the top row in bit 0
+const GLYPHS: [[u8; GLYPH_WIDTH]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // '!'
+    [0x00, 0x07, 0x00, 0x07, 0x00], // '"'
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // '#'
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // '$'
+    [0x23, 0x13, 0x08, 0x64, 0x62], // '%'
+    [0x36, 0x49, 0x55, 0x22, 0x50], // '&'
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '\''
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // '('
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // ')'
+    [0x14, 0x08, 0x3E, 0x08, 0x14], // '*'
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // '+'
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ','
+    [0x08, 0x08, 0x08, 0x08, 0x08], // '-'
+    [0x00, 0x60, 0x60, 0x00, 0x00], // '.'
+    [0x20, 0x10, 0x08, 0x04, 0x02], // '/'
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // '0'
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // '1'
+    [0x42, 0x61, 0x51, 0x49, 0x46], // '2'
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // '3'
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // '4'
+    [0x27, 0x45, 0x45, 0x45, 0x39], // '5'
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // '6'
+    [0x01, 0x71, 0x09, 0x05, 0x03], // '7'
+    [0x36, 0x49, 0x49, 0x49, 0x36], // '8'
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // '9'
+    [0x00, 0x36, 0x36, 0x00, 0x00], // ':'
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ';'
+    [0x08, 0x14, 0x22, 0x41, 0x00], // '<'
+    [0x14, 0x14, 0x14, 0x14, 0x14], // '='
+    [0x00, 0x41, 0x22, 0x14, 0x08], // '>'
+    [0x02, 0x01, 0x51, 0x09, 0x06], // '?'
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // '@'
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // 'A'
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // 'B'
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // 'C'
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // 'D'
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // 'E'
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // 'F'
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // 'G'
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // 'H'
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // 'I'
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // 'J'
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // 'K'
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // 'L'
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // 'M'
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // 'N'
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // 'O'
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // 'P'
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // 'Q'
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // 'R'
+    [0x46, 0x49, 0x49, 0x49, 0x31], // 'S'
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // 'T'
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // 'U'
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // 'V'
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // 'W'
+    [0x63, 0x14, 0x08, 0x14, 0x63], // 'X'
+    [0x07, 0x08, 0x70, 0x08, 0x07], // 'Y'
+    [0x61, 0x51, 0x49, 0x45, 0x43], // 'Z'
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // '['
+    [0x02, 0x04, 0x08, 0x10, 0x20], // '\\'
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ']'
+    [0x04, 0x02, 0x01, 0x02, 0x04], // '^'
+    [0x40, 0x40, 0x40, 0x40, 0x40], // '_'
+    [0x00, 0x01, 0x02, 0x04, 0x00], // '`'
+    [0x20, 0x54, 0x54, 0x54, 0x78], // 'a'
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // 'b'
+    [0x38, 0x44, 0x44, 0x44, 0x20], // 'c'
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // 'd'
+    [0x38, 0x54, 0x54, 0x54, 0x18], // 'e'
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // 'f'
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // 'g'
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // 'h'
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // 'i'
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // 'j'
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // 'k'
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // 'l'
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // 'm'
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // 'n'
+    [0x38, 0x44, 0x44, 0x44, 0x38], // 'o'
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // 'p'
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // 'q'
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // 'r'
+    [0x48, 0x54, 0x54, 0x54, 0x20], // 's'
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // 't'
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // 'u'
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // 'v'
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // 'w'
+    [0x44, 0x28, 0x10, 0x28, 0x44], // 'x'
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // 'y'
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // 'z'
+    [0x00, 0x08, 0x36, 0x41, 0x00], // '{'
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // '|'
+    [0x00, 0x41, 0x36, 0x08, 0x00], // '}'
+    [0x08, 0x04, 0x08, 0x10, 0x08], // '~'
+];
+
+/// Glyphs for symbols beyond ASCII, sorted by character
+const SYMBOLS: [(char, [u8; GLYPH_WIDTH]); 18] = [
+    ('£', [0x48, 0x7E, 0x49, 0x41, 0x42]),
+    ('°', [0x00, 0x06, 0x09, 0x09, 0x06]),
+    ('±', [0x44, 0x44, 0x5F, 0x44, 0x44]),
+    ('µ', [0x7E, 0x20, 0x20, 0x10, 0x3E]),
+    ('·', [0x00, 0x00, 0x08, 0x00, 0x00]),
+    ('×', [0x22, 0x14, 0x08, 0x14, 0x22]),
+    ('÷', [0x08, 0x08, 0x2A, 0x08, 0x08]),
+    ('…', [0x40, 0x00, 0x40, 0x00, 0x40]),
+    ('€', [0x14, 0x3E, 0x55, 0x41, 0x22]),
+    ('←', [0x08, 0x1C, 0x2A, 0x08, 0x08]),
+    ('↑', [0x04, 0x02, 0x7F, 0x02, 0x04]),
+    ('→', [0x08, 0x08, 0x2A, 0x1C, 0x08]),
+    ('↓', [0x10, 0x20, 0x7F, 0x20, 0x10]),
+    ('█', [0x7F, 0x7F, 0x7F, 0x7F, 0x7F]),
+    ('♥', [0x0C, 0x1E, 0x3C, 0x1E, 0x0C]),
+    ('✓', [0x10, 0x20, 0x10, 0x08, 0x04]),
+    ('✗', [0x22, 0x14, 0x08, 0x14, 0x22]),
+    ('\u{FFFD}', REPLACEMENT),
+];
+
+/// Base letters of `'\u{C0}'` to `'\u{17F}'`, `'?'` where there is none
+const FOLDED: &[u8; 192] = b"AAAAAA?CEEEEIIII?NOOOOO?OUUUUY??aaaaaa?ceeeeiiii?nooooo?ouuuuy?y\
+    AaAaAaCcCcCcCcDdDdEeEeEeEeEeGgGgGgGgHhHhIiIiIiIiIi??JjKk?LlLlLlLlLlNnNnNn???OoOoOo??RrRrRrSsSsSsSsTtTtTtUuUuUuUuUuUuWwYyYZzZzZzs";
+
+/// Get the ASCII letter an accented Latin letter is drawn as
+///
+/// Covers Latin-1 and Latin Extended-A, so `'é'` folds to `'e'` and `'Ł'` to
+/// `'L'`. Returns `None` for other characters, including ligatures like
+/// `'Æ'`.
+pub fn fold(c: char) -> Option<char> {
+    let index = (c as u32).checked_sub(0xC0)?;
+    FOLDED
+        .get(index as usize)
+        .filter(|&&base| base != b'?')
+        .map(|&base| base as char)
+}
+
+/// Check whether a character has a glyph of its own or folds to one, rather
+/// than being drawn as a box
+pub fn has_glyph(c: char) -> bool {
+    matches!(c, ' '..='~')
+        || SYMBOLS
+            .binary_search_by_key(&c, |&(symbol, _)| symbol)
+            .is_ok()
+        || fold(c).is_some()
+}
+
+/// Get the glyph for a character, one byte per column with the top row in
+/// bit 0
+///
+/// Symbols are looked up first, then accented letters are folded to their
+/// base letter; anything else gets a hollow box.
+pub fn glyph(c: char) -> &'static [u8; GLYPH_WIDTH] {
+    let ascii = |c: char| {
+        (c as u32)
+            .checked_sub(' ' as u32)
+            .and_then(|index| GLYPHS.get(index as usize))
+    };
+    ascii(c)
+        .or_else(|| {
+            SYMBOLS
+                .binary_search_by_key(&c, |&(symbol, _)| symbol)
+                .ok()
+                .map(|index| &SYMBOLS[index].1)
+        })
+        .or_else(|| fold(c).and_then(ascii))
+        .unwrap_or(&REPLACEMENT)
+}
+
+/// Get the width in pixels of a string drawn with
+/// [`Hub75FrameBuffer::draw_text`], including the spacing after the last
+/// character
+pub fn text_width(text: &str) -> usize {
+    text.chars().count() * GLYPH_ADVANCE
+}
+
+/// Get the width in pixels of a string drawn with the built-in font enlarged
+/// by `scale`, without the spacing after the last character
+pub(crate) fn visible_width(text: &str, scale: u8) -> i32 {
+    let width = Scaled::new(Font5x7, scale).text_width(text) as i32;
+    (width - scale.max(1) as i32).max(0)
+}
+
+/// Bitmap font that text can be measured in and drawn with
+///
+/// Implemented by the built-in [`Font5x7`] and, with the `bdf` feature, by
+/// proportional fonts loaded from BDF files. Text in any font is drawn with
+/// [`Hub75FrameBuffer::draw_text_with`].
+pub trait Font {
+    /// Get the height of a line of text in pixels
+    fn line_height(&self) -> usize;
+
+    /// Get the horizontal distance from the start of a character to the
+    /// start of the next in pixels
+    fn advance(&self, c: char) -> usize;
+
+    /// Call `pixel` with the position of every lit pixel of a character,
+    /// relative to the top-left corner of its line
+    fn for_each_pixel(&self, c: char, pixel: &mut dyn FnMut(i32, i32));
+
+    /// Get the width of a string in pixels, including the spacing after the
+    /// last character
+    fn text_width(&self, text: &str) -> usize {
+        text.chars().map(|c| self.advance(c)).sum()
+    }
+}
+
+/// The built-in 5x7 font as a [`Font`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Font5x7;
+
+impl Font for Font5x7 {
+    fn line_height(&self) -> usize {
+        GLYPH_HEIGHT
+    }
+
+    fn advance(&self, _c: char) -> usize {
+        GLYPH_ADVANCE
+    }
+
+    fn for_each_pixel(&self, c: char, pixel: &mut dyn FnMut(i32, i32)) {
+        for (x, column) in (0..).zip(glyph(c)) {
+            for y in 0..GLYPH_HEIGHT as i32 {
+                if column >> y & 1 == 1 {
+                    pixel(x, y);
+                }
+            }
+        }
+    }
+}
+
+/// A [`Font`] with every pixel drawn as a square block, for large digits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scaled<F> {
+    /// Font being enlarged
+    font: F,
+    /// Width and height of a block in pixels
+    scale: u8,
+}
+
+impl<F: Font> Scaled<F> {
+    /// Enlarge `font` by `scale` in each direction, at least 1
+    pub fn new(font: F, scale: u8) -> Self {
+        Self {
+            font,
+            scale: scale.max(1),
+        }
+    }
+
+    /// Get the width and height of a block in pixels
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+}
+
+impl<F: Font> Font for Scaled<F> {
+    fn line_height(&self) -> usize {
+        self.font.line_height() * self.scale as usize
+    }
+
+    fn advance(&self, c: char) -> usize {
+        self.font.advance(c) * self.scale as usize
+    }
+
+    fn for_each_pixel(&self, c: char, pixel: &mut dyn FnMut(i32, i32)) {
+        let scale = self.scale as i32;
+        self.font.for_each_pixel(c, &mut |x, y| {
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    pixel(x * scale + dx, y * scale + dy);
+                }
+            }
+        });
+    }
+}
+
+/// Color and blending of text drawn through a [`TextWriter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextStyle<const COLOR_BITS: usize> {
+    /// Color of the lit pixels
+    pub color: Hub75Color<COLOR_BITS>,
+    /// How the lit pixels are combined with the frame content
+    pub blend: BlendMode,
+    /// Blank rows between lines
+    pub line_spacing: usize,
+}
+
+impl<const COLOR_BITS: usize> TextStyle<COLOR_BITS> {
+    /// Create a style replacing the frame content with `color`, with one
+    /// blank row between lines
+    pub fn new(color: Hub75Color<COLOR_BITS>) -> Self {
+        Self {
+            color,
+            blend: BlendMode::Replace,
+            line_spacing: 1,
+        }
+    }
+
+    /// Combine the lit pixels with the frame content using `blend`
+    pub fn with_blend(self, blend: BlendMode) -> Self {
+        Self { blend, ..self }
+    }
+
+    /// Leave `spacing` blank rows between lines
+    pub fn with_line_spacing(self, spacing: usize) -> Self {
+        Self {
+            line_spacing: spacing,
+            ..self
+        }
+    }
+}
+
+impl<const COLOR_BITS: usize> From<Hub75Color<COLOR_BITS>> for TextStyle<COLOR_BITS> {
+    fn from(color: Hub75Color<COLOR_BITS>) -> Self {
+        Self::new(color)
+    }
+}
+
+/// Text cursor drawing everything written to it, created by
+/// [`Hub75FrameBuffer::text_at`], [`Window::text_at`](crate::Window::text_at)
+/// or [`Hub75Display::text_at`](crate::Hub75Display::text_at)
+///
+/// Implements [`core::fmt::Write`], so `write!` formats straight into the
+/// frame. Each string continues where the last one ended, and `'\n'` moves
+/// to the start of the next line. Writing never fails; text outside the frame
+/// or window is clipped.
+pub struct TextWriter<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Frame buffer being drawn into
+    buffer: &'a mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    /// Frame buffer coordinates of the origin of the cursor coordinates
+    offset: Point,
+    /// Region drawn into, in frame buffer coordinates
+    clip: Rectangle,
+    /// Column lines start at, in cursor coordinates
+    left: i32,
+    /// Top-left corner of the next character, in cursor coordinates
+    cursor: Point,
+    /// Color and blending
+    style: TextStyle<COLOR_BITS>,
+}
+
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    TextWriter<'a, WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a writer starting at `start`, translated by `offset` and
+    /// clipped to `clip`, which must lie within the frame
+    pub(crate) fn new(
+        buffer: &'a mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        offset: Point,
+        clip: Rectangle,
+        start: Point,
+        style: TextStyle<COLOR_BITS>,
+    ) -> Self {
+        Self {
+            buffer,
+            offset,
+            clip,
+            left: start.x,
+            cursor: start,
+            style,
+        }
+    }
+
+    /// Get the top-left corner of the next character
+    ///
+    /// In the coordinates the writer was created with, so window coordinates
+    /// for a writer created by [`Window::text_at`](crate::Window::text_at).
+    pub fn position(&self) -> Point {
+        self.cursor
+    }
+
+    /// Change the style of the text written from now on
+    pub fn set_style(&mut self, style: impl Into<TextStyle<COLOR_BITS>>) {
+        self.style = style.into();
+    }
+
+    /// Draw a string without line breaks and advance the cursor past it
+    fn draw_line(&mut self, line: &str) {
+        let style = self.style;
+        let (Some(x), Some(y)) = (
+            self.offset.x.checked_add(self.cursor.x),
+            self.offset.y.checked_add(self.cursor.y),
+        ) else {
+            return;
+        };
+        self.cursor.x = self
+            .buffer
+            .draw_text_clipped(line, Point::new(x, y), style.color, style.blend, self.clip)
+            .saturating_sub(self.offset.x);
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> fmt::Write
+    for TextWriter<'_, WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        let mut lines = text.split('\n');
+        if let Some(first) = lines.next() {
+            self.draw_line(first);
+        }
+        for line in lines {
+            self.cursor = Point::new(
+                self.left,
+                self.cursor.y + (GLYPH_HEIGHT + self.style.line_spacing) as i32,
+            );
+            self.draw_line(line);
+        }
+        Ok(())
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Get a [`TextWriter`] drawing with its top-left corner at `(x, y)`
+    ///
+    /// `style` can be a [`TextStyle`] or just a color.
+    pub fn text_at(
+        &mut self,
+        x: i32,
+        y: i32,
+        style: impl Into<TextStyle<COLOR_BITS>>,
+    ) -> TextWriter<'_, WIDTH, HEIGHT, COLOR_BITS> {
+        let frame = Rectangle::new(Point::zero(), Size::new(WIDTH as u32, HEIGHT as u32));
+        TextWriter::new(self, Point::zero(), frame, Point::new(x, y), style.into())
+    }
+
+    /// Draw a character with its top-left corner at `(x, y)`
+    ///
+    /// Only the lit pixels are drawn; the parts outside the frame are clipped.
+    pub fn draw_char(&mut self, c: char, x: i32, y: i32, color: Hub75Color<COLOR_BITS>) {
+        self.draw_char_blended(c, x, y, color, BlendMode::Replace);
+    }
+
+    /// Draw a character, combining its lit pixels with the frame content
+    /// using `blend`
+    pub fn draw_char_blended(
+        &mut self,
+        c: char,
+        x: i32,
+        y: i32,
+        color: Hub75Color<COLOR_BITS>,
+        blend: BlendMode,
+    ) {
+        let mut buffer = [0; 4];
+        self.draw_text_blended(c.encode_utf8(&mut buffer), x, y, color, blend);
+    }
+
+    /// Draw a string with its top-left corner at `(x, y)`
+    ///
+    /// Returns the x coordinate following the last character, where more
+    /// text can be appended.
+    pub fn draw_text(&mut self, text: &str, x: i32, y: i32, color: Hub75Color<COLOR_BITS>) -> i32 {
+        self.draw_text_blended(text, x, y, color, BlendMode::Replace)
+    }
+
+    /// Draw a string, combining its lit pixels with the frame content using
+    /// `blend`
+    ///
+    /// Returns the x coordinate following the last character.
+    pub fn draw_text_blended(
+        &mut self,
+        text: &str,
+        x: i32,
+        y: i32,
+        color: Hub75Color<COLOR_BITS>,
+        blend: BlendMode,
+    ) -> i32 {
+        let frame = Rectangle::new(Point::zero(), Size::new(WIDTH as u32, HEIGHT as u32));
+        self.draw_text_clipped(text, Point::new(x, y), color, blend, frame)
+    }
+
+    /// Draw a string in `font` with its top-left corner at `(x, y)`
+    ///
+    /// Only the lit pixels are drawn; the parts outside the frame are clipped.
+    /// Returns the x coordinate following the last character.
+    pub fn draw_text_with<F: Font + ?Sized>(
+        &mut self,
+        font: &F,
+        text: &str,
+        x: i32,
+        y: i32,
+        color: Hub75Color<COLOR_BITS>,
+    ) -> i32 {
+        let frame = Rectangle::new(Point::zero(), Size::new(WIDTH as u32, HEIGHT as u32));
+        let origin = Point::new(x, y);
+        self.draw_font_text_clipped(font, text, origin, color, BlendMode::Replace, frame)
+    }
+
+    /// Draw a string in `font` with its top-left corner at `origin`, leaving
+    /// everything outside `clip` untouched
+    ///
+    /// `clip` must lie within the frame. Returns the x coordinate following
+    /// the last character.
+    pub(crate) fn draw_font_text_clipped<F: Font + ?Sized>(
+        &mut self,
+        font: &F,
+        text: &str,
+        origin: Point,
+        color: Hub75Color<COLOR_BITS>,
+        blend: BlendMode,
+        clip: Rectangle,
+    ) -> i32 {
+        let mut cursor = origin.x;
+        for c in text.chars() {
+            if clip.bottom_right().is_some_and(|corner| cursor <= corner.x) {
+                font.for_each_pixel(c, &mut |x, y| {
+                    let point = Point::new(cursor + x, origin.y + y);
+                    if clip.contains(point) {
+                        let (px, py) = (point.x as usize, point.y as usize);
+                        // SAFETY: the clip rectangle lies within the frame
+                        unsafe {
+                            let below = self.get_pixel_unchecked(px, py);
+                            self.set_pixel_unchecked(px, py, blend.apply(below, color));
+                        }
+                    }
+                });
+            }
+            cursor += font.advance(c) as i32;
+        }
+        cursor
+    }
+
+    /// Draw a string with its top-left corner at `origin`, leaving everything
+    /// outside `clip` untouched
+    ///
+    /// `clip` must lie within the frame. Returns the x coordinate following
+    /// the last character.
+    pub(crate) fn draw_text_clipped(
+        &mut self,
+        text: &str,
+        origin: Point,
+        color: Hub75Color<COLOR_BITS>,
+        blend: BlendMode,
+        clip: Rectangle,
+    ) -> i32 {
+        let Some(bottom_right) = clip.bottom_right() else {
+            return origin.x + text_width(text) as i32;
+        };
+        let columns = clip.top_left.x..=bottom_right.x;
+        let rows = clip.top_left.y..=bottom_right.y;
+
+        let mut cursor = origin.x;
+        for c in text.chars() {
+            if cursor > bottom_right.x {
+                break;
+            }
+            for (px, column) in (cursor..).zip(glyph(c)) {
+                if !columns.contains(&px) {
+                    continue;
+                }
+                for (py, row) in (origin.y..).zip(0..GLYPH_HEIGHT) {
+                    if column >> row & 1 == 1 && rows.contains(&py) {
+                        let (px, py) = (px as usize, py as usize);
+                        // SAFETY: the clip rectangle lies within the frame
+                        unsafe {
+                            let below = self.get_pixel_unchecked(px, py);
+                            self.set_pixel_unchecked(px, py, blend.apply(below, color));
+                        }
+                    }
+                }
+            }
+            cursor += GLYPH_ADVANCE as i32;
+        }
+        origin.x + text_width(text) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glyph_lookup() {
+        assert_eq!(glyph(' '), &[0; GLYPH_WIDTH]);
+        assert_eq!(glyph('I'), &[0x00, 0x41, 0x7F, 0x41, 0x00]);
+        assert_eq!(glyph('~'), &GLYPHS[94]);
+        assert_eq!(glyph('\n'), &REPLACEMENT);
+        assert_eq!(glyph('\u{7f}'), &REPLACEMENT);
+        assert_eq!(glyph('中'), &REPLACEMENT);
+
+        // Symbols have glyphs of their own, accented letters share their base
+        assert_eq!(glyph('°'), &[0x00, 0x06, 0x09, 0x09, 0x06]);
+        assert_eq!(glyph('é'), glyph('e'));
+        assert_eq!(glyph('Ł'), glyph('L'));
+        assert_eq!(glyph('ſ'), glyph('s'));
+        assert_eq!(glyph('Æ'), &REPLACEMENT);
+        assert_eq!(fold('ÿ'), Some('y'));
+        assert_eq!(fold('ƀ'), None);
+        assert!(has_glyph('€') && has_glyph('ü') && !has_glyph('Æ'));
+        assert!(SYMBOLS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(text_width("héllo"), 5 * GLYPH_ADVANCE);
+    }
+
+    #[test]
+    fn test_draw_clipped() {
+        let mut frame = Hub75FrameBuffer::<8, 8, 6>::new();
+        let white = Hub75Color::white();
+
+        // The vertical bar of 'I' is column 2, rows 0 to 6
+        frame.draw_char('I', -2, 2, white);
+        assert_eq!(frame.get_pixel(0, 2).unwrap(), white);
+        assert_eq!(frame.get_pixel(0, 7).unwrap(), white);
+        assert_eq!(frame.get_pixel(1, 2).unwrap(), white);
+        assert_eq!(frame.get_pixel(1, 3).unwrap(), Hub75Color::black());
+
+        frame.clear();
+        assert_eq!(
+            frame.draw_text("II", 1, -6, white),
+            1 + 2 * GLYPH_ADVANCE as i32
+        );
+        assert_eq!(frame.get_pixel(3, 0).unwrap(), white);
+        assert_eq!(frame.get_pixel(3, 1).unwrap(), Hub75Color::black());
+    }
+
+    #[test]
+    fn test_font_trait() {
+        let font: &dyn Font = &Font5x7;
+        assert_eq!(font.text_width("abc"), text_width("abc"));
+        assert_eq!(font.line_height(), GLYPH_HEIGHT);
+
+        // Drawing through the trait matches the built-in path
+        let mut expected = Hub75FrameBuffer::<16, 8, 6>::new();
+        let mut frame = Hub75FrameBuffer::<16, 8, 6>::new();
+        let red = Hub75Color::red();
+        assert_eq!(
+            frame.draw_text_with(font, "A#", -2, 1, red),
+            expected.draw_text("A#", -2, 1, red)
+        );
+        assert!(frame == expected);
+    }
+
+    #[test]
+    fn test_scaled_font() {
+        let font = Scaled::new(Font5x7, 2);
+        assert_eq!(font.line_height(), 2 * GLYPH_HEIGHT);
+        assert_eq!(font.text_width("12"), 4 * GLYPH_ADVANCE);
+        assert_eq!(Scaled::new(Font5x7, 0).scale(), 1);
+
+        // The bar of '|' becomes two columns wide and twice as tall
+        let mut frame = Hub75FrameBuffer::<16, 16, 6>::new();
+        let white = Hub75Color::white();
+        frame.draw_text_with(&font, "|", 0, 0, white);
+        assert_eq!(frame.get_pixel(4, 0).unwrap(), white);
+        assert_eq!(frame.get_pixel(5, 13).unwrap(), white);
+        assert_eq!(frame.get_pixel(6, 0).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(4, 14).unwrap(), Hub75Color::black());
+    }
+
+    #[test]
+    fn test_text_writer() {
+        use core::fmt::Write;
+
+        let mut frame = Hub75FrameBuffer::<16, 20, 6>::new();
+        let white = Hub75Color::white();
+
+        // The vertical bar of '|' is column 2, rows 0 to 6
+        let mut writer = frame.text_at(-6, 1, white);
+        write!(writer, "{}|", 1).unwrap();
+        assert_eq!(writer.position(), Point::new(6, 1));
+        writer.set_style(TextStyle::new(white).with_line_spacing(3));
+        write!(writer, "\n |").unwrap();
+        assert_eq!(writer.position(), Point::new(6, 11));
+
+        assert_eq!(frame.get_pixel(2, 1).unwrap(), white);
+        assert_eq!(frame.get_pixel(2, 0).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(2, 11).unwrap(), white);
+        assert_eq!(frame.get_pixel(2, 10).unwrap(), Hub75Color::black());
+    }
+}
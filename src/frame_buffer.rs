@@ -1,32 +1,109 @@
 //! Frame buffer management for HUB75 displays
 
-use crate::{color::Hub75Color, Hub75Error};
+use crate::{
+    color::{Hub75Color, Hub75Pixel},
+    palette::Palette,
+    window::Window,
+    Hub75Error,
+};
+use embedded_graphics_core::{
+    geometry::{Point, Size},
+    primitives::Rectangle,
+};
 use heapless::Vec;
 
+/// FNV-1a offset basis used for content hashing
+pub(crate) const FNV_OFFSET_BASIS: u32 = 0x811c_9dc5;
+/// FNV-1a prime used for content hashing
+const FNV_PRIME: u32 = 0x0100_0193;
+
+/// Feed a single byte into an FNV-1a hash
+#[inline(always)]
+pub(crate) const fn fnv1a(hash: u32, byte: u8) -> u32 {
+    (hash ^ byte as u32).wrapping_mul(FNV_PRIME)
+}
+
+/// Box blur one row or column of pixels, passing each result to `set`
+///
+/// Keeps a running sum of the window so each pixel costs one add and one
+/// subtract regardless of `radius`.
+fn box_blur_line<const BITS: usize, P: Hub75Pixel<BITS>>(
+    line: &[P],
+    radius: usize,
+    mut set: impl FnMut(usize, P),
+) {
+    let channels = |color: P| color.levels();
+    let len = line.len();
+    let mut sum = [0u32; 3];
+
+    for &color in &line[..radius.min(len)] {
+        sum.iter_mut()
+            .zip(channels(color))
+            .for_each(|(total, c)| *total += c);
+    }
+    for i in 0..len {
+        if let Some(&entering) = line.get(i + radius) {
+            sum.iter_mut()
+                .zip(channels(entering))
+                .for_each(|(total, c)| *total += c);
+        }
+        if i > radius {
+            let leaving = line[i - radius - 1];
+            sum.iter_mut()
+                .zip(channels(leaving))
+                .for_each(|(total, c)| *total -= c);
+        }
+        let count = ((i + radius).min(len - 1) + 1 - i.saturating_sub(radius)) as u32;
+        set(
+            i,
+            P::from_levels(sum.map(|total| (total + count / 2) / count)),
+        );
+    }
+}
+
 /// Frame buffer for storing pixel data
+///
+/// Pixels are [`Hub75Color`]s unless another [`Hub75Pixel`] type is named,
+/// such as [`Hub75Color16`](crate::Hub75Color16) for depths above 8 bits.
+/// Buffers of any pixel type can be filled, copied and scanned out; drawing
+/// text, windows and embedded-graphics need `Hub75Color` pixels.
+///
+/// ```rust
+/// use hub75::{Hub75Color16, Hub75FrameBuffer};
+///
+/// let mut frame = Hub75FrameBuffer::<64, 32, 12, Hub75Color16<12>>::default();
+/// frame.set_pixel(0, 0, Hub75Color16::new(4095, 0, 0))?;
+/// # Ok::<(), hub75::Hub75Error>(())
+/// ```
 #[derive(Debug, PartialEq, Eq)]
-pub struct Hub75FrameBuffer<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+pub struct Hub75FrameBuffer<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    P = Hub75Color<COLOR_BITS>,
+> {
     /// Pixel data stored as a flat array
-    pixels: [[Hub75Color<COLOR_BITS>; WIDTH]; HEIGHT],
+    pixels: [[P; WIDTH]; HEIGHT],
 }
 
-impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
-    Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+impl<
+        const WIDTH: usize,
+        const HEIGHT: usize,
+        const COLOR_BITS: usize,
+        P: Hub75Pixel<COLOR_BITS>,
+    > Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P>
 {
-    /// Create a new frame buffer filled with black pixels
-    pub fn new() -> Self {
-        Self {
-            pixels: [[Hub75Color::black(); WIDTH]; HEIGHT],
-        }
-    }
+    /// Fails to compile when `COLOR_BITS` is 0 or does not fit in a channel of
+    /// the pixel type
+    const COLOR_BITS_VALID: () = assert!(P::MAX_LEVEL >= 1);
 
     /// Clear the frame buffer (set all pixels to black)
     pub fn clear(&mut self) {
-        self.fill(Hub75Color::black());
+        self.fill(P::default());
     }
 
     /// Fill the entire frame buffer with a single color
-    pub fn fill(&mut self, color: Hub75Color<COLOR_BITS>) {
+    pub fn fill(&mut self, color: P) {
         for row in &mut self.pixels {
             for pixel in row {
                 *pixel = color;
@@ -34,13 +111,39 @@ impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
         }
     }
 
+    /// Draw a horizontal line of `length` pixels starting at (x, y)
+    ///
+    /// The line is clipped to the frame buffer; pixels outside it are ignored.
+    pub fn hline(&mut self, x: usize, y: usize, length: usize, color: P) {
+        self.fill_rect(x, y, length, 1, color);
+    }
+
+    /// Draw a vertical line of `length` pixels starting at (x, y)
+    ///
+    /// The line is clipped to the frame buffer; pixels outside it are ignored.
+    pub fn vline(&mut self, x: usize, y: usize, length: usize, color: P) {
+        self.fill_rect(x, y, 1, length, color);
+    }
+
+    /// Fill a `width` x `height` rectangle with its top-left corner at (x, y)
+    ///
+    /// Each row of the rectangle is written as a single contiguous span. The
+    /// rectangle is clipped to the frame buffer; pixels outside it are ignored.
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: P) {
+        if x >= WIDTH || y >= HEIGHT {
+            return;
+        }
+
+        let x_end = x.saturating_add(width).min(WIDTH);
+        let y_end = y.saturating_add(height).min(HEIGHT);
+        for row in &mut self.pixels[y..y_end] {
+            row[x..x_end].fill(color);
+        }
+    }
+
     /// Get a mutable reference to a pixel at the specified coordinates
     #[inline(always)]
-    pub fn pixel_mut(
-        &mut self,
-        x: usize,
-        y: usize,
-    ) -> Result<&mut Hub75Color<COLOR_BITS>, Hub75Error> {
+    pub fn pixel_mut(&mut self, x: usize, y: usize) -> Result<&mut P, Hub75Error> {
         if x >= WIDTH || y >= HEIGHT {
             Err(Hub75Error::InvalidCoordinates)
         } else {
@@ -50,7 +153,7 @@ impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
 
     /// Get a reference to a pixel at the specified coordinates
     #[inline(always)]
-    pub fn pixel(&self, x: usize, y: usize) -> Result<&Hub75Color<COLOR_BITS>, Hub75Error> {
+    pub fn pixel(&self, x: usize, y: usize) -> Result<&P, Hub75Error> {
         if x >= WIDTH || y >= HEIGHT {
             Err(Hub75Error::InvalidCoordinates)
         } else {
@@ -60,19 +163,14 @@ impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
 
     /// Set a pixel at the specified coordinates
     #[inline(always)]
-    pub fn set_pixel(
-        &mut self,
-        x: usize,
-        y: usize,
-        color: Hub75Color<COLOR_BITS>,
-    ) -> Result<(), Hub75Error> {
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: P) -> Result<(), Hub75Error> {
         *self.pixel_mut(x, y)? = color;
         Ok(())
     }
 
     /// Get a pixel at the specified coordinates
     #[inline(always)]
-    pub fn get_pixel(&self, x: usize, y: usize) -> Result<Hub75Color<COLOR_BITS>, Hub75Error> {
+    pub fn get_pixel(&self, x: usize, y: usize) -> Result<P, Hub75Error> {
         Ok(*self.pixel(x, y)?)
     }
 
@@ -81,7 +179,7 @@ impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
     /// # Safety
     /// The caller must ensure that x < WIDTH and y < HEIGHT
     #[inline(always)]
-    pub unsafe fn get_pixel_unchecked(&self, x: usize, y: usize) -> Hub75Color<COLOR_BITS> {
+    pub unsafe fn get_pixel_unchecked(&self, x: usize, y: usize) -> P {
         *self.pixels.get_unchecked(y).get_unchecked(x)
     }
 
@@ -90,12 +188,7 @@ impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
     /// # Safety
     /// The caller must ensure that x < WIDTH and y < HEIGHT
     #[inline(always)]
-    pub unsafe fn set_pixel_unchecked(
-        &mut self,
-        x: usize,
-        y: usize,
-        color: Hub75Color<COLOR_BITS>,
-    ) {
+    pub unsafe fn set_pixel_unchecked(&mut self, x: usize, y: usize, color: P) {
         *self.pixels.get_unchecked_mut(y).get_unchecked_mut(x) = color;
     }
 
@@ -115,7 +208,7 @@ impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
     }
 
     /// Get a row of pixels for efficient scanning
-    pub fn get_row(&self, y: usize) -> Result<&[Hub75Color<COLOR_BITS>; WIDTH], Hub75Error> {
+    pub fn get_row(&self, y: usize) -> Result<&[P; WIDTH], Hub75Error> {
         if y >= HEIGHT {
             return Err(Hub75Error::InvalidCoordinates);
         }
@@ -124,10 +217,7 @@ impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
     }
 
     /// Get a mutable row of pixels for efficient modification
-    pub fn get_row_mut(
-        &mut self,
-        y: usize,
-    ) -> Result<&mut [Hub75Color<COLOR_BITS>; WIDTH], Hub75Error> {
+    pub fn get_row_mut(&mut self, y: usize) -> Result<&mut [P; WIDTH], Hub75Error> {
         if y >= HEIGHT {
             return Err(Hub75Error::InvalidCoordinates);
         }
@@ -145,8 +235,91 @@ impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
         core::mem::swap(&mut self.pixels, &mut other.pixels);
     }
 
+    /// Fade every pixel toward black, leaving a trail of earlier frames
+    ///
+    /// `amount` is a 0.8 fixed-point fraction removed from each channel: 0
+    /// leaves the frame unchanged and 255 clears it. Results are rounded
+    /// down, so any non-zero amount darkens every lit channel by at least
+    /// one level and trails always fade out completely. Call once per frame
+    /// before drawing the new content, e.g. for Matrix rain or radar sweeps.
+    pub fn decay(&mut self, amount: u8) {
+        if amount == 0 {
+            return;
+        }
+        let keep = 255 - amount as u32;
+        for pixel in self.pixels.iter_mut().flatten() {
+            *pixel = P::from_levels(pixel.levels().map(|level| level * keep / 255));
+        }
+    }
+
+    /// Soften the frame with a box blur of the given radius
+    ///
+    /// Each pixel becomes the average of the `(2 * radius + 1)` square around
+    /// it, computed as a horizontal and a vertical pass with running sums, so
+    /// the cost does not grow with the radius. Only a single row or column is
+    /// buffered at a time. Pixels near the edges average over the part of the
+    /// square inside the frame. For glow or soft shadows, blur a separate
+    /// layer and combine it with [`BlendMode::Add`](crate::composite::BlendMode::Add)
+    /// or a darker blend.
+    pub fn blur(&mut self, radius: usize) {
+        if radius == 0 {
+            return;
+        }
+
+        let mut row = [P::default(); WIDTH];
+        for y in 0..HEIGHT {
+            row.copy_from_slice(&self.pixels[y]);
+            box_blur_line(&row, radius, |x, color| self.pixels[y][x] = color);
+        }
+
+        let mut column = [P::default(); HEIGHT];
+        for x in 0..WIDTH {
+            for (y, pixel) in column.iter_mut().enumerate() {
+                *pixel = self.pixels[y][x];
+            }
+            box_blur_line(&column, radius, |y, color| self.pixels[y][x] = color);
+        }
+    }
+
+    /// Compute a cheap content hash of a single row
+    ///
+    /// Equal rows always produce equal hashes, so comparing the hash of a row
+    /// against a previously stored value is a fast way to detect changes.
+    pub fn row_hash(&self, y: usize) -> Result<u32, Hub75Error> {
+        let row = self.get_row(y)?;
+        Ok(Self::hash_row(row))
+    }
+
+    /// Compute a cheap content hash of the whole frame
+    ///
+    /// Useful for "render only on change" logic: store the hash of the last
+    /// displayed frame and skip redundant work when it is unchanged.
+    pub fn content_hash(&self) -> u32 {
+        let mut hash = FNV_OFFSET_BASIS;
+        for row in &self.pixels {
+            for byte in Self::hash_row(row).to_le_bytes() {
+                hash = fnv1a(hash, byte);
+            }
+        }
+        hash
+    }
+
+    /// Hash a row of pixels with FNV-1a
+    fn hash_row(row: &[P; WIDTH]) -> u32 {
+        let mut hash = FNV_OFFSET_BASIS;
+        for pixel in row {
+            for level in pixel.levels() {
+                for &byte in &level.to_le_bytes()[..P::CHANNEL_BYTES] {
+                    hash = fnv1a(hash, byte);
+                }
+            }
+        }
+        hash
+    }
+
     /// Get RGB bit values for a specific row and bit plane
     /// Returns vectors of (upper_r, upper_g, upper_b, lower_r, lower_g, lower_b) for each column
+    #[allow(clippy::type_complexity)]
     pub fn get_row_bit_plane(
         &self,
         row: usize,
@@ -177,6 +350,104 @@ impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
         Ok(result)
     }
 
+    /// Convert frame buffer to raw RGB data
+    pub fn to_rgb_data(&self) -> heapless::Vec<u8, 65536> {
+        let mut data = heapless::Vec::new();
+
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let color = self.pixels[y][x];
+                let (r, g, b) = color.to_rgb8();
+                data.push(r).ok();
+                data.push(g).ok();
+                data.push(b).ok();
+            }
+        }
+
+        data
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a new frame buffer filled with black pixels
+    ///
+    /// Fails to compile when `COLOR_BITS` is 0 or above 8, which needs
+    /// [`Hub75Color16`](crate::Hub75Color16) pixels:
+    ///
+    /// ```rust,compile_fail
+    /// let frame = hub75::Hub75FrameBuffer::<64, 32, 0>::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Convert every pixel to gray of the same perceived brightness
+    pub fn to_grayscale(&mut self) {
+        for row in &mut self.pixels {
+            for pixel in row {
+                *pixel = pixel.to_grayscale();
+            }
+        }
+    }
+
+    /// Blend every pixel towards the matching pixel of another frame
+    ///
+    /// `t` is a 0.8 fixed-point fraction: 0 leaves the frame unchanged and
+    /// 255 copies `other`.
+    pub fn lerp(&mut self, other: &Self, t: u8) {
+        for (pixel, &target) in self
+            .pixels
+            .iter_mut()
+            .flatten()
+            .zip(other.pixels.iter().flatten())
+        {
+            *pixel = Hub75Color::lerp(*pixel, target, t);
+        }
+    }
+
+    /// Get the average perceived brightness of the frame
+    ///
+    /// Returned at the frame buffer's bit depth. Useful for brightness
+    /// heuristics that take frame content into account.
+    pub fn average_luma(&self) -> u8 {
+        if WIDTH * HEIGHT == 0 {
+            return 0;
+        }
+
+        let total: u64 = self
+            .pixels
+            .iter()
+            .flatten()
+            .map(|pixel| pixel.luma() as u64)
+            .sum();
+        (total / (WIDTH * HEIGHT) as u64) as u8
+    }
+
+    /// Get a clipped and translated view onto a region of the frame buffer
+    ///
+    /// Coordinates passed to the returned [`Window`] are relative to the
+    /// top-left corner of `area`, and drawing outside `area` is clipped.
+    pub fn window(&mut self, area: Rectangle) -> Window<'_, WIDTH, HEIGHT, COLOR_BITS> {
+        Window::new(self, area)
+    }
+
+    /// Iterate over the rectangles in which this frame differs from `other`
+    ///
+    /// Every differing pixel is covered by exactly one rectangle and no
+    /// rectangle covers an unchanged pixel. Horizontal runs of changed pixels
+    /// are merged with identical runs on the rows below them, so solid changed
+    /// areas are reported as a single rectangle.
+    pub fn diff<'a>(&'a self, other: &'a Self) -> FrameDiff<'a, WIDTH, HEIGHT, COLOR_BITS> {
+        FrameDiff {
+            a: self,
+            b: other,
+            x: 0,
+            y: 0,
+        }
+    }
+
     /// Create a frame buffer from raw RGB data
     pub fn from_rgb_data(data: &[u8]) -> Result<Self, Hub75Error> {
         if data.len() != WIDTH * HEIGHT * 3 {
@@ -196,34 +467,145 @@ impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
         Ok(buffer)
     }
 
-    /// Convert frame buffer to raw RGB data
-    pub fn to_rgb_data(&self) -> heapless::Vec<u8, 65536> {
-        let mut data = heapless::Vec::new();
+    /// Create frame buffer from raw RGB data with ordered dithering
+    ///
+    /// Like [`from_rgb_data`](Self::from_rgb_data), but uses
+    /// [`Hub75Color::from_rgb8_dithered`] to avoid banding at low bit depths.
+    pub fn from_rgb_data_dithered(data: &[u8]) -> Result<Self, Hub75Error> {
+        if data.len() != WIDTH * HEIGHT * 3 {
+            return Err(Hub75Error::InvalidColor);
+        }
 
-        for y in 0..HEIGHT {
-            for x in 0..WIDTH {
-                let color = self.pixels[y][x];
-                let (r, g, b) = color.to_rgb8();
-                data.push(r).ok();
-                data.push(g).ok();
-                data.push(b).ok();
+        let mut buffer = Self::new();
+
+        for (y, row) in buffer.pixels.iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                let idx = (y * WIDTH + x) * 3;
+                *pixel =
+                    Hub75Color::from_rgb8_dithered(data[idx], data[idx + 1], data[idx + 2], x, y);
             }
         }
 
-        data
+        Ok(buffer)
+    }
+
+    /// Fill the frame buffer from palette indices (one byte per pixel)
+    ///
+    /// Returns `Hub75Error::InvalidColor` if the number of indices does not
+    /// match the frame size or an index is outside the palette, leaving the
+    /// frame unchanged.
+    pub fn draw_indexed<const N: usize>(
+        &mut self,
+        indices: &[u8],
+        palette: &Palette<N>,
+    ) -> Result<(), Hub75Error> {
+        if indices.len() != WIDTH * HEIGHT || indices.iter().any(|&index| index as usize >= N) {
+            return Err(Hub75Error::InvalidColor);
+        }
+
+        for (i, &index) in indices.iter().enumerate() {
+            let color = palette.color(index).ok_or(Hub75Error::InvalidColor)?;
+            // SAFETY: i < WIDTH * HEIGHT, so both coordinates are in bounds
+            unsafe { self.set_pixel_unchecked(i % WIDTH, i / WIDTH, color) };
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the changed regions between two frame buffers
+///
+/// Created by [`Hub75FrameBuffer::diff`].
+pub struct FrameDiff<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    a: &'a Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    b: &'a Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    x: usize,
+    y: usize,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameDiff<'_, WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Check whether the pixel at the given coordinates differs
+    #[inline(always)]
+    fn differs(&self, x: usize, y: usize) -> bool {
+        self.a.pixels[y][x] != self.b.pixels[y][x]
+    }
+
+    /// Check whether row `y` contains exactly the changed run `start..end`
+    fn has_run(&self, y: usize, start: usize, end: usize) -> bool {
+        (start..end).all(|x| self.differs(x, y))
+            && (start == 0 || !self.differs(start - 1, y))
+            && (end == WIDTH || !self.differs(end, y))
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> Iterator
+    for FrameDiff<'_, WIDTH, HEIGHT, COLOR_BITS>
+{
+    type Item = Rectangle;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.y < HEIGHT {
+            let y = self.y;
+
+            while self.x < WIDTH {
+                if !self.differs(self.x, y) {
+                    self.x += 1;
+                    continue;
+                }
+
+                let start = self.x;
+                while self.x < WIDTH && self.differs(self.x, y) {
+                    self.x += 1;
+                }
+                let end = self.x;
+
+                // Runs continuing one from the row above were already reported
+                if y > 0 && self.has_run(y - 1, start, end) {
+                    continue;
+                }
+
+                let mut height = 1;
+                while y + height < HEIGHT && self.has_run(y + height, start, end) {
+                    height += 1;
+                }
+
+                return Some(Rectangle::new(
+                    Point::new(start as i32, y as i32),
+                    Size::new((end - start) as u32, height as u32),
+                ));
+            }
+
+            self.x = 0;
+            self.y += 1;
+        }
+
+        None
     }
 }
 
-impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> Default
-    for Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+impl<
+        const WIDTH: usize,
+        const HEIGHT: usize,
+        const COLOR_BITS: usize,
+        P: Hub75Pixel<COLOR_BITS>,
+    > Default for Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P>
 {
     fn default() -> Self {
-        Self::new()
+        #[allow(clippy::let_unit_value)]
+        let () = Self::COLOR_BITS_VALID;
+        Self {
+            pixels: [[P::default(); WIDTH]; HEIGHT],
+        }
     }
 }
 
-impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> Clone
-    for Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+impl<
+        const WIDTH: usize,
+        const HEIGHT: usize,
+        const COLOR_BITS: usize,
+        P: Hub75Pixel<COLOR_BITS>,
+    > Clone for Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS, P>
 {
     fn clone(&self) -> Self {
         Self {
@@ -235,10 +617,12 @@ impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> Clone
 #[cfg(feature = "embedded-graphics")]
 mod embedded_graphics_support {
     use super::*;
+    use core::marker::PhantomData;
     use embedded_graphics_core::{
         draw_target::DrawTarget,
-        geometry::{OriginDimensions, Size},
-        pixelcolor::Rgb565,
+        geometry::{Dimensions, OriginDimensions, Size},
+        pixelcolor::{PixelColor, Rgb565, Rgb888, RgbColor},
+        primitives::{PointsIter, Rectangle},
         Pixel,
     };
 
@@ -262,6 +646,51 @@ mod embedded_graphics_support {
             }
             Ok(())
         }
+
+        fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Self::Color>,
+        {
+            let drawable_area = area.intersection(&self.bounding_box());
+
+            // Partially visible areas need per-pixel clipping
+            if drawable_area != *area {
+                return self.draw_iter(
+                    area.points()
+                        .zip(colors)
+                        .filter(|(point, _)| drawable_area.contains(*point))
+                        .map(|(point, color)| Pixel(point, color)),
+                );
+            }
+
+            let mut colors = colors.into_iter();
+            for y in area.rows() {
+                let row = &mut self.pixels[y as usize];
+                for x in area.columns() {
+                    match colors.next() {
+                        Some(color) => row[x as usize] = Hub75Color::from(color),
+                        None => return Ok(()),
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+            let area = area.intersection(&self.bounding_box());
+            if area.is_zero_sized() {
+                return Ok(());
+            }
+
+            self.fill_rect(
+                area.top_left.x as usize,
+                area.top_left.y as usize,
+                area.size.width as usize,
+                area.size.height as usize,
+                Hub75Color::from(color),
+            );
+            Ok(())
+        }
     }
 
     impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> OriginDimensions
@@ -271,8 +700,137 @@ mod embedded_graphics_support {
             Size::new(WIDTH as u32, HEIGHT as u32)
         }
     }
+
+    impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+        Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+    {
+        /// Get a draw target that accepts native [`Hub75Color`] values
+        ///
+        /// The frame buffer itself is a `DrawTarget<Color = Rgb565>`. Drawing
+        /// through the returned adapter stores colors exactly as given, without
+        /// a round trip through `Rgb565`.
+        pub fn native_target(&mut self) -> NativeTarget<'_, WIDTH, HEIGHT, COLOR_BITS> {
+            NativeTarget { buffer: self }
+        }
+
+        /// Get a draw target that converts colors with ordered dithering
+        ///
+        /// Accepts any color convertible to `Rgb888` (such as `Rgb565` or
+        /// `Rgb888` itself) and stores it using
+        /// [`Hub75Color::from_rgb8_dithered`], which keeps gradients from
+        /// banding at 3-4 bit depths.
+        pub fn dithered_target<C>(&mut self) -> DitheredTarget<'_, C, WIDTH, HEIGHT, COLOR_BITS>
+        where
+            C: PixelColor + Into<Rgb888>,
+        {
+            DitheredTarget {
+                buffer: self,
+                _color: PhantomData,
+            }
+        }
+    }
+
+    /// Draw target over a frame buffer using native [`Hub75Color`] values
+    ///
+    /// Created by [`Hub75FrameBuffer::native_target`].
+    pub struct NativeTarget<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+        buffer: &'a mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    }
+
+    impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> DrawTarget
+        for NativeTarget<'_, WIDTH, HEIGHT, COLOR_BITS>
+    {
+        type Color = Hub75Color<COLOR_BITS>;
+        type Error = Hub75Error;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            for Pixel(coord, color) in pixels {
+                let x = coord.x as usize;
+                let y = coord.y as usize;
+                if x < WIDTH && y < HEIGHT {
+                    self.buffer.pixels[y][x] = color;
+                }
+            }
+            Ok(())
+        }
+
+        fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+            let area = area.intersection(&self.bounding_box());
+            if !area.is_zero_sized() {
+                self.buffer.fill_rect(
+                    area.top_left.x as usize,
+                    area.top_left.y as usize,
+                    area.size.width as usize,
+                    area.size.height as usize,
+                    color,
+                );
+            }
+            Ok(())
+        }
+    }
+
+    impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> OriginDimensions
+        for NativeTarget<'_, WIDTH, HEIGHT, COLOR_BITS>
+    {
+        fn size(&self) -> Size {
+            Size::new(WIDTH as u32, HEIGHT as u32)
+        }
+    }
+
+    /// Draw target over a frame buffer that dithers incoming colors
+    ///
+    /// Created by [`Hub75FrameBuffer::dithered_target`].
+    pub struct DitheredTarget<
+        'a,
+        C,
+        const WIDTH: usize,
+        const HEIGHT: usize,
+        const COLOR_BITS: usize,
+    > {
+        buffer: &'a mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        _color: PhantomData<C>,
+    }
+
+    impl<C, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> DrawTarget
+        for DitheredTarget<'_, C, WIDTH, HEIGHT, COLOR_BITS>
+    where
+        C: PixelColor + Into<Rgb888>,
+    {
+        type Color = C;
+        type Error = Hub75Error;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            for Pixel(coord, color) in pixels {
+                let x = coord.x as usize;
+                let y = coord.y as usize;
+                if x < WIDTH && y < HEIGHT {
+                    let color: Rgb888 = color.into();
+                    self.buffer.pixels[y][x] =
+                        Hub75Color::from_rgb8_dithered(color.r(), color.g(), color.b(), x, y);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl<C, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> OriginDimensions
+        for DitheredTarget<'_, C, WIDTH, HEIGHT, COLOR_BITS>
+    {
+        fn size(&self) -> Size {
+            Size::new(WIDTH as u32, HEIGHT as u32)
+        }
+    }
 }
 
+#[cfg(feature = "embedded-graphics")]
+pub use embedded_graphics_support::{DitheredTarget, NativeTarget};
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -319,4 +877,260 @@ mod tests {
         assert_eq!(buffer.get_pixel(0, 0).unwrap(), Hub75Color::black());
         assert_eq!(buffer.get_pixel(63, 31).unwrap(), Hub75Color::black());
     }
+
+    #[test]
+    fn test_lines_and_rects() {
+        let mut buffer = Hub75FrameBuffer::<16, 8, 6>::new();
+        let red = Hub75Color::red();
+
+        buffer.hline(14, 0, 5, red);
+        assert_eq!(buffer.get_pixel(14, 0).unwrap(), red);
+        assert_eq!(buffer.get_pixel(15, 0).unwrap(), red);
+        assert_eq!(buffer.get_pixel(13, 0).unwrap(), Hub75Color::black());
+
+        buffer.vline(2, 6, 10, red);
+        assert_eq!(buffer.get_pixel(2, 6).unwrap(), red);
+        assert_eq!(buffer.get_pixel(2, 7).unwrap(), red);
+        assert_eq!(buffer.get_pixel(2, 5).unwrap(), Hub75Color::black());
+
+        buffer.fill_rect(4, 2, 3, 2, Hub75Color::blue());
+        assert_eq!(buffer.get_pixel(4, 2).unwrap(), Hub75Color::blue());
+        assert_eq!(buffer.get_pixel(6, 3).unwrap(), Hub75Color::blue());
+        assert_eq!(buffer.get_pixel(7, 3).unwrap(), Hub75Color::black());
+        assert_eq!(buffer.get_pixel(4, 4).unwrap(), Hub75Color::black());
+
+        // Entirely off-buffer primitives are ignored
+        buffer.fill_rect(16, 0, 4, 4, red);
+        buffer.hline(0, 8, 4, red);
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let mut a = Hub75FrameBuffer::<64, 32, 6>::new();
+        let b = Hub75FrameBuffer::<64, 32, 6>::new();
+        assert_eq!(a.content_hash(), b.content_hash());
+
+        a.set_pixel(10, 5, Hub75Color::red()).unwrap();
+        assert_ne!(a.content_hash(), b.content_hash());
+        assert_ne!(a.row_hash(5).unwrap(), b.row_hash(5).unwrap());
+        assert_eq!(a.row_hash(6).unwrap(), b.row_hash(6).unwrap());
+        assert!(a.row_hash(32).is_err());
+    }
+
+    #[test]
+    fn test_deep_color_frame() {
+        use crate::color::Hub75Color16;
+
+        let mut frame = Hub75FrameBuffer::<4, 2, 12, Hub75Color16<12>>::default();
+        frame.fill(Hub75Color16::new(4000, 100, 0));
+        frame.set_pixel(0, 1, Hub75Color16::white()).unwrap();
+        assert_eq!(
+            frame.get_row_bit_plane(0, 11).unwrap()[0],
+            (true, false, false, true, true, true)
+        );
+
+        // Levels above 8 bits survive decay and blur without wrapping
+        frame.decay(128);
+        assert_eq!(
+            frame.get_pixel(1, 0).unwrap(),
+            Hub75Color16::new(1992, 49, 0)
+        );
+        frame.blur(1);
+        assert_eq!(
+            frame.get_pixel(3, 0).unwrap(),
+            Hub75Color16::new(1992, 49, 0)
+        );
+
+        // Hashes tell apart levels differing in the upper byte
+        let mut other = frame.clone();
+        other.set_pixel(2, 1, Hub75Color16::new(256, 0, 0)).unwrap();
+        frame.set_pixel(2, 1, Hub75Color16::new(0, 0, 0)).unwrap();
+        assert_ne!(frame.row_hash(1).unwrap(), other.row_hash(1).unwrap());
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut a = Hub75FrameBuffer::<16, 8, 6>::new();
+        let b = Hub75FrameBuffer::<16, 8, 6>::new();
+        assert_eq!(a.diff(&b).count(), 0);
+
+        // A 3x2 block and a single pixel
+        for y in 2..4 {
+            for x in 4..7 {
+                a.set_pixel(x, y, Hub75Color::red()).unwrap();
+            }
+        }
+        a.set_pixel(15, 7, Hub75Color::blue()).unwrap();
+
+        let mut diff = a.diff(&b);
+        assert_eq!(
+            diff.next(),
+            Some(Rectangle::new(Point::new(4, 2), Size::new(3, 2)))
+        );
+        assert_eq!(
+            diff.next(),
+            Some(Rectangle::new(Point::new(15, 7), Size::new(1, 1)))
+        );
+        assert_eq!(diff.next(), None);
+
+        // An L shape is split into two rectangles covering every changed pixel
+        let mut c = Hub75FrameBuffer::<16, 8, 6>::new();
+        c.set_pixel(0, 0, Hub75Color::red()).unwrap();
+        c.set_pixel(0, 1, Hub75Color::red()).unwrap();
+        c.set_pixel(1, 1, Hub75Color::red()).unwrap();
+        let area: u32 = c.diff(&b).map(|r| r.size.width * r.size.height).sum();
+        assert_eq!(area, 3);
+    }
+
+    #[cfg(feature = "embedded-graphics")]
+    #[test]
+    fn test_fill_fast_paths() {
+        use embedded_graphics_core::{draw_target::DrawTarget, pixelcolor::Rgb565, prelude::*};
+
+        let mut buffer = Hub75FrameBuffer::<16, 8, 6>::new();
+
+        // Solid fill clipped against the right and bottom edges
+        let area = Rectangle::new(Point::new(12, 6), Size::new(10, 10));
+        buffer.fill_solid(&area, Rgb565::RED).unwrap();
+        assert_eq!(buffer.get_pixel(12, 6).unwrap(), Hub75Color::red());
+        assert_eq!(buffer.get_pixel(15, 7).unwrap(), Hub75Color::red());
+        assert_eq!(buffer.get_pixel(11, 6).unwrap(), Hub75Color::black());
+
+        // Contiguous fill entirely inside the buffer
+        let area = Rectangle::new(Point::new(0, 0), Size::new(2, 2));
+        let colors = [Rgb565::RED, Rgb565::GREEN, Rgb565::BLUE, Rgb565::WHITE];
+        buffer.fill_contiguous(&area, colors).unwrap();
+        assert_eq!(buffer.get_pixel(1, 0).unwrap(), Hub75Color::green());
+        assert_eq!(buffer.get_pixel(0, 1).unwrap(), Hub75Color::blue());
+
+        // Contiguous fill hanging off the left edge keeps the color order
+        let area = Rectangle::new(Point::new(-1, 4), Size::new(2, 1));
+        buffer
+            .fill_contiguous(&area, [Rgb565::RED, Rgb565::GREEN])
+            .unwrap();
+        assert_eq!(buffer.get_pixel(0, 4).unwrap(), Hub75Color::green());
+    }
+
+    #[test]
+    fn test_grayscale() {
+        let mut buffer = Hub75FrameBuffer::<8, 4, 8>::new();
+        assert_eq!(buffer.average_luma(), 0);
+
+        buffer.fill_rect(0, 0, 4, 4, Hub75Color::red());
+        buffer.fill_rect(4, 0, 4, 4, Hub75Color::white());
+        // Half red (luma 77), half white (luma 255)
+        assert_eq!(buffer.average_luma(), 166);
+
+        buffer.to_grayscale();
+        assert_eq!(buffer.get_pixel(0, 0).unwrap(), Hub75Color::new(77, 77, 77));
+        assert_eq!(buffer.get_pixel(7, 3).unwrap(), Hub75Color::white());
+    }
+
+    #[test]
+    fn test_decay() {
+        let mut buffer = Hub75FrameBuffer::<4, 2, 4>::new();
+        buffer.set_pixel(0, 0, Hub75Color::new(15, 8, 1)).unwrap();
+
+        buffer.decay(0);
+        assert_eq!(buffer.get_pixel(0, 0).unwrap(), Hub75Color::new(15, 8, 1));
+
+        buffer.decay(128);
+        assert_eq!(buffer.get_pixel(0, 0).unwrap(), Hub75Color::new(7, 3, 0));
+
+        // Even a tiny amount fades trails out completely
+        for _ in 0..15 {
+            buffer.decay(1);
+        }
+        assert_eq!(buffer.get_pixel(0, 0).unwrap(), Hub75Color::black());
+
+        buffer.fill(Hub75Color::white());
+        buffer.decay(255);
+        assert_eq!(buffer.average_luma(), 0);
+    }
+
+    #[test]
+    fn test_blur() {
+        let mut buffer = Hub75FrameBuffer::<5, 5, 8>::new();
+        buffer.set_pixel(2, 2, Hub75Color::new(225, 90, 9)).unwrap();
+
+        buffer.blur(0);
+        assert_eq!(buffer.get_pixel(2, 2).unwrap(), Hub75Color::new(225, 90, 9));
+
+        // Spread evenly over the 3x3 square around it
+        buffer.blur(1);
+        for y in 0..5 {
+            for x in 0..5 {
+                let expected = if (1..=3).contains(&x) && (1..=3).contains(&y) {
+                    Hub75Color::new(25, 10, 1)
+                } else {
+                    Hub75Color::black()
+                };
+                assert_eq!(buffer.get_pixel(x, y).unwrap(), expected, "({x}, {y})");
+            }
+        }
+
+        // Edges average only over pixels inside the frame
+        buffer.fill(Hub75Color::white());
+        buffer.blur(3);
+        assert_eq!(buffer.average_luma(), 255);
+    }
+
+    #[test]
+    fn test_from_rgb_data_dithered() {
+        // A flat color halfway between two 4-bit levels
+        let data = [136u8; 4 * 4 * 3];
+        let buffer = Hub75FrameBuffer::<4, 4, 4>::from_rgb_data_dithered(&data).unwrap();
+
+        let mut ups = 0;
+        for y in 0..4 {
+            for x in 0..4 {
+                let pixel = buffer.get_pixel(x, y).unwrap();
+                assert!(pixel.r == 8 || pixel.r == 9);
+                ups += (pixel.r == 9) as usize;
+            }
+        }
+        assert_eq!(ups, 8);
+
+        assert!(Hub75FrameBuffer::<4, 4, 4>::from_rgb_data_dithered(&data[1..]).is_err());
+    }
+
+    #[cfg(feature = "embedded-graphics")]
+    #[test]
+    fn test_dithered_target() {
+        use embedded_graphics_core::{pixelcolor::Rgb888, prelude::*};
+
+        let mut buffer = Hub75FrameBuffer::<8, 4, 4>::new();
+        buffer
+            .dithered_target()
+            .fill_solid(
+                &Rectangle::new(Point::zero(), Size::new(8, 4)),
+                Rgb888::new(136, 0, 255),
+            )
+            .unwrap();
+
+        assert_eq!(buffer.get_pixel(0, 0).unwrap(), Hub75Color::new(8, 0, 15));
+        assert_eq!(buffer.get_pixel(1, 0).unwrap(), Hub75Color::new(9, 0, 15));
+        // The pattern repeats every 4 pixels
+        assert_eq!(buffer.get_pixel(5, 0).unwrap(), Hub75Color::new(9, 0, 15));
+        assert_eq!(buffer.get_pixel(4, 0).unwrap(), Hub75Color::new(8, 0, 15));
+    }
+
+    #[cfg(feature = "embedded-graphics")]
+    #[test]
+    fn test_native_target() {
+        use embedded_graphics_core::{draw_target::DrawTarget, prelude::*};
+
+        let mut buffer = Hub75FrameBuffer::<16, 8, 4>::new();
+        let color = Hub75Color::<4>::new(3, 7, 11);
+
+        let mut target = buffer.native_target();
+        Pixel(Point::new(1, 1), color).draw(&mut target).unwrap();
+        target
+            .fill_solid(&Rectangle::new(Point::new(14, 6), Size::new(4, 4)), color)
+            .unwrap();
+
+        // Colors are stored exactly, without an Rgb565 round trip
+        assert_eq!(buffer.get_pixel(1, 1).unwrap(), color);
+        assert_eq!(buffer.get_pixel(15, 7).unwrap(), color);
+    }
 }
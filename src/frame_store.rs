@@ -0,0 +1,257 @@
+//! Paged frame loading from external storage
+//!
+//! Long animations quickly outgrow the RAM of small microcontrollers: a single
+//! 64x32 frame buffer is already several kilobytes. This module lets frames live
+//! in external storage (SPI flash, SD card, EEPROM, ...) and be paged into a
+//! frame buffer on demand, one frame at a time.
+//!
+//! Frames are stored as raw RGB888 data (`WIDTH * HEIGHT * 3` bytes per frame),
+//! the same layout used by [`AnimationData::RgbData`](crate::animation::AnimationData::RgbData)
+//! and [`Hub75FrameBuffer::from_rgb_data`].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::frame_store::{FrameStore, StorageFrameStore};
+//! use hub75::Hub75FrameBuffer;
+//! use embedded_storage_async::ReadStorage;
+//!
+//! # async fn example(flash: impl ReadStorage) -> Result<(), hub75::Hub75Error> {
+//! // 24 frames stored back to back starting at offset 0x1000
+//! let mut store = StorageFrameStore::<_, 64, 32, 6>::new(flash, 0x1000, 24)?;
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//!
+//! for index in 0..store.frame_count() {
+//!     store.load_frame(index, &mut frame).await?;
+//!     // Copy `frame` into the display's back buffer and swap
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer, AnimationError, Hub75Error};
+use embedded_storage_async::ReadStorage;
+
+/// Number of bytes read from storage per transfer (a multiple of 3 so that
+/// every chunk holds whole RGB888 pixels)
+const CHUNK_SIZE: usize = 96;
+
+/// Source of animation frames that are loaded on demand
+#[allow(async_fn_in_trait)]
+pub trait FrameStore<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Get the number of frames available in the store
+    fn frame_count(&self) -> usize;
+
+    /// Load the frame at `index` into the provided frame buffer
+    async fn load_frame(
+        &mut self,
+        index: usize,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error>;
+}
+
+/// Frame store backed by any [`ReadStorage`] implementation
+///
+/// Frames are read sequentially starting at `base_offset`, each occupying
+/// `WIDTH * HEIGHT * 3` bytes. Only a small fixed-size chunk buffer is kept on
+/// the stack while loading, so the RAM cost is a single frame buffer owned by
+/// the caller.
+pub struct StorageFrameStore<S, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Underlying storage device
+    storage: S,
+    /// Offset of the first frame in storage
+    base_offset: u32,
+    /// Number of frames stored
+    frame_count: usize,
+}
+
+impl<S, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    StorageFrameStore<S, WIDTH, HEIGHT, COLOR_BITS>
+where
+    S: ReadStorage,
+{
+    /// Size of a single stored frame in bytes
+    pub const FRAME_SIZE: usize = WIDTH * HEIGHT * 3;
+
+    /// Create a new frame store
+    ///
+    /// Returns `AnimationError::InvalidData` if no frames are stored or if the
+    /// frames would extend past the end of the storage device, and
+    /// [`Hub75Error::StorageError`] if their size overflows `usize`.
+    pub fn new(storage: S, base_offset: u32, frame_count: usize) -> Result<Self, Hub75Error> {
+        if frame_count == 0 {
+            return Err(AnimationError::InvalidData.into());
+        }
+
+        let end = frame_count
+            .checked_mul(Self::FRAME_SIZE)
+            .and_then(|size| size.checked_add(base_offset as usize))
+            .ok_or(Hub75Error::StorageError)?;
+        if end > storage.capacity() {
+            return Err(AnimationError::InvalidData.into());
+        }
+        Ok(Self {
+            storage,
+            base_offset,
+            frame_count,
+        })
+    }
+
+    /// Get the storage offset of the frame at `index`
+    pub fn frame_offset(&self, index: usize) -> u32 {
+        self.base_offset + (index * Self::FRAME_SIZE) as u32
+    }
+
+    /// Release the underlying storage device
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+}
+
+impl<S, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameStore<WIDTH, HEIGHT, COLOR_BITS> for StorageFrameStore<S, WIDTH, HEIGHT, COLOR_BITS>
+where
+    S: ReadStorage,
+{
+    fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    async fn load_frame(
+        &mut self,
+        index: usize,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        if index >= self.frame_count {
+            return Err(AnimationError::InvalidData.into());
+        }
+
+        let mut chunk = [0u8; CHUNK_SIZE];
+        let mut offset = self.frame_offset(index);
+        let mut pixel = 0;
+        let total_pixels = WIDTH * HEIGHT;
+
+        while pixel < total_pixels {
+            let pixels_in_chunk = (total_pixels - pixel).min(CHUNK_SIZE / 3);
+            let bytes = &mut chunk[..pixels_in_chunk * 3];
+
+            self.storage
+                .read(offset, bytes)
+                .await
+                .map_err(|_| Hub75Error::StorageError)?;
+
+            for rgb in bytes.chunks_exact(3) {
+                let color = Hub75Color::from_rgb8(rgb[0], rgb[1], rgb[2]);
+                // SAFETY: pixel < WIDTH * HEIGHT, so both coordinates are in bounds
+                unsafe { frame.set_pixel_unchecked(pixel % WIDTH, pixel / WIDTH, color) };
+                pixel += 1;
+            }
+
+            offset += bytes.len() as u32;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+
+    /// In-memory storage for tests
+    struct MemoryStorage<'a> {
+        data: &'a [u8],
+        reads: usize,
+    }
+
+    impl ReadStorage for MemoryStorage<'_> {
+        type Error = ();
+
+        async fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let start = offset as usize;
+            let end = start + bytes.len();
+            if end > self.data.len() {
+                return Err(());
+            }
+            bytes.copy_from_slice(&self.data[start..end]);
+            self.reads += 1;
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.data.len()
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn test_store_validation() {
+        let data = [0u8; 16 * 8 * 3 * 2];
+
+        let storage = MemoryStorage {
+            data: &data,
+            reads: 0,
+        };
+        assert!(StorageFrameStore::<_, 16, 8, 6>::new(storage, 0, 2).is_ok());
+
+        let storage = MemoryStorage {
+            data: &data,
+            reads: 0,
+        };
+        assert!(StorageFrameStore::<_, 16, 8, 6>::new(storage, 1, 2).is_err());
+
+        let storage = MemoryStorage {
+            data: &data,
+            reads: 0,
+        };
+        assert!(StorageFrameStore::<_, 16, 8, 6>::new(storage, 0, 0).is_err());
+
+        let storage = MemoryStorage {
+            data: &data,
+            reads: 0,
+        };
+        assert!(matches!(
+            StorageFrameStore::<_, 16, 8, 6>::new(storage, 0, usize::MAX),
+            Err(Hub75Error::StorageError)
+        ));
+    }
+
+    #[test]
+    fn test_load_frame() {
+        // Two 16x8 frames: the first all black, the second with a red pixel at (5, 3)
+        let mut data = [0u8; 16 * 8 * 3 * 2];
+        let idx = 16 * 8 * 3 + (3 * 16 + 5) * 3;
+        data[idx] = 255;
+
+        let storage = MemoryStorage {
+            data: &data,
+            reads: 0,
+        };
+        let mut store = StorageFrameStore::<_, 16, 8, 6>::new(storage, 0, 2).unwrap();
+        let mut frame = Hub75FrameBuffer::<16, 8, 6>::new();
+
+        block_on(store.load_frame(1, &mut frame)).unwrap();
+        assert_eq!(frame.get_pixel(5, 3).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(4, 3).unwrap(), Hub75Color::black());
+
+        block_on(store.load_frame(0, &mut frame)).unwrap();
+        assert_eq!(frame.get_pixel(5, 3).unwrap(), Hub75Color::black());
+
+        assert!(block_on(store.load_frame(2, &mut frame)).is_err());
+
+        // 128 pixels per frame at 32 pixels per chunk
+        assert_eq!(store.into_inner().reads, 8);
+    }
+}
@@ -0,0 +1,505 @@
+//! Built-in icons for status displays
+//!
+//! A small set of 8x8 pictograms (Wi-Fi, battery, thermometer, arrows and
+//! weather symbols) that can be drawn in any color at 8x8 or, with every
+//! pixel doubled, at 16x16. Only the lit pixels are drawn, so icons can be
+//! placed over a background, and they are clipped at the frame edges.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::icon::{Icon, IconSize};
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! frame.draw_icon(Icon::Battery(75), 56, 0, IconSize::Small, Hub75Color::green());
+//! frame.draw_icon(Icon::Rain, 0, 8, IconSize::Large, Hub75Color::blue());
+//! ```
+
+use embedded_graphics_core::{
+    geometry::{Point, Size},
+    primitives::{PointsIter, Rectangle},
+};
+
+use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer};
+
+/// Width and height of an icon bitmap in pixels
+pub const ICON_SIZE: usize = 8;
+
+/// Convert rows of `'#'` (lit) and `'.'` into a bitmap, leftmost pixel in the
+/// top bit
+const fn bitmap(art: [&str; ICON_SIZE]) -> [u8; ICON_SIZE] {
+    let mut bitmap = [0; ICON_SIZE];
+    let mut y = 0;
+    while y < ICON_SIZE {
+        let row = art[y].as_bytes();
+        let mut x = 0;
+        while x < ICON_SIZE {
+            if row[x] == b'#' {
+                bitmap[y] |= 0x80 >> x;
+            }
+            x += 1;
+        }
+        y += 1;
+    }
+    bitmap
+}
+
+#[rustfmt::skip]
+const WIFI: [u8; ICON_SIZE] = bitmap([
+    ".######.",
+    "#......#",
+    "..####..",
+    ".#....#.",
+    "...##...",
+    "..#..#..",
+    "........",
+    "...##...",
+]);
+
+#[rustfmt::skip]
+const BATTERY: [u8; ICON_SIZE] = bitmap([
+    "........",
+    "#######.",
+    "#.....#.",
+    "#.....##",
+    "#.....##",
+    "#.....#.",
+    "#######.",
+    "........",
+]);
+
+#[rustfmt::skip]
+const THERMOMETER: [u8; ICON_SIZE] = bitmap([
+    "...##...",
+    "..#..#..",
+    "..#..#..",
+    "..#..#..",
+    "..####..",
+    ".######.",
+    ".######.",
+    "..####..",
+]);
+
+#[rustfmt::skip]
+const DROP: [u8; ICON_SIZE] = bitmap([
+    "...##...",
+    "...##...",
+    "..####..",
+    ".######.",
+    ".######.",
+    ".######.",
+    "..####..",
+    "........",
+]);
+
+#[rustfmt::skip]
+const ARROW_UP: [u8; ICON_SIZE] = bitmap([
+    "...##...",
+    "..####..",
+    ".######.",
+    "########",
+    "...##...",
+    "...##...",
+    "...##...",
+    "...##...",
+]);
+
+#[rustfmt::skip]
+const ARROW_DOWN: [u8; ICON_SIZE] = bitmap([
+    "...##...",
+    "...##...",
+    "...##...",
+    "...##...",
+    "########",
+    ".######.",
+    "..####..",
+    "...##...",
+]);
+
+#[rustfmt::skip]
+const ARROW_LEFT: [u8; ICON_SIZE] = bitmap([
+    "...#....",
+    "..##....",
+    ".###....",
+    "########",
+    "########",
+    ".###....",
+    "..##....",
+    "...#....",
+]);
+
+#[rustfmt::skip]
+const ARROW_RIGHT: [u8; ICON_SIZE] = bitmap([
+    "....#...",
+    "....##..",
+    "....###.",
+    "########",
+    "########",
+    "....###.",
+    "....##..",
+    "....#...",
+]);
+
+#[rustfmt::skip]
+const SUN: [u8; ICON_SIZE] = bitmap([
+    "#..##..#",
+    ".#....#.",
+    "..####..",
+    "#.####.#",
+    "#.####.#",
+    "..####..",
+    ".#....#.",
+    "#..##..#",
+]);
+
+#[rustfmt::skip]
+const MOON: [u8; ICON_SIZE] = bitmap([
+    "..####..",
+    ".###....",
+    "###.....",
+    "###.....",
+    "###.....",
+    "###.....",
+    ".###....",
+    "..####..",
+]);
+
+#[rustfmt::skip]
+const CLOUD: [u8; ICON_SIZE] = bitmap([
+    "........",
+    "........",
+    "...##...",
+    "..####..",
+    ".######.",
+    "########",
+    ".######.",
+    "........",
+]);
+
+#[rustfmt::skip]
+const PARTLY_CLOUDY: [u8; ICON_SIZE] = bitmap([
+    "...#...#",
+    "....###.",
+    "...#####",
+    "..##.##.",
+    ".####.#.",
+    "######..",
+    ".####...",
+    "........",
+]);
+
+#[rustfmt::skip]
+const RAIN: [u8; ICON_SIZE] = bitmap([
+    "...##...",
+    "..####..",
+    ".######.",
+    "########",
+    ".######.",
+    "........",
+    ".#..#..#",
+    "#..#..#.",
+]);
+
+#[rustfmt::skip]
+const SNOW: [u8; ICON_SIZE] = bitmap([
+    "...##...",
+    "..####..",
+    ".######.",
+    "########",
+    ".######.",
+    "........",
+    "..#...#.",
+    "#...#...",
+]);
+
+#[rustfmt::skip]
+const THUNDERSTORM: [u8; ICON_SIZE] = bitmap([
+    "...##...",
+    "..####..",
+    ".######.",
+    "########",
+    ".##..##.",
+    "...##...",
+    "..##....",
+    "...#....",
+]);
+
+#[rustfmt::skip]
+const FOG: [u8; ICON_SIZE] = bitmap([
+    "........",
+    "######..",
+    "........",
+    "..######",
+    "........",
+    "######..",
+    "........",
+    "..######",
+]);
+
+#[rustfmt::skip]
+const WIND: [u8; ICON_SIZE] = bitmap([
+    "....##..",
+    "......#.",
+    "######..",
+    "........",
+    "#######.",
+    "........",
+    "#####...",
+    ".....#..",
+]);
+
+#[rustfmt::skip]
+const HEART: [u8; ICON_SIZE] = bitmap([
+    ".##..##.",
+    "########",
+    "########",
+    "########",
+    ".######.",
+    "..####..",
+    "...##...",
+    "........",
+]);
+
+#[rustfmt::skip]
+const CHECK: [u8; ICON_SIZE] = bitmap([
+    "........",
+    ".......#",
+    "......#.",
+    ".....#..",
+    "#...#...",
+    ".#.#....",
+    "..#.....",
+    "........",
+]);
+
+#[rustfmt::skip]
+const CROSS: [u8; ICON_SIZE] = bitmap([
+    "#......#",
+    ".#....#.",
+    "..#..#..",
+    "...##...",
+    "...##...",
+    "..#..#..",
+    ".#....#.",
+    "#......#",
+]);
+
+#[rustfmt::skip]
+const WARNING: [u8; ICON_SIZE] = bitmap([
+    "...##...",
+    "..####..",
+    "..#..#..",
+    ".##..##.",
+    ".######.",
+    "###..###",
+    "########",
+    "........",
+]);
+
+/// Built-in pictogram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Icon {
+    /// Wi-Fi signal with 0 to 3 arcs above the dot
+    Wifi(u8),
+    /// Battery filled to a percentage
+    Battery(u8),
+    /// Thermometer
+    Thermometer,
+    /// Water drop, for humidity
+    Drop,
+    /// Arrow pointing up
+    ArrowUp,
+    /// Arrow pointing down
+    ArrowDown,
+    /// Arrow pointing left
+    ArrowLeft,
+    /// Arrow pointing right
+    ArrowRight,
+    /// Clear day
+    Sun,
+    /// Clear night
+    Moon,
+    /// Overcast
+    Cloud,
+    /// Sun behind a cloud
+    PartlyCloudy,
+    /// Cloud with rain
+    Rain,
+    /// Cloud with snow
+    Snow,
+    /// Cloud with lightning
+    Thunderstorm,
+    /// Fog or mist
+    Fog,
+    /// Wind
+    Wind,
+    /// Heart
+    Heart,
+    /// Check mark
+    Check,
+    /// Cross
+    Cross,
+    /// Warning triangle
+    Warning,
+}
+
+impl Icon {
+    /// Get the 8x8 bitmap, one byte per row from the top with the leftmost
+    /// pixel in the top bit
+    pub fn bitmap(&self) -> [u8; ICON_SIZE] {
+        match *self {
+            Icon::Wifi(arcs) => {
+                // Each arc takes two rows, from the outermost down to the dot
+                let hidden = 2 * 3usize.saturating_sub(arcs as usize);
+                let mut bitmap = WIFI;
+                bitmap[..hidden].fill(0);
+                bitmap
+            }
+            Icon::Battery(percent) => {
+                // Fill the five inner columns from the left, rounding
+                let columns = (percent.min(100) as usize * 5 + 50) / 100;
+                let fill = (1..=columns).fold(0, |fill, x| fill | 0x80 >> x);
+                let mut bitmap = BATTERY;
+                bitmap[2..6].iter_mut().for_each(|row| *row |= fill);
+                bitmap
+            }
+            Icon::Thermometer => THERMOMETER,
+            Icon::Drop => DROP,
+            Icon::ArrowUp => ARROW_UP,
+            Icon::ArrowDown => ARROW_DOWN,
+            Icon::ArrowLeft => ARROW_LEFT,
+            Icon::ArrowRight => ARROW_RIGHT,
+            Icon::Sun => SUN,
+            Icon::Moon => MOON,
+            Icon::Cloud => CLOUD,
+            Icon::PartlyCloudy => PARTLY_CLOUDY,
+            Icon::Rain => RAIN,
+            Icon::Snow => SNOW,
+            Icon::Thunderstorm => THUNDERSTORM,
+            Icon::Fog => FOG,
+            Icon::Wind => WIND,
+            Icon::Heart => HEART,
+            Icon::Check => CHECK,
+            Icon::Cross => CROSS,
+            Icon::Warning => WARNING,
+        }
+    }
+}
+
+/// Size an icon is drawn at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IconSize {
+    /// 8x8 pixels
+    #[default]
+    Small,
+    /// 16x16 pixels, every pixel doubled
+    Large,
+}
+
+impl IconSize {
+    /// Get the width and height in pixels
+    pub const fn pixels(self) -> usize {
+        match self {
+            IconSize::Small => ICON_SIZE,
+            IconSize::Large => 2 * ICON_SIZE,
+        }
+    }
+
+    /// Number of panel pixels per bitmap pixel in each direction
+    const fn scale(self) -> i32 {
+        (self.pixels() / ICON_SIZE) as i32
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Draw an icon with its top-left corner at `(x, y)`
+    ///
+    /// Only the lit pixels are drawn; the parts outside the frame are clipped.
+    pub fn draw_icon(
+        &mut self,
+        icon: Icon,
+        x: i32,
+        y: i32,
+        size: IconSize,
+        color: Hub75Color<COLOR_BITS>,
+    ) {
+        let frame = Rectangle::new(Point::zero(), Size::new(WIDTH as u32, HEIGHT as u32));
+        self.draw_icon_clipped(icon, Point::new(x, y), size, color, frame);
+    }
+
+    /// Draw an icon with its top-left corner at `origin`, leaving everything
+    /// outside `clip` untouched
+    ///
+    /// `clip` must lie within the frame.
+    pub(crate) fn draw_icon_clipped(
+        &mut self,
+        icon: Icon,
+        origin: Point,
+        size: IconSize,
+        color: Hub75Color<COLOR_BITS>,
+        clip: Rectangle,
+    ) {
+        let scale = size.scale();
+        for (y, row) in (0..).zip(icon.bitmap()) {
+            for x in (0..ICON_SIZE as i32).filter(|x| row << x & 0x80 != 0) {
+                let pixel = Rectangle::new(
+                    origin + Point::new(x, y) * scale,
+                    Size::new(scale as u32, scale as u32),
+                );
+                for point in pixel.intersection(&clip).points() {
+                    // SAFETY: the clip rectangle lies within the frame
+                    unsafe { self.set_pixel_unchecked(point.x as usize, point.y as usize, color) };
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_bitmaps() {
+        assert_eq!(Icon::Cross.bitmap()[0], 0b1000_0001);
+        assert_eq!(Icon::ArrowLeft.bitmap()[3], 0xFF);
+
+        // Battery fill grows from the left of the inside
+        assert_eq!(Icon::Battery(0).bitmap(), BATTERY);
+        assert_eq!(Icon::Battery(50).bitmap()[3], 0b1111_0011);
+        assert_eq!(Icon::Battery(100).bitmap()[2], 0b1111_1110);
+        assert_eq!(Icon::Battery(255).bitmap(), Icon::Battery(100).bitmap());
+
+        // Wi-Fi arcs disappear from the outside in
+        assert_eq!(Icon::Wifi(3).bitmap(), WIFI);
+        assert_eq!(Icon::Wifi(9).bitmap(), WIFI);
+        assert_eq!(Icon::Wifi(1).bitmap()[..4], [0; 4]);
+        assert_eq!(Icon::Wifi(0).bitmap()[..6], [0; 6]);
+        assert_eq!(Icon::Wifi(0).bitmap()[7], WIFI[7]);
+    }
+
+    #[test]
+    fn test_draw_icon() {
+        let mut frame = Hub75FrameBuffer::<16, 16, 6>::new();
+        let red = Hub75Color::red();
+
+        // The top-left pixel of the cross becomes a 2x2 block
+        frame.draw_icon(Icon::Cross, -2, 0, IconSize::Large, red);
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(0, 2).unwrap(), red);
+        assert_eq!(frame.get_pixel(1, 3).unwrap(), red);
+        assert_eq!(frame.get_pixel(12, 0).unwrap(), red);
+        assert_eq!(frame.get_pixel(12, 15).unwrap(), red);
+
+        frame.clear();
+        frame.draw_icon(Icon::Cross, 12, 12, IconSize::Small, red);
+        assert_eq!(frame.get_pixel(12, 12).unwrap(), red);
+        assert_eq!(frame.get_pixel(13, 12).unwrap(), Hub75Color::black());
+        assert_eq!(IconSize::Large.pixels(), 16);
+    }
+}
@@ -0,0 +1,288 @@
+//! Windows bitmap (BMP) decoding
+//!
+//! Handles the uncompressed variants image editors write: 1, 4 and 8 bits
+//! per pixel with a palette, and 16, 24 and 32 bits per pixel, including
+//! custom channel masks (`BI_BITFIELDS`). Both bottom-up and top-down pixel
+//! orders are supported. Run-length compressed files are rejected.
+
+use crate::{frame_buffer::Hub75FrameBuffer, AnimationError, Hub75Error};
+
+use super::{over_black, ImageFit, Placement};
+
+/// Uncompressed pixel data
+const BI_RGB: u32 = 0;
+/// Uncompressed pixel data with channel masks
+const BI_BITFIELDS: u32 = 3;
+/// Size of the file header before the DIB header
+const FILE_HEADER_LEN: usize = 14;
+
+/// Dimensions of a bitmap
+///
+/// Returns [`AnimationError::InvalidData`] if `data` is not a supported
+/// bitmap.
+pub fn dimensions(data: &[u8]) -> Result<(u32, u32), Hub75Error> {
+    let header = Header::parse(data)?;
+    Ok((header.width, header.height))
+}
+
+/// Fields of the file and DIB headers needed for decoding
+struct Header {
+    /// Offset of the pixel data
+    pixels: usize,
+    /// Width in pixels
+    width: u32,
+    /// Height in pixels
+    height: u32,
+    /// Whether the first row in the file is the top row
+    top_down: bool,
+    /// Bits per pixel
+    bits: u16,
+    /// Offset of the palette
+    palette: usize,
+    /// Number of palette entries
+    palette_len: usize,
+    /// Red, green, blue and alpha masks for 16 and 32 bit pixels
+    masks: [u32; 4],
+}
+
+impl Header {
+    /// Read and check the headers
+    fn parse(data: &[u8]) -> Result<Self, Hub75Error> {
+        let invalid = || Hub75Error::from(AnimationError::InvalidData);
+        if data.get(..2) != Some(b"BM") {
+            return Err(invalid());
+        }
+        let u16_at = |at: usize| {
+            data.get(at..at + 2)
+                .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+                .ok_or_else(invalid)
+        };
+        let u32_at = |at: usize| {
+            data.get(at..at + 4)
+                .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .ok_or_else(invalid)
+        };
+
+        let pixels = u32_at(10)? as usize;
+        let dib_len = u32_at(14)? as usize;
+        if dib_len < 40 {
+            // OS/2 bitmap headers are not supported
+            return Err(invalid());
+        }
+        let width = u32_at(18)? as i32;
+        let height = u32_at(22)? as i32;
+        let bits = u16_at(28)?;
+        let compression = u32_at(30)?;
+        if width <= 0 || height == 0 || height == i32::MIN {
+            return Err(invalid());
+        }
+
+        let masks = match (compression, bits) {
+            (BI_RGB, 16) => [0x7C00, 0x03E0, 0x001F, 0],
+            (BI_RGB, 32) => [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0],
+            (BI_RGB, 1 | 4 | 8 | 24) => [0; 4],
+            (BI_BITFIELDS, 16 | 32) => {
+                // Masks follow a basic header, or are part of a larger one
+                let at = FILE_HEADER_LEN + 40;
+                let alpha = if dib_len >= 56 { u32_at(at + 12)? } else { 0 };
+                [u32_at(at)?, u32_at(at + 4)?, u32_at(at + 8)?, alpha]
+            }
+            _ => return Err(invalid()),
+        };
+
+        let palette_len = match bits {
+            1 | 4 | 8 => match u32_at(46)? {
+                0 => 1 << bits,
+                used => (used as usize).min(1 << bits),
+            },
+            _ => 0,
+        };
+        let palette = FILE_HEADER_LEN + dib_len;
+        if data.len() < palette + palette_len * 4 {
+            return Err(invalid());
+        }
+
+        let header = Self {
+            pixels,
+            width: width as u32,
+            height: height.unsigned_abs(),
+            top_down: height < 0,
+            bits,
+            palette,
+            palette_len,
+            masks,
+        };
+        let end = header.stride() as u64 * header.height as u64 + pixels as u64;
+        if end > data.len() as u64 {
+            return Err(invalid());
+        }
+        Ok(header)
+    }
+
+    /// Bytes per row, padded to whole 32-bit words
+    fn stride(&self) -> usize {
+        (self.width as usize * self.bits as usize).div_ceil(32) * 4
+    }
+
+    /// Color of a palette entry, or black if out of range
+    fn palette_color(&self, data: &[u8], index: usize) -> (u8, u8, u8) {
+        if index >= self.palette_len {
+            return (0, 0, 0);
+        }
+        let entry = &data[self.palette + index * 4..];
+        (entry[2], entry[1], entry[0])
+    }
+
+    /// Color of a pixel stored in a masked 16 or 32 bit value
+    fn masked_color(&self, value: u32) -> (u8, u8, u8) {
+        let channel = |mask: u32| {
+            if mask == 0 {
+                return 255;
+            }
+            let max = mask >> mask.trailing_zeros();
+            ((value & mask) >> mask.trailing_zeros()) as u64 * 255 / max as u64
+        };
+        let [r, g, b, a] = self.masks.map(|mask| channel(mask) as u8);
+        over_black(r, g, b, a)
+    }
+
+    /// Color of pixel `x` of a row
+    fn pixel(&self, data: &[u8], row: &[u8], x: usize) -> (u8, u8, u8) {
+        match self.bits {
+            1 | 4 | 8 => {
+                let bits = self.bits as usize;
+                let bit = x * bits;
+                let shift = 8 - bits - bit % 8;
+                let index = (row[bit / 8] >> shift) as usize & ((1 << bits) - 1);
+                self.palette_color(data, index)
+            }
+            16 => self.masked_color(u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]) as u32),
+            24 => (row[x * 3 + 2], row[x * 3 + 1], row[x * 3]),
+            _ => {
+                let bytes = [row[x * 4], row[x * 4 + 1], row[x * 4 + 2], row[x * 4 + 3]];
+                self.masked_color(u32::from_le_bytes(bytes))
+            }
+        }
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a frame buffer showing a bitmap scaled to fit the panel
+    ///
+    /// Returns [`AnimationError::InvalidData`] if `data` is not a supported
+    /// bitmap.
+    pub fn from_bmp(data: &[u8]) -> Result<Self, Hub75Error> {
+        let mut frame = Self::new();
+        frame.draw_bmp(data, ImageFit::Fit)?;
+        Ok(frame)
+    }
+
+    /// Draw a bitmap over the frame, sized and positioned by `fit`
+    ///
+    /// Pixels outside the image are left unchanged. Returns
+    /// [`AnimationError::InvalidData`] if `data` is not a supported bitmap,
+    /// in which case the frame is unchanged.
+    pub fn draw_bmp(&mut self, data: &[u8], fit: ImageFit) -> Result<(), Hub75Error> {
+        let header = Header::parse(data)?;
+        let placement = Placement::new::<WIDTH, HEIGHT>(header.width, header.height, fit);
+        let stride = header.stride();
+        for y in 0..header.height {
+            let stored = if header.top_down {
+                y
+            } else {
+                header.height - 1 - y
+            };
+            let start = header.pixels + stored as usize * stride;
+            let row = &data[start..start + stride];
+            for x in 0..header.width {
+                let color = header.pixel(data, row, x as usize);
+                placement.put(self, x, y, color);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Hub75Color;
+
+    /// Build a bitmap with a 40 byte header and optional palette
+    fn bitmap<const N: usize>(
+        width: i32,
+        height: i32,
+        bits: u16,
+        palette: &[[u8; 4]],
+        pixels: &[u8],
+    ) -> ([u8; N], usize) {
+        let mut data = [0u8; N];
+        let offset = 54 + palette.len() * 4;
+        data[..2].copy_from_slice(b"BM");
+        data[10..14].copy_from_slice(&(offset as u32).to_le_bytes());
+        data[14..18].copy_from_slice(&40u32.to_le_bytes());
+        data[18..22].copy_from_slice(&width.to_le_bytes());
+        data[22..26].copy_from_slice(&height.to_le_bytes());
+        data[26..28].copy_from_slice(&1u16.to_le_bytes());
+        data[28..30].copy_from_slice(&bits.to_le_bytes());
+        data[46..50].copy_from_slice(&(palette.len() as u32).to_le_bytes());
+        for (i, entry) in palette.iter().enumerate() {
+            data[54 + i * 4..58 + i * 4].copy_from_slice(entry);
+        }
+        data[offset..offset + pixels.len()].copy_from_slice(pixels);
+        (data, offset + pixels.len())
+    }
+
+    #[test]
+    fn test_24_bit_bottom_up() {
+        // Rows are padded to 8 bytes; the bottom row comes first
+        let pixels = [
+            0, 0, 255, 0, 255, 0, 0, 0, // bottom: red, green
+            255, 0, 0, 255, 255, 255, 0, 0, // top: blue, white
+        ];
+        let (data, len) = bitmap::<80>(2, 2, 24, &[], &pixels);
+        assert_eq!(dimensions(&data[..len]).unwrap(), (2, 2));
+
+        let frame = Hub75FrameBuffer::<2, 2, 6>::from_bmp(&data[..len]).unwrap();
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::blue());
+        assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::white());
+        assert_eq!(frame.get_pixel(0, 1).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(1, 1).unwrap(), Hub75Color::green());
+
+        // Truncated pixel data is rejected
+        assert!(Hub75FrameBuffer::<2, 2, 6>::from_bmp(&data[..len - 1]).is_err());
+    }
+
+    #[test]
+    fn test_paletted_top_down() {
+        // 4 bits per pixel: indices 1, 0, 1 in a row
+        let palette = [[0, 0, 0, 0], [0, 255, 255, 0]];
+        let (data, len) = bitmap::<80>(3, -1, 4, &palette, &[0x10, 0x10, 0, 0]);
+
+        let mut frame = Hub75FrameBuffer::<6, 2, 6>::new();
+        frame.draw_bmp(&data[..len], ImageFit::Stretch).unwrap();
+        assert_eq!(frame.get_pixel(0, 1).unwrap(), Hub75Color::yellow());
+        assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::yellow());
+        assert_eq!(frame.get_pixel(2, 0).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(5, 1).unwrap(), Hub75Color::yellow());
+    }
+
+    #[test]
+    fn test_16_bit_and_unsupported() {
+        // 5-5-5: full red, then full blue
+        let (data, len) = bitmap::<64>(2, 1, 16, &[], &[0x00, 0x7C, 0x1F, 0x00]);
+        let frame = Hub75FrameBuffer::<2, 1, 6>::from_bmp(&data[..len]).unwrap();
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::blue());
+
+        let (mut data, len) = bitmap::<64>(2, 1, 16, &[], &[0; 4]);
+        data[30] = 1; // run-length compressed
+        assert_eq!(
+            dimensions(&data[..len]),
+            Err(AnimationError::InvalidData.into())
+        );
+        assert!(dimensions(b"PNG").is_err());
+    }
+}
@@ -0,0 +1,790 @@
+//! Animated GIF playback
+//!
+//! A [`GifAnimation`] plays a GIF file straight from flash. Frames are
+//! decoded one at a time as they become due, composited onto a canvas the
+//! size of the panel according to each frame's disposal method, and held for
+//! the frame's delay. The loop count from the file is honored unless
+//! overridden.
+//!
+//! The player keeps its own canvas, since GIF frames only redraw what
+//! changed, plus a second frame buffer for frames that restore the previous
+//! image and about 16 KiB of decoder tables. Keep it in a `static` rather
+//! than on the stack.
+
+use crate::{
+    animation::AnimationState, color::Hub75Color, frame_buffer::Hub75FrameBuffer,
+    scene::FrameSource, AnimationError, Hub75Error,
+};
+
+use super::{ImageFit, Placement};
+
+/// Largest code in the LZW dictionary
+const MAX_CODES: usize = 4096;
+/// Delay used for frames that ask for none, as browsers do
+const DEFAULT_DELAY_MS: u32 = 100;
+
+/// Block introducers and labels
+const EXTENSION: u8 = 0x21;
+const IMAGE: u8 = 0x2C;
+const TRAILER: u8 = 0x3B;
+const GRAPHIC_CONTROL: u8 = 0xF9;
+const APPLICATION: u8 = 0xFF;
+
+/// What happens to a frame's area before the next frame is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+enum Disposal {
+    /// Leave the frame in place
+    #[default]
+    Keep,
+    /// Clear the frame's area to black
+    Background,
+    /// Put back what was there before the frame
+    Previous,
+}
+
+/// A frame area in logical screen pixels
+#[derive(Debug, Clone, Copy, Default)]
+struct Area {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// Settings from a graphic control extension, applying to the next image
+#[derive(Debug, Clone, Copy, Default)]
+struct Control {
+    /// Disposal of the image once shown
+    disposal: Disposal,
+    /// How long the image is shown, in milliseconds
+    delay_ms: u32,
+    /// Palette index drawn as transparent
+    transparent: Option<u8>,
+}
+
+/// Reads the bytes of a chain of data sub-blocks
+struct SubBlocks<'a> {
+    /// File contents
+    data: &'a [u8],
+    /// Offset of the next byte
+    position: usize,
+    /// Bytes left in the current sub-block
+    remaining: usize,
+}
+
+impl SubBlocks<'_> {
+    /// Next data byte, or `None` at the terminating empty sub-block
+    fn next_byte(&mut self) -> Result<Option<u8>, Hub75Error> {
+        if self.remaining == 0 {
+            let length = *self
+                .data
+                .get(self.position)
+                .ok_or(AnimationError::InvalidData)?;
+            self.position += 1;
+            if length == 0 {
+                return Ok(None);
+            }
+            self.remaining = length as usize;
+        }
+        let byte = *self
+            .data
+            .get(self.position)
+            .ok_or(AnimationError::InvalidData)?;
+        self.position += 1;
+        self.remaining -= 1;
+        Ok(Some(byte))
+    }
+
+    /// Skip to just past the terminating sub-block
+    fn finish(&mut self) -> Result<usize, Hub75Error> {
+        while self.next_byte()?.is_some() {}
+        Ok(self.position)
+    }
+}
+
+/// LZW dictionary and output stack
+struct Lzw {
+    /// Code each entry extends
+    prefix: [u16; MAX_CODES],
+    /// Byte each entry appends
+    suffix: [u8; MAX_CODES],
+    /// Bytes of the string being output, last first
+    stack: [u8; MAX_CODES],
+}
+
+impl Lzw {
+    /// Decode image data, passing each palette index to `output`
+    fn decode(
+        &mut self,
+        blocks: &mut SubBlocks<'_>,
+        min_code_size: u8,
+        mut output: impl FnMut(u8),
+    ) -> Result<(), Hub75Error> {
+        if !(2..=8).contains(&min_code_size) {
+            return Err(AnimationError::InvalidData.into());
+        }
+        let clear = 1u16 << min_code_size;
+        let end = clear + 1;
+        let mut code_size = min_code_size + 1;
+        let mut next_code = clear + 2;
+        let mut previous: Option<u16> = None;
+        let (mut bits, mut bit_count) = (0u32, 0u8);
+
+        loop {
+            while bit_count < code_size {
+                match blocks.next_byte()? {
+                    Some(byte) => bits |= (byte as u32) << bit_count,
+                    // Data ended without an end code
+                    None => return Ok(()),
+                }
+                bit_count += 8;
+            }
+            let code = (bits & ((1 << code_size) - 1)) as u16;
+            bits >>= code_size;
+            bit_count -= code_size;
+
+            if code == clear {
+                code_size = min_code_size + 1;
+                next_code = clear + 2;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                blocks.finish()?;
+                return Ok(());
+            }
+
+            let Some(prev) = previous else {
+                if code >= clear {
+                    return Err(AnimationError::InvalidData.into());
+                }
+                output(code as u8);
+                previous = Some(code);
+                continue;
+            };
+
+            // A code not yet in the dictionary is the previous string plus
+            // its own first byte
+            let known = code < next_code;
+            if !known && code != next_code {
+                return Err(AnimationError::InvalidData.into());
+            }
+            let first = self.emit(if known { code } else { prev }, clear, &mut output);
+            if !known {
+                output(first);
+            }
+
+            if (next_code as usize) < MAX_CODES {
+                self.prefix[next_code as usize] = prev;
+                self.suffix[next_code as usize] = first;
+                next_code += 1;
+                if next_code == 1 << code_size && code_size < 12 {
+                    code_size += 1;
+                }
+            }
+            previous = Some(code);
+        }
+    }
+
+    /// Output the string for a code, returning its first byte
+    fn emit(&mut self, mut code: u16, clear: u16, output: &mut impl FnMut(u8)) -> u8 {
+        let mut length = 0;
+        while code > clear && length < MAX_CODES - 1 {
+            self.stack[length] = self.suffix[code as usize];
+            code = self.prefix[code as usize];
+            length += 1;
+        }
+        let first = code as u8;
+        output(first);
+        for &byte in self.stack[..length].iter().rev() {
+            output(byte);
+        }
+        first
+    }
+}
+
+/// Streaming GIF player drawing onto a panel-sized canvas
+pub struct GifAnimation<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// File contents
+    data: &'a [u8],
+    /// Offset of the global color table, if any
+    global_palette: Option<(usize, usize)>,
+    /// Offset of the first block after the header
+    first_block: usize,
+    /// Offset of the next block to read
+    position: usize,
+    /// Mapping of the logical screen onto the panel
+    placement: Placement,
+    /// The image as composited so far
+    canvas: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    /// Canvas before the current frame, for restoring it
+    saved: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    /// Decoder tables
+    lzw: Lzw,
+    /// Disposal still to be done for the frame on the canvas
+    pending: Option<(Disposal, Area)>,
+    /// Loops requested by the file or the user; `None` loops forever
+    loops: Option<u16>,
+    /// Whether the user's loop count overrides the file's
+    loops_overridden: bool,
+    /// Loops completed so far
+    plays: u16,
+    /// Index of the frame on the canvas
+    frame_index: usize,
+    /// Whether playback has finished
+    done: bool,
+    /// Whether a frame has been decoded yet
+    started: bool,
+    /// Delay of the frame on the canvas, in milliseconds
+    delay_ms: u32,
+    /// Time the frame on the canvas was first shown
+    shown_at_ms: u64,
+    /// Caller's frame period for frame-based playback, in milliseconds
+    frame_interval_ms: u32,
+    /// Time shown so far in frame-based playback, in milliseconds
+    elapsed_ms: u32,
+}
+
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    GifAnimation<'a, WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Open a GIF file, scaling its logical screen by `fit`
+    ///
+    /// Returns [`AnimationError::InvalidData`] if `data` is not a GIF file.
+    pub fn new(data: &'a [u8], fit: ImageFit) -> Result<Self, Hub75Error> {
+        let invalid = || Hub75Error::from(AnimationError::InvalidData);
+        let header = data.get(..13).ok_or_else(invalid)?;
+        if &header[..6] != b"GIF87a" && &header[..6] != b"GIF89a" {
+            return Err(invalid());
+        }
+        let width = u16::from_le_bytes([header[6], header[7]]) as u32;
+        let height = u16::from_le_bytes([header[8], header[9]]) as u32;
+        let flags = header[10];
+
+        let mut first_block = 13;
+        let global_palette = if flags & 0x80 != 0 {
+            let entries = 2 << (flags & 0x07);
+            first_block += entries * 3;
+            Some((13, entries))
+        } else {
+            None
+        };
+        if data.len() < first_block {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            data,
+            global_palette,
+            first_block,
+            position: first_block,
+            placement: Placement::new::<WIDTH, HEIGHT>(width, height, fit),
+            canvas: Hub75FrameBuffer::new(),
+            saved: Hub75FrameBuffer::new(),
+            lzw: Lzw {
+                prefix: [0; MAX_CODES],
+                suffix: [0; MAX_CODES],
+                stack: [0; MAX_CODES],
+            },
+            pending: None,
+            loops: Some(1),
+            loops_overridden: false,
+            plays: 0,
+            frame_index: 0,
+            done: false,
+            started: false,
+            delay_ms: 0,
+            shown_at_ms: 0,
+            frame_interval_ms: 16,
+            elapsed_ms: 0,
+        })
+    }
+
+    /// Override the file's loop count; `None` loops forever
+    ///
+    /// Files without a loop count play once.
+    pub fn set_loops(&mut self, loops: Option<u16>) {
+        self.loops = loops;
+        self.loops_overridden = true;
+    }
+
+    /// Set the caller's frame period for [`Self::next_into`], in milliseconds
+    pub fn set_frame_interval_ms(&mut self, interval_ms: u32) {
+        self.frame_interval_ms = interval_ms.max(1);
+    }
+
+    /// Index of the frame last decoded, counting from 0 in each loop
+    pub fn frame_index(&self) -> usize {
+        self.frame_index
+    }
+
+    /// Check if playback has finished
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Start again from the first frame
+    pub fn reset(&mut self) {
+        self.rewind();
+        self.plays = 0;
+        self.done = false;
+        self.started = false;
+    }
+
+    /// Go back to the first frame on a blank canvas
+    fn rewind(&mut self) {
+        self.position = self.first_block;
+        self.canvas.clear();
+        self.pending = None;
+        self.frame_index = 0;
+        self.elapsed_ms = 0;
+    }
+
+    /// Decode the next frame onto the canvas
+    ///
+    /// Returns `false` once the last loop has finished.
+    fn decode_next(&mut self) -> Result<bool, Hub75Error> {
+        if let Some((disposal, area)) = self.pending.take() {
+            match disposal {
+                Disposal::Keep => {}
+                Disposal::Background => self.placement.fill_area(
+                    &mut self.canvas,
+                    (area.x, area.y, area.width, area.height),
+                    Hub75Color::black(),
+                ),
+                Disposal::Previous => self.canvas.copy_from(&self.saved),
+            }
+            self.frame_index += 1;
+        }
+
+        let mut control = Control::default();
+        let mut rewound = false;
+        loop {
+            let data = self.data;
+            let introducer = *data.get(self.position).ok_or(AnimationError::InvalidData)?;
+            match introducer {
+                EXTENSION => {
+                    let label = *data
+                        .get(self.position + 1)
+                        .ok_or(AnimationError::InvalidData)?;
+                    self.position += 2;
+                    match label {
+                        GRAPHIC_CONTROL => control = self.read_control()?,
+                        APPLICATION => self.read_application()?,
+                        _ => self.skip_blocks()?,
+                    }
+                }
+                IMAGE => {
+                    self.read_image(control)?;
+                    return Ok(true);
+                }
+                TRAILER => {
+                    self.plays = self.plays.saturating_add(1);
+                    let finished = self.loops.is_some_and(|loops| self.plays >= loops);
+                    // A file without frames would otherwise loop forever
+                    if finished || rewound {
+                        return Ok(false);
+                    }
+                    self.rewind();
+                    rewound = true;
+                }
+                _ => return Err(AnimationError::InvalidData.into()),
+            }
+        }
+    }
+
+    /// Skip the sub-blocks of an extension
+    fn skip_blocks(&mut self) -> Result<(), Hub75Error> {
+        self.position = self.blocks().finish()?;
+        Ok(())
+    }
+
+    /// Sub-block reader starting at the current position
+    fn blocks(&self) -> SubBlocks<'a> {
+        SubBlocks {
+            data: self.data,
+            position: self.position,
+            remaining: 0,
+        }
+    }
+
+    /// Read a graphic control extension
+    fn read_control(&mut self) -> Result<Control, Hub75Error> {
+        let block = self
+            .data
+            .get(self.position..self.position + 5)
+            .ok_or(AnimationError::InvalidData)?;
+        let flags = block[1];
+        let delay = u16::from_le_bytes([block[2], block[3]]) as u32 * 10;
+        let control = Control {
+            disposal: match (flags >> 2) & 0x07 {
+                2 => Disposal::Background,
+                3 => Disposal::Previous,
+                _ => Disposal::Keep,
+            },
+            delay_ms: if delay < 20 { DEFAULT_DELAY_MS } else { delay },
+            transparent: (flags & 0x01 != 0).then_some(block[4]),
+        };
+        self.skip_blocks()?;
+        Ok(control)
+    }
+
+    /// Read an application extension, picking up the loop count
+    fn read_application(&mut self) -> Result<(), Hub75Error> {
+        let identifier = self.data.get(self.position..self.position + 12);
+        if identifier == Some(b"\x0bNETSCAPE2.0") {
+            self.position += 12;
+            let sub_block = self.data.get(self.position..self.position + 4);
+            if let (Some(&[3, 1, low, high]), false) = (sub_block, self.loops_overridden) {
+                // A count of 0 means forever; otherwise it counts repeats
+                self.loops = match u16::from_le_bytes([low, high]) {
+                    0 => None,
+                    repeats => Some(repeats.saturating_add(1)),
+                };
+            }
+        }
+        self.skip_blocks()
+    }
+
+    /// Read an image descriptor and draw the image onto the canvas
+    fn read_image(&mut self, control: Control) -> Result<(), Hub75Error> {
+        let descriptor = self
+            .data
+            .get(self.position + 1..self.position + 10)
+            .ok_or(AnimationError::InvalidData)?;
+        let value = |at: usize| u16::from_le_bytes([descriptor[at], descriptor[at + 1]]) as u32;
+        let area = Area {
+            x: value(0),
+            y: value(2),
+            width: value(4),
+            height: value(6),
+        };
+        let flags = descriptor[8];
+        self.position += 10;
+
+        let palette = if flags & 0x80 != 0 {
+            let entries = 2 << (flags & 0x07);
+            let palette = (self.position, entries);
+            self.position += entries * 3;
+            Some(palette)
+        } else {
+            self.global_palette
+        };
+        let (palette_start, palette_len) = palette.ok_or(AnimationError::InvalidData)?;
+        let palette = self
+            .data
+            .get(palette_start..palette_start + palette_len * 3)
+            .ok_or(AnimationError::InvalidData)?;
+        let min_code_size = *self
+            .data
+            .get(self.position)
+            .ok_or(AnimationError::InvalidData)?;
+        self.position += 1;
+
+        if control.disposal == Disposal::Previous {
+            self.saved.copy_from(&self.canvas);
+        }
+
+        let mut rows = Rows::new(area.height, flags & 0x40 != 0);
+        let (mut x, mut y) = (0, rows.next());
+        let mut blocks = self.blocks();
+        let (placement, canvas) = (&self.placement, &mut self.canvas);
+        self.lzw.decode(&mut blocks, min_code_size, |index| {
+            let Some(row) = y else { return };
+            let index = index as usize;
+            if control.transparent != Some(index as u8) && index < palette_len {
+                let rgb = (
+                    palette[index * 3],
+                    palette[index * 3 + 1],
+                    palette[index * 3 + 2],
+                );
+                placement.put(canvas, area.x + x, area.y + row, rgb);
+            }
+            x += 1;
+            if x == area.width {
+                x = 0;
+                y = rows.next();
+            }
+        })?;
+        self.position = blocks.position;
+
+        self.pending = Some((control.disposal, area));
+        self.delay_ms = control.delay_ms;
+        Ok(())
+    }
+
+    /// Decode the next frame, marking playback done at the end or on error
+    fn advance(&mut self) -> bool {
+        match self.decode_next() {
+            Ok(true) => true,
+            _ => {
+                self.done = true;
+                false
+            }
+        }
+    }
+
+    /// Copy the current image into `frame`
+    pub fn render_into(&self, frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) {
+        frame.copy_from(&self.canvas);
+    }
+
+    /// Advance by one of the caller's frames, decoding frames as they fall due
+    ///
+    /// Frame delays are counted in multiples of the interval set with
+    /// [`Self::set_frame_interval_ms`]. Returns [`AnimationState::Wait`]
+    /// while a frame is held and [`AnimationState::Done`] once the last loop
+    /// has finished or the file turns out to be malformed.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        if self.done {
+            return AnimationState::Done;
+        }
+        if self.started {
+            self.elapsed_ms += self.frame_interval_ms;
+            if self.elapsed_ms < self.delay_ms {
+                return AnimationState::Wait;
+            }
+            self.elapsed_ms -= self.delay_ms;
+        }
+        self.show(frame)
+    }
+
+    /// Advance to the given time, decoding frames as they fall due
+    ///
+    /// `now_ms` is a monotonic timestamp from the caller's clock. The first
+    /// call shows the first frame; frames that fall due while a late call is
+    /// pending are skipped over so playback keeps to the file's timing.
+    pub fn next_at_into(
+        &mut self,
+        now_ms: u64,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        if self.done {
+            return AnimationState::Done;
+        }
+        if !self.started {
+            self.shown_at_ms = now_ms;
+            return self.show(frame);
+        }
+        if now_ms < self.shown_at_ms + self.delay_ms as u64 {
+            return AnimationState::Wait;
+        }
+        while now_ms >= self.shown_at_ms + self.delay_ms as u64 {
+            self.shown_at_ms += self.delay_ms as u64;
+            if !self.advance() {
+                return AnimationState::Done;
+            }
+        }
+        GifAnimation::render_into(self, frame);
+        AnimationState::Rendered
+    }
+
+    /// Decode the next frame and draw it into `frame`
+    fn show(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        self.started = true;
+        if !self.advance() {
+            return AnimationState::Done;
+        }
+        GifAnimation::render_into(self, frame);
+        AnimationState::Rendered
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for GifAnimation<'_, WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        GifAnimation::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        GifAnimation::render_into(self, frame);
+        Ok(())
+    }
+}
+
+/// Order in which image rows arrive, interlaced or not
+struct Rows {
+    /// Rows in the image
+    height: u32,
+    /// Whether rows arrive in four interlaced passes
+    interlaced: bool,
+    /// Current pass
+    pass: u8,
+    /// Next row to return
+    row: u32,
+}
+
+impl Rows {
+    /// Start at the first row
+    fn new(height: u32, interlaced: bool) -> Self {
+        Self {
+            height,
+            interlaced,
+            pass: 0,
+            row: 0,
+        }
+    }
+
+    /// Next row in arrival order, or `None` when all have arrived
+    fn next(&mut self) -> Option<u32> {
+        // Pass starts and steps: every 8th row from 0, every 8th from 4,
+        // every 4th from 2, then every other row from 1
+        const PASSES: [(u32, u32); 4] = [(0, 8), (4, 8), (2, 4), (1, 2)];
+        while self.row >= self.height {
+            if !self.interlaced || self.pass >= 3 {
+                return None;
+            }
+            self.pass += 1;
+            self.row = PASSES[self.pass as usize].0;
+        }
+        let row = self.row;
+        self.row += if self.interlaced {
+            PASSES[self.pass as usize].1
+        } else {
+            1
+        };
+        Some(row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The 10x10 sample from "What's in a GIF": white, red, blue and black
+    const SAMPLE: [u8; 69] = [
+        0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x0A, 0x00, 0x0A, 0x00, 0x91, 0x00, 0x00, 0xFF, 0xFF,
+        0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF, 0x00, 0x00, 0x00, 0x21, 0xF9, 0x04, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x2C, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x0A, 0x00, 0x00, 0x02, 0x16,
+        0x8C, 0x2D, 0x99, 0x87, 0x2A, 0x1C, 0xDC, 0x33, 0xA0, 0x02, 0x75, 0xEC, 0x95, 0xFA, 0xA8,
+        0xDE, 0x60, 0x8C, 0x04, 0x91, 0x4C, 0x01, 0x00, 0x3B,
+    ];
+
+    /// A 2x4 animation of four frames, looping twice, palette black, red,
+    /// green and blue:
+    ///
+    /// 1. The whole screen, then cleared to black after 100 ms
+    /// 2. Red at (1, 1) with a transparent pixel at (0, 1), kept for 200 ms
+    /// 3. Interlaced rows of red, green, blue and red, undone after 50 ms
+    /// 4. Green at (0, 0), shown for the default 100 ms
+    const ANIMATION: [u8; 141] = [
+        0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x02, 0x00, 0x04, 0x00, 0x81, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0xFF, 0x00, 0x00, 0x00, 0xFF, 0x00, 0x00, 0x00, 0xFF, 0x21, 0xFF, 0x0B, 0x4E, 0x45,
+        0x54, 0x53, 0x43, 0x41, 0x50, 0x45, 0x32, 0x2E, 0x30, 0x03, 0x01, 0x01, 0x00, 0x00, 0x21,
+        0xF9, 0x04, 0x08, 0x0A, 0x00, 0x00, 0x00, 0x2C, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x04,
+        0x00, 0x00, 0x02, 0x04, 0x8C, 0x06, 0x61, 0x52, 0x00, 0x21, 0xF9, 0x04, 0x05, 0x14, 0x00,
+        0x03, 0x00, 0x2C, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x5C,
+        0x0A, 0x00, 0x21, 0xF9, 0x04, 0x0C, 0x05, 0x00, 0x00, 0x00, 0x2C, 0x00, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x04, 0x00, 0x40, 0x02, 0x04, 0x4C, 0x36, 0x22, 0x56, 0x00, 0x21, 0xF9, 0x04,
+        0x04, 0x00, 0x00, 0x00, 0x00, 0x2C, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00,
+        0x02, 0x02, 0x54, 0x01, 0x00, 0x3B,
+    ];
+
+    #[test]
+    fn test_lzw_sample() {
+        let mut gif = GifAnimation::<10, 10, 6>::new(&SAMPLE, ImageFit::Center).unwrap();
+        let mut frame = Hub75FrameBuffer::new();
+        assert!(matches!(
+            gif.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+
+        let white = Hub75Color::white();
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(9, 0).unwrap(), Hub75Color::blue());
+        assert_eq!(frame.get_pixel(3, 3).unwrap(), white);
+        assert_eq!(frame.get_pixel(6, 6).unwrap(), white);
+        assert_eq!(frame.get_pixel(7, 6).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(4, 9).unwrap(), Hub75Color::blue());
+        assert_eq!(frame.get_pixel(5, 9).unwrap(), Hub75Color::red());
+
+        // A single frame without a loop count plays once
+        for _ in 0..6 {
+            assert!(matches!(gif.next_into(&mut frame), AnimationState::Wait));
+        }
+        assert!(matches!(gif.next_into(&mut frame), AnimationState::Done));
+    }
+
+    #[test]
+    fn test_disposal_and_transparency() {
+        let mut gif = GifAnimation::<2, 4, 6>::new(&ANIMATION, ImageFit::Center).unwrap();
+        let mut frame = Hub75FrameBuffer::new();
+        let pixels = |frame: &Hub75FrameBuffer<2, 4, 6>| {
+            let mut pixels = [[Hub75Color::black(); 2]; 4];
+            for (y, row) in pixels.iter_mut().enumerate() {
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    *pixel = frame.get_pixel(x, y).unwrap();
+                }
+            }
+            pixels
+        };
+        let (k, r, g, b) = (
+            Hub75Color::black(),
+            Hub75Color::red(),
+            Hub75Color::green(),
+            Hub75Color::blue(),
+        );
+
+        gif.next_at_into(1_000, &mut frame);
+        assert_eq!(pixels(&frame), [[r, g], [b, k], [r, r], [g, g]]);
+
+        assert!(matches!(
+            gif.next_at_into(1_099, &mut frame),
+            AnimationState::Wait
+        ));
+        gif.next_at_into(1_100, &mut frame);
+        assert_eq!(pixels(&frame), [[k, k], [k, r], [k, k], [k, k]]);
+        assert_eq!(gif.frame_index(), 1);
+
+        gif.next_at_into(1_300, &mut frame);
+        assert_eq!(pixels(&frame), [[r, r], [g, g], [b, b], [r, r]]);
+
+        gif.next_at_into(1_350, &mut frame);
+        assert_eq!(pixels(&frame), [[g, k], [k, r], [k, k], [k, k]]);
+
+        // The loop count asks for one repeat
+        gif.next_at_into(1_450, &mut frame);
+        assert_eq!(gif.frame_index(), 0);
+        assert_eq!(pixels(&frame), [[r, g], [b, k], [r, r], [g, g]]);
+        assert!(matches!(
+            gif.next_at_into(5_000, &mut frame),
+            AnimationState::Done
+        ));
+        assert!(gif.is_done());
+
+        gif.reset();
+        gif.set_loops(None);
+        assert!(matches!(
+            gif.next_at_into(0, &mut frame),
+            AnimationState::Rendered
+        ));
+        assert!(matches!(
+            gif.next_at_into(100_000, &mut frame),
+            AnimationState::Rendered
+        ));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(GifAnimation::<2, 2, 6>::new(b"GIF90a", ImageFit::Fit).is_err());
+
+        // Truncated image data ends playback
+        let mut gif = GifAnimation::<10, 10, 6>::new(&SAMPLE[..50], ImageFit::Fit).unwrap();
+        let mut frame = Hub75FrameBuffer::new();
+        assert!(matches!(gif.next_into(&mut frame), AnimationState::Done));
+    }
+}
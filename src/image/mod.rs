@@ -0,0 +1,208 @@
+//! Decoding still images into frame buffers
+//!
+//! Images stored in flash as BMP or QOI files can be drawn without converting
+//! them offline, and animated GIFs played back frame by frame. Decoding
+//! streams through the file once without allocating, maps each source pixel
+//! to the panel with nearest-neighbour scaling according to an [`ImageFit`],
+//! and converts it to the frame buffer's bit depth. Each format has its own
+//! feature: `bmp`, `gif` and `qoi`.
+//!
+//! Transparent pixels are drawn over black.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "qoi")]
+//! # {
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::image::ImageFit;
+//!
+//! // A 2x1 QOI image: a red pixel, then a green one
+//! let qoi = [
+//!     b'q', b'o', b'i', b'f', 0, 0, 0, 2, 0, 0, 0, 1, 3, 0,
+//!     0xFE, 255, 0, 0, 0xFE, 0, 255, 0,
+//!     0, 0, 0, 0, 0, 0, 0, 1,
+//! ];
+//! let frame = Hub75FrameBuffer::<4, 2, 6>::from_qoi(&qoi).unwrap();
+//! assert_eq!(frame.get_pixel(0, 1).unwrap(), Hub75Color::red());
+//! assert_eq!(frame.get_pixel(3, 0).unwrap(), Hub75Color::green());
+//!
+//! let mut frame = Hub75FrameBuffer::<4, 2, 6>::new();
+//! frame.draw_qoi(&qoi, ImageFit::Center).unwrap();
+//! assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::black());
+//! assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::red());
+//! # }
+//! ```
+
+#[cfg(feature = "bmp")]
+pub mod bmp;
+#[cfg(feature = "gif")]
+pub mod gif;
+#[cfg(feature = "qoi")]
+pub mod qoi;
+
+use core::ops::Range;
+
+use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer};
+
+/// How an image is sized and positioned on the panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ImageFit {
+    /// Keep the original size, centered and cropped at the panel edges
+    Center,
+    /// Scale to fit inside the panel, keeping the aspect ratio and leaving
+    /// the uncovered border untouched
+    #[default]
+    Fit,
+    /// Scale to cover the whole panel, keeping the aspect ratio and cropping
+    /// whatever overhangs
+    Fill,
+    /// Scale each axis to the panel size, distorting the image
+    Stretch,
+}
+
+/// Mapping of one source axis onto a panel axis
+#[derive(Debug, Clone, Copy)]
+struct Axis {
+    /// Source length in pixels
+    source: u32,
+    /// Scaled length in pixels
+    scaled: u32,
+    /// Panel coordinate of the first scaled pixel
+    offset: i64,
+}
+
+impl Axis {
+    /// Panel coordinates covered by a source pixel, possibly empty
+    fn targets(&self, index: u32) -> Range<i64> {
+        let edge = |i: u32| (i as u64 * self.scaled as u64).div_ceil(self.source as u64) as i64;
+        self.offset + edge(index)..self.offset + edge(index + 1)
+    }
+}
+
+/// Maps decoded source pixels onto a frame buffer
+pub(crate) struct Placement {
+    /// Horizontal mapping
+    x: Axis,
+    /// Vertical mapping
+    y: Axis,
+}
+
+impl Placement {
+    /// Place a `width` by `height` image on a `WIDTH` by `HEIGHT` panel
+    pub(crate) fn new<const WIDTH: usize, const HEIGHT: usize>(
+        width: u32,
+        height: u32,
+        fit: ImageFit,
+    ) -> Self {
+        let (panel_w, panel_h) = (WIDTH as u64, HEIGHT as u64);
+        let (w, h) = (width.max(1) as u64, height.max(1) as u64);
+        // Whether the image is wider than the panel, relative to its height
+        let wider = w * panel_h > h * panel_w;
+        let (scaled_w, scaled_h) = match fit {
+            ImageFit::Center => (w, h),
+            ImageFit::Stretch => (panel_w, panel_h),
+            ImageFit::Fit | ImageFit::Fill if wider == (fit == ImageFit::Fit) => {
+                (panel_w, (h * panel_w / w).max(1))
+            }
+            ImageFit::Fit | ImageFit::Fill => ((w * panel_h / h).max(1), panel_h),
+        };
+
+        let axis = |source: u64, scaled: u64, panel: u64| Axis {
+            source: source as u32,
+            scaled: scaled as u32,
+            offset: (panel as i64 - scaled as i64) / 2,
+        };
+        Self {
+            x: axis(w, scaled_w, panel_w),
+            y: axis(h, scaled_h, panel_h),
+        }
+    }
+
+    /// Fill the panel pixels covered by a rectangle of source pixels
+    #[cfg(feature = "gif")]
+    pub(crate) fn fill_area<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        (x, y, width, height): (u32, u32, u32, u32),
+        color: Hub75Color<COLOR_BITS>,
+    ) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        let columns = self.x.targets(x).start..self.x.targets(x + width - 1).end;
+        let rows = self.y.targets(y).start..self.y.targets(y + height - 1).end;
+        let (columns, rows) = (clip(columns, WIDTH), clip(rows, HEIGHT));
+        if !columns.is_empty() && !rows.is_empty() {
+            frame.fill_rect(columns.start, rows.start, columns.len(), rows.len(), color);
+        }
+    }
+
+    /// Draw a source pixel wherever it lands on the panel
+    pub(crate) fn put<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        x: u32,
+        y: u32,
+        (r, g, b): (u8, u8, u8),
+    ) {
+        let columns = clip(self.x.targets(x), WIDTH);
+        let rows = clip(self.y.targets(y), HEIGHT);
+        if !columns.is_empty() && !rows.is_empty() {
+            let color = Hub75Color::from_rgb8(r, g, b);
+            frame.fill_rect(columns.start, rows.start, columns.len(), rows.len(), color);
+        }
+    }
+}
+
+/// Limit a range of panel coordinates to `0..length`
+fn clip(range: Range<i64>, length: usize) -> Range<usize> {
+    let start = range.start.clamp(0, length as i64) as usize;
+    let end = range.end.clamp(0, length as i64) as usize;
+    start..end.max(start)
+}
+
+/// Blend a pixel with alpha over black
+#[cfg(any(feature = "bmp", feature = "qoi"))]
+pub(crate) fn over_black(r: u8, g: u8, b: u8, alpha: u8) -> (u8, u8, u8) {
+    let blend = |channel: u8| (channel as u16 * alpha as u16 / 255) as u8;
+    (blend(r), blend(g), blend(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placement() {
+        // A 4x2 image fitted to an 8x8 panel doubles and is centered
+        let fit = Placement::new::<8, 8>(4, 2, ImageFit::Fit);
+        assert_eq!(fit.x.targets(0), 0..2);
+        assert_eq!(fit.y.targets(1), 4..6);
+
+        // Filling crops the sides instead
+        let fill = Placement::new::<8, 8>(4, 2, ImageFit::Fill);
+        assert_eq!(fill.x.targets(0), -4..0);
+        assert_eq!(fill.y.targets(0), 0..4);
+
+        // Downscaling drops source pixels that do not land on any pixel
+        let stretch = Placement::new::<8, 8>(16, 3, ImageFit::Stretch);
+        assert_eq!(stretch.x.targets(2), 1..2);
+        assert!(stretch.x.targets(3).is_empty());
+        assert_eq!(stretch.y.targets(1), 3..6);
+
+        let center = Placement::new::<8, 8>(10, 2, ImageFit::Center);
+        assert_eq!(center.x.targets(0), -1..0);
+        assert_eq!(clip(center.x.targets(0), 8), 0..0);
+        assert_eq!(center.y.targets(0), 3..4);
+    }
+
+    #[cfg(any(feature = "bmp", feature = "qoi"))]
+    #[test]
+    fn test_over_black() {
+        assert_eq!(over_black(200, 100, 50, 255), (200, 100, 50));
+        assert_eq!(over_black(200, 100, 50, 0), (0, 0, 0));
+        assert_eq!(over_black(200, 100, 50, 128), (100, 50, 25));
+    }
+}
@@ -0,0 +1,191 @@
+//! Quite OK Image (QOI) decoding
+//!
+//! QOI compresses about as well as PNG for pixel art and UI graphics but
+//! decodes in a single pass with a 64-entry color cache, which makes it a
+//! good fit for images in microcontroller flash.
+
+use crate::{frame_buffer::Hub75FrameBuffer, AnimationError, Hub75Error};
+
+use super::{over_black, ImageFit, Placement};
+
+/// Size of the header
+const HEADER_LEN: usize = 14;
+/// Bytes marking the end of the stream
+const END_MARKER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+/// Operation tags
+const OP_RGB: u8 = 0xFE;
+const OP_RGBA: u8 = 0xFF;
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+/// Mask of the tag of 2-bit operations, the last of which (`0b11`) is a run
+const TAG_MASK: u8 = 0xC0;
+
+/// Dimensions of a QOI image
+///
+/// Returns [`AnimationError::InvalidData`] if `data` does not start with a
+/// QOI header.
+pub fn dimensions(data: &[u8]) -> Result<(u32, u32), Hub75Error> {
+    let header = data
+        .get(..HEADER_LEN)
+        .filter(|header| header.starts_with(b"qoif"))
+        .ok_or(AnimationError::InvalidData)?;
+    let width = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    let height = u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+    if width == 0 || height == 0 || !matches!(header[12], 3 | 4) {
+        return Err(AnimationError::InvalidData.into());
+    }
+    Ok((width, height))
+}
+
+/// Position of a pixel in the color cache
+fn cache_index([r, g, b, a]: [u8; 4]) -> usize {
+    (r as usize * 3 + g as usize * 5 + b as usize * 7 + a as usize * 11) % 64
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a frame buffer showing a QOI image scaled to fit the panel
+    ///
+    /// Returns [`AnimationError::InvalidData`] if `data` is not a valid QOI
+    /// image.
+    pub fn from_qoi(data: &[u8]) -> Result<Self, Hub75Error> {
+        let mut frame = Self::new();
+        frame.draw_qoi(data, ImageFit::Fit)?;
+        Ok(frame)
+    }
+
+    /// Draw a QOI image over the frame, sized and positioned by `fit`
+    ///
+    /// Pixels outside the image are left unchanged. Returns
+    /// [`AnimationError::InvalidData`] if `data` is not a valid QOI image; a
+    /// stream that ends early leaves the pixels decoded so far in place.
+    pub fn draw_qoi(&mut self, data: &[u8], fit: ImageFit) -> Result<(), Hub75Error> {
+        let (width, height) = dimensions(data)?;
+        let placement = Placement::new::<WIDTH, HEIGHT>(width, height, fit);
+        let stream = &data[HEADER_LEN..];
+        let stream = stream
+            .strip_suffix(&END_MARKER)
+            .ok_or(AnimationError::InvalidData)?;
+
+        let mut cache = [[0u8; 4]; 64];
+        let mut pixel = [0, 0, 0, 255];
+        let mut run = 0;
+        let mut bytes = stream.iter().copied();
+        let mut next = || bytes.next().ok_or(AnimationError::InvalidData);
+
+        for y in 0..height {
+            for x in 0..width {
+                if run > 0 {
+                    run -= 1;
+                } else {
+                    let op = next()?;
+                    match op {
+                        OP_RGB => pixel = [next()?, next()?, next()?, pixel[3]],
+                        OP_RGBA => pixel = [next()?, next()?, next()?, next()?],
+                        _ => match op & TAG_MASK {
+                            OP_INDEX => pixel = cache[op as usize],
+                            OP_DIFF => {
+                                let diff = |shift: u8| ((op >> shift) & 0x03).wrapping_sub(2);
+                                pixel[0] = pixel[0].wrapping_add(diff(4));
+                                pixel[1] = pixel[1].wrapping_add(diff(2));
+                                pixel[2] = pixel[2].wrapping_add(diff(0));
+                            }
+                            OP_LUMA => {
+                                let green = (op & 0x3F).wrapping_sub(32);
+                                let red_blue = next()?;
+                                let red = green.wrapping_add(red_blue >> 4).wrapping_sub(8);
+                                let blue = green.wrapping_add(red_blue & 0x0F).wrapping_sub(8);
+                                pixel[0] = pixel[0].wrapping_add(red);
+                                pixel[1] = pixel[1].wrapping_add(green);
+                                pixel[2] = pixel[2].wrapping_add(blue);
+                            }
+                            _ => run = op & !TAG_MASK,
+                        },
+                    }
+                    cache[cache_index(pixel)] = pixel;
+                }
+
+                let [r, g, b, a] = pixel;
+                placement.put(self, x, y, over_black(r, g, b, a));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Hub75Color;
+
+    /// Wrap an operation stream in a header and end marker
+    fn image<const N: usize>(width: u8, height: u8, ops: &[u8]) -> [u8; N] {
+        let mut data = [0u8; N];
+        data[..4].copy_from_slice(b"qoif");
+        data[7] = width;
+        data[11] = height;
+        data[12] = 4;
+        data[HEADER_LEN..HEADER_LEN + ops.len()].copy_from_slice(ops);
+        data[HEADER_LEN + ops.len()..].copy_from_slice(&END_MARKER);
+        data
+    }
+
+    #[test]
+    fn test_operations() {
+        let ops = [
+            OP_RGB,
+            100,
+            50,
+            200,                  // (100, 50, 200)
+            OP_DIFF | 0b11_01_10, // (101, 49, 200)
+            OP_LUMA | 40,
+            0x9A,     // green +8, red +9, blue +10
+            TAG_MASK, // a run repeating once
+            OP_INDEX | cache_index([100, 50, 200, 255]) as u8,
+            OP_RGBA,
+            255,
+            255,
+            255,
+            0, // transparent
+        ];
+        let data = image::<{ 14 + 14 + 8 }>(6, 1, &ops);
+        assert_eq!(dimensions(&data).unwrap(), (6, 1));
+
+        let frame = Hub75FrameBuffer::<6, 1, 8>::from_qoi(&data).unwrap();
+        let expected = [
+            (100, 50, 200),
+            (101, 49, 200),
+            (110, 57, 210),
+            (110, 57, 210),
+            (100, 50, 200),
+            (0, 0, 0),
+        ];
+        for (x, &(r, g, b)) in expected.iter().enumerate() {
+            assert_eq!(
+                frame.get_pixel(x, 0).unwrap(),
+                Hub75Color::from_rgb8(r, g, b),
+                "{x}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_fit_and_errors() {
+        // A 1x2 image fitted to a 4x4 panel becomes a centered 2x4 bar
+        let data = image::<{ 14 + 8 + 8 }>(1, 2, &[OP_RGB, 255, 0, 0, OP_RGB, 0, 0, 255]);
+        let frame = Hub75FrameBuffer::<4, 4, 6>::from_qoi(&data).unwrap();
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(1, 1).unwrap(), Hub75Color::red());
+        assert_eq!(frame.get_pixel(2, 3).unwrap(), Hub75Color::blue());
+        assert_eq!(frame.get_pixel(3, 3).unwrap(), Hub75Color::black());
+
+        // Missing pixels and a missing end marker
+        let short = image::<{ 14 + 4 + 8 }>(1, 2, &[OP_RGB, 255, 0, 0]);
+        assert!(Hub75FrameBuffer::<4, 4, 6>::from_qoi(&short).is_err());
+        assert!(Hub75FrameBuffer::<4, 4, 6>::from_qoi(&data[..data.len() - 1]).is_err());
+        assert!(dimensions(b"qoif").is_err());
+    }
+}
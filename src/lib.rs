@@ -102,11 +102,62 @@
 //! # }
 //! ```
 
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
 pub mod animation;
+#[cfg(feature = "bdf")]
+pub mod bdf;
+pub mod bitstream;
+pub mod buffering;
 pub mod color;
+pub mod composite;
+#[cfg(feature = "ddp")]
+pub mod ddp;
+pub mod delay;
+pub mod diagnostics;
 pub mod display;
+#[cfg(any(feature = "artnet", feature = "sacn"))]
+pub mod dmx;
+pub mod effects;
+#[cfg(feature = "embassy")]
+pub mod embassy;
+pub mod expander;
+pub mod font;
 pub mod frame_buffer;
+#[cfg(feature = "frame-store")]
+pub mod frame_store;
+pub mod icon;
+#[cfg(any(feature = "bmp", feature = "gif", feature = "qoi"))]
+pub mod image;
+pub mod math;
+pub mod memory;
+pub mod modulation;
+pub mod mono;
+#[cfg(feature = "nrf52")]
+pub mod nrf;
+pub mod palette;
 pub mod pins;
+#[cfg(any(test, feature = "std"))]
+mod png;
+#[cfg(feature = "qrcode")]
+pub mod qr;
+pub mod rtic;
+pub mod scene;
+pub mod schedule;
+pub mod sequence;
+pub mod shared;
+#[cfg(feature = "simulator")]
+pub mod simulator;
+pub mod sink;
+#[cfg(feature = "async")]
+pub mod spi;
+pub mod stream;
+pub mod timing;
+#[cfg(any(test, feature = "waveform"))]
+pub mod waveform;
+pub mod widgets;
+pub mod window;
 
 /// Macro to simplify pin error handling
 macro_rules! pin_op {
@@ -117,6 +168,16 @@ macro_rules! pin_op {
 
 pub(crate) use pin_op;
 
+/// Emit a defmt trace event when the `defmt-trace` feature is enabled
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "defmt-trace")]
+        defmt::trace!($($arg)*);
+    };
+}
+
+pub(crate) use trace_event;
+
 // Error types (moved from error.rs for consolidation)
 
 /// Errors that can occur when using the HUB75 driver
@@ -133,6 +194,10 @@ pub enum Hub75Error {
     AnimationError(AnimationError),
     /// Buffer overflow
     BufferOverflow,
+    /// External storage operation failed
+    StorageError,
+    /// Pin configuration does not suit the panel
+    PinConfig(PinConfigError),
 }
 
 /// Animation-specific errors
@@ -153,15 +218,66 @@ impl From<AnimationError> for Hub75Error {
     }
 }
 
+/// Problems found when validating a pin configuration against a panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PinConfigError {
+    /// Pin groups that were never set
+    Missing {
+        /// RGB pins are missing
+        rgb: bool,
+        /// Address pins are missing
+        address: bool,
+        /// Control pins are missing
+        control: bool,
+    },
+    /// The E address pin was given without the D pin
+    AddressGap,
+    /// Too few address pins to select every row of the panel
+    NotEnoughAddressPins {
+        /// Address pins the panel height needs
+        required: usize,
+        /// Address pins given
+        provided: usize,
+    },
+    /// The panel has no pixels or an odd number of rows
+    InvalidGeometry,
+}
+
+impl From<PinConfigError> for Hub75Error {
+    fn from(err: PinConfigError) -> Self {
+        Hub75Error::PinConfig(err)
+    }
+}
+
 // Re-export main types
-pub use animation::{Animation, AnimationEffect, AnimationState};
-pub use color::Hub75Color;
-pub use display::Hub75Display;
-pub use frame_buffer::Hub75FrameBuffer;
-pub use pins::{Hub75AddressPins, Hub75ControlPins, Hub75Pins, Hub75RgbPins};
+pub use animation::{
+    Animation, AnimationData, AnimationEffect, AnimationEffectTrait, AnimationEvent,
+    AnimationState, Direction, Easing, FrameGenerator, Origin, PlaybackMode,
+};
+pub use color::{Hsv, Hub75Color, Hub75Color16, Hub75Pixel, LinearRgb};
+pub use composite::{BlendMode, Composite};
+pub use display::{
+    Brightness, BrightnessCurve, BrightnessPreset, BrightnessZone, ColorBalance, ColorMode,
+    Hub75Display, PlaneOrder, ScanOrder, Tint,
+};
+#[cfg(feature = "embedded-graphics")]
+pub use frame_buffer::{DitheredTarget, NativeTarget};
+pub use frame_buffer::{FrameDiff, Hub75FrameBuffer};
+#[cfg(feature = "frame-store")]
+pub use frame_store::{FrameStore, StorageFrameStore};
+pub use modulation::Modulation;
+pub use mono::MonoFrameBuffer;
+pub use palette::Palette;
+pub use pins::{AddressPins, Hub75AddressPins, Hub75ControlPins, Hub75Pins, Hub75RgbPins};
+pub use scene::{FrameSource, SceneManager};
+pub use sequence::{AnimationSequence, SequenceItem};
+pub use sink::FrameSink;
+pub use window::Window;
 
 // Re-export commonly used types from dependencies
 pub use embedded_hal::digital::OutputPin;
+#[cfg(feature = "async")]
 pub use embedded_hal_async::delay::DelayNs;
 
 #[cfg(feature = "embedded-graphics")]
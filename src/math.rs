@@ -0,0 +1,241 @@
+//! Fixed-point math for effects and animations
+//!
+//! Cortex-M0 and many other targets driving HUB75 panels have no FPU, so this
+//! module provides the handful of functions effects need in integer form:
+//! table-based sine and cosine, `atan2`, integer square root, and seeded 2D
+//! and 3D value noise for organic textures.
+//!
+//! Angles are `u16` fractions of a full turn, so `0x4000` is a quarter turn
+//! and arithmetic on angles wraps around naturally. Sine and cosine return
+//! Q15 values, where `32767` is 1.0.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::math;
+//!
+//! assert_eq!(math::sin(math::QUARTER_TURN), 32767);
+//! assert_eq!(math::cos(math::degrees(180)), -32767);
+//! assert_eq!(math::isqrt(1000), 31);
+//! assert_eq!(math::atan2(1, 0), math::QUARTER_TURN);
+//! ```
+
+/// A quarter turn (90 degrees)
+pub const QUARTER_TURN: u16 = 0x4000;
+/// Half a turn (180 degrees)
+pub const HALF_TURN: u16 = 0x8000;
+
+/// `sin` over a quarter turn in 64 steps, in Q15
+const SIN_TABLE: [i16; 65] = [
+    0, 804, 1608, 2410, 3212, 4011, 4808, 5602, 6393, 7179, 7962, 8739, 9512, 10278, 11039, 11793,
+    12539, 13279, 14010, 14732, 15446, 16151, 16846, 17530, 18204, 18868, 19519, 20159, 20787,
+    21403, 22005, 22594, 23170, 23731, 24279, 24811, 25329, 25832, 26319, 26790, 27245, 27683,
+    28105, 28510, 28898, 29268, 29621, 29956, 30273, 30571, 30852, 31113, 31356, 31580, 31785,
+    31971, 32137, 32285, 32412, 32521, 32609, 32678, 32728, 32757, 32767,
+];
+
+/// Convert whole degrees to an angle (wrapping past 360)
+pub const fn degrees(degrees: u32) -> u16 {
+    ((degrees % 360) * 0x10000 / 360) as u16
+}
+
+/// `sin` over the first quarter turn, interpolated between table entries
+fn sin_quarter(angle: u16) -> i32 {
+    let index = (angle >> 8) as usize;
+    let fraction = (angle & 0xFF) as i32;
+    let low = SIN_TABLE[index] as i32;
+    match SIN_TABLE.get(index + 1) {
+        Some(&high) => low + (high as i32 - low) * fraction / 256,
+        None => low,
+    }
+}
+
+/// Sine of an angle, in Q15 (-32767 to 32767)
+pub fn sin(angle: u16) -> i16 {
+    let within = angle % QUARTER_TURN;
+    let value = match angle / QUARTER_TURN {
+        0 | 2 => sin_quarter(within),
+        _ => sin_quarter(QUARTER_TURN - within),
+    };
+    if angle >= HALF_TURN {
+        -value as i16
+    } else {
+        value as i16
+    }
+}
+
+/// Cosine of an angle, in Q15 (-32767 to 32767)
+pub fn cos(angle: u16) -> i16 {
+    sin(angle.wrapping_add(QUARTER_TURN))
+}
+
+/// Sine wave between 0 and 255, centered on 128, with 256 steps per turn
+///
+/// Handy for pulsing brightness and cycling color channels.
+pub fn sin8(angle: u8) -> u8 {
+    (128 + sin((angle as u16) << 8) as i32 * 127 / 32767) as u8
+}
+
+/// Angle of the vector `(x, y)` from the positive x axis, counterclockwise
+///
+/// Accurate to within a quarter of a degree. Returns 0 for `(0, 0)`.
+pub fn atan2(y: i32, x: i32) -> u16 {
+    let (ax, ay) = (x.unsigned_abs() as u64, y.unsigned_abs() as u64);
+    if ax == 0 && ay == 0 {
+        return 0;
+    }
+
+    // atan(t) ≈ π/4·t + 0.273·t·(1 − t) for 0 ≤ t ≤ 1, scaled to angle units
+    let octant = |t: u64| ((8192 * t + 2847 * t * (65536 - t) / 65536) / 65536) as u16;
+    let angle = if ax >= ay {
+        octant((ay << 16) / ax)
+    } else {
+        QUARTER_TURN - octant((ax << 16) / ay)
+    };
+
+    match (x >= 0, y >= 0) {
+        (true, true) => angle,
+        (false, true) => HALF_TURN - angle,
+        (false, false) => HALF_TURN + angle,
+        (true, false) => 0u16.wrapping_sub(angle),
+    }
+}
+
+/// Integer square root, rounded down
+pub fn isqrt(value: u32) -> u32 {
+    value.isqrt()
+}
+
+/// Hash lattice coordinates and a seed to a pseudo-random 32-bit value
+pub fn hash(x: u32, y: u32, seed: u32) -> u32 {
+    let mut h = seed ^ x.wrapping_mul(0x27D4_EB2D) ^ y.wrapping_mul(0x1656_67B1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xC2B2_AE35);
+    h ^ (h >> 16)
+}
+
+/// Smoothstep the 8-bit fraction of a 24.8 coordinate so cell edges do not show
+fn smooth_fraction(coordinate: u32) -> i32 {
+    let f = (coordinate & 0xFF) as i32;
+    f * f * (3 * 256 - 2 * f) / 65536
+}
+
+/// Interpolate from `a` to `b` by `t` out of 256
+fn mix(a: i32, b: i32, t: i32) -> i32 {
+    a + (b - a) * t / 256
+}
+
+/// Smooth 2D value noise between 0 and 255
+///
+/// `x` and `y` are in 24.8 fixed point, so the noise changes smoothly over
+/// 256 units and repeats every 2^24 lattice cells. Different seeds give
+/// unrelated patterns.
+pub fn value_noise(x: u32, y: u32, seed: u32) -> u8 {
+    let (cell_x, cell_y) = (x >> 8, y >> 8);
+    let corner = |dx: u32, dy: u32| {
+        (hash(cell_x.wrapping_add(dx), cell_y.wrapping_add(dy), seed) >> 24) as i32
+    };
+
+    let (fx, fy) = (smooth_fraction(x), smooth_fraction(y));
+    let top = mix(corner(0, 0), corner(1, 0), fx);
+    let bottom = mix(corner(0, 1), corner(1, 1), fx);
+    mix(top, bottom, fy) as u8
+}
+
+/// Smooth 3D value noise between 0 and 255
+///
+/// Like [`value_noise`] with a third coordinate `z`, also in 24.8 fixed
+/// point. Using time as `z` makes a 2D pattern evolve smoothly instead of
+/// just sliding across the panel.
+pub fn value_noise3(x: u32, y: u32, z: u32, seed: u32) -> u8 {
+    let cell_z = z >> 8;
+    let fz = smooth_fraction(z);
+    let below = value_noise(x, y, hash(cell_z, 0, seed)) as i32;
+    let above = value_noise(x, y, hash(cell_z.wrapping_add(1), 0, seed)) as i32;
+    mix(below, above, fz) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trig() {
+        assert_eq!(sin(0), 0);
+        assert_eq!(sin(QUARTER_TURN), 32767);
+        assert_eq!(sin(HALF_TURN), 0);
+        assert_eq!(sin(degrees(270)), -32767);
+        assert_eq!(cos(0), 32767);
+
+        // Within 0.1% of the real values between table entries
+        for degree in 0..360 {
+            let exact = (degree as f64).to_radians().sin() * 32767.0;
+            assert!(
+                (sin(degrees(degree)) as f64 - exact).abs() < 33.0,
+                "{degree}"
+            );
+        }
+
+        assert_eq!(sin8(0), 128);
+        assert_eq!(sin8(64), 255);
+        assert_eq!(sin8(192), 1);
+    }
+
+    #[test]
+    fn test_atan2() {
+        assert_eq!(atan2(0, 0), 0);
+        assert_eq!(atan2(0, 5), 0);
+        assert_eq!(atan2(5, 0), QUARTER_TURN);
+        assert_eq!(atan2(0, -5), HALF_TURN);
+        assert_eq!(atan2(-5, 0), 3 * QUARTER_TURN);
+
+        // Within a quarter of a degree all the way round
+        for degree in 0..360 {
+            let radians = (degree as f64).to_radians();
+            let (x, y) = (
+                (radians.cos() * 1000.0) as i32,
+                (radians.sin() * 1000.0) as i32,
+            );
+            let error = atan2(y, x).wrapping_sub(degrees(degree)) as i16;
+            assert!(error.unsigned_abs() < 50, "{degree}: {error}");
+        }
+    }
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(u32::MAX), 65535);
+    }
+
+    #[test]
+    fn test_value_noise() {
+        // Lattice points take the hashed corner values
+        assert_eq!(value_noise(3 << 8, 7 << 8, 1), (hash(3, 7, 1) >> 24) as u8);
+        assert_ne!(
+            value_noise(3 << 8, 7 << 8, 2),
+            value_noise(3 << 8, 7 << 8, 1)
+        );
+
+        // Neighbouring samples differ only a little
+        for x in 0..1024 {
+            let step = value_noise(x, 100, 7) as i32 - value_noise(x + 1, 100, 7) as i32;
+            assert!(step.abs() <= 3, "{x}");
+        }
+
+        // Whole steps of z land on unrelated 2D slices, smoothly joined
+        let slice = hash(4, 0, 7);
+        assert_eq!(
+            value_noise3(300, 900, 4 << 8, 7),
+            value_noise(300, 900, slice)
+        );
+        for z in 0..1024 {
+            let step =
+                value_noise3(300, 900, z, 7) as i32 - value_noise3(300, 900, z + 1, 7) as i32;
+            assert!(step.abs() <= 3, "{z}");
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Compile-time memory usage
+//!
+//! Frame buffers grow with panel size and quickly take up most of the RAM of
+//! small microcontrollers: a 64x64 panel needs 12 KB per frame buffer, twice
+//! that with double buffering and again twice that with
+//! [`Hub75Color16`](crate::Hub75Color16) pixels. The `const fn`s here give the
+//! exact size in bytes of the buffers a configuration with the default
+//! [`Hub75Color`](crate::Hub75Color) pixels uses, so a configuration can be
+//! sized before it is flashed, and [`assert_memory_budget!`](crate::assert_memory_budget) turns
+//! going over a budget into a compile error.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::memory;
+//!
+//! const USAGE: usize = memory::display_buffer_bytes::<64, 32, 6>(true)
+//!     + memory::bitstream_bytes::<64, 32, 6>()
+//!     + memory::animation_frames_bytes::<64, 32, 6>(2);
+//!
+//! // Fails to compile once the buffers outgrow 60 KB of a 64 KB part
+//! hub75::assert_memory_budget!(USAGE, 60 * 1024);
+//! ```
+
+use crate::{
+    buffering::{DoubleBuffer, SingleBuffer},
+    frame_buffer::Hub75FrameBuffer,
+    mono::MonoFrameBuffer,
+};
+
+/// Get the size of a frame buffer in bytes
+pub const fn frame_buffer_bytes<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+>() -> usize {
+    core::mem::size_of::<Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>()
+}
+
+/// Get the size of a monochrome frame buffer in bytes
+pub const fn mono_frame_buffer_bytes<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+>() -> usize {
+    core::mem::size_of::<MonoFrameBuffer<WIDTH, HEIGHT, COLOR_BITS>>()
+}
+
+/// Get the size of the buffers a display holds in bytes, with
+/// [`DoubleBuffer`] or [`SingleBuffer`] storage
+pub const fn display_buffer_bytes<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+>(
+    double_buffered: bool,
+) -> usize {
+    if double_buffered {
+        core::mem::size_of::<DoubleBuffer<WIDTH, HEIGHT, COLOR_BITS>>()
+    } else {
+        core::mem::size_of::<SingleBuffer<WIDTH, HEIGHT, COLOR_BITS>>()
+    }
+}
+
+/// Get the size of a [`Bitstream`](crate::bitstream::Bitstream) cache for a
+/// panel in bytes, including the padding to word alignment
+pub const fn bitstream_bytes<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+) -> usize {
+    (WIDTH * (HEIGHT / 2) * COLOR_BITS).next_multiple_of(4)
+}
+
+/// Get the size of `frames` pre-rendered frame buffers for
+/// [`AnimationData::Frames`](crate::animation::AnimationData::Frames)
+pub const fn animation_frames_bytes<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+>(
+    frames: usize,
+) -> usize {
+    frames * frame_buffer_bytes::<WIDTH, HEIGHT, COLOR_BITS>()
+}
+
+/// Get the size of `frames` frames of raw RGB888 data for
+/// [`AnimationData::RgbData`](crate::animation::AnimationData::RgbData) or a
+/// `FrameStore` (`frame-store` feature)
+pub const fn rgb_data_bytes<const WIDTH: usize, const HEIGHT: usize>(frames: usize) -> usize {
+    frames * WIDTH * HEIGHT * 3
+}
+
+/// Fail to compile when a memory usage in bytes exceeds a budget
+///
+/// Both arguments must be constant expressions, such as sums of the
+/// [`memory`](crate::memory) functions.
+///
+/// ```rust,compile_fail
+/// // Two 64x64 frame buffers do not fit in 16 KB
+/// hub75::assert_memory_budget!(hub75::memory::display_buffer_bytes::<64, 64, 6>(true), 16 * 1024);
+/// ```
+#[macro_export]
+macro_rules! assert_memory_budget {
+    ($bytes:expr, $budget:expr $(,)?) => {
+        const _: () = ::core::assert!($bytes <= $budget, "hub75 buffers exceed the memory budget");
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitstream::Bitstream;
+    use crate::color::Hub75Color16;
+
+    #[test]
+    fn test_memory_usage() {
+        assert_eq!(frame_buffer_bytes::<64, 32, 6>(), 64 * 32 * 3);
+        assert_eq!(mono_frame_buffer_bytes::<64, 32, 6>(), 64 * 32);
+        assert_eq!(
+            core::mem::size_of::<Hub75FrameBuffer<64, 32, 12, Hub75Color16<12>>>(),
+            2 * frame_buffer_bytes::<64, 32, 12>()
+        );
+        assert_eq!(
+            display_buffer_bytes::<64, 32, 6>(true),
+            2 * display_buffer_bytes::<64, 32, 6>(false)
+        );
+        assert_eq!(
+            bitstream_bytes::<64, 32, 6>(),
+            core::mem::size_of::<Bitstream<64, 32, 6, 16>>()
+        );
+        assert_eq!(
+            bitstream_bytes::<5, 2, 3>(),
+            core::mem::size_of::<Bitstream<5, 2, 3, 1>>()
+        );
+        assert_eq!(
+            animation_frames_bytes::<32, 16, 4>(3),
+            3 * frame_buffer_bytes::<32, 16, 4>()
+        );
+        assert_eq!(rgb_data_bytes::<32, 16>(2), 32 * 16 * 3 * 2);
+
+        // Usable in constant expressions
+        crate::assert_memory_budget!(
+            display_buffer_bytes::<64, 32, 6>(true) + bitstream_bytes::<64, 32, 6>(),
+            64 * 1024,
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Modulation schemes turning color levels into on-times
+//!
+//! A HUB75 panel can only switch each LED on or off, so color depth comes from
+//! showing every row several times per frame, in *subframes* of different
+//! lengths, and lighting each LED in a subset of them. A [`Modulation`]
+//! decides how many subframes there are, how long each one lasts and which
+//! levels are lit in it:
+//!
+//! - [`Bcm`]: Binary Code Modulation, one subframe per bit weighted by its
+//!   place value. The fewest subframes, so the highest refresh rate. This is
+//!   the default.
+//! - [`Pwm`]: one equal subframe per level, lit while the level is above the
+//!   subframe index. Avoids the artifacts BCM shows on camera and with fast
+//!   motion, at a much lower refresh rate for deep colors.
+//! - [`Hybrid`]: BCM for the upper bits and temporal dithering across frames
+//!   for the lower ones, trading a little flicker for a higher refresh rate
+//!   at the same depth.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::modulation::Hybrid;
+//! use hub75::{Hub75Display, Hub75Pins};
+//! use embedded_hal::digital::OutputPin;
+//!
+//! # fn example<P: OutputPin + 'static>(pins: Hub75Pins<P>) -> Result<(), hub75::Hub75Error> {
+//! let mut display = Hub75Display::<_, 64, 32, 8>::new(pins)?;
+//!
+//! // 6 BCM subframes per frame, the 2 lowest bits spread over 4 frames
+//! display.set_modulation(&Hybrid { temporal_bits: 2 });
+//! # Ok(())
+//! # }
+//! ```
+
+/// Scheme deciding which levels are lit in each subframe of a frame
+///
+/// Implementations should make the on-time of a level, summed over
+/// [`subframes`](Self::subframes) and averaged over frames, proportional to
+/// the level, so that brightness stays linear in the frame buffer content.
+pub trait Modulation {
+    /// Get the number of subframes in a frame at a color depth
+    fn subframes(&self, color_bits: usize) -> usize;
+
+    /// Get the on-time of a subframe in units of the refresh interval
+    fn weight(&self, color_bits: usize, subframe: usize) -> u32;
+
+    /// Check whether a channel at `level` is lit during a subframe
+    ///
+    /// `phase` counts frames, offset per pixel so that neighbouring pixels
+    /// are out of step, for schemes that spread levels over several frames.
+    fn is_lit(&self, color_bits: usize, level: u32, subframe: usize, phase: u8) -> bool;
+
+    /// Get the bit of the level shown unchanged during a subframe, if any
+    ///
+    /// Returning `Some` lets the display take its bit-plane fast path instead
+    /// of calling [`is_lit`](Self::is_lit) for every pixel.
+    fn bit_plane(&self, _color_bits: usize, _subframe: usize) -> Option<usize> {
+        None
+    }
+}
+
+/// Binary Code Modulation: one subframe per bit, weighted by its place value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Bcm;
+
+impl Modulation for Bcm {
+    fn subframes(&self, color_bits: usize) -> usize {
+        color_bits
+    }
+
+    fn weight(&self, _color_bits: usize, subframe: usize) -> u32 {
+        1 << subframe
+    }
+
+    fn is_lit(&self, _color_bits: usize, level: u32, subframe: usize, _phase: u8) -> bool {
+        (level >> subframe) & 1 != 0
+    }
+
+    fn bit_plane(&self, _color_bits: usize, subframe: usize) -> Option<usize> {
+        Some(subframe)
+    }
+}
+
+/// Pulse width modulation: one equal subframe per level
+///
+/// A frame has `2^COLOR_BITS - 1` subframes, so keep the depth low: 4 bits
+/// need 15 subframes against BCM's 4, 8 bits need 255.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Pwm;
+
+impl Modulation for Pwm {
+    fn subframes(&self, color_bits: usize) -> usize {
+        (1 << color_bits) - 1
+    }
+
+    fn weight(&self, _color_bits: usize, _subframe: usize) -> u32 {
+        1
+    }
+
+    fn is_lit(&self, _color_bits: usize, level: u32, subframe: usize, _phase: u8) -> bool {
+        level as usize > subframe
+    }
+}
+
+/// BCM for the upper bits, temporal dithering for the lower ones
+///
+/// Only `COLOR_BITS - temporal_bits` subframes are shown per frame. The lower
+/// `temporal_bits` of each level round the upper bits up on a matching
+/// fraction of `2^temporal_bits` frames, so the average level is exact up to
+/// the highest level the upper bits can show. Levels above that cannot round
+/// up and are clamped: at 6 bits with 2 temporal bits, levels 61 to 63 all
+/// average 60. At least one bit is always shown with BCM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Hybrid {
+    /// Number of low bits shown by dithering across frames
+    pub temporal_bits: u8,
+}
+
+impl Hybrid {
+    /// Number of temporal bits usable at a color depth
+    fn temporal_bits(&self, color_bits: usize) -> usize {
+        (self.temporal_bits as usize).min(color_bits.saturating_sub(1))
+    }
+}
+
+impl Modulation for Hybrid {
+    fn subframes(&self, color_bits: usize) -> usize {
+        color_bits - self.temporal_bits(color_bits)
+    }
+
+    fn weight(&self, color_bits: usize, subframe: usize) -> u32 {
+        1 << (subframe + self.temporal_bits(color_bits))
+    }
+
+    fn is_lit(&self, color_bits: usize, level: u32, subframe: usize, phase: u8) -> bool {
+        let temporal_bits = self.temporal_bits(color_bits);
+        let upper = level >> temporal_bits;
+        let fraction = level & ((1 << temporal_bits) - 1);
+
+        // Bit-reversed frame order spreads the rounded-up frames evenly
+        let threshold = if temporal_bits == 0 {
+            0
+        } else {
+            (phase as u32).reverse_bits() >> (32 - temporal_bits)
+        };
+        let max_upper = (1 << (color_bits - temporal_bits)) - 1;
+        let upper = (upper + (fraction > threshold) as u32).min(max_upper);
+        (upper >> subframe) & 1 != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Average on-time of a level over `frames` frames
+    fn on_time(modulation: &impl Modulation, color_bits: usize, level: u32, frames: u8) -> u32 {
+        let total: u32 = (0..frames)
+            .flat_map(|phase| {
+                (0..modulation.subframes(color_bits)).map(move |subframe| (phase, subframe))
+            })
+            .filter(|&(phase, subframe)| modulation.is_lit(color_bits, level, subframe, phase))
+            .map(|(_, subframe)| modulation.weight(color_bits, subframe))
+            .sum();
+        total / frames as u32
+    }
+
+    #[test]
+    fn test_bcm() {
+        assert_eq!(Bcm.subframes(6), 6);
+        assert_eq!(Bcm.weight(6, 5), 32);
+        assert_eq!(Bcm.bit_plane(6, 3), Some(3));
+        for level in 0..64 {
+            assert_eq!(on_time(&Bcm, 6, level, 1), level);
+        }
+    }
+
+    #[test]
+    fn test_pwm() {
+        assert_eq!(Pwm.subframes(4), 15);
+        assert_eq!(Pwm.bit_plane(4, 0), None);
+        assert!(Pwm.is_lit(4, 3, 2, 0));
+        assert!(!Pwm.is_lit(4, 3, 3, 0));
+        for level in 0..16 {
+            assert_eq!(on_time(&Pwm, 4, level, 1), level);
+        }
+    }
+
+    #[test]
+    fn test_hybrid() {
+        let hybrid = Hybrid { temporal_bits: 2 };
+        assert_eq!(hybrid.subframes(6), 4);
+        assert_eq!(hybrid.weight(6, 0), 4);
+        assert_eq!(hybrid.weight(6, 3), 32);
+
+        // Exact on average over the four frame dithering cycle
+        for level in 0..=60 {
+            assert_eq!(on_time(&hybrid, 6, level, 4), level);
+        }
+        // The top levels cannot round up any further
+        assert_eq!(on_time(&hybrid, 6, 63, 4), 60);
+
+        // Level 1 is lit in one frame out of four
+        let lit = (0..4)
+            .filter(|&phase| hybrid.is_lit(6, 1, 0, phase))
+            .count();
+        assert_eq!(lit, 1);
+
+        // At least one bit is always shown with BCM
+        let all_temporal = Hybrid { temporal_bits: 8 };
+        assert_eq!(all_temporal.subframes(4), 1);
+        assert_eq!(Hybrid::default().subframes(4), 4);
+        for level in 0..16 {
+            assert_eq!(on_time(&Hybrid::default(), 4, level, 1), level);
+        }
+    }
+}
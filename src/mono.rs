@@ -0,0 +1,220 @@
+//! Single-channel frame buffers for monochrome message boards
+//!
+//! A [`MonoFrameBuffer`] stores one intensity level per pixel instead of three
+//! color channels, so each one takes a third of the size of a
+//! [`Hub75FrameBuffer`]. This suits keeping many pre-rendered text and
+//! animation frames for single-color panels. The display itself still scans
+//! out of full color frame buffers, so its own buffers are not any smaller:
+//! frames are copied into them in any ink color with
+//! [`MonoFrameBuffer::copy_to`]; combined with
+//! [`ColorMode::Monochrome`](crate::ColorMode::Monochrome) or
+//! [`ColorMode::RedOnly`](crate::ColorMode::RedOnly) only the red channel is
+//! scanned out.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75FrameBuffer, MonoFrameBuffer};
+//!
+//! let mut message = MonoFrameBuffer::<64, 32, 6>::new();
+//! message.set_pixel(3, 4, 63).unwrap();
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! message.copy_to(&mut frame, Hub75Color::red());
+//! assert_eq!(frame.get_pixel(3, 4).unwrap(), Hub75Color::red());
+//! ```
+
+use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer, Hub75Error};
+
+/// Frame buffer storing a single intensity level per pixel
+#[derive(Debug, PartialEq, Eq)]
+pub struct MonoFrameBuffer<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Intensity levels (0 to `Hub75Color::<COLOR_BITS>::MAX_VALUE`)
+    pixels: [[u8; WIDTH]; HEIGHT],
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    MonoFrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Maximum intensity level for this bit depth
+    pub const MAX_LEVEL: u8 = Hub75Color::<COLOR_BITS>::MAX_VALUE;
+
+    /// Create a new frame buffer with all pixels off
+    pub fn new() -> Self {
+        Self {
+            pixels: [[0; WIDTH]; HEIGHT],
+        }
+    }
+
+    /// Create a frame buffer from the brightest channel of each color pixel
+    pub fn from_frame_buffer(buffer: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) -> Self {
+        let mut mono = Self::new();
+        for (y, row) in mono.pixels.iter_mut().enumerate() {
+            // SAFETY: y < HEIGHT, the row of a frame buffer with the same dimensions
+            let colors = unsafe { buffer.get_row(y).unwrap_unchecked() };
+            for (level, color) in row.iter_mut().zip(colors) {
+                *level = color.r.max(color.g).max(color.b);
+            }
+        }
+        mono
+    }
+
+    /// Clear the frame buffer (turn all pixels off)
+    pub fn clear(&mut self) {
+        self.fill(0);
+    }
+
+    /// Fill the entire frame buffer with a single level
+    pub fn fill(&mut self, level: u8) {
+        let level = level.min(Self::MAX_LEVEL);
+        for row in &mut self.pixels {
+            row.fill(level);
+        }
+    }
+
+    /// Set the level of a pixel, clamped to the bit depth
+    pub fn set_pixel(&mut self, x: usize, y: usize, level: u8) -> Result<(), Hub75Error> {
+        if x >= WIDTH || y >= HEIGHT {
+            return Err(Hub75Error::InvalidCoordinates);
+        }
+        self.pixels[y][x] = level.min(Self::MAX_LEVEL);
+        Ok(())
+    }
+
+    /// Get the level of a pixel
+    pub fn get_pixel(&self, x: usize, y: usize) -> Result<u8, Hub75Error> {
+        if x >= WIDTH || y >= HEIGHT {
+            return Err(Hub75Error::InvalidCoordinates);
+        }
+        Ok(self.pixels[y][x])
+    }
+
+    /// Copy into a color frame buffer, scaling `ink` by each pixel's level
+    ///
+    /// Pixels at full level take the ink color and pixels that are off are
+    /// black.
+    pub fn copy_to(
+        &self,
+        target: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        ink: Hub75Color<COLOR_BITS>,
+    ) {
+        for (y, row) in self.pixels.iter().enumerate() {
+            // SAFETY: y < HEIGHT, the row of a frame buffer with the same dimensions
+            let colors = unsafe { target.get_row_mut(y).unwrap_unchecked() };
+            for (color, &level) in colors.iter_mut().zip(row) {
+                *color = ink.blend_multiply(Hub75Color::new(level, level, level));
+            }
+        }
+    }
+
+    /// Get the width of the frame buffer
+    pub const fn width(&self) -> usize {
+        WIDTH
+    }
+
+    /// Get the height of the frame buffer
+    pub const fn height(&self) -> usize {
+        HEIGHT
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> Default
+    for MonoFrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+mod embedded_graphics_support {
+    use super::*;
+    use embedded_graphics_core::{
+        draw_target::DrawTarget,
+        geometry::{OriginDimensions, Size},
+        pixelcolor::{Gray8, GrayColor},
+        Pixel,
+    };
+
+    impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> DrawTarget
+        for MonoFrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+    {
+        type Color = Gray8;
+        type Error = Hub75Error;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            for Pixel(coord, color) in pixels {
+                let x = coord.x as usize;
+                let y = coord.y as usize;
+                if x < WIDTH && y < HEIGHT {
+                    self.pixels[y][x] = Hub75Color::<COLOR_BITS>::gray(color.luma()).r;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> OriginDimensions
+        for MonoFrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+    {
+        fn size(&self) -> Size {
+            Size::new(WIDTH as u32, HEIGHT as u32)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mono_pixels() {
+        let mut buffer = MonoFrameBuffer::<16, 8, 4>::new();
+
+        buffer.set_pixel(2, 3, 200).unwrap();
+        assert_eq!(buffer.get_pixel(2, 3).unwrap(), 15);
+        assert!(buffer.set_pixel(16, 0, 1).is_err());
+        assert!(buffer.get_pixel(0, 8).is_err());
+
+        buffer.fill(7);
+        assert_eq!(buffer.get_pixel(15, 7).unwrap(), 7);
+        buffer.clear();
+        assert_eq!(buffer.get_pixel(15, 7).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_mono_conversion() {
+        let mut color = Hub75FrameBuffer::<16, 8, 4>::new();
+        color.set_pixel(1, 1, Hub75Color::new(3, 9, 5)).unwrap();
+
+        let mono = MonoFrameBuffer::from_frame_buffer(&color);
+        assert_eq!(mono.get_pixel(1, 1).unwrap(), 9);
+        assert_eq!(mono.get_pixel(0, 0).unwrap(), 0);
+
+        let mut target = Hub75FrameBuffer::<16, 8, 4>::new();
+        target.fill(Hub75Color::white());
+        mono.copy_to(&mut target, Hub75Color::new(15, 10, 0));
+        assert_eq!(target.get_pixel(1, 1).unwrap(), Hub75Color::new(9, 6, 0));
+        assert_eq!(target.get_pixel(0, 0).unwrap(), Hub75Color::black());
+    }
+
+    #[cfg(feature = "embedded-graphics")]
+    #[test]
+    fn test_mono_draw_target() {
+        use embedded_graphics_core::{pixelcolor::Gray8, prelude::*};
+
+        let mut buffer = MonoFrameBuffer::<16, 8, 4>::new();
+        Pixel(Point::new(4, 2), Gray8::WHITE)
+            .draw(&mut buffer)
+            .unwrap();
+        Pixel(Point::new(20, 2), Gray8::WHITE)
+            .draw(&mut buffer)
+            .unwrap();
+
+        assert_eq!(buffer.get_pixel(4, 2).unwrap(), 15);
+        assert_eq!(buffer.size(), Size::new(16, 8));
+    }
+}
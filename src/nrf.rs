@@ -0,0 +1,432 @@
+//! Hardware clock pulses on nRF52 through GPIOTE and PPI
+//!
+//! nRF52 parts have no parallel output that DMA can drive, so the panel
+//! clock is normally toggled from software. An [`NrfClock`] hands that job to
+//! the peripherals: a TIMER is started once per column, and PPI routes its
+//! compare events to GPIOTE tasks that raise and lower CLK. The CPU only
+//! writes the column's six color bits to the port and starts the timer,
+//! which roughly doubles the pixel clock compared to driving each pin
+//! through `OutputPin`.
+//!
+//! The six color pins must be on the same GPIO port so a column can be
+//! written with one `OUTSET` and one `OUTCLR`. The CLK pin is taken over by
+//! GPIOTE; the `clk` pin given to the display is held low and can be any
+//! spare pin. Address, latch and output enable stay on GPIO as usual.
+//!
+//! CLK is toggled through the GPIOTE channel's `TASKS_OUT`, which every
+//! nRF52 part has, rather than `TASKS_SET` and `TASKS_CLR`, which the
+//! nRF52832 lacks. Dropping the clock stops the timer, disables its PPI
+//! channels and hands the CLK pin back to GPIO.
+//!
+//! The peripherals are accessed through their registers directly, so the
+//! clock works next to any HAL, which must leave the chosen TIMER, GPIOTE
+//! channel and PPI channels alone. Set the color pins as outputs through
+//! the HAL before use.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::{Hub75Display, Hub75Pins};
+//! use hub75::nrf::{NrfClock, NrfClockConfig, Timer};
+//! use embedded_hal::digital::OutputPin;
+//! use embedded_hal_async::delay::DelayNs;
+//!
+//! # async fn example<P: OutputPin + 'static>(
+//! #     pins: Hub75Pins<P>,
+//! #     delay: &mut impl DelayNs,
+//! # ) -> Result<(), hub75::Hub75Error> {
+//! let config = NrfClockConfig {
+//!     timer: Timer::Timer2,
+//!     gpiote_channel: 0,
+//!     ppi_channels: [0, 1],
+//!     clk_pin: 17,
+//!     // R1, G1, B1, R2, G2, B2 on P0
+//!     data_pins: [2, 3, 4, 5, 28, 29],
+//! };
+//! // SAFETY: nothing else uses TIMER2, GPIOTE channel 0 or PPI channels 0 and 1
+//! let mut clock = unsafe { NrfClock::new(config)? };
+//! let mut display = Hub75Display::<_, 64, 32, 6>::new(pins)?;
+//!
+//! loop {
+//!     display.render_frame_nrf(&mut clock, delay).await?;
+//! }
+//! # }
+//! ```
+
+use crate::{display::RowShifter, pins::Hub75RgbPins, Hub75Error};
+use embedded_hal::digital::OutputPin;
+
+const GPIOTE_BASE: usize = 0x4000_6000;
+const PPI_BASE: usize = 0x4001_F000;
+const P0_BASE: usize = 0x5000_0000;
+const P1_BASE: usize = 0x5000_0300;
+
+const GPIOTE_TASKS_OUT: usize = 0x000;
+const GPIOTE_CONFIG: usize = 0x510;
+/// GPIOTE channel in task mode, toggling, starting low
+const GPIOTE_MODE_TASK: u32 = 3 | 3 << 16;
+
+const TIMER_TASKS_START: usize = 0x000;
+const TIMER_TASKS_STOP: usize = 0x004;
+const TIMER_TASKS_CLEAR: usize = 0x00C;
+const TIMER_EVENTS_COMPARE: usize = 0x140;
+const TIMER_SHORTS: usize = 0x200;
+const TIMER_MODE: usize = 0x504;
+const TIMER_BITMODE: usize = 0x508;
+const TIMER_PRESCALER: usize = 0x510;
+const TIMER_CC: usize = 0x540;
+/// Clear and stop the timer once the pulse has ended on COMPARE1
+const TIMER_SHORTS_COMPARE1_CLEAR_STOP: u32 = 1 << 1 | 1 << 9;
+
+const PPI_CHENSET: usize = 0x504;
+const PPI_CHENCLR: usize = 0x508;
+const PPI_CH_EEP: usize = 0x510;
+const PPI_CH_TEP: usize = 0x514;
+
+const GPIO_OUTSET: usize = 0x508;
+const GPIO_OUTCLR: usize = 0x50C;
+
+/// Timer used to time the clock pulses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Timer {
+    /// TIMER0, usually taken by the SoftDevice
+    Timer0,
+    /// TIMER1
+    Timer1,
+    /// TIMER2
+    Timer2,
+    /// TIMER3
+    Timer3,
+    /// TIMER4
+    Timer4,
+}
+
+impl Timer {
+    /// Get the base address of the timer's registers
+    const fn base(self) -> usize {
+        match self {
+            Timer::Timer0 => 0x4000_8000,
+            Timer::Timer1 => 0x4000_9000,
+            Timer::Timer2 => 0x4000_A000,
+            Timer::Timer3 => 0x4001_A000,
+            Timer::Timer4 => 0x4001_B000,
+        }
+    }
+}
+
+/// Peripherals and pins used by an [`NrfClock`]
+///
+/// Pins are numbered as in `P0.x` = `x` and `P1.x` = `32 + x`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NrfClockConfig {
+    /// Timer started once per column
+    pub timer: Timer,
+    /// GPIOTE channel driving CLK (0-7)
+    pub gpiote_channel: u8,
+    /// PPI channels raising and lowering CLK (0-19)
+    pub ppi_channels: [u8; 2],
+    /// Pin wired to the panel's CLK
+    pub clk_pin: u8,
+    /// Pins wired to R1, G1, B1, R2, G2 and B2, all on the same port
+    pub data_pins: [u8; 6],
+}
+
+/// Register blocks used by the clock
+#[derive(Debug, Clone, Copy)]
+struct Registers {
+    gpiote: usize,
+    timer: usize,
+    ppi: usize,
+    port: usize,
+}
+
+/// Clock pulses generated by a TIMER, PPI and GPIOTE
+pub struct NrfClock {
+    /// Register blocks of the peripherals
+    registers: Registers,
+    /// GPIOTE channel driving CLK
+    gpiote_channel: u8,
+    /// PPI channels raising and lowering CLK
+    ppi_channels: [u8; 2],
+    /// Port bit of each color pin
+    masks: [u32; 6],
+    /// Whether a pulse may still be in progress
+    pulsing: bool,
+}
+
+impl NrfClock {
+    /// Set up the peripherals and take over the CLK pin
+    ///
+    /// Returns [`Hub75Error::PinError`] if the color pins are on different
+    /// ports or a pin, channel or timer number is out of range.
+    ///
+    /// # Safety
+    ///
+    /// The timer, GPIOTE channel and PPI channels in `config` must not be used
+    /// by anything else for as long as the clock exists.
+    pub unsafe fn new(config: NrfClockConfig) -> Result<Self, Hub75Error> {
+        let port = config.data_pins[0] / 32;
+        let valid = config.gpiote_channel < 8
+            && config.ppi_channels.iter().all(|&channel| channel < 20)
+            && config.ppi_channels[0] != config.ppi_channels[1]
+            && config.clk_pin < 48
+            && config
+                .data_pins
+                .iter()
+                .all(|&pin| pin < 48 && pin / 32 == port);
+        if !valid {
+            return Err(Hub75Error::PinError);
+        }
+
+        let registers = Registers {
+            gpiote: GPIOTE_BASE,
+            timer: config.timer.base(),
+            ppi: PPI_BASE,
+            port: if port == 0 { P0_BASE } else { P1_BASE },
+        };
+        Ok(Self::with_registers(registers, config))
+    }
+
+    /// Set up the peripherals at the given register blocks
+    unsafe fn with_registers(registers: Registers, config: NrfClockConfig) -> Self {
+        let clock = Self {
+            registers,
+            gpiote_channel: config.gpiote_channel,
+            ppi_channels: config.ppi_channels,
+            masks: config.data_pins.map(|pin| 1 << (pin % 32)),
+            pulsing: false,
+        };
+
+        let channel = config.gpiote_channel as usize;
+        let psel = ((config.clk_pin as u32) & 31) << 8 | ((config.clk_pin as u32) >> 5) << 13;
+        clock.write(
+            registers.gpiote + GPIOTE_CONFIG + 4 * channel,
+            GPIOTE_MODE_TASK | psel,
+        );
+
+        // 16 MHz, CLK rises on the first tick and falls on the second
+        let timer = registers.timer;
+        clock.write(timer + TIMER_TASKS_STOP, 1);
+        clock.write(timer + TIMER_MODE, 0);
+        clock.write(timer + TIMER_BITMODE, 0);
+        clock.write(timer + TIMER_PRESCALER, 0);
+        clock.write(timer + TIMER_CC, 1);
+        clock.write(timer + TIMER_CC + 4, 2);
+        clock.write(timer + TIMER_SHORTS, TIMER_SHORTS_COMPARE1_CLEAR_STOP);
+        clock.write(timer + TIMER_TASKS_CLEAR, 1);
+        clock.write(timer + TIMER_EVENTS_COMPARE + 4, 0);
+
+        // Both compares toggle CLK, which starts low: up on the first, down on
+        // the second
+        let events = [TIMER_EVENTS_COMPARE, TIMER_EVENTS_COMPARE + 4];
+        for (&ppi_channel, event) in config.ppi_channels.iter().zip(events) {
+            let ppi_channel = ppi_channel as usize;
+            let ppi = registers.ppi + 8 * ppi_channel;
+            clock.write(ppi + PPI_CH_EEP, (timer + event) as u32);
+            clock.write(
+                ppi + PPI_CH_TEP,
+                (registers.gpiote + GPIOTE_TASKS_OUT + 4 * channel) as u32,
+            );
+            clock.write(registers.ppi + PPI_CHENSET, 1 << ppi_channel);
+        }
+
+        clock
+    }
+
+    /// Get the port bits set for a column
+    fn column_bits(&self, column: &(bool, bool, bool, bool, bool, bool)) -> u32 {
+        let levels = [column.0, column.1, column.2, column.3, column.4, column.5];
+        levels
+            .iter()
+            .zip(self.masks)
+            .filter(|(&level, _)| level)
+            .fold(0, |bits, (_, mask)| bits | mask)
+    }
+
+    /// Wait for the last clock pulse to end
+    fn wait_for_pulse(&mut self) {
+        if !self.pulsing {
+            return;
+        }
+        let done = self.registers.timer + TIMER_EVENTS_COMPARE + 4;
+        // SAFETY: the register belongs to the timer reserved for the clock
+        while unsafe { core::ptr::read_volatile(done as *const u32) } == 0 {
+            core::hint::spin_loop();
+        }
+        self.write(done, 0);
+        self.pulsing = false;
+    }
+
+    /// Write a register of one of the reserved peripherals
+    fn write(&self, address: usize, value: u32) {
+        // SAFETY: addresses are computed from the register blocks of the
+        // peripherals reserved when the clock was created
+        unsafe { core::ptr::write_volatile(address as *mut u32, value) }
+    }
+}
+
+impl Drop for NrfClock {
+    fn drop(&mut self) {
+        self.wait_for_pulse();
+        self.write(self.registers.timer + TIMER_TASKS_STOP, 1);
+        self.write(self.registers.timer + TIMER_SHORTS, 0);
+
+        for ppi_channel in self.ppi_channels {
+            let ppi_channel = ppi_channel as usize;
+            self.write(self.registers.ppi + PPI_CHENCLR, 1 << ppi_channel);
+            let ppi = self.registers.ppi + 8 * ppi_channel;
+            self.write(ppi + PPI_CH_EEP, 0);
+            self.write(ppi + PPI_CH_TEP, 0);
+        }
+
+        // Disabling the GPIOTE channel returns the CLK pin to GPIO
+        let channel = self.gpiote_channel as usize;
+        self.write(self.registers.gpiote + GPIOTE_CONFIG + 4 * channel, 0);
+    }
+}
+
+impl RowShifter for NrfClock {
+    fn shift_row<P: OutputPin>(
+        &mut self,
+        columns: &[(bool, bool, bool, bool, bool, bool)],
+        _rgb: &mut Hub75RgbPins<P>,
+    ) -> Result<(), Hub75Error> {
+        let all = self.masks.iter().fold(0, |bits, mask| bits | mask);
+        for column in columns {
+            let bits = self.column_bits(column);
+            self.wait_for_pulse();
+            self.write(self.registers.port + GPIO_OUTCLR, all & !bits);
+            self.write(self.registers.port + GPIO_OUTSET, bits);
+            self.write(self.registers.timer + TIMER_TASKS_START, 1);
+            self.pulsing = true;
+        }
+        self.wait_for_pulse();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waveform::WaveformRecorder;
+
+    /// Memory standing in for the register blocks, only accessed through
+    /// raw pointers once the clock has been created
+    struct FakeRegisters {
+        memory: std::vec::Vec<u32>,
+    }
+
+    impl FakeRegisters {
+        fn new() -> Self {
+            Self {
+                memory: std::vec![0; 4 * 0x200],
+            }
+        }
+
+        fn registers(&mut self) -> Registers {
+            let base = self.memory.as_mut_ptr() as usize;
+            Registers {
+                gpiote: base,
+                timer: base + 0x800,
+                ppi: base + 0x1000,
+                port: base + 0x1800,
+            }
+        }
+    }
+
+    fn peek(address: usize) -> u32 {
+        unsafe { core::ptr::read_volatile(address as *const u32) }
+    }
+
+    fn poke(address: usize, value: u32) {
+        unsafe { core::ptr::write_volatile(address as *mut u32, value) }
+    }
+
+    const CONFIG: NrfClockConfig = NrfClockConfig {
+        timer: Timer::Timer2,
+        gpiote_channel: 3,
+        ppi_channels: [4, 7],
+        clk_pin: 32 + 9,
+        data_pins: [2, 3, 4, 5, 28, 29],
+    };
+
+    #[test]
+    fn test_setup() {
+        let mut fake = FakeRegisters::new();
+        let registers = fake.registers();
+        let _clock = unsafe { NrfClock::with_registers(registers, CONFIG) };
+
+        assert_eq!(
+            peek(registers.gpiote + GPIOTE_CONFIG + 12),
+            GPIOTE_MODE_TASK | 9 << 8 | 1 << 13
+        );
+        assert_eq!(peek(registers.timer + TIMER_CC), 1);
+        assert_eq!(peek(registers.timer + TIMER_CC + 4), 2);
+        assert_eq!(
+            peek(registers.ppi + PPI_CH_EEP + 8 * 7),
+            (registers.timer + TIMER_EVENTS_COMPARE + 4) as u32
+        );
+        assert_eq!(
+            peek(registers.ppi + PPI_CH_TEP + 8 * 4),
+            (registers.gpiote + GPIOTE_TASKS_OUT + 12) as u32
+        );
+        assert_eq!(peek(registers.ppi + PPI_CHENSET), 1 << 7);
+    }
+
+    #[test]
+    fn test_drop_releases_channels() {
+        let mut fake = FakeRegisters::new();
+        let registers = fake.registers();
+        let clock = unsafe { NrfClock::with_registers(registers, CONFIG) };
+        drop(clock);
+
+        assert_eq!(peek(registers.gpiote + GPIOTE_CONFIG + 12), 0);
+        assert_eq!(peek(registers.timer + TIMER_TASKS_STOP), 1);
+        assert_eq!(peek(registers.ppi + PPI_CHENCLR), 1 << 7);
+        assert_eq!(peek(registers.ppi + PPI_CH_EEP + 8 * 4), 0);
+        assert_eq!(peek(registers.ppi + PPI_CH_TEP + 8 * 7), 0);
+    }
+
+    #[test]
+    fn test_shift_column() {
+        let mut fake = FakeRegisters::new();
+        let registers = fake.registers();
+        let mut clock = unsafe { NrfClock::with_registers(registers, CONFIG) };
+        let mut pins = WaveformRecorder::new().pins(3);
+
+        // The fake timer finishes its pulse at once
+        poke(registers.timer + TIMER_EVENTS_COMPARE + 4, 1);
+        let column = (true, false, false, false, true, false);
+        clock.shift_row(&[column], &mut pins.rgb).unwrap();
+
+        assert_eq!(peek(registers.port + GPIO_OUTSET), 1 << 2 | 1 << 28);
+        assert_eq!(
+            peek(registers.port + GPIO_OUTCLR),
+            1 << 3 | 1 << 4 | 1 << 5 | 1 << 29
+        );
+        assert_eq!(peek(registers.timer + TIMER_TASKS_START), 1);
+        assert_eq!(peek(registers.timer + TIMER_EVENTS_COMPARE + 4), 0);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let mixed_ports = NrfClockConfig {
+            data_pins: [2, 3, 4, 5, 28, 40],
+            ..CONFIG
+        };
+        assert!(matches!(
+            unsafe { NrfClock::new(mixed_ports) },
+            Err(Hub75Error::PinError)
+        ));
+        let shared_ppi = NrfClockConfig {
+            ppi_channels: [4, 4],
+            ..CONFIG
+        };
+        assert!(matches!(
+            unsafe { NrfClock::new(shared_ppi) },
+            Err(Hub75Error::PinError)
+        ));
+    }
+}
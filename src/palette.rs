@@ -0,0 +1,205 @@
+//! Fixed color palettes and palette quantization
+//!
+//! Animation assets stored as raw RGB888 take 3 bytes per pixel. Mapping them
+//! to a small fixed palette shrinks each pixel to a single index byte, a third
+//! of the size, at the cost of limiting every frame to the palette colors.
+//! Optional ordered dithering keeps gradients smooth with few colors.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::palette::Palette;
+//!
+//! const PALETTE: Palette<4> = Palette::new([0x000000, 0xFF0000, 0x00FF00, 0xFFFFFF]);
+//!
+//! // A 2x1 image: dark red and near-white
+//! let rgb = [200, 10, 0, 240, 240, 250];
+//! let mut indices = [0u8; 2];
+//! PALETTE.quantize_rgb_data::<2>(&rgb, &mut indices, false).unwrap();
+//! assert_eq!(indices, [1, 3]);
+//!
+//! let mut frame = Hub75FrameBuffer::<2, 1, 6>::new();
+//! frame.draw_indexed(&indices, &PALETTE).unwrap();
+//! assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::white());
+//! ```
+
+use crate::{
+    color::{Hub75Color, BAYER_4X4},
+    Hub75Error,
+};
+
+/// Strength of ordered dithering, in 8-bit levels (peak to peak)
+const DITHER_SPREAD: i32 = 64;
+
+/// A fixed set of up to 256 colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette<const N: usize> {
+    /// Palette colors as 8-bit RGB
+    colors: [[u8; 3]; N],
+}
+
+impl<const N: usize> Palette<N> {
+    /// Create a palette from packed `0xRRGGBB` colors
+    ///
+    /// Fails to compile if the palette is empty or has more than 256 colors.
+    pub const fn new(colors: [u32; N]) -> Self {
+        assert!(N >= 1 && N <= 256, "palettes hold between 1 and 256 colors");
+
+        let mut rgb = [[0u8; 3]; N];
+        let mut i = 0;
+        while i < N {
+            let color = colors[i];
+            rgb[i] = [(color >> 16) as u8, (color >> 8) as u8, color as u8];
+            i += 1;
+        }
+        Self { colors: rgb }
+    }
+
+    /// Get the number of colors in the palette
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Check whether the palette is empty (never true for a valid palette)
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Get a palette color as 8-bit RGB values
+    pub fn rgb8(&self, index: u8) -> Option<(u8, u8, u8)> {
+        self.colors.get(index as usize).map(|&[r, g, b]| (r, g, b))
+    }
+
+    /// Get a palette color at the given bit depth
+    pub fn color<const BITS: usize>(&self, index: u8) -> Option<Hub75Color<BITS>> {
+        self.rgb8(index)
+            .map(|(r, g, b)| Hub75Color::from_rgb8(r, g, b))
+    }
+
+    /// Find the index of the palette color closest to an 8-bit RGB value
+    ///
+    /// Distance is measured in RGB space, weighted toward green to roughly
+    /// follow perceived brightness.
+    pub fn nearest(&self, r: u8, g: u8, b: u8) -> u8 {
+        self.nearest_signed(r as i32, g as i32, b as i32)
+    }
+
+    /// Find the closest palette color with ordered dithering
+    ///
+    /// The pixel position selects an offset from a 4x4 Bayer matrix, so flat
+    /// areas between two palette colors become a pattern of both.
+    pub fn nearest_dithered(&self, r: u8, g: u8, b: u8, x: usize, y: usize) -> u8 {
+        let threshold = BAYER_4X4[y & 3][x & 3] as i32;
+        let offset = (2 * threshold + 1) * DITHER_SPREAD / 32 - DITHER_SPREAD / 2;
+        self.nearest_signed(r as i32 + offset, g as i32 + offset, b as i32 + offset)
+    }
+
+    fn nearest_signed(&self, r: i32, g: i32, b: i32) -> u8 {
+        let mut best = 0;
+        let mut best_distance = i32::MAX;
+        for (index, &[pr, pg, pb]) in self.colors.iter().enumerate() {
+            let (dr, dg, db) = (r - pr as i32, g - pg as i32, b - pb as i32);
+            let distance = 2 * dr * dr + 4 * dg * dg + 3 * db * db;
+            if distance < best_distance {
+                best = index;
+                best_distance = distance;
+            }
+        }
+        best as u8
+    }
+
+    /// Quantize raw RGB888 data into palette indices
+    ///
+    /// `WIDTH` is the image width, used to position the dither pattern.
+    /// Returns `Hub75Error::InvalidColor` if the palette is empty or `data` is
+    /// not exactly three bytes per entry of `indices`, and
+    /// `Hub75Error::InvalidCoordinates` if `WIDTH` is zero.
+    pub fn quantize_rgb_data<const WIDTH: usize>(
+        &self,
+        data: &[u8],
+        indices: &mut [u8],
+        dithering: bool,
+    ) -> Result<(), Hub75Error> {
+        if self.is_empty() || data.len() != indices.len() * 3 {
+            return Err(Hub75Error::InvalidColor);
+        }
+        if WIDTH == 0 {
+            return Err(Hub75Error::InvalidCoordinates);
+        }
+
+        for (i, (rgb, index)) in data.chunks_exact(3).zip(indices.iter_mut()).enumerate() {
+            let (x, y) = (i % WIDTH, i / WIDTH);
+            *index = if dithering {
+                self.nearest_dithered(rgb[0], rgb[1], rgb[2], x, y)
+            } else {
+                self.nearest(rgb[0], rgb[1], rgb[2])
+            };
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame_buffer::Hub75FrameBuffer;
+
+    const GRAYS: Palette<3> = Palette::new([0x000000, 0x808080, 0xFFFFFF]);
+
+    #[test]
+    fn test_palette_lookup() {
+        assert_eq!(GRAYS.len(), 3);
+        assert_eq!(GRAYS.rgb8(1), Some((128, 128, 128)));
+        assert_eq!(GRAYS.rgb8(3), None);
+        assert_eq!(GRAYS.color::<6>(2), Some(Hub75Color::white()));
+
+        assert_eq!(GRAYS.nearest(10, 0, 20), 0);
+        assert_eq!(GRAYS.nearest(150, 120, 100), 1);
+        assert_eq!(GRAYS.nearest(250, 255, 230), 2);
+    }
+
+    #[test]
+    fn test_quantize_dithered() {
+        // A flat color halfway between black and mid-gray
+        let data = [64u8; 4 * 4 * 3];
+        let mut indices = [0u8; 16];
+
+        GRAYS
+            .quantize_rgb_data::<4>(&data, &mut indices, false)
+            .unwrap();
+        assert!(indices.iter().all(|&index| index == indices[0]));
+
+        GRAYS
+            .quantize_rgb_data::<4>(&data, &mut indices, true)
+            .unwrap();
+        let mid = indices.iter().filter(|&&index| index == 1).count();
+        assert_eq!(
+            indices.iter().filter(|&&index| index == 0).count(),
+            16 - mid
+        );
+        assert_eq!(mid, 8);
+
+        assert!(GRAYS
+            .quantize_rgb_data::<4>(&data[1..], &mut indices, true)
+            .is_err());
+        assert_eq!(
+            GRAYS.quantize_rgb_data::<0>(&data, &mut indices, true),
+            Err(Hub75Error::InvalidCoordinates)
+        );
+    }
+
+    #[test]
+    fn test_draw_indexed() {
+        let mut frame = Hub75FrameBuffer::<2, 2, 6>::new();
+
+        frame.draw_indexed(&[0, 1, 2, 2], &GRAYS).unwrap();
+        assert_eq!(frame.get_pixel(1, 0).unwrap(), Hub75Color::new(32, 32, 32));
+        assert_eq!(frame.get_pixel(0, 1).unwrap(), Hub75Color::white());
+
+        // A bad index leaves the frame untouched
+        assert!(frame.draw_indexed(&[1, 1, 1, 3], &GRAYS).is_err());
+        assert_eq!(frame.get_pixel(0, 0).unwrap(), Hub75Color::black());
+        assert!(frame.draw_indexed(&[0, 1, 2], &GRAYS).is_err());
+    }
+}
@@ -40,9 +40,39 @@
 //! };
 //! # }
 //! ```
+//!
+//! The RGB, address and control groups can each use a different pin type,
+//! for example when the control pins go through a level shifter driven by
+//! another peripheral:
+//!
+//! ```rust,no_run
+//! use hub75::{Hub75Pins, Hub75RgbPins, Hub75AddressPins, Hub75ControlPins};
+//! use embedded_hal::digital::OutputPin;
+//!
+//! # fn example(gpio: impl OutputPin + Clone, other: impl OutputPin + Clone) {
+//! let pins = Hub75Pins {
+//!     rgb: Hub75RgbPins {
+//!         r1: gpio.clone(), g1: gpio.clone(), b1: gpio.clone(),
+//!         r2: gpio.clone(), g2: gpio.clone(), b2: gpio.clone(),
+//!     },
+//!     address: Hub75AddressPins {
+//!         a: gpio.clone(), b: gpio.clone(), c: gpio.clone(),
+//!         d: Some(gpio), e: None,
+//!     },
+//!     control: Hub75ControlPins {
+//!         clk: other.clone(), lat: other.clone(), oe: other,
+//!     },
+//! };
+//! # }
+//! ```
+//!
+//! To catch a panel with more scan rows than the address pins can select at
+//! compile time, build the address pins as an [`AddressPins`], which carries
+//! the pin count in its type, and create the display with
+//! [`Hub75Display::new_checked`](crate::Hub75Display::new_checked).
 
-use crate::{pin_op, Hub75Error};
-use embedded_hal::digital::OutputPin;
+use crate::{pin_op, Hub75Error, PinConfigError};
+use embedded_hal::digital::{Error, ErrorKind, ErrorType, OutputPin};
 
 /// Complete pin configuration for a HUB75 display
 ///
@@ -57,13 +87,22 @@ use embedded_hal::digital::OutputPin;
 /// - **3 control pins**: CLK, LAT, OE (always required)
 ///
 /// Total: 12-16 pins depending on panel size
-pub struct Hub75Pins<P: OutputPin> {
+///
+/// # Pin Types
+///
+/// Each group has its own pin type: `P` for the RGB pins, `A` for the address
+/// pins and `C` for the control pins, with the address and control pins
+/// defaulting to `P`. Pins within a group share a type; on HALs where every
+/// pin has its own type, use the HAL's type-erased pin (usually from
+/// `degrade()` or `into()`), and combine two pin types in one group with
+/// [`EitherPin`].
+pub struct Hub75Pins<P: OutputPin, A: OutputPin = P, C: OutputPin = P> {
     /// RGB pins for upper and lower halves
     pub rgb: Hub75RgbPins<P>,
     /// Address pins for row selection
-    pub address: Hub75AddressPins<P>,
+    pub address: Hub75AddressPins<A>,
     /// Control pins for timing and latching
-    pub control: Hub75ControlPins<P>,
+    pub control: Hub75ControlPins<C>,
 }
 
 /// RGB data pins for HUB75 interface
@@ -96,6 +135,91 @@ pub struct Hub75AddressPins<P: OutputPin> {
     pub e: Option<P>,
 }
 
+/// Address pins with their count in the type
+///
+/// Created with [`abc`](AddressPins::abc), [`abcd`](AddressPins::abcd) or
+/// [`abcde`](AddressPins::abcde), so `N` is always the number of pins held.
+/// [`Hub75Display::new_checked`](crate::Hub75Display::new_checked) uses it to
+/// reject a panel with more scan rows than the pins can select at compile
+/// time, where [`Hub75Display::new`](crate::Hub75Display::new) returns
+/// [`Hub75Error::InvalidCoordinates`] at runtime.
+pub struct AddressPins<P: OutputPin, const N: usize> {
+    /// The pins, with D and E present as `N` requires
+    pins: Hub75AddressPins<P>,
+}
+
+impl<P: OutputPin> AddressPins<P, 3> {
+    /// Address pins A, B and C, selecting up to 8 scan rows
+    pub fn abc(a: P, b: P, c: P) -> Self {
+        Self {
+            pins: Hub75AddressPins {
+                a,
+                b,
+                c,
+                d: None,
+                e: None,
+            },
+        }
+    }
+}
+
+impl<P: OutputPin> AddressPins<P, 4> {
+    /// Address pins A to D, selecting up to 16 scan rows
+    pub fn abcd(a: P, b: P, c: P, d: P) -> Self {
+        Self {
+            pins: Hub75AddressPins {
+                a,
+                b,
+                c,
+                d: Some(d),
+                e: None,
+            },
+        }
+    }
+}
+
+impl<P: OutputPin> AddressPins<P, 5> {
+    /// Address pins A to E, selecting up to 32 scan rows
+    pub fn abcde(a: P, b: P, c: P, d: P, e: P) -> Self {
+        Self {
+            pins: Hub75AddressPins {
+                a,
+                b,
+                c,
+                d: Some(d),
+                e: Some(e),
+            },
+        }
+    }
+}
+
+impl<P: OutputPin, const N: usize> AddressPins<P, N> {
+    /// Number of scan rows the pins can select
+    pub const ROWS: usize = 1 << N;
+
+    /// Return the pins without their count in the type
+    pub fn into_inner(self) -> Hub75AddressPins<P> {
+        self.pins
+    }
+}
+
+impl<P: OutputPin, const N: usize> From<AddressPins<P, N>> for Hub75AddressPins<P> {
+    fn from(pins: AddressPins<P, N>) -> Self {
+        pins.into_inner()
+    }
+}
+
+/// Fails to compile when `ADDRESS_PINS` pins cannot select every scan row of
+/// a panel `HEIGHT` pixels high
+pub(crate) struct AddressCapacity<const ADDRESS_PINS: usize, const HEIGHT: usize>;
+
+impl<const ADDRESS_PINS: usize, const HEIGHT: usize> AddressCapacity<ADDRESS_PINS, HEIGHT> {
+    pub(crate) const CHECK: () = assert!(
+        HEIGHT / 2 <= 1 << ADDRESS_PINS,
+        "not enough address pins to select every scan row, HEIGHT / 2 must be at most 2^N"
+    );
+}
+
 /// Control pins for timing and data latching
 pub struct Hub75ControlPins<P: OutputPin> {
     /// Clock pin for shifting data
@@ -107,23 +231,16 @@ pub struct Hub75ControlPins<P: OutputPin> {
 }
 
 /// Builder for constructing Hub75Pins with a fluent interface
-pub struct Hub75PinsBuilder<P: OutputPin> {
+#[allow(clippy::type_complexity)]
+pub struct Hub75PinsBuilder<P: OutputPin, A: OutputPin = P, C: OutputPin = P> {
     rgb: Option<(P, P, P, P, P, P)>,
-    address: Option<(P, P, P, Option<P>, Option<P>)>,
-    control: Option<(P, P, P)>,
+    address: Option<(A, A, A, Option<A>, Option<A>)>,
+    control: Option<(C, C, C)>,
 }
 
 impl<P: OutputPin> Hub75Pins<P> {
-    /// Create a builder for constructing pin configuration
-    pub fn builder() -> Hub75PinsBuilder<P> {
-        Hub75PinsBuilder {
-            rgb: None,
-            address: None,
-            control: None,
-        }
-    }
-
     /// Create a new pin configuration for a standard HUB75 display
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         r1: P,
         g1: P,
@@ -155,6 +272,7 @@ impl<P: OutputPin> Hub75Pins<P> {
     }
 
     /// Create pin configuration for 32x16 display (3 address pins)
+    #[allow(clippy::too_many_arguments)]
     pub fn new_32x16(
         r1: P,
         g1: P,
@@ -173,6 +291,7 @@ impl<P: OutputPin> Hub75Pins<P> {
     }
 
     /// Create pin configuration for 64x32 display (4 address pins)
+    #[allow(clippy::too_many_arguments)]
     pub fn new_64x32(
         r1: P,
         g1: P,
@@ -192,6 +311,7 @@ impl<P: OutputPin> Hub75Pins<P> {
     }
 
     /// Create pin configuration for 64x64 display (5 address pins)
+    #[allow(clippy::too_many_arguments)]
     pub fn new_64x64(
         r1: P,
         g1: P,
@@ -225,6 +345,17 @@ impl<P: OutputPin> Hub75Pins<P> {
             oe,
         )
     }
+}
+
+impl<P: OutputPin, A: OutputPin, C: OutputPin> Hub75Pins<P, A, C> {
+    /// Create a builder for constructing pin configuration
+    pub fn builder() -> Hub75PinsBuilder<P, A, C> {
+        Hub75PinsBuilder {
+            rgb: None,
+            address: None,
+            control: None,
+        }
+    }
 
     /// Initialize all pins to their default states
     pub fn init(&mut self) -> Result<(), Hub75Error> {
@@ -273,7 +404,7 @@ impl<P: OutputPin> Hub75Pins<P> {
     }
 }
 
-impl<P: OutputPin> Hub75PinsBuilder<P> {
+impl<P: OutputPin, A: OutputPin, C: OutputPin> Hub75PinsBuilder<P, A, C> {
     /// Set RGB pins for upper and lower halves
     pub fn rgb(mut self, r1: P, g1: P, b1: P, r2: P, g2: P, b2: P) -> Self {
         self.rgb = Some((r1, g1, b1, r2, g2, b2));
@@ -281,30 +412,91 @@ impl<P: OutputPin> Hub75PinsBuilder<P> {
     }
 
     /// Set address pins (A, B, C are required)
-    pub fn address(mut self, a: P, b: P, c: P) -> Self {
+    pub fn address(mut self, a: A, b: A, c: A) -> Self {
         self.address = Some((a, b, c, None, None));
         self
     }
 
     /// Set address pins with optional D and E pins
-    pub fn address_with_optional(mut self, a: P, b: P, c: P, d: Option<P>, e: Option<P>) -> Self {
+    pub fn address_with_optional(mut self, a: A, b: A, c: A, d: Option<A>, e: Option<A>) -> Self {
         self.address = Some((a, b, c, d, e));
         self
     }
 
     /// Set control pins
-    pub fn control(mut self, clk: P, lat: P, oe: P) -> Self {
+    pub fn control(mut self, clk: C, lat: C, oe: C) -> Self {
         self.control = Some((clk, lat, oe));
         self
     }
 
+    /// Build the pin configuration for a `WIDTH` x `HEIGHT` panel
+    ///
+    /// Unlike [`build`](Self::build), checks that the address pins can select
+    /// every row pair of the panel and reports every missing group at once.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hub75::{Hub75Pins, PinConfigError};
+    /// use embedded_hal::digital::OutputPin;
+    ///
+    /// # fn example(pin: impl OutputPin + Clone) {
+    /// // Three address pins only reach 8 row pairs, a 64x32 panel has 16
+    /// let result = Hub75Pins::builder()
+    ///     .rgb(pin.clone(), pin.clone(), pin.clone(), pin.clone(), pin.clone(), pin.clone())
+    ///     .address(pin.clone(), pin.clone(), pin.clone())
+    ///     .control(pin.clone(), pin.clone(), pin)
+    ///     .build_for::<64, 32>();
+    /// assert!(matches!(
+    ///     result,
+    ///     Err(PinConfigError::NotEnoughAddressPins { required: 4, provided: 3 })
+    /// ));
+    /// # }
+    /// ```
+    pub fn build_for<const WIDTH: usize, const HEIGHT: usize>(
+        self,
+    ) -> Result<Hub75Pins<P, A, C>, PinConfigError> {
+        if WIDTH == 0 || HEIGHT == 0 || HEIGHT % 2 != 0 {
+            return Err(PinConfigError::InvalidGeometry);
+        }
+        let missing = PinConfigError::Missing {
+            rgb: self.rgb.is_none(),
+            address: self.address.is_none(),
+            control: self.control.is_none(),
+        };
+        let (Some(rgb), Some(address), Some(control)) = (self.rgb, self.address, self.control)
+        else {
+            return Err(missing);
+        };
+        if let (_, _, _, None, Some(_)) = address {
+            return Err(PinConfigError::AddressGap);
+        }
+
+        let pins = Self::assemble(rgb, address, control);
+        let required = required_address_pins(HEIGHT / 2);
+        let provided = pins.address_pin_count();
+        if provided < required {
+            return Err(PinConfigError::NotEnoughAddressPins { required, provided });
+        }
+        Ok(pins)
+    }
+
     /// Build the Hub75Pins configuration
-    pub fn build(self) -> Result<Hub75Pins<P>, Hub75Error> {
+    pub fn build(self) -> Result<Hub75Pins<P, A, C>, Hub75Error> {
         let rgb = self.rgb.ok_or(Hub75Error::InvalidCoordinates)?;
         let address = self.address.ok_or(Hub75Error::InvalidCoordinates)?;
         let control = self.control.ok_or(Hub75Error::InvalidCoordinates)?;
+        Ok(Self::assemble(rgb, address, control))
+    }
 
-        Ok(Hub75Pins {
+    /// Put the pin groups together
+    #[allow(clippy::type_complexity)]
+    fn assemble(
+        rgb: (P, P, P, P, P, P),
+        address: (A, A, A, Option<A>, Option<A>),
+        control: (C, C, C),
+    ) -> Hub75Pins<P, A, C> {
+        Hub75Pins {
             rgb: Hub75RgbPins {
                 r1: rgb.0,
                 g1: rgb.1,
@@ -325,8 +517,19 @@ impl<P: OutputPin> Hub75PinsBuilder<P> {
                 lat: control.1,
                 oe: control.2,
             },
-        })
+        }
+    }
+}
+
+/// Get the number of address pins needed to select `rows` row pairs
+///
+/// Panels always have the A, B and C pins, so at least 3 are needed.
+pub const fn required_address_pins(rows: usize) -> usize {
+    let mut pins = 3;
+    while (1 << pins) < rows {
+        pins += 1;
     }
+    pins
 }
 
 impl<P: OutputPin> Hub75RgbPins<P> {
@@ -426,3 +629,148 @@ impl<P: OutputPin> Hub75ControlPins<P> {
         Ok(())
     }
 }
+
+/// Pin of one of two types, for mixing pin types within a group
+///
+/// Useful when one pin of a group is wired differently from the rest, such as
+/// an address pin on an I/O expander next to MCU pins. Errors from either
+/// side are reported as their [`ErrorKind`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use hub75::pins::{EitherPin, Hub75AddressPins};
+/// use embedded_hal::digital::OutputPin;
+///
+/// # fn example(mcu: impl OutputPin + Clone, expander: impl OutputPin + Clone) {
+/// let address = Hub75AddressPins {
+///     a: EitherPin::Left(mcu.clone()),
+///     b: EitherPin::Left(mcu.clone()),
+///     c: EitherPin::Left(mcu.clone()),
+///     d: Some(EitherPin::Left(mcu)),
+///     e: Some(EitherPin::Right(expander)),
+/// };
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EitherPin<L, R> {
+    /// Pin of the first type
+    Left(L),
+    /// Pin of the second type
+    Right(R),
+}
+
+impl<L: OutputPin, R: OutputPin> ErrorType for EitherPin<L, R> {
+    type Error = ErrorKind;
+}
+
+impl<L: OutputPin, R: OutputPin> OutputPin for EitherPin<L, R> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        match self {
+            EitherPin::Left(pin) => pin.set_low().map_err(|e| e.kind()),
+            EitherPin::Right(pin) => pin.set_low().map_err(|e| e.kind()),
+        }
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        match self {
+            EitherPin::Left(pin) => pin.set_high().map_err(|e| e.kind()),
+            EitherPin::Right(pin) => pin.set_high().map_err(|e| e.kind()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waveform::{RecordingPin, Signal, WaveformRecorder};
+
+    fn builder(recorder: &WaveformRecorder) -> Hub75PinsBuilder<RecordingPin> {
+        let pin = |signal| recorder.pin(signal);
+        Hub75Pins::builder().rgb(
+            pin(Signal::R1),
+            pin(Signal::G1),
+            pin(Signal::B1),
+            pin(Signal::R2),
+            pin(Signal::G2),
+            pin(Signal::B2),
+        )
+    }
+
+    #[test]
+    fn test_required_address_pins() {
+        assert_eq!(required_address_pins(1), 3);
+        assert_eq!(required_address_pins(8), 3);
+        assert_eq!(required_address_pins(16), 4);
+        assert_eq!(required_address_pins(32), 5);
+        assert_eq!(required_address_pins(33), 6);
+    }
+
+    #[test]
+    fn test_build_for() {
+        let recorder = WaveformRecorder::new();
+        let pin = |signal| recorder.pin(signal);
+
+        assert!(matches!(
+            builder(&recorder).build_for::<64, 32>(),
+            Err(PinConfigError::Missing {
+                rgb: false,
+                address: true,
+                control: true
+            })
+        ));
+        assert!(matches!(
+            builder(&recorder).build_for::<64, 31>(),
+            Err(PinConfigError::InvalidGeometry)
+        ));
+
+        let with_address = |d: Option<Signal>, e: Option<Signal>| {
+            builder(&recorder)
+                .address_with_optional(
+                    pin(Signal::A),
+                    pin(Signal::B),
+                    pin(Signal::C),
+                    d.map(pin),
+                    e.map(pin),
+                )
+                .control(pin(Signal::Clk), pin(Signal::Lat), pin(Signal::Oe))
+        };
+        assert!(matches!(
+            with_address(None, Some(Signal::E)).build_for::<64, 32>(),
+            Err(PinConfigError::AddressGap)
+        ));
+        assert!(matches!(
+            with_address(Some(Signal::D), None).build_for::<64, 64>(),
+            Err(PinConfigError::NotEnoughAddressPins {
+                required: 5,
+                provided: 4
+            })
+        ));
+        let pins = with_address(Some(Signal::D), None)
+            .build_for::<64, 32>()
+            .unwrap();
+        assert_eq!(pins.max_addressable_rows(), 16);
+    }
+
+    #[test]
+    fn test_address_pins() {
+        let recorder = WaveformRecorder::new();
+        let pin = |signal| recorder.pin(signal);
+
+        let abc = AddressPins::abc(pin(Signal::A), pin(Signal::B), pin(Signal::C));
+        assert_eq!(AddressPins::<RecordingPin, 3>::ROWS, 8);
+        let abc = abc.into_inner();
+        assert!(abc.d.is_none() && abc.e.is_none());
+
+        let abcde: Hub75AddressPins<_> = AddressPins::abcde(
+            pin(Signal::A),
+            pin(Signal::B),
+            pin(Signal::C),
+            pin(Signal::D),
+            pin(Signal::E),
+        )
+        .into();
+        assert!(abcde.d.is_some() && abcde.e.is_some());
+        assert_eq!(AddressPins::<RecordingPin, 5>::ROWS, 32);
+    }
+}
@@ -0,0 +1,115 @@
+//! Minimal PNG encoding for screenshots and simulated panels
+//!
+//! Images are written uncompressed, as stored deflate blocks, which keeps the
+//! encoder tiny at the cost of file size.
+
+use std::{
+    io::{self, Write},
+    vec::Vec,
+};
+
+/// Write RGB888 LEDs as a PNG, scaled up with a dark gap between LEDs
+pub(crate) fn write_leds_png(
+    writer: impl Write,
+    width: usize,
+    height: usize,
+    leds: &[u8],
+    scale: usize,
+) -> io::Result<()> {
+    let scale = scale.max(1);
+    // A one pixel gap between LEDs once they are large enough to show it
+    let lit = if scale >= 3 { scale - 1 } else { scale };
+    let (image_width, image_height) = (width * scale, height * scale);
+
+    let mut scanlines = Vec::with_capacity((image_width * 3 + 1) * image_height);
+    for y in 0..image_height {
+        // Filter type: none
+        scanlines.push(0);
+        for x in 0..image_width {
+            let index = ((y / scale) * width + x / scale) * 3;
+            if x % scale < lit && y % scale < lit {
+                scanlines.extend_from_slice(&leds[index..index + 3]);
+            } else {
+                scanlines.extend_from_slice(&[0, 0, 0]);
+            }
+        }
+    }
+    write_png(writer, image_width as u32, image_height as u32, &scanlines)
+}
+
+/// Write filtered RGB888 scanlines as an uncompressed PNG
+fn write_png(mut writer: impl Write, width: u32, height: u32, scanlines: &[u8]) -> io::Result<()> {
+    writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+    let mut header = [0; 13];
+    header[..4].copy_from_slice(&width.to_be_bytes());
+    header[4..8].copy_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, truecolor, default compression, filtering and no interlace
+    header[8..10].copy_from_slice(&[8, 2]);
+    write_chunk(&mut writer, b"IHDR", &header)?;
+
+    // A zlib stream of stored deflate blocks
+    let mut data = Vec::with_capacity(scanlines.len() + scanlines.len() / 65535 * 5 + 11);
+    data.extend_from_slice(&[0x78, 0x01]);
+    let mut blocks = scanlines.chunks(65535).peekable();
+    if blocks.peek().is_none() {
+        data.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let length = block.len() as u16;
+        data.push(blocks.peek().is_none() as u8);
+        data.extend_from_slice(&length.to_le_bytes());
+        data.extend_from_slice(&(!length).to_le_bytes());
+        data.extend_from_slice(block);
+    }
+    data.extend_from_slice(&adler32(scanlines).to_be_bytes());
+    write_chunk(&mut writer, b"IDAT", &data)?;
+
+    write_chunk(&mut writer, b"IEND", &[])?;
+    writer.flush()
+}
+
+/// Write a PNG chunk with its length and checksum
+fn write_chunk(writer: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    let crc = crc32(crc32(!0, kind), data);
+    writer.write_all(&(!crc).to_be_bytes())
+}
+
+/// Continue a CRC-32 (ISO-HDLC) over `data`, without the final inversion
+fn crc32(mut crc: u32, data: &[u8]) -> u32 {
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// Adler-32 checksum of `data`
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(!crc32(!0, b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+}
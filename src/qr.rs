@@ -0,0 +1,756 @@
+//! QR code encoding and rendering
+//!
+//! [`QrCode::encode`] turns up to a few hundred bytes, such as a Wi-Fi
+//! onboarding string or a ticket URL, into a QR code without allocating.
+//! [`QrCode::draw`] then scales it by a whole number of pixels per module and
+//! centers it in a region of the frame, keeping as much of the quiet zone
+//! around it as fits.
+//!
+//! Codes use byte mode and go up to version 10 (57 by 57 modules), the
+//! largest that fits a 64 pixel high panel. On an LED panel the lit pixels
+//! are the light modules, so draw codes with a bright `light` color on a
+//! black `dark` color; a light-on-dark code does not scan.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::qr::{ErrorCorrection, QrCode};
+//! use embedded_graphics_core::{geometry::{Point, Size}, primitives::Rectangle};
+//!
+//! let code = QrCode::encode(b"WIFI:T:WPA;S:Lobby;P:hunter22;;", ErrorCorrection::Medium).unwrap();
+//! assert_eq!(code.version(), 3);
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! let area = Rectangle::new(Point::new(32, 0), Size::new(32, 32));
+//! code.draw(&mut frame, area, Hub75Color::black(), Hub75Color::white()).unwrap();
+//! ```
+
+use embedded_graphics_core::primitives::Rectangle;
+
+use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer, Hub75Error};
+
+/// Largest supported version
+pub const MAX_VERSION: u8 = 10;
+
+/// Modules per side of the largest supported version
+const MAX_SIZE: usize = 17 + 4 * MAX_VERSION as usize;
+
+/// Codewords in the largest supported version
+const MAX_CODEWORDS: usize = 346;
+
+/// Error correction codewords per block, by level and version
+const ECC_CODEWORDS_PER_BLOCK: [[u8; MAX_VERSION as usize + 1]; 4] = [
+    [0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18],
+    [0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26],
+    [0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24],
+    [0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28],
+];
+
+/// Error correction blocks, by level and version
+const ERROR_CORRECTION_BLOCKS: [[u8; MAX_VERSION as usize + 1]; 4] = [
+    [0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4],
+    [0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5],
+    [0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8],
+    [0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8],
+];
+
+/// Most error correction blocks of any supported version
+const MAX_BLOCKS: usize = 8;
+
+/// Most error correction codewords per block of any supported version
+const MAX_BLOCK_ECC: usize = 30;
+
+/// Quiet zone around a code called for by the standard, in modules
+const QUIET_ZONE: usize = 4;
+
+/// Share of the code that can be damaged and still read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ErrorCorrection {
+    /// About 7% recoverable
+    Low,
+    /// About 15% recoverable
+    #[default]
+    Medium,
+    /// About 25% recoverable
+    Quartile,
+    /// About 30% recoverable
+    High,
+}
+
+impl ErrorCorrection {
+    /// Row of the block tables
+    const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Two bits identifying the level in the format information
+    const fn format_bits(self) -> u32 {
+        match self {
+            ErrorCorrection::Low => 1,
+            ErrorCorrection::Medium => 0,
+            ErrorCorrection::Quartile => 3,
+            ErrorCorrection::High => 2,
+        }
+    }
+}
+
+/// An encoded QR code of up to version [`MAX_VERSION`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrCode {
+    /// Version, from 1 to [`MAX_VERSION`]
+    version: u8,
+    /// Error correction level
+    error_correction: ErrorCorrection,
+    /// Mask pattern applied, from 0 to 7
+    mask: u8,
+    /// Dark modules, one bit per column in each row
+    modules: [u64; MAX_SIZE],
+    /// Modules belonging to finder, timing and other fixed patterns
+    function: [u64; MAX_SIZE],
+}
+
+impl QrCode {
+    /// Encode bytes at the smallest version that holds them
+    ///
+    /// Returns [`Hub75Error::BufferOverflow`] if the data does not fit in
+    /// version [`MAX_VERSION`] at the requested error correction level.
+    pub fn encode(data: &[u8], error_correction: ErrorCorrection) -> Result<Self, Hub75Error> {
+        let version = (1..=MAX_VERSION)
+            .find(|&version| {
+                let used = 4 + char_count_bits(version) + data.len() * 8;
+                used <= data_codewords(version, error_correction) * 8
+            })
+            .ok_or(Hub75Error::BufferOverflow)?;
+
+        let mut code = Self {
+            version,
+            error_correction,
+            mask: 0,
+            modules: [0; MAX_SIZE],
+            function: [0; MAX_SIZE],
+        };
+        code.draw_function_patterns();
+        let codewords = code.add_error_correction(&code.data_codewords(data));
+        code.draw_codewords(&codewords[..total_codewords(version)]);
+
+        // Keep the mask that leaves the fewest patterns confusing to scanners
+        let mut best = (u32::MAX, 0);
+        for mask in 0..8 {
+            code.apply_mask(mask);
+            code.draw_format_bits(mask);
+            let penalty = code.penalty();
+            if penalty < best.0 {
+                best = (penalty, mask);
+            }
+            code.apply_mask(mask);
+        }
+        code.mask = best.1;
+        code.apply_mask(code.mask);
+        code.draw_format_bits(code.mask);
+        Ok(code)
+    }
+
+    /// Encode text as UTF-8 bytes
+    pub fn encode_text(text: &str, error_correction: ErrorCorrection) -> Result<Self, Hub75Error> {
+        Self::encode(text.as_bytes(), error_correction)
+    }
+
+    /// Get the version, from 1 to [`MAX_VERSION`]
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Get the error correction level
+    pub fn error_correction(&self) -> ErrorCorrection {
+        self.error_correction
+    }
+
+    /// Get the mask pattern chosen, from 0 to 7
+    pub fn mask(&self) -> u8 {
+        self.mask
+    }
+
+    /// Number of modules along each side, excluding the quiet zone
+    pub fn size(&self) -> usize {
+        17 + 4 * self.version as usize
+    }
+
+    /// Check if a module is dark, treating modules outside the code as light
+    pub fn module(&self, x: usize, y: usize) -> bool {
+        x < self.size() && y < self.size() && self.modules[y] & (1 << x) != 0
+    }
+
+    /// Draw the code as large as fits, centered in `area`
+    ///
+    /// The area is filled with `light`, then each module is drawn as a square
+    /// of whole pixels. Up to four modules of quiet zone are kept around the
+    /// code, shrinking to none on small panels. Returns
+    /// [`Hub75Error::InvalidCoordinates`] if the code does not fit even at one
+    /// pixel per module.
+    pub fn draw<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        area: Rectangle,
+        dark: Hub75Color<COLOR_BITS>,
+        light: Hub75Color<COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        let side = area.size.width.min(area.size.height) as usize;
+        let size = self.size();
+        let scale = (0..=QUIET_ZONE)
+            .rev()
+            .map(|quiet| side / (size + 2 * quiet))
+            .find(|&scale| scale > 0)
+            .ok_or(Hub75Error::InvalidCoordinates)?;
+
+        let (x0, y0) = (area.top_left.x, area.top_left.y);
+        fill(
+            frame,
+            x0,
+            y0,
+            area.size.width as usize,
+            area.size.height as usize,
+            light,
+        );
+        let offset_x = x0 + (area.size.width as usize - size * scale) as i32 / 2;
+        let offset_y = y0 + (area.size.height as usize - size * scale) as i32 / 2;
+        for y in 0..size {
+            for x in 0..size {
+                if self.module(x, y) {
+                    let px = offset_x + (x * scale) as i32;
+                    let py = offset_y + (y * scale) as i32;
+                    fill(frame, px, py, scale, scale, dark);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Set a module and mark it as part of a fixed pattern
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.set_module(x, y, dark);
+        self.function[y] |= 1 << x;
+    }
+
+    /// Set a module
+    fn set_module(&mut self, x: usize, y: usize, dark: bool) {
+        if dark {
+            self.modules[y] |= 1 << x;
+        } else {
+            self.modules[y] &= !(1 << x);
+        }
+    }
+
+    /// Check if a module is part of a fixed pattern
+    fn is_function(&self, x: usize, y: usize) -> bool {
+        self.function[y] & (1 << x) != 0
+    }
+
+    /// Draw timing, finder and alignment patterns, and reserve format areas
+    fn draw_function_patterns(&mut self) {
+        let size = self.size();
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4..=4i32 {
+                for dx in -4..=4i32 {
+                    let (x, y) = (cx as i32 + dx, cy as i32 + dy);
+                    if (0..size as i32).contains(&x) && (0..size as i32).contains(&y) {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(x as usize, y as usize, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+
+        let (positions, count) = self.alignment_positions();
+        for (i, &cy) in positions[..count].iter().enumerate() {
+            for (j, &cx) in positions[..count].iter().enumerate() {
+                // Skip the three corners taken by finder patterns
+                let last = count - 1;
+                if [(0, 0), (0, last), (last, 0)].contains(&(i, j)) {
+                    continue;
+                }
+                for dy in -2..=2i32 {
+                    for dx in -2..=2i32 {
+                        let (x, y) = ((cx as i32 + dx) as usize, (cy as i32 + dy) as usize);
+                        self.set_function(x, y, dx.abs().max(dy.abs()) != 1);
+                    }
+                }
+            }
+        }
+
+        // Placeholder format bits, so codewords skip their modules
+        self.draw_format_bits(0);
+        self.draw_version();
+    }
+
+    /// Centers of the alignment patterns along each axis
+    fn alignment_positions(&self) -> ([usize; 3], usize) {
+        let mut positions = [0; 3];
+        if self.version == 1 {
+            return (positions, 0);
+        }
+        let count = self.version as usize / 7 + 2;
+        let step = (self.version as usize * 4 + count * 2 + 1) / (count * 2 - 2) * 2;
+        positions[0] = 6;
+        let mut position = self.size() - 7;
+        for slot in positions[1..count].iter_mut().rev() {
+            *slot = position;
+            position -= step;
+        }
+        (positions, count)
+    }
+
+    /// Draw both copies of the error correction level and mask
+    fn draw_format_bits(&mut self, mask: u8) {
+        let bits = format_bits(self.error_correction, mask);
+        let bit = |i: u32| (bits >> i) & 1 != 0;
+        let size = self.size();
+
+        for i in 0..6 {
+            self.set_function(8, i as usize, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i as usize, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i as usize, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i as usize, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Draw both copies of the version from version 7 on
+    fn draw_version(&mut self) {
+        if self.version < 7 {
+            return;
+        }
+        let bits = version_bits(self.version);
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let (a, b) = (self.size() - 11 + i % 3, i / 3);
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// Mode, length, data and padding as data codewords
+    fn data_codewords(&self, data: &[u8]) -> [u8; MAX_CODEWORDS] {
+        let mut codewords = [0; MAX_CODEWORDS];
+        let capacity = data_codewords(self.version, self.error_correction);
+        let mut writer = BitWriter {
+            bytes: &mut codewords,
+            length: 0,
+        };
+        writer.push(0b0100, 4);
+        writer.push(data.len() as u32, char_count_bits(self.version));
+        for &byte in data {
+            writer.push(byte as u32, 8);
+        }
+
+        // Terminator, then byte alignment, then alternating pad bytes
+        let terminator = (capacity * 8 - writer.length).min(4);
+        writer.push(0, terminator);
+        writer.push(0, (8 - writer.length % 8) % 8);
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if writer.length >= capacity * 8 {
+                break;
+            }
+            writer.push(pad, 8);
+        }
+        codewords
+    }
+
+    /// Split data into blocks, add error correction, and interleave
+    fn add_error_correction(&self, data: &[u8; MAX_CODEWORDS]) -> [u8; MAX_CODEWORDS] {
+        let level = self.error_correction.index();
+        let version = self.version as usize;
+        let blocks = ERROR_CORRECTION_BLOCKS[level][version] as usize;
+        let block_ecc = ECC_CODEWORDS_PER_BLOCK[level][version] as usize;
+        let total = total_codewords(self.version);
+        let short_blocks = blocks - total % blocks;
+        let short_data = total / blocks - block_ecc;
+        let data_length = |block: usize| short_data + (block >= short_blocks) as usize;
+
+        let divisor = reed_solomon_divisor(block_ecc);
+        let mut ecc = [[0; MAX_BLOCK_ECC]; MAX_BLOCKS];
+        let mut start = 0;
+        for (block, remainder) in ecc[..blocks].iter_mut().enumerate() {
+            let length = data_length(block);
+            reed_solomon_remainder(
+                &data[start..start + length],
+                &divisor[..block_ecc],
+                remainder,
+            );
+            start += length;
+        }
+
+        let mut result = [0; MAX_CODEWORDS];
+        let mut index = 0;
+        for i in 0..=short_data {
+            let mut start = 0;
+            for block in 0..blocks {
+                if i < data_length(block) {
+                    result[index] = data[start + i];
+                    index += 1;
+                }
+                start += data_length(block);
+            }
+        }
+        for i in 0..block_ecc {
+            for remainder in &ecc[..blocks] {
+                result[index] = remainder[i];
+                index += 1;
+            }
+        }
+        result
+    }
+
+    /// Place codeword bits in the zigzag order, skipping fixed patterns
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size();
+        let mut i = 0;
+        let mut right = size - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let y = if upward {
+                        size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !self.is_function(x, y) && i < codewords.len() * 8 {
+                        let dark = (codewords[i >> 3] >> (7 - (i & 7))) & 1 != 0;
+                        self.set_module(x, y, dark);
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// Invert data modules selected by a mask pattern (applying twice undoes it)
+    fn apply_mask(&mut self, mask: u8) {
+        let size = self.size();
+        for y in 0..size {
+            for x in 0..size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if invert && !self.is_function(x, y) {
+                    self.modules[y] ^= 1 << x;
+                }
+            }
+        }
+    }
+
+    /// Score patterns that make a code hard to scan, lower being better
+    fn penalty(&self) -> u32 {
+        let size = self.size();
+        let mut penalty = 0;
+
+        for line in 0..size {
+            let row = |i: usize| self.module(i, line);
+            let column = |i: usize| self.module(line, i);
+            penalty += line_penalty(size, row) + line_penalty(size, column);
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let dark = self.module(x, y);
+                if self.module(x + 1, y) == dark
+                    && self.module(x, y + 1) == dark
+                    && self.module(x + 1, y + 1) == dark
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // 10 points for every 5% the dark share strays from half
+        let dark = self.modules[..size]
+            .iter()
+            .map(|row| row.count_ones())
+            .sum::<u32>();
+        let total = (size * size) as u32;
+        let deviation = (dark * 20).abs_diff(total * 10);
+        penalty + deviation.div_ceil(total).saturating_sub(1) * 10
+    }
+}
+
+/// Penalty for long runs and finder-like patterns along one row or column
+fn line_penalty(size: usize, module: impl Fn(usize) -> bool) -> u32 {
+    let mut penalty = 0;
+    let mut run = 0;
+    for i in 0..size {
+        run += 1;
+        if i + 1 == size || module(i + 1) != module(i) {
+            if run >= 5 {
+                penalty += run - 2;
+            }
+            run = 0;
+        }
+    }
+
+    // Dark-light-dark-dark-dark-light-dark with four light modules either side
+    const FINDER: [bool; 7] = [true, false, true, true, true, false, true];
+    let light =
+        |from: isize| (from..from + 4).all(|i| i < 0 || i >= size as isize || !module(i as usize));
+    for start in 0..=size.saturating_sub(7) {
+        if (0..7).all(|i| module(start + i) == FINDER[i])
+            && (light(start as isize - 4) || light(start as isize + 7))
+        {
+            penalty += 40;
+        }
+    }
+    penalty
+}
+
+/// Fills a rectangle that may extend past the frame edges
+fn fill<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+    frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    color: Hub75Color<COLOR_BITS>,
+) {
+    let (left, top) = (x.max(0) as usize, y.max(0) as usize);
+    let right = (x + width as i32).clamp(0, WIDTH as i32) as usize;
+    let bottom = (y + height as i32).clamp(0, HEIGHT as i32) as usize;
+    if left < right && top < bottom {
+        frame.fill_rect(left, top, right - left, bottom - top, color);
+    }
+}
+
+/// Appends bits to a byte buffer, most significant first
+struct BitWriter<'a> {
+    /// Destination bytes
+    bytes: &'a mut [u8],
+    /// Bits written so far
+    length: usize,
+}
+
+impl BitWriter<'_> {
+    /// Append the low `count` bits of `value`
+    fn push(&mut self, value: u32, count: usize) {
+        for i in (0..count).rev() {
+            if (value >> i) & 1 != 0 {
+                self.bytes[self.length / 8] |= 0x80 >> (self.length % 8);
+            }
+            self.length += 1;
+        }
+    }
+}
+
+/// Bits of the character count field in byte mode
+fn char_count_bits(version: u8) -> usize {
+    if version < 10 {
+        8
+    } else {
+        16
+    }
+}
+
+/// Codewords in a version, data and error correction together
+fn total_codewords(version: u8) -> usize {
+    let version = version as usize;
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignment = version / 7 + 2;
+        modules -= (25 * alignment - 10) * alignment - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules / 8
+}
+
+/// Data codewords in a version at an error correction level
+fn data_codewords(version: u8, error_correction: ErrorCorrection) -> usize {
+    let level = error_correction.index();
+    let version_index = version as usize;
+    total_codewords(version)
+        - ECC_CODEWORDS_PER_BLOCK[level][version_index] as usize
+            * ERROR_CORRECTION_BLOCKS[level][version_index] as usize
+}
+
+/// Format information: level and mask with BCH error correction, masked
+fn format_bits(error_correction: ErrorCorrection, mask: u8) -> u32 {
+    let data = error_correction.format_bits() << 3 | mask as u32;
+    let mut remainder = data;
+    for _ in 0..10 {
+        remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+    }
+    (data << 10 | remainder) ^ 0x5412
+}
+
+/// Version information with BCH error correction
+fn version_bits(version: u8) -> u32 {
+    let mut remainder = version as u32;
+    for _ in 0..12 {
+        remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+    }
+    (version as u32) << 12 | remainder
+}
+
+/// Multiply in GF(256) with the QR code polynomial
+fn gf_multiply(a: u8, b: u8) -> u8 {
+    let mut product: u16 = 0;
+    for i in (0..8).rev() {
+        product = (product << 1) ^ ((product >> 7) * 0x11D);
+        product ^= ((b >> i) & 1) as u16 * a as u16;
+    }
+    product as u8
+}
+
+/// Generator polynomial of a Reed-Solomon code, without its leading term
+fn reed_solomon_divisor(degree: usize) -> [u8; MAX_BLOCK_ECC] {
+    let mut divisor = [0; MAX_BLOCK_ECC];
+    divisor[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            divisor[j] = gf_multiply(divisor[j], root);
+            if j + 1 < degree {
+                divisor[j] ^= divisor[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    divisor
+}
+
+/// Error correction codewords for a block of data
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8], remainder: &mut [u8]) {
+    let remainder = &mut remainder[..divisor.len()];
+    remainder.fill(0);
+    for &byte in data {
+        let factor = byte ^ remainder[0];
+        remainder.copy_within(1.., 0);
+        remainder[divisor.len() - 1] = 0;
+        for (value, &coefficient) in remainder.iter_mut().zip(divisor) {
+            *value ^= gf_multiply(coefficient, factor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::geometry::{Point, Size};
+
+    #[test]
+    fn test_tables() {
+        assert_eq!(total_codewords(1), 26);
+        assert_eq!(total_codewords(7), 196);
+        assert_eq!(total_codewords(MAX_VERSION), MAX_CODEWORDS);
+        assert_eq!(data_codewords(1, ErrorCorrection::Low), 19);
+        assert_eq!(data_codewords(5, ErrorCorrection::Quartile), 62);
+        assert_eq!(data_codewords(10, ErrorCorrection::High), 122);
+
+        // Published format and version information
+        assert_eq!(format_bits(ErrorCorrection::Low, 0), 0b111011111000100);
+        assert_eq!(format_bits(ErrorCorrection::Medium, 0), 0b101010000010010);
+        assert_eq!(format_bits(ErrorCorrection::High, 7), 0b000100000111011);
+        assert_eq!(version_bits(7), 0b000111110010010100);
+    }
+
+    #[test]
+    fn test_reed_solomon() {
+        // "HELLO WORLD" at 1-M
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        let divisor = reed_solomon_divisor(10);
+        let mut remainder = [0; 10];
+        reed_solomon_remainder(&data, &divisor[..10], &mut remainder);
+        assert_eq!(remainder, [196, 35, 39, 119, 235, 215, 231, 226, 93, 23]);
+    }
+
+    #[test]
+    fn test_encode() {
+        let code = QrCode::encode_text("https://example.com", ErrorCorrection::Medium).unwrap();
+        assert_eq!(code.version(), 2);
+        assert_eq!(code.size(), 25);
+
+        // Finder patterns, the timing pattern and the always-dark module
+        for (x, y) in [(0, 0), (6, 6), (24, 0), (0, 24), (2, 2), (8, 17)] {
+            assert!(code.module(x, y), "{x},{y}");
+        }
+        for (x, y) in [(1, 1), (7, 7), (9, 6), (25, 0)] {
+            assert!(!code.module(x, y), "{x},{y}");
+        }
+        // The alignment pattern of version 2
+        assert!(code.module(18, 18) && !code.module(17, 18) && code.module(16, 18));
+
+        // Format bits read back from around the top left finder
+        let mut bits = 0;
+        for (i, y) in [0, 1, 2, 3, 4, 5, 7, 8].into_iter().enumerate() {
+            bits |= (code.module(8, y) as u32) << i;
+        }
+        bits |= (code.module(7, 8) as u32) << 8;
+        for i in 9..15 {
+            bits |= (code.module(14 - i, 8) as u32) << i;
+        }
+        assert_eq!(bits, format_bits(ErrorCorrection::Medium, code.mask()));
+
+        assert!(QrCode::encode(&[0; 272], ErrorCorrection::Low).is_err());
+        assert_eq!(
+            QrCode::encode(&[0; 271], ErrorCorrection::Low)
+                .unwrap()
+                .version(),
+            10
+        );
+    }
+
+    #[test]
+    fn test_draw() {
+        let code = QrCode::encode_text("HUB75", ErrorCorrection::Low).unwrap();
+        assert_eq!(code.size(), 21);
+
+        // 21 modules plus a 4 module quiet zone on each side at 2x is 58
+        let mut frame = Hub75FrameBuffer::<64, 64, 6>::new();
+        let area = Rectangle::new(Point::zero(), Size::new(64, 64));
+        code.draw(&mut frame, area, Hub75Color::black(), Hub75Color::white())
+            .unwrap();
+        assert_eq!(frame.get_pixel(11, 11).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(10, 11).unwrap(), Hub75Color::white());
+        assert_eq!(frame.get_pixel(52, 11).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(53, 11).unwrap(), Hub75Color::white());
+
+        // Too small for even one pixel per module
+        let area = Rectangle::new(Point::zero(), Size::new(20, 20));
+        assert_eq!(
+            code.draw(&mut frame, area, Hub75Color::black(), Hub75Color::white()),
+            Err(Hub75Error::InvalidCoordinates)
+        );
+    }
+}
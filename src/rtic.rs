@@ -0,0 +1,167 @@
+//! Refreshing from a timer interrupt
+//!
+//! RTIC applications, and any others without an async executor, can refresh
+//! the panel from a hardware timer instead of awaiting delays. A
+//! [`TickDriven`] display renders exactly one row and bit plane per timer
+//! interrupt with [`Hub75Display::render_step`], leaves it lit, and returns
+//! the timer count at which the next interrupt is due.
+//!
+//! Deadlines advance from the previous deadline rather than from the time
+//! the interrupt ran, so interrupt latency does not accumulate into the
+//! Binary Code Modulation timing.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::{rtic::TickDriven, Hub75Display, Hub75Pins};
+//! use embedded_hal::digital::OutputPin;
+//!
+//! # fn example<P: OutputPin + 'static>(pins: Hub75Pins<P>) -> Result<(), hub75::Hub75Error> {
+//! // A timer counting at 1 MHz
+//! let display = Hub75Display::<_, 64, 32, 6>::new(pins)?;
+//! let mut refresh = TickDriven::new(display, 1_000_000);
+//! refresh.start(0);
+//!
+//! // In the timer interrupt handler:
+//! let deadline = refresh.on_timer_tick()?;
+//! // set the timer compare register to `deadline as u32`
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    buffering::{BufferStorage, DoubleBuffer},
+    Hub75Display, Hub75Error,
+};
+use embedded_hal::digital::OutputPin;
+
+/// Display refreshed one step per timer interrupt
+pub struct TickDriven<
+    P: OutputPin + 'static,
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    B = DoubleBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    A: OutputPin + 'static = P,
+    C: OutputPin + 'static = P,
+> {
+    /// Display being refreshed
+    display: Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>,
+    /// Timer frequency in Hz
+    tick_hz: u32,
+    /// Timer count at which the next step is due
+    deadline: u64,
+}
+
+impl<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, B, A, C>
+    TickDriven<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>
+where
+    P: OutputPin,
+    A: OutputPin,
+    C: OutputPin,
+    B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS>,
+{
+    /// Drive a display from a timer counting at `tick_hz`
+    pub fn new(display: Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>, tick_hz: u32) -> Self {
+        Self {
+            display,
+            tick_hz,
+            deadline: 0,
+        }
+    }
+
+    /// Set the timer count that deadlines are measured from
+    ///
+    /// Call this with the current count before arming the first interrupt.
+    pub fn start(&mut self, now: u64) {
+        self.deadline = now;
+    }
+
+    /// Render one step and return the timer count of the next interrupt
+    ///
+    /// The deadline is not advanced if the step fails; [`deadline`](Self::deadline)
+    /// still holds the previous one.
+    pub fn on_timer_tick(&mut self) -> Result<u64, Hub75Error> {
+        let duration_ns = self.display.render_step()?;
+        self.deadline = self.deadline.wrapping_add(self.ns_to_ticks(duration_ns));
+        Ok(self.deadline)
+    }
+
+    /// Get the timer count of the next step
+    pub fn deadline(&self) -> u64 {
+        self.deadline
+    }
+
+    /// Get the timer frequency in Hz
+    pub fn tick_hz(&self) -> u32 {
+        self.tick_hz
+    }
+
+    /// Convert a duration to whole timer ticks, rounding up to at least one
+    pub fn ns_to_ticks(&self, duration_ns: u32) -> u64 {
+        (duration_ns as u64 * self.tick_hz as u64)
+            .div_ceil(1_000_000_000)
+            .max(1)
+    }
+
+    /// Get the display for drawing
+    pub fn display(&mut self) -> &mut Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C> {
+        &mut self.display
+    }
+
+    /// Stop refreshing and return the display
+    pub fn release(self) -> Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C> {
+        self.display
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::Hub75Color, waveform::WaveformRecorder};
+
+    #[test]
+    fn test_deadlines_follow_bit_planes() {
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        display.set_refresh_interval_ns(1_000);
+        display.set_brightness(crate::display::Brightness::new(255));
+        let mut refresh = TickDriven::new(display, 1_000_000);
+        refresh.start(100);
+
+        // Eight rows of the first bit plane, 1 us each
+        for row in 1..=8 {
+            assert_eq!(refresh.on_timer_tick(), Ok(100 + row));
+        }
+        // Second bit plane lights twice as long
+        assert_eq!(refresh.on_timer_tick(), Ok(110));
+        assert_eq!(refresh.deadline(), 110);
+    }
+
+    #[test]
+    fn test_ticks_round_up() {
+        let recorder = WaveformRecorder::new();
+        let display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        let refresh = TickDriven::new(display, 32_768);
+        assert_eq!(refresh.ns_to_ticks(0), 1);
+        assert_eq!(refresh.ns_to_ticks(30_518), 2);
+        assert_eq!(refresh.ns_to_ticks(1_000_000_000), 32_768);
+    }
+
+    #[test]
+    fn test_frame_follows_protocol() {
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        display.set_pixel(3, 12, Hub75Color::white()).unwrap();
+        let mut refresh = TickDriven::new(display, 1_000_000);
+
+        for _ in 0..2 * 4 * 8 {
+            refresh.on_timer_tick().unwrap();
+        }
+        assert_eq!(recorder.check_protocol(32, 8), Ok(()));
+        assert_eq!(
+            recorder.rising_edges(crate::waveform::Signal::Clk),
+            2 * 4 * 8 * 32
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Scenes and transitions between them
+//!
+//! A [`SceneManager`] owns a set of scenes, each a [`FrameSource`] such as an
+//! [`Animation`], an [`AnimationSequence`] or a drawing closure. One scene is
+//! live at a time; [`switch_to`](SceneManager::switch_to) moves to another
+//! with a transition effect. Applications drive the manager like a single
+//! animation and decide when to switch scenes.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{AnimationEffect, AnimationState, Direction, Hub75Color, Hub75FrameBuffer};
+//! use hub75::scene::SceneManager;
+//!
+//! let mut clock = |frame: &mut Hub75FrameBuffer<64, 32, 6>| frame.fill(Hub75Color::blue());
+//! let mut weather = |frame: &mut Hub75FrameBuffer<64, 32, 6>| frame.fill(Hub75Color::green());
+//!
+//! let mut scenes = SceneManager::<64, 32, 6, 2>::new();
+//! let _clock = scenes.add_scene(&mut clock).unwrap();
+//! let weather = scenes.add_scene(&mut weather).unwrap();
+//!
+//! // Slide the weather scene in over one second at 60 frames per second
+//! scenes
+//!     .switch_to(weather, AnimationEffect::Slide(Direction::Left), 60)
+//!     .unwrap();
+//!
+//! let mut frame = Hub75FrameBuffer::new();
+//! match scenes.next_into(&mut frame) {
+//!     AnimationState::Rendered => { /* show `frame` */ }
+//!     _ => {}
+//! }
+//! ```
+
+use heapless::Vec;
+
+use crate::{
+    animation::{Animation, AnimationEffect, AnimationEffectTrait, AnimationState},
+    frame_buffer::Hub75FrameBuffer,
+    sequence::AnimationSequence,
+    AnimationError, Hub75Error,
+};
+
+/// Something that produces frames over time
+///
+/// Implemented for [`Animation`], [`AnimationSequence`] and for closures
+/// drawing into a frame buffer, which are called once per frame.
+pub trait FrameSource<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Advance by one frame
+    ///
+    /// Returns [`AnimationState::Rendered`] when `frame` has been updated.
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS>;
+
+    /// Render the content currently shown into `frame` again
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error>;
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, E>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for Animation<'_, WIDTH, HEIGHT, COLOR_BITS, E>
+where
+    E: AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS>,
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        Animation::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        self.render_current_into(frame)
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize, E>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS>
+    for AnimationSequence<'_, WIDTH, HEIGHT, COLOR_BITS, N, E>
+where
+    E: AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS>,
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        AnimationSequence::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        self.render_current_into(frame)
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, F>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for F
+where
+    F: FnMut(&mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>),
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        self(frame);
+        AnimationState::Rendered
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        self(frame);
+        Ok(())
+    }
+}
+
+/// Transition in progress between the live scene and another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SceneTransition {
+    /// Scene being switched to
+    target: usize,
+    /// Effect blending the live scene into the target
+    effect: AnimationEffect,
+    /// Current transition step
+    step: usize,
+    /// Number of frames between steps
+    frames_per_step: usize,
+    /// Frames elapsed since the last step
+    frame_counter: usize,
+}
+
+/// A set of scenes, one of which is live, with transitions between them
+///
+/// Holds up to `N` scenes, identified by the index returned from
+/// [`add_scene`](Self::add_scene).
+pub struct SceneManager<
+    'a,
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    const N: usize,
+> {
+    /// Registered scenes
+    scenes: Vec<&'a mut dyn FrameSource<WIDTH, HEIGHT, COLOR_BITS>, N>,
+    /// Index of the live scene
+    live: usize,
+    /// Transition in progress, if any
+    transition: Option<SceneTransition>,
+}
+
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize>
+    SceneManager<'a, WIDTH, HEIGHT, COLOR_BITS, N>
+{
+    /// Create a scene manager without scenes
+    pub fn new() -> Self {
+        Self {
+            scenes: Vec::new(),
+            live: 0,
+            transition: None,
+        }
+    }
+
+    /// Register a scene and return its index
+    ///
+    /// The first scene added is live.
+    pub fn add_scene(
+        &mut self,
+        scene: &'a mut dyn FrameSource<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<usize, Hub75Error> {
+        self.scenes
+            .push(scene)
+            .map_err(|_| Hub75Error::BufferOverflow)?;
+        Ok(self.scenes.len() - 1)
+    }
+
+    /// Get the index of the live scene
+    ///
+    /// During a transition this is still the scene being switched away from.
+    pub fn live_scene(&self) -> usize {
+        self.live
+    }
+
+    /// Check if a transition is in progress
+    pub fn is_transitioning(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Switch to another scene with a transition lasting about `duration` frames
+    ///
+    /// Both scenes hold their current image while the transition plays. With
+    /// [`AnimationEffect::None`], or when `scene` is already live, the switch
+    /// is immediate. Switching during a transition abandons it and starts
+    /// over from the live scene.
+    pub fn switch_to(
+        &mut self,
+        scene: usize,
+        effect: AnimationEffect,
+        duration: usize,
+    ) -> Result<(), Hub75Error> {
+        if scene >= self.scenes.len() {
+            return Err(Hub75Error::AnimationError(AnimationError::InvalidData));
+        }
+
+        if scene == self.live || effect == AnimationEffect::None {
+            self.live = scene;
+            self.transition = None;
+            return Ok(());
+        }
+
+        let steps = AnimationEffectTrait::<WIDTH, HEIGHT, COLOR_BITS>::steps_per_frame(&effect);
+        self.transition = Some(SceneTransition {
+            target: scene,
+            effect,
+            step: 0,
+            frames_per_step: duration / steps.max(1),
+            frame_counter: 0,
+        });
+        Ok(())
+    }
+
+    /// Get the next state
+    ///
+    /// Returns the frame by value; prefer [`next_into`](Self::next_into) for
+    /// large frames.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        let mut frame = Hub75FrameBuffer::new();
+        match self.next_into(&mut frame) {
+            AnimationState::Rendered => AnimationState::Apply(frame),
+            state => state,
+        }
+    }
+
+    /// Advance the live scene or the transition, rendering into `frame`
+    ///
+    /// Behaves like [`Animation::next_into`]. Returns
+    /// [`AnimationState::Done`] when there are no scenes or the live scene
+    /// has finished.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        let Some(mut transition) = self.transition else {
+            return match self.scenes.get_mut(self.live) {
+                Some(scene) => scene.next_into(frame),
+                None => AnimationState::Done,
+            };
+        };
+
+        transition.frame_counter += 1;
+        if transition.frame_counter < transition.frames_per_step {
+            self.transition = Some(transition);
+            return AnimationState::Wait;
+        }
+
+        if self.render_transition(frame, &transition).is_err() {
+            self.transition = None;
+            return AnimationState::Done;
+        }
+
+        let steps =
+            AnimationEffectTrait::<WIDTH, HEIGHT, COLOR_BITS>::steps_per_frame(&transition.effect);
+        transition.step += 1;
+        transition.frame_counter = 0;
+        if transition.step < steps {
+            self.transition = Some(transition);
+        } else {
+            self.live = transition.target;
+            self.transition = None;
+        }
+        AnimationState::Rendered
+    }
+
+    /// Render the current transition step into `frame`
+    fn render_transition(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        transition: &SceneTransition,
+    ) -> Result<(), Hub75Error> {
+        self.scenes[self.live].render_into(frame)?;
+        let target = &mut self.scenes[transition.target];
+        transition
+            .effect
+            .apply_transition(frame, transition.step, |frame| target.render_into(frame))
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize> Default
+    for SceneManager<'_, WIDTH, HEIGHT, COLOR_BITS, N>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animation::{AnimationData, Direction, PlaybackMode},
+        color::Hub75Color,
+    };
+
+    #[test]
+    fn test_scene_transition() {
+        let mut red = Hub75FrameBuffer::<2, 1, 6>::new();
+        red.fill(Hub75Color::red());
+        let frames = [red];
+        let mut animation =
+            Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 1).unwrap();
+        animation.set_playback_mode(PlaybackMode::Loop);
+        let mut blue = |frame: &mut Hub75FrameBuffer<2, 1, 6>| frame.fill(Hub75Color::blue());
+
+        let mut scenes = SceneManager::<2, 1, 6, 2>::new();
+        assert!(matches!(scenes.next(), AnimationState::Done));
+        let first = scenes.add_scene(&mut animation).unwrap();
+        let second = scenes.add_scene(&mut blue).unwrap();
+        assert_eq!((first, second), (0, 1));
+        assert!(scenes.switch_to(2, AnimationEffect::Fade, 1).is_err());
+
+        let mut frame = Hub75FrameBuffer::new();
+        let (r, b) = (Hub75Color::red(), Hub75Color::blue());
+        assert!(matches!(
+            scenes.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(*frame.get_row(0).unwrap(), [r, r]);
+
+        // Two slide steps, two frames each
+        scenes
+            .switch_to(second, AnimationEffect::Slide(Direction::Left), 4)
+            .unwrap();
+        assert!(scenes.is_transitioning());
+        assert!(matches!(scenes.next_into(&mut frame), AnimationState::Wait));
+        assert!(matches!(
+            scenes.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(*frame.get_row(0).unwrap(), [r, r]);
+        assert!(matches!(scenes.next_into(&mut frame), AnimationState::Wait));
+        assert!(matches!(
+            scenes.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(*frame.get_row(0).unwrap(), [r, b]);
+
+        assert!(!scenes.is_transitioning());
+        assert_eq!(scenes.live_scene(), second);
+        assert!(matches!(
+            scenes.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(*frame.get_row(0).unwrap(), [b, b]);
+
+        // Switching without an effect is immediate
+        scenes.switch_to(first, AnimationEffect::None, 10).unwrap();
+        assert_eq!(scenes.live_scene(), first);
+        assert!(!scenes.is_transitioning());
+    }
+}
@@ -0,0 +1,380 @@
+//! Switching between day and night display profiles
+//!
+//! A [`NightMode`] scheduler picks a [`DisplayProfile`] from the time of day:
+//! one for the day, one for the night and optionally a blank one for hours
+//! when the display should be dark, as bedroom clocks and storefront signs
+//! need. Call [`update`](NightMode::update) periodically, for example once
+//! per frame or once a minute, with the time from a [`TimeSource`] such as
+//! an RTC. The profile is only applied when the phase changes, so manual
+//! brightness changes last until the next switch.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::schedule::{DisplayProfile, NightMode, TimeOfDay};
+//! use hub75::{Hub75Display, Hub75Pins};
+//! use embedded_hal::digital::OutputPin;
+//!
+//! # fn example<P: OutputPin + 'static>(pins: Hub75Pins<P>) -> Result<(), hub75::Hub75Error> {
+//! let mut display = Hub75Display::<_, 64, 32, 6>::new(pins)?;
+//!
+//! // Dim and warm from 21:30 to 7:00, dark from 1:00 to 5:00
+//! let mut night_mode = NightMode::new(TimeOfDay::new(21, 30), TimeOfDay::new(7, 0))
+//!     .with_blanking(TimeOfDay::new(1, 0), TimeOfDay::new(5, 0));
+//!
+//! let now = TimeOfDay::new(23, 15); // read from the RTC
+//! night_mode.update(&mut display, now);
+//! assert_eq!(display.brightness(), DisplayProfile::NIGHT.brightness);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    buffering::BufferStorage,
+    display::{Brightness, BrightnessPreset, Tint},
+    Hub75Display,
+};
+use embedded_hal::digital::OutputPin;
+
+/// Minutes in a day
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// Time of day with minute resolution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimeOfDay {
+    /// Minutes since midnight
+    minutes: u16,
+}
+
+impl TimeOfDay {
+    /// Midnight
+    pub const MIDNIGHT: Self = Self { minutes: 0 };
+
+    /// Create a time from hours (0-23) and minutes (0-59), wrapping past
+    /// midnight
+    pub const fn new(hour: u8, minute: u8) -> Self {
+        Self::from_minutes(hour as u16 * 60 + minute as u16)
+    }
+
+    /// Create a time from minutes since midnight, wrapping past midnight
+    pub const fn from_minutes(minutes: u16) -> Self {
+        Self {
+            minutes: minutes % MINUTES_PER_DAY,
+        }
+    }
+
+    /// Get the minutes since midnight
+    pub const fn minutes(&self) -> u16 {
+        self.minutes
+    }
+
+    /// Get the hour (0-23)
+    pub const fn hour(&self) -> u8 {
+        (self.minutes / 60) as u8
+    }
+
+    /// Get the minute within the hour (0-59)
+    pub const fn minute(&self) -> u8 {
+        (self.minutes % 60) as u8
+    }
+
+    /// Check whether the time falls in the window from `start` up to `end`,
+    /// which may span midnight
+    ///
+    /// A window whose start and end are equal is empty.
+    pub fn is_between(&self, start: TimeOfDay, end: TimeOfDay) -> bool {
+        if start <= end {
+            *self >= start && *self < end
+        } else {
+            *self >= start || *self < end
+        }
+    }
+}
+
+/// Source of the current time of day, such as an RTC
+pub trait TimeSource {
+    /// Get the current time of day
+    fn time_of_day(&mut self) -> TimeOfDay;
+}
+
+impl<F: FnMut() -> TimeOfDay> TimeSource for F {
+    fn time_of_day(&mut self) -> TimeOfDay {
+        self()
+    }
+}
+
+/// Display settings applied for a phase of the day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DisplayProfile {
+    /// Global brightness
+    pub brightness: Brightness,
+    /// Global tint, used to warm the color temperature
+    pub tint: Tint,
+}
+
+impl DisplayProfile {
+    /// Normal brightness without tint
+    pub const DAY: Self = Self {
+        brightness: Brightness::new(128),
+        tint: Tint::None,
+    };
+
+    /// Dim and warm, cutting most of the blue light
+    pub const NIGHT: Self = Self {
+        brightness: Brightness::new(32),
+        tint: Tint::Multiply {
+            r: 255,
+            g: 160,
+            b: 64,
+        },
+    };
+
+    /// Dark
+    pub const BLANK: Self = Self {
+        brightness: Brightness::MIN,
+        tint: Tint::None,
+    };
+
+    /// Create a profile
+    pub fn new(brightness: impl Into<Brightness>, tint: Tint) -> Self {
+        Self {
+            brightness: brightness.into(),
+            tint,
+        }
+    }
+
+    /// Apply the profile to a display
+    pub fn apply<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, B, A, C>(
+        &self,
+        display: &mut Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>,
+    ) where
+        P: OutputPin,
+        A: OutputPin,
+        C: OutputPin,
+        B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS>,
+    {
+        display.set_brightness(self.brightness);
+        display.set_tint(self.tint);
+    }
+}
+
+impl Default for DisplayProfile {
+    fn default() -> Self {
+        Self::new(BrightnessPreset::Normal, Tint::None)
+    }
+}
+
+/// Phase of the day chosen by a [`NightMode`] scheduler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Phase {
+    /// Day profile
+    Day,
+    /// Night profile
+    Night,
+    /// Blanking hours
+    Blank,
+}
+
+/// Scheduler switching display profiles by time of day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NightMode {
+    /// Profile outside the night
+    day: DisplayProfile,
+    /// Profile during the night
+    night: DisplayProfile,
+    /// Profile during blanking hours
+    blank: DisplayProfile,
+    /// Start and end of the night
+    night_hours: (TimeOfDay, TimeOfDay),
+    /// Start and end of the blanking hours, if any
+    blank_hours: Option<(TimeOfDay, TimeOfDay)>,
+    /// Phase last applied to the display
+    phase: Option<Phase>,
+}
+
+impl NightMode {
+    /// Create a scheduler with the default profiles and a night from `start`
+    /// up to `end`
+    pub fn new(start: TimeOfDay, end: TimeOfDay) -> Self {
+        Self {
+            day: DisplayProfile::DAY,
+            night: DisplayProfile::NIGHT,
+            blank: DisplayProfile::BLANK,
+            night_hours: (start, end),
+            blank_hours: None,
+            phase: None,
+        }
+    }
+
+    /// Use a different day profile
+    pub fn with_day(mut self, profile: DisplayProfile) -> Self {
+        self.day = profile;
+        self
+    }
+
+    /// Use a different night profile
+    pub fn with_night(mut self, profile: DisplayProfile) -> Self {
+        self.night = profile;
+        self
+    }
+
+    /// Blank the display from `start` up to `end`, which takes precedence
+    /// over the night
+    pub fn with_blanking(mut self, start: TimeOfDay, end: TimeOfDay) -> Self {
+        self.blank_hours = Some((start, end));
+        self
+    }
+
+    /// Get the phase for a time of day
+    pub fn phase_at(&self, time: TimeOfDay) -> Phase {
+        if let Some((start, end)) = self.blank_hours {
+            if time.is_between(start, end) {
+                return Phase::Blank;
+            }
+        }
+        let (start, end) = self.night_hours;
+        if time.is_between(start, end) {
+            Phase::Night
+        } else {
+            Phase::Day
+        }
+    }
+
+    /// Get the profile used for a phase
+    pub fn profile(&self, phase: Phase) -> DisplayProfile {
+        match phase {
+            Phase::Day => self.day,
+            Phase::Night => self.night,
+            Phase::Blank => self.blank,
+        }
+    }
+
+    /// Get the phase last applied, if any
+    pub fn phase(&self) -> Option<Phase> {
+        self.phase
+    }
+
+    /// Apply the profile for `time` if the phase has changed
+    ///
+    /// Returns the new phase when the display was updated.
+    pub fn update<P, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, B, A, C>(
+        &mut self,
+        display: &mut Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>,
+        time: TimeOfDay,
+    ) -> Option<Phase>
+    where
+        P: OutputPin,
+        A: OutputPin,
+        C: OutputPin,
+        B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS>,
+    {
+        let phase = self.phase_at(time);
+        if self.phase == Some(phase) {
+            return None;
+        }
+        self.profile(phase).apply(display);
+        self.phase = Some(phase);
+        Some(phase)
+    }
+
+    /// Read the time from `source` and [`update`](Self::update) the display
+    pub fn update_from<
+        P,
+        const WIDTH: usize,
+        const HEIGHT: usize,
+        const COLOR_BITS: usize,
+        B,
+        A,
+        C,
+    >(
+        &mut self,
+        display: &mut Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>,
+        source: &mut impl TimeSource,
+    ) -> Option<Phase>
+    where
+        P: OutputPin,
+        A: OutputPin,
+        C: OutputPin,
+        B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS>,
+    {
+        let time = source.time_of_day();
+        self.update(display, time)
+    }
+
+    /// Forget the applied phase so the next update applies its profile again
+    pub fn reset(&mut self) {
+        self.phase = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waveform::WaveformRecorder;
+
+    #[test]
+    fn test_time_of_day() {
+        let time = TimeOfDay::new(21, 45);
+        assert_eq!((time.hour(), time.minute()), (21, 45));
+        assert_eq!(time.minutes(), 1305);
+        assert_eq!(TimeOfDay::from_minutes(24 * 60 + 5), TimeOfDay::new(0, 5));
+
+        // Windows may span midnight
+        let (evening, morning) = (TimeOfDay::new(22, 0), TimeOfDay::new(6, 0));
+        assert!(TimeOfDay::new(23, 0).is_between(evening, morning));
+        assert!(TimeOfDay::MIDNIGHT.is_between(evening, morning));
+        assert!(!TimeOfDay::new(6, 0).is_between(evening, morning));
+        assert!(TimeOfDay::new(6, 0).is_between(morning, evening));
+        assert!(!TimeOfDay::new(6, 0).is_between(morning, morning));
+    }
+
+    #[test]
+    fn test_phases() {
+        let schedule = NightMode::new(TimeOfDay::new(21, 30), TimeOfDay::new(7, 0))
+            .with_blanking(TimeOfDay::new(1, 0), TimeOfDay::new(5, 0));
+        assert_eq!(schedule.phase_at(TimeOfDay::new(12, 0)), Phase::Day);
+        assert_eq!(schedule.phase_at(TimeOfDay::new(21, 30)), Phase::Night);
+        assert_eq!(schedule.phase_at(TimeOfDay::new(3, 0)), Phase::Blank);
+        assert_eq!(schedule.phase_at(TimeOfDay::new(6, 59)), Phase::Night);
+        assert_eq!(schedule.phase_at(TimeOfDay::new(7, 0)), Phase::Day);
+    }
+
+    #[test]
+    fn test_update_applies_on_change() {
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        let mut schedule = NightMode::new(TimeOfDay::new(22, 0), TimeOfDay::new(6, 0))
+            .with_day(DisplayProfile::new(BrightnessPreset::Bright, Tint::None));
+
+        assert_eq!(
+            schedule.update(&mut display, TimeOfDay::new(23, 0)),
+            Some(Phase::Night)
+        );
+        assert_eq!(display.brightness(), DisplayProfile::NIGHT.brightness);
+        assert_eq!(display.tint(), DisplayProfile::NIGHT.tint);
+
+        // Manual changes last until the phase changes
+        display.set_brightness(BrightnessPreset::Normal);
+        assert_eq!(schedule.update(&mut display, TimeOfDay::new(23, 30)), None);
+        assert_eq!(display.brightness(), BrightnessPreset::Normal.into());
+
+        let mut clock = || TimeOfDay::new(8, 0);
+        assert_eq!(
+            schedule.update_from(&mut display, &mut clock),
+            Some(Phase::Day)
+        );
+        assert_eq!(display.brightness(), BrightnessPreset::Bright.into());
+        assert_eq!(display.tint(), Tint::None);
+
+        schedule.reset();
+        assert_eq!(schedule.phase(), None);
+        assert_eq!(
+            schedule.update_from(&mut display, &mut clock),
+            Some(Phase::Day)
+        );
+    }
+}
@@ -0,0 +1,464 @@
+//! Playlists of animations
+//!
+//! An [`AnimationSequence`] plays a list of [`Animation`]s one after another,
+//! repeating each item a given number of times and optionally blending into the
+//! next item with a transition effect. It exposes the same
+//! [`next`](AnimationSequence::next) / [`AnimationState`] interface as a single
+//! animation, so signage rotations need no hand-written state machine.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Animation, AnimationData, AnimationEffect, AnimationState, Hub75FrameBuffer};
+//! use hub75::sequence::{AnimationSequence, SequenceItem};
+//!
+//! let logo = [Hub75FrameBuffer::<64, 32, 6>::new()];
+//! let banner = [Hub75FrameBuffer::<64, 32, 6>::new()];
+//!
+//! let mut sequence = AnimationSequence::<64, 32, 6, 2>::new();
+//! sequence
+//!     .push(
+//!         SequenceItem::new(
+//!             Animation::new(AnimationData::Frames(&logo), AnimationEffect::None, 60).unwrap(),
+//!         )
+//!         .with_repeat(3)
+//!         .with_transition(AnimationEffect::Crossfade, 2),
+//!     )
+//!     .unwrap();
+//! sequence
+//!     .push(SequenceItem::new(
+//!         Animation::new(AnimationData::Frames(&banner), AnimationEffect::None, 60).unwrap(),
+//!     ))
+//!     .unwrap();
+//! sequence.set_looping(true);
+//!
+//! let mut frame = Hub75FrameBuffer::new();
+//! match sequence.next_into(&mut frame) {
+//!     AnimationState::Rendered => { /* show `frame` */ }
+//!     _ => {}
+//! }
+//! ```
+
+use heapless::Vec;
+
+use crate::{
+    animation::{Animation, AnimationEffect, AnimationEffectTrait, AnimationState},
+    frame_buffer::Hub75FrameBuffer,
+    AnimationError, Hub75Error,
+};
+
+/// Transition played between a sequence item and the next one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Transition {
+    /// Effect blending the last frame of an item into the first of the next
+    pub effect: AnimationEffect,
+    /// Number of frames between transition steps
+    pub frames_per_step: usize,
+}
+
+/// An animation in a sequence, with its repeat count and outgoing transition
+pub struct SequenceItem<
+    'a,
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    E = AnimationEffect,
+> {
+    /// Animation to play
+    animation: Animation<'a, WIDTH, HEIGHT, COLOR_BITS, E>,
+    /// Number of times to play the animation
+    repeat: usize,
+    /// Transition into the next item
+    transition: Option<Transition>,
+}
+
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, E>
+    SequenceItem<'a, WIDTH, HEIGHT, COLOR_BITS, E>
+where
+    E: AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS>,
+{
+    /// Create an item playing `animation` once, with no transition
+    ///
+    /// The animation should use [`PlaybackMode::Once`](crate::PlaybackMode::Once)
+    /// or `LoopN`, otherwise it never finishes and the sequence never moves on.
+    pub fn new(animation: Animation<'a, WIDTH, HEIGHT, COLOR_BITS, E>) -> Self {
+        Self {
+            animation,
+            repeat: 1,
+            transition: None,
+        }
+    }
+
+    /// Play the animation `repeat` times before moving on
+    pub fn with_repeat(mut self, repeat: usize) -> Self {
+        self.repeat = repeat.max(1);
+        self
+    }
+
+    /// Blend into the next item with `effect`, advancing one transition step
+    /// every `frames_per_step` frames
+    pub fn with_transition(mut self, effect: AnimationEffect, frames_per_step: usize) -> Self {
+        self.transition = Some(Transition {
+            effect,
+            frames_per_step,
+        });
+        self
+    }
+
+    /// Get the animation played by this item
+    pub fn animation(&mut self) -> &mut Animation<'a, WIDTH, HEIGHT, COLOR_BITS, E> {
+        &mut self.animation
+    }
+}
+
+/// What the sequence is currently playing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// Playing the current item
+    Playing,
+    /// Transitioning from the current item into the next one
+    Transition {
+        /// Current transition step
+        step: usize,
+        /// Frames elapsed since the last transition step
+        frame_counter: usize,
+    },
+    /// All items have played
+    Done,
+}
+
+/// A playlist of animations played one after another
+///
+/// Holds up to `N` items. Every item shares the effect type `E`; use
+/// `&dyn AnimationEffectTrait` to mix effects.
+pub struct AnimationSequence<
+    'a,
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    const N: usize,
+    E = AnimationEffect,
+> {
+    /// Items in playback order
+    items: Vec<SequenceItem<'a, WIDTH, HEIGHT, COLOR_BITS, E>, N>,
+    /// Index of the item being played
+    current: usize,
+    /// Number of times the current item has finished
+    plays: usize,
+    /// What is currently being played
+    phase: Phase,
+    /// Whether to start over after the last item
+    looping: bool,
+}
+
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize, E>
+    AnimationSequence<'a, WIDTH, HEIGHT, COLOR_BITS, N, E>
+where
+    E: AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS>,
+{
+    /// Create an empty sequence
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            current: 0,
+            plays: 0,
+            phase: Phase::Playing,
+            looping: false,
+        }
+    }
+
+    /// Append an item to the sequence
+    pub fn push(
+        &mut self,
+        item: SequenceItem<'a, WIDTH, HEIGHT, COLOR_BITS, E>,
+    ) -> Result<(), Hub75Error> {
+        self.items
+            .push(item)
+            .map_err(|_| Hub75Error::BufferOverflow)
+    }
+
+    /// Start over from the first item after the last one instead of finishing
+    ///
+    /// The transition of the last item, if any, leads into the first item.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Get the number of items in the sequence
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check if the sequence has no items
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Get the index of the item being played
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Get the next sequence state
+    ///
+    /// Returns the frame by value; prefer [`next_into`](Self::next_into) for
+    /// large frames.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        let mut frame = Hub75FrameBuffer::new();
+        match self.next_into(&mut frame) {
+            AnimationState::Rendered => AnimationState::Apply(frame),
+            state => state,
+        }
+    }
+
+    /// Advance the sequence, rendering into a provided frame buffer
+    ///
+    /// Behaves like [`Animation::next_into`]: returns
+    /// [`AnimationState::Rendered`] when `frame` holds the next frame.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        // Bounded so that a sequence of items that never render cannot spin
+        for _ in 0..=2 * self.items.len() {
+            match self.phase {
+                Phase::Done => return AnimationState::Done,
+                Phase::Playing => {
+                    let Some(item) = self.items.get_mut(self.current) else {
+                        self.phase = Phase::Done;
+                        return AnimationState::Done;
+                    };
+                    match item.animation.next_into(frame) {
+                        AnimationState::Done => self.finish_item(),
+                        state => return state,
+                    }
+                }
+                Phase::Transition {
+                    step,
+                    frame_counter,
+                } => return self.next_transition_step(frame, step, frame_counter),
+            }
+        }
+        AnimationState::Done
+    }
+
+    /// Handle the current item finishing a play-through
+    fn finish_item(&mut self) {
+        let item = &mut self.items[self.current];
+        self.plays += 1;
+        if self.plays < item.repeat {
+            item.animation.reset();
+            return;
+        }
+
+        let Some(next) = self.next_index() else {
+            self.phase = Phase::Done;
+            return;
+        };
+        self.items[next].animation.reset();
+        self.phase = match self.items[self.current].transition {
+            Some(_) => Phase::Transition {
+                step: 0,
+                frame_counter: 0,
+            },
+            None => {
+                self.start_item(next);
+                Phase::Playing
+            }
+        };
+    }
+
+    /// Index of the item after the current one, if any
+    fn next_index(&self) -> Option<usize> {
+        if self.current + 1 < self.items.len() {
+            Some(self.current + 1)
+        } else if self.looping {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    /// Make `index` the current item
+    fn start_item(&mut self, index: usize) {
+        self.current = index;
+        self.plays = 0;
+    }
+
+    /// Advance the transition out of the current item
+    fn next_transition_step(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        step: usize,
+        frame_counter: usize,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        let (Some(transition), Some(next)) =
+            (self.items[self.current].transition, self.next_index())
+        else {
+            self.phase = Phase::Done;
+            return AnimationState::Done;
+        };
+
+        let frame_counter = frame_counter + 1;
+        if frame_counter < transition.frames_per_step {
+            self.phase = Phase::Transition {
+                step,
+                frame_counter,
+            };
+            return AnimationState::Wait;
+        }
+
+        if self
+            .render_transition(frame, transition.effect, next, step)
+            .is_err()
+        {
+            self.phase = Phase::Done;
+            return AnimationState::Done;
+        }
+
+        let steps =
+            AnimationEffectTrait::<WIDTH, HEIGHT, COLOR_BITS>::steps_per_frame(&transition.effect);
+        self.phase = if step + 1 < steps {
+            Phase::Transition {
+                step: step + 1,
+                frame_counter: 0,
+            }
+        } else {
+            self.start_item(next);
+            Phase::Playing
+        };
+        AnimationState::Rendered
+    }
+
+    /// Render one transition step from the current item into item `next`
+    fn render_transition(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        effect: AnimationEffect,
+        next: usize,
+        step: usize,
+    ) -> Result<(), Hub75Error> {
+        self.items[self.current]
+            .animation
+            .render_current_into(frame)?;
+        let incoming = &mut self.items[next].animation;
+        effect.apply_transition(frame, step, |frame| incoming.render_current_into(frame))
+    }
+
+    /// Render the frame of the current item most recently shown again
+    pub fn render_current_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        match self.items.get_mut(self.current) {
+            Some(item) => item.animation.render_current_into(frame),
+            None => Err(Hub75Error::AnimationError(AnimationError::InvalidData)),
+        }
+    }
+
+    /// Check if the sequence has finished
+    pub fn is_done(&self) -> bool {
+        self.phase == Phase::Done
+    }
+
+    /// Restart the sequence from the first item
+    pub fn reset(&mut self) {
+        self.start_item(0);
+        self.phase = Phase::Playing;
+        if let Some(item) = self.items.first_mut() {
+            item.animation.reset();
+        }
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize, E> Default
+    for AnimationSequence<'_, WIDTH, HEIGHT, COLOR_BITS, N, E>
+where
+    E: AnimationEffectTrait<WIDTH, HEIGHT, COLOR_BITS>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animation::{AnimationData, Direction},
+        color::Hub75Color,
+    };
+
+    #[test]
+    fn test_sequence_repeats_and_transitions() {
+        let mut red = Hub75FrameBuffer::<2, 1, 6>::new();
+        red.fill(Hub75Color::red());
+        let mut blue = Hub75FrameBuffer::<2, 1, 6>::new();
+        blue.fill(Hub75Color::blue());
+        let first = [red];
+        let second = [blue];
+
+        let mut sequence = AnimationSequence::<2, 1, 6, 2>::new();
+        sequence
+            .push(
+                SequenceItem::new(
+                    Animation::new(AnimationData::Frames(&first), AnimationEffect::None, 1)
+                        .unwrap(),
+                )
+                .with_repeat(2)
+                .with_transition(AnimationEffect::Slide(Direction::Left), 1),
+            )
+            .unwrap();
+        sequence
+            .push(SequenceItem::new(
+                Animation::new(AnimationData::Frames(&second), AnimationEffect::None, 1).unwrap(),
+            ))
+            .unwrap();
+        assert_eq!(sequence.len(), 2);
+
+        let mut frame = Hub75FrameBuffer::new();
+        let mut shown = [[Hub75Color::black(); 2]; 5];
+        for pixels in &mut shown {
+            assert!(matches!(
+                sequence.next_into(&mut frame),
+                AnimationState::Rendered
+            ));
+            *pixels = *frame.get_row(0).unwrap();
+        }
+        let (r, b) = (Hub75Color::red(), Hub75Color::blue());
+        assert_eq!(shown, [[r, r], [r, r], [r, r], [r, b], [b, b]]);
+        assert_eq!(sequence.current_index(), 1);
+        assert!(matches!(
+            sequence.next_into(&mut frame),
+            AnimationState::Done
+        ));
+    }
+
+    #[test]
+    fn test_sequence_done_and_looping() {
+        let frames = [Hub75FrameBuffer::<2, 1, 6>::new()];
+        let mut sequence = AnimationSequence::<2, 1, 6, 1>::new();
+        sequence
+            .push(SequenceItem::new(
+                Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 1).unwrap(),
+            ))
+            .unwrap();
+        assert!(sequence
+            .push(SequenceItem::new(
+                Animation::new(AnimationData::Frames(&frames), AnimationEffect::None, 1).unwrap(),
+            ))
+            .is_err());
+
+        assert!(matches!(sequence.next(), AnimationState::Apply(_)));
+        assert!(matches!(sequence.next(), AnimationState::Done));
+        assert!(sequence.is_done());
+
+        sequence.reset();
+        sequence.set_looping(true);
+        for _ in 0..4 {
+            assert!(matches!(sequence.next(), AnimationState::Apply(_)));
+        }
+    }
+}
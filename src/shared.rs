@@ -0,0 +1,649 @@
+//! Drawing from other tasks through a command queue
+//!
+//! Sharing a display behind a mutex means the refresh task holds the lock for
+//! a whole frame, and a drawing task waiting on it can be starved for as long
+//! as the panel is being refreshed. A [`SharedHub75`] avoids the lock: drawing
+//! code queues [`Command`]s through a [`Hub75Handle`], and the refresh task
+//! owns the display and applies whatever is pending between frames with a
+//! [`Hub75Receiver`].
+//!
+//! The queue is a lock-free single-producer, single-consumer ring buffer. The
+//! receiver can be moved to another executor or interrupt priority. Handles
+//! are cheap to copy but cannot leave the thread they were created on, so
+//! every handle feeds the queue from a single context, such as the tasks of
+//! one cooperative executor. Queueing never waits; a full queue is reported
+//! as [`Hub75Error::BufferOverflow`] and the command is dropped.
+//!
+//! Reading goes the same way: [`Hub75Handle::request_inspect`] queues a
+//! request, and the refresh task answers it with
+//! [`Hub75Receiver::apply_inspecting`], running a closure on the front
+//! buffer between frames, for screenshots or streaming the frame elsewhere.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::{color::Hub75Color, display::Brightness, Hub75Display, Hub75Pins};
+//! use hub75::shared::SharedHub75;
+//! use embedded_hal::digital::OutputPin;
+//! use embedded_hal_async::delay::DelayNs;
+//!
+//! # async fn example<P: OutputPin + 'static>(
+//! #     pins: Hub75Pins<P>,
+//! #     delay: &mut impl DelayNs,
+//! # ) -> Result<(), hub75::Hub75Error> {
+//! let mut shared = SharedHub75::<64, 32, 6, 64>::new();
+//! let (handle, mut receiver) = shared.split();
+//!
+//! // Drawing side
+//! handle.set_brightness(Brightness::new(128))?;
+//! handle.fill_rect(0, 0, 8, 8, Hub75Color::new(63, 0, 0))?;
+//! handle.swap_buffers()?;
+//!
+//! // Refresh side
+//! let mut display = Hub75Display::<_, 64, 32, 6>::new(pins)?;
+//! display.set_double_buffering(true);
+//! loop {
+//!     receiver.apply(&mut display);
+//!     display.render_frame(delay).await?;
+//! }
+//! # }
+//! ```
+
+use crate::{
+    buffering::BufferStorage, color::Hub75Color, display::Brightness,
+    frame_buffer::Hub75FrameBuffer, Hub75Display, Hub75Error,
+};
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use embedded_hal::digital::OutputPin;
+
+/// Drawing operation queued for the refresh task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Command<const COLOR_BITS: usize> {
+    /// Set a single pixel
+    SetPixel {
+        x: u16,
+        y: u16,
+        color: Hub75Color<COLOR_BITS>,
+    },
+    /// Fill a rectangle with its top-left corner at (x, y)
+    FillRect {
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        color: Hub75Color<COLOR_BITS>,
+    },
+    /// Fill the whole display with a color
+    Fill(Hub75Color<COLOR_BITS>),
+    /// Clear the display
+    Clear,
+    /// Change the global brightness
+    SetBrightness(Brightness),
+    /// Present the back buffer
+    SwapBuffers,
+    /// Run the receiver's inspector on the front buffer, see
+    /// [`Hub75Receiver::apply_inspecting`]
+    InspectFront,
+}
+
+impl<const COLOR_BITS: usize> Command<COLOR_BITS> {
+    /// Apply the command to a display
+    pub fn apply<P, const WIDTH: usize, const HEIGHT: usize, B, A, C>(
+        self,
+        display: &mut Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>,
+    ) -> Result<(), Hub75Error>
+    where
+        P: OutputPin,
+        A: OutputPin,
+        C: OutputPin,
+        B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS, Pixel = Hub75Color<COLOR_BITS>>,
+    {
+        match self {
+            Command::SetPixel { x, y, color } => display.set_pixel(x.into(), y.into(), color)?,
+            Command::FillRect {
+                x,
+                y,
+                width,
+                height,
+                color,
+            } => display.back_buffer().fill_rect(
+                x.into(),
+                y.into(),
+                width.into(),
+                height.into(),
+                color,
+            ),
+            Command::Fill(color) => display.fill(color),
+            Command::Clear => display.clear(),
+            Command::SetBrightness(brightness) => display.set_brightness(brightness),
+            Command::SwapBuffers => display.swap_buffers(),
+            Command::InspectFront => {}
+        }
+        Ok(())
+    }
+}
+
+/// Command queue shared between drawing code and the refresh task
+///
+/// Holds up to `N` commands. Place it in a `static` (for example with
+/// `StaticCell`) and [`split`](Self::split) it once to get the two ends.
+pub struct SharedHub75<
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    const N: usize,
+> {
+    /// Command slots, indexed by position modulo `N`
+    slots: [UnsafeCell<MaybeUninit<Command<COLOR_BITS>>>; N],
+    /// Position of the next command to read, only written by the receiver
+    head: AtomicUsize,
+    /// Position of the next free slot, only written by the handles
+    tail: AtomicUsize,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize>
+    SharedHub75<WIDTH, HEIGHT, COLOR_BITS, N>
+{
+    /// Compile-time check that the queue has room for a command
+    const NOT_EMPTY: () = assert!(N > 0, "command queue needs at least one slot");
+
+    /// Create an empty queue
+    pub const fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::NOT_EMPTY;
+        Self {
+            slots: [const { UnsafeCell::new(MaybeUninit::uninit()) }; N],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Split the queue into a handle for drawing and a receiver for the
+    /// refresh task
+    pub fn split(
+        &mut self,
+    ) -> (
+        Hub75Handle<'_, WIDTH, HEIGHT, COLOR_BITS, N>,
+        Hub75Receiver<'_, WIDTH, HEIGHT, COLOR_BITS, N>,
+    ) {
+        let queue = &*self;
+        (
+            Hub75Handle {
+                queue,
+                _local: PhantomData,
+            },
+            Hub75Receiver { queue },
+        )
+    }
+
+    /// Get the number of commands waiting to be applied
+    pub fn len(&self) -> usize {
+        self.tail
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.head.load(Ordering::Acquire))
+    }
+
+    /// Check whether no commands are waiting
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the number of commands the queue can hold
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Slot holding the command at a position
+    fn slot(&self, position: usize) -> *mut MaybeUninit<Command<COLOR_BITS>> {
+        self.slots[position % N].get()
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize> Default
+    for SharedHub75<WIDTH, HEIGHT, COLOR_BITS, N>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Producer end of a [`SharedHub75`]
+///
+/// Copies of a handle all feed the same queue and must stay on the thread
+/// that split it, which is what keeps the queue single-producer.
+#[derive(Clone, Copy)]
+pub struct Hub75Handle<
+    'a,
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    const N: usize,
+> {
+    /// Queue the commands go to
+    queue: &'a SharedHub75<WIDTH, HEIGHT, COLOR_BITS, N>,
+    /// Keeps the handle from being sent to another thread
+    _local: PhantomData<*const ()>,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize>
+    Hub75Handle<'_, WIDTH, HEIGHT, COLOR_BITS, N>
+{
+    /// Queue a command
+    ///
+    /// Returns [`Hub75Error::BufferOverflow`] if the queue is full.
+    pub fn send(&self, command: Command<COLOR_BITS>) -> Result<(), Hub75Error> {
+        let tail = self.queue.tail.load(Ordering::Relaxed);
+        let head = self.queue.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) >= N {
+            return Err(Hub75Error::BufferOverflow);
+        }
+        // SAFETY: the slot is outside head..tail, so the receiver does not
+        // read it, and handles never leave this thread
+        unsafe { (*self.queue.slot(tail)).write(command) };
+        self.queue
+            .tail
+            .store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    /// Queue setting a pixel at the specified coordinates
+    pub fn set_pixel(
+        &self,
+        x: usize,
+        y: usize,
+        color: Hub75Color<COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        if x >= WIDTH || y >= HEIGHT {
+            return Err(Hub75Error::InvalidCoordinates);
+        }
+        self.send(Command::SetPixel {
+            x: x as u16,
+            y: y as u16,
+            color,
+        })
+    }
+
+    /// Queue filling a `width` x `height` rectangle with its top-left corner
+    /// at (x, y)
+    ///
+    /// The rectangle is clipped to the display when applied.
+    pub fn fill_rect(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        color: Hub75Color<COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        if x >= WIDTH || y >= HEIGHT {
+            return Ok(());
+        }
+        let clamp = |value: usize| value.min(u16::MAX as usize) as u16;
+        self.send(Command::FillRect {
+            x: x as u16,
+            y: y as u16,
+            width: clamp(width),
+            height: clamp(height),
+            color,
+        })
+    }
+
+    /// Queue filling the display with a single color
+    pub fn fill(&self, color: Hub75Color<COLOR_BITS>) -> Result<(), Hub75Error> {
+        self.send(Command::Fill(color))
+    }
+
+    /// Queue clearing the display
+    pub fn clear(&self) -> Result<(), Hub75Error> {
+        self.send(Command::Clear)
+    }
+
+    /// Queue a brightness change
+    pub fn set_brightness(&self, brightness: impl Into<Brightness>) -> Result<(), Hub75Error> {
+        self.send(Command::SetBrightness(brightness.into()))
+    }
+
+    /// Queue presenting the back buffer
+    pub fn swap_buffers(&self) -> Result<(), Hub75Error> {
+        self.send(Command::SwapBuffers)
+    }
+
+    /// Queue a request to read the front buffer
+    ///
+    /// The refresh task runs the closure it passed to
+    /// [`Hub75Receiver::apply_inspecting`] on the front buffer when it gets
+    /// to the request, after every command queued before it and between
+    /// frames, so the closure sees a whole frame without racing the scan.
+    pub fn request_inspect(&self) -> Result<(), Hub75Error> {
+        self.send(Command::InspectFront)
+    }
+
+    /// Get the number of commands that can still be queued
+    pub fn free(&self) -> usize {
+        N - self.queue.len()
+    }
+}
+
+/// Consumer end of a [`SharedHub75`], owned by the refresh task
+pub struct Hub75Receiver<
+    'a,
+    const WIDTH: usize,
+    const HEIGHT: usize,
+    const COLOR_BITS: usize,
+    const N: usize,
+> {
+    /// Queue the commands come from
+    queue: &'a SharedHub75<WIDTH, HEIGHT, COLOR_BITS, N>,
+}
+
+// SAFETY: the receiver only touches `head` and slots the handles have
+// released, so it can run in a different context from them
+unsafe impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize> Send
+    for Hub75Receiver<'_, WIDTH, HEIGHT, COLOR_BITS, N>
+{
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize>
+    Hub75Receiver<'_, WIDTH, HEIGHT, COLOR_BITS, N>
+{
+    /// Take the oldest pending command, if any
+    pub fn recv(&mut self) -> Option<Command<COLOR_BITS>> {
+        let head = self.queue.head.load(Ordering::Relaxed);
+        let tail = self.queue.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        // SAFETY: the slot is inside head..tail, so a handle has written it
+        // and will not touch it until `head` moves past
+        let command = unsafe { (*self.queue.slot(head)).assume_init() };
+        self.queue
+            .head
+            .store(head.wrapping_add(1), Ordering::Release);
+        Some(command)
+    }
+
+    /// Apply every pending command to the display, returning how many were
+    /// applied
+    ///
+    /// Call this between frames. Commands queued while it runs are applied
+    /// too, so a busy producer can delay the next frame; bound the work with
+    /// [`apply_at_most`](Self::apply_at_most) if that matters.
+    pub fn apply<P, B, A, C>(
+        &mut self,
+        display: &mut Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>,
+    ) -> usize
+    where
+        P: OutputPin,
+        A: OutputPin,
+        C: OutputPin,
+        B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS, Pixel = Hub75Color<COLOR_BITS>>,
+    {
+        self.apply_at_most(display, usize::MAX)
+    }
+
+    /// Apply every pending command to the display like
+    /// [`apply`](Self::apply), running `inspect` on the front buffer for
+    /// every [`Command::InspectFront`]
+    ///
+    /// `inspect` runs in the refresh task, so it should only copy what it
+    /// needs, such as into a buffer or channel read by the requesting task.
+    pub fn apply_inspecting<P, B, A, C>(
+        &mut self,
+        display: &mut Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>,
+        mut inspect: impl FnMut(&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>),
+    ) -> usize
+    where
+        P: OutputPin,
+        A: OutputPin,
+        C: OutputPin,
+        B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS, Pixel = Hub75Color<COLOR_BITS>>,
+    {
+        let mut applied = 0;
+        while let Some(command) = self.recv() {
+            if command == Command::InspectFront {
+                display.inspect_front(&mut inspect);
+            } else {
+                let _ = command.apply(display);
+            }
+            applied += 1;
+        }
+        applied
+    }
+
+    /// Apply up to `limit` pending commands to the display, returning how
+    /// many were applied
+    pub fn apply_at_most<P, B, A, C>(
+        &mut self,
+        display: &mut Hub75Display<P, WIDTH, HEIGHT, COLOR_BITS, B, A, C>,
+        limit: usize,
+    ) -> usize
+    where
+        P: OutputPin,
+        A: OutputPin,
+        C: OutputPin,
+        B: BufferStorage<WIDTH, HEIGHT, COLOR_BITS, Pixel = Hub75Color<COLOR_BITS>>,
+    {
+        let mut applied = 0;
+        while applied < limit {
+            let Some(command) = self.recv() else {
+                break;
+            };
+            // Coordinates were checked against the display when queued
+            let _ = command.apply(display);
+            applied += 1;
+        }
+        applied
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+mod embedded_graphics_support {
+    use super::*;
+    use embedded_graphics_core::{
+        draw_target::DrawTarget,
+        geometry::{Dimensions, OriginDimensions, Size},
+        pixelcolor::Rgb565,
+        primitives::Rectangle,
+        Pixel,
+    };
+
+    impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize>
+        OriginDimensions for Hub75Handle<'_, WIDTH, HEIGHT, COLOR_BITS, N>
+    {
+        fn size(&self) -> Size {
+            Size::new(WIDTH as u32, HEIGHT as u32)
+        }
+    }
+
+    /// Each pixel drawn is one command, so prefer filled shapes: solid fills
+    /// are queued as a single [`Command::FillRect`].
+    impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize, const N: usize>
+        DrawTarget for Hub75Handle<'_, WIDTH, HEIGHT, COLOR_BITS, N>
+    {
+        type Color = Rgb565;
+        type Error = Hub75Error;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            for Pixel(point, color) in pixels {
+                if let (Ok(x), Ok(y)) = (usize::try_from(point.x), usize::try_from(point.y)) {
+                    if x < WIDTH && y < HEIGHT {
+                        self.set_pixel(x, y, color.into())?;
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+            let area = area.intersection(&self.bounding_box());
+            if area.size.width == 0 || area.size.height == 0 {
+                return Ok(());
+            }
+            self.fill_rect(
+                area.top_left.x as usize,
+                area.top_left.y as usize,
+                area.size.width as usize,
+                area.size.height as usize,
+                color.into(),
+            )
+        }
+
+        fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+            self.fill(color.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waveform::WaveformRecorder;
+
+    #[test]
+    fn test_queue_order_and_overflow() {
+        let mut shared = SharedHub75::<32, 16, 4, 3>::new();
+        let (handle, mut receiver) = shared.split();
+        let copy = handle;
+
+        handle.clear().unwrap();
+        copy.fill(Hub75Color::white()).unwrap();
+        handle.swap_buffers().unwrap();
+        assert_eq!(handle.free(), 0);
+        assert_eq!(handle.clear(), Err(Hub75Error::BufferOverflow));
+
+        assert_eq!(receiver.recv(), Some(Command::Clear));
+        assert_eq!(handle.free(), 1);
+        handle.set_brightness(Brightness::new(10)).unwrap();
+        assert_eq!(receiver.recv(), Some(Command::Fill(Hub75Color::white())));
+        assert_eq!(receiver.recv(), Some(Command::SwapBuffers));
+        assert_eq!(
+            receiver.recv(),
+            Some(Command::SetBrightness(Brightness::new(10)))
+        );
+        assert_eq!(receiver.recv(), None);
+        assert!(shared.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_pixels_off_the_display() {
+        let mut shared = SharedHub75::<32, 16, 4, 4>::new();
+        let (handle, _receiver) = shared.split();
+        assert_eq!(
+            handle.set_pixel(32, 0, Hub75Color::white()),
+            Err(Hub75Error::InvalidCoordinates)
+        );
+        assert_eq!(handle.fill_rect(40, 0, 2, 2, Hub75Color::white()), Ok(()));
+        assert_eq!(handle.free(), 4);
+    }
+
+    #[test]
+    fn test_apply_to_display() {
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        display.set_double_buffering(true);
+        let red = Hub75Color::new(15, 0, 0);
+
+        let mut shared = SharedHub75::<32, 16, 4, 8>::new();
+        let (handle, mut receiver) = shared.split();
+        handle.fill_rect(30, 14, 10, 10, red).unwrap();
+        handle.set_pixel(1, 2, Hub75Color::white()).unwrap();
+        handle.set_brightness(Brightness::new(42)).unwrap();
+
+        assert_eq!(receiver.apply(&mut display), 3);
+        assert_eq!(display.brightness(), Brightness::new(42));
+        // Nothing is visible until the swap is applied
+        assert_eq!(display.get_pixel(31, 15), Ok(Hub75Color::black()));
+
+        handle.swap_buffers().unwrap();
+        handle.clear().unwrap();
+        assert_eq!(receiver.apply_at_most(&mut display, 1), 1);
+        assert_eq!(display.get_pixel(31, 15), Ok(red));
+        assert_eq!(display.get_pixel(29, 15), Ok(Hub75Color::black()));
+        assert_eq!(display.get_pixel(1, 2), Ok(Hub75Color::white()));
+        assert_eq!(receiver.apply(&mut display), 1);
+    }
+
+    #[test]
+    fn test_apply_inspecting() {
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        display.set_double_buffering(true);
+
+        let mut shared = SharedHub75::<32, 16, 4, 8>::new();
+        let (handle, mut receiver) = shared.split();
+        handle.set_pixel(1, 2, Hub75Color::white()).unwrap();
+        handle.request_inspect().unwrap();
+        handle.swap_buffers().unwrap();
+        handle.request_inspect().unwrap();
+
+        // Each request sees the front buffer as of its place in the queue
+        let mut seen = heapless::Vec::<_, 2>::new();
+        let applied = receiver.apply_inspecting(&mut display, |front| {
+            seen.push(front.get_pixel(1, 2).unwrap()).unwrap();
+        });
+        assert_eq!(applied, 4);
+        assert_eq!(seen, [Hub75Color::black(), Hub75Color::white()]);
+
+        // Without an inspector the requests are skipped
+        handle.request_inspect().unwrap();
+        assert_eq!(receiver.apply(&mut display), 1);
+        assert_eq!(
+            display.inspect_front(|front| front.get_pixel(1, 2)),
+            Ok(Hub75Color::white())
+        );
+        display.with_back_buffer(|back| back.set_pixel(0, 0, Hub75Color::white()).unwrap());
+        assert_eq!(display.get_pixel(0, 0), Ok(Hub75Color::black()));
+    }
+
+    #[cfg(feature = "embedded-graphics")]
+    #[test]
+    fn test_draw_target_queues_fills() {
+        use embedded_graphics_core::{
+            draw_target::DrawTarget, geometry::Point, geometry::Size, pixelcolor::Rgb565,
+            pixelcolor::RgbColor, primitives::Rectangle, Pixel,
+        };
+
+        let mut shared = SharedHub75::<32, 16, 4, 8>::new();
+        let (mut handle, mut receiver) = shared.split();
+        handle
+            .fill_solid(
+                &Rectangle::new(Point::new(-4, 10), Size::new(8, 20)),
+                Rgb565::BLUE,
+            )
+            .unwrap();
+        handle
+            .draw_iter([
+                Pixel(Point::new(3, 3), Rgb565::RED),
+                Pixel(Point::new(-1, 3), Rgb565::RED),
+                Pixel(Point::new(3, 16), Rgb565::RED),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            receiver.recv(),
+            Some(Command::FillRect {
+                x: 0,
+                y: 10,
+                width: 4,
+                height: 6,
+                color: Rgb565::BLUE.into(),
+            })
+        );
+        assert_eq!(
+            receiver.recv(),
+            Some(Command::SetPixel {
+                x: 3,
+                y: 3,
+                color: Rgb565::RED.into(),
+            })
+        );
+        assert_eq!(receiver.recv(), None);
+    }
+}
@@ -0,0 +1,385 @@
+//! Desktop simulation of a panel
+//!
+//! With the `simulator` feature (which needs `std`) the real
+//! [`Hub75Display`](crate::Hub75Display) can run on a desktop: a [`SimPanel`]
+//! hands out pins and a delay that model a HUB75 panel's shift registers,
+//! latches and output enable, and accumulates how long each LED is lit. The
+//! driver's own scanning, bit planes and color correction therefore show up
+//! in the simulated image exactly as they would on hardware, and the result
+//! can be written out as a PNG to check animations, widgets and layouts
+//! before flashing.
+//!
+//! For iterating on content alone, [`write_frame_png`] renders a frame buffer
+//! directly and [`PngRecorder`] saves every frame published to it as a
+//! [`FrameSink`].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::{Hub75Color, Hub75Display};
+//! use hub75::simulator::SimPanel;
+//!
+//! # async fn example() -> Result<(), hub75::Hub75Error> {
+//! let panel = SimPanel::<64, 32>::new();
+//! let mut display = Hub75Display::<_, 64, 32, 6>::new(panel.pins())?;
+//! let mut delay = panel.delay();
+//!
+//! display.fill(Hub75Color::orange());
+//! display.render_frame(&mut delay).await?;
+//! panel.save_png("frame.png", 8).expect("failed to write frame.png");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    vec,
+    vec::Vec,
+};
+
+use embedded_hal::digital::{ErrorType, OutputPin};
+
+use crate::{
+    frame_buffer::Hub75FrameBuffer, pins::Hub75Pins, png::write_leds_png, sink::FrameSink,
+    waveform::Signal, Hub75Error,
+};
+
+/// Signals and light of the simulated panel
+struct State {
+    /// Panel width in pixels
+    width: usize,
+    /// Panel height in pixels
+    height: usize,
+    /// Current level of each pin
+    levels: [bool; Signal::ALL.len()],
+    /// Contents of the column shift registers, oldest first
+    shift: VecDeque<[bool; 6]>,
+    /// Column data latched for display
+    latched: Vec<[bool; 6]>,
+    /// Nanoseconds each channel of each pixel has been lit
+    exposure: Vec<[u64; 3]>,
+    /// Nanoseconds the outputs have been enabled
+    enabled_ns: u64,
+}
+
+impl State {
+    /// Drive a pin, shifting on clock rising edges
+    fn set(&mut self, signal: Signal, level: bool) {
+        let rising = level && !self.levels[signal as usize];
+        self.levels[signal as usize] = level;
+        if signal == Signal::Clk && rising {
+            let mut data = [false; 6];
+            data.copy_from_slice(&self.levels[..6]);
+            self.shift.push_back(data);
+            if self.shift.len() > self.width {
+                self.shift.pop_front();
+            }
+        }
+        // The latch is transparent while LAT is high and holds once it falls
+        if self.levels[Signal::Lat as usize] {
+            // Columns not shifted in since power-up stay dark
+            let missing = self.width - self.shift.len();
+            self.latched.fill([false; 6]);
+            for (x, data) in self.shift.iter().enumerate() {
+                self.latched[missing + x] = *data;
+            }
+        }
+    }
+
+    /// Let time pass, lighting the latched row if the outputs are enabled
+    fn elapse(&mut self, ns: u64) {
+        // OE is active low
+        if self.levels[Signal::Oe as usize] {
+            return;
+        }
+        let rows = self.height / 2;
+        let address = Signal::ADDRESS
+            .iter()
+            .enumerate()
+            .filter(|(_, &signal)| self.levels[signal as usize])
+            .fold(0, |address, (bit, _)| address | 1 << bit)
+            % rows;
+
+        for (x, data) in self.latched.iter().enumerate() {
+            for (half, y) in [address, address + rows].into_iter().enumerate() {
+                let pixel = &mut self.exposure[y * self.width + x];
+                for channel in 0..3 {
+                    if data[half * 3 + channel] {
+                        pixel[channel] += ns;
+                    }
+                }
+            }
+        }
+        self.enabled_ns += ns;
+    }
+}
+
+/// Output pin driving a simulated panel
+pub struct SimPin {
+    /// Panel the pin belongs to
+    state: Rc<RefCell<State>>,
+    /// Signal the pin carries
+    signal: Signal,
+}
+
+impl ErrorType for SimPin {
+    type Error = core::convert::Infallible;
+}
+
+impl OutputPin for SimPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.state.borrow_mut().set(self.signal, false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.state.borrow_mut().set(self.signal, true);
+        Ok(())
+    }
+}
+
+/// Delay that advances a simulated panel's clock instead of waiting
+pub struct SimDelay {
+    /// Panel whose clock is advanced
+    state: Rc<RefCell<State>>,
+}
+
+impl embedded_hal_async::delay::DelayNs for SimDelay {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.state.borrow_mut().elapse(ns as u64);
+    }
+}
+
+/// Simulated HUB75 panel
+///
+/// The panel records how long each LED has been lit since it was created or
+/// since [`clear_exposure`](Self::clear_exposure), and reports the share of
+/// the time a row is scanned as the LED's brightness.
+pub struct SimPanel<const WIDTH: usize, const HEIGHT: usize> {
+    /// State shared with the pins and delay
+    state: Rc<RefCell<State>>,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize> SimPanel<WIDTH, HEIGHT> {
+    /// Create a dark panel
+    pub fn new() -> Self {
+        Self {
+            state: Rc::new(RefCell::new(State {
+                width: WIDTH,
+                height: HEIGHT,
+                levels: [false; Signal::ALL.len()],
+                shift: VecDeque::with_capacity(WIDTH),
+                latched: vec![[false; 6]; WIDTH],
+                exposure: vec![[0; 3]; WIDTH * HEIGHT],
+                enabled_ns: 0,
+            })),
+        }
+    }
+
+    /// Create the pins to build a [`Hub75Display`](crate::Hub75Display) on
+    ///
+    /// Address pins D and E are included as the panel's height requires.
+    pub fn pins(&self) -> Hub75Pins<SimPin> {
+        let pin = |signal| SimPin {
+            state: self.state.clone(),
+            signal,
+        };
+        let rows = HEIGHT / 2;
+        Hub75Pins::new(
+            pin(Signal::R1),
+            pin(Signal::G1),
+            pin(Signal::B1),
+            pin(Signal::R2),
+            pin(Signal::G2),
+            pin(Signal::B2),
+            pin(Signal::A),
+            pin(Signal::B),
+            pin(Signal::C),
+            (rows > 8).then(|| pin(Signal::D)),
+            (rows > 16).then(|| pin(Signal::E)),
+            pin(Signal::Clk),
+            pin(Signal::Lat),
+            pin(Signal::Oe),
+        )
+    }
+
+    /// Create a delay that advances the panel's clock
+    pub fn delay(&self) -> SimDelay {
+        SimDelay {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Forget the light recorded so far
+    pub fn clear_exposure(&self) {
+        let mut state = self.state.borrow_mut();
+        state.exposure.fill([0; 3]);
+        state.enabled_ns = 0;
+    }
+
+    /// Get the apparent RGB888 color of an LED
+    pub fn pixel(&self, x: usize, y: usize) -> Option<(u8, u8, u8)> {
+        if x >= WIDTH || y >= HEIGHT {
+            return None;
+        }
+        let state = self.state.borrow();
+        let level = |ns: u64| match state.enabled_ns {
+            0 => 0,
+            total => (ns * (HEIGHT as u64 / 2) * 255 / total).min(255) as u8,
+        };
+        let [r, g, b] = state.exposure[y * WIDTH + x];
+        Some((level(r), level(g), level(b)))
+    }
+
+    /// Get the apparent colors of all LEDs as RGB888 rows
+    pub fn to_rgb8(&self) -> Vec<u8> {
+        let mut image = Vec::with_capacity(WIDTH * HEIGHT * 3);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let (r, g, b) = self.pixel(x, y).unwrap_or_default();
+                image.extend_from_slice(&[r, g, b]);
+            }
+        }
+        image
+    }
+
+    /// Write the panel as a PNG with each LED drawn `scale` pixels wide
+    pub fn write_png(&self, writer: impl Write, scale: usize) -> io::Result<()> {
+        write_leds_png(writer, WIDTH, HEIGHT, &self.to_rgb8(), scale)
+    }
+
+    /// Save the panel as a PNG file with each LED drawn `scale` pixels wide
+    pub fn save_png(&self, path: impl AsRef<Path>, scale: usize) -> io::Result<()> {
+        self.write_png(BufWriter::new(File::create(path)?), scale)
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize> Default for SimPanel<WIDTH, HEIGHT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Write a frame buffer as a PNG with each pixel drawn `scale` pixels wide
+pub fn write_frame_png<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+    frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    writer: impl Write,
+    scale: usize,
+) -> io::Result<()> {
+    let mut image = Vec::with_capacity(WIDTH * HEIGHT * 3);
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let (r, g, b) = frame.get_pixel(x, y).unwrap_or_default().to_rgb8();
+            image.extend_from_slice(&[r, g, b]);
+        }
+    }
+    write_leds_png(writer, WIDTH, HEIGHT, &image, scale)
+}
+
+/// Frame sink saving every frame as a numbered PNG file
+///
+/// Files are named `frame_00000.png`, `frame_00001.png` and so on. Errors
+/// writing them are reported as [`Hub75Error::StorageError`].
+pub struct PngRecorder {
+    /// Directory the frames are saved in
+    directory: PathBuf,
+    /// Pixels per LED in the saved images
+    scale: usize,
+    /// Frames saved so far
+    frames: usize,
+}
+
+impl PngRecorder {
+    /// Create a recorder saving frames into an existing `directory`
+    pub fn new(directory: impl Into<PathBuf>, scale: usize) -> Self {
+        Self {
+            directory: directory.into(),
+            scale,
+            frames: 0,
+        }
+    }
+
+    /// Get the number of frames saved
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSink<WIDTH, HEIGHT, COLOR_BITS> for PngRecorder
+{
+    fn publish(
+        &mut self,
+        frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        let path = self
+            .directory
+            .join(std::format!("frame_{:05}.png", self.frames));
+        File::create(path)
+            .and_then(|file| write_frame_png(frame, BufWriter::new(file), self.scale))
+            .map_err(|_| Hub75Error::StorageError)?;
+        self.frames += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::Hub75Color, Hub75Display};
+    use core::future::Future;
+    use core::task::{Context, Poll, Waker};
+
+    #[test]
+    fn test_simulated_display() {
+        let panel = SimPanel::<16, 16>::new();
+        let mut display = Hub75Display::<_, 16, 16, 6>::new(panel.pins()).unwrap();
+        let mut delay = panel.delay();
+
+        display.set_pixel(3, 2, Hub75Color::red()).unwrap();
+        display.set_pixel(7, 0, Hub75Color::green()).unwrap();
+        display.set_pixel(15, 12, Hub75Color::blue()).unwrap();
+        display
+            .set_pixel(0, 15, Hub75Color::new(21, 63, 0))
+            .unwrap();
+        let result = {
+            let mut future = core::pin::pin!(display.render_frame(&mut delay));
+            future
+                .as_mut()
+                .poll(&mut Context::from_waker(Waker::noop()))
+        };
+        assert!(matches!(result, Poll::Ready(Ok(()))));
+
+        assert_eq!(panel.pixel(3, 2), Some((255, 0, 0)));
+        assert_eq!(panel.pixel(7, 0), Some((0, 255, 0)));
+        assert_eq!(panel.pixel(15, 12), Some((0, 0, 255)));
+        // A third of full red through bit code modulation
+        assert_eq!(panel.pixel(0, 15), Some((85, 255, 0)));
+        assert_eq!(panel.pixel(4, 2), Some((0, 0, 0)));
+        assert_eq!(panel.pixel(16, 0), None);
+
+        panel.clear_exposure();
+        assert_eq!(panel.pixel(3, 2), Some((0, 0, 0)));
+    }
+
+    #[test]
+    fn test_png() {
+        let mut frame = Hub75FrameBuffer::<2, 1, 6>::new();
+        frame.set_pixel(1, 0, Hub75Color::white()).unwrap();
+        let mut png = Vec::new();
+        write_frame_png(&frame, &mut png, 1).unwrap();
+
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR"));
+        assert_eq!(&png[16..24], &[0, 0, 0, 2, 0, 0, 0, 1]);
+        assert!(png.ends_with(b"IEND\xae\x42\x60\x82"));
+        // One stored block holding the filter byte and both pixels
+        let idat = &png[33 + 8..];
+        assert_eq!(&idat[..7], &[0x78, 0x01, 1, 7, 0, !7, 0xFF]);
+        assert_eq!(&idat[7..14], &[0, 0, 0, 0, 252, 252, 252]);
+    }
+}
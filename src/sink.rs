@@ -0,0 +1,70 @@
+//! Publishing completed frames
+//!
+//! A [`FrameSink`] receives every frame the display presents, for recording
+//! to external flash, mirroring onto a second output or streaming back to a
+//! host for debugging. Pass a sink to
+//! [`Hub75Display::swap_buffers_into`](crate::Hub75Display::swap_buffers_into)
+//! in place of [`swap_buffers`](crate::Hub75Display::swap_buffers) and it is
+//! handed the new front buffer right after the swap.
+//!
+//! Sinks are implemented for frame buffers, which keep a copy of the last
+//! frame, and for closures taking the frame.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{FrameSink, Hub75Color, Hub75FrameBuffer};
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! frame.fill(Hub75Color::blue());
+//!
+//! // Count presented frames while mirroring them
+//! let mut presented = 0;
+//! let mut counter = |_: &Hub75FrameBuffer<64, 32, 6>| presented += 1;
+//! let mut mirror = Hub75FrameBuffer::<64, 32, 6>::new();
+//! counter.publish(&frame).unwrap();
+//! mirror.publish(&frame).unwrap();
+//!
+//! assert_eq!(presented, 1);
+//! assert_eq!(mirror.get_pixel(0, 0).unwrap(), Hub75Color::blue());
+//! ```
+
+use crate::{frame_buffer::Hub75FrameBuffer, Hub75Error};
+
+/// Destination for completed frames
+pub trait FrameSink<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Receive a frame that has just been presented
+    ///
+    /// The frame is only borrowed for the call; sinks that need it later must
+    /// copy or encode it.
+    fn publish(
+        &mut self,
+        frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error>;
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSink<WIDTH, HEIGHT, COLOR_BITS> for Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn publish(
+        &mut self,
+        frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        self.copy_from(frame);
+        Ok(())
+    }
+}
+
+impl<F, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSink<WIDTH, HEIGHT, COLOR_BITS> for F
+where
+    F: FnMut(&Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>),
+{
+    fn publish(
+        &mut self,
+        frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        self(frame);
+        Ok(())
+    }
+}
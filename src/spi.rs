@@ -0,0 +1,294 @@
+//! Clocking pixel data out through an SPI peripheral
+//!
+//! On MCUs without PIO, I2S or a parallel DMA output, toggling CLK from
+//! software is what limits the refresh rate. An [`SpiOutput`] lets an SPI
+//! peripheral generate the clock instead: SCK is wired to the panel's CLK and
+//! MOSI either carries one of the six color lines or is left unconnected.
+//! Rows are packed into bytes, eight columns to a byte with the leftmost
+//! column sent first, and handed to the bus in as few writes as possible.
+//!
+//! The color lines not on MOSI stay on GPIO and can only change between
+//! bytes, so they have a resolution of eight columns: each group of eight
+//! columns lights a GPIO line if any of its columns does. This suits message
+//! boards that only use one color on one half, or that tie the color inputs
+//! together, and panels showing coarse content such as large text and bars.
+//!
+//! The bus must use SPI mode 0, MSB first, since panels shift data in on the
+//! rising edge of CLK. The `clk` pin given to the display is held low and can
+//! be any spare pin. `WIDTH` must be a multiple of 8. Address, latch and
+//! output enable stay on GPIO as usual; see
+//! [`Hub75Display::render_frame_spi`](crate::Hub75Display::render_frame_spi).
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::{Hub75Display, Hub75Pins};
+//! use hub75::spi::{DataLine, SpiOutput};
+//! use embedded_hal::{digital::OutputPin, spi::SpiBus};
+//! use embedded_hal_async::delay::DelayNs;
+//!
+//! # async fn example<P: OutputPin + 'static, S: SpiBus>(
+//! #     pins: Hub75Pins<P>,
+//! #     spi: S,
+//! #     delay: &mut impl DelayNs,
+//! # ) -> Result<(), hub75::Hub75Error> {
+//! // MOSI drives R1; SCK drives CLK
+//! let mut output = SpiOutput::new(spi, Some(DataLine::R1));
+//! let mut display = Hub75Display::<_, 64, 32, 6>::new(pins)?;
+//!
+//! loop {
+//!     display.render_frame_spi(&mut output, delay).await?;
+//! }
+//! # }
+//! ```
+
+use crate::{display::RowShifter, pin_op, pins::Hub75RgbPins, Hub75Error};
+use embedded_hal::{digital::OutputPin, spi::SpiBus};
+
+/// Bytes packed before they are written to the bus
+const CHUNK_BYTES: usize = 32;
+
+/// Color data line of the panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DataLine {
+    /// Red data for the upper half
+    R1,
+    /// Green data for the upper half
+    G1,
+    /// Blue data for the upper half
+    B1,
+    /// Red data for the lower half
+    R2,
+    /// Green data for the lower half
+    G2,
+    /// Blue data for the lower half
+    B2,
+}
+
+impl DataLine {
+    /// Every line, in the order of a column's pin levels
+    pub const ALL: [DataLine; 6] = [
+        DataLine::R1,
+        DataLine::G1,
+        DataLine::B1,
+        DataLine::R2,
+        DataLine::G2,
+        DataLine::B2,
+    ];
+
+    /// Get the line's level from a column's pin levels
+    fn level(self, levels: &(bool, bool, bool, bool, bool, bool)) -> bool {
+        match self {
+            DataLine::R1 => levels.0,
+            DataLine::G1 => levels.1,
+            DataLine::B1 => levels.2,
+            DataLine::R2 => levels.3,
+            DataLine::G2 => levels.4,
+            DataLine::B2 => levels.5,
+        }
+    }
+
+    /// Get the GPIO pin driving the line
+    fn pin<P: OutputPin>(self, rgb: &mut Hub75RgbPins<P>) -> &mut P {
+        match self {
+            DataLine::R1 => &mut rgb.r1,
+            DataLine::G1 => &mut rgb.g1,
+            DataLine::B1 => &mut rgb.b1,
+            DataLine::R2 => &mut rgb.r2,
+            DataLine::G2 => &mut rgb.g2,
+            DataLine::B2 => &mut rgb.b2,
+        }
+    }
+}
+
+/// SPI bus generating the panel clock
+pub struct SpiOutput<S> {
+    /// Bus with SCK wired to CLK
+    spi: S,
+    /// Line wired to MOSI, if any
+    line: Option<DataLine>,
+}
+
+impl<S: SpiBus> SpiOutput<S> {
+    /// Create an output on a bus, with MOSI wired to `line` or unconnected
+    pub fn new(spi: S, line: Option<DataLine>) -> Self {
+        Self { spi, line }
+    }
+
+    /// Get the line wired to MOSI
+    pub fn line(&self) -> Option<DataLine> {
+        self.line
+    }
+
+    /// Release the bus
+    pub fn release(self) -> S {
+        self.spi
+    }
+
+    /// Pack the MOSI line of up to eight columns into a byte, first column in
+    /// the most significant bit
+    pub fn pack_byte(&self, columns: &[(bool, bool, bool, bool, bool, bool)]) -> u8 {
+        let Some(line) = self.line else {
+            return 0;
+        };
+        columns
+            .iter()
+            .take(8)
+            .enumerate()
+            .filter(|(_, levels)| line.level(levels))
+            .fold(0, |byte, (i, _)| byte | 0x80 >> i)
+    }
+
+    /// Write bytes to the bus
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Hub75Error> {
+        if !bytes.is_empty() {
+            pin_op!(self.spi.write(bytes));
+        }
+        Ok(())
+    }
+
+    /// Drive the lines not wired to MOSI
+    fn set_gpio<P: OutputPin>(
+        &self,
+        levels: [bool; 6],
+        rgb: &mut Hub75RgbPins<P>,
+    ) -> Result<(), Hub75Error> {
+        for (line, level) in DataLine::ALL.into_iter().zip(levels) {
+            if Some(line) == self.line {
+                continue;
+            }
+            let pin = line.pin(rgb);
+            if level {
+                pin_op!(pin.set_high());
+            } else {
+                pin_op!(pin.set_low());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: SpiBus> RowShifter for SpiOutput<S> {
+    /// Shift a row of column levels into the panel
+    ///
+    /// The GPIO lines are updated between bytes whenever a group of eight
+    /// columns needs different levels from the one before, after waiting for
+    /// the bus to finish. The bus is idle when this returns, so the row can
+    /// be latched straight away.
+    fn shift_row<P: OutputPin>(
+        &mut self,
+        columns: &[(bool, bool, bool, bool, bool, bool)],
+        rgb: &mut Hub75RgbPins<P>,
+    ) -> Result<(), Hub75Error> {
+        if columns.len() % 8 != 0 {
+            return Err(Hub75Error::InvalidCoordinates);
+        }
+
+        let mut chunk = [0u8; CHUNK_BYTES];
+        let mut pending = 0;
+        let mut gpio = None;
+        for group in columns.chunks_exact(8) {
+            let levels = DataLine::ALL.map(|line| group.iter().any(|c| line.level(c)));
+            if gpio != Some(levels) || pending == CHUNK_BYTES {
+                self.write(&chunk[..pending])?;
+                pending = 0;
+            }
+            if gpio != Some(levels) {
+                // Let the bus finish before data lines change under the clock
+                pin_op!(self.spi.flush());
+                self.set_gpio(levels, rgb)?;
+                gpio = Some(levels);
+            }
+            chunk[pending] = self.pack_byte(group);
+            pending += 1;
+        }
+        self.write(&chunk[..pending])?;
+        pin_op!(self.spi.flush());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::waveform::{Signal, WaveformRecorder};
+    #[cfg(feature = "async")]
+    use crate::{color::Hub75Color, Hub75Display};
+    #[cfg(feature = "async")]
+    use core::future::Future;
+    #[cfg(feature = "async")]
+    use core::task::{Context, Poll, Waker};
+
+    const OFF: (bool, bool, bool, bool, bool, bool) = (false, false, false, false, false, false);
+
+    #[cfg(feature = "async")]
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = core::pin::pin!(future);
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("recording futures never wait"),
+        }
+    }
+
+    #[test]
+    fn test_pack_byte() {
+        let mut columns = [OFF; 8];
+        columns[0].0 = true;
+        columns[6].0 = true;
+        columns[3].3 = true;
+
+        let recorder = WaveformRecorder::new();
+        let output = SpiOutput::new(recorder.spi(None), Some(DataLine::R1));
+        assert_eq!(output.pack_byte(&columns), 0b1000_0010);
+        let output = SpiOutput::new(recorder.spi(None), Some(DataLine::R2));
+        assert_eq!(output.pack_byte(&columns), 0b0001_0000);
+        let output = SpiOutput::new(recorder.spi(None), None);
+        assert_eq!(output.pack_byte(&columns), 0);
+    }
+
+    #[test]
+    fn test_shift_row() {
+        let recorder = WaveformRecorder::new();
+        let mut pins = recorder.pins(3);
+        let mut output = SpiOutput::new(recorder.spi(Some(Signal::B1)), Some(DataLine::B1));
+
+        // Blue on MOSI in column 9, green on GPIO in the last group
+        let mut columns = [OFF; 24];
+        columns[9].2 = true;
+        columns[20].1 = true;
+        output.shift_row(&columns, &mut pins.rgb).unwrap();
+
+        assert_eq!(recorder.rising_edges(Signal::Clk), 24);
+        assert_eq!(recorder.rising_edges(Signal::B1), 1);
+        assert_eq!(recorder.rising_edges(Signal::G1), 1);
+        assert!(recorder.level(Signal::G1));
+        assert!(!recorder.level(Signal::Clk));
+
+        // Rows must fill whole bytes
+        assert_eq!(
+            output.shift_row(&columns[..12], &mut pins.rgb),
+            Err(Hub75Error::InvalidCoordinates)
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_render_frame_follows_protocol() {
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        let mut output = SpiOutput::new(recorder.spi(Some(Signal::R1)), Some(DataLine::R1));
+        let mut delay = recorder.delay();
+        display.set_pixel(5, 3, Hub75Color::white()).unwrap();
+        display
+            .set_pixel(31, 15, Hub75Color::new(7, 0, 15))
+            .unwrap();
+
+        block_on(display.render_frame_spi(&mut output, &mut delay)).unwrap();
+        assert_eq!(recorder.check_protocol(32, 8), Ok(()));
+        assert_eq!(recorder.rising_edges(Signal::Clk), 4 * 8 * 32);
+    }
+}
@@ -0,0 +1,671 @@
+//! Streaming frames from a host over a serial link
+//!
+//! A small framed protocol lets a PC or single-board computer send live
+//! content over a UART or USB-CDC link. A [`StreamReceiver`] parses the byte
+//! stream into updates of its own frame buffer and reports each packet as a
+//! [`StreamEvent`]; the application copies the frame to the display when the
+//! host sends [`PacketKind::Present`].
+//!
+//! # Packet format
+//!
+//! | Field   | Size     | Contents                                        |
+//! |---------|----------|-------------------------------------------------|
+//! | Magic   | 2        | `b"HB"`                                         |
+//! | Kind    | 1        | A [`PacketKind`]                                |
+//! | Length  | 2        | Payload length, little endian                   |
+//! | Payload | `Length` | Depends on the kind                             |
+//! | CRC     | 2        | CRC-16/CCITT-FALSE of kind, length and payload, little endian |
+//!
+//! [`PacketKind::Raw`] and [`PacketKind::Rle`] payloads start with a region
+//! header of `x`, `y`, `width` and `height` as little endian `u16`s, followed
+//! by RGB888 pixels row by row, or by `count, r, g, b` runs. Sending only the
+//! region that changed keeps the link's bandwidth for what matters;
+//! [`DirtyRows`] finds the rows that changed between frames.
+//!
+//! Pixels are written as they arrive, so a packet that fails its CRC check
+//! leaves part of its region updated; the host should resend the frame.
+//! After any error the receiver hunts for the next magic bytes to recover.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::stream::{self, PacketKind, StreamEvent, StreamReceiver};
+//!
+//! // What a host would send: one red pixel at (2, 1), then present
+//! let mut payload = [0u8; 11];
+//! payload[..8].copy_from_slice(&stream::region_header(2, 1, 1, 1));
+//! payload[8..].copy_from_slice(&[255, 0, 0]);
+//! let mut bytes = [0u8; 32];
+//! let mut length = stream::encode_packet(PacketKind::Raw, &payload, &mut bytes).unwrap();
+//! length += stream::encode_packet(PacketKind::Present, &[], &mut bytes[length..]).unwrap();
+//!
+//! let mut receiver = StreamReceiver::<64, 32, 6>::new();
+//! let mut display_frame = Hub75FrameBuffer::new();
+//! for &byte in &bytes[..length] {
+//!     if let Some(StreamEvent::Present) = receiver.push(byte) {
+//!         display_frame.copy_from(receiver.frame());
+//!     }
+//! }
+//! assert_eq!(display_frame.get_pixel(2, 1).unwrap(), Hub75Color::red());
+//! ```
+
+use core::{future::Future, ops::Range};
+
+use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer, Hub75Error};
+
+/// Bytes starting every packet
+pub const MAGIC: [u8; 2] = *b"HB";
+/// Size of a region header
+pub const REGION_HEADER_LEN: usize = 8;
+/// Bytes of framing around the payload
+pub const PACKET_OVERHEAD: usize = 7;
+
+/// Type of a packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum PacketKind {
+    /// A region header followed by uncompressed RGB888 pixels
+    Raw = 0x01,
+    /// A region header followed by `count, r, g, b` runs
+    Rle = 0x02,
+    /// Show the frame built so far; no payload
+    Present = 0x03,
+}
+
+impl PacketKind {
+    /// Kind with the given code
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0x01 => Some(Self::Raw),
+            0x02 => Some(Self::Rle),
+            0x03 => Some(Self::Present),
+            _ => None,
+        }
+    }
+}
+
+/// Reasons a packet was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StreamError {
+    /// The packet kind is not known
+    UnknownKind,
+    /// The region lies outside the frame
+    OutOfBounds,
+    /// The payload length does not match the region
+    BadLength,
+    /// The checksum did not match
+    BadCrc,
+}
+
+/// Outcome of a complete packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StreamEvent {
+    /// Pixels of the receiver's frame were updated
+    Updated,
+    /// The host asked for the frame to be shown
+    Present,
+    /// A packet was rejected
+    Error(StreamError),
+}
+
+/// Source of bytes for [`StreamReceiver::receive`], such as a UART driver
+pub trait ByteReader {
+    /// Error reported by the underlying link
+    type Error;
+
+    /// Read at least one byte into `buffer`, returning how many were read
+    fn read(&mut self, buffer: &mut [u8]) -> impl Future<Output = Result<usize, Self::Error>>;
+}
+
+/// Position in the packet being parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Waiting for the first magic byte
+    Magic0,
+    /// Waiting for the second magic byte
+    Magic1,
+    /// Waiting for the kind
+    Kind,
+    /// Waiting for the low length byte
+    Length0,
+    /// Waiting for the high length byte
+    Length1,
+    /// Inside the payload
+    Payload,
+    /// Waiting for the low CRC byte
+    Crc0,
+    /// Waiting for the high CRC byte
+    Crc1,
+}
+
+/// Parses the streaming protocol into a frame buffer
+pub struct StreamReceiver<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Frame being built
+    frame: Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    /// Parser state
+    state: State,
+    /// Kind code of the current packet
+    kind: u8,
+    /// Payload length of the current packet
+    length: u16,
+    /// Payload bytes received so far
+    received: u16,
+    /// Running checksum of the current packet
+    crc: u16,
+    /// Low byte of the received checksum
+    crc_low: u8,
+    /// First problem found in the current packet
+    error: Option<StreamError>,
+    /// Region header being collected
+    header: [u8; REGION_HEADER_LEN],
+    /// Partial pixel or run being collected
+    group: [u8; 4],
+    /// Bytes of `group` filled
+    group_len: usize,
+    /// Pixels of the region written so far
+    pixel: usize,
+    /// Bytes read from the link but not parsed yet
+    pending: [u8; 64],
+    /// Range of `pending` still to parse
+    pending_range: (usize, usize),
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    StreamReceiver<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a receiver with a blank frame
+    pub fn new() -> Self {
+        Self {
+            frame: Hub75FrameBuffer::new(),
+            state: State::Magic0,
+            kind: 0,
+            length: 0,
+            received: 0,
+            crc: 0,
+            crc_low: 0,
+            error: None,
+            header: [0; REGION_HEADER_LEN],
+            group: [0; 4],
+            group_len: 0,
+            pixel: 0,
+            pending: [0; 64],
+            pending_range: (0, 0),
+        }
+    }
+
+    /// Get the frame built from the packets received so far
+    pub fn frame(&self) -> &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS> {
+        &self.frame
+    }
+
+    /// Parse one byte, returning an event when it completes a packet
+    pub fn push(&mut self, byte: u8) -> Option<StreamEvent> {
+        match self.state {
+            State::Magic0 => {
+                if byte == MAGIC[0] {
+                    self.state = State::Magic1;
+                }
+            }
+            State::Magic1 => {
+                self.state = match byte {
+                    _ if byte == MAGIC[1] => State::Kind,
+                    _ if byte == MAGIC[0] => State::Magic1,
+                    _ => State::Magic0,
+                };
+            }
+            State::Kind => {
+                self.kind = byte;
+                self.crc = crc16_update(0xFFFF, byte);
+                self.error = None;
+                self.state = State::Length0;
+            }
+            State::Length0 => {
+                self.length = byte as u16;
+                self.crc = crc16_update(self.crc, byte);
+                self.state = State::Length1;
+            }
+            State::Length1 => {
+                self.length |= (byte as u16) << 8;
+                self.crc = crc16_update(self.crc, byte);
+                self.received = 0;
+                self.group_len = 0;
+                self.pixel = 0;
+                self.start_payload();
+            }
+            State::Payload => {
+                self.crc = crc16_update(self.crc, byte);
+                self.payload_byte(byte);
+                self.received += 1;
+                if self.received == self.length {
+                    self.finish_payload();
+                    self.state = State::Crc0;
+                }
+            }
+            State::Crc0 => {
+                self.crc_low = byte;
+                self.state = State::Crc1;
+            }
+            State::Crc1 => {
+                self.state = State::Magic0;
+                let crc = u16::from_le_bytes([self.crc_low, byte]);
+                let error = if crc != self.crc {
+                    Some(StreamError::BadCrc)
+                } else {
+                    self.error
+                };
+                return Some(match (error, PacketKind::from_code(self.kind)) {
+                    (Some(error), _) => StreamEvent::Error(error),
+                    (None, Some(PacketKind::Present)) => StreamEvent::Present,
+                    (None, _) => StreamEvent::Updated,
+                });
+            }
+        }
+        None
+    }
+
+    /// Parse bytes until one completes a packet
+    ///
+    /// Returns the number of bytes consumed and the event, if any. Bytes
+    /// after the event are left for the next call.
+    pub fn feed(&mut self, bytes: &[u8]) -> (usize, Option<StreamEvent>) {
+        for (i, &byte) in bytes.iter().enumerate() {
+            if let Some(event) = self.push(byte) {
+                return (i + 1, Some(event));
+            }
+        }
+        (bytes.len(), None)
+    }
+
+    /// Read from `reader` until a packet is complete
+    ///
+    /// Bytes read past the end of the packet are kept for the next call.
+    /// Only errors from the link are returned as errors; rejected packets are
+    /// reported as [`StreamEvent::Error`].
+    pub async fn receive<R: ByteReader>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<StreamEvent, R::Error> {
+        loop {
+            let (start, end) = self.pending_range;
+            if start == end {
+                let mut pending = self.pending;
+                let read = reader.read(&mut pending).await?.min(pending.len());
+                self.pending = pending;
+                self.pending_range = (0, read);
+                continue;
+            }
+
+            let pending = self.pending;
+            let (consumed, event) = self.feed(&pending[start..end]);
+            self.pending_range.0 += consumed;
+            if let Some(event) = event {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Check the packet header before its payload arrives
+    fn start_payload(&mut self) {
+        match PacketKind::from_code(self.kind) {
+            None => self.error = Some(StreamError::UnknownKind),
+            Some(PacketKind::Present) if self.length != 0 => {
+                self.error = Some(StreamError::BadLength)
+            }
+            Some(PacketKind::Raw | PacketKind::Rle)
+                if (self.length as usize) < REGION_HEADER_LEN =>
+            {
+                self.error = Some(StreamError::BadLength)
+            }
+            _ => {}
+        }
+        self.state = if self.length == 0 {
+            State::Crc0
+        } else {
+            State::Payload
+        };
+    }
+
+    /// Region of the current packet as `(x, y, width, height)`
+    fn region(&self) -> (usize, usize, usize, usize) {
+        let value = |at: usize| u16::from_le_bytes([self.header[at], self.header[at + 1]]) as usize;
+        (value(0), value(2), value(4), value(6))
+    }
+
+    /// Handle a payload byte
+    fn payload_byte(&mut self, byte: u8) {
+        if self.error.is_some() {
+            return;
+        }
+        let index = self.received as usize;
+        if index < REGION_HEADER_LEN {
+            self.header[index] = byte;
+            if index + 1 == REGION_HEADER_LEN {
+                self.check_region();
+            }
+            return;
+        }
+
+        self.group[self.group_len] = byte;
+        self.group_len += 1;
+        let rle = self.kind == PacketKind::Rle as u8;
+        match (rle, self.group_len) {
+            (false, 3) => {
+                let [r, g, b, _] = self.group;
+                self.write_pixels(1, Hub75Color::from_rgb8(r, g, b));
+                self.group_len = 0;
+            }
+            (true, 4) => {
+                let [count, r, g, b] = self.group;
+                self.write_pixels(count as usize, Hub75Color::from_rgb8(r, g, b));
+                self.group_len = 0;
+            }
+            _ => {}
+        }
+    }
+
+    /// Check that the region fits the frame and matches the payload length
+    fn check_region(&mut self) {
+        let (x, y, width, height) = self.region();
+        let pixels_length = self.length as usize - REGION_HEADER_LEN;
+        let length_ok = if self.kind == PacketKind::Rle as u8 {
+            pixels_length % 4 == 0
+        } else {
+            pixels_length == width * height * 3
+        };
+        if x + width > WIDTH || y + height > HEIGHT {
+            self.error = Some(StreamError::OutOfBounds);
+        } else if !length_ok {
+            self.error = Some(StreamError::BadLength);
+        }
+    }
+
+    /// Write the next `count` pixels of the region
+    fn write_pixels(&mut self, count: usize, color: Hub75Color<COLOR_BITS>) {
+        let (x, y, width, height) = self.region();
+        if self.pixel + count > width * height {
+            self.error = Some(StreamError::BadLength);
+            return;
+        }
+        for pixel in self.pixel..self.pixel + count {
+            let _ = self
+                .frame
+                .set_pixel(x + pixel % width, y + pixel / width, color);
+        }
+        self.pixel += count;
+    }
+
+    /// Check that the payload filled its region
+    fn finish_payload(&mut self) {
+        if self.error.is_none() && self.kind == PacketKind::Rle as u8 {
+            let (_, _, width, height) = self.region();
+            if self.pixel != width * height {
+                self.error = Some(StreamError::BadLength);
+            }
+        }
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> Default
+    for StreamReceiver<WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Region header for a [`PacketKind::Raw`] or [`PacketKind::Rle`] payload
+pub fn region_header(x: u16, y: u16, width: u16, height: u16) -> [u8; REGION_HEADER_LEN] {
+    let mut header = [0; REGION_HEADER_LEN];
+    for (chunk, value) in header.chunks_exact_mut(2).zip([x, y, width, height]) {
+        chunk.copy_from_slice(&value.to_le_bytes());
+    }
+    header
+}
+
+/// Frame a payload as a packet, returning the number of bytes written
+///
+/// Returns [`Hub75Error::BufferOverflow`] if `out` is shorter than the
+/// payload plus [`PACKET_OVERHEAD`] or the payload exceeds 65535 bytes.
+pub fn encode_packet(
+    kind: PacketKind,
+    payload: &[u8],
+    out: &mut [u8],
+) -> Result<usize, Hub75Error> {
+    let total = payload.len() + PACKET_OVERHEAD;
+    if payload.len() > u16::MAX as usize || out.len() < total {
+        return Err(Hub75Error::BufferOverflow);
+    }
+    out[..2].copy_from_slice(&MAGIC);
+    out[2] = kind as u8;
+    out[3..5].copy_from_slice(&(payload.len() as u16).to_le_bytes());
+    out[5..5 + payload.len()].copy_from_slice(payload);
+    let crc = out[2..5 + payload.len()]
+        .iter()
+        .fold(0xFFFF, |crc, &byte| crc16_update(crc, byte));
+    out[5 + payload.len()..total].copy_from_slice(&crc.to_le_bytes());
+    Ok(total)
+}
+
+/// Tracks which rows of a frame changed since it was last sent
+///
+/// Stores a [`Hub75FrameBuffer::row_hash`] per row, so a sender can keep one
+/// of these next to its frame and put only the changed rows in the next
+/// [`PacketKind::Raw`] or [`PacketKind::Rle`] region instead of the whole
+/// frame.
+#[derive(Debug, Clone)]
+pub struct DirtyRows<const HEIGHT: usize> {
+    /// Hash of each row as last sent; `None` until the row is first sent
+    hashes: [Option<u32>; HEIGHT],
+}
+
+impl<const HEIGHT: usize> DirtyRows<HEIGHT> {
+    /// Create a tracker that reports every row as changed on first use
+    pub const fn new() -> Self {
+        Self {
+            hashes: [None; HEIGHT],
+        }
+    }
+
+    /// Record the rows of `frame` as sent, returning the band of rows that
+    /// changed since the previous call
+    ///
+    /// The band runs from the first to the last changed row, so it can be sent
+    /// as a single region. Returns `None` if no row changed.
+    pub fn update<const WIDTH: usize, const COLOR_BITS: usize>(
+        &mut self,
+        frame: &Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Option<Range<usize>> {
+        let mut band: Option<Range<usize>> = None;
+        for (y, stored) in self.hashes.iter_mut().enumerate() {
+            let hash = frame.row_hash(y).ok();
+            if *stored != hash {
+                *stored = hash;
+                band = Some(band.map_or(y..y + 1, |band| band.start..y + 1));
+            }
+        }
+        band
+    }
+
+    /// Forget the sent rows so the next [`update`](Self::update) reports the
+    /// whole frame, e.g. after the receiver was reset
+    pub fn invalidate(&mut self) {
+        self.hashes = [None; HEIGHT];
+    }
+}
+
+impl<const HEIGHT: usize> Default for DirtyRows<HEIGHT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Add a byte to a CRC-16/CCITT-FALSE checksum started at `0xFFFF`
+pub fn crc16_update(crc: u16, byte: u8) -> u16 {
+    let mut crc = crc ^ ((byte as u16) << 8);
+    for _ in 0..8 {
+        crc = if crc & 0x8000 != 0 {
+            (crc << 1) ^ 0x1021
+        } else {
+            crc << 1
+        };
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+
+    type TestReceiver = StreamReceiver<4, 4, 6>;
+
+    fn packet(kind: PacketKind, payload: &[u8]) -> ([u8; 64], usize) {
+        let mut out = [0; 64];
+        let length = encode_packet(kind, payload, &mut out).unwrap();
+        (out, length)
+    }
+
+    #[test]
+    fn test_dirty_rows() {
+        let mut frame = Hub75FrameBuffer::<4, 4, 6>::new();
+        let mut dirty = DirtyRows::<4>::new();
+        assert_eq!(dirty.update(&frame), Some(0..4));
+        assert_eq!(dirty.update(&frame), None);
+
+        frame.set_pixel(1, 1, Hub75Color::red()).unwrap();
+        frame.set_pixel(0, 2, Hub75Color::blue()).unwrap();
+        assert_eq!(dirty.update(&frame), Some(1..3));
+        assert_eq!(dirty.update(&frame), None);
+
+        dirty.invalidate();
+        assert_eq!(dirty.update(&frame), Some(0..4));
+    }
+
+    #[test]
+    fn test_crc() {
+        let crc = b"123456789"
+            .iter()
+            .fold(0xFFFF, |crc, &byte| crc16_update(crc, byte));
+        assert_eq!(crc, 0x29B1);
+    }
+
+    #[test]
+    fn test_raw_and_rle() {
+        let mut receiver = TestReceiver::new();
+
+        let mut payload = [0; 20];
+        payload[..8].copy_from_slice(&region_header(1, 1, 2, 2));
+        payload[8..11].copy_from_slice(&[0, 255, 0]);
+        payload[17..20].copy_from_slice(&[0, 0, 255]);
+        let (bytes, length) = packet(PacketKind::Raw, &payload);
+        assert_eq!(
+            receiver.feed(&bytes[..length]),
+            (length, Some(StreamEvent::Updated))
+        );
+        assert_eq!(
+            receiver.frame().get_pixel(1, 1).unwrap(),
+            Hub75Color::green()
+        );
+        assert_eq!(
+            receiver.frame().get_pixel(2, 2).unwrap(),
+            Hub75Color::blue()
+        );
+
+        // A whole frame of red in two runs
+        let mut payload = [0; 16];
+        payload[..8].copy_from_slice(&region_header(0, 0, 4, 4));
+        payload[8..].copy_from_slice(&[10, 255, 0, 0, 6, 255, 0, 0]);
+        let (bytes, length) = packet(PacketKind::Rle, &payload);
+        let (present, present_length) = packet(PacketKind::Present, &[]);
+        assert_eq!(
+            receiver.feed(&bytes[..length]).1,
+            Some(StreamEvent::Updated)
+        );
+        assert_eq!(
+            receiver.feed(&present[..present_length]).1,
+            Some(StreamEvent::Present)
+        );
+        assert_eq!(receiver.frame().get_pixel(3, 3).unwrap(), Hub75Color::red());
+    }
+
+    #[test]
+    fn test_errors_and_resync() {
+        let mut receiver = TestReceiver::new();
+
+        // Corrupted checksum
+        let mut payload = [0; 11];
+        payload[..8].copy_from_slice(&region_header(0, 0, 1, 1));
+        let (mut bytes, length) = packet(PacketKind::Raw, &payload);
+        bytes[length - 1] ^= 0xFF;
+        assert_eq!(
+            receiver.feed(&bytes[..length]).1,
+            Some(StreamEvent::Error(StreamError::BadCrc))
+        );
+
+        // A region hanging off the frame, after some line noise
+        payload[..8].copy_from_slice(&region_header(3, 3, 2, 1));
+        let (bytes, length) = packet(PacketKind::Raw, &payload);
+        assert_eq!(receiver.feed(&[0x00, b'H', 0x17]), (3, None));
+        assert_eq!(
+            receiver.feed(&bytes[..length]).1,
+            Some(StreamEvent::Error(StreamError::OutOfBounds))
+        );
+
+        // Runs that overflow the region
+        let mut payload = [0; 12];
+        payload[..8].copy_from_slice(&region_header(0, 0, 1, 1));
+        payload[8] = 2;
+        let (bytes, length) = packet(PacketKind::Rle, &payload);
+        assert_eq!(
+            receiver.feed(&bytes[..length]).1,
+            Some(StreamEvent::Error(StreamError::BadLength))
+        );
+
+        let mut out = [0; PACKET_OVERHEAD];
+        assert_eq!(
+            encode_packet(PacketKind::Raw, &[0], &mut out),
+            Err(Hub75Error::BufferOverflow)
+        );
+    }
+
+    /// Hands out a fixed byte string a few bytes at a time
+    struct Chunks<'a>(&'a [u8]);
+
+    impl ByteReader for Chunks<'_> {
+        type Error = ();
+
+        async fn read(&mut self, buffer: &mut [u8]) -> Result<usize, ()> {
+            let length = self.0.len().min(buffer.len()).min(5);
+            if length == 0 {
+                return Err(());
+            }
+            buffer[..length].copy_from_slice(&self.0[..length]);
+            self.0 = &self.0[length..];
+            Ok(length)
+        }
+    }
+
+    #[test]
+    fn test_async_receive() {
+        let (present, length) = packet(PacketKind::Present, &[]);
+        let mut bytes = [0; 14];
+        bytes[..length].copy_from_slice(&present[..length]);
+        bytes[length..length * 2].copy_from_slice(&present[..length]);
+        let mut reader = Chunks(&bytes);
+        let mut receiver = TestReceiver::new();
+        let mut context = Context::from_waker(Waker::noop());
+
+        for expected in [Ok(StreamEvent::Present), Ok(StreamEvent::Present), Err(())] {
+            let result = {
+                let mut future = pin!(receiver.receive(&mut reader));
+                future.as_mut().poll(&mut context)
+            };
+            assert_eq!(result, Poll::Ready(expected));
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Measuring how long bit planes are actually lit
+//!
+//! Each row is lit for the time the display asks its delay for, plus whatever
+//! the delay overshoots by and the time it takes to turn the output off
+//! again. On slow pin backends and coarse delays that overhead is the same
+//! for every plane, so the short planes come out proportionally much longer
+//! than the long ones and the low bits of every color too bright.
+//!
+//! A [`PlaneTimer`] reads a [`NanoClock`] around every row while rendering
+//! with [`render_frame_timed`](crate::Hub75Display::render_frame_timed) and
+//! keeps, per subframe, the average requested and measured on-time of the
+//! last frame. The smallest deviation over all subframes is the systematic
+//! overhead, which the display can subtract from every delay with
+//! [`calibrate_timing`](crate::Hub75Display::calibrate_timing), or after
+//! every timed frame with auto-calibration enabled.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use hub75::timing::PlaneTimer;
+//!
+//! // Any free-running nanosecond count, wrapping at u32::MAX
+//! let mut timer = PlaneTimer::new(|| embassy_time::Instant::now().as_micros() as u32 * 1000);
+//! timer.set_auto_calibration(true);
+//!
+//! loop {
+//!     display.render_frame_timed(&mut delay, &mut timer).await?;
+//!     defmt::info!("LSB plane off by {} ns", timer.deviation_ns(0));
+//! }
+//! ```
+
+/// Number of subframes whose timing is kept, covering 16-bit BCM
+pub const MAX_TIMED_SUBFRAMES: usize = 16;
+
+/// Free-running clock counting nanoseconds
+///
+/// The count may wrap at `u32::MAX`; only differences over a single plane are
+/// used.
+pub trait NanoClock {
+    /// Get the current time in nanoseconds
+    fn now_ns(&mut self) -> u32;
+}
+
+impl<F: FnMut() -> u32> NanoClock for F {
+    fn now_ns(&mut self) -> u32 {
+        self()
+    }
+}
+
+/// On-times of one subframe summed over the rows of a frame
+#[derive(Debug, Clone, Copy, Default)]
+struct PlaneSums {
+    /// Requested on-time
+    requested_ns: u64,
+    /// Measured on-time
+    measured_ns: u64,
+    /// Number of rows measured
+    rows: u32,
+}
+
+/// Measures the on-time of every subframe while rendering
+pub struct PlaneTimer<T> {
+    /// Clock read around every row
+    clock: T,
+    /// Sums of the last completed frame
+    planes: [PlaneSums; MAX_TIMED_SUBFRAMES],
+    /// Sums of the frame being rendered
+    pending: [PlaneSums; MAX_TIMED_SUBFRAMES],
+    /// Time spent shifting and latching rows in the last frame
+    shift_ns: u64,
+    /// Time spent shifting and latching rows in the frame being rendered
+    pending_shift_ns: u64,
+    /// Rows shifted in the last frame
+    shift_rows: u32,
+    /// Rows shifted in the frame being rendered
+    pending_shift_rows: u32,
+    /// Whether the display is calibrated after every timed frame
+    auto_calibration: bool,
+}
+
+impl<T: NanoClock> PlaneTimer<T> {
+    /// Create a timer reading `clock`, without auto-calibration
+    pub fn new(clock: T) -> Self {
+        Self {
+            clock,
+            planes: [PlaneSums::default(); MAX_TIMED_SUBFRAMES],
+            pending: [PlaneSums::default(); MAX_TIMED_SUBFRAMES],
+            shift_ns: 0,
+            pending_shift_ns: 0,
+            shift_rows: 0,
+            pending_shift_rows: 0,
+            auto_calibration: false,
+        }
+    }
+
+    /// Enable or disable calibrating the display after every timed frame
+    pub fn set_auto_calibration(&mut self, enabled: bool) {
+        self.auto_calibration = enabled;
+    }
+
+    /// Check whether the display is calibrated after every timed frame
+    pub fn is_auto_calibrating(&self) -> bool {
+        self.auto_calibration
+    }
+
+    /// Get the average on-time of a subframe requested in the last frame
+    pub fn requested_ns(&self, subframe: usize) -> Option<u32> {
+        let plane = self.plane(subframe)?;
+        Some((plane.requested_ns / plane.rows as u64) as u32)
+    }
+
+    /// Get the average on-time of a subframe measured in the last frame
+    pub fn measured_ns(&self, subframe: usize) -> Option<u32> {
+        let plane = self.plane(subframe)?;
+        Some((plane.measured_ns / plane.rows as u64) as u32)
+    }
+
+    /// Get how much longer than requested a subframe was lit in the last frame
+    pub fn deviation_ns(&self, subframe: usize) -> Option<i32> {
+        let plane = self.plane(subframe)?;
+        let deviation = plane.measured_ns as i64 - plane.requested_ns as i64;
+        Some((deviation / plane.rows as i64) as i32)
+    }
+
+    /// Get the overhead common to all subframes of the last frame
+    ///
+    /// This is the smallest deviation, since a constant overhead shows up in
+    /// every subframe while delay granularity only adds to some of them.
+    pub fn overhead_ns(&self) -> Option<i32> {
+        (0..MAX_TIMED_SUBFRAMES)
+            .filter_map(|subframe| self.deviation_ns(subframe))
+            .min()
+    }
+
+    /// Get the average time taken to shift out and latch a row in the last
+    /// frame, while the output was off
+    pub fn shift_ns(&self) -> Option<u32> {
+        (self.shift_rows > 0).then(|| (self.shift_ns / self.shift_rows as u64) as u32)
+    }
+
+    /// Forget all measurements
+    pub fn reset(&mut self) {
+        self.planes = [PlaneSums::default(); MAX_TIMED_SUBFRAMES];
+        self.pending = [PlaneSums::default(); MAX_TIMED_SUBFRAMES];
+        self.shift_ns = 0;
+        self.pending_shift_ns = 0;
+        self.shift_rows = 0;
+        self.pending_shift_rows = 0;
+    }
+
+    /// Return the clock
+    pub fn release(self) -> T {
+        self.clock
+    }
+
+    /// Get the sums of a subframe measured in the last frame
+    fn plane(&self, subframe: usize) -> Option<&PlaneSums> {
+        self.planes.get(subframe).filter(|plane| plane.rows > 0)
+    }
+
+    /// Read the clock
+    pub(crate) fn now_ns(&mut self) -> u32 {
+        self.clock.now_ns()
+    }
+
+    /// Record the time taken to shift out and latch a row
+    pub(crate) fn record_shift(&mut self, start_ns: u32, end_ns: u32) {
+        self.pending_shift_ns += end_ns.wrapping_sub(start_ns) as u64;
+        self.pending_shift_rows += 1;
+    }
+
+    /// Record how long a row was lit during a subframe
+    pub(crate) fn record_plane(
+        &mut self,
+        subframe: usize,
+        requested_ns: u64,
+        start_ns: u32,
+        end_ns: u32,
+    ) {
+        if let Some(plane) = self.pending.get_mut(subframe) {
+            plane.requested_ns += requested_ns;
+            plane.measured_ns += end_ns.wrapping_sub(start_ns) as u64;
+            plane.rows += 1;
+        }
+    }
+
+    /// Make the measurements of the frame just rendered current
+    pub(crate) fn finish_frame(&mut self) {
+        self.planes = core::mem::take(&mut self.pending);
+        self.shift_ns = core::mem::take(&mut self.pending_shift_ns);
+        self.shift_rows = core::mem::take(&mut self.pending_shift_rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plane_timer() {
+        let mut timer = PlaneTimer::new(|| 0u32);
+        assert_eq!(timer.deviation_ns(0), None);
+        assert_eq!(timer.overhead_ns(), None);
+
+        // Subframe 0 overshoots by 300 ns on average, subframe 1 by 200 ns
+        timer.record_plane(0, 100, 0, 400);
+        timer.record_plane(0, 100, u32::MAX - 99, 300);
+        timer.record_plane(1, 1_000, 5_000, 6_200);
+        timer.record_shift(0, 640);
+        // Nothing is current until the frame is finished
+        assert_eq!(timer.measured_ns(0), None);
+        timer.finish_frame();
+
+        assert_eq!(timer.requested_ns(0), Some(100));
+        assert_eq!(timer.measured_ns(0), Some(400));
+        assert_eq!(timer.deviation_ns(0), Some(300));
+        assert_eq!(timer.deviation_ns(1), Some(200));
+        assert_eq!(timer.deviation_ns(2), None);
+        assert_eq!(timer.overhead_ns(), Some(200));
+        assert_eq!(timer.shift_ns(), Some(640));
+
+        // Subframes beyond the kept ones are ignored
+        timer.record_plane(MAX_TIMED_SUBFRAMES, 100, 0, 100);
+        timer.finish_frame();
+        assert_eq!(timer.overhead_ns(), None);
+
+        timer.record_plane(0, 100, 0, 100);
+        timer.finish_frame();
+        timer.reset();
+        assert_eq!(timer.measured_ns(0), None);
+    }
+}
@@ -0,0 +1,698 @@
+//! Recording pin activity to verify the HUB75 protocol
+//!
+//! A [`WaveformRecorder`] hands out pins and a delay that record every level
+//! change with a timestamp instead of driving hardware. Build a
+//! [`Hub75Display`](crate::Hub75Display) on them, render, and check the
+//! capture against the rules a panel relies on with
+//! [`check_protocol`](WaveformRecorder::check_protocol):
+//!
+//! - color data only changes while the clock is low, so it is stable before
+//!   each rising edge
+//! - the output stays disabled (OE high) whenever the latch is open and
+//!   whenever the row address changes
+//! - every latch follows exactly one row's worth of clock pulses
+//! - rows are lit in order, starting again from row 0 after the last one
+//!
+//! Captures can be saved as a Value Change Dump with
+//! [`save_vcd`](WaveformRecorder::save_vcd) to inspect a failing test in
+//! GTKWave next to a logic analyzer capture from a real panel.
+//!
+//! Time only advances through the recorder's delay and a fixed time per pin
+//! change, so captures are deterministic. The module is always built for the
+//! crate's own tests and is available to applications with the `waveform`
+//! feature, which needs `std`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75Display};
+//! use hub75::waveform::WaveformRecorder;
+//!
+//! # async fn example() -> Result<(), hub75::Hub75Error> {
+//! let recorder = WaveformRecorder::new();
+//! let mut display = Hub75Display::<_, 64, 32, 6>::new(recorder.pins(4))?;
+//! let mut delay = recorder.delay();
+//!
+//! display.fill(Hub75Color::white());
+//! display.swap_buffers();
+//! display.render_frame(&mut delay).await?;
+//! assert_eq!(recorder.check_protocol(64, 16), Ok(()));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    rc::Rc,
+    vec::Vec,
+};
+
+use embedded_hal::digital::{ErrorType, OutputPin};
+
+use crate::pins::Hub75Pins;
+
+/// HUB75 signal carried by a pin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Signal {
+    /// Red data for the upper half
+    R1,
+    /// Green data for the upper half
+    G1,
+    /// Blue data for the upper half
+    B1,
+    /// Red data for the lower half
+    R2,
+    /// Green data for the lower half
+    G2,
+    /// Blue data for the lower half
+    B2,
+    /// Row address bit 0
+    A,
+    /// Row address bit 1
+    B,
+    /// Row address bit 2
+    C,
+    /// Row address bit 3
+    D,
+    /// Row address bit 4
+    E,
+    /// Shift clock
+    Clk,
+    /// Latch
+    Lat,
+    /// Output enable, active low
+    Oe,
+}
+
+impl Signal {
+    /// Every signal, in declaration order
+    pub const ALL: [Signal; 14] = [
+        Signal::R1,
+        Signal::G1,
+        Signal::B1,
+        Signal::R2,
+        Signal::G2,
+        Signal::B2,
+        Signal::A,
+        Signal::B,
+        Signal::C,
+        Signal::D,
+        Signal::E,
+        Signal::Clk,
+        Signal::Lat,
+        Signal::Oe,
+    ];
+
+    /// Address signals, least significant bit first
+    pub const ADDRESS: [Signal; 5] = [Signal::A, Signal::B, Signal::C, Signal::D, Signal::E];
+
+    /// Get the signal's name as printed on panel connectors
+    pub const fn name(self) -> &'static str {
+        match self {
+            Signal::R1 => "R1",
+            Signal::G1 => "G1",
+            Signal::B1 => "B1",
+            Signal::R2 => "R2",
+            Signal::G2 => "G2",
+            Signal::B2 => "B2",
+            Signal::A => "A",
+            Signal::B => "B",
+            Signal::C => "C",
+            Signal::D => "D",
+            Signal::E => "E",
+            Signal::Clk => "CLK",
+            Signal::Lat => "LAT",
+            Signal::Oe => "OE",
+        }
+    }
+
+    /// Check if the signal carries color data
+    pub const fn is_data(self) -> bool {
+        (self as usize) < Signal::A as usize
+    }
+
+    /// Check if the signal is a row address bit
+    pub const fn is_address(self) -> bool {
+        matches!(
+            self,
+            Signal::A | Signal::B | Signal::C | Signal::D | Signal::E
+        )
+    }
+}
+
+/// A recorded change of a pin's level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Transition {
+    /// Time of the change in nanoseconds since recording started
+    pub time_ns: u64,
+    /// Signal that changed
+    pub signal: Signal,
+    /// New level
+    pub level: bool,
+}
+
+/// Broken protocol rule found in a capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Violation {
+    /// Color data changed while the clock was high
+    DataChangedWhileClockHigh {
+        /// Time of the change
+        time_ns: u64,
+        /// Data signal that changed
+        signal: Signal,
+    },
+    /// The output was enabled while the latch was open
+    OutputEnabledDuringLatch {
+        /// Time the rule was broken
+        time_ns: u64,
+    },
+    /// The row address changed while the output was enabled
+    AddressChangedWhileEnabled {
+        /// Time of the change
+        time_ns: u64,
+    },
+    /// A latch did not follow one row's worth of clock pulses
+    ClockCountMismatch {
+        /// Time of the latch
+        time_ns: u64,
+        /// Clock pulses expected
+        expected: usize,
+        /// Clock pulses seen
+        found: usize,
+    },
+    /// A row was lit out of order
+    AddressOutOfSequence {
+        /// Time the output was enabled
+        time_ns: u64,
+        /// Row expected
+        expected: usize,
+        /// Row addressed
+        found: usize,
+    },
+}
+
+/// Shared capture state
+struct Capture {
+    /// Current level of each signal
+    levels: [bool; Signal::ALL.len()],
+    /// Level changes so far
+    transitions: Vec<Transition>,
+    /// Current time
+    time_ns: u64,
+    /// Time each pin change takes
+    edge_ns: u64,
+}
+
+/// Records the pin activity of a display
+#[derive(Clone)]
+pub struct WaveformRecorder {
+    /// State shared with the pins and delay
+    capture: Rc<RefCell<Capture>>,
+}
+
+impl WaveformRecorder {
+    /// Create an empty recorder where each pin change takes 10 ns
+    ///
+    /// All signals start low.
+    pub fn new() -> Self {
+        Self {
+            capture: Rc::new(RefCell::new(Capture {
+                levels: [false; Signal::ALL.len()],
+                transitions: Vec::new(),
+                time_ns: 0,
+                edge_ns: 10,
+            })),
+        }
+    }
+
+    /// Create a recording pin for one signal
+    pub fn pin(&self, signal: Signal) -> RecordingPin {
+        RecordingPin {
+            capture: self.capture.clone(),
+            signal,
+        }
+    }
+
+    /// Create recording pins with 3 to 5 address pins
+    pub fn pins(&self, address_pins: usize) -> Hub75Pins<RecordingPin> {
+        Hub75Pins::new(
+            self.pin(Signal::R1),
+            self.pin(Signal::G1),
+            self.pin(Signal::B1),
+            self.pin(Signal::R2),
+            self.pin(Signal::G2),
+            self.pin(Signal::B2),
+            self.pin(Signal::A),
+            self.pin(Signal::B),
+            self.pin(Signal::C),
+            (address_pins > 3).then(|| self.pin(Signal::D)),
+            (address_pins > 4).then(|| self.pin(Signal::E)),
+            self.pin(Signal::Clk),
+            self.pin(Signal::Lat),
+            self.pin(Signal::Oe),
+        )
+    }
+
+    /// Create an SPI bus recording SCK as the shift clock and MOSI as `mosi`
+    ///
+    /// Each bit sets MOSI while the clock is low and then pulses the clock, as
+    /// a bus in SPI mode 0 would.
+    pub fn spi(&self, mosi: Option<Signal>) -> RecordingSpi {
+        RecordingSpi {
+            clk: self.pin(Signal::Clk),
+            mosi: mosi.map(|signal| self.pin(signal)),
+        }
+    }
+
+    /// Create a delay that advances the recording's clock
+    pub fn delay(&self) -> RecordingDelay {
+        RecordingDelay {
+            capture: self.capture.clone(),
+        }
+    }
+
+    /// Set how long each pin change takes, in nanoseconds
+    pub fn set_edge_ns(&self, edge_ns: u64) {
+        self.capture.borrow_mut().edge_ns = edge_ns;
+    }
+
+    /// Get the current time in nanoseconds
+    pub fn time_ns(&self) -> u64 {
+        self.capture.borrow().time_ns
+    }
+
+    /// Get the current level of a signal
+    pub fn level(&self, signal: Signal) -> bool {
+        self.capture.borrow().levels[signal as usize]
+    }
+
+    /// Get the level changes recorded so far
+    pub fn transitions(&self) -> Vec<Transition> {
+        self.capture.borrow().transitions.clone()
+    }
+
+    /// Count the rising edges of a signal
+    pub fn rising_edges(&self, signal: Signal) -> usize {
+        self.capture
+            .borrow()
+            .transitions
+            .iter()
+            .filter(|t| t.signal == signal && t.level)
+            .count()
+    }
+
+    /// Forget the recorded changes, keeping the current levels and time
+    pub fn clear(&self) {
+        self.capture.borrow_mut().transitions.clear();
+    }
+
+    /// Check the capture against the HUB75 rules
+    ///
+    /// `width` is the number of clock pulses expected per latch and `rows` the
+    /// number of addressed rows, half the panel height. Rows must be lit in
+    /// order starting from row 0. Returns the first rule broken.
+    pub fn check_protocol(&self, width: usize, rows: usize) -> Result<(), Violation> {
+        let capture = self.capture.borrow();
+        let mut levels = capture.initial_levels();
+        let mut clocks = 0;
+        let mut next_row = 0;
+        for &Transition {
+            time_ns,
+            signal,
+            level,
+        } in &capture.transitions
+        {
+            let enabled = !levels[Signal::Oe as usize];
+            match signal {
+                _ if signal.is_data() && levels[Signal::Clk as usize] => {
+                    return Err(Violation::DataChangedWhileClockHigh { time_ns, signal });
+                }
+                _ if signal.is_address() && enabled => {
+                    return Err(Violation::AddressChangedWhileEnabled { time_ns });
+                }
+                Signal::Clk if level => clocks += 1,
+                Signal::Lat if !level => {
+                    if clocks != width {
+                        return Err(Violation::ClockCountMismatch {
+                            time_ns,
+                            expected: width,
+                            found: clocks,
+                        });
+                    }
+                    clocks = 0;
+                }
+                Signal::Oe if !level => {
+                    if levels[Signal::Lat as usize] {
+                        return Err(Violation::OutputEnabledDuringLatch { time_ns });
+                    }
+                    let found = address(&levels);
+                    if found != next_row {
+                        return Err(Violation::AddressOutOfSequence {
+                            time_ns,
+                            expected: next_row,
+                            found,
+                        });
+                    }
+                    next_row = (next_row + 1) % rows.max(1);
+                }
+                Signal::Lat if level && enabled => {
+                    return Err(Violation::OutputEnabledDuringLatch { time_ns });
+                }
+                _ => {}
+            }
+            levels[signal as usize] = level;
+        }
+        Ok(())
+    }
+
+    /// Write the capture as a Value Change Dump for GTKWave and similar tools
+    ///
+    /// Each signal becomes a one-bit wire named as on the panel connector,
+    /// and the row address is also shown as a five-bit `ADDR` bus. Times are
+    /// in nanoseconds.
+    pub fn write_vcd(&self, mut writer: impl Write) -> io::Result<()> {
+        let capture = self.capture.borrow();
+        let code = |signal: Signal| (b'!' + signal as u8) as char;
+        let address_code = (b'!' + Signal::ALL.len() as u8) as char;
+
+        writeln!(writer, "$version hub75 waveform recorder $end")?;
+        writeln!(writer, "$timescale 1ns $end")?;
+        writeln!(writer, "$scope module hub75 $end")?;
+        for signal in Signal::ALL {
+            writeln!(
+                writer,
+                "$var wire 1 {} {} $end",
+                code(signal),
+                signal.name()
+            )?;
+        }
+        writeln!(writer, "$var wire 5 {address_code} ADDR [4:0] $end")?;
+        writeln!(writer, "$upscope $end")?;
+        writeln!(writer, "$enddefinitions $end")?;
+
+        let mut levels = capture.initial_levels();
+        writeln!(writer, "#0")?;
+        writeln!(writer, "$dumpvars")?;
+        for signal in Signal::ALL {
+            writeln!(writer, "{}{}", levels[signal as usize] as u8, code(signal))?;
+        }
+        writeln!(writer, "b{:05b} {address_code}", address(&levels))?;
+        writeln!(writer, "$end")?;
+
+        let mut time_ns = 0;
+        for transition in &capture.transitions {
+            if transition.time_ns != time_ns {
+                time_ns = transition.time_ns;
+                writeln!(writer, "#{time_ns}")?;
+            }
+            levels[transition.signal as usize] = transition.level;
+            writeln!(
+                writer,
+                "{}{}",
+                transition.level as u8,
+                code(transition.signal)
+            )?;
+            if transition.signal.is_address() {
+                writeln!(writer, "b{:05b} {address_code}", address(&levels))?;
+            }
+        }
+        if capture.time_ns > time_ns {
+            writeln!(writer, "#{}", capture.time_ns)?;
+        }
+        writer.flush()
+    }
+
+    /// Save the capture as a Value Change Dump file
+    pub fn save_vcd(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_vcd(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl Capture {
+    /// Levels in force before the first recorded change
+    fn initial_levels(&self) -> [bool; Signal::ALL.len()] {
+        let mut levels = self.levels;
+        for transition in self.transitions.iter().rev() {
+            levels[transition.signal as usize] = !transition.level;
+        }
+        levels
+    }
+}
+
+/// Row selected by the address signals
+fn address(levels: &[bool; Signal::ALL.len()]) -> usize {
+    Signal::ADDRESS
+        .iter()
+        .enumerate()
+        .filter(|(_, &signal)| levels[signal as usize])
+        .fold(0, |address, (bit, _)| address | 1 << bit)
+}
+
+impl Default for WaveformRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Output pin recording its level changes
+pub struct RecordingPin {
+    /// Capture the pin records into
+    capture: Rc<RefCell<Capture>>,
+    /// Signal the pin carries
+    signal: Signal,
+}
+
+impl RecordingPin {
+    /// Set the level, recording it if it changed
+    fn set(&mut self, level: bool) {
+        let mut capture = self.capture.borrow_mut();
+        if capture.levels[self.signal as usize] == level {
+            return;
+        }
+        capture.time_ns += capture.edge_ns;
+        capture.levels[self.signal as usize] = level;
+        let transition = Transition {
+            time_ns: capture.time_ns,
+            signal: self.signal,
+            level,
+        };
+        capture.transitions.push(transition);
+    }
+}
+
+impl ErrorType for RecordingPin {
+    type Error = core::convert::Infallible;
+}
+
+impl OutputPin for RecordingPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set(true);
+        Ok(())
+    }
+}
+
+/// SPI bus recording its clock and data line
+pub struct RecordingSpi {
+    /// Pin recording SCK
+    clk: RecordingPin,
+    /// Pin recording MOSI, if it is connected
+    mosi: Option<RecordingPin>,
+}
+
+impl embedded_hal::spi::ErrorType for RecordingSpi {
+    type Error = core::convert::Infallible;
+}
+
+impl embedded_hal::spi::SpiBus for RecordingSpi {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.transfer_in_place(words)
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        for byte in words {
+            for bit in (0..8).rev() {
+                if let Some(mosi) = &mut self.mosi {
+                    mosi.set(byte >> bit & 1 != 0);
+                }
+                self.clk.set(true);
+                self.clk.set(false);
+            }
+        }
+        Ok(())
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        read.fill(0);
+        self.write(write)?;
+        let extra = read.len().saturating_sub(write.len());
+        self.write(&[0].repeat(extra))
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.write(words)?;
+        words.fill(0);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Delay that advances a recording's clock instead of waiting
+pub struct RecordingDelay {
+    /// Capture whose clock is advanced
+    capture: Rc<RefCell<Capture>>,
+}
+
+impl embedded_hal::delay::DelayNs for RecordingDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.capture.borrow_mut().time_ns += ns as u64;
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for RecordingDelay {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.capture.borrow_mut().time_ns += ns as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "async")]
+    use crate::{color::Hub75Color, Hub75Display};
+    #[cfg(feature = "async")]
+    use core::future::Future;
+    #[cfg(feature = "async")]
+    use core::task::{Context, Poll, Waker};
+
+    #[cfg(feature = "async")]
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = core::pin::pin!(future);
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("recording futures never wait"),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_display_follows_protocol() {
+        let recorder = WaveformRecorder::new();
+        let mut display = Hub75Display::<_, 32, 16, 4>::new(recorder.pins(3)).unwrap();
+        let mut delay = recorder.delay();
+        display.set_pixel(5, 3, Hub75Color::white()).unwrap();
+        display
+            .set_pixel(31, 15, Hub75Color::new(7, 0, 15))
+            .unwrap();
+
+        for _ in 0..2 {
+            block_on(display.render_frame(&mut delay)).unwrap();
+        }
+        let result = recorder.check_protocol(32, 8);
+        if result.is_err() {
+            let path = std::env::temp_dir().join("hub75_protocol.vcd");
+            if recorder.save_vcd(&path).is_ok() {
+                std::eprintln!("capture saved to {}", path.display());
+            }
+        }
+        assert_eq!(result, Ok(()));
+        assert_eq!(recorder.rising_edges(Signal::Clk), 2 * 4 * 8 * 32);
+    }
+
+    #[test]
+    fn test_violations() {
+        let recorder = WaveformRecorder::new();
+        let [mut r1, mut clk, mut lat, mut oe, mut a] =
+            [Signal::R1, Signal::Clk, Signal::Lat, Signal::Oe, Signal::A].map(|s| recorder.pin(s));
+
+        // Data changing while the clock is high
+        clk.set_high().unwrap();
+        r1.set_high().unwrap();
+        assert!(matches!(
+            recorder.check_protocol(1, 2),
+            Err(Violation::DataChangedWhileClockHigh {
+                signal: Signal::R1,
+                ..
+            })
+        ));
+
+        // Latching after too few clocks, with the output enabled
+        recorder.clear();
+        clk.set_low().unwrap();
+        lat.set_high().unwrap();
+        lat.set_low().unwrap();
+        assert!(matches!(
+            recorder.check_protocol(2, 2),
+            Err(Violation::OutputEnabledDuringLatch { .. })
+        ));
+        oe.set_high().unwrap();
+        recorder.clear();
+        lat.set_high().unwrap();
+        lat.set_low().unwrap();
+        assert!(matches!(
+            recorder.check_protocol(2, 2),
+            Err(Violation::ClockCountMismatch {
+                expected: 2,
+                found: 0,
+                ..
+            })
+        ));
+
+        // Lighting row 1 first, then changing rows while lit
+        recorder.clear();
+        a.set_high().unwrap();
+        oe.set_low().unwrap();
+        assert!(matches!(
+            recorder.check_protocol(1, 2),
+            Err(Violation::AddressOutOfSequence {
+                expected: 0,
+                found: 1,
+                ..
+            })
+        ));
+        recorder.clear();
+        a.set_low().unwrap();
+        assert!(matches!(
+            recorder.check_protocol(1, 2),
+            Err(Violation::AddressChangedWhileEnabled { .. })
+        ));
+    }
+
+    #[test]
+    fn test_vcd() {
+        let recorder = WaveformRecorder::new();
+        let [mut b, mut oe] = [Signal::B, Signal::Oe].map(|s| recorder.pin(s));
+        oe.set_high().unwrap();
+        recorder.clear();
+        b.set_high().unwrap();
+        embedded_hal::delay::DelayNs::delay_ns(&mut recorder.delay(), 100);
+
+        let mut vcd = Vec::new();
+        recorder.write_vcd(&mut vcd).unwrap();
+        let vcd = std::string::String::from_utf8(vcd).unwrap();
+        assert!(vcd.contains("$timescale 1ns $end"));
+        assert!(vcd.contains("$var wire 1 . OE $end"));
+        assert!(vcd.contains("$var wire 5 / ADDR [4:0] $end"));
+        // Levels before the capture, then B rising at 20 ns and the delay
+        assert!(vcd.contains("$dumpvars\n0!\n"));
+        assert!(vcd.contains("1.\nb00000 /\n$end\n"));
+        assert!(vcd.ends_with("#20\n1(\nb00010 /\n#120\n"));
+    }
+}
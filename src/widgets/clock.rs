@@ -0,0 +1,418 @@
+//! Digital and analog clock faces
+//!
+//! A [`Clock`] reads the time from a user-supplied [`TimeSource`], such as an
+//! RTC driver or a closure over a network time, and draws it into a region of
+//! the frame whenever the displayed time changes. The digital faces use the
+//! built-in [`font`](crate::font) with an optional blinking colon; the analog
+//! face draws hour marks and hands using fixed-point trigonometry.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{AnimationState, Hub75Color, Hub75FrameBuffer};
+//! use hub75::widgets::{Clock, ClockFace, TimeOfDay};
+//! use embedded_graphics_core::{geometry::{Point, Size}, primitives::Rectangle};
+//!
+//! let now = || TimeOfDay::new(13, 37, 5);
+//! let area = Rectangle::new(Point::zero(), Size::new(64, 32));
+//! let mut clock = Clock::<_, 64, 32, 6>::new(now, area, Hub75Color::cyan());
+//! clock.set_face(ClockFace::DigitalSeconds);
+//!
+//! let mut frame = Hub75FrameBuffer::new();
+//! assert!(matches!(clock.next_into(&mut frame), AnimationState::Rendered));
+//! // Nothing changed, so there is nothing to redraw
+//! assert!(matches!(clock.next_into(&mut frame), AnimationState::Wait));
+//! ```
+
+use embedded_graphics_core::primitives::Rectangle;
+
+use crate::{
+    animation::AnimationState,
+    color::Hub75Color,
+    font::{self, GLYPH_HEIGHT},
+    frame_buffer::Hub75FrameBuffer,
+    math,
+    scene::FrameSource,
+    window::Window,
+    Hub75Error,
+};
+
+/// A time of day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimeOfDay {
+    /// Hours, 0 to 23
+    pub hours: u8,
+    /// Minutes, 0 to 59
+    pub minutes: u8,
+    /// Seconds, 0 to 59
+    pub seconds: u8,
+}
+
+impl TimeOfDay {
+    /// Create a time of day, wrapping out-of-range fields
+    pub const fn new(hours: u8, minutes: u8, seconds: u8) -> Self {
+        Self {
+            hours: hours % 24,
+            minutes: minutes % 60,
+            seconds: seconds % 60,
+        }
+    }
+
+    /// Create a time of day from the seconds since midnight
+    pub const fn from_seconds(seconds: u32) -> Self {
+        let seconds = seconds % 86_400;
+        Self::new(
+            (seconds / 3600) as u8,
+            (seconds / 60 % 60) as u8,
+            (seconds % 60) as u8,
+        )
+    }
+}
+
+/// Source of the current time of day
+///
+/// Implemented for closures returning a [`TimeOfDay`].
+pub trait TimeSource {
+    /// Get the current time of day
+    fn time_of_day(&self) -> TimeOfDay;
+}
+
+impl<F: Fn() -> TimeOfDay> TimeSource for F {
+    fn time_of_day(&self) -> TimeOfDay {
+        self()
+    }
+}
+
+/// How the time is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClockFace {
+    /// `HH:MM`
+    #[default]
+    Digital,
+    /// `HH:MM:SS`
+    DigitalSeconds,
+    /// Hour marks with hour, minute and second hands
+    Analog,
+}
+
+/// A clock drawn into a region of the frame
+#[derive(Debug, Clone)]
+pub struct Clock<T, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Where the time comes from
+    source: T,
+    /// Region of the frame the clock is drawn in
+    area: Rectangle,
+    /// How the time is shown
+    face: ClockFace,
+    /// Color of the digits, or of the hour marks and main hands
+    color: Hub75Color<COLOR_BITS>,
+    /// Color of the second hand
+    accent: Hub75Color<COLOR_BITS>,
+    /// Whether colons are hidden on odd seconds
+    blink_colon: bool,
+    /// Whether digital faces count hours from 1 to 12
+    twelve_hour: bool,
+    /// Time most recently drawn
+    shown: Option<TimeOfDay>,
+}
+
+impl<T, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    Clock<T, WIDTH, HEIGHT, COLOR_BITS>
+where
+    T: TimeSource,
+{
+    /// Create a 24-hour digital clock with a blinking colon and a red second
+    /// hand for the analog face
+    pub fn new(source: T, area: Rectangle, color: Hub75Color<COLOR_BITS>) -> Self {
+        Self {
+            source,
+            area,
+            face: ClockFace::Digital,
+            color,
+            accent: Hub75Color::red(),
+            blink_colon: true,
+            twelve_hour: false,
+            shown: None,
+        }
+    }
+
+    /// Set how the time is shown
+    pub fn set_face(&mut self, face: ClockFace) {
+        self.face = face;
+        self.shown = None;
+    }
+
+    /// Set the main color
+    pub fn set_color(&mut self, color: Hub75Color<COLOR_BITS>) {
+        self.color = color;
+        self.shown = None;
+    }
+
+    /// Set the color of the analog second hand
+    pub fn set_accent_color(&mut self, color: Hub75Color<COLOR_BITS>) {
+        self.accent = color;
+        self.shown = None;
+    }
+
+    /// Set whether colons blink, hidden on odd seconds
+    pub fn set_blink_colon(&mut self, blink: bool) {
+        self.blink_colon = blink;
+        self.shown = None;
+    }
+
+    /// Set whether digital faces show hours from 1 to 12 instead of 0 to 23
+    pub fn set_twelve_hour(&mut self, twelve_hour: bool) {
+        self.twelve_hour = twelve_hour;
+        self.shown = None;
+    }
+
+    /// Get the time source
+    pub fn source(&self) -> &T {
+        &self.source
+    }
+
+    /// Draw the current time, clearing the rest of the area
+    pub fn render_into(&mut self, frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) {
+        let time = self.source.time_of_day();
+        self.draw(frame, time);
+    }
+
+    /// Draw the time into `frame` if it changed since the last call
+    ///
+    /// Returns [`AnimationState::Wait`] when the displayed time is the same;
+    /// a clock is never done.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        let mut time = self.source.time_of_day();
+        if self.face == ClockFace::Digital && !self.blink_colon {
+            time.seconds = 0;
+        }
+        if self.shown == Some(time) {
+            return AnimationState::Wait;
+        }
+        self.draw(frame, time);
+        AnimationState::Rendered
+    }
+
+    /// Draw the given time and remember it
+    fn draw(&mut self, frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>, time: TimeOfDay) {
+        self.shown = Some(time);
+        let mut window = Window::new(frame, self.area);
+        window.clear();
+        match self.face {
+            ClockFace::Digital | ClockFace::DigitalSeconds => self.draw_digital(&mut window, time),
+            ClockFace::Analog => self.draw_analog(&mut window, time),
+        }
+    }
+
+    /// Draw the time as centered digits
+    fn draw_digital(&self, window: &mut Window<'_, WIDTH, HEIGHT, COLOR_BITS>, time: TimeOfDay) {
+        let hours = match (self.twelve_hour, time.hours % 12) {
+            (true, 0) => 12,
+            (true, hours) => hours,
+            (false, _) => time.hours,
+        };
+        let colon = if self.blink_colon && time.seconds % 2 == 1 {
+            b' '
+        } else {
+            b':'
+        };
+
+        let digit = |value: u8, tens: bool| b'0' + if tens { value / 10 } else { value % 10 };
+        let mut text = [
+            digit(hours, true),
+            digit(hours, false),
+            colon,
+            digit(time.minutes, true),
+            digit(time.minutes, false),
+            colon,
+            digit(time.seconds, true),
+            digit(time.seconds, false),
+        ];
+        if self.twelve_hour && text[0] == b'0' {
+            text[0] = b' ';
+        }
+        let len = if self.face == ClockFace::DigitalSeconds {
+            8
+        } else {
+            5
+        };
+        // Only ASCII digits, spaces and colons were written
+        let text = core::str::from_utf8(&text[..len]).unwrap_or_default();
+
+        let text_width = font::text_width(text) as i32 - 1;
+        let x = (window.width() as i32 - text_width) / 2;
+        let y = (window.height() as i32 - GLYPH_HEIGHT as i32) / 2;
+        window.draw_text(text, x, y, self.color);
+    }
+
+    /// Draw hour marks and hands
+    fn draw_analog(&self, window: &mut Window<'_, WIDTH, HEIGHT, COLOR_BITS>, time: TimeOfDay) {
+        let center = (
+            (window.width() as i32 - 1) / 2,
+            (window.height() as i32 - 1) / 2,
+        );
+        let radius = (window.width().min(window.height()) as i32 - 1) / 2;
+        let point = |degrees: u32, length: i32| {
+            (
+                center.0 + math::sin(math::degrees(degrees)) as i32 * length / 32767,
+                center.1 - math::cos(math::degrees(degrees)) as i32 * length / 32767,
+            )
+        };
+
+        for hour in 0..12 {
+            let (x, y) = point(hour * 30, radius);
+            plot(window, x, y, self.color);
+        }
+
+        let minutes = time.minutes as u32;
+        let hours = (time.hours % 12) as u32 * 30 + minutes / 2;
+        draw_line(window, center, point(hours, radius / 2), self.color);
+        draw_line(
+            window,
+            center,
+            point(minutes * 6, radius * 4 / 5),
+            self.color,
+        );
+        draw_line(
+            window,
+            center,
+            point(time.seconds as u32 * 6, radius - 1),
+            self.accent,
+        );
+    }
+}
+
+/// Set a pixel given in signed window coordinates, ignoring points outside
+fn plot<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+    window: &mut Window<'_, WIDTH, HEIGHT, COLOR_BITS>,
+    x: i32,
+    y: i32,
+    color: Hub75Color<COLOR_BITS>,
+) {
+    if x >= 0 && y >= 0 {
+        window.set_pixel(x as usize, y as usize, color).ok();
+    }
+}
+
+/// Draw a straight line between two points (Bresenham)
+fn draw_line<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+    window: &mut Window<'_, WIDTH, HEIGHT, COLOR_BITS>,
+    from: (i32, i32),
+    to: (i32, i32),
+    color: Hub75Color<COLOR_BITS>,
+) {
+    let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+    let (sx, sy) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+    let (mut x, mut y, mut error) = (from.0, from.1, dx + dy);
+    loop {
+        plot(window, x, y, color);
+        if (x, y) == to {
+            break;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += sx;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+}
+
+impl<T, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for Clock<T, WIDTH, HEIGHT, COLOR_BITS>
+where
+    T: TimeSource,
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        Clock::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        Clock::render_into(self, frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use embedded_graphics_core::geometry::{Point, Size};
+
+    const AREA: Rectangle = Rectangle::new(Point::new(0, 0), Size::new(31, 31));
+
+    #[test]
+    fn test_time_of_day() {
+        assert_eq!(TimeOfDay::from_seconds(3_600 + 62), TimeOfDay::new(1, 1, 2));
+        assert_eq!(TimeOfDay::from_seconds(86_400 + 5), TimeOfDay::new(0, 0, 5));
+        assert_eq!(TimeOfDay::new(25, 60, 61), TimeOfDay::new(1, 0, 1));
+    }
+
+    #[test]
+    fn test_digital_colon_blink() {
+        let now = Cell::new(TimeOfDay::new(9, 5, 0));
+        let mut clock = Clock::<_, 31, 31, 6>::new(|| now.get(), AREA, Hub75Color::white());
+        let mut frame = Hub75FrameBuffer::new();
+        let lit = |frame: &Hub75FrameBuffer<31, 31, 6>| {
+            (0..31)
+                .flat_map(|y| (0..31).map(move |x| (x, y)))
+                .filter(|&(x, y)| frame.get_pixel(x, y).unwrap() != Hub75Color::black())
+                .count()
+        };
+
+        assert!(matches!(
+            clock.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        let with_colon = lit(&frame);
+        assert!(matches!(clock.next_into(&mut frame), AnimationState::Wait));
+
+        now.set(TimeOfDay::new(9, 5, 1));
+        assert!(matches!(
+            clock.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(lit(&frame), with_colon - 8);
+
+        // Without blinking, only minute changes redraw
+        clock.set_blink_colon(false);
+        assert!(matches!(
+            clock.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        now.set(TimeOfDay::new(9, 5, 2));
+        assert!(matches!(clock.next_into(&mut frame), AnimationState::Wait));
+        assert_eq!(lit(&frame), with_colon);
+    }
+
+    #[test]
+    fn test_analog_hands() {
+        let mut clock =
+            Clock::<_, 31, 31, 6>::new(|| TimeOfDay::new(3, 0, 30), AREA, Hub75Color::white());
+        clock.set_face(ClockFace::Analog);
+        let mut frame = Hub75FrameBuffer::new();
+        clock.render_into(&mut frame);
+
+        // Hour hand to the right, minute hand up, second hand down
+        assert_eq!(frame.get_pixel(22, 15).unwrap(), Hub75Color::white());
+        assert_eq!(frame.get_pixel(15, 4).unwrap(), Hub75Color::white());
+        assert_eq!(frame.get_pixel(15, 28).unwrap(), Hub75Color::red());
+        // Hour marks on the rim
+        assert_eq!(frame.get_pixel(30, 15).unwrap(), Hub75Color::white());
+        assert_eq!(frame.get_pixel(25, 15).unwrap(), Hub75Color::black());
+    }
+}
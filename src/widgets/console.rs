@@ -0,0 +1,271 @@
+//! Scrolling text console
+//!
+//! A [`Console`] keeps the last `LINES` lines of text written to it, each up
+//! to `COLUMNS` bytes of UTF-8 long, and draws the newest ones that fit into a
+//! region of the frame like a terminal. It implements [`core::fmt::Write`],
+//! so log output or chat-style messages can be written to it with `write!`
+//! and `writeln!`. Long lines wrap, `'\n'` starts a new line and `'\r'` is
+//! ignored. Text uses the built-in [`font`](crate::font); characters it has
+//! no glyph for are stored as `'\u{7f}'`, which is drawn as a hollow box.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use core::fmt::Write;
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::widgets::Console;
+//! use embedded_graphics_core::{geometry::{Point, Size}, primitives::Rectangle};
+//!
+//! // 10 characters per line, 16 lines of history
+//! let mut console = Console::<10, 16>::new();
+//! for t in [21, 22, 23, 24] {
+//!     writeln!(console, "T={}C", t).unwrap();
+//! }
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! let area = Rectangle::new(Point::zero(), Size::new(64, 32));
+//! // Four lines of 8 rows fit
+//! assert_eq!(console.draw(&mut frame, area, Hub75Color::green()), 4);
+//! ```
+
+use core::fmt;
+
+use embedded_graphics_core::primitives::Rectangle;
+use heapless::{Deque, String};
+
+use crate::{
+    color::Hub75Color,
+    font::{self, GLYPH_HEIGHT},
+    frame_buffer::Hub75FrameBuffer,
+    window::Window,
+};
+
+/// Stored in place of characters the font does not cover, drawn as a box
+const REPLACEMENT: char = '\u{7f}';
+
+/// Terminal-like text area with a ring buffer of history
+///
+/// Holds up to `LINES` lines of up to `COLUMNS` bytes, so `COLUMNS` ASCII
+/// characters or fewer symbols and accented letters; `LINES` must be at least
+/// 1.
+#[derive(Debug, Clone)]
+pub struct Console<const COLUMNS: usize, const LINES: usize> {
+    /// Lines from oldest to newest, the newest being the one written to
+    lines: Deque<String<COLUMNS>, LINES>,
+    /// Whether a `'\n'` was written that has not started a line yet
+    pending_newline: bool,
+    /// Number of lines the view is scrolled back from the newest
+    scroll: usize,
+    /// Blank rows between lines
+    line_spacing: usize,
+}
+
+impl<const COLUMNS: usize, const LINES: usize> Default for Console<COLUMNS, LINES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const COLUMNS: usize, const LINES: usize> Console<COLUMNS, LINES> {
+    /// Create an empty console with one blank row between lines
+    pub fn new() -> Self {
+        Self {
+            lines: Deque::new(),
+            pending_newline: false,
+            scroll: 0,
+            line_spacing: 1,
+        }
+    }
+
+    /// Set the number of blank rows between lines
+    pub fn set_line_spacing(&mut self, spacing: usize) {
+        self.line_spacing = spacing;
+    }
+
+    /// Scroll the view back by `lines` from the newest line
+    ///
+    /// The scroll position is kept as lines are added, and is limited so the
+    /// oldest line stays in view.
+    pub fn set_scroll(&mut self, lines: usize) {
+        self.scroll = lines.min(self.lines.len().saturating_sub(1));
+    }
+
+    /// Get the number of lines the view is scrolled back
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// Get the number of lines in the history
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Check whether nothing was written since the console was created or
+    /// cleared
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Iterate over the lines in the history, oldest first
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(String::as_str)
+    }
+
+    /// Forget all lines and scroll back to the newest
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.pending_newline = false;
+        self.scroll = 0;
+    }
+
+    /// Append a character, wrapping or starting a new line as needed
+    pub fn push_char(&mut self, c: char) {
+        match c {
+            '\r' => {}
+            '\n' => {
+                // Started lazily so a trailing newline does not leave an empty
+                // line at the bottom of the view
+                if core::mem::replace(&mut self.pending_newline, true) || self.lines.is_empty() {
+                    self.new_line();
+                }
+            }
+            c => {
+                let c = if font::has_glyph(c) { c } else { REPLACEMENT };
+                let full = self
+                    .lines
+                    .back()
+                    .is_none_or(|line| line.len() + c.len_utf8() > COLUMNS);
+                if core::mem::take(&mut self.pending_newline) || full {
+                    self.new_line();
+                }
+                if let Some(line) = self.lines.back_mut() {
+                    let _ = line.push(c);
+                }
+            }
+        }
+    }
+
+    /// Append a string, see [`push_char`](Self::push_char)
+    pub fn push_str(&mut self, text: &str) {
+        text.chars().for_each(|c| self.push_char(c));
+    }
+
+    /// Start an empty line, dropping the oldest if the history is full
+    fn new_line(&mut self) {
+        // A console without room for text drops everything
+        if COLUMNS == 0 {
+            return;
+        }
+        if self.lines.is_full() {
+            self.lines.pop_front();
+        } else if self.scroll > 0 {
+            // Keep the same lines in view
+            self.scroll += 1;
+        }
+        // Cannot fail, there is room now
+        let _ = self.lines.push_back(String::new());
+    }
+
+    /// Draw the newest lines that fit inside `area`, top to bottom, returning
+    /// how many were drawn
+    ///
+    /// The area is not cleared first. With the view scrolled back, the lines
+    /// that many lines before the newest are drawn instead.
+    pub fn draw<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        area: Rectangle,
+        color: Hub75Color<COLOR_BITS>,
+    ) -> usize {
+        let pitch = GLYPH_HEIGHT + self.line_spacing;
+        let fitting = (area.size.height as usize + self.line_spacing) / pitch;
+        let end = self.lines.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(fitting);
+
+        let mut window = Window::new(frame, area);
+        let mut y = 0;
+        for line in self.lines().skip(start).take(end - start) {
+            window.draw_text(line, 0, y, color);
+            y += pitch as i32;
+        }
+        end - start
+    }
+}
+
+impl<const COLUMNS: usize, const LINES: usize> fmt::Write for Console<COLUMNS, LINES> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.push_str(text);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push_char(c);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+    use embedded_graphics_core::geometry::{Point, Size};
+
+    fn lines<const COLUMNS: usize, const LINES: usize>(
+        console: &Console<COLUMNS, LINES>,
+    ) -> heapless::Vec<&str, LINES> {
+        console.lines().collect()
+    }
+
+    #[test]
+    fn test_console_lines() {
+        let mut console = Console::<4, 3>::new();
+        assert!(console.is_empty());
+
+        // A trailing newline only takes effect once more text follows
+        writeln!(console, "ab\r").unwrap();
+        assert_eq!(lines(&console), ["ab"]);
+        write!(console, "cdefgh\n\n中").unwrap();
+        assert_eq!(lines(&console), ["gh", "", "\u{7f}"]);
+
+        // Multi-byte characters wrap by their encoded length
+        write!(console, "é°").unwrap();
+        assert_eq!(lines(&console), ["", "\u{7f}é", "°"]);
+
+        // Leading newlines still start lines
+        console.clear();
+        console.push_str("\nx");
+        assert_eq!(lines(&console), ["", "x"]);
+
+        let mut empty = Console::<0, 4>::new();
+        empty.push_str("ignored\n");
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_console_draw() {
+        let mut frame = Hub75FrameBuffer::<16, 20, 6>::new();
+        let area = Rectangle::new(Point::new(0, 2), Size::new(16, 18));
+        let white = Hub75Color::white();
+        let mut console = Console::<2, 8>::new();
+
+        // The two newest lines fit, "|" is column 2 of the glyph
+        console.push_str("-\n|\n |");
+        assert_eq!(console.draw(&mut frame, area, white), 2);
+        assert_eq!(frame.get_pixel(2, 2).unwrap(), white);
+        assert_eq!(frame.get_pixel(8, 10).unwrap(), white);
+        assert_eq!(frame.get_pixel(2, 10).unwrap(), Hub75Color::black());
+
+        // Scrolled back, the view follows its lines as more are added
+        frame.clear();
+        console.set_scroll(1);
+        console.push_str("\n-");
+        assert_eq!(console.scroll(), 2);
+        assert_eq!(console.draw(&mut frame, area, white), 2);
+        assert_eq!(frame.get_pixel(2, 2).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(2, 5).unwrap(), white);
+        assert_eq!(frame.get_pixel(2, 10).unwrap(), white);
+
+        console.set_scroll(100);
+        assert_eq!(console.scroll(), 3);
+    }
+}
@@ -0,0 +1,441 @@
+//! Horizontally scrolling text
+//!
+//! A [`Marquee`] scrolls a string of any length across a band of the panel
+//! using the built-in [`font`](crate::font). The speed is set in 1/256ths of
+//! a pixel per frame; at fractional positions each column is spread over two
+//! neighbouring pixels, so slow scrolling moves smoothly instead of in visible
+//! one-pixel jumps.
+//!
+//! Like a commercial LED sign, a marquee can also stop and hold still once
+//! the text has scrolled fully into view, or each time a word reaches the
+//! left edge, before scrolling on (see [`Pause`]).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{AnimationState, Hub75Color, Hub75FrameBuffer};
+//! use hub75::widgets::{Marquee, Pause};
+//!
+//! let mut marquee = Marquee::<64, 32, 6>::new("Next train: 4 min", 12, Hub75Color::yellow());
+//! marquee.set_speed(Marquee::<64, 32, 6>::PIXEL / 2);
+//! marquee.set_looping(true);
+//! // Hold still for a second at 60 fps at the start of every word
+//! marquee.set_pause(Pause::EachWord, 60);
+//!
+//! let mut frame = Hub75FrameBuffer::new();
+//! assert!(matches!(marquee.next_into(&mut frame), AnimationState::Rendered));
+//! ```
+
+use crate::{
+    animation::AnimationState,
+    color::Hub75Color,
+    composite::BlendMode,
+    font::{self, GLYPH_ADVANCE, GLYPH_HEIGHT},
+    frame_buffer::Hub75FrameBuffer,
+    scene::FrameSource,
+    Hub75Error,
+};
+
+/// Where a [`Marquee`] stops scrolling and holds still
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Pause {
+    /// Scroll without stopping
+    #[default]
+    Never,
+    /// Stop with the text centered, or with its start at the left edge if it
+    /// is wider than the panel
+    WhenVisible,
+    /// Stop each time the start of a word reaches the left edge
+    EachWord,
+}
+
+/// Scrolling text confined to the rows it is drawn on
+#[derive(Debug, Clone)]
+pub struct Marquee<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Text being scrolled
+    text: &'a str,
+    /// Top row of the text
+    y: i32,
+    /// Text color
+    color: Hub75Color<COLOR_BITS>,
+    /// Distance scrolled so far, in 1/256 pixels
+    position: u32,
+    /// Distance scrolled per frame, in 1/256 pixels
+    speed: u16,
+    /// Blank pixels between repeats when looping
+    gap: usize,
+    /// Whether the text repeats instead of scrolling out once
+    looping: bool,
+    /// Where the text stops scrolling
+    pause: Pause,
+    /// Number of frames the text holds still at each stop
+    hold_frames: usize,
+    /// Frames left before scrolling on from the current stop
+    holding: usize,
+    /// Whether the text has reached a stop that has not been drawn yet
+    stopped: bool,
+}
+
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    Marquee<'a, WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Speed of one pixel per frame
+    pub const PIXEL: u16 = 256;
+
+    /// Create a marquee drawing `text` with its top row at `y`
+    ///
+    /// The text starts just past the right edge and scrolls one pixel per
+    /// frame, once, with a gap of three characters when set to loop.
+    pub fn new(text: &'a str, y: i32, color: Hub75Color<COLOR_BITS>) -> Self {
+        Self {
+            text,
+            y,
+            color,
+            position: 0,
+            speed: Self::PIXEL,
+            gap: 3 * GLYPH_ADVANCE,
+            looping: false,
+            pause: Pause::Never,
+            hold_frames: 0,
+            holding: 0,
+            stopped: false,
+        }
+    }
+
+    /// Replace the text and scroll it in from the right edge
+    pub fn set_text(&mut self, text: &'a str) {
+        self.text = text;
+        self.reset();
+    }
+
+    /// Get the text being scrolled
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Set the text color
+    pub fn set_color(&mut self, color: Hub75Color<COLOR_BITS>) {
+        self.color = color;
+    }
+
+    /// Set the scroll speed in 1/256 pixels per frame (see [`Self::PIXEL`])
+    pub fn set_speed(&mut self, speed: u16) {
+        self.speed = speed;
+    }
+
+    /// Get the scroll speed in 1/256 pixels per frame
+    pub fn speed(&self) -> u16 {
+        self.speed
+    }
+
+    /// Set the number of blank pixels between repeats when looping
+    pub fn set_gap(&mut self, gap: usize) {
+        self.gap = gap;
+    }
+
+    /// Set whether the text repeats forever instead of scrolling out once
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Set where the text stops scrolling and for how many frames it holds
+    /// still each time
+    pub fn set_pause(&mut self, pause: Pause, hold_frames: usize) {
+        self.pause = pause;
+        self.hold_frames = hold_frames;
+        self.holding = self.holding.min(hold_frames);
+    }
+
+    /// Check if the text is holding still at a stop
+    pub fn is_paused(&self) -> bool {
+        self.holding > 0
+    }
+
+    /// Check if the text has scrolled out and will not repeat
+    pub fn is_done(&self) -> bool {
+        !self.looping && self.position >= self.end_position()
+    }
+
+    /// Scroll the text back to just past the right edge
+    pub fn reset(&mut self) {
+        self.position = 0;
+        self.holding = 0;
+        self.stopped = false;
+    }
+
+    /// Distance from the start of one repeat to the next, in pixels
+    fn period(&self) -> u32 {
+        (font::text_width(self.text) + self.gap) as u32
+    }
+
+    /// Position at which the text has completely left the panel
+    fn end_position(&self) -> u32 {
+        (font::text_width(self.text) + WIDTH) as u32 * Self::PIXEL as u32
+    }
+
+    /// Positions of the first repeat at which the text stops
+    fn stops(&self) -> impl Iterator<Item = u32> + '_ {
+        let text_width = font::text_width(self.text);
+        // Left edge of the text at each stop, in pixels
+        let centered = match self.pause {
+            // The spacing after the last character does not count
+            Pause::WhenVisible => Some((WIDTH as i32 - text_width as i32 + 1).max(0) / 2),
+            _ => None,
+        };
+        let words = self
+            .text
+            .chars()
+            .scan(' ', |previous, c| {
+                Some((core::mem::replace(previous, c), c))
+            })
+            .enumerate()
+            .filter(|&(_, (previous, c))| previous == ' ' && c != ' ')
+            .map(|(index, _)| -((index * GLYPH_ADVANCE) as i32))
+            .filter(|_| self.pause == Pause::EachWord);
+        centered
+            .into_iter()
+            .chain(words)
+            .map(|x| (WIDTH as i32 - x) as u32 * Self::PIXEL as u32)
+    }
+
+    /// First position in `(from, to]` at which the text, or any of its
+    /// repeats when looping, stops
+    fn next_stop(&self, from: u32, to: u32) -> Option<u32> {
+        let period = self.period() * Self::PIXEL as u32;
+        self.stops()
+            .map(|stop| match from.checked_sub(stop) {
+                Some(behind) if self.looping && period > 0 => stop + (behind / period + 1) * period,
+                Some(_) => u32::MAX,
+                None => stop,
+            })
+            .filter(|&stop| stop <= to)
+            .min()
+    }
+
+    /// Move the text by one frame's worth of scrolling
+    fn advance(&mut self) {
+        let to = self.position.saturating_add(self.speed as u32);
+        let stop = self.next_stop(self.position, to);
+        self.stopped = stop.is_some();
+        self.position = stop.unwrap_or(to);
+        if self.looping {
+            // Once the first repeat has left, the next one takes its place
+            let period = self.period() * Self::PIXEL as u32;
+            let limit = WIDTH as u32 * Self::PIXEL as u32 + period;
+            if period > 0 && self.position >= limit {
+                self.position = limit - period + (self.position - limit) % period;
+            }
+        } else {
+            self.position = self.position.min(self.end_position());
+        }
+    }
+
+    /// Draw the text at its current position, clearing the rows it occupies
+    pub fn render_into(&self, frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) {
+        let top = self.y.clamp(0, HEIGHT as i32) as usize;
+        let bottom = (self.y + GLYPH_HEIGHT as i32).clamp(0, HEIGHT as i32) as usize;
+        frame.fill_rect(0, top, WIDTH, bottom - top, Hub75Color::black());
+
+        // Left edge of the first repeat in 1/256 pixels, split into a whole
+        // pixel and the share of each column carried over to the next pixel
+        let x = (WIDTH as i32 * Self::PIXEL as i32) - self.position as i32;
+        let (x, fraction) = (x >> 8, (x & 0xFF) as u8);
+        let repeats = if self.looping {
+            (WIDTH as u32).div_ceil(self.period().max(1)) as i32 + 1
+        } else {
+            1
+        };
+
+        for repeat in 0..repeats {
+            let x = x + repeat * self.period() as i32;
+            let (near, far) = (self.color.scale(255 - fraction), self.color.scale(fraction));
+            frame.draw_text_blended(self.text, x, self.y, near, BlendMode::Add);
+            if fraction > 0 {
+                frame.draw_text_blended(self.text, x + 1, self.y, far, BlendMode::Add);
+            }
+        }
+    }
+
+    /// Scroll by one frame and draw the text into `frame`
+    ///
+    /// Returns [`AnimationState::Wait`] while the text holds still at a stop
+    /// and [`AnimationState::Done`] once the text has scrolled out and the
+    /// marquee is not looping.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        if self.is_done() {
+            return AnimationState::Done;
+        }
+        if self.holding > 0 {
+            self.holding -= 1;
+            if self.holding == 0 {
+                self.advance();
+            }
+            return AnimationState::Wait;
+        }
+        Marquee::render_into(self, frame);
+        // Hold still once the text has been drawn at a stop
+        if core::mem::take(&mut self.stopped) && self.hold_frames > 0 {
+            self.holding = self.hold_frames;
+        } else {
+            self.advance();
+        }
+        AnimationState::Rendered
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for Marquee<'_, WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        Marquee::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        Marquee::render_into(self, frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestMarquee<'a> = Marquee<'a, 12, 7, 6>;
+
+    fn column_lit(frame: &Hub75FrameBuffer<12, 7, 6>, x: usize) -> bool {
+        (0..7).any(|y| frame.get_pixel(x, y).unwrap() != Hub75Color::black())
+    }
+
+    #[test]
+    fn test_scroll_once() {
+        let mut marquee = TestMarquee::new("|", 0, Hub75Color::white());
+        let mut frame = Hub75FrameBuffer::new();
+        frame.fill(Hub75Color::red());
+
+        // Starts off screen, clearing its rows
+        assert!(matches!(
+            marquee.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert!((0..12).all(|x| !column_lit(&frame, x)));
+
+        // The bar of '|' is its third column
+        for _ in 0..10 {
+            marquee.next_into(&mut frame);
+        }
+        assert!(column_lit(&frame, 4));
+        assert!(!column_lit(&frame, 3) && !column_lit(&frame, 5));
+
+        // Scrolls out after its width plus the panel width
+        for _ in 11..18 {
+            marquee.next_into(&mut frame);
+        }
+        assert!(marquee.is_done());
+        assert!(matches!(
+            marquee.next_into(&mut frame),
+            AnimationState::Done
+        ));
+    }
+
+    #[test]
+    fn test_sub_pixel_scrolling() {
+        let mut marquee = TestMarquee::new("|", 0, Hub75Color::white());
+        marquee.set_speed(TestMarquee::PIXEL / 2);
+        let mut frame = Hub75FrameBuffer::new();
+        for _ in 0..22 {
+            marquee.next_into(&mut frame);
+        }
+
+        // Halfway between two pixels, the bar is split over both
+        assert_eq!(
+            frame.get_pixel(3, 3).unwrap(),
+            Hub75Color::white().scale(127)
+        );
+        assert_eq!(
+            frame.get_pixel(4, 3).unwrap(),
+            Hub75Color::white().scale(128)
+        );
+        assert!(!column_lit(&frame, 2) && !column_lit(&frame, 5));
+    }
+
+    #[test]
+    fn test_looping() {
+        let mut marquee = TestMarquee::new("||", 0, Hub75Color::white());
+        marquee.set_gap(0);
+        marquee.set_looping(true);
+        let mut frame = Hub75FrameBuffer::new();
+
+        // With a 12 pixel period the screen keeps showing two bars
+        for _ in 0..200 {
+            assert!(matches!(
+                marquee.next_into(&mut frame),
+                AnimationState::Rendered
+            ));
+        }
+        let lit = (0..12).filter(|&x| column_lit(&frame, x)).count();
+        assert_eq!(lit, 2);
+        assert!(!marquee.is_done());
+    }
+
+    /// Advance until the marquee pauses, returning the frames rendered
+    fn frames_to_pause(marquee: &mut TestMarquee, frame: &mut Hub75FrameBuffer<12, 7, 6>) -> usize {
+        let mut frames = 0;
+        while matches!(marquee.next_into(frame), AnimationState::Rendered) {
+            frames += 1;
+        }
+        frames
+    }
+
+    #[test]
+    fn test_pause_when_visible() {
+        let mut marquee = TestMarquee::new("|", 0, Hub75Color::white());
+        marquee.set_speed(TestMarquee::PIXEL * 3 / 2);
+        marquee.set_pause(Pause::WhenVisible, 2);
+        let mut frame = Hub75FrameBuffer::new();
+
+        // Stops exactly with the 5 pixel glyph centered, at x = 3
+        assert_eq!(frames_to_pause(&mut marquee, &mut frame), 7);
+        assert!(marquee.is_paused());
+        assert!(matches!(
+            marquee.next_into(&mut frame),
+            AnimationState::Wait
+        ));
+        assert!(!marquee.is_paused());
+        assert!(column_lit(&frame, 5));
+        assert!(!column_lit(&frame, 4) && !column_lit(&frame, 6));
+
+        // Scrolls on and out without stopping again
+        let mut frames = 0;
+        while !marquee.is_done() {
+            assert!(matches!(
+                marquee.next_into(&mut frame),
+                AnimationState::Rendered
+            ));
+            frames += 1;
+        }
+        assert_eq!(frames, 5);
+    }
+
+    #[test]
+    fn test_pause_each_word() {
+        let mut marquee = TestMarquee::new("a  bc d", 0, Hub75Color::white());
+        marquee.set_pause(Pause::EachWord, 1);
+        marquee.set_looping(true);
+        let mut frame = Hub75FrameBuffer::new();
+
+        // Words start 0, 18 and 36 pixels into the text, and the next repeat
+        // 42 + 18 pixels after the first
+        let gaps: [usize; 4] = core::array::from_fn(|_| frames_to_pause(&mut marquee, &mut frame));
+        assert_eq!(gaps, [13, 18, 18, 24]);
+    }
+}
@@ -0,0 +1,30 @@
+//! Ready-made display widgets
+//!
+//! Widgets draw into a frame buffer, usually the display's back buffer.
+//! Animated widgets keep their own state between frames and also implement
+//! [`FrameSource`](crate::scene::FrameSource), so they can be shown as a scene
+//! or layered in a [`Composite`](crate::composite::Composite).
+
+pub mod clock;
+pub mod console;
+pub mod marquee;
+pub mod scoreboard;
+pub mod sparkline;
+pub mod spectrum;
+pub mod text_box;
+pub mod text_effects;
+pub mod ticker;
+pub mod timer;
+pub mod weather;
+
+pub use clock::{Clock, ClockFace, TimeOfDay, TimeSource};
+pub use console::Console;
+pub use marquee::{Marquee, Pause};
+pub use scoreboard::{Scoreboard, Side};
+pub use sparkline::{GraphStyle, Sparkline};
+pub use spectrum::{BarColors, Spectrum};
+pub use text_box::{Alignment, TextBox, VerticalAlignment};
+pub use text_effects::TextEffects;
+pub use ticker::Ticker;
+pub use timer::{Timer, TimerMode};
+pub use weather::{Condition, TemperatureUnit, WeatherDashboard, WeatherReport};
@@ -0,0 +1,401 @@
+//! Two-team scoreboard
+//!
+//! A [`Scoreboard`] splits a region of the frame into a home and an away
+//! half, each with the team name along the top and its score in large digits
+//! below, and a period or game clock line along the bottom. The digits are
+//! drawn as large as the region allows, so the same scoreboard fills a 64x32
+//! panel as well as a chain of larger ones. Scores are changed with
+//! [`increment`](Scoreboard::increment) and
+//! [`decrement`](Scoreboard::decrement), which map directly onto buttons.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{AnimationState, Hub75FrameBuffer};
+//! use hub75::widgets::{Scoreboard, Side};
+//! use embedded_graphics_core::{geometry::{Point, Size}, primitives::Rectangle};
+//!
+//! let area = Rectangle::new(Point::zero(), Size::new(64, 32));
+//! let mut scoreboard = Scoreboard::<64, 32, 6>::new("HOME", "AWAY", area);
+//! scoreboard.set_period("Q2 07:45");
+//!
+//! let mut frame = Hub75FrameBuffer::new();
+//! assert!(matches!(scoreboard.next_into(&mut frame), AnimationState::Rendered));
+//!
+//! // A button press for the home team
+//! scoreboard.increment(Side::Home);
+//! assert_eq!(scoreboard.score(Side::Home), 1);
+//! assert!(matches!(scoreboard.next_into(&mut frame), AnimationState::Rendered));
+//! assert!(matches!(scoreboard.next_into(&mut frame), AnimationState::Wait));
+//! ```
+
+use core::fmt::Write;
+
+use embedded_graphics_core::{
+    geometry::{Point, Size},
+    primitives::Rectangle,
+};
+
+use crate::{
+    animation::AnimationState,
+    color::Hub75Color,
+    font::{visible_width, Font5x7, Scaled, GLYPH_ADVANCE, GLYPH_HEIGHT},
+    frame_buffer::Hub75FrameBuffer,
+    scene::FrameSource,
+    window::Window,
+    Hub75Error,
+};
+
+/// Largest factor the score digits are enlarged by
+const MAX_SCALE: u8 = 8;
+
+/// One of the two teams on a [`Scoreboard`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Side {
+    /// Team shown on the left
+    #[default]
+    Home,
+    /// Team shown on the right
+    Away,
+}
+
+impl Side {
+    /// Get the other team
+    pub fn other(self) -> Self {
+        match self {
+            Side::Home => Side::Away,
+            Side::Away => Side::Home,
+        }
+    }
+
+    /// Index into per-team arrays
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Team names, scores and a period line laid out in a region of the frame
+#[derive(Debug, Clone)]
+pub struct Scoreboard<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Region of the frame the scoreboard is drawn in
+    area: Rectangle,
+    /// Home and away team names
+    names: [&'a str; 2],
+    /// Home and away scores
+    scores: [u16; 2],
+    /// Period, game clock or other status along the bottom
+    period: &'a str,
+    /// Colors of the home and away names
+    team_colors: [Hub75Color<COLOR_BITS>; 2],
+    /// Color of the scores
+    score_color: Hub75Color<COLOR_BITS>,
+    /// Color of the period line
+    period_color: Hub75Color<COLOR_BITS>,
+    /// Whether anything changed since the scoreboard was last drawn
+    changed: bool,
+}
+
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    Scoreboard<'a, WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a scoreboard for two teams at 0 inside `area`
+    ///
+    /// The home name is red, the away name cyan, the scores yellow and the
+    /// period line white.
+    pub fn new(home: &'a str, away: &'a str, area: Rectangle) -> Self {
+        Self {
+            area,
+            names: [home, away],
+            scores: [0; 2],
+            period: "",
+            team_colors: [Hub75Color::red(), Hub75Color::cyan()],
+            score_color: Hub75Color::yellow(),
+            period_color: Hub75Color::white(),
+            changed: true,
+        }
+    }
+
+    /// Set the name of a team
+    pub fn set_name(&mut self, side: Side, name: &'a str) {
+        self.changed |= self.names[side.index()] != name;
+        self.names[side.index()] = name;
+    }
+
+    /// Set the period, game clock or other status shown along the bottom
+    pub fn set_period(&mut self, period: &'a str) {
+        self.changed |= self.period != period;
+        self.period = period;
+    }
+
+    /// Get the score of a team
+    pub fn score(&self, side: Side) -> u16 {
+        self.scores[side.index()]
+    }
+
+    /// Set the score of a team
+    pub fn set_score(&mut self, side: Side, score: u16) {
+        self.changed |= self.scores[side.index()] != score;
+        self.scores[side.index()] = score;
+    }
+
+    /// Add one to the score of a team, returning the new score
+    pub fn increment(&mut self, side: Side) -> u16 {
+        self.add(side, 1)
+    }
+
+    /// Subtract one from the score of a team, stopping at 0, returning the
+    /// new score
+    ///
+    /// Meant for taking back a mistaken button press.
+    pub fn decrement(&mut self, side: Side) -> u16 {
+        let score = self.score(side).saturating_sub(1);
+        self.set_score(side, score);
+        score
+    }
+
+    /// Add `points` to the score of a team, saturating, returning the new
+    /// score
+    pub fn add(&mut self, side: Side, points: u16) -> u16 {
+        let score = self.score(side).saturating_add(points);
+        self.set_score(side, score);
+        score
+    }
+
+    /// Set both scores back to 0
+    pub fn reset(&mut self) {
+        self.set_score(Side::Home, 0);
+        self.set_score(Side::Away, 0);
+    }
+
+    /// Set the color of a team's name
+    pub fn set_team_color(&mut self, side: Side, color: Hub75Color<COLOR_BITS>) {
+        self.team_colors[side.index()] = color;
+        self.changed = true;
+    }
+
+    /// Set the color of the scores
+    pub fn set_score_color(&mut self, color: Hub75Color<COLOR_BITS>) {
+        self.score_color = color;
+        self.changed = true;
+    }
+
+    /// Set the color of the period line
+    pub fn set_period_color(&mut self, color: Hub75Color<COLOR_BITS>) {
+        self.period_color = color;
+        self.changed = true;
+    }
+
+    /// Get the largest factor the score digits can be enlarged by for both
+    /// scores, and at least two digits, to fit into `width` by `height`
+    fn score_scale(&self, width: i32, height: i32) -> u8 {
+        let digits = self
+            .scores
+            .iter()
+            .map(|&score| score.checked_ilog10().unwrap_or(0) as i32 + 1)
+            .max()
+            .unwrap_or(1)
+            .max(2);
+        (1..=MAX_SCALE)
+            .rev()
+            .find(|&scale| {
+                let scale = scale as i32;
+                // One column of margin on each side
+                digits * GLYPH_ADVANCE as i32 * scale - scale <= width - 2
+                    && GLYPH_HEIGHT as i32 * scale <= height
+            })
+            .unwrap_or(1)
+    }
+
+    /// Draw the scoreboard, clearing the rest of the area
+    pub fn render_into(&mut self, frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) {
+        self.changed = false;
+        Window::new(frame, self.area).clear();
+        let width = self.area.size.width as i32;
+        let height = self.area.size.height as i32;
+        let line = GLYPH_HEIGHT as i32 + 1;
+
+        // Names need room for a line of scores below, the period for another
+        let with_names = height >= 2 * line;
+        let with_period = height >= 3 * line;
+        let top = if with_names { line } else { 0 };
+        let bottom = if with_period { height - line } else { height };
+
+        let half = width / 2;
+        let scale = self.score_scale(half, bottom - top);
+        let font = Scaled::new(Font5x7, scale);
+        for (side, x) in [(Side::Home, 0), (Side::Away, width - half)] {
+            let area = Rectangle::new(
+                self.area.top_left + Point::new(x, 0),
+                Size::new(half as u32, height as u32),
+            );
+            let mut window = Window::new(frame, area);
+            if with_names {
+                let name = self.names[side.index()];
+                let x = (half - visible_width(name, 1)) / 2;
+                window.draw_text(name, x.max(0), 0, self.team_colors[side.index()]);
+            }
+
+            let mut digits = heapless::String::<5>::new();
+            let _ = write!(digits, "{}", self.score(side));
+            let x = (half - visible_width(&digits, scale)) / 2;
+            let y = top + (bottom - top - GLYPH_HEIGHT as i32 * scale as i32) / 2;
+            window.draw_text_with(&font, &digits, x.max(0), y, self.score_color);
+        }
+
+        if with_period {
+            let mut window = Window::new(frame, self.area);
+            let x = (width - visible_width(self.period, 1)) / 2;
+            window.draw_text(self.period, x.max(0), bottom + 1, self.period_color);
+        }
+    }
+
+    /// Draw the scoreboard into `frame` if anything changed since it was last
+    /// drawn
+    ///
+    /// Returns [`AnimationState::Wait`] when nothing changed; a scoreboard is
+    /// never done.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        if !self.changed {
+            return AnimationState::Wait;
+        }
+        Scoreboard::render_into(self, frame);
+        AnimationState::Rendered
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for Scoreboard<'_, WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        Scoreboard::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        Scoreboard::render_into(self, frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::primitives::PointsIter;
+
+    fn lit<const W: usize, const H: usize>(
+        frame: &Hub75FrameBuffer<W, H, 6>,
+        area: Rectangle,
+        color: Hub75Color<6>,
+    ) -> usize {
+        area.points()
+            .filter(|p| frame.get_pixel(p.x as usize, p.y as usize).unwrap() == color)
+            .count()
+    }
+
+    #[test]
+    fn test_score_updates() {
+        let area = Rectangle::new(Point::zero(), Size::new(64, 32));
+        let mut scoreboard = Scoreboard::<64, 32, 6>::new("A", "B", area);
+        let mut frame = Hub75FrameBuffer::new();
+        scoreboard.render_into(&mut frame);
+
+        assert_eq!(scoreboard.increment(Side::Home), 1);
+        assert_eq!(scoreboard.add(Side::Away, 3), 3);
+        assert_eq!(scoreboard.decrement(Side::Away), 2);
+        assert_eq!(
+            (scoreboard.score(Side::Home), scoreboard.score(Side::Away)),
+            (1, 2)
+        );
+        assert!(matches!(
+            scoreboard.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+
+        // Nothing to undo at 0, so nothing to redraw
+        scoreboard.reset();
+        scoreboard.render_into(&mut frame);
+        assert_eq!(scoreboard.decrement(Side::Home), 0);
+        scoreboard.set_name(Side::Away.other(), "A");
+        assert!(matches!(
+            scoreboard.next_into(&mut frame),
+            AnimationState::Wait
+        ));
+        scoreboard.set_score(Side::Home, u16::MAX);
+        assert_eq!(scoreboard.increment(Side::Home), u16::MAX);
+    }
+
+    #[test]
+    fn test_score_scale() {
+        let area = Rectangle::new(Point::zero(), Size::new(64, 32));
+        let mut scoreboard = Scoreboard::<64, 32, 6>::new("A", "B", area);
+        // 16 rows between names and period, 32 columns per half
+        assert_eq!(scoreboard.score_scale(32, 16), 2);
+        // Limited by the width, at least two digits wide
+        assert_eq!(scoreboard.score_scale(64, 46), 5);
+        scoreboard.set_score(Side::Away, 100);
+        assert_eq!(scoreboard.score_scale(64, 46), 3);
+        assert_eq!(scoreboard.score_scale(8, 4), 1);
+    }
+
+    #[test]
+    fn test_scoreboard_layout() {
+        let area = Rectangle::new(Point::zero(), Size::new(64, 32));
+        let mut scoreboard = Scoreboard::<64, 32, 6>::new("HOME", "AWAY", area);
+        scoreboard.set_period("Q2");
+        scoreboard.set_score(Side::Away, 7);
+        let mut frame = Hub75FrameBuffer::new();
+        scoreboard.render_into(&mut frame);
+
+        // Names along the top of each half
+        let top = |x| Rectangle::new(Point::new(x, 0), Size::new(32, 7));
+        assert!(lit(&frame, top(0), Hub75Color::red()) > 0);
+        assert_eq!(lit(&frame, top(32), Hub75Color::red()), 0);
+        assert!(lit(&frame, top(32), Hub75Color::cyan()) > 0);
+
+        // Single digits at double size centered in rows 9 to 22 of each half
+        let scores = |x| Rectangle::new(Point::new(x, 8), Size::new(32, 16));
+        let yellow = Hub75Color::yellow();
+        assert!(lit(&frame, scores(0), yellow) > 0);
+        assert!(lit(&frame, scores(32), yellow) > 0);
+        let column = |x| {
+            lit(
+                &frame,
+                Rectangle::new(Point::new(x, 8), Size::new(1, 17)),
+                yellow,
+            )
+        };
+        assert_eq!(
+            (column(10), column(11), column(20), column(21)),
+            (0, 10, 10, 0)
+        );
+        assert_eq!(
+            lit(
+                &frame,
+                Rectangle::new(Point::new(0, 8), Size::new(64, 1)),
+                yellow
+            ),
+            0
+        );
+        assert_eq!(
+            lit(
+                &frame,
+                Rectangle::new(Point::new(0, 23), Size::new(64, 1)),
+                yellow
+            ),
+            0
+        );
+
+        // Period centered along the bottom
+        let period = Rectangle::new(Point::new(26, 25), Size::new(11, 7));
+        assert!(lit(&frame, period, Hub75Color::white()) > 0);
+    }
+}
@@ -0,0 +1,231 @@
+//! Scrolling graphs of recent samples
+//!
+//! A [`Sparkline`] keeps the last `N` samples in a ring buffer and draws them
+//! as a line or bar graph, one column per sample with the newest at the right
+//! edge. Pushing a sample each tick scrolls the graph to the left. The value
+//! range is either fixed or follows the samples currently shown.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::widgets::{GraphStyle, Sparkline};
+//! use embedded_graphics_core::{geometry::{Point, Size}, primitives::Rectangle};
+//!
+//! let mut temperature = Sparkline::<64>::new();
+//! temperature.set_style(GraphStyle::Bars);
+//! for reading in [21, 22, 22, 24, 23] {
+//!     temperature.push(reading);
+//! }
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! let area = Rectangle::new(Point::new(0, 16), Size::new(64, 16));
+//! temperature.draw(&mut frame, area, Hub75Color::green());
+//! ```
+
+use embedded_graphics_core::primitives::Rectangle;
+use heapless::Deque;
+
+use crate::{color::Hub75Color, frame_buffer::Hub75FrameBuffer, window::Window};
+
+/// How samples are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum GraphStyle {
+    /// A connected line through the samples
+    #[default]
+    Line,
+    /// A bar from the bottom edge up to each sample
+    Bars,
+}
+
+/// A graph of the most recent `N` samples
+#[derive(Debug, Clone)]
+pub struct Sparkline<const N: usize> {
+    /// Samples from oldest to newest
+    samples: Deque<i32, N>,
+    /// How samples are drawn
+    style: GraphStyle,
+    /// Fixed value range, or `None` to fit the samples shown
+    range: Option<(i32, i32)>,
+}
+
+impl<const N: usize> Sparkline<N> {
+    /// Create an empty line graph scaled to fit its samples
+    pub fn new() -> Self {
+        Self {
+            samples: Deque::new(),
+            style: GraphStyle::Line,
+            range: None,
+        }
+    }
+
+    /// Add a sample, dropping the oldest one when full
+    pub fn push(&mut self, sample: i32) {
+        if self.samples.is_full() {
+            self.samples.pop_front();
+        }
+        // Cannot fail, as there is room after dropping the oldest sample
+        let _ = self.samples.push_back(sample);
+    }
+
+    /// Remove all samples
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Get the number of samples held
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Check if no samples are held
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Get the most recent sample
+    pub fn latest(&self) -> Option<i32> {
+        self.samples.back().copied()
+    }
+
+    /// Set how samples are drawn
+    pub fn set_style(&mut self, style: GraphStyle) {
+        self.style = style;
+    }
+
+    /// Fix the values shown at the bottom and top edges
+    ///
+    /// Samples outside the range are clamped to the edges.
+    pub fn set_range(&mut self, min: i32, max: i32) {
+        self.range = Some((min.min(max), min.max(max)));
+    }
+
+    /// Scale the graph to fit the samples shown
+    pub fn set_auto_range(&mut self) {
+        self.range = None;
+    }
+
+    /// Draw the newest samples that fit into `area`, clearing it first
+    pub fn draw<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        area: Rectangle,
+        color: Hub75Color<COLOR_BITS>,
+    ) {
+        let mut window = Window::new(frame, area);
+        window.clear();
+
+        let (width, height) = (window.width(), window.height());
+        let shown = self.samples.len().min(width);
+        if shown == 0 || height == 0 {
+            return;
+        }
+        let visible = || {
+            self.samples
+                .iter()
+                .copied()
+                .skip(self.samples.len() - shown)
+        };
+
+        let (min, max) = self.range.unwrap_or_else(|| {
+            visible().fold((i32::MAX, i32::MIN), |(min, max), sample| {
+                (min.min(sample), max.max(sample))
+            })
+        });
+        let bottom = height - 1;
+        let row = |sample: i32| {
+            if max == min {
+                return bottom;
+            }
+            let offset = (sample.clamp(min, max) as i64 - min as i64) * bottom as i64;
+            bottom - (offset / (max as i64 - min as i64)) as usize
+        };
+
+        let mut previous = None;
+        for (x, sample) in (width - shown..).zip(visible()) {
+            let y = row(sample);
+            let (top, end) = match (self.style, previous) {
+                (GraphStyle::Bars, _) => (y, bottom),
+                // Join to the previous sample with a vertical run
+                (GraphStyle::Line, Some(previous)) => (y.min(previous), y.max(previous)),
+                (GraphStyle::Line, None) => (y, y),
+            };
+            window.fill_rect(x, top, 1, end - top + 1, color);
+            previous = Some(y);
+        }
+    }
+}
+
+impl<const N: usize> Default for Sparkline<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::geometry::{Point, Size};
+
+    const AREA: Rectangle = Rectangle::new(Point::new(1, 1), Size::new(4, 5));
+
+    fn column(frame: &Hub75FrameBuffer<6, 7, 6>, x: usize) -> [bool; 7] {
+        let mut lit = [false; 7];
+        for (y, lit) in lit.iter_mut().enumerate() {
+            *lit = frame.get_pixel(x, y).unwrap() != Hub75Color::black();
+        }
+        lit
+    }
+
+    #[test]
+    fn test_ring_buffer() {
+        let mut graph = Sparkline::<3>::new();
+        assert!(graph.is_empty());
+        for sample in 1..=5 {
+            graph.push(sample);
+        }
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.latest(), Some(5));
+        graph.clear();
+        assert_eq!(graph.latest(), None);
+    }
+
+    #[test]
+    fn test_line_and_bars() {
+        let mut graph = Sparkline::<8>::new();
+        for sample in [100, 0, 40, 0, 20] {
+            graph.push(sample);
+        }
+        let mut frame = Hub75FrameBuffer::<6, 7, 6>::new();
+
+        // Only the newest four fit; scaled from 0 at the bottom to 40 at the top
+        graph.draw(&mut frame, AREA, Hub75Color::white());
+        assert_eq!(
+            column(&frame, 1),
+            [false, false, false, false, false, true, false]
+        );
+        assert_eq!(
+            column(&frame, 2),
+            [false, true, true, true, true, true, false]
+        );
+        assert_eq!(
+            column(&frame, 4),
+            [false, false, false, true, true, true, false]
+        );
+
+        // A fixed range clamps and bars fill from the bottom
+        graph.set_range(0, 20);
+        graph.set_style(GraphStyle::Bars);
+        graph.draw(&mut frame, AREA, Hub75Color::white());
+        assert_eq!(
+            column(&frame, 2),
+            [false, true, true, true, true, true, false]
+        );
+        assert_eq!(
+            column(&frame, 3),
+            [false, false, false, false, false, true, false]
+        );
+        assert_eq!(column(&frame, 0), [false; 7]);
+    }
+}
@@ -0,0 +1,297 @@
+//! Audio spectrum bars with peak hold
+//!
+//! A [`Spectrum`] takes one magnitude per frequency band each frame and draws
+//! a bar per band. Bars jump up to new magnitudes at once and fall back
+//! smoothly, and a peak marker above each bar holds its highest point for a
+//! while before dropping. Decay is measured in magnitude per second and
+//! scaled by the time since the previous update, so bars fall at the same
+//! speed whatever the frame rate.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::widgets::{BarColors, Spectrum};
+//! use embedded_graphics_core::{geometry::{Point, Size}, primitives::Rectangle};
+//!
+//! let mut spectrum = Spectrum::<16, 6>::new();
+//! spectrum.set_colors(BarColors::Gradient {
+//!     bottom: Hub75Color::green(),
+//!     top: Hub75Color::red(),
+//! });
+//!
+//! // Magnitudes from an FFT, 16 ms after the previous frame
+//! let bands = [200, 180, 150, 120, 90, 80, 60, 50, 40, 30, 30, 20, 20, 10, 10, 5];
+//! spectrum.update(&bands, 16);
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! spectrum.draw(&mut frame, Rectangle::new(Point::zero(), Size::new(64, 32)));
+//! ```
+
+use embedded_graphics_core::primitives::Rectangle;
+
+use crate::{
+    color::{Hsv, Hub75Color},
+    frame_buffer::Hub75FrameBuffer,
+    window::Window,
+};
+
+/// Full-scale level in 8.8 fixed point
+const FULL_SCALE: u32 = 255 << 8;
+
+/// How bars are colored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BarColors<const COLOR_BITS: usize> {
+    /// Every bar in one color
+    Solid(Hub75Color<COLOR_BITS>),
+    /// Blend from one color at the bottom edge to another at the top edge
+    Gradient {
+        /// Color of the bottom row
+        bottom: Hub75Color<COLOR_BITS>,
+        /// Color of the top row
+        top: Hub75Color<COLOR_BITS>,
+    },
+    /// A different hue for each band, from red at the lowest band to violet
+    Rainbow,
+}
+
+/// Bars for `BANDS` frequency bands with peak hold and decay
+#[derive(Debug, Clone)]
+pub struct Spectrum<const BANDS: usize, const COLOR_BITS: usize> {
+    /// Displayed bar levels in 8.8 fixed point
+    levels: [u32; BANDS],
+    /// Peak marker levels in 8.8 fixed point
+    peaks: [u32; BANDS],
+    /// Time left before each peak starts to fall, in milliseconds
+    peak_hold_left: [u32; BANDS],
+    /// How fast bars fall, in magnitude per second
+    fall_rate: u32,
+    /// How fast peaks fall after holding, in magnitude per second
+    peak_fall_rate: u32,
+    /// How long peaks hold, in milliseconds
+    peak_hold_ms: u32,
+    /// How bars are colored
+    colors: BarColors<COLOR_BITS>,
+    /// Color of the peak markers, or `None` to hide them
+    peak_color: Option<Hub75Color<COLOR_BITS>>,
+    /// Blank columns between bars
+    gap: usize,
+}
+
+impl<const BANDS: usize, const COLOR_BITS: usize> Spectrum<BANDS, COLOR_BITS> {
+    /// Create green bars with white peak markers
+    ///
+    /// Bars fall from full scale to zero in half a second; peaks hold for half
+    /// a second and then fall in two seconds.
+    pub fn new() -> Self {
+        Self {
+            levels: [0; BANDS],
+            peaks: [0; BANDS],
+            peak_hold_left: [0; BANDS],
+            fall_rate: 510,
+            peak_fall_rate: 128,
+            peak_hold_ms: 500,
+            colors: BarColors::Solid(Hub75Color::green()),
+            peak_color: Some(Hub75Color::white()),
+            gap: 1,
+        }
+    }
+
+    /// Set how fast bars fall, in magnitude (0 to 255) per second
+    pub fn set_fall_rate(&mut self, rate: u32) {
+        self.fall_rate = rate;
+    }
+
+    /// Set how long peaks hold and how fast they fall afterwards
+    pub fn set_peak_hold(&mut self, hold_ms: u32, fall_rate: u32) {
+        self.peak_hold_ms = hold_ms;
+        self.peak_fall_rate = fall_rate;
+    }
+
+    /// Set how bars are colored
+    pub fn set_colors(&mut self, colors: BarColors<COLOR_BITS>) {
+        self.colors = colors;
+    }
+
+    /// Set the peak marker color, or `None` to hide the markers
+    pub fn set_peak_color(&mut self, color: Option<Hub75Color<COLOR_BITS>>) {
+        self.peak_color = color;
+    }
+
+    /// Set the number of blank columns between bars
+    pub fn set_gap(&mut self, gap: usize) {
+        self.gap = gap;
+    }
+
+    /// Get the displayed level of each band, 0 to 255
+    pub fn levels(&self) -> [u8; BANDS] {
+        self.levels.map(|level| (level >> 8) as u8)
+    }
+
+    /// Get the peak marker level of each band, 0 to 255
+    pub fn peaks(&self) -> [u8; BANDS] {
+        self.peaks.map(|peak| (peak >> 8) as u8)
+    }
+
+    /// Drop all bars and peaks to zero
+    pub fn reset(&mut self) {
+        self.levels = [0; BANDS];
+        self.peaks = [0; BANDS];
+        self.peak_hold_left = [0; BANDS];
+    }
+
+    /// Feed new band magnitudes, `elapsed_ms` after the previous update
+    pub fn update(&mut self, magnitudes: &[u8; BANDS], elapsed_ms: u32) {
+        let fall = Self::decay(self.fall_rate, elapsed_ms);
+        let peak_fall = Self::decay(self.peak_fall_rate, elapsed_ms);
+
+        for (band, &magnitude) in magnitudes.iter().enumerate() {
+            let level = (self.levels[band].saturating_sub(fall)).max((magnitude as u32) << 8);
+            self.levels[band] = level;
+
+            if level >= self.peaks[band] {
+                self.peaks[band] = level;
+                self.peak_hold_left[band] = self.peak_hold_ms;
+            } else if self.peak_hold_left[band] > elapsed_ms {
+                self.peak_hold_left[band] -= elapsed_ms;
+            } else {
+                self.peak_hold_left[band] = 0;
+                self.peaks[band] = self.peaks[band].saturating_sub(peak_fall).max(level);
+            }
+        }
+    }
+
+    /// Level drop in 8.8 fixed point for a rate in magnitude per second
+    fn decay(rate: u32, elapsed_ms: u32) -> u32 {
+        (rate as u64 * elapsed_ms as u64 * 256 / 1000).min(FULL_SCALE as u64) as u32
+    }
+
+    /// Draw the bars into `area`, clearing it first
+    ///
+    /// The bars share the width evenly, with any leftover columns split
+    /// between the left and right edges.
+    pub fn draw<const WIDTH: usize, const HEIGHT: usize>(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        area: Rectangle,
+    ) {
+        let mut window = Window::new(frame, area);
+        window.clear();
+
+        let (width, height) = (window.width(), window.height());
+        let gaps = self.gap * BANDS.saturating_sub(1);
+        if BANDS == 0 || height == 0 || width < gaps + BANDS {
+            return;
+        }
+        let bar_width = (width - gaps) / BANDS;
+        let margin = (width - gaps - bar_width * BANDS) / 2;
+        let rows = |level: u32| (level as u64 * height as u64 / FULL_SCALE as u64) as usize;
+
+        for band in 0..BANDS {
+            let x = margin + band * (bar_width + self.gap);
+            let bar = rows(self.levels[band]);
+            for row in 0..bar {
+                let color = self.bar_color(band, row, height);
+                window.fill_rect(x, height - 1 - row, bar_width, 1, color);
+            }
+
+            let peak = rows(self.peaks[band]);
+            if let (Some(color), 1..) = (self.peak_color, peak) {
+                window.fill_rect(x, height - peak, bar_width, 1, color);
+            }
+        }
+    }
+
+    /// Color of a bar pixel `row` rows above the bottom edge
+    fn bar_color(&self, band: usize, row: usize, height: usize) -> Hub75Color<COLOR_BITS> {
+        match self.colors {
+            BarColors::Solid(color) => color,
+            BarColors::Gradient { bottom, top } => {
+                let t = row * 255 / height.saturating_sub(1).max(1);
+                Hub75Color::lerp(bottom, top, t as u8)
+            }
+            BarColors::Rainbow => {
+                let hue = band * 300 / BANDS.saturating_sub(1).max(1);
+                Hsv::new(hue as u16, 255, 255).into()
+            }
+        }
+    }
+}
+
+impl<const BANDS: usize, const COLOR_BITS: usize> Default for Spectrum<BANDS, COLOR_BITS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::geometry::{Point, Size};
+
+    #[test]
+    fn test_decay_and_peak_hold() {
+        let mut spectrum = Spectrum::<2, 6>::new();
+        spectrum.set_fall_rate(100);
+        spectrum.set_peak_hold(200, 50);
+
+        spectrum.update(&[200, 50], 10);
+        assert_eq!(spectrum.levels(), [200, 50]);
+        assert_eq!(spectrum.peaks(), [200, 50]);
+
+        // Falls by the same amount for the same time, whatever the frame rate
+        let mut fast = spectrum.clone();
+        spectrum.update(&[0, 0], 100);
+        for _ in 0..10 {
+            fast.update(&[0, 0], 10);
+        }
+        assert_eq!(spectrum.levels(), [190, 40]);
+        assert_eq!(fast.levels(), spectrum.levels());
+        assert_eq!(spectrum.peaks(), [200, 50]);
+
+        // Peaks fall once the hold time has passed, never below the bar
+        spectrum.update(&[0, 0], 50);
+        assert_eq!(spectrum.peaks(), [200, 50]);
+        spectrum.update(&[0, 0], 200);
+        assert_eq!(spectrum.levels(), [165, 15]);
+        assert_eq!(spectrum.peaks(), [190, 40]);
+
+        // New magnitudes show at once and reset the hold
+        spectrum.update(&[255, 0], 10);
+        assert_eq!(spectrum.levels()[0], 255);
+        assert_eq!(spectrum.peaks()[0], 255);
+
+        spectrum.reset();
+        assert_eq!(spectrum.peaks(), [0, 0]);
+    }
+
+    #[test]
+    fn test_draw_bars() {
+        let mut spectrum = Spectrum::<2, 6>::new();
+        spectrum.set_colors(BarColors::Gradient {
+            bottom: Hub75Color::green(),
+            top: Hub75Color::red(),
+        });
+        spectrum.update(&[255, 128], 0);
+        spectrum.update(&[255, 0], 400);
+
+        let mut frame = Hub75FrameBuffer::<8, 6, 6>::new();
+        let area = Rectangle::new(Point::new(0, 1), Size::new(8, 5));
+        spectrum.draw(&mut frame, area);
+
+        // Bars 3 wide with 1 column of gap, the leftover column on the right
+        assert_eq!(frame.get_pixel(0, 5).unwrap(), Hub75Color::green());
+        assert_eq!(
+            frame.get_pixel(2, 2).unwrap(),
+            Hub75Color::lerp(Hub75Color::green(), Hub75Color::red(), 191)
+        );
+        assert_eq!(frame.get_pixel(2, 1).unwrap(), Hub75Color::white());
+        assert_eq!(frame.get_pixel(3, 5).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(7, 1).unwrap(), Hub75Color::black());
+
+        // The second bar has fallen away, leaving its held peak marker
+        assert_eq!(frame.get_pixel(4, 5).unwrap(), Hub75Color::black());
+        assert_eq!(frame.get_pixel(4, 4).unwrap(), Hub75Color::white());
+    }
+}
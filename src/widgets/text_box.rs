@@ -0,0 +1,239 @@
+//! Word-wrapped, aligned text in a region of the frame
+//!
+//! A [`TextBox`] breaks a string into lines that fit the width of a region,
+//! at spaces where possible and mid-word otherwise, then draws as many lines
+//! as fit with the chosen horizontal and vertical alignment. `'\n'` forces a
+//! line break. Text uses the built-in [`font`](crate::font).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::widgets::{Alignment, TextBox, VerticalAlignment};
+//! use embedded_graphics_core::{geometry::{Point, Size}, primitives::Rectangle};
+//!
+//! let mut text = TextBox::new("Platform 2 delayed by 5 minutes");
+//! text.set_alignment(Alignment::Center);
+//! text.set_vertical_alignment(VerticalAlignment::Middle);
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! let area = Rectangle::new(Point::zero(), Size::new(64, 32));
+//! assert_eq!(text.draw(&mut frame, area, Hub75Color::white()), 3);
+//! ```
+
+use embedded_graphics_core::primitives::Rectangle;
+
+use crate::{
+    color::Hub75Color,
+    font::{self, GLYPH_ADVANCE, GLYPH_HEIGHT},
+    frame_buffer::Hub75FrameBuffer,
+    window::Window,
+};
+
+/// Horizontal placement of each line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Alignment {
+    /// Lines start at the left edge
+    #[default]
+    Left,
+    /// Lines are centered
+    Center,
+    /// Lines end at the right edge
+    Right,
+}
+
+/// Vertical placement of the block of lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum VerticalAlignment {
+    /// The first line is at the top edge
+    #[default]
+    Top,
+    /// The lines are centered
+    Middle,
+    /// The last line is at the bottom edge
+    Bottom,
+}
+
+/// Iterator over the lines of wrapped text, see [`TextBox::lines`]
+#[derive(Debug, Clone)]
+pub struct WrappedLines<'a> {
+    /// Text not yet broken into lines
+    rest: Option<&'a str>,
+    /// Maximum number of characters per line
+    max_chars: usize,
+}
+
+impl<'a> Iterator for WrappedLines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let text = self.rest?.trim_start_matches(' ');
+        let (segment, after) = match text.find('\n') {
+            Some(newline) => (&text[..newline], Some(&text[newline + 1..])),
+            None => (text, None),
+        };
+
+        // Byte offset just past the last character that fits, if any do not
+        let Some((cut, next)) = segment.char_indices().nth(self.max_chars) else {
+            self.rest = after.filter(|after| !after.is_empty());
+            return Some(segment.trim_end());
+        };
+
+        // Break at the last space that keeps the line short enough, which
+        // may be the one right after it
+        let space = if next == ' ' {
+            Some(cut)
+        } else {
+            segment[..cut].rfind(' ').filter(|&space| space > 0)
+        };
+        let (line, rest) = match space {
+            Some(space) => (&text[..space], &text[space + 1..]),
+            None => (&text[..cut], &text[cut..]),
+        };
+        self.rest = Some(rest);
+        Some(line.trim_end())
+    }
+}
+
+/// A block of text wrapped to fit a region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextBox<'a> {
+    /// Text to lay out
+    text: &'a str,
+    /// Horizontal placement of each line
+    alignment: Alignment,
+    /// Vertical placement of the block of lines
+    vertical_alignment: VerticalAlignment,
+    /// Blank rows between lines
+    line_spacing: usize,
+}
+
+impl<'a> TextBox<'a> {
+    /// Create a left- and top-aligned text box with one blank row between
+    /// lines
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            alignment: Alignment::Left,
+            vertical_alignment: VerticalAlignment::Top,
+            line_spacing: 1,
+        }
+    }
+
+    /// Replace the text
+    pub fn set_text(&mut self, text: &'a str) {
+        self.text = text;
+    }
+
+    /// Set the horizontal placement of each line
+    pub fn set_alignment(&mut self, alignment: Alignment) {
+        self.alignment = alignment;
+    }
+
+    /// Set the vertical placement of the block of lines
+    pub fn set_vertical_alignment(&mut self, alignment: VerticalAlignment) {
+        self.vertical_alignment = alignment;
+    }
+
+    /// Set the number of blank rows between lines
+    pub fn set_line_spacing(&mut self, spacing: usize) {
+        self.line_spacing = spacing;
+    }
+
+    /// Break the text into lines no wider than `width` pixels
+    pub fn lines(&self, width: usize) -> WrappedLines<'a> {
+        WrappedLines {
+            rest: Some(self.text).filter(|text| !text.is_empty()),
+            // The spacing after the last character may fall outside
+            max_chars: ((width + 1) / GLYPH_ADVANCE).max(1),
+        }
+    }
+
+    /// Draw the lines that fit inside `area`, returning how many were drawn
+    ///
+    /// The area is not cleared first, and lines that do not fit below the
+    /// others are left out.
+    pub fn draw<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        area: Rectangle,
+        color: Hub75Color<COLOR_BITS>,
+    ) -> usize {
+        let (width, height) = (area.size.width as usize, area.size.height as usize);
+        let pitch = GLYPH_HEIGHT + self.line_spacing;
+        let fitting = (height + self.line_spacing) / pitch;
+        let count = self.lines(width).take(fitting).count();
+        if count == 0 {
+            return 0;
+        }
+
+        let block = count * pitch - self.line_spacing;
+        let mut y = match self.vertical_alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Middle => (height - block) / 2,
+            VerticalAlignment::Bottom => height - block,
+        } as i32;
+
+        let mut window = Window::new(frame, area);
+        for line in self.lines(width).take(count) {
+            // Trailing spacing does not count towards the visible width
+            let line_width = font::text_width(line).saturating_sub(1);
+            let x = match self.alignment {
+                Alignment::Left => 0,
+                Alignment::Center => (width.saturating_sub(line_width) / 2) as i32,
+                Alignment::Right => width.saturating_sub(line_width) as i32,
+            };
+            window.draw_text(line, x, y, color);
+            y += pitch as i32;
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::geometry::{Point, Size};
+
+    fn wrap<const N: usize>(text: &str, width: usize) -> heapless::Vec<&str, N> {
+        TextBox::new(text).lines(width).collect()
+    }
+
+    #[test]
+    fn test_wrapping() {
+        // Four characters per line
+        assert_eq!(wrap::<4>("ab cd ef", 23), ["ab", "cd", "ef"]);
+        assert_eq!(wrap::<4>("abcd efgh", 23), ["abcd", "efgh"]);
+        assert_eq!(wrap::<4>("abcdefghij", 23), ["abcd", "efgh", "ij"]);
+        assert_eq!(wrap::<4>("ab\n\ncd  ", 23), ["ab", "", "cd"]);
+        assert_eq!(wrap::<4>("a  b", 23), ["a  b"]);
+        assert_eq!(wrap::<4>("", 23).len(), 0);
+        assert_eq!(wrap::<10>("héllo wörld", 0).len(), 10);
+    }
+
+    #[test]
+    fn test_alignment() {
+        let mut frame = Hub75FrameBuffer::<24, 20, 6>::new();
+        let area = Rectangle::new(Point::new(0, 2), Size::new(24, 18));
+        let mut text = TextBox::new("| | | |");
+        let white = Hub75Color::white();
+
+        // Two lines of "| |" fit, the third is left out
+        text.set_alignment(Alignment::Right);
+        text.set_vertical_alignment(VerticalAlignment::Bottom);
+        assert_eq!(text.draw(&mut frame, area, white), 2);
+        assert_eq!(frame.get_pixel(21, 19).unwrap(), white);
+        assert_eq!(frame.get_pixel(9, 11).unwrap(), white);
+        assert_eq!(frame.get_pixel(9, 4).unwrap(), Hub75Color::black());
+
+        frame.clear();
+        text.set_text("|");
+        text.set_alignment(Alignment::Center);
+        text.set_vertical_alignment(VerticalAlignment::Middle);
+        assert_eq!(text.draw(&mut frame, area, white), 1);
+        assert_eq!(frame.get_pixel(11, 7).unwrap(), white);
+        assert_eq!(frame.get_pixel(11, 14).unwrap(), Hub75Color::black());
+    }
+}
@@ -0,0 +1,243 @@
+//! Blinking, flashing and color-cycling text
+//!
+//! [`TextEffects`] wraps a [`TextStyle`] and, counting frames, decides how
+//! text is drawn each frame: blinked off for part of every blink period,
+//! drawn in a highlight color for a few frames after its content changed, or
+//! with its hue cycling around the color wheel. Call
+//! [`tick`](TextEffects::tick) once per frame and draw with
+//! [`draw`](TextEffects::draw) or [`style`](TextEffects::style).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{Hub75Color, Hub75FrameBuffer};
+//! use hub75::widgets::TextEffects;
+//!
+//! let mut alert = TextEffects::new(Hub75Color::red());
+//! // On for 30 frames, off for 10; white for 20 frames after a change
+//! alert.set_blink(30, 10);
+//! alert.set_flash(Hub75Color::white(), 20);
+//!
+//! let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+//! for level in ["OK", "OK", "HIGH"] {
+//!     alert.update(level);
+//!     frame.clear();
+//!     alert.draw(&mut frame, level, 0, 12);
+//!     alert.tick();
+//! }
+//! assert!(alert.is_flashing());
+//! ```
+
+use crate::{
+    color::{Hsv, Hub75Color},
+    font::{self, TextStyle},
+    frame_buffer::{fnv1a, Hub75FrameBuffer, FNV_OFFSET_BASIS},
+};
+
+/// Frame-counted blink, flash and color cycle modifiers for a text style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextEffects<const COLOR_BITS: usize> {
+    /// Style without effects
+    style: TextStyle<COLOR_BITS>,
+    /// Frames the text is shown per blink period
+    blink_on_frames: u32,
+    /// Frames the text is hidden per blink period, 0 for no blinking
+    blink_off_frames: u32,
+    /// Color used while flashing
+    flash_color: Hub75Color<COLOR_BITS>,
+    /// Frames a flash lasts, 0 for no flashing
+    flash_frames: u32,
+    /// Frames left of the current flash
+    flash_remaining: u32,
+    /// Frames per turn around the color wheel, 0 for no cycling
+    cycle_frames: u32,
+    /// Frames since the effects were started
+    frame: u32,
+    /// Hash of the text last passed to `update`
+    content: Option<u32>,
+}
+
+impl<const COLOR_BITS: usize> TextEffects<COLOR_BITS> {
+    /// Create effects for `style`, all disabled
+    ///
+    /// `style` can be a [`TextStyle`] or just a color.
+    pub fn new(style: impl Into<TextStyle<COLOR_BITS>>) -> Self {
+        Self {
+            style: style.into(),
+            blink_on_frames: 1,
+            blink_off_frames: 0,
+            flash_color: Hub75Color::white(),
+            flash_frames: 0,
+            flash_remaining: 0,
+            cycle_frames: 0,
+            frame: 0,
+            content: None,
+        }
+    }
+
+    /// Replace the style the effects are applied to
+    pub fn set_style(&mut self, style: impl Into<TextStyle<COLOR_BITS>>) {
+        self.style = style.into();
+    }
+
+    /// Show the text for `on_frames` and hide it for `off_frames` in turn
+    ///
+    /// Blinking is disabled with `off_frames` set to 0.
+    pub fn set_blink(&mut self, on_frames: u32, off_frames: u32) {
+        self.blink_on_frames = on_frames.max(1);
+        self.blink_off_frames = off_frames;
+    }
+
+    /// Draw the text in `color` for `frames` frames when it is flashed
+    ///
+    /// Flashing is disabled with `frames` set to 0.
+    pub fn set_flash(&mut self, color: Hub75Color<COLOR_BITS>, frames: u32) {
+        self.flash_color = color;
+        self.flash_frames = frames;
+        self.flash_remaining = self.flash_remaining.min(frames);
+    }
+
+    /// Turn the hue of the text once around the color wheel every `frames`
+    /// frames, at full saturation and the brightness of the style color
+    ///
+    /// Cycling is disabled with `frames` set to 0.
+    pub fn set_color_cycle(&mut self, frames: u32) {
+        self.cycle_frames = frames;
+    }
+
+    /// Start a flash, restarting the blink period so the text shows
+    pub fn flash(&mut self) {
+        self.flash_remaining = self.flash_frames;
+        self.frame = 0;
+    }
+
+    /// Flash if `text` differs from the text last passed here
+    ///
+    /// Returns `true` if it changed. The first text passed never flashes.
+    pub fn update(&mut self, text: &str) -> bool {
+        let hash = text.bytes().fold(FNV_OFFSET_BASIS, fnv1a);
+        let changed = self.content.is_some_and(|content| content != hash);
+        self.content = Some(hash);
+        if changed {
+            self.flash();
+        }
+        changed
+    }
+
+    /// Advance by one frame
+    pub fn tick(&mut self) {
+        self.frame = self.frame.wrapping_add(1);
+        self.flash_remaining = self.flash_remaining.saturating_sub(1);
+    }
+
+    /// Restart all effects and forget the last text
+    pub fn reset(&mut self) {
+        self.frame = 0;
+        self.flash_remaining = 0;
+        self.content = None;
+    }
+
+    /// Check whether the text is drawn in the flash color this frame
+    pub fn is_flashing(&self) -> bool {
+        self.flash_remaining > 0
+    }
+
+    /// Check whether the text is drawn this frame
+    ///
+    /// Flashing text is always drawn.
+    pub fn is_visible(&self) -> bool {
+        let period = self.blink_on_frames + self.blink_off_frames;
+        self.is_flashing() || self.frame % period < self.blink_on_frames
+    }
+
+    /// Get the style to draw the text with this frame, or `None` while it is
+    /// blinked off
+    pub fn style(&self) -> Option<TextStyle<COLOR_BITS>> {
+        if !self.is_visible() {
+            return None;
+        }
+        let color = if self.is_flashing() {
+            self.flash_color
+        } else if self.cycle_frames > 0 {
+            let turn = (self.frame % self.cycle_frames) as u64 * 360 / self.cycle_frames as u64;
+            let base = Hsv::from(self.style.color);
+            Hsv::new(base.h + turn as u16, 255, base.v).into()
+        } else {
+            self.style.color
+        };
+        Some(TextStyle {
+            color,
+            ..self.style
+        })
+    }
+
+    /// Draw `text` with its top-left corner at `(x, y)` if it is visible this
+    /// frame
+    ///
+    /// Returns the x coordinate following the last character either way.
+    pub fn draw<const WIDTH: usize, const HEIGHT: usize>(
+        &self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        text: &str,
+        x: i32,
+        y: i32,
+    ) -> i32 {
+        match self.style() {
+            Some(style) => frame.draw_text_blended(text, x, y, style.color, style.blend),
+            None => x + font::text_width(text) as i32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blink_and_flash() {
+        let red = Hub75Color::red();
+        let mut effects = TextEffects::<6>::new(red);
+        assert_eq!(effects.style().unwrap().color, red);
+
+        // On for 2 frames, off for 1
+        effects.set_blink(2, 1);
+        let visible: [bool; 6] = core::array::from_fn(|_| {
+            let visible = effects.is_visible();
+            effects.tick();
+            visible
+        });
+        assert_eq!(visible, [true, true, false, true, true, false]);
+
+        // A change flashes for 3 frames, shown even during the off phase
+        effects.set_flash(Hub75Color::white(), 3);
+        assert!(!effects.update("12"));
+        assert!(!effects.update("12"));
+        assert!(effects.update("13"));
+        effects.tick();
+        effects.tick();
+        assert_eq!(effects.style().unwrap().color, Hub75Color::white());
+        effects.tick();
+        assert!(effects.is_visible() && !effects.is_flashing());
+        assert_eq!(effects.style().unwrap().color, red);
+
+        // Blinked off, nothing is drawn
+        let mut frame = Hub75FrameBuffer::<16, 8, 6>::new();
+        effects.tick();
+        effects.tick();
+        assert_eq!(effects.style(), None);
+        assert_eq!(effects.draw(&mut frame, "-", 0, 0), 6);
+        assert_eq!(frame.get_pixel(0, 3).unwrap(), Hub75Color::black());
+    }
+
+    #[test]
+    fn test_color_cycle() {
+        let mut effects = TextEffects::<8>::new(Hub75Color::red());
+        effects.set_color_cycle(3);
+        let hues: [u16; 4] = core::array::from_fn(|_| {
+            let hue = Hsv::from(effects.style().unwrap().color).h;
+            effects.tick();
+            hue
+        });
+        assert_eq!(hues, [0, 120, 240, 0]);
+    }
+}
@@ -0,0 +1,358 @@
+//! Message rotation with scroll-in, hold and scroll-out
+//!
+//! A [`Ticker`] shows a list of messages one at a time in a region of the
+//! panel. Each message scrolls in from one side, holds still so it can be
+//! read, then scrolls out the opposite side before the next one comes in, as
+//! on a scoreboard or news ticker. Messages are drawn in the built-in
+//! [`font`](crate::font), centered in the region.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{AnimationState, Direction, Hub75Color, Hub75FrameBuffer};
+//! use hub75::widgets::Ticker;
+//! use embedded_graphics_core::{geometry::{Point, Size}, primitives::Rectangle};
+//!
+//! const NEWS: [&str; 2] = ["HOME 3", "AWAY 1"];
+//!
+//! let area = Rectangle::new(Point::new(0, 24), Size::new(64, 8));
+//! let mut ticker = Ticker::<64, 32, 6>::new(&NEWS, area, Hub75Color::white());
+//! ticker.set_direction(Direction::Up);
+//! ticker.set_hold_frames(120);
+//!
+//! let mut frame = Hub75FrameBuffer::new();
+//! assert!(matches!(ticker.next_into(&mut frame), AnimationState::Rendered));
+//! ```
+
+use embedded_graphics_core::{geometry::Point, primitives::Rectangle};
+
+use crate::{
+    animation::{AnimationState, Direction},
+    color::Hub75Color,
+    font::{self, GLYPH_HEIGHT},
+    frame_buffer::Hub75FrameBuffer,
+    scene::FrameSource,
+    window::Window,
+    Hub75Error,
+};
+
+/// Where the current message is in its cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// Moving in, with the distance still to go in pixels
+    Entering(usize),
+    /// At rest, with the frames left before leaving
+    Holding(usize),
+    /// Moving out, with the distance covered in pixels
+    Leaving(usize),
+    /// The last message has left and the ticker does not loop
+    Done,
+}
+
+/// Messages scrolled in, held and scrolled out one after another
+#[derive(Debug, Clone)]
+pub struct Ticker<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Messages in display order
+    messages: &'a [&'a str],
+    /// Region of the frame the messages are shown in
+    area: Rectangle,
+    /// Text color
+    color: Hub75Color<COLOR_BITS>,
+    /// Direction the messages move in
+    direction: Direction,
+    /// Number of frames each message rests before leaving
+    hold_frames: usize,
+    /// Number of frames per pixel of movement
+    frames_per_pixel: usize,
+    /// Whether to start over after the last message
+    looping: bool,
+    /// Index of the current message
+    index: usize,
+    /// Progress of the current message
+    phase: Phase,
+    /// Frames since the last pixel of movement
+    frame_counter: usize,
+    /// Whether the initial state has been drawn
+    started: bool,
+}
+
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    Ticker<'a, WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a ticker cycling through `messages` inside `area`
+    ///
+    /// Messages scroll up one pixel per frame, hold for 60 frames and repeat
+    /// from the first message after the last.
+    pub fn new(messages: &'a [&'a str], area: Rectangle, color: Hub75Color<COLOR_BITS>) -> Self {
+        let mut ticker = Self {
+            messages,
+            area,
+            color,
+            direction: Direction::Up,
+            hold_frames: 60,
+            frames_per_pixel: 1,
+            looping: true,
+            index: 0,
+            phase: Phase::Done,
+            frame_counter: 0,
+            started: false,
+        };
+        ticker.reset();
+        ticker
+    }
+
+    /// Set the direction the messages move in
+    ///
+    /// With [`Direction::Up`] messages come in from the bottom and leave at
+    /// the top; horizontal directions work the same way sideways.
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.direction = direction;
+        self.reset();
+    }
+
+    /// Set how many frames each message rests before scrolling out
+    pub fn set_hold_frames(&mut self, frames: usize) {
+        self.hold_frames = frames;
+    }
+
+    /// Set the scroll speed as the number of frames per pixel of movement
+    pub fn set_frames_per_pixel(&mut self, frames: usize) {
+        self.frames_per_pixel = frames.max(1);
+    }
+
+    /// Set whether to start over after the last message
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Get the index of the message currently shown
+    pub fn current_index(&self) -> usize {
+        self.index
+    }
+
+    /// Check if the last message has left and the ticker does not loop
+    pub fn is_done(&self) -> bool {
+        self.phase == Phase::Done
+    }
+
+    /// Start over with the first message scrolling in
+    pub fn reset(&mut self) {
+        self.index = 0;
+        self.phase = if self.messages.is_empty() {
+            Phase::Done
+        } else {
+            Phase::Entering(self.span())
+        };
+        self.frame_counter = 0;
+        self.started = false;
+    }
+
+    /// Distance a message travels between the edge of the area and rest
+    fn span(&self) -> usize {
+        let (size, extent) = if self.direction.is_horizontal() {
+            let text = self.messages.get(self.index).copied().unwrap_or("");
+            (self.area.size.width as usize, font::text_width(text))
+        } else {
+            (self.area.size.height as usize, GLYPH_HEIGHT)
+        };
+        (size + extent).div_ceil(2)
+    }
+
+    /// Top-left corner of the current message in area coordinates
+    fn position(&self, text: &str) -> Point {
+        let rest = Point::new(
+            (self.area.size.width as i32 - font::text_width(text) as i32 + 1) / 2,
+            (self.area.size.height as i32 - GLYPH_HEIGHT as i32) / 2,
+        );
+        let step = match self.direction {
+            Direction::Left => Point::new(-1, 0),
+            Direction::Right => Point::new(1, 0),
+            Direction::Up => Point::new(0, -1),
+            Direction::Down => Point::new(0, 1),
+        };
+        match self.phase {
+            Phase::Entering(distance) => rest - step * distance as i32,
+            Phase::Leaving(distance) => rest + step * distance as i32,
+            Phase::Holding(_) | Phase::Done => rest,
+        }
+    }
+
+    /// Move on by one frame, returning whether the message moved
+    fn advance(&mut self) -> bool {
+        if let Phase::Holding(frames) = self.phase {
+            self.phase = match frames {
+                0 => Phase::Leaving(0),
+                _ => Phase::Holding(frames - 1),
+            };
+            return false;
+        }
+
+        self.frame_counter += 1;
+        if self.frame_counter < self.frames_per_pixel {
+            return false;
+        }
+        self.frame_counter = 0;
+
+        self.phase = match self.phase {
+            Phase::Entering(0..=1) => Phase::Holding(self.hold_frames),
+            Phase::Entering(distance) => Phase::Entering(distance - 1),
+            Phase::Leaving(distance) if distance + 1 < self.span() => Phase::Leaving(distance + 1),
+            Phase::Leaving(_) => {
+                self.index += 1;
+                if self.index < self.messages.len() {
+                    Phase::Entering(self.span())
+                } else if self.looping {
+                    self.index = 0;
+                    Phase::Entering(self.span())
+                } else {
+                    self.index -= 1;
+                    Phase::Done
+                }
+            }
+            phase => phase,
+        };
+        true
+    }
+
+    /// Draw the current message, clearing the rest of the area
+    pub fn render_into(&self, frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) {
+        let mut window = Window::new(frame, self.area);
+        window.clear();
+        if let (Some(text), false) = (self.messages.get(self.index), self.is_done()) {
+            let position = self.position(text);
+            window.draw_text(text, position.x, position.y, self.color);
+        }
+    }
+
+    /// Advance by one frame, drawing the area into `frame` when it changes
+    ///
+    /// Returns [`AnimationState::Wait`] while a message holds still and
+    /// [`AnimationState::Done`] after the last message has left when not
+    /// looping.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        if self.is_done() {
+            return AnimationState::Done;
+        }
+        if self.started && !self.advance() {
+            return AnimationState::Wait;
+        }
+        self.started = true;
+        Ticker::render_into(self, frame);
+        AnimationState::Rendered
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for Ticker<'_, WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        Ticker::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        Ticker::render_into(self, frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::geometry::Size;
+
+    type TestTicker<'a> = Ticker<'a, 12, 10, 6>;
+
+    const AREA: Rectangle = Rectangle::new(Point::new(0, 1), Size::new(12, 8));
+
+    fn lit_rows(frame: &Hub75FrameBuffer<12, 10, 6>) -> [bool; 10] {
+        let mut rows = [false; 10];
+        for (y, lit) in rows.iter_mut().enumerate() {
+            *lit = (0..12).any(|x| frame.get_pixel(x, y).unwrap() != Hub75Color::black());
+        }
+        rows
+    }
+
+    #[test]
+    fn test_scroll_hold_and_leave() {
+        const MESSAGES: [&str; 2] = ["|", "||"];
+        let mut ticker = TestTicker::new(&MESSAGES, AREA, Hub75Color::white());
+        ticker.set_hold_frames(2);
+        ticker.set_looping(false);
+        let mut frame = Hub75FrameBuffer::new();
+        frame.fill(Hub75Color::red());
+
+        // Starts hidden below the area, which is cleared but nothing else is
+        assert!(matches!(
+            ticker.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(
+            lit_rows(&frame),
+            [true, false, false, false, false, false, false, false, false, true]
+        );
+
+        // Comes up to rest in the middle of the area
+        let mut frames = 0;
+        while !matches!(ticker.next_into(&mut frame), AnimationState::Wait) {
+            frames += 1;
+        }
+        assert_eq!(frames, 8);
+        assert_eq!(
+            lit_rows(&frame),
+            [true, true, true, true, true, true, true, true, false, true]
+        );
+
+        // Holds, then leaves at the top with nothing outside the area touched
+        assert!(matches!(ticker.next_into(&mut frame), AnimationState::Wait));
+        assert!(matches!(ticker.next_into(&mut frame), AnimationState::Wait));
+        assert!(matches!(
+            ticker.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(
+            lit_rows(&frame),
+            [true, true, true, true, true, true, true, false, false, true]
+        );
+        assert_eq!(ticker.current_index(), 0);
+
+        let mut frames = 0;
+        while !ticker.is_done() {
+            ticker.next_into(&mut frame);
+            frames += 1;
+        }
+        assert_eq!(ticker.current_index(), 1);
+        assert!(frames > 20);
+        assert!(matches!(ticker.next_into(&mut frame), AnimationState::Done));
+        assert_eq!(
+            lit_rows(&frame),
+            [true, false, false, false, false, false, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn test_looping_and_empty() {
+        const MESSAGES: [&str; 1] = ["|"];
+        let mut ticker = TestTicker::new(&MESSAGES, AREA, Hub75Color::white());
+        ticker.set_direction(Direction::Left);
+        ticker.set_hold_frames(0);
+        let mut frame = Hub75FrameBuffer::new();
+        for _ in 0..100 {
+            assert!(!matches!(
+                ticker.next_into(&mut frame),
+                AnimationState::Done
+            ));
+        }
+
+        let mut empty = TestTicker::new(&[], AREA, Hub75Color::white());
+        assert!(matches!(empty.next_into(&mut frame), AnimationState::Done));
+    }
+}
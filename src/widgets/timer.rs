@@ -0,0 +1,459 @@
+//! Countdown timer and stopwatch
+//!
+//! A [`Timer`] counts down from a duration or up from zero as the caller
+//! reports elapsed time with [`tick`](Timer::tick), so it works with any time
+//! source: a hardware timer, an async delay loop or the frame rate of the
+//! display. Times are shown as `H:MM:SS` or `M:SS`, switching to tenths of a
+//! second, `S.t` or `M:SS.t`, below a configurable threshold. When a
+//! countdown runs out, its `0.0` flashes until the flash time is over or the
+//! timer is stopped or reset.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{AnimationState, Hub75Color, Hub75FrameBuffer};
+//! use hub75::widgets::Timer;
+//! use embedded_graphics_core::{geometry::{Point, Size}, primitives::Rectangle};
+//!
+//! let area = Rectangle::new(Point::zero(), Size::new(64, 32));
+//! let mut timer = Timer::<64, 32, 6>::countdown(90_000, area, Hub75Color::green());
+//! timer.start();
+//!
+//! let mut frame = Hub75FrameBuffer::new();
+//! assert!(matches!(timer.next_into(&mut frame), AnimationState::Rendered));
+//! // "1:30" becomes "1:29" after a second
+//! timer.tick(400);
+//! assert!(matches!(timer.next_into(&mut frame), AnimationState::Wait));
+//! timer.tick(600);
+//! assert!(matches!(timer.next_into(&mut frame), AnimationState::Rendered));
+//!
+//! // The last tick returns true once the countdown is over
+//! assert!(timer.tick(89_000));
+//! assert!(timer.is_finished());
+//! ```
+
+use core::fmt::Write;
+
+use embedded_graphics_core::primitives::Rectangle;
+use heapless::String;
+
+use crate::{
+    animation::AnimationState,
+    color::Hub75Color,
+    font::{visible_width, Font5x7, Scaled, GLYPH_HEIGHT},
+    frame_buffer::Hub75FrameBuffer,
+    scene::FrameSource,
+    window::Window,
+    Hub75Error,
+};
+
+/// Longest text a time is shown as, `1193:02:47` for `u32::MAX` milliseconds
+type TimeText = String<12>;
+
+/// Whether a [`Timer`] counts down or up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TimerMode {
+    /// Count down from a duration to zero
+    #[default]
+    Countdown,
+    /// Count up from zero
+    Stopwatch,
+}
+
+/// Countdown timer or stopwatch drawn into a region of the frame
+#[derive(Debug, Clone)]
+pub struct Timer<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Region of the frame the timer is drawn in
+    area: Rectangle,
+    /// Color of the digits
+    color: Hub75Color<COLOR_BITS>,
+    /// Whether the timer counts down or up
+    mode: TimerMode,
+    /// Time counted down from, in milliseconds
+    duration: u32,
+    /// Time counted so far, in milliseconds
+    elapsed: u32,
+    /// Whether ticks advance the time
+    running: bool,
+    /// Times below this many milliseconds are shown with tenths
+    tenths_below: u32,
+    /// Factor the digits are enlarged by
+    scale: u8,
+    /// Color of the digits while flashing
+    flash_color: Hub75Color<COLOR_BITS>,
+    /// Milliseconds the digits are shown and then hidden for while flashing
+    flash_period: u32,
+    /// Milliseconds the flashing lasts, 0 to not flash
+    flash_duration: u32,
+    /// Milliseconds since the countdown ran out while flashing
+    flashing: Option<u32>,
+    /// Text and color most recently drawn, the color `None` if hidden
+    shown: Option<(TimeText, Option<Hub75Color<COLOR_BITS>>)>,
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    Timer<WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a stopped timer counting down from `duration_ms` milliseconds
+    ///
+    /// Tenths are shown below 10 seconds, and once it runs out the timer
+    /// flashes red four times a second for 5 seconds.
+    pub fn countdown(duration_ms: u32, area: Rectangle, color: Hub75Color<COLOR_BITS>) -> Self {
+        Self {
+            area,
+            color,
+            mode: TimerMode::Countdown,
+            duration: duration_ms,
+            elapsed: 0,
+            running: false,
+            tenths_below: 10_000,
+            scale: 1,
+            flash_color: Hub75Color::red(),
+            flash_period: 125,
+            flash_duration: 5_000,
+            flashing: None,
+            shown: None,
+        }
+    }
+
+    /// Create a stopped stopwatch at zero
+    ///
+    /// Tenths are shown for the first 10 seconds.
+    pub fn stopwatch(area: Rectangle, color: Hub75Color<COLOR_BITS>) -> Self {
+        Self {
+            mode: TimerMode::Stopwatch,
+            ..Self::countdown(0, area, color)
+        }
+    }
+
+    /// Get whether the timer counts down or up
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    /// Set the time counted down from and reset the timer
+    pub fn set_duration(&mut self, duration_ms: u32) {
+        self.duration = duration_ms;
+        self.reset();
+    }
+
+    /// Set the color of the digits
+    pub fn set_color(&mut self, color: Hub75Color<COLOR_BITS>) {
+        self.color = color;
+    }
+
+    /// Show tenths of a second for times below `ms` milliseconds
+    ///
+    /// Tenths are never shown with `ms` set to 0.
+    pub fn set_tenths_below(&mut self, ms: u32) {
+        self.tenths_below = ms;
+    }
+
+    /// Set the factor the digits are enlarged by, at least 1
+    pub fn set_scale(&mut self, scale: u8) {
+        self.scale = scale.max(1);
+    }
+
+    /// Flash the digits in `color` when a countdown runs out, shown and then
+    /// hidden for `period_ms` milliseconds each, for `duration_ms`
+    /// milliseconds
+    ///
+    /// Flashing is disabled with `duration_ms` set to 0; with `u32::MAX` it
+    /// lasts until the timer is stopped or reset.
+    pub fn set_flash(&mut self, color: Hub75Color<COLOR_BITS>, period_ms: u32, duration_ms: u32) {
+        self.flash_color = color;
+        self.flash_period = period_ms.max(1);
+        self.flash_duration = duration_ms;
+        if duration_ms == 0 {
+            self.flashing = None;
+        }
+    }
+
+    /// Start or resume counting
+    ///
+    /// A countdown that ran out stays at zero until it is reset.
+    pub fn start(&mut self) {
+        self.running = !self.is_finished();
+    }
+
+    /// Pause counting, and stop the flashing of a countdown that ran out
+    pub fn stop(&mut self) {
+        self.running = false;
+        self.flashing = None;
+    }
+
+    /// Stop and go back to the full duration, or to zero for a stopwatch
+    pub fn reset(&mut self) {
+        self.stop();
+        self.elapsed = 0;
+    }
+
+    /// Check whether ticks advance the time
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Check whether a countdown ran out
+    pub fn is_finished(&self) -> bool {
+        self.mode == TimerMode::Countdown && self.elapsed >= self.duration
+    }
+
+    /// Check whether the digits are flashing after a countdown ran out
+    pub fn is_flashing(&self) -> bool {
+        self.flashing.is_some()
+    }
+
+    /// Get the time counted so far in milliseconds
+    pub fn elapsed_ms(&self) -> u32 {
+        self.elapsed
+    }
+
+    /// Get the time left of a countdown in milliseconds, or `None` for a
+    /// stopwatch
+    pub fn remaining_ms(&self) -> Option<u32> {
+        match self.mode {
+            TimerMode::Countdown => Some(self.duration.saturating_sub(self.elapsed)),
+            TimerMode::Stopwatch => None,
+        }
+    }
+
+    /// Advance the time by `ms` milliseconds if running, and the flashing
+    /// either way
+    ///
+    /// Returns `true` on the tick a countdown runs out, so the caller can
+    /// sound a buzzer or move on.
+    pub fn tick(&mut self, ms: u32) -> bool {
+        if let Some(flashed) = self.flashing {
+            let flashed = flashed.saturating_add(ms);
+            self.flashing = (flashed < self.flash_duration).then_some(flashed);
+        }
+        if !self.running {
+            return false;
+        }
+
+        self.elapsed = self.elapsed.saturating_add(ms);
+        if !self.is_finished() {
+            return false;
+        }
+        self.elapsed = self.duration;
+        self.running = false;
+        if self.flash_duration > 0 {
+            self.flashing = Some(0);
+        }
+        true
+    }
+
+    /// Format the time as it is shown
+    ///
+    /// A countdown rounds up, so it shows zero only once it ran out, and a
+    /// stopwatch rounds down.
+    fn text(&self) -> TimeText {
+        let (ms, round_up) = match self.mode {
+            TimerMode::Countdown => (self.duration.saturating_sub(self.elapsed), true),
+            TimerMode::Stopwatch => (self.elapsed, false),
+        };
+        let divide = |unit: u32| {
+            if round_up {
+                ms.div_ceil(unit)
+            } else {
+                ms / unit
+            }
+        };
+
+        let mut text = TimeText::new();
+        // Cannot fail, the longest time fits
+        let _ = if ms < self.tenths_below {
+            let tenths = divide(100);
+            let seconds = tenths / 10;
+            if seconds >= 60 {
+                write!(text, "{}:{:02}.{}", seconds / 60, seconds % 60, tenths % 10)
+            } else {
+                write!(text, "{}.{}", seconds, tenths % 10)
+            }
+        } else {
+            let seconds = divide(1000);
+            let (hours, minutes) = (seconds / 3600, seconds / 60 % 60);
+            if hours > 0 {
+                write!(text, "{}:{:02}:{:02}", hours, minutes, seconds % 60)
+            } else {
+                write!(text, "{}:{:02}", minutes, seconds % 60)
+            }
+        };
+        text
+    }
+
+    /// Color the digits are drawn in right now, or `None` if hidden
+    fn current_color(&self) -> Option<Hub75Color<COLOR_BITS>> {
+        match self.flashing {
+            Some(flashed) if (flashed / self.flash_period) % 2 == 1 => None,
+            Some(_) => Some(self.flash_color),
+            None => Some(self.color),
+        }
+    }
+
+    /// Draw the time centered in the area, clearing the rest of it
+    pub fn render_into(&mut self, frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) {
+        let text = self.text();
+        let color = self.current_color();
+
+        let mut window = Window::new(frame, self.area);
+        window.clear();
+        if let Some(color) = color {
+            let x = (window.width() as i32 - visible_width(&text, self.scale)) / 2;
+            let y = (window.height() as i32 - (GLYPH_HEIGHT * self.scale as usize) as i32) / 2;
+            let font = Scaled::new(Font5x7, self.scale);
+            window.draw_text_with(&font, &text, x, y, color);
+        }
+        self.shown = Some((text, color));
+    }
+
+    /// Draw the time into `frame` if it looks different from when it was last
+    /// drawn
+    ///
+    /// Returns [`AnimationState::Wait`] when nothing changed; a timer is
+    /// never done.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        let current = (self.text(), self.current_color());
+        if self.shown.as_ref() == Some(&current) {
+            return AnimationState::Wait;
+        }
+        Timer::render_into(self, frame);
+        AnimationState::Rendered
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for Timer<WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        Timer::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        Timer::render_into(self, frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::geometry::{Point, Size};
+
+    fn area() -> Rectangle {
+        Rectangle::new(Point::zero(), Size::new(64, 32))
+    }
+
+    #[test]
+    fn test_countdown_text() {
+        let mut timer = Timer::<64, 32, 6>::countdown(3_723_000, area(), Hub75Color::white());
+        assert_eq!(timer.text(), "1:02:03");
+        timer.start();
+        // Rounded up until the second is over
+        timer.tick(3_000_999);
+        assert_eq!(timer.text(), "12:03");
+        timer.tick(1);
+        assert_eq!(timer.text(), "12:02");
+
+        // Tenths below the threshold, with minutes above one
+        timer.set_tenths_below(120_000);
+        timer.tick(602_950);
+        assert_eq!(timer.text(), "1:59.1");
+        assert_eq!(timer.remaining_ms(), Some(119_050));
+        timer.tick(110_000);
+        assert_eq!(timer.text(), "9.1");
+        timer.set_tenths_below(0);
+        assert_eq!(timer.text(), "0:10");
+    }
+
+    #[test]
+    fn test_stopwatch() {
+        let mut timer = Timer::<64, 32, 6>::stopwatch(area(), Hub75Color::white());
+        assert_eq!(timer.text(), "0.0");
+        // Not running yet
+        assert!(!timer.tick(500));
+        timer.start();
+        timer.tick(9_999);
+        assert_eq!(timer.text(), "9.9");
+        timer.tick(1);
+        assert_eq!(timer.text(), "0:10");
+        timer.stop();
+        timer.tick(1_000);
+        assert_eq!(timer.elapsed_ms(), 10_000);
+        assert!(!timer.is_finished() && timer.remaining_ms().is_none());
+
+        timer.reset();
+        assert_eq!((timer.elapsed_ms(), timer.is_running()), (0, false));
+        timer.start();
+        timer.tick(u32::MAX);
+        assert_eq!(timer.text(), "1193:02:47");
+    }
+
+    #[test]
+    fn test_countdown_finish_and_flash() {
+        let mut timer = Timer::<64, 32, 6>::countdown(1_000, area(), Hub75Color::white());
+        timer.set_flash(Hub75Color::red(), 100, 400);
+        timer.start();
+        assert!(!timer.tick(900));
+        assert_eq!(timer.text(), "0.1");
+        assert!(timer.tick(500));
+        assert_eq!((timer.elapsed_ms(), timer.text().as_str()), (1_000, "0.0"));
+        assert!(timer.is_finished() && !timer.is_running());
+        // Starting again needs a reset
+        timer.start();
+        assert!(!timer.is_running());
+
+        // Shown in the flash color, hidden, shown again, then back to normal
+        let mut frame = Hub75FrameBuffer::new();
+        let lit = |frame: &Hub75FrameBuffer<64, 32, 6>, color| {
+            (0..64)
+                .flat_map(|x| (0..32).map(move |y| (x, y)))
+                .filter(|&(x, y)| frame.get_pixel(x, y).unwrap() == color)
+                .count()
+        };
+        assert!(matches!(
+            timer.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert!(lit(&frame, Hub75Color::red()) > 0);
+        timer.tick(50);
+        assert!(matches!(timer.next_into(&mut frame), AnimationState::Wait));
+        timer.tick(50);
+        assert!(matches!(
+            timer.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(lit(&frame, Hub75Color::black()), 64 * 32);
+        timer.tick(100);
+        assert!(matches!(
+            timer.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert!(lit(&frame, Hub75Color::red()) > 0);
+        timer.tick(200);
+        assert!(!timer.is_flashing());
+        assert!(matches!(
+            timer.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert!(lit(&frame, Hub75Color::white()) > 0);
+
+        // Stopping acknowledges the flashing early
+        timer.reset();
+        timer.start();
+        timer.tick(1_000);
+        assert!(timer.is_flashing());
+        timer.stop();
+        assert!(!timer.is_flashing());
+    }
+}
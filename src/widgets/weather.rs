@@ -0,0 +1,397 @@
+//! Weather dashboard
+//!
+//! A [`WeatherDashboard`] lays out a [`WeatherReport`] the way most weather
+//! displays on 64x32 panels do: a condition [`Icon`] on the left, the
+//! temperature in large digits next to it and a caption line, such as the
+//! forecast or humidity, along the bottom. The layout adapts to the region it
+//! is given, falling back to the small icon and regular digits when the large
+//! ones do not fit, and leaving out the caption on regions too short for it.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hub75::{AnimationState, Hub75FrameBuffer};
+//! use hub75::widgets::{Condition, TemperatureUnit, WeatherDashboard, WeatherReport};
+//! use embedded_graphics_core::{geometry::{Point, Size}, primitives::Rectangle};
+//!
+//! let report = WeatherReport {
+//!     condition: Condition::Rain,
+//!     temperature: -3,
+//!     unit: TemperatureUnit::Celsius,
+//!     caption: "Hum 80%",
+//! };
+//! let area = Rectangle::new(Point::zero(), Size::new(64, 32));
+//! let mut weather = WeatherDashboard::<64, 32, 6>::new(report, area);
+//!
+//! let mut frame = Hub75FrameBuffer::new();
+//! assert!(matches!(weather.next_into(&mut frame), AnimationState::Rendered));
+//! // Nothing changed, so there is nothing to redraw
+//! assert!(matches!(weather.next_into(&mut frame), AnimationState::Wait));
+//! ```
+
+use core::fmt::Write;
+
+use embedded_graphics_core::primitives::Rectangle;
+
+use crate::{
+    animation::AnimationState,
+    color::Hub75Color,
+    font::{visible_width, Font, Font5x7, Scaled, GLYPH_HEIGHT},
+    frame_buffer::Hub75FrameBuffer,
+    icon::{Icon, IconSize, ICON_SIZE},
+    scene::FrameSource,
+    window::Window,
+    Hub75Error,
+};
+
+/// Weather condition shown by the icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Condition {
+    /// Clear sky during the day
+    #[default]
+    Clear,
+    /// Clear sky at night
+    ClearNight,
+    /// Some clouds
+    PartlyCloudy,
+    /// Overcast
+    Cloudy,
+    /// Rain or drizzle
+    Rain,
+    /// Snow or sleet
+    Snow,
+    /// Thunderstorm
+    Thunderstorm,
+    /// Fog or mist
+    Fog,
+    /// Strong wind
+    Windy,
+}
+
+impl Condition {
+    /// Get the icon for the condition
+    pub fn icon(self) -> Icon {
+        match self {
+            Condition::Clear => Icon::Sun,
+            Condition::ClearNight => Icon::Moon,
+            Condition::PartlyCloudy => Icon::PartlyCloudy,
+            Condition::Cloudy => Icon::Cloud,
+            Condition::Rain => Icon::Rain,
+            Condition::Snow => Icon::Snow,
+            Condition::Thunderstorm => Icon::Thunderstorm,
+            Condition::Fog => Icon::Fog,
+            Condition::Windy => Icon::Wind,
+        }
+    }
+
+    /// Get the color the icon is drawn in unless one is set
+    pub fn color<const COLOR_BITS: usize>(self) -> Hub75Color<COLOR_BITS> {
+        match self {
+            Condition::Clear | Condition::PartlyCloudy | Condition::Thunderstorm => {
+                Hub75Color::yellow()
+            }
+            Condition::Rain => Hub75Color::cyan(),
+            Condition::ClearNight | Condition::Cloudy | Condition::Snow => Hub75Color::white(),
+            Condition::Fog | Condition::Windy => Hub75Color::from_rgb8(160, 160, 160),
+        }
+    }
+}
+
+/// Unit a temperature is given in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TemperatureUnit {
+    /// Degrees Celsius
+    #[default]
+    Celsius,
+    /// Degrees Fahrenheit
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    /// Get the unit as shown after the digits
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+}
+
+/// Data shown on a [`WeatherDashboard`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WeatherReport<'a> {
+    /// Condition shown by the icon
+    pub condition: Condition,
+    /// Temperature in whole degrees
+    pub temperature: i16,
+    /// Unit of the temperature
+    pub unit: TemperatureUnit,
+    /// Line of text along the bottom, centered and cut off at the edges
+    pub caption: &'a str,
+}
+
+/// Icon, temperature and caption laid out in a region of the frame
+#[derive(Debug, Clone)]
+pub struct WeatherDashboard<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Data being shown
+    report: WeatherReport<'a>,
+    /// Region of the frame the dashboard is drawn in
+    area: Rectangle,
+    /// Icon color, or `None` for the color of the condition
+    icon_color: Option<Hub75Color<COLOR_BITS>>,
+    /// Color of the temperature
+    temperature_color: Hub75Color<COLOR_BITS>,
+    /// Color of the caption
+    caption_color: Hub75Color<COLOR_BITS>,
+    /// Whether the report changed since it was last drawn
+    changed: bool,
+}
+
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    WeatherDashboard<'a, WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a dashboard showing `report` inside `area`
+    ///
+    /// The icon takes the color of the condition, the temperature is white
+    /// and the caption orange.
+    pub fn new(report: WeatherReport<'a>, area: Rectangle) -> Self {
+        Self {
+            report,
+            area,
+            icon_color: None,
+            temperature_color: Hub75Color::white(),
+            caption_color: Hub75Color::orange(),
+            changed: true,
+        }
+    }
+
+    /// Show a new report, redrawing on the next frame if it differs
+    pub fn set_report(&mut self, report: WeatherReport<'a>) {
+        self.changed |= report != self.report;
+        self.report = report;
+    }
+
+    /// Get the report being shown
+    pub fn report(&self) -> &WeatherReport<'a> {
+        &self.report
+    }
+
+    /// Set the icon color, or `None` to use the color of the condition
+    pub fn set_icon_color(&mut self, color: Option<Hub75Color<COLOR_BITS>>) {
+        self.icon_color = color;
+        self.changed = true;
+    }
+
+    /// Set the color of the temperature
+    pub fn set_temperature_color(&mut self, color: Hub75Color<COLOR_BITS>) {
+        self.temperature_color = color;
+        self.changed = true;
+    }
+
+    /// Set the color of the caption
+    pub fn set_caption_color(&mut self, color: Hub75Color<COLOR_BITS>) {
+        self.caption_color = color;
+        self.changed = true;
+    }
+
+    /// Draw the dashboard, clearing the rest of the area
+    pub fn render_into(&mut self, frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>) {
+        self.changed = false;
+        let mut window = Window::new(frame, self.area);
+        window.clear();
+        let (width, height) = (window.width() as i32, window.height() as i32);
+        let glyph_height = GLYPH_HEIGHT as i32;
+
+        // The caption takes the bottom line if the rest still fits an icon
+        let with_caption = height - glyph_height > ICON_SIZE as i32;
+        let top = if with_caption {
+            let caption = self.report.caption;
+            let x = (width - visible_width(caption, 1)) / 2;
+            window.draw_text(caption, x.max(0), height - glyph_height, self.caption_color);
+            height - glyph_height - 1
+        } else {
+            height
+        };
+
+        let size = if top >= IconSize::Large.pixels() as i32 && width >= 40 {
+            IconSize::Large
+        } else {
+            IconSize::Small
+        };
+        let icon_size = size.pixels() as i32;
+        let color = self
+            .icon_color
+            .unwrap_or_else(|| self.report.condition.color());
+        let icon = self.report.condition.icon();
+        window.draw_icon(icon, 1, (top - icon_size) / 2, size, color);
+
+        // Digits in large type if they fit next to the icon, unit in small
+        let mut digits = heapless::String::<8>::new();
+        let _ = write!(digits, "{}", self.report.temperature);
+        let unit = self.report.unit.symbol();
+        let left = icon_size + 2;
+        let unit_width = visible_width(unit, 1);
+        let scale = if top >= 2 * glyph_height
+            && visible_width(&digits, 2) + 1 + unit_width <= width - left
+        {
+            2
+        } else {
+            1
+        };
+        let font = Scaled::new(Font5x7, scale);
+        let total = visible_width(&digits, scale) + 1 + unit_width;
+        let x = left + (width - left - total).max(0) / 2;
+        let y = (top - font.line_height() as i32) / 2;
+        let x = window.draw_text_with(&font, &digits, x, y, self.temperature_color);
+        // One column apart, like the digits are from each other
+        window.draw_text(unit, x - scale as i32 + 1, y, self.temperature_color);
+    }
+
+    /// Draw the dashboard into `frame` if anything changed since it was last
+    /// drawn
+    ///
+    /// Returns [`AnimationState::Wait`] when nothing changed; a dashboard is
+    /// never done.
+    pub fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        if !self.changed {
+            return AnimationState::Wait;
+        }
+        WeatherDashboard::render_into(self, frame);
+        AnimationState::Rendered
+    }
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    FrameSource<WIDTH, HEIGHT, COLOR_BITS> for WeatherDashboard<'_, WIDTH, HEIGHT, COLOR_BITS>
+{
+    fn next_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> AnimationState<WIDTH, HEIGHT, COLOR_BITS> {
+        WeatherDashboard::next_into(self, frame)
+    }
+
+    fn render_into(
+        &mut self,
+        frame: &mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        WeatherDashboard::render_into(self, frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::{
+        geometry::{Point, Size},
+        primitives::PointsIter,
+    };
+
+    const REPORT: WeatherReport<'static> = WeatherReport {
+        condition: Condition::Clear,
+        temperature: -12,
+        unit: TemperatureUnit::Celsius,
+        caption: "Sunny",
+    };
+
+    fn lit<const W: usize, const H: usize>(
+        frame: &Hub75FrameBuffer<W, H, 6>,
+        area: Rectangle,
+    ) -> usize {
+        area.points()
+            .filter(|p| frame.get_pixel(p.x as usize, p.y as usize).unwrap() != Hub75Color::black())
+            .count()
+    }
+
+    #[test]
+    fn test_weather_layout() {
+        let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+        let area = Rectangle::new(Point::zero(), Size::new(64, 32));
+        let mut weather = WeatherDashboard::new(REPORT, area);
+        weather.render_into(&mut frame);
+
+        // Large sun in the top left, centered above the caption
+        let icon = Rectangle::new(Point::new(1, 4), Size::new(16, 16));
+        assert!(lit(&frame, icon) > 0);
+        assert_eq!(frame.get_pixel(1, 3).unwrap(), Hub75Color::black());
+        assert!(icon.points().all(|p| {
+            let color = frame.get_pixel(p.x as usize, p.y as usize).unwrap();
+            color == Hub75Color::black() || color == Hub75Color::yellow()
+        }));
+        // "-12" at double size and "°C" exactly fill the region right of it
+        let temperature = Rectangle::new(Point::new(18, 5), Size::new(46, 14));
+        assert!(lit(&frame, temperature) > 0);
+        let minus = Rectangle::new(Point::new(18, 11), Size::new(10, 2));
+        assert_eq!(lit(&frame, minus), 20);
+        let right = |x| lit(&frame, Rectangle::new(Point::new(x, 0), Size::new(1, 24)));
+        assert_eq!(right(17), 0);
+        assert!(right(63) > 0);
+        // Caption along the bottom in orange
+        let caption = Rectangle::new(Point::new(0, 25), Size::new(64, 7));
+        assert!(caption
+            .points()
+            .any(|p| frame.get_pixel(p.x as usize, p.y as usize).unwrap() == Hub75Color::orange()));
+    }
+
+    #[test]
+    fn test_weather_small_area() {
+        // Too short for a caption or large digits
+        let mut frame = Hub75FrameBuffer::<32, 10, 6>::new();
+        let area = Rectangle::new(Point::new(0, 1), Size::new(32, 9));
+        let mut weather = WeatherDashboard::new(REPORT, area);
+        weather.set_icon_color(Some(Hub75Color::red()));
+        weather.render_into(&mut frame);
+
+        let icon = Rectangle::new(Point::new(1, 1), Size::new(8, 8));
+        assert!(lit(&frame, icon) > 0);
+        assert!(icon.points().all(|p| {
+            let color = frame.get_pixel(p.x as usize, p.y as usize).unwrap();
+            color == Hub75Color::black() || color == Hub75Color::red()
+        }));
+        // Nothing drawn outside the area
+        assert_eq!(
+            lit(&frame, Rectangle::new(Point::zero(), Size::new(32, 1))),
+            0
+        );
+        // Regular digits right of the icon, the minus sign a single row
+        let minus = |y| lit(&frame, Rectangle::new(Point::new(10, y), Size::new(5, 1)));
+        assert_eq!((minus(4), minus(5), minus(6)), (0, 5, 0));
+    }
+
+    #[test]
+    fn test_weather_redraw() {
+        let mut frame = Hub75FrameBuffer::<64, 32, 6>::new();
+        let area = Rectangle::new(Point::zero(), Size::new(64, 32));
+        let mut weather = WeatherDashboard::new(REPORT, area);
+        assert!(matches!(
+            weather.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert!(matches!(
+            weather.next_into(&mut frame),
+            AnimationState::Wait
+        ));
+
+        // The same report again does not redraw, a different one does
+        weather.set_report(REPORT);
+        assert!(matches!(
+            weather.next_into(&mut frame),
+            AnimationState::Wait
+        ));
+        weather.set_report(WeatherReport {
+            temperature: 3,
+            ..REPORT
+        });
+        assert!(matches!(
+            weather.next_into(&mut frame),
+            AnimationState::Rendered
+        ));
+        assert_eq!(weather.report().temperature, 3);
+    }
+}
@@ -0,0 +1,458 @@
+//! Clipped and translated sub-regions of a frame buffer
+//!
+//! A [`Window`] is a view onto a rectangular region of a frame buffer with its
+//! own coordinate system: (0, 0) is the top-left corner of the region and
+//! drawing outside the region is clipped. This makes multi-widget layouts
+//! (clock pane, ticker pane, status pane, ...) simple, as each widget can be
+//! drawn as if it owned a small display of its own.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use hub75::Hub75FrameBuffer;
+//! use embedded_graphics::prelude::*;
+//! use embedded_graphics::pixelcolor::Rgb565;
+//! use embedded_graphics::primitives::{Rectangle, PrimitiveStyle};
+//!
+//! let mut buffer = Hub75FrameBuffer::<64, 32, 6>::new();
+//!
+//! // The bottom 8 rows are a status pane
+//! let mut status = buffer.window(Rectangle::new(Point::new(0, 24), Size::new(64, 8)));
+//!
+//! // Drawn at (0, 0) in pane coordinates, which is (0, 24) on the panel
+//! Rectangle::new(Point::zero(), Size::new(8, 8))
+//!     .into_styled(PrimitiveStyle::with_fill(Rgb565::GREEN))
+//!     .draw(&mut status)
+//!     .unwrap();
+//! ```
+
+use crate::{
+    color::Hub75Color,
+    composite::BlendMode,
+    font::{Font, TextStyle, TextWriter},
+    frame_buffer::Hub75FrameBuffer,
+    icon::{Icon, IconSize},
+    Hub75Error,
+};
+use embedded_graphics_core::{
+    geometry::{Point, Size},
+    primitives::Rectangle,
+};
+
+/// A clipped and translated view onto a region of a frame buffer
+///
+/// Created by [`Hub75FrameBuffer::window`] or
+/// [`Hub75Display::window`](crate::Hub75Display::window).
+pub struct Window<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> {
+    /// Frame buffer being drawn into
+    buffer: &'a mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+    /// Region of the frame buffer covered by this window
+    area: Rectangle,
+}
+
+impl<'a, const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize>
+    Window<'a, WIDTH, HEIGHT, COLOR_BITS>
+{
+    /// Create a new window onto `area` of the given frame buffer
+    pub fn new(
+        buffer: &'a mut Hub75FrameBuffer<WIDTH, HEIGHT, COLOR_BITS>,
+        area: Rectangle,
+    ) -> Self {
+        Self { buffer, area }
+    }
+
+    /// Get the region of the frame buffer covered by this window
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Get the width of the window
+    pub fn width(&self) -> usize {
+        self.area.size.width as usize
+    }
+
+    /// Get the height of the window
+    pub fn height(&self) -> usize {
+        self.area.size.height as usize
+    }
+
+    /// Get the part of the window that lies on the frame buffer, in buffer coordinates
+    fn visible_area(&self) -> Rectangle {
+        self.area.intersection(&Rectangle::new(
+            Point::zero(),
+            Size::new(WIDTH as u32, HEIGHT as u32),
+        ))
+    }
+
+    /// Translate window coordinates into frame buffer coordinates
+    ///
+    /// Returns `None` if the point is outside the window or the frame buffer.
+    fn to_buffer(&self, point: Point) -> Option<(usize, usize)> {
+        if point.x < 0
+            || point.y < 0
+            || point.x >= self.area.size.width as i32
+            || point.y >= self.area.size.height as i32
+        {
+            return None;
+        }
+
+        let p = self.translate(point)?;
+        if p.x < 0 || p.y < 0 || p.x as usize >= WIDTH || p.y as usize >= HEIGHT {
+            return None;
+        }
+        Some((p.x as usize, p.y as usize))
+    }
+
+    /// Translate window coordinates into frame buffer coordinates without
+    /// clipping
+    ///
+    /// Returns `None` if the result does not fit in an `i32`.
+    fn translate(&self, point: Point) -> Option<Point> {
+        Some(Point::new(
+            self.area.top_left.x.checked_add(point.x)?,
+            self.area.top_left.y.checked_add(point.y)?,
+        ))
+    }
+
+    /// Set a pixel at the specified window coordinates
+    pub fn set_pixel(
+        &mut self,
+        x: usize,
+        y: usize,
+        color: Hub75Color<COLOR_BITS>,
+    ) -> Result<(), Hub75Error> {
+        let (x, y) = self
+            .to_buffer(Point::new(x as i32, y as i32))
+            .ok_or(Hub75Error::InvalidCoordinates)?;
+        // SAFETY: to_buffer only returns coordinates inside the frame buffer
+        unsafe { self.buffer.set_pixel_unchecked(x, y, color) };
+        Ok(())
+    }
+
+    /// Get a pixel at the specified window coordinates
+    pub fn get_pixel(&self, x: usize, y: usize) -> Result<Hub75Color<COLOR_BITS>, Hub75Error> {
+        let (x, y) = self
+            .to_buffer(Point::new(x as i32, y as i32))
+            .ok_or(Hub75Error::InvalidCoordinates)?;
+        // SAFETY: to_buffer only returns coordinates inside the frame buffer
+        Ok(unsafe { self.buffer.get_pixel_unchecked(x, y) })
+    }
+
+    /// Fill a rectangle given in window coordinates, clipped to the window
+    pub fn fill_rect(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        color: Hub75Color<COLOR_BITS>,
+    ) {
+        let Some(top_left) = i32::try_from(x)
+            .ok()
+            .zip(i32::try_from(y).ok())
+            .and_then(|(x, y)| self.translate(Point::new(x, y)))
+        else {
+            return;
+        };
+        let rect = Rectangle::new(top_left, Size::new(width as u32, height as u32))
+            .intersection(&self.visible_area());
+
+        if !rect.is_zero_sized() {
+            self.buffer.fill_rect(
+                rect.top_left.x as usize,
+                rect.top_left.y as usize,
+                rect.size.width as usize,
+                rect.size.height as usize,
+                color,
+            );
+        }
+    }
+
+    /// Fill the whole window with a single color
+    pub fn fill(&mut self, color: Hub75Color<COLOR_BITS>) {
+        self.fill_rect(0, 0, self.width(), self.height(), color);
+    }
+
+    /// Clear the window (set all pixels to black)
+    pub fn clear(&mut self) {
+        self.fill(Hub75Color::black());
+    }
+
+    /// Draw a string in the built-in [`font`](crate::font) with its top-left
+    /// corner at `(x, y)` in window coordinates, clipped to the window
+    ///
+    /// Returns the x coordinate following the last character.
+    pub fn draw_text(&mut self, text: &str, x: i32, y: i32, color: Hub75Color<COLOR_BITS>) -> i32 {
+        self.draw_text_blended(text, x, y, color, BlendMode::Replace)
+    }
+
+    /// Draw a string clipped to the window, combining its lit pixels with the
+    /// frame content using `blend`
+    ///
+    /// Returns the x coordinate following the last character.
+    pub fn draw_text_blended(
+        &mut self,
+        text: &str,
+        x: i32,
+        y: i32,
+        color: Hub75Color<COLOR_BITS>,
+        blend: BlendMode,
+    ) -> i32 {
+        let Some(origin) = self.translate(Point::new(x, y)) else {
+            return x;
+        };
+        let end = self
+            .buffer
+            .draw_text_clipped(text, origin, color, blend, self.visible_area());
+        end.saturating_sub(self.area.top_left.x)
+    }
+
+    /// Draw a string in `font` with its top-left corner at `(x, y)` in window
+    /// coordinates, clipped to the window
+    ///
+    /// Returns the x coordinate following the last character.
+    pub fn draw_text_with<F: Font + ?Sized>(
+        &mut self,
+        font: &F,
+        text: &str,
+        x: i32,
+        y: i32,
+        color: Hub75Color<COLOR_BITS>,
+    ) -> i32 {
+        let Some(origin) = self.translate(Point::new(x, y)) else {
+            return x;
+        };
+        let clip = self.visible_area();
+        let end =
+            self.buffer
+                .draw_font_text_clipped(font, text, origin, color, BlendMode::Replace, clip);
+        end.saturating_sub(self.area.top_left.x)
+    }
+
+    /// Draw an icon with its top-left corner at `(x, y)` in window
+    /// coordinates, clipped to the window
+    pub fn draw_icon(
+        &mut self,
+        icon: Icon,
+        x: i32,
+        y: i32,
+        size: IconSize,
+        color: Hub75Color<COLOR_BITS>,
+    ) {
+        let Some(origin) = self.translate(Point::new(x, y)) else {
+            return;
+        };
+        let clip = self.visible_area();
+        self.buffer
+            .draw_icon_clipped(icon, origin, size, color, clip);
+    }
+
+    /// Get a [`TextWriter`] drawing with its top-left corner at `(x, y)` in
+    /// window coordinates, clipped to the window
+    pub fn text_at(
+        &mut self,
+        x: i32,
+        y: i32,
+        style: impl Into<TextStyle<COLOR_BITS>>,
+    ) -> TextWriter<'_, WIDTH, HEIGHT, COLOR_BITS> {
+        let clip = self.visible_area();
+        TextWriter::new(
+            self.buffer,
+            self.area.top_left,
+            clip,
+            Point::new(x, y),
+            style.into(),
+        )
+    }
+}
+
+#[cfg(feature = "embedded-graphics")]
+mod embedded_graphics_support {
+    use super::*;
+    use embedded_graphics_core::{
+        draw_target::DrawTarget,
+        geometry::{OriginDimensions, Size},
+        pixelcolor::Rgb565,
+        primitives::PointsIter,
+        Pixel,
+    };
+
+    impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> DrawTarget
+        for Window<'_, WIDTH, HEIGHT, COLOR_BITS>
+    {
+        type Color = Rgb565;
+        type Error = Hub75Error;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            for Pixel(point, color) in pixels {
+                if let Some((x, y)) = self.to_buffer(point) {
+                    // SAFETY: to_buffer only returns coordinates inside the frame buffer
+                    unsafe {
+                        self.buffer
+                            .set_pixel_unchecked(x, y, Hub75Color::from(color))
+                    };
+                }
+            }
+            Ok(())
+        }
+
+        fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Self::Color>,
+        {
+            let window = Rectangle::new(Point::zero(), self.area.size);
+            let drawable_area = area.intersection(&window);
+
+            let offset = self.area.top_left;
+            self.buffer.draw_iter(
+                area.points()
+                    .zip(colors)
+                    .filter(|(point, _)| drawable_area.contains(*point))
+                    .filter_map(|(point, color)| {
+                        let x = point.x.checked_add(offset.x)?;
+                        let y = point.y.checked_add(offset.y)?;
+                        Some(Pixel(Point::new(x, y), color))
+                    }),
+            )
+        }
+
+        fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+            let window = Rectangle::new(Point::zero(), self.area.size);
+            let area = area.intersection(&window);
+            if !area.is_zero_sized() {
+                self.fill_rect(
+                    area.top_left.x as usize,
+                    area.top_left.y as usize,
+                    area.size.width as usize,
+                    area.size.height as usize,
+                    Hub75Color::from(color),
+                );
+            }
+            Ok(())
+        }
+    }
+
+    impl<const WIDTH: usize, const HEIGHT: usize, const COLOR_BITS: usize> OriginDimensions
+        for Window<'_, WIDTH, HEIGHT, COLOR_BITS>
+    {
+        fn size(&self) -> Size {
+            self.area.size
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_translation_and_clipping() {
+        let mut buffer = Hub75FrameBuffer::<16, 8, 6>::new();
+        let red = Hub75Color::red();
+
+        {
+            let mut window = buffer.window(Rectangle::new(Point::new(4, 2), Size::new(4, 4)));
+            assert_eq!((window.width(), window.height()), (4, 4));
+
+            window.set_pixel(0, 0, red).unwrap();
+            assert!(window.set_pixel(4, 0, red).is_err());
+
+            // Filling past the window edge is clipped to the window
+            window.fill_rect(2, 2, 10, 10, Hub75Color::blue());
+        }
+
+        assert_eq!(buffer.get_pixel(4, 2).unwrap(), red);
+        assert_eq!(buffer.get_pixel(7, 5).unwrap(), Hub75Color::blue());
+        assert_eq!(buffer.get_pixel(8, 5).unwrap(), Hub75Color::black());
+        assert_eq!(buffer.get_pixel(7, 6).unwrap(), Hub75Color::black());
+    }
+
+    #[test]
+    fn test_window_partially_off_buffer() {
+        let mut buffer = Hub75FrameBuffer::<16, 8, 6>::new();
+
+        {
+            let mut window = buffer.window(Rectangle::new(Point::new(12, 6), Size::new(8, 8)));
+            window.fill(Hub75Color::green());
+            assert!(window.set_pixel(5, 5, Hub75Color::red()).is_err());
+        }
+
+        assert_eq!(buffer.get_pixel(15, 7).unwrap(), Hub75Color::green());
+        assert_eq!(buffer.get_pixel(11, 7).unwrap(), Hub75Color::black());
+    }
+
+    #[test]
+    fn test_window_coordinate_overflow() {
+        let mut buffer = Hub75FrameBuffer::<16, 8, 6>::new();
+        let red = Hub75Color::red();
+
+        {
+            let far = Point::new(i32::MAX - 2, i32::MAX - 2);
+            let mut window = buffer.window(Rectangle::new(far, Size::new(8, 8)));
+            assert!(window.set_pixel(5, 5, red).is_err());
+            window.fill_rect(4, 4, 4, 4, red);
+            assert_eq!(window.draw_text("A", 5, 0, red), 5);
+        }
+
+        {
+            let mut window = buffer.window(Rectangle::new(Point::new(2, 2), Size::new(8, 4)));
+            window.fill_rect(usize::MAX, 0, 4, 4, red);
+            assert_eq!(window.draw_text("A", i32::MAX, 0, red), i32::MAX);
+        }
+
+        assert_eq!(buffer, Hub75FrameBuffer::new());
+    }
+
+    #[test]
+    fn test_window_text_writer() {
+        use core::fmt::Write;
+
+        let mut buffer = Hub75FrameBuffer::<16, 8, 6>::new();
+        let white = Hub75Color::white();
+
+        {
+            let mut window = buffer.window(Rectangle::new(Point::new(4, 2), Size::new(4, 4)));
+            // The vertical bar of '|' lands on the first window column
+            let mut writer = window.text_at(-2, 0, white);
+            write!(writer, "|").unwrap();
+            assert_eq!(writer.position(), Point::new(4, 0));
+        }
+
+        assert_eq!(buffer.get_pixel(4, 2).unwrap(), white);
+        assert_eq!(buffer.get_pixel(4, 5).unwrap(), white);
+        assert_eq!(buffer.get_pixel(4, 6).unwrap(), Hub75Color::black());
+    }
+
+    #[cfg(feature = "embedded-graphics")]
+    #[test]
+    fn test_window_draw_target() {
+        use embedded_graphics_core::{draw_target::DrawTarget, pixelcolor::Rgb565, prelude::*};
+
+        let mut buffer = Hub75FrameBuffer::<16, 8, 6>::new();
+
+        {
+            let mut window = buffer.window(Rectangle::new(Point::new(8, 0), Size::new(8, 4)));
+            window
+                .fill_solid(
+                    &Rectangle::new(Point::new(-2, -2), Size::new(4, 4)),
+                    Rgb565::RED,
+                )
+                .unwrap();
+            Pixel(Point::new(7, 3), Rgb565::BLUE)
+                .draw(&mut window)
+                .unwrap();
+            Pixel(Point::new(8, 3), Rgb565::BLUE)
+                .draw(&mut window)
+                .unwrap();
+        }
+
+        assert_eq!(buffer.get_pixel(8, 0).unwrap(), Hub75Color::red());
+        assert_eq!(buffer.get_pixel(9, 1).unwrap(), Hub75Color::red());
+        assert_eq!(buffer.get_pixel(7, 0).unwrap(), Hub75Color::black());
+        assert_eq!(buffer.get_pixel(15, 3).unwrap(), Hub75Color::blue());
+        assert_eq!(buffer.get_pixel(0, 4).unwrap(), Hub75Color::black());
+    }
+}